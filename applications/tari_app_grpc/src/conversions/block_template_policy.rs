@@ -0,0 +1,80 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::tari_rpc as grpc;
+use std::convert::{TryFrom, TryInto};
+use tari_core::{
+    mempool::{BlockTemplatePolicy, ExcludedTransaction, ExclusionReason},
+    transactions::{tari_amount::MicroTari, types::Signature},
+};
+use tari_crypto::tari_utilities::ByteArray;
+
+impl TryFrom<grpc::BlockTemplatePolicy> for BlockTemplatePolicy {
+    type Error = String;
+
+    fn try_from(policy: grpc::BlockTemplatePolicy) -> Result<Self, Self::Error> {
+        let always_include = policy
+            .always_include
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Signature>, _>>()?;
+        let always_exclude = policy
+            .always_exclude
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Signature>, _>>()?;
+
+        Ok(Self {
+            min_fee_per_gram: MicroTari::from(policy.min_fee_per_gram),
+            reserved_priority_weight: policy.reserved_priority_weight,
+            always_include,
+            always_exclude,
+        })
+    }
+}
+
+impl From<ExcludedTransaction> for grpc::ExcludedTransaction {
+    fn from(excluded: ExcludedTransaction) -> Self {
+        let reason: grpc::ExclusionReason = excluded.reason.into();
+        Self {
+            excess_sig: Some(grpc::Signature {
+                public_nonce: Vec::from(excluded.excess_sig.get_public_nonce().as_bytes()),
+                signature: Vec::from(excluded.excess_sig.get_signature().as_bytes()),
+            }),
+            reason: reason as i32,
+        }
+    }
+}
+
+impl From<ExclusionReason> for grpc::ExclusionReason {
+    fn from(reason: ExclusionReason) -> Self {
+        use ExclusionReason::*;
+        match reason {
+            ExcludedByPolicy => grpc::ExclusionReason::ExcludedByPolicy,
+            AncestorExcluded => grpc::ExclusionReason::AncestorExcluded,
+            BelowMinimumFee => grpc::ExclusionReason::BelowMinimumFee,
+            InsufficientWeight => grpc::ExclusionReason::InsufficientWeight,
+            DoubleSpend => grpc::ExclusionReason::DoubleSpend,
+            NotFound => grpc::ExclusionReason::NotFound,
+        }
+    }
+}
@@ -23,30 +23,41 @@
 mod aggregate_body;
 mod block;
 mod block_header;
+mod block_template_policy;
 mod chain_metadata;
+mod commitment_history;
 mod consensus_constants;
 mod historical_block;
+mod mempool_event;
 mod new_block_template;
 mod output_features;
 mod peer;
 mod proof_of_work;
+mod pruned_output;
+mod reorg;
 mod signature;
 mod transaction;
 mod transaction_input;
 mod transaction_kernel;
 mod transaction_output;
+mod utxo_selection_strategy;
 
 pub use self::{
     aggregate_body::*,
     block::*,
     block_header::*,
+    block_template_policy::*,
     chain_metadata::*,
+    commitment_history::*,
     consensus_constants::*,
     historical_block::*,
+    mempool_event::*,
     new_block_template::*,
     output_features::*,
     peer::*,
     proof_of_work::*,
+    pruned_output::*,
+    reorg::*,
     signature::*,
     transaction::*,
     transaction_input::*,
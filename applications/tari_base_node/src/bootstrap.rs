@@ -45,6 +45,7 @@ use tari_core::{
         state_machine_service::{initializer::BaseNodeStateMachineInitializer, states::HorizonSyncConfig},
         BaseNodeStateMachineConfig,
         BlockSyncConfig,
+        FeeEstimator,
         StateMachineHandle,
     },
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, BlockchainDatabase},
@@ -171,7 +172,8 @@ where B: BlockchainBackend + 'static
             .expect("P2pInitializer was not added to the stack or did not add UnspawnedCommsNode");
 
         let comms = comms.add_protocol_extension(mempool_protocol);
-        let comms = Self::setup_rpc_services(comms, &handles, self.db.into());
+        let fee_estimator = FeeEstimator::new(self.db.clone().into(), self.mempool.clone(), rules);
+        let comms = Self::setup_rpc_services(comms, config, &handles, self.db.into(), fee_estimator);
         let comms = initialization::spawn_comms_using_transport(comms, transport_type).await?;
         // Save final node identity after comms has initialized. This is required because the public_address can be
         // changed by comms during initialization when using tor.
@@ -189,14 +191,28 @@ where B: BlockchainBackend + 'static
 
     fn setup_rpc_services(
         comms: UnspawnedCommsNode,
+        config: &GlobalConfig,
         handles: &ServiceHandles,
         db: AsyncBlockchainDb<B>,
+        fee_estimator: FeeEstimator<B>,
     ) -> UnspawnedCommsNode
     {
         let dht = handles.expect_handle::<Dht>();
 
+        let mut rpc_server = RpcServer::new();
+        if let Some(limit) = config.rpc_max_sessions_per_peer {
+            rpc_server = rpc_server.with_maximum_sessions_per_peer(limit);
+        }
+        if let Some(limit) = config.rpc_max_requests_per_second_per_method {
+            rpc_server = rpc_server.with_maximum_requests_per_second_per_method(limit);
+        }
+        if let Some(limit) = config.rpc_max_bytes_per_peer {
+            rpc_server = rpc_server
+                .with_maximum_bytes_per_peer(limit, Duration::from_secs(config.rpc_max_bytes_per_peer_window));
+        }
+
         // Add your RPC services here ‍🏴‍☠️️☮️🌊
-        let rpc_server = RpcServer::new()
+        let rpc_server = rpc_server
             .add_service(dht.rpc_service())
             .add_service(base_node::create_base_node_sync_rpc_service(db.clone()))
             .add_service(mempool::create_mempool_rpc_service(
@@ -206,6 +222,7 @@ where B: BlockchainBackend + 'static
                 db,
                 handles.expect_handle::<MempoolHandle>(),
                 handles.expect_handle::<StateMachineHandle>(),
+                fee_estimator,
             ));
 
         comms.add_protocol_extension(rpc_server)
@@ -50,6 +50,7 @@ const LOG_TARGET: &str = "c::bn::initialization";
 /// on the comms stack.
 pub struct BaseNodeContext {
     blockchain_db: BlockchainDatabase<LMDBDatabase>,
+    mempool: Mempool,
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
@@ -80,6 +81,11 @@ impl BaseNodeContext {
         self.base_node_handles.expect_handle::<LocalMempoolService>()
     }
 
+    /// Returns the Mempool
+    pub fn mempool(&self) -> Mempool {
+        self.mempool.clone()
+    }
+
     /// Returns the CommsNode.
     pub fn base_node_comms(&self) -> &CommsNode {
         &self.base_node_comms
@@ -143,7 +149,8 @@ pub async fn configure_and_initialize_node(
             unimplemented!();
         },
         DatabaseType::LMDB(p) => {
-            let backend = create_lmdb_database(&p, config.db_config.clone())?;
+            let mut backend = create_lmdb_database(&p, config.db_config.clone())?;
+            backend.set_commitment_history_index(config.commitment_history_index)?;
             build_node_context(
                 backend,
                 node_identity,
@@ -205,7 +212,7 @@ async fn build_node_context(
         config,
         node_identity: base_node_identity,
         db: blockchain_db.clone(),
-        mempool,
+        mempool: mempool.clone(),
         rules: rules.clone(),
         factories: factories.clone(),
         interrupt_signal: interrupt_signal.clone(),
@@ -218,6 +225,7 @@ async fn build_node_context(
 
     Ok(BaseNodeContext {
         blockchain_db,
+        mempool,
         base_node_comms,
         base_node_dht,
         base_node_handles,
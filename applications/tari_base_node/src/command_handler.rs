@@ -27,6 +27,7 @@ use log::*;
 use regex::Regex;
 use std::{
     io::{self, Write},
+    path::PathBuf,
     string::ToString,
     sync::Arc,
     time::{Duration, Instant},
@@ -39,6 +40,7 @@ use tari_comms::{
 use tari_comms_dht::{envelope::NodeDestination, DhtDiscoveryRequester, MetricsCollectorHandle};
 use tari_core::{
     base_node::{
+        snapshot,
         state_machine_service::states::{PeerMetadata, StatusInfo},
         LocalNodeCommsInterface,
     },
@@ -690,6 +692,42 @@ impl CommandHandler {
         });
     }
 
+    /// Function to process the list-reorgs command
+    pub fn list_reorgs(&self) {
+        let blockchain_db = self.blockchain_db.clone();
+        self.executor.spawn(async move {
+            let reorgs = match blockchain_db.fetch_all_reorgs().await {
+                Ok(reorgs) if reorgs.is_empty() => {
+                    println!("No reorgs have been recorded");
+                    return;
+                },
+                Ok(reorgs) => reorgs,
+                Err(err) => {
+                    println!("Failed to retrieve reorgs: {:?}", err);
+                    warn!(target: LOG_TARGET, "Error communicating with base node: {}", err,);
+                    return;
+                },
+            };
+
+            let mut table = Table::new();
+            table.set_titles(vec!["Time", "Fork Height", "From", "To", "Removed", "Added"]);
+            for reorg in &reorgs {
+                let local_time: DateTime<Utc> = reorg.local_time.into();
+                table.add_row(row![
+                    local_time.format("%Y-%m-%d %H:%M:%S"),
+                    reorg.fork_height,
+                    format!("#{} {}", reorg.prev_height, reorg.prev_hash.to_hex()),
+                    format!("#{} {}", reorg.new_height, reorg.new_hash.to_hex()),
+                    reorg.removed_blocks.len(),
+                    reorg.added_blocks.len(),
+                ]);
+            }
+            table.print_std();
+
+            println!("{} reorg(s)", reorgs.len());
+        });
+    }
+
     /// Function to process the get-headers command
     async fn get_headers(
         blockchain_db: &AsyncBlockchainDb<LMDBDatabase>,
@@ -756,6 +794,31 @@ impl CommandHandler {
         });
     }
 
+    /// Function to process the export-snapshot command
+    pub fn export_snapshot(&self, path: PathBuf, height: Option<u64>) {
+        let db = self.blockchain_db.clone();
+        self.executor.spawn(async move {
+            let height = match height {
+                Some(height) => height,
+                None => match db.get_chain_metadata().await {
+                    Ok(metadata) => metadata.height_of_longest_chain(),
+                    Err(err) => {
+                        println!("Error retrieving chain metadata: {}", err);
+                        return;
+                    },
+                },
+            };
+            println!("Exporting snapshot at height {} to {}", height, path.display());
+            match snapshot::export_snapshot(&db, height, &path).await {
+                Ok(info) => println!("{}", info),
+                Err(err) => {
+                    println!("Failed to export snapshot: {}", err);
+                    warn!(target: LOG_TARGET, "Failed to export snapshot: {}", err);
+                },
+            }
+        });
+    }
+
     /// Function to process the check-db command
     pub fn check_db(&self) {
         let mut node = self.node_service.clone();
@@ -38,20 +38,28 @@ use tari_common::GlobalConfig;
 use tari_comms::PeerManager;
 use tari_core::{
     base_node::{
-        comms_interface::Broadcast,
+        comms_interface::{BlockEvent, Broadcast},
         state_machine_service::states::BlockSyncInfo,
+        FeeEstimator,
         LocalNodeCommsInterface,
         StateMachineHandle,
     },
     blocks::{Block, BlockHeader, NewBlockTemplate},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockAddResult, ChainStorageError, LMDBDatabase, Reorg},
     consensus::{ConsensusManager, ConsensusManagerBuilder, Network},
     crypto::tari_utilities::hex::Hex,
-    mempool::{service::LocalMempoolService, TxStorageResponse},
+    mempool::{service::LocalMempoolService, BlockTemplatePolicy, Mempool, MempoolTransactionEvent, TxStorageResponse},
     proof_of_work::PowAlgorithm,
-    transactions::{transaction::Transaction, types::Signature},
+    transactions::{
+        transaction::Transaction,
+        types::{Commitment, HashOutput, Signature},
+    },
+};
+use tari_crypto::tari_utilities::{message_format::MessageFormat, ByteArray, Hashable};
+use tokio::{
+    runtime,
+    sync::{broadcast, mpsc},
 };
-use tari_crypto::tari_utilities::{message_format::MessageFormat, Hashable};
-use tokio::{runtime, sync::mpsc};
 use tonic::{Request, Response, Status};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -72,6 +80,17 @@ const LIST_HEADERS_MAX_NUM_HEADERS: u64 = 10_000;
 const LIST_HEADERS_PAGE_SIZE: usize = 10;
 // The `num_headers` value if none is provided.
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
+// The maximum number of commitments that can be looked up in one GetCommitmentHistory request
+const GET_COMMITMENT_HISTORY_MAX_COMMITMENTS: usize = 1_000;
+// The maximum, and default, number of inputs or outputs returned by GetBlockInputs and GetBlockOutputs
+const GET_BLOCK_CONTENTS_MAX_LIMIT: u64 = 1_000;
+// The number of chain events that are buffered for a StreamChainEvents client before block events are held up
+const CHAIN_EVENTS_BUFFER_SIZE: usize = 100;
+// The number of blocks that are buffered for a StreamBlocks client before block events are held up
+const STREAM_BLOCKS_BUFFER_SIZE: usize = 10;
+// The number of mempool events that are buffered for a StreamMempoolEvents client before mempool events are held up,
+// enough for all the transactions that leave the mempool when a full block is mined
+const MEMPOOL_EVENTS_BUFFER_SIZE: usize = 2_000;
 
 pub struct BaseNodeGrpcServer {
     executor: runtime::Handle,
@@ -81,6 +100,8 @@ pub struct BaseNodeGrpcServer {
     state_machine_handle: StateMachineHandle,
     peer_manager: Arc<PeerManager>,
     consensus_rules: ConsensusManager,
+    blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    fee_estimator: FeeEstimator<LMDBDatabase>,
 }
 
 impl BaseNodeGrpcServer {
@@ -91,18 +112,98 @@ impl BaseNodeGrpcServer {
         node_config: GlobalConfig,
         state_machine_handle: StateMachineHandle,
         peer_manager: Arc<PeerManager>,
+        blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+        mempool: Mempool,
     ) -> Self
     {
+        let consensus_rules = ConsensusManager::builder(node_config.network.into()).build();
+        let fee_estimator = FeeEstimator::new(blockchain_db.clone(), mempool, consensus_rules.clone());
         Self {
             executor,
             node_service: local_node,
             mempool_service: local_mempool,
-            consensus_rules: ConsensusManager::builder(node_config.network.into()).build(),
+            consensus_rules,
             node_config,
             state_machine_handle,
             peer_manager,
+            blockchain_db,
+            fee_estimator,
         }
     }
+
+    /// Finds the header of a block in the best chain by hash, or by height if no hash is given
+    async fn fetch_requested_header(&self, hash: Vec<u8>, height: u64) -> Result<BlockHeader, Status> {
+        let header = if hash.is_empty() {
+            self.blockchain_db.fetch_header(height).await
+        } else {
+            self.blockchain_db.fetch_header_by_block_hash(hash).await
+        };
+        header
+            .map_err(|err| Status::internal(err.to_string()))?
+            .ok_or_else(|| Status::not_found("Block not found"))
+    }
+}
+
+/// Returns the chain events that are streamed to StreamChainEvents clients for a block event
+fn chain_events_from_block_event(block_event: &BlockEvent) -> Vec<tari_rpc::ChainEvent> {
+    use tari_rpc::chain_event::Event;
+    let new_tip = |height, hash| tari_rpc::ChainEvent {
+        event: Some(Event::NewTip(tari_rpc::NewTipEvent { height, hash })),
+    };
+    let reorg = |reorg: Reorg| tari_rpc::ChainEvent {
+        event: Some(Event::Reorg(reorg.into())),
+    };
+
+    match block_event {
+        BlockEvent::ValidBlockAdded(_, BlockAddResult::Ok(block), _) => {
+            vec![new_tip(block.block.header.height, block.accumulated_data.hash.clone())]
+        },
+        BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg(removed, added), _) => {
+            let event = Reorg::from_reorged_blocks(removed, added);
+            let tip = new_tip(event.new_height, event.new_hash.clone());
+            vec![reorg(event), tip]
+        },
+        BlockEvent::BlockSyncRewind(removed) if !removed.is_empty() => {
+            vec![reorg(Reorg::from_reorged_blocks(removed, &[]))]
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the hashes of the blocks that a block event added to the best chain, in ascending height order
+fn added_block_hashes(block_event: &BlockEvent) -> Vec<HashOutput> {
+    match block_event {
+        BlockEvent::ValidBlockAdded(_, BlockAddResult::Ok(block), _) => vec![block.accumulated_data.hash.clone()],
+        BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg(_, added), _) => {
+            added.iter().map(|block| block.accumulated_data.hash.clone()).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the event that is streamed to StreamMempoolEvents clients for a mempool transaction event
+fn mempool_event_from_transaction_event(transaction_event: &MempoolTransactionEvent) -> tari_rpc::MempoolEvent {
+    use tari_rpc::mempool_event::Event;
+    let event = match transaction_event {
+        MempoolTransactionEvent::Added(tx) => Event::TransactionAdded(tx.as_ref().clone().into()),
+        MempoolTransactionEvent::Removed(tx, reason) => {
+            let reason: tari_rpc::MempoolRemovalReason = (*reason).into();
+            Event::TransactionRemoved(tari_rpc::MempoolTransactionRemoved {
+                transaction: Some(tx.as_ref().clone().into()),
+                reason: reason as i32,
+            })
+        },
+    };
+    tari_rpc::MempoolEvent { event: Some(event) }
+}
+
+/// Returns the offset and limit of a block contents request as `(skip, take)`
+fn block_contents_page(request: &tari_rpc::GetBlockContentsRequest) -> (usize, usize) {
+    let limit = match request.limit {
+        0 => GET_BLOCK_CONTENTS_MAX_LIMIT,
+        limit => cmp::min(limit, GET_BLOCK_CONTENTS_MAX_LIMIT),
+    };
+    (request.offset as usize, limit as usize)
 }
 
 pub async fn get_heights(
@@ -117,12 +218,16 @@ pub async fn get_heights(
 impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type FetchMatchingUtxosStream = mpsc::Receiver<Result<tari_rpc::FetchMatchingUtxosResponse, Status>>;
     type GetBlocksStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type GetCommitmentHistoryStream = mpsc::Receiver<Result<tari_rpc::CommitmentHistory, Status>>;
     type GetMempoolTransactionsStream = mpsc::Receiver<Result<tari_rpc::GetMempoolTransactionsResponse, Status>>;
     type GetNetworkDifficultyStream = mpsc::Receiver<Result<tari_rpc::NetworkDifficultyResponse, Status>>;
     type GetPeersStream = mpsc::Receiver<Result<tari_rpc::GetPeersResponse, Status>>;
     type GetTokensInCirculationStream = mpsc::Receiver<Result<tari_rpc::ValueAtHeightResponse, Status>>;
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeader, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type StreamBlocksStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type StreamChainEventsStream = mpsc::Receiver<Result<tari_rpc::ChainEvent, Status>>;
+    type StreamMempoolEventsStream = mpsc::Receiver<Result<tari_rpc::MempoolEvent, Status>>;
 
     async fn get_network_difficulty(
         &self,
@@ -410,10 +515,15 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             .pow_algo as u64)
             .try_into()
             .map_err(|_| Status::invalid_argument("No valid pow algo selected".to_string()))?;
+        let policy = request
+            .policy
+            .map(BlockTemplatePolicy::try_from)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid block template policy: {}", e)))?;
         let mut handler = self.node_service.clone();
 
-        let new_template = handler
-            .get_new_block_template(algo, request.max_weight)
+        let (new_template, excluded_transactions) = handler
+            .get_new_block_template_with_policy(algo, request.max_weight, policy)
             .await
             .map_err(|e| {
                 warn!(
@@ -436,6 +546,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             new_block_template: Some(new_template.into()),
 
             initial_sync_achieved: (*status_watch.borrow()).bootstrapped,
+            excluded_transactions: excluded_transactions.into_iter().map(Into::into).collect(),
         };
 
         debug!(target: LOG_TARGET, "Sending GetNewBlockTemplate response to client");
@@ -1038,6 +1149,262 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             None => Err(Status::not_found(format!("Header not found with hash `{}`", hash_hex))),
         }
     }
+
+    async fn get_commitment_history(
+        &self,
+        request: Request<tari_rpc::GetCommitmentHistoryRequest>,
+    ) -> Result<Response<Self::GetCommitmentHistoryStream>, Status>
+    {
+        let request = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetCommitmentHistory: {} commitment(s)",
+            request.commitments.len()
+        );
+        if request.commitments.len() > GET_COMMITMENT_HISTORY_MAX_COMMITMENTS {
+            return Err(Status::invalid_argument(format!(
+                "At most {} commitments can be requested at a time",
+                GET_COMMITMENT_HISTORY_MAX_COMMITMENTS
+            )));
+        }
+        let commitments = request
+            .commitments
+            .iter()
+            .map(|c| Commitment::from_bytes(c))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Status::invalid_argument("Invalid commitment"))?;
+
+        let db = self.blockchain_db.clone();
+        let (mut tx, rx) = mpsc::channel(GET_BLOCKS_PAGE_SIZE);
+        self.executor.spawn(async move {
+            for commitment in commitments {
+                let result = match db.fetch_commitment_history(commitment).await {
+                    Ok(Some(history)) => Ok(history.into()),
+                    Ok(None) => continue,
+                    Err(err @ ChainStorageError::IndexNotEnabled(_)) => {
+                        Err(Status::failed_precondition(err.to_string()))
+                    },
+                    Err(err) => {
+                        warn!(target: LOG_TARGET, "Error fetching commitment history: {}", err);
+                        Err(Status::internal(err.to_string()))
+                    },
+                };
+                let is_err = result.is_err();
+                if let Err(err) = tx.send(result).await {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Error sending commitment history via GRPC:  {}", err
+                    );
+                    return;
+                }
+                if is_err {
+                    return;
+                }
+            }
+        });
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending GetCommitmentHistory response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
+    async fn get_block_outputs(
+        &self,
+        request: Request<tari_rpc::GetBlockContentsRequest>,
+    ) -> Result<Response<tari_rpc::GetBlockOutputsResponse>, Status>
+    {
+        let request = request.into_inner();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetBlockOutputs");
+        let (skip, take) = block_contents_page(&request);
+        let header = self.fetch_requested_header(request.hash, request.height).await?;
+        let block_hash = header.hash();
+        let outputs = self
+            .blockchain_db
+            .fetch_outputs_in_block(block_hash.clone(), skip, take)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        debug!(target: LOG_TARGET, "Sending GetBlockOutputs response to client");
+        Ok(Response::new(tari_rpc::GetBlockOutputsResponse {
+            block_hash,
+            height: header.height,
+            outputs: outputs.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_block_inputs(
+        &self,
+        request: Request<tari_rpc::GetBlockContentsRequest>,
+    ) -> Result<Response<tari_rpc::GetBlockInputsResponse>, Status>
+    {
+        let request = request.into_inner();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetBlockInputs");
+        let (skip, take) = block_contents_page(&request);
+        let header = self.fetch_requested_header(request.hash, request.height).await?;
+        let block_hash = header.hash();
+        let inputs = self
+            .blockchain_db
+            .fetch_inputs_in_block(block_hash.clone(), skip, take)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        debug!(target: LOG_TARGET, "Sending GetBlockInputs response to client");
+        Ok(Response::new(tari_rpc::GetBlockInputsResponse {
+            block_hash,
+            height: header.height,
+            inputs: inputs.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_fee_per_gram_estimate(
+        &self,
+        request: Request<tari_rpc::FeePerGramEstimateRequest>,
+    ) -> Result<Response<tari_rpc::FeePerGramEstimateResponse>, Status>
+    {
+        let request = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetFeePerGramEstimate (target = {})", request.confirmation_target
+        );
+        let estimate = self
+            .fee_estimator
+            .estimate_fee_per_gram(request.confirmation_target)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        debug!(target: LOG_TARGET, "Sending GetFeePerGramEstimate response to client");
+        Ok(Response::new(tari_rpc::FeePerGramEstimateResponse {
+            fee_per_gram: estimate.fee_per_gram.into(),
+            confirmation_target: estimate.confirmation_target,
+            tip_height: estimate.tip_height,
+        }))
+    }
+
+    async fn stream_chain_events(
+        &self,
+        _request: Request<tari_rpc::Empty>,
+    ) -> Result<Response<Self::StreamChainEventsStream>, Status>
+    {
+        debug!(target: LOG_TARGET, "Incoming GRPC request for StreamChainEvents");
+        let mut block_events = self.node_service.get_block_event_stream();
+        let (mut tx, rx) = mpsc::channel(CHAIN_EVENTS_BUFFER_SIZE);
+        self.executor.spawn(async move {
+            loop {
+                let block_event = match block_events.recv().await {
+                    Ok(block_event) => block_event,
+                    Err(broadcast::RecvError::Lagged(n)) => {
+                        // The client can no longer rely on the stream to track the chain, so it is ended
+                        warn!(
+                            target: LOG_TARGET,
+                            "StreamChainEvents client missed {} block event(s)", n
+                        );
+                        let status = Status::data_loss(format!("{} chain event(s) were missed", n));
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    },
+                    Err(broadcast::RecvError::Closed) => return,
+                };
+
+                for chain_event in chain_events_from_block_event(&block_event) {
+                    if tx.send(Ok(chain_event)).await.is_err() {
+                        debug!(target: LOG_TARGET, "StreamChainEvents client disconnected");
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(rx))
+    }
+
+    async fn stream_blocks(
+        &self,
+        _request: Request<tari_rpc::Empty>,
+    ) -> Result<Response<Self::StreamBlocksStream>, Status>
+    {
+        debug!(target: LOG_TARGET, "Incoming GRPC request for StreamBlocks");
+        let mut block_events = self.node_service.get_block_event_stream();
+        let blockchain_db = self.blockchain_db.clone();
+        let (mut tx, rx) = mpsc::channel(STREAM_BLOCKS_BUFFER_SIZE);
+        self.executor.spawn(async move {
+            loop {
+                let block_event = match block_events.recv().await {
+                    Ok(block_event) => block_event,
+                    Err(broadcast::RecvError::Lagged(n)) => {
+                        warn!(target: LOG_TARGET, "StreamBlocks client missed {} block event(s)", n);
+                        let status = Status::data_loss(format!("{} block event(s) were missed", n));
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    },
+                    Err(broadcast::RecvError::Closed) => return,
+                };
+
+                for hash in added_block_hashes(&block_event) {
+                    let block = match blockchain_db.fetch_block_by_hash(hash.clone()).await {
+                        Ok(Some(block)) => tari_rpc::HistoricalBlock::try_from(block)
+                            .map_err(|err| Status::internal(format!("Could not provide block: {}", err))),
+                        Ok(None) => {
+                            // The block was reorged out before it could be fetched
+                            debug!(
+                                target: LOG_TARGET,
+                                "Block {} is no longer in the database, not streaming it",
+                                hash.to_hex()
+                            );
+                            continue;
+                        },
+                        Err(err) => Err(Status::internal(err.to_string())),
+                    };
+                    let is_err = block.is_err();
+                    if tx.send(block).await.is_err() {
+                        debug!(target: LOG_TARGET, "StreamBlocks client disconnected");
+                        return;
+                    }
+                    if is_err {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(rx))
+    }
+
+    async fn stream_mempool_events(
+        &self,
+        _request: Request<tari_rpc::Empty>,
+    ) -> Result<Response<Self::StreamMempoolEventsStream>, Status>
+    {
+        debug!(target: LOG_TARGET, "Incoming GRPC request for StreamMempoolEvents");
+        let mut transaction_events = self.mempool_service.get_mempool_transaction_event_stream();
+        let (mut tx, rx) = mpsc::channel(MEMPOOL_EVENTS_BUFFER_SIZE);
+        self.executor.spawn(async move {
+            loop {
+                let transaction_event = match transaction_events.recv().await {
+                    Ok(transaction_event) => transaction_event,
+                    Err(broadcast::RecvError::Lagged(n)) => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "StreamMempoolEvents client missed {} mempool event(s)", n
+                        );
+                        let status = Status::data_loss(format!("{} mempool event(s) were missed", n));
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    },
+                    Err(broadcast::RecvError::Closed) => return,
+                };
+
+                let mempool_event = mempool_event_from_transaction_event(&transaction_event);
+                if tx.send(Ok(mempool_event)).await.is_err() {
+                    debug!(target: LOG_TARGET, "StreamMempoolEvents client disconnected");
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(rx))
+    }
 }
 
 enum BlockGroupType {
@@ -89,8 +89,10 @@ mod builder;
 mod cli;
 mod command_handler;
 mod grpc;
+mod metrics;
 mod parser;
 mod recovery;
+mod snapshot;
 mod utils;
 
 use crate::command_handler::CommandHandler;
@@ -110,6 +112,7 @@ use tari_app_utilities::{
 };
 use tari_common::configuration::bootstrap::ApplicationType;
 use tari_comms::peer_manager::PeerFeatures;
+use tari_core::mempool::MempoolPersistence;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::{task, time};
 use tonic::transport::Server;
@@ -168,6 +171,15 @@ fn main_inner() -> Result<(), ExitCodes> {
         return Ok(());
     }
 
+    if let Some(path) = &bootstrap.import_snapshot {
+        rt.block_on(snapshot::import_snapshot(&node_config, path))
+            .map_err(|err| {
+                error!(target: LOG_TARGET, "Failed to import chain snapshot: {}", err);
+                println!("Failed to import chain snapshot: {}", err);
+                ExitCodes::UnknownError
+            })?;
+    }
+
     // Build, node, build!
     let ctx = rt
         .block_on(builder::configure_and_initialize_node(
@@ -190,11 +202,40 @@ fn main_inner() -> Result<(), ExitCodes> {
             node_config.clone(),
             ctx.state_machine(),
             ctx.base_node_comms().peer_manager(),
+            ctx.blockchain_db().into(),
+            ctx.mempool(),
         );
 
         rt.spawn(run_grpc(grpc, node_config.grpc_base_node_address, shutdown.to_signal()));
     }
 
+    let mempool_persistence_handle = if node_config.mempool_persistence {
+        let persistence = MempoolPersistence::new(
+            ctx.mempool(),
+            node_config.mempool_persistence_file.clone(),
+            Duration::from_secs(node_config.mempool_persistence_interval),
+        );
+        if let Err(err) = persistence.restore() {
+            warn!(
+                target: LOG_TARGET,
+                "Unable to restore saved mempool transactions: {}", err
+            );
+        }
+        Some(rt.spawn(persistence.run(shutdown.to_signal())))
+    } else {
+        None
+    };
+
+    if let Some(address) = node_config.metrics_server_address {
+        match metrics::MetricsUpdater::new(&ctx) {
+            Ok(updater) => {
+                rt.spawn(updater.run(shutdown.to_signal()));
+                rt.spawn(metrics::run_metrics_server(address, shutdown.to_signal()));
+            },
+            Err(err) => warn!(target: LOG_TARGET, "Unable to register base node metrics: {}", err),
+        }
+    }
+
     // Run, node, run!
     let base_node_handle;
     let command_handler = Arc::new(CommandHandler::new(rt.handle().clone(), &ctx));
@@ -214,7 +255,15 @@ fn main_inner() -> Result<(), ExitCodes> {
         base_node_handle = rt.spawn(ctx.run());
     }
     match rt.block_on(base_node_handle) {
-        Ok(_) => info!(target: LOG_TARGET, "Node shutdown successfully."),
+        Ok(_) => {
+            info!(target: LOG_TARGET, "Node shutdown successfully.");
+            // Wait for the mempool to be saved before the runtime is dropped
+            if let Some(handle) = mempool_persistence_handle {
+                if let Err(e) = rt.block_on(handle) {
+                    error!(target: LOG_TARGET, "Mempool persistence task failed: {}", e);
+                }
+            }
+        },
         Err(e) => error!(target: LOG_TARGET, "Node has crashed: {}", e),
     }
 
@@ -0,0 +1,169 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Periodically samples the state of the base node into the process-wide metrics registry, and serves the registry
+//! over HTTP when `metrics_server_address` is configured.
+
+use crate::builder::BaseNodeContext;
+use futures::FutureExt;
+use log::*;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::PeerManager};
+use tari_core::{
+    base_node::state_machine_service::states::{StateInfo, StatusInfo},
+    chain_storage::{async_db::AsyncBlockchainDb, LMDBDatabase},
+    mempool::service::LocalMempoolService,
+};
+use tari_metrics::{server::MetricsServer, IntGauge, IntGaugeVec, MetricsError};
+use tari_shutdown::ShutdownSignal;
+use tokio::{sync::watch, time};
+
+const LOG_TARGET: &str = "base_node::app::metrics";
+
+/// How often the node state gauges are refreshed
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+const SYNC_STATES: [&str; 6] = [
+    "starting",
+    "header_sync",
+    "horizon_sync",
+    "block_sync",
+    "backfill_sync",
+    "listening",
+];
+
+pub struct MetricsUpdater {
+    chain_height: IntGauge,
+    sync_state: IntGaugeVec,
+    mempool_txs: IntGaugeVec,
+    mempool_weight: IntGauge,
+    peers: IntGauge,
+    active_connections: IntGauge,
+    blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    state_machine_info: watch::Receiver<StatusInfo>,
+    mempool: LocalMempoolService,
+    peer_manager: Arc<PeerManager>,
+    connectivity: ConnectivityRequester,
+}
+
+impl MetricsUpdater {
+    pub fn new(ctx: &BaseNodeContext) -> Result<Self, MetricsError> {
+        Ok(Self {
+            chain_height: tari_metrics::register_int_gauge(
+                "base_node_chain_height",
+                "The height of the longest chain known to this node",
+            )?,
+            sync_state: tari_metrics::register_int_gauge_vec(
+                "base_node_sync_state",
+                "Set to 1 for the state the base node state machine is currently in, otherwise 0",
+                &["state"],
+            )?,
+            mempool_txs: tari_metrics::register_int_gauge_vec(
+                "base_node_mempool_transactions",
+                "The number of transactions in each mempool pool",
+                &["pool"],
+            )?,
+            mempool_weight: tari_metrics::register_int_gauge(
+                "base_node_mempool_weight",
+                "The total weight of the transactions in the mempool",
+            )?,
+            peers: tari_metrics::register_int_gauge("base_node_peers", "The number of peers in the peer database")?,
+            active_connections: tari_metrics::register_int_gauge(
+                "base_node_active_connections",
+                "The number of active peer connections",
+            )?,
+            blockchain_db: ctx.blockchain_db().into(),
+            state_machine_info: ctx.get_state_machine_info_channel(),
+            mempool: ctx.local_mempool(),
+            peer_manager: ctx.base_node_comms().peer_manager(),
+            connectivity: ctx.base_node_comms().connectivity(),
+        })
+    }
+
+    /// Refreshes the gauges every `UPDATE_INTERVAL` until the shutdown signal is triggered
+    pub async fn run(mut self, mut shutdown_signal: ShutdownSignal) {
+        loop {
+            self.update().await;
+
+            let mut delay = time::delay_for(UPDATE_INTERVAL).fuse();
+            futures::select! {
+                () = delay => {},
+                _ = shutdown_signal => {
+                    info!(target: LOG_TARGET, "Metrics updater shutting down because the shutdown signal was received");
+                    break;
+                },
+            }
+        }
+    }
+
+    async fn update(&mut self) {
+        match self.blockchain_db.get_chain_metadata().await {
+            Ok(metadata) => self.chain_height.set(metadata.height_of_longest_chain() as i64),
+            Err(err) => warn!(target: LOG_TARGET, "Unable to fetch chain metadata: {}", err),
+        }
+
+        let current = sync_state_label(&self.state_machine_info.borrow().state_info);
+        for state in SYNC_STATES.iter() {
+            self.sync_state
+                .with_label_values(&[state])
+                .set(if *state == current { 1 } else { 0 });
+        }
+
+        match self.mempool.get_mempool_stats().await {
+            Ok(stats) => {
+                self.mempool_txs
+                    .with_label_values(&["unconfirmed"])
+                    .set(stats.unconfirmed_txs as i64);
+                self.mempool_txs
+                    .with_label_values(&["reorg"])
+                    .set(stats.reorg_txs as i64);
+                self.mempool_weight.set(stats.total_weight as i64);
+            },
+            Err(err) => warn!(target: LOG_TARGET, "Unable to fetch mempool stats: {}", err),
+        }
+
+        self.peers.set(self.peer_manager.count().await as i64);
+
+        match self.connectivity.get_active_connections().await {
+            Ok(conns) => self.active_connections.set(conns.len() as i64),
+            Err(err) => warn!(target: LOG_TARGET, "Unable to fetch active connections: {}", err),
+        }
+    }
+}
+
+fn sync_state_label(state: &StateInfo) -> &'static str {
+    match state {
+        StateInfo::StartUp => "starting",
+        StateInfo::HeaderSync(_) => "header_sync",
+        StateInfo::HorizonSync(_) => "horizon_sync",
+        StateInfo::BlockSync(_) => "block_sync",
+        StateInfo::BackfillSync(_) => "backfill_sync",
+        StateInfo::Listening(_) => "listening",
+    }
+}
+
+/// Serves the metrics registry on `address` until the shutdown signal is triggered
+pub async fn run_metrics_server(address: SocketAddr, shutdown_signal: ShutdownSignal) {
+    if let Err(err) = MetricsServer::new(address).run(shutdown_signal).await {
+        error!(target: LOG_TARGET, "Metrics server failed: {}", err);
+    }
+}
@@ -32,7 +32,7 @@ use rustyline::{
     Context,
 };
 use rustyline_derive::{Helper, Highlighter, Validator};
-use std::{str::FromStr, string::ToString, sync::Arc, time::Duration};
+use std::{path::PathBuf, str::FromStr, string::ToString, sync::Arc, time::Duration};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter, EnumString};
 use tari_app_utilities::utilities::{
@@ -65,7 +65,9 @@ pub enum BaseNodeCommand {
     ListBannedPeers,
     ListConnections,
     ListHeaders,
+    ListReorgs,
     CheckDb,
+    ExportSnapshot,
     PeriodStats,
     HeaderStats,
     CalcTiming,
@@ -198,6 +200,9 @@ impl Parser {
             CheckDb => {
                 self.command_handler.check_db();
             },
+            ExportSnapshot => {
+                self.process_export_snapshot(args);
+            },
             PeriodStats => {
                 self.process_period_stats(args);
             },
@@ -222,6 +227,9 @@ impl Parser {
             ListHeaders => {
                 self.process_list_headers(args);
             },
+            ListReorgs => {
+                self.command_handler.list_reorgs();
+            },
             CalcTiming => {
                 self.process_calc_timing(args);
             },
@@ -308,6 +316,10 @@ impl Parser {
             CheckDb => {
                 println!("Checks the blockchain database for missing blocks and headers");
             },
+            ExportSnapshot => {
+                println!("Exports a checksummed snapshot of the chain that a new node can import at startup");
+                println!("export-snapshot [file path] [height, defaults to the chain tip]");
+            },
             HeaderStats => {
                 println!(
                     "Prints out certain stats to of the block chain in csv format for easy copy, use as follows: "
@@ -332,6 +344,9 @@ impl Parser {
                 println!("list-headers [first header height] [last header height]");
                 println!("list-headers [number of headers starting from the chain tip back]");
             },
+            ListReorgs => {
+                println!("Lists the most recent chain reorgs that this node has seen, oldest first");
+            },
             CalcTiming => {
                 println!("Calculates the time average time taken to mine a given range of blocks.");
             },
@@ -591,6 +606,27 @@ impl Parser {
         self.command_handler.list_headers(start, end)
     }
 
+    /// Function to process the export-snapshot command
+    fn process_export_snapshot<'a, I: Iterator<Item = &'a str>>(&self, mut args: I) {
+        let path = match args.next() {
+            Some(path) => PathBuf::from(path),
+            None => {
+                println!("Command entered incorrectly, please use the following format: ");
+                println!("export-snapshot [file path] [height, defaults to the chain tip]");
+                return;
+            },
+        };
+        let height = match args.next().map(u64::from_str) {
+            Some(Ok(height)) => Some(height),
+            Some(Err(_)) => {
+                println!("Not a valid height provided");
+                return;
+            },
+            None => None,
+        };
+        self.command_handler.export_snapshot(path, height)
+    }
+
     /// Function to process the calc-timing command
     fn process_calc_timing<'a, I: Iterator<Item = &'a str>>(&self, mut args: I) {
         let start = args.next().map(u64::from_str).map(Result::ok).flatten();
@@ -0,0 +1,90 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use anyhow::anyhow;
+use log::*;
+use std::path::Path;
+use tari_common::{DatabaseType, GlobalConfig};
+use tari_core::{
+    base_node::{snapshot::SnapshotImporter, SyncValidators},
+    chain_storage::{
+        async_db::AsyncBlockchainDb,
+        create_lmdb_database,
+        BlockchainDatabase,
+        BlockchainDatabaseConfig,
+        Validators,
+    },
+    consensus::ConsensusManagerBuilder,
+    proof_of_work::randomx_factory::{RandomXConfig, RandomXFactory},
+    transactions::types::CryptoFactories,
+    validation::{
+        block_validators::{BodyOnlyValidator, OrphanBlockValidator},
+        header_validator::HeaderValidator,
+    },
+};
+
+pub const LOG_TARGET: &str = "base_node::app::snapshot";
+
+/// Imports the chain snapshot at `path` into the node's blockchain database, which must not contain any blocks after
+/// the genesis block.
+pub async fn import_snapshot(node_config: &GlobalConfig, path: &Path) -> Result<(), anyhow::Error> {
+    let backend = match &node_config.db_type {
+        DatabaseType::LMDB(p) => create_lmdb_database(&p, node_config.db_config.clone()).map_err(|e| {
+            error!(target: LOG_TARGET, "Error opening db: {}", e);
+            anyhow!("Could not open DB: {}", e)
+        })?,
+        _ => {
+            error!(target: LOG_TARGET, "Snapshot import is only available for LMDB");
+            return Err(anyhow!("Snapshot import is only available for LMDB"));
+        },
+    };
+    let rules = ConsensusManagerBuilder::new(node_config.network.into()).build();
+    let factories = CryptoFactories::default();
+    let randomx_factory = RandomXFactory::new(RandomXConfig::default(), node_config.max_randomx_vms);
+    let validators = Validators::new(
+        BodyOnlyValidator::default(),
+        HeaderValidator::new(rules.clone(), randomx_factory.clone()),
+        OrphanBlockValidator::new(rules.clone(), factories.clone()),
+    );
+    let db_config = BlockchainDatabaseConfig {
+        orphan_storage_capacity: node_config.orphan_storage_capacity,
+        pruning_horizon: node_config.pruning_horizon,
+        pruning_interval: node_config.pruned_mode_cleanup_interval,
+    };
+    let db = BlockchainDatabase::new(backend, &rules, validators, db_config, false)?;
+    let db = AsyncBlockchainDb::from(db);
+
+    println!(
+        "Importing chain snapshot from {}. This may take a while.",
+        path.display()
+    );
+    let importer = SnapshotImporter::new(
+        db,
+        rules.clone(),
+        randomx_factory,
+        SyncValidators::full_consensus(rules, factories.clone()),
+        factories,
+    );
+    let info = importer.import(path).await?;
+    println!("Imported {}", info);
+    Ok(())
+}
@@ -31,7 +31,13 @@ use std::{
 };
 use tari_app_utilities::utilities::parse_emoji_id_or_public_key;
 
-use tari_core::transactions::{tari_amount::MicroTari, types::PublicKey};
+use tari_core::{
+    tari_utilities::hex::Hex,
+    transactions::{
+        tari_amount::MicroTari,
+        types::{Commitment, PublicKey},
+    },
+};
 
 #[derive(Debug)]
 pub struct ParsedCommand {
@@ -50,6 +56,16 @@ impl Display for ParsedCommand {
             WalletCommand::Whois => "whois",
             WalletCommand::ListUtxos => "list-utxos",
             WalletCommand::CountUtxos => "count-utxos",
+            WalletCommand::ExportWatchOnlyKeys => "export-watch-only-keys",
+            WalletCommand::LabelUtxo => "label-utxo",
+            WalletCommand::NoteUtxo => "note-utxo",
+            WalletCommand::FreezeUtxo => "freeze-utxo",
+            WalletCommand::UnfreezeUtxo => "unfreeze-utxo",
+            WalletCommand::SendTariFromInputs => "send-tari-from-inputs",
+            WalletCommand::CoinSplitFromInputs => "coin-split-from-inputs",
+            WalletCommand::ExportUnsignedTx => "export-unsigned-tx",
+            WalletCommand::SignTx => "sign-tx",
+            WalletCommand::ImportTx => "import-tx",
         };
 
         let args = self
@@ -71,6 +87,8 @@ pub enum ParsedArgument {
     Float(f64),
     Int(u64),
     Date(DateTime<Utc>),
+    Commitment(Commitment),
+    Commitments(Vec<Commitment>),
 }
 
 impl Display for ParsedArgument {
@@ -82,6 +100,10 @@ impl Display for ParsedArgument {
             ParsedArgument::Float(v) => write!(f, "{}", v.to_string()),
             ParsedArgument::Int(v) => write!(f, "{}", v.to_string()),
             ParsedArgument::Date(v) => write!(f, "{}", v.to_string()),
+            ParsedArgument::Commitment(v) => write!(f, "{}", v.to_hex()),
+            ParsedArgument::Commitments(v) => {
+                write!(f, "{}", v.iter().map(|c| c.to_hex()).collect::<Vec<String>>().join(","))
+            },
         }
     }
 }
@@ -103,6 +125,14 @@ pub fn parse_command(command: &str) -> Result<ParsedCommand, ParseError> {
         Whois => parse_whois(args)?,
         ListUtxos => Vec::new(), // todo: only show X number of utxos
         CountUtxos => Vec::new(),
+        ExportWatchOnlyKeys => Vec::new(),
+        LabelUtxo | NoteUtxo => parse_utxo_text(args)?,
+        FreezeUtxo | UnfreezeUtxo => parse_utxos(args)?,
+        SendTariFromInputs => parse_send_tari_from_inputs(args)?,
+        CoinSplitFromInputs => parse_coin_split_from_inputs(args)?,
+        ExportUnsignedTx => parse_export_unsigned_tx(args)?,
+        SignTx => parse_files(args, 2)?,
+        ImportTx => parse_files(args, 1)?,
     };
 
     Ok(ParsedCommand { command, args })
@@ -233,11 +263,119 @@ fn parse_coin_split(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, Pa
     Ok(parsed_args)
 }
 
+fn parse_commitment(commitment: &str) -> Result<Commitment, ParseError> {
+    Commitment::from_hex(commitment).map_err(|_| ParseError::Commitment)
+}
+
+/// Parse a comma separated list of commitments
+fn parse_commitments(commitments: &str) -> Result<Vec<Commitment>, ParseError> {
+    commitments.split(',').map(parse_commitment).collect()
+}
+
+fn parse_utxo_text(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // commitment
+    let commitment = args.next().ok_or_else(|| ParseError::Empty("commitment".to_string()))?;
+    parsed_args.push(ParsedArgument::Commitment(parse_commitment(commitment)?));
+
+    // label or note, an empty text clears it
+    let text = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(text));
+
+    Ok(parsed_args)
+}
+
+fn parse_utxos(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let commitments = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("commitments".to_string()))?;
+    Ok(vec![ParsedArgument::Commitments(parse_commitments(commitments)?)])
+}
+
+fn parse_send_tari_from_inputs(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // inputs
+    let inputs = args.next().ok_or_else(|| ParseError::Empty("inputs".to_string()))?;
+    parsed_args.push(ParsedArgument::Commitments(parse_commitments(inputs)?));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_coin_split_from_inputs(args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut args = args;
+    let mut parsed_args = parse_coin_split(args.clone())?;
+
+    // inputs
+    let inputs = args.nth(2).ok_or_else(|| ParseError::Empty("inputs".to_string()))?;
+    parsed_args.push(ParsedArgument::Commitments(parse_commitments(inputs)?));
+
+    Ok(parsed_args)
+}
+
+fn parse_export_unsigned_tx(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // output file
+    let file = args.next().ok_or_else(|| ParseError::Empty("file".to_string()))?;
+    parsed_args.push(ParsedArgument::Text(file.to_string()));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+/// Parse up to two file paths of which the first `required` must be present
+fn parse_files(mut args: SplitWhitespace, required: usize) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    for name in &["input file", "output file"] {
+        match args.next() {
+            Some(file) => parsed_args.push(ParsedArgument::Text(file.to_string())),
+            None if parsed_args.len() < required => return Err(ParseError::Empty(name.to_string())),
+            None => break,
+        }
+    }
+
+    Ok(parsed_args)
+}
+
 #[test]
 fn test_parse_command() {
     use rand::rngs::OsRng;
-    use tari_core::transactions::types::PublicKey;
-    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_core::transactions::types::{CryptoFactories, PublicKey};
+    use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::PublicKey as PublicKeyTrait};
 
     let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
 
@@ -302,4 +440,46 @@ fn test_parse_command() {
     } else {
         panic!("Parsed public key is not the same as provided.");
     }
+
+    let factories = CryptoFactories::default();
+    let commitment = factories.commitment.zero();
+    let command_str = format!("freeze-utxo {},{}", commitment.to_hex(), commitment.to_hex());
+    let parsed = parse_command(&command_str).unwrap();
+
+    if let ParsedArgument::Commitments(commitments) = parsed.args[0].clone() {
+        assert_eq!(commitments, vec![commitment.clone(), commitment.clone()]);
+    } else {
+        panic!("Parsed commitments are not the same as provided.");
+    }
+
+    let command_str = format!("label-utxo {} cold storage", commitment.to_hex());
+    let parsed = parse_command(&command_str).unwrap();
+
+    if let ParsedArgument::Text(label) = parsed.args[1].clone() {
+        assert_eq!(label, "cold storage");
+    } else {
+        panic!("Parsed label is not the same as provided.");
+    }
+
+    let command_str = "label-utxo nothex cold storage";
+    let parsed = parse_command(command_str);
+    assert!(parsed.is_err());
+
+    let command_str = format!("export-unsigned-tx 1T {} unsigned.tx cold payment", public_key);
+    let parsed = parse_command(&command_str).unwrap();
+
+    if let (ParsedArgument::Text(file), ParsedArgument::Text(msg)) = (parsed.args[2].clone(), parsed.args[3].clone()) {
+        assert_eq!(file, "unsigned.tx");
+        assert_eq!(msg, "cold payment");
+    } else {
+        panic!("Parsed file and message are not the same as provided.");
+    }
+
+    let command_str = "sign-tx unsigned.tx";
+    let parsed = parse_command(command_str);
+    assert!(parsed.is_err());
+
+    let command_str = "import-tx finalized.tx";
+    let parsed = parse_command(command_str).unwrap();
+    assert_eq!(parsed.args.len(), 1);
 }
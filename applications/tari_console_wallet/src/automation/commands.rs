@@ -26,6 +26,7 @@ use chrono::{DateTime, Utc};
 use futures::{FutureExt, StreamExt};
 use log::*;
 use std::{
+    fs,
     str::FromStr,
     time::{Duration, Instant},
 };
@@ -35,7 +36,10 @@ use tari_comms::connectivity::{ConnectivityEvent, ConnectivityRequester};
 use tari_comms_dht::{envelope::NodeDestination, DhtDiscoveryRequester};
 use tari_core::{
     tari_utilities::hex::Hex,
-    transactions::tari_amount::{uT, MicroTari, Tari},
+    transactions::{
+        tari_amount::{uT, MicroTari, Tari},
+        transaction_protocol::partially_signed::PartiallySignedTransaction,
+    },
 };
 use tari_wallet::{
     output_manager_service::{handle::OutputManagerHandle, TxId},
@@ -64,6 +68,16 @@ pub enum WalletCommand {
     Whois,
     ListUtxos,
     CountUtxos,
+    ExportWatchOnlyKeys,
+    LabelUtxo,
+    NoteUtxo,
+    FreezeUtxo,
+    UnfreezeUtxo,
+    SendTariFromInputs,
+    CoinSplitFromInputs,
+    ExportUnsignedTx,
+    SignTx,
+    ImportTx,
 }
 
 #[derive(Debug, EnumString, PartialEq, Clone)]
@@ -112,6 +126,40 @@ pub async fn send_tari(
         .map_err(CommandError::Transaction)
 }
 
+pub async fn send_tari_from_inputs(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    args: Vec<ParsedArgument>,
+) -> Result<TxId, CommandError>
+{
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let dest_pubkey = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let inputs = match args[2].clone() {
+        Commitments(inputs) => Ok(inputs),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let message = match args[3].clone() {
+        Text(msg) => Ok(msg),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    wallet_transaction_service
+        .send_transaction_with_inputs(dest_pubkey, amount, fee_per_gram, message, inputs)
+        .await
+        .map_err(CommandError::Transaction)
+}
+
 pub async fn coin_split(
     args: &[ParsedArgument],
     output_service: &mut OutputManagerHandle,
@@ -129,9 +177,25 @@ pub async fn coin_split(
         _ => Err(CommandError::Argument),
     }?;
 
-    let (tx_id, tx, fee, amount) = output_service
-        .create_coin_split(amount_per_split, num_splits as usize, MicroTari(100), None)
-        .await?;
+    let (tx_id, tx, fee, amount) = match args.get(2) {
+        Some(Commitments(inputs)) => {
+            output_service
+                .create_coin_split_with_inputs(
+                    amount_per_split,
+                    num_splits as usize,
+                    MicroTari(100),
+                    None,
+                    inputs.clone(),
+                )
+                .await?
+        },
+        Some(_) => return Err(CommandError::Argument),
+        None => {
+            output_service
+                .create_coin_split(amount_per_split, num_splits as usize, MicroTari(100), None)
+                .await?
+        },
+    };
     transaction_service
         .submit_transaction(tx_id, tx, fee, amount, "Coin split".into())
         .await?;
@@ -139,6 +203,84 @@ pub async fn coin_split(
     Ok(tx_id)
 }
 
+fn read_partially_signed_transaction(path: &str) -> Result<PartiallySignedTransaction, CommandError> {
+    let bytes = fs::read(path)?;
+    PartiallySignedTransaction::from_bytes(&bytes).map_err(CommandError::PartiallySignedTransaction)
+}
+
+fn write_partially_signed_transaction(path: &str, pst: &PartiallySignedTransaction) -> Result<(), CommandError> {
+    fs::write(path, pst.to_bytes())?;
+    println!("{} transaction (TxId: {}) written to {}", pst.stage, pst.tx_id, path);
+    Ok(())
+}
+
+/// Create an unsigned transaction for an offline wallet to sign and write it to a file
+pub async fn export_unsigned_tx(wallet: &mut WalletSqlite, args: Vec<ParsedArgument>) -> Result<(), CommandError> {
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let dest_pubkey = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let (file, message) = match (args[2].clone(), args[3].clone()) {
+        (Text(file), Text(msg)) => Ok((file, msg)),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let pst = wallet
+        .create_unsigned_transaction(dest_pubkey, amount, fee_per_gram, message)
+        .await?;
+    write_partially_signed_transaction(&file, &pst)
+}
+
+/// Sign the partially signed transaction in the input file and write the next stage to the output file
+pub async fn sign_tx(wallet: &mut WalletSqlite, args: Vec<ParsedArgument>) -> Result<(), CommandError> {
+    let (input, output) = match (args[0].clone(), args[1].clone()) {
+        (ParsedArgument::Text(input), ParsedArgument::Text(output)) => Ok((input, output)),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let pst = read_partially_signed_transaction(&input)?;
+    let pst = wallet.sign_partially_signed_transaction(pst).await?;
+    write_partially_signed_transaction(&output, &pst)
+}
+
+/// Import the partially signed transaction in the input file. When importing a sender message the recipient's reply is
+/// written to the output file.
+pub async fn import_tx(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    args: Vec<ParsedArgument>,
+) -> Result<(), CommandError>
+{
+    let input = match args[0].clone() {
+        ParsedArgument::Text(input) => Ok(input),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let pst = read_partially_signed_transaction(&input)?;
+    let tx_id = pst.tx_id;
+    match wallet_transaction_service
+        .import_partially_signed_transaction(pst)
+        .await?
+    {
+        Some(next_stage) => match args.get(1) {
+            Some(ParsedArgument::Text(output)) => write_partially_signed_transaction(output, &next_stage),
+            _ => Err(CommandError::Argument),
+        },
+        None => {
+            println!("Transaction (TxId: {}) imported", tx_id);
+            Ok(())
+        },
+    }
+}
+
 async fn wait_for_comms(connectivity_requester: &ConnectivityRequester) -> Result<bool, CommandError> {
     let mut connectivity = connectivity_requester.get_event_subscription().fuse();
     print!("Waiting for connectivity... ");
@@ -392,7 +534,7 @@ pub async fn monitor_transactions(
 pub async fn command_runner(
     handle: Handle,
     commands: Vec<ParsedCommand>,
-    wallet: WalletSqlite,
+    mut wallet: WalletSqlite,
     config: GlobalConfig,
 ) -> Result<(), CommandError>
 {
@@ -436,7 +578,12 @@ pub async fn command_runner(
                 let rain_ids = make_it_rain(handle.clone(), transaction_service.clone(), parsed.args).await?;
                 tx_ids.extend(rain_ids);
             },
-            CoinSplit => {
+            SendTariFromInputs => {
+                let tx_id = send_tari_from_inputs(transaction_service.clone(), parsed.args).await?;
+                debug!(target: LOG_TARGET, "send-tari-from-inputs tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
+            CoinSplit | CoinSplitFromInputs => {
                 let tx_id = coin_split(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
                 tx_ids.push(tx_id);
                 println!("Coin split succeeded");
@@ -452,11 +599,24 @@ pub async fn command_runner(
                 println!("Emoji ID  : {}", emoji_id);
             },
             ListUtxos => {
-                let utxos = output_service.get_unspent_outputs().await?;
+                let utxos = output_service.get_unspent_output_details().await?;
                 let count = utxos.len();
-                let sum: MicroTari = utxos.iter().map(|utxo| utxo.value).sum();
+                let sum: MicroTari = utxos.iter().map(|utxo| utxo.unblinded_output.value).sum();
                 for (i, utxo) in utxos.iter().enumerate() {
-                    println!("{}. Value: {} {}", i + 1, utxo.value, utxo.features);
+                    println!(
+                        "{}. Value: {} {} Commitment: {}{}",
+                        i + 1,
+                        utxo.unblinded_output.value,
+                        utxo.unblinded_output.features,
+                        utxo.commitment.to_hex(),
+                        if utxo.frozen { " [frozen]" } else { "" }
+                    );
+                    if let Some(label) = &utxo.label {
+                        println!("   Label: {}", label);
+                    }
+                    if let Some(note) = &utxo.note {
+                        println!("   Note: {}", note);
+                    }
                 }
                 println!("Total number of UTXOs: {}", count);
                 println!("Total value of UTXOs: {}", sum);
@@ -480,6 +640,40 @@ pub async fn command_runner(
                     println!("Maximum value UTXO   : {}", max);
                 }
             },
+            ExportWatchOnlyKeys => {
+                let keys = output_service.get_watch_only_keys().await?;
+                println!("Rewind key          : {}", keys.rewind_key.to_hex());
+                println!("Rewind blinding key : {}", keys.rewind_blinding_key.to_hex());
+            },
+            LabelUtxo | NoteUtxo => {
+                let (commitment, text) = match (parsed.args[0].clone(), parsed.args[1].clone()) {
+                    (ParsedArgument::Commitment(c), ParsedArgument::Text(t)) => {
+                        Ok((c, Some(t).filter(|t| !t.is_empty())))
+                    },
+                    _ => Err(CommandError::Argument),
+                }?;
+                if parsed.command == LabelUtxo {
+                    output_service.set_output_label(commitment, text).await?;
+                } else {
+                    output_service.set_output_note(commitment, text).await?;
+                }
+                println!("UTXO updated");
+            },
+            FreezeUtxo | UnfreezeUtxo => {
+                let commitments = match parsed.args[0].clone() {
+                    ParsedArgument::Commitments(c) => Ok(c),
+                    _ => Err(CommandError::Argument),
+                }?;
+                let frozen = parsed.command == FreezeUtxo;
+                let count = commitments.len();
+                for commitment in commitments {
+                    output_service.set_output_frozen(commitment, frozen).await?;
+                }
+                println!("{} {} UTXOs", if frozen { "Froze" } else { "Unfroze" }, count);
+            },
+            ExportUnsignedTx => export_unsigned_tx(&mut wallet, parsed.args).await?,
+            SignTx => sign_tx(&mut wallet, parsed.args).await?,
+            ImportTx => import_tx(transaction_service.clone(), parsed.args).await?,
         }
     }
 
@@ -20,13 +20,17 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::num::{ParseFloatError, ParseIntError};
+use std::{
+    io,
+    num::{ParseFloatError, ParseIntError},
+};
 
 use chrono_english::DateError;
 use log::*;
 use tari_app_utilities::utilities::ExitCodes;
 use tari_core::transactions::tari_amount::MicroTariError;
 use tari_wallet::{
+    error::WalletError,
     output_manager_service::error::OutputManagerError,
     transaction_service::error::TransactionServiceError,
 };
@@ -49,6 +53,12 @@ pub enum CommandError {
     Config(String),
     #[error("Comms error `{0}`")]
     Comms(String),
+    #[error("Wallet error `{0}`")]
+    Wallet(#[from] WalletError),
+    #[error("File error `{0}`")]
+    File(#[from] io::Error),
+    #[error("Invalid partially signed transaction file `{0}`")]
+    PartiallySignedTransaction(String),
 }
 
 impl From<CommandError> for ExitCodes {
@@ -67,6 +77,8 @@ pub enum ParseError {
     MicroTariAmount(#[from] MicroTariError),
     #[error("Failed to parse public key or emoji id.")]
     PublicKey,
+    #[error("Failed to parse commitment.")]
+    Commitment,
     #[error("Failed to parse a missing {0}.")]
     Empty(String),
     #[error("Failed to parse float.")]
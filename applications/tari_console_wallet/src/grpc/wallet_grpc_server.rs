@@ -4,8 +4,11 @@ use tari_app_grpc::{
     conversions::naive_datetime_to_timestamp,
     tari_rpc::{
         wallet_server,
+        Account,
         CoinSplitRequest,
         CoinSplitResponse,
+        CreateAccountRequest,
+        CreateAccountResponse,
         GetBalanceRequest,
         GetBalanceResponse,
         GetCoinbaseRequest,
@@ -18,21 +21,37 @@ use tari_app_grpc::{
         GetTransactionInfoResponse,
         GetVersionRequest,
         GetVersionResponse,
+        ListAccountsRequest,
+        ListAccountsResponse,
+        ListUnspentOutputsRequest,
+        ListUnspentOutputsResponse,
+        SetOutputFrozenRequest,
+        SetOutputFrozenResponse,
+        SetOutputLabelRequest,
+        SetOutputLabelResponse,
+        SetOutputNoteRequest,
+        SetOutputNoteResponse,
         TransactionDirection,
         TransactionInfo,
         TransactionStatus,
         TransferRequest,
         TransferResponse,
         TransferResult,
+        UnspentOutput,
+        UtxoSelectionStrategy,
     },
 };
 use tari_comms::types::CommsPublicKey;
 use tari_core::{
-    tari_utilities::{hex::Hex, ByteArray},
-    transactions::tari_amount::MicroTari,
+    tari_utilities::{hex::Hex, ByteArray, ByteArrayError},
+    transactions::{tari_amount::MicroTari, types::Commitment},
 };
 use tari_wallet::{
-    output_manager_service::handle::OutputManagerHandle,
+    error::WalletError,
+    output_manager_service::{
+        error::{OutputManagerError, OutputManagerStorageError},
+        handle::OutputManagerHandle,
+    },
     transaction_service::{handle::TransactionServiceHandle, storage::models},
     WalletSqlite,
 };
@@ -57,6 +76,37 @@ impl WalletGrpcServer {
     fn get_output_manager_service(&self) -> OutputManagerHandle {
         self.wallet.output_manager_service.clone()
     }
+
+    /// Returns the transaction service handle for the named account, or for the active account if the name is empty
+    fn get_account_transaction_service(&self, account: &str) -> TransactionServiceHandle {
+        if account.is_empty() {
+            self.get_transaction_service()
+        } else {
+            self.wallet.transaction_service.with_account(account.to_string())
+        }
+    }
+
+    /// Returns the output manager handle for the named account, or for the active account if the name is empty
+    fn get_account_output_manager_service(&self, account: &str) -> OutputManagerHandle {
+        if account.is_empty() {
+            self.get_output_manager_service()
+        } else {
+            self.wallet.output_manager_service.with_account(account.to_string())
+        }
+    }
+
+    /// Returns the given fee per gram, or the base node's estimate if a confirmation target is set
+    async fn resolve_fee_per_gram(&self, fee_per_gram: u64, confirmation_target: u64) -> Result<MicroTari, Status> {
+        if confirmation_target == 0 {
+            return Ok(fee_per_gram.into());
+        }
+        self.wallet
+            .base_node_service
+            .clone()
+            .get_fee_per_gram_estimate(confirmation_target)
+            .await
+            .map_err(|e| Status::unavailable(format!("Could not estimate the fee per gram: {}", e)))
+    }
 }
 
 #[tonic::async_trait]
@@ -80,8 +130,9 @@ impl wallet_server::Wallet for WalletGrpcServer {
         }))
     }
 
-    async fn get_balance(&self, _request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
-        let mut output_service = self.get_output_manager_service();
+    async fn get_balance(&self, request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
+        let account = request.into_inner().account;
+        let mut output_service = self.get_account_output_manager_service(&account);
         let balance;
         match output_service.get_balance().await {
             Ok(b) => balance = b,
@@ -101,7 +152,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
     {
         let request = request.into_inner();
 
-        let mut tx_service = self.get_transaction_service();
+        let mut tx_service = self.get_account_transaction_service(&request.account);
         let response = tx_service
             .generate_coinbase_transaction(request.reward.into(), request.fee.into(), request.height)
             .await;
@@ -116,6 +167,7 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn transfer(&self, request: Request<TransferRequest>) -> Result<Response<TransferResponse>, Status> {
         let message = request.into_inner();
+        let account = message.account;
         let recipients = message
             .recipients
             .into_iter()
@@ -123,24 +175,63 @@ impl wallet_server::Wallet for WalletGrpcServer {
             .map(|(idx, dest)| -> Result<_, String> {
                 let pk = CommsPublicKey::from_hex(&dest.address)
                     .map_err(|_| format!("Destination address at index {} is malformed", idx))?;
-                Ok((dest.address, pk, dest.amount, dest.fee_per_gram, dest.message))
+                let utxo_selection_strategy = UtxoSelectionStrategy::from_i32(dest.utxo_selection_strategy)
+                    .ok_or_else(|| format!("UTXO selection strategy at index {} is invalid", idx))?;
+                let inputs =
+                    convert_commitments(&dest.inputs).map_err(|_| format!("Inputs at index {} are malformed", idx))?;
+                Ok((
+                    dest.address,
+                    pk,
+                    dest.amount,
+                    (dest.fee_per_gram, dest.confirmation_target),
+                    dest.message,
+                    utxo_selection_strategy,
+                    inputs,
+                ))
             })
             .collect::<Result<Vec<_>, _>>()
             .map_err(Status::invalid_argument)?;
 
-        let transfers = recipients
-            .into_iter()
-            .map(|(address, pk, amount, fee_per_gram, message)| {
-                let mut transaction_service = self.get_transaction_service();
+        let mut resolved_recipients = Vec::with_capacity(recipients.len());
+        for (address, pk, amount, (fee_per_gram, confirmation_target), message, utxo_selection_strategy, inputs) in
+            recipients
+        {
+            let fee_per_gram = self.resolve_fee_per_gram(fee_per_gram, confirmation_target).await?;
+            resolved_recipients.push((
+                address,
+                pk,
+                amount,
+                fee_per_gram,
+                message,
+                utxo_selection_strategy,
+                inputs,
+            ));
+        }
+        let recipients = resolved_recipients;
+
+        let transfers = recipients.into_iter().map(
+            |(address, pk, amount, fee_per_gram, message, utxo_selection_strategy, inputs)| {
+                let mut transaction_service = self.get_account_transaction_service(&account);
                 async move {
-                    (
-                        address,
+                    let result = if inputs.is_empty() {
                         transaction_service
-                            .send_transaction(pk, amount.into(), fee_per_gram.into(), message)
-                            .await,
-                    )
+                            .send_transaction_with_utxo_selection(
+                                pk,
+                                amount.into(),
+                                fee_per_gram,
+                                message,
+                                utxo_selection_strategy.into(),
+                            )
+                            .await
+                    } else {
+                        transaction_service
+                            .send_transaction_with_inputs(pk, amount.into(), fee_per_gram, message, inputs)
+                            .await
+                    };
+                    (address, result)
                 }
-            });
+            },
+        );
 
         let results = future::join_all(transfers).await;
 
@@ -209,18 +300,32 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
     async fn get_completed_transactions(
         &self,
-        _request: Request<GetCompletedTransactionsRequest>,
+        request: Request<GetCompletedTransactionsRequest>,
     ) -> Result<Response<Self::GetCompletedTransactionsStream>, Status>
     {
         debug!(
             target: LOG_TARGET,
             "Incoming GRPC request for GetAllCompletedTransactions"
         );
-        let mut transaction_service = self.get_transaction_service();
-        let transactions = transaction_service
-            .get_completed_transactions()
-            .await
-            .map_err(|err| Status::not_found(format!("No completed transactions found: {:?}", err)))?;
+        let account = request.into_inner().account;
+        let transactions = if account.is_empty() {
+            let mut transaction_service = self.get_transaction_service();
+            transaction_service
+                .get_completed_transactions()
+                .await
+                .map_err(|err| Status::not_found(format!("No completed transactions found: {:?}", err)))?
+        } else {
+            let mut wallet = self.wallet.clone();
+            wallet
+                .get_account_completed_transactions(&account)
+                .await
+                .map_err(|err| match err {
+                    WalletError::OutputManagerError(err @ OutputManagerError::AccountNotFound(_)) => {
+                        Status::not_found(err.to_string())
+                    },
+                    err => Status::not_found(format!("No completed transactions found: {:?}", err)),
+                })?
+        };
 
         let (mut sender, receiver) = mpsc::channel(transactions.len());
         task::spawn(async move {
@@ -274,20 +379,190 @@ impl wallet_server::Wallet for WalletGrpcServer {
             Some(message.lock_height)
         };
 
+        let inputs =
+            convert_commitments(&message.inputs).map_err(|_| Status::invalid_argument("Inputs are malformed"))?;
+        let fee_per_gram = self
+            .resolve_fee_per_gram(message.fee_per_gram, message.confirmation_target)
+            .await?;
+
         let mut wallet = self.wallet.clone();
+        wallet.output_manager_service = self.get_account_output_manager_service(&message.account);
+
+        let tx_id = if inputs.is_empty() {
+            wallet
+                .coin_split(
+                    MicroTari::from(message.amount_per_split),
+                    message.split_count as usize,
+                    fee_per_gram,
+                    message.message,
+                    lock_height,
+                )
+                .await
+        } else {
+            wallet
+                .coin_split_with_inputs(
+                    MicroTari::from(message.amount_per_split),
+                    message.split_count as usize,
+                    fee_per_gram,
+                    message.message,
+                    lock_height,
+                    inputs,
+                )
+                .await
+        }
+        .map_err(|e| Status::internal(format!("{:?}", e)))?;
 
-        let tx_id = wallet
-            .coin_split(
-                MicroTari::from(message.amount_per_split),
-                message.split_count as usize,
-                MicroTari::from(message.fee_per_gram),
-                message.message,
-                lock_height,
-            )
+        Ok(Response::new(CoinSplitResponse { tx_id }))
+    }
+
+    async fn list_unspent_outputs(
+        &self,
+        _request: Request<ListUnspentOutputsRequest>,
+    ) -> Result<Response<ListUnspentOutputsResponse>, Status>
+    {
+        let mut output_service = self.get_output_manager_service();
+        let outputs = output_service
+            .get_unspent_output_details()
             .await
-            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(|o| UnspentOutput {
+                commitment: o.commitment.to_vec(),
+                value: o.unblinded_output.value.into(),
+                maturity: o.unblinded_output.features.maturity,
+                label: o.label.unwrap_or_default(),
+                note: o.note.unwrap_or_default(),
+                frozen: o.frozen,
+            })
+            .collect();
 
-        Ok(Response::new(CoinSplitResponse { tx_id }))
+        Ok(Response::new(ListUnspentOutputsResponse { outputs }))
+    }
+
+    async fn set_output_label(
+        &self,
+        request: Request<SetOutputLabelRequest>,
+    ) -> Result<Response<SetOutputLabelResponse>, Status>
+    {
+        let message = request.into_inner();
+        let commitment = Commitment::from_bytes(&message.commitment)
+            .map_err(|_| Status::invalid_argument("Commitment is malformed"))?;
+        let label = Some(message.label).filter(|l| !l.is_empty());
+
+        let mut output_service = self.get_output_manager_service();
+        output_service
+            .set_output_label(commitment, label)
+            .await
+            .map_err(convert_output_metadata_error)?;
+
+        Ok(Response::new(SetOutputLabelResponse {}))
+    }
+
+    async fn set_output_note(
+        &self,
+        request: Request<SetOutputNoteRequest>,
+    ) -> Result<Response<SetOutputNoteResponse>, Status>
+    {
+        let message = request.into_inner();
+        let commitment = Commitment::from_bytes(&message.commitment)
+            .map_err(|_| Status::invalid_argument("Commitment is malformed"))?;
+        let note = Some(message.note).filter(|n| !n.is_empty());
+
+        let mut output_service = self.get_output_manager_service();
+        output_service
+            .set_output_note(commitment, note)
+            .await
+            .map_err(convert_output_metadata_error)?;
+
+        Ok(Response::new(SetOutputNoteResponse {}))
+    }
+
+    async fn set_output_frozen(
+        &self,
+        request: Request<SetOutputFrozenRequest>,
+    ) -> Result<Response<SetOutputFrozenResponse>, Status>
+    {
+        let message = request.into_inner();
+        let commitment = Commitment::from_bytes(&message.commitment)
+            .map_err(|_| Status::invalid_argument("Commitment is malformed"))?;
+
+        let mut output_service = self.get_output_manager_service();
+        output_service
+            .set_output_frozen(commitment, message.frozen)
+            .await
+            .map_err(convert_output_metadata_error)?;
+
+        Ok(Response::new(SetOutputFrozenResponse {}))
+    }
+
+    async fn create_account(
+        &self,
+        request: Request<CreateAccountRequest>,
+    ) -> Result<Response<CreateAccountResponse>, Status>
+    {
+        let name = request.into_inner().name;
+        if name.is_empty() {
+            return Err(Status::invalid_argument("Account name is empty"));
+        }
+
+        let mut output_service = self.get_output_manager_service();
+        let account = output_service
+            .create_account(name)
+            .await
+            .map_err(convert_account_error)?;
+
+        Ok(Response::new(CreateAccountResponse {
+            account: Some(Account {
+                id: account.id,
+                name: account.name,
+                active: false,
+            }),
+        }))
+    }
+
+    async fn list_accounts(
+        &self,
+        _request: Request<ListAccountsRequest>,
+    ) -> Result<Response<ListAccountsResponse>, Status>
+    {
+        let mut output_service = self.get_output_manager_service();
+        let active_account = output_service
+            .get_active_account()
+            .await
+            .map_err(convert_account_error)?;
+        let accounts = output_service.get_accounts().await.map_err(convert_account_error)?;
+
+        Ok(Response::new(ListAccountsResponse {
+            accounts: accounts
+                .into_iter()
+                .map(|a| Account {
+                    id: a.id,
+                    active: a.id == active_account.id,
+                    name: a.name,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn convert_commitments(commitments: &[Vec<u8>]) -> Result<Vec<Commitment>, ByteArrayError> {
+    commitments.iter().map(|c| Commitment::from_bytes(c)).collect()
+}
+
+fn convert_account_error(err: OutputManagerError) -> Status {
+    match err {
+        OutputManagerError::AccountNotFound(_) => Status::not_found(err.to_string()),
+        OutputManagerError::DuplicateAccount(_) => Status::already_exists(err.to_string()),
+        err => Status::internal(err.to_string()),
+    }
+}
+
+fn convert_output_metadata_error(err: OutputManagerError) -> Status {
+    match err {
+        OutputManagerError::OutputManagerStorageError(OutputManagerStorageError::ValuesNotFound) => {
+            Status::not_found("Output not found")
+        },
+        err => Status::internal(err.to_string()),
     }
 }
 
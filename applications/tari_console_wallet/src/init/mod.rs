@@ -59,6 +59,7 @@ use tari_wallet::{
                 DbKeyValuePair as OutputDbKeyValuePair,
                 KeyManagerState,
                 OutputManagerBackend,
+                WatchOnlyKeys,
                 WriteOperation as OutputWriteOperation,
             },
             sqlite_db::OutputManagerSqliteDatabase,
@@ -87,6 +88,7 @@ pub enum WalletBoot {
     New,
     Existing,
     Recovery,
+    WatchOnly,
 }
 
 /// Gets the password provided by command line argument or environment variable if available.
@@ -200,6 +202,11 @@ pub fn wallet_mode(bootstrap: ConfigBootstrap, boot_mode: WalletBoot) -> WalletM
         return WalletMode::Recovery;
     }
 
+    // Watch-only mode
+    if bootstrap.watch_only {
+        return WalletMode::WatchOnly;
+    }
+
     match (bootstrap.daemon_mode, bootstrap.input_file, bootstrap.command) {
         // TUI mode
         (false, None, None) => WalletMode::Tui,
@@ -260,6 +267,7 @@ pub async fn init_wallet(
     config: &GlobalConfig,
     arg_password: Option<String>,
     master_key: Option<PrivateKey>,
+    watch_only_keys: Option<WatchOnlyKeys>,
     shutdown_signal: ShutdownSignal,
 ) -> Result<WalletSqlite, ExitCodes>
 {
@@ -408,17 +416,34 @@ pub async fn init_wallet(
     wallet_config.buffer_size = std::cmp::max(BASE_NODE_BUFFER_MIN_SIZE, config.buffer_size_base_node);
 
     let recovery = set_master_key(&output_manager_backend, master_key).await?;
-
-    let mut wallet = Wallet::new(
-        wallet_config,
-        wallet_backend,
-        transaction_backend,
-        output_manager_backend,
-        contacts_backend,
-        shutdown_signal,
-    )
-    .await
-    .map_err(|e| {
+    let watch_only = watch_only_keys.is_some();
+
+    let wallet = match watch_only_keys {
+        Some(keys) => {
+            Wallet::new_watch_only(
+                wallet_config,
+                wallet_backend,
+                transaction_backend,
+                output_manager_backend,
+                contacts_backend,
+                shutdown_signal,
+                keys,
+            )
+            .await
+        },
+        None => {
+            Wallet::new(
+                wallet_config,
+                wallet_backend,
+                transaction_backend,
+                output_manager_backend,
+                contacts_backend,
+                shutdown_signal,
+            )
+            .await
+        },
+    };
+    let mut wallet = wallet.map_err(|e| {
         if let WalletError::CommsInitializationError(e) = e {
             ExitCodes::WalletError(e.to_friendly_string())
         } else {
@@ -457,7 +482,8 @@ pub async fn init_wallet(
 
         debug!(target: LOG_TARGET, "Wallet encrypted.");
 
-        if interactive && !recovery {
+        // A watch-only wallet has no seed words
+        if interactive && !recovery && !watch_only {
             confirm_seed_words(&mut wallet).await?;
         }
     }
@@ -634,6 +660,11 @@ pub(crate) fn boot(bootstrap: &ConfigBootstrap, config: &GlobalConfig) -> Result
         return Ok(WalletBoot::Recovery);
     }
 
+    // a new watch-only wallet is created from its rewind keys, an existing one is opened as normal
+    if bootstrap.watch_only && !wallet_exists {
+        return Ok(WalletBoot::WatchOnly);
+    }
+
     if wallet_exists {
         // normal startup of existing wallet
         Ok(WalletBoot::Existing)
@@ -22,7 +22,8 @@ use recovery::prompt_private_key_from_seed_words;
 use tari_app_utilities::{initialization::init_configuration, utilities::ExitCodes};
 use tari_common::configuration::bootstrap::ApplicationType;
 use tari_shutdown::Shutdown;
-use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, tui_mode, WalletMode};
+use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, tui_mode, watch_only_mode, WalletMode};
+use watch_only::prompt_watch_only_keys;
 
 pub const LOG_TARGET: &str = "wallet::console_wallet::main";
 
@@ -34,6 +35,7 @@ mod recovery;
 mod ui;
 mod utils;
 pub mod wallet_modes;
+mod watch_only;
 
 /// Application entry point
 fn main() {
@@ -75,6 +77,12 @@ fn main_inner() -> Result<(), ExitCodes> {
         None
     };
 
+    let watch_only_keys = if matches!(boot_mode, WalletBoot::WatchOnly) {
+        Some(prompt_watch_only_keys()?)
+    } else {
+        None
+    };
+
     if bootstrap.init {
         info!(target: LOG_TARGET, "Default configuration created. Done.");
     }
@@ -91,7 +99,13 @@ fn main_inner() -> Result<(), ExitCodes> {
     }
 
     // initialize wallet
-    let mut wallet = runtime.block_on(init_wallet(&config, arg_password, master_key, shutdown_signal))?;
+    let mut wallet = runtime.block_on(init_wallet(
+        &config,
+        arg_password,
+        master_key,
+        watch_only_keys,
+        shutdown_signal,
+    ))?;
 
     // get base node/s
     let base_node_config = runtime.block_on(get_base_node_peer_config(&config, &mut wallet))?;
@@ -126,6 +140,7 @@ fn main_inner() -> Result<(), ExitCodes> {
             base_node_config,
             notify_script,
         ),
+        WalletMode::WatchOnly => watch_only_mode(handle, config, wallet.clone()),
         WalletMode::Invalid => Err(ExitCodes::InputError(
             "Invalid wallet mode - are you trying too many command options at once?".to_string(),
         )),
@@ -20,22 +20,14 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use chrono::offset::Local;
 use futures::StreamExt;
 use log::*;
 use rustyline::Editor;
-use std::convert::TryFrom;
 use tari_app_utilities::utilities::ExitCodes;
 use tari_comms::peer_manager::Peer;
-use tari_core::{
-    base_node::sync::rpc,
-    blocks::BlockHeader,
-    proto::base_node::{SyncUtxosRequest, SyncUtxosResponse},
-    tari_utilities::{hex::Hex, Hashable},
-    transactions::{tari_amount::MicroTari, transaction::TransactionOutput, types::PrivateKey},
-};
+use tari_core::{tari_utilities::hex::Hex, transactions::types::PrivateKey};
 use tari_key_manager::mnemonic::to_secretkey;
-use tari_wallet::WalletSqlite;
+use tari_wallet::{utxo_scanner_service::handle::UtxoScannerEvent, WalletSqlite};
 
 pub const LOG_TARGET: &str = "wallet::recovery";
 
@@ -62,81 +54,69 @@ pub fn prompt_private_key_from_seed_words() -> Result<PrivateKey, ExitCodes> {
     }
 }
 
-/// Recovers wallet funds by connecting to a given base node peer, downloading the transaction outputs stored in the
-/// blockchain, and attempting to rewind them. Any outputs that are successfully rewound are then imported into the
-/// wallet.
+/// Recovers wallet funds by waiting for the wallet's UTXO scanner to scan the blockchain from the genesis block. Any
+/// outputs that can be rewound with the recovered master key are imported into the wallet as they are found.
 pub async fn wallet_recovery(wallet: &mut WalletSqlite, base_node: &Peer) -> Result<(), ExitCodes> {
     println!(
-        "Connecting to base node with public key: {}",
+        "Scanning the blockchain using base node with public key: {}",
         base_node.public_key.to_hex()
     );
 
-    let node_id = wallet.comms.node_identity();
-    let public_key = node_id.public_key();
-
-    let mut conn = wallet.comms.connectivity().dial_peer(base_node.node_id.clone()).await?;
-    let mut client = conn.connect_rpc::<rpc::BaseNodeSyncRpcClient>().await?;
-    println!("Base node connected.");
-
-    let latency = client.get_last_request_latency().await?;
-    println!("Latency: {} ms.", latency.unwrap_or_default().as_millis());
-
-    let chain_metadata = client.get_chain_metadata().await?;
-    let height = chain_metadata.height_of_longest_chain();
-    println!("Chain Height: {}.", height);
-
-    let header = client.get_header_by_height(height).await?;
-    let header = BlockHeader::try_from(header).map_err(ExitCodes::ConversionError)?;
-    let start = 0;
-    let end_header_hash = header.hash();
-    let request = SyncUtxosRequest { start, end_header_hash };
-    let mut output_stream = client.sync_utxos(request).await?;
-    println!("Streaming transaction outputs...");
-
-    let mut num_utxos = 0;
-    let mut total_amount = MicroTari::from(0);
-
-    while let Some(response) = output_stream.next().await {
-        let response: SyncUtxosResponse = response.map_err(|e| ExitCodes::ConversionError(e.to_string()))?;
-
-        let outputs: Vec<TransactionOutput> = response
-            .utxos
-            .into_iter()
-            .filter_map(|utxo| {
-                if let Some(output) = utxo.output {
-                    TransactionOutput::try_from(output).ok()
-                } else {
-                    None
-                }
-            })
-            .collect();
-        println!("Scanning {} outputs...", outputs.len());
-
-        let unblinded_outputs = wallet.output_manager_service.rewind_outputs(outputs).await?;
-
-        if !unblinded_outputs.is_empty() {
-            println!("Importing {} outputs...", unblinded_outputs.len());
-
-            for uo in unblinded_outputs {
-                wallet
-                    .import_utxo(
-                        uo.value,
-                        &uo.spending_key,
-                        public_key,
-                        format!("Recovered on {}.", Local::now()),
-                    )
-                    .await?;
+    let mut scanner_events = wallet.utxo_scanner_service.get_event_stream_fused();
+    // The scan may have already completed before we subscribed to its events
+    if let Some(block) = wallet
+        .utxo_scanner_service
+        .get_last_scanned_block()
+        .await
+        .map_err(|e| ExitCodes::RecoveryError(e.to_string()))?
+    {
+        println!("Blockchain already scanned up to height {}.", block.height);
+        return Ok(());
+    }
 
-                num_utxos += 1;
-                total_amount += uo.value;
-            }
+    while let Some(event) = scanner_events.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(target: LOG_TARGET, "Lagging on UTXO scanner event stream: {}", e);
+                continue;
+            },
+        };
+        match &*event {
+            UtxoScannerEvent::ScanningStarted { tip_height, .. } => {
+                println!("Chain Height: {}.", tip_height);
+                println!("Streaming transaction outputs...");
+            },
+            UtxoScannerEvent::ReorgDetected { fork_height } => {
+                println!("Chain reorg detected, rescanning from height {}.", fork_height);
+            },
+            UtxoScannerEvent::OutputsRecovered { num_outputs, value } => {
+                println!("Imported {} outputs with a value of {}.", num_outputs, value);
+            },
+            UtxoScannerEvent::Progress {
+                current_index,
+                total_index,
+            } => {
+                println!("Scanned {} of {} outputs...", current_index, total_index);
+            },
+            UtxoScannerEvent::ScanningCompleted {
+                num_recovered,
+                value_recovered,
+                ..
+            } => {
+                println!(
+                    "Recovered and imported {} outputs, with a total value of {}.",
+                    num_recovered, value_recovered
+                );
+                return Ok(());
+            },
+            UtxoScannerEvent::ScanningFailed(e) => {
+                return Err(ExitCodes::RecoveryError(e.clone()));
+            },
         }
     }
 
-    println!(
-        "Recovered and imported {} outputs, with a total value of {}.",
-        num_utxos, total_amount
-    );
-
-    Ok(())
+    Err(ExitCodes::RecoveryError(
+        "The UTXO scanner stopped before recovery completed".to_string(),
+    ))
 }
@@ -25,6 +25,7 @@ use crate::{
     notifier::Notifier,
     recovery::wallet_recovery,
     ui::{run, App},
+    watch_only::watch_only_reporting,
 };
 
 use log::*;
@@ -48,6 +49,7 @@ pub enum WalletMode {
     Script(PathBuf),
     Command(String),
     Recovery,
+    WatchOnly,
     Invalid,
 }
 
@@ -215,6 +217,19 @@ pub fn grpc_mode(handle: Handle, wallet: WalletSqlite, node_config: GlobalConfig
     Ok(())
 }
 
+/// Runs a watch-only wallet. The wallet's UTXO scanner finds the outputs that can be rewound with the wallet's rewind
+/// keys in the background while the gRPC server provides access to the balance and transaction history.
+pub fn watch_only_mode(handle: Handle, config: GlobalConfig, wallet: WalletSqlite) -> Result<(), ExitCodes> {
+    println!("Starting watch-only wallet");
+    let reporting_wallet = wallet.clone();
+    handle.spawn(async move {
+        if let Err(e) = watch_only_reporting(reporting_wallet).await {
+            error!(target: LOG_TARGET, "Watch-only reporting stopped: {}", e);
+        }
+    });
+    grpc_mode(handle, wallet, config)
+}
+
 async fn run_grpc(grpc: WalletGrpcServer, grpc_console_wallet_address: SocketAddr) -> Result<(), String> {
     info!(target: LOG_TARGET, "Starting GRPC on {}", grpc_console_wallet_address);
 
@@ -0,0 +1,107 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use futures::StreamExt;
+use log::*;
+use rustyline::Editor;
+use tari_app_utilities::utilities::ExitCodes;
+use tari_core::{tari_utilities::hex::Hex, transactions::types::PrivateKey};
+use tari_wallet::{
+    output_manager_service::storage::database::WatchOnlyKeys,
+    utxo_scanner_service::handle::UtxoScannerEvent,
+    WalletSqlite,
+};
+
+pub const LOG_TARGET: &str = "wallet::watch_only";
+
+/// Prompt the user to input the hex encoded rewind key and rewind blinding key of the wallet to watch.
+pub fn prompt_watch_only_keys() -> Result<WatchOnlyKeys, ExitCodes> {
+    debug!(target: LOG_TARGET, "Prompting for watch-only keys.");
+    let mut rl = Editor::<()>::new();
+
+    println!("Watch-only Mode");
+    println!();
+    println!("The rewind keys of a wallet can be exported with the `export-watch-only-keys` command.");
+    let rewind_key = prompt_private_key(&mut rl, "Type or paste the rewind key:")?;
+    let rewind_blinding_key = prompt_private_key(&mut rl, "Type or paste the rewind blinding key:")?;
+
+    Ok(WatchOnlyKeys {
+        rewind_key,
+        rewind_blinding_key,
+    })
+}
+
+fn prompt_private_key(rl: &mut Editor<()>, prompt: &str) -> Result<PrivateKey, ExitCodes> {
+    loop {
+        println!("{}", prompt);
+        let input = rl.readline(">> ").map_err(|e| ExitCodes::IOError(e.to_string()))?;
+
+        match PrivateKey::from_hex(input.trim()) {
+            Ok(key) => break Ok(key),
+            Err(e) => {
+                debug!(target: LOG_TARGET, "Error parsing private key: {}", e);
+                println!("Failed to parse the key! It should be a 64 character hex string.");
+                continue;
+            },
+        }
+    }
+}
+
+/// Reports the funds found by the wallet's UTXO scanner. The scanner rewinds the outputs in every new block with the
+/// wallet's rewind keys, this prints the updated balance whenever outputs belonging to the wallet are imported. This
+/// only returns once the UTXO scanner shuts down.
+pub async fn watch_only_reporting(mut wallet: WalletSqlite) -> Result<(), ExitCodes> {
+    let mut scanner_events = wallet.utxo_scanner_service.get_event_stream_fused();
+
+    while let Some(event) = scanner_events.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                trace!(target: LOG_TARGET, "Lagging on UTXO scanner event stream: {}", e);
+                continue;
+            },
+        };
+        match &*event {
+            UtxoScannerEvent::ScanningCompleted {
+                height,
+                num_recovered,
+                value_recovered,
+            } if *num_recovered > 0 => {
+                let balance = wallet.output_manager_service.get_balance().await?;
+                println!(
+                    "Imported {} outputs with a total value of {} up to height {}.\n{}",
+                    num_recovered, value_recovered, height, balance
+                );
+            },
+            UtxoScannerEvent::ReorgDetected { fork_height } => {
+                println!("Chain reorg detected, rescanning from height {}.", fork_height);
+            },
+            UtxoScannerEvent::ScanningFailed(e) => {
+                warn!(target: LOG_TARGET, "UTXO scan failed: {}", e);
+            },
+            _ => (),
+        }
+    }
+
+    info!(target: LOG_TARGET, "UTXO scanner event stream closed");
+    Ok(())
+}
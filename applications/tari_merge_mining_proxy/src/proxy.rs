@@ -446,12 +446,14 @@ impl InnerService {
             miner_data,
             new_block_template,
             initial_sync_achieved,
+            ..
         } = grpc_client
             .get_new_block_template(grpc::NewBlockTemplateRequest {
                 algo: Some(grpc::PowAlgo {
                     pow_algo: grpc::pow_algo::PowAlgos::Monero.into(),
                 }),
                 max_weight: 0,
+                policy: None,
             })
             .await
             .map_err(|status| MmProxyError::GrpcRequestError {
@@ -94,7 +94,11 @@ impl MinerConfig {
                 pow_algo: PowAlgos::Sha3.into(),
             }),
         };
-        NewBlockTemplateRequest { algo, max_weight: 0 }
+        NewBlockTemplateRequest {
+            algo,
+            max_weight: 0,
+            policy: None,
+        }
     }
 
     pub fn wait_timeout(&self) -> Duration {
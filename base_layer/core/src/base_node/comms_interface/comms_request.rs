@@ -23,6 +23,7 @@
 use crate::{
     blocks::NewBlockTemplate,
     chain_storage::MmrTree,
+    mempool::BlockTemplatePolicy,
     proof_of_work::PowAlgorithm,
     transactions::types::{Commitment, HashOutput, Signature},
 };
@@ -63,6 +64,8 @@ pub enum NodeCommsRequest {
 pub struct GetNewBlockTemplateRequest {
     pub algo: PowAlgorithm,
     pub max_weight: u64,
+    /// Overrides the configured transaction selection policy of the mempool
+    pub policy: Option<BlockTemplatePolicy>,
 }
 
 impl Display for NodeCommsRequest {
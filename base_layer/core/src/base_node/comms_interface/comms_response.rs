@@ -23,6 +23,7 @@
 use crate::{
     blocks::{block_header::BlockHeader, Block, NewBlockTemplate},
     chain_storage::HistoricalBlock,
+    mempool::ExcludedTransaction,
     proof_of_work::Difficulty,
     transactions::{
         transaction::{TransactionKernel, TransactionOutput},
@@ -43,7 +44,10 @@ pub enum NodeCommsResponse {
     TransactionOutputs(Vec<TransactionOutput>),
     HistoricalBlocks(Vec<HistoricalBlock>),
     HistoricalBlock(Box<Option<HistoricalBlock>>),
-    NewBlockTemplate(NewBlockTemplate),
+    NewBlockTemplate {
+        block_template: NewBlockTemplate,
+        excluded_transactions: Vec<ExcludedTransaction>,
+    },
     NewBlock {
         success: bool,
         error: Option<String>,
@@ -65,7 +69,7 @@ impl Display for NodeCommsResponse {
             HistoricalBlock(_) => write!(f, "HistoricalBlock"),
             TransactionOutputs(_) => write!(f, "TransactionOutputs"),
             HistoricalBlocks(_) => write!(f, "HistoricalBlocks"),
-            NewBlockTemplate(_) => write!(f, "NewBlockTemplate"),
+            NewBlockTemplate { .. } => write!(f, "NewBlockTemplate"),
             NewBlock {
                 success,
                 error,
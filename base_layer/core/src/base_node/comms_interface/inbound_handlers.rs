@@ -382,11 +382,9 @@ where T: BlockchainBackend + 'static
                     request.max_weight
                 };
 
-                let transactions = async_mempool::retrieve(self.mempool.clone(), asking_weight)
-                    .await?
-                    .iter()
-                    .map(|tx| (**tx).clone())
-                    .collect();
+                let selection =
+                    async_mempool::retrieve_for_template(self.mempool.clone(), asking_weight, request.policy).await?;
+                let transactions = selection.transactions.iter().map(|tx| (**tx).clone()).collect();
 
                 let height = header.height;
 
@@ -397,9 +395,14 @@ where T: BlockchainBackend + 'static
                 );
                 debug!(
                     target: LOG_TARGET,
-                    "New block template requested at height {}", block_template.header.height,
+                    "New block template requested at height {}, {} unconfirmed transaction(s) left out",
+                    block_template.header.height,
+                    selection.excluded.len(),
                 );
-                Ok(NodeCommsResponse::NewBlockTemplate(block_template))
+                Ok(NodeCommsResponse::NewBlockTemplate {
+                    block_template,
+                    excluded_transactions: selection.excluded,
+                })
             },
             NodeCommsRequest::GetNewBlock(block_template) => {
                 let block = self.blockchain_db.prepare_block_merkle_roots(block_template).await?;
@@ -30,6 +30,7 @@ use crate::{
     },
     blocks::{Block, BlockHeader, NewBlockTemplate},
     chain_storage::HistoricalBlock,
+    mempool::{BlockTemplatePolicy, ExcludedTransaction},
     proof_of_work::PowAlgorithm,
     transactions::{
         transaction::{TransactionKernel, TransactionOutput},
@@ -111,17 +112,37 @@ impl LocalNodeCommsInterface {
         pow_algorithm: PowAlgorithm,
         max_weight: u64,
     ) -> Result<NewBlockTemplate, CommsInterfaceError>
+    {
+        let (new_block_template, _) = self
+            .get_new_block_template_with_policy(pow_algorithm, max_weight, None)
+            .await?;
+        Ok(new_block_template)
+    }
+
+    /// Request the construction of a new mineable block template from the base node service, selecting the
+    /// transactions with the provided policy instead of the configured one. The unconfirmed transactions that were
+    /// left out of the template are returned along with it.
+    pub async fn get_new_block_template_with_policy(
+        &mut self,
+        pow_algorithm: PowAlgorithm,
+        max_weight: u64,
+        policy: Option<BlockTemplatePolicy>,
+    ) -> Result<(NewBlockTemplate, Vec<ExcludedTransaction>), CommsInterfaceError>
     {
         let request = GetNewBlockTemplateRequest {
             algo: pow_algorithm,
             max_weight,
+            policy,
         };
         match self
             .request_sender
             .call(NodeCommsRequest::GetNewBlockTemplate(request))
             .await??
         {
-            NodeCommsResponse::NewBlockTemplate(new_block_template) => Ok(new_block_template),
+            NodeCommsResponse::NewBlockTemplate {
+                block_template,
+                excluded_transactions,
+            } => Ok((block_template, excluded_transactions)),
             _ => Err(CommsInterfaceError::UnexpectedApiResponse),
         }
     }
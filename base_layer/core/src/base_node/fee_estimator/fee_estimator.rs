@@ -0,0 +1,192 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::error::FeeEstimatorError;
+use crate::{
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, HistoricalBlock},
+    consensus::{ConsensusManager, WEIGHT_PER_OUTPUT},
+    mempool::{async_mempool, Mempool},
+    transactions::tari_amount::MicroTari,
+};
+use log::*;
+
+const LOG_TARGET: &str = "c::bn::fee_estimator";
+
+/// The largest number of blocks a transaction can be estimated to be mined within. Larger confirmation targets are
+/// treated as this value.
+pub const MAX_CONFIRMATION_TARGET: u64 = 30;
+/// The number of recent blocks that the estimate is based on
+const BLOCK_WINDOW: u64 = 30;
+/// Blocks that use at least this percentage of the maximum block weight are considered full
+const FULL_BLOCK_PERCENTAGE: u64 = 90;
+/// The estimate that is returned when there is no competition for block space
+const MIN_FEE_PER_GRAM: MicroTari = MicroTari(1);
+
+/// A fee per gram estimate for a confirmation target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeEstimate {
+    pub fee_per_gram: MicroTari,
+    /// The number of blocks within which a transaction paying the estimated fee per gram is expected to be mined
+    pub confirmation_target: u64,
+    /// The height of the chain tip the estimate was made at
+    pub tip_height: u64,
+}
+
+/// Estimates the fee per gram a transaction needs to pay to be mined within a number of blocks.
+///
+/// Two estimates are combined and the higher one is returned:
+/// * Recent blocks: a block that was not full would have accepted any fee, while a full block is assumed to only have
+///   accepted transactions paying at least its average fee per gram. A transaction that pays enough to be accepted by
+///   one out of every `confirmation_target` recent blocks is expected to be mined in time.
+/// * Mempool: the unconfirmed transactions are ranked by fee per gram and fill up `confirmation_target` blocks. A
+///   transaction has to outbid the transaction at which the blocks are full.
+pub struct FeeEstimator<B> {
+    db: AsyncBlockchainDb<B>,
+    mempool: Mempool,
+    consensus_manager: ConsensusManager,
+}
+
+impl<B> Clone for FeeEstimator<B> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            mempool: self.mempool.clone(),
+            consensus_manager: self.consensus_manager.clone(),
+        }
+    }
+}
+
+impl<B: BlockchainBackend + 'static> FeeEstimator<B> {
+    pub fn new(db: AsyncBlockchainDb<B>, mempool: Mempool, consensus_manager: ConsensusManager) -> Self {
+        Self {
+            db,
+            mempool,
+            consensus_manager,
+        }
+    }
+
+    /// Estimates the fee per gram for a transaction to be mined within `confirmation_target` blocks. The target is
+    /// clamped to the range `1..=MAX_CONFIRMATION_TARGET`.
+    pub async fn estimate_fee_per_gram(&self, confirmation_target: u64) -> Result<FeeEstimate, FeeEstimatorError> {
+        let confirmation_target = confirmation_target.max(1).min(MAX_CONFIRMATION_TARGET);
+        let tip_height = self.db.get_chain_metadata().await?.height_of_longest_chain();
+        let constants = self.consensus_manager.consensus_constants(tip_height + 1);
+
+        let start = tip_height.saturating_sub(BLOCK_WINDOW - 1);
+        let blocks = self.db.fetch_blocks(start..=tip_height).await?;
+        let block_fee_rates = blocks
+            .iter()
+            .map(|block| block_fee_rate(block, constants.get_max_block_transaction_weight()))
+            .collect::<Vec<_>>();
+        let blocks_estimate = estimate_from_blocks(block_fee_rates, confirmation_target);
+
+        let distribution = async_mempool::fee_per_gram_distribution(self.mempool.clone()).await?;
+        let block_capacity = constants.get_max_block_weight_excluding_coinbase() * confirmation_target;
+        let mempool_estimate = estimate_from_mempool(&distribution, block_capacity);
+
+        let fee_per_gram = MIN_FEE_PER_GRAM.max(blocks_estimate).max(mempool_estimate);
+        debug!(
+            target: LOG_TARGET,
+            "Estimated {} per gram to be mined within {} block(s) at height {} (blocks: {}, mempool: {})",
+            fee_per_gram,
+            confirmation_target,
+            tip_height,
+            blocks_estimate,
+            mempool_estimate
+        );
+        Ok(FeeEstimate {
+            fee_per_gram,
+            confirmation_target,
+            tip_height,
+        })
+    }
+}
+
+// The lowest fee per gram the block is assumed to have accepted. Blocks that are not full would have accepted any fee.
+fn block_fee_rate(block: &HistoricalBlock, max_block_weight: u64) -> MicroTari {
+    let body = &block.block().body;
+    let weight = body.calculate_weight() + block.pruned_outputs().len() as u64 * WEIGHT_PER_OUTPUT;
+    if weight == 0 || weight * 100 < max_block_weight * FULL_BLOCK_PERCENTAGE {
+        return MicroTari::from(0);
+    }
+    MicroTari::from(u64::from(body.get_total_fee()) / weight)
+}
+
+// Returns the lowest fee per gram that would have been accepted by at least one out of every `confirmation_target`
+// blocks
+fn estimate_from_blocks(mut block_fee_rates: Vec<MicroTari>, confirmation_target: u64) -> MicroTari {
+    if block_fee_rates.is_empty() {
+        return MicroTari::from(0);
+    }
+    block_fee_rates.sort();
+    let num_blocks = block_fee_rates.len() as u64;
+    let num_accepting_blocks = (num_blocks + confirmation_target - 1) / confirmation_target;
+    block_fee_rates[(num_accepting_blocks - 1) as usize]
+}
+
+// Returns the fee per gram needed to outbid the unconfirmed transactions that do not fit into the available block
+// capacity. The distribution must be ordered from the highest to the lowest fee per gram.
+fn estimate_from_mempool(distribution: &[(f64, u64)], block_capacity: u64) -> MicroTari {
+    let mut total_weight = 0u64;
+    for (fee_per_gram, weight) in distribution {
+        total_weight += weight;
+        if total_weight > block_capacity {
+            return MicroTari::from(*fee_per_gram as u64 + 1);
+        }
+    }
+    MicroTari::from(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn estimate_from_recent_blocks() {
+        assert_eq!(estimate_from_blocks(vec![], 1), MicroTari(0));
+        let rates = vec![
+            MicroTari(0),
+            MicroTari(40),
+            MicroTari(10),
+            MicroTari(0),
+            MicroTari(25),
+            MicroTari(30),
+        ];
+        // Every block has to accept the transaction
+        assert_eq!(estimate_from_blocks(rates.clone(), 1), MicroTari(40));
+        // Three out of the six blocks have to accept the transaction
+        assert_eq!(estimate_from_blocks(rates.clone(), 2), MicroTari(10));
+        assert_eq!(estimate_from_blocks(rates.clone(), 4), MicroTari(0));
+        assert_eq!(estimate_from_blocks(rates, MAX_CONFIRMATION_TARGET), MicroTari(0));
+    }
+
+    #[test]
+    fn estimate_from_mempool_weight() {
+        let distribution = vec![(50.5, 100), (30.0, 200), (20.2, 300), (5.0, 400)];
+        assert_eq!(estimate_from_mempool(&[], 100), MicroTari(0));
+        // Everything fits
+        assert_eq!(estimate_from_mempool(&distribution, 1000), MicroTari(0));
+        assert_eq!(estimate_from_mempool(&distribution, 999), MicroTari(6));
+        assert_eq!(estimate_from_mempool(&distribution, 300), MicroTari(21));
+        assert_eq!(estimate_from_mempool(&distribution, 50), MicroTari(51));
+    }
+}
@@ -40,15 +40,23 @@ pub mod comms_interface;
 #[cfg(feature = "base_node")]
 pub use comms_interface::{LocalNodeCommsInterface, OutboundNodeCommsInterface};
 
+#[cfg(feature = "base_node")]
+pub mod fee_estimator;
+#[cfg(feature = "base_node")]
+pub use fee_estimator::FeeEstimator;
+
 #[cfg(feature = "base_node")]
 pub mod service;
 
+#[cfg(feature = "base_node")]
+pub mod snapshot;
+
 #[cfg(feature = "base_node")]
 pub mod state_machine_service;
 #[cfg(feature = "base_node")]
 pub use state_machine_service::{BaseNodeStateMachine, BaseNodeStateMachineConfig, StateMachineHandle};
 
-#[cfg(feature = "base_node")]
+#[cfg(any(feature = "base_node", feature = "base_node_proto"))]
 pub mod sync;
 #[cfg(feature = "base_node")]
 pub use sync::{
@@ -82,6 +82,8 @@ impl TryInto<ci::NodeCommsRequest> for ProtoNodeCommsRequest {
                 let request = GetNewBlockTemplateRequest {
                     algo: PowAlgorithm::try_from(message.algo)?,
                     max_weight: message.max_weight,
+                    // Remote nodes build templates using their own configured policy
+                    policy: None,
                 };
                 ci::NodeCommsRequest::GetNewBlockTemplate(request)
             },
@@ -75,7 +75,10 @@ impl TryInto<ci::NodeCommsResponse> for ProtoNodeCommsResponse {
                 let blocks = try_convert_all(blocks.blocks)?;
                 ci::NodeCommsResponse::HistoricalBlocks(blocks)
             },
-            NewBlockTemplate(block_template) => ci::NodeCommsResponse::NewBlockTemplate(block_template.try_into()?),
+            NewBlockTemplate(block_template) => ci::NodeCommsResponse::NewBlockTemplate {
+                block_template: block_template.try_into()?,
+                excluded_transactions: Vec::new(),
+            },
             NewBlock(block) => ci::NodeCommsResponse::NewBlock {
                 success: block.success,
                 error: Some(block.error),
@@ -119,7 +122,7 @@ impl From<ci::NodeCommsResponse> for ProtoNodeCommsResponse {
                 let historical_blocks = historical_blocks.into_iter().map(Into::into).collect();
                 ProtoNodeCommsResponse::HistoricalBlocks(historical_blocks)
             },
-            NewBlockTemplate(block_template) => ProtoNodeCommsResponse::NewBlockTemplate(block_template.into()),
+            NewBlockTemplate { block_template, .. } => ProtoNodeCommsResponse::NewBlockTemplate(block_template.into()),
             NewBlock { success, error, block } => ProtoNodeCommsResponse::NewBlock(ProtoNewBlockResponse {
                 success,
                 error: error.unwrap_or_else(|| "".to_string()),
@@ -23,9 +23,11 @@
 #[cfg(feature = "base_node")]
 mod service;
 #[cfg(feature = "base_node")]
-use crate::base_node::StateMachineHandle;
+use crate::base_node::{FeeEstimator, StateMachineHandle};
 use crate::proto::{
     base_node::{
+        FeePerGramEstimateRequest,
+        FeePerGramEstimateResponse,
         FetchMatchingUtxos,
         FetchUtxosResponse,
         Signatures,
@@ -68,6 +70,12 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
         &self,
         request: Request<FetchMatchingUtxos>,
     ) -> Result<Response<FetchUtxosResponse>, RpcStatus>;
+
+    #[rpc(method = 5)]
+    async fn get_fee_per_gram_estimate(
+        &self,
+        request: Request<FeePerGramEstimateRequest>,
+    ) -> Result<Response<FeePerGramEstimateResponse>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
@@ -75,7 +83,8 @@ pub fn create_base_node_wallet_rpc_service<B: BlockchainBackend + 'static>(
     db: AsyncBlockchainDb<B>,
     mempool: MempoolHandle,
     state_machine: StateMachineHandle,
+    fee_estimator: FeeEstimator<B>,
 ) -> BaseNodeWalletRpcServer<BaseNodeWalletRpcService<B>>
 {
-    BaseNodeWalletRpcServer::new(BaseNodeWalletRpcService::new(db, mempool, state_machine))
+    BaseNodeWalletRpcServer::new(BaseNodeWalletRpcService::new(db, mempool, state_machine, fee_estimator))
 }
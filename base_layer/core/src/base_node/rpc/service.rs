@@ -21,11 +21,13 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    base_node::{rpc::BaseNodeWalletService, state_machine_service::states::StateInfo, StateMachineHandle},
+    base_node::{rpc::BaseNodeWalletService, FeeEstimator, StateMachineHandle},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
     mempool::{service::MempoolHandle, TxStorageResponse},
     proto::{
         base_node::{
+            FeePerGramEstimateRequest,
+            FeePerGramEstimateResponse,
             FetchMatchingUtxos,
             FetchUtxosResponse,
             Signatures as SignaturesProto,
@@ -49,14 +51,22 @@ pub struct BaseNodeWalletRpcService<B> {
     db: AsyncBlockchainDb<B>,
     mempool: MempoolHandle,
     state_machine: StateMachineHandle,
+    fee_estimator: FeeEstimator<B>,
 }
 
 impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
-    pub fn new(db: AsyncBlockchainDb<B>, mempool: MempoolHandle, state_machine: StateMachineHandle) -> Self {
+    pub fn new(
+        db: AsyncBlockchainDb<B>,
+        mempool: MempoolHandle,
+        state_machine: StateMachineHandle,
+        fee_estimator: FeeEstimator<B>,
+    ) -> Self
+    {
         Self {
             db,
             mempool,
             state_machine,
+            fee_estimator,
         }
     }
 
@@ -154,10 +164,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         // Determine if we are synced
         let status_watch = state_machine.get_status_info_watch();
-        let is_synced = match (*status_watch.borrow()).state_info {
-            StateInfo::Listening(li) => li.is_synced(),
-            _ => false,
-        };
+        let is_synced = status_watch.borrow().state_info.is_synced();
 
         let response = match mempool
             .submit_transaction(transaction.clone())
@@ -230,10 +237,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         // Determine if we are synced
         let status_watch = state_machine.get_status_info_watch();
-        let is_synced = match (*status_watch.borrow()).state_info {
-            StateInfo::Listening(li) => li.is_synced(),
-            _ => false,
-        };
+        let is_synced = status_watch.borrow().state_info.is_synced();
 
         let message = request.into_message();
         let signature = Signature::try_from(message).map_err(|_| RpcStatus::bad_request("Signature was invalid"))?;
@@ -252,10 +256,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         // Determine if we are synced
         let status_watch = state_machine.get_status_info_watch();
-        let is_synced = match (*status_watch.borrow()).state_info {
-            StateInfo::Listening(li) => li.is_synced(),
-            _ => false,
-        };
+        let is_synced = status_watch.borrow().state_info.is_synced();
 
         let message = request.into_message();
 
@@ -284,10 +285,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
         let state_machine = self.state_machine();
         // Determine if we are synced
         let status_watch = state_machine.get_status_info_watch();
-        let is_synced = match (*status_watch.borrow()).state_info {
-            StateInfo::Listening(li) => li.is_synced(),
-            _ => false,
-        };
+        let is_synced = status_watch.borrow().state_info.is_synced();
 
         let db = self.db();
         let mut res = Vec::with_capacity(message.output_hashes.len());
@@ -308,4 +306,30 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
             is_synced,
         }))
     }
+
+    async fn get_fee_per_gram_estimate(
+        &self,
+        request: Request<FeePerGramEstimateRequest>,
+    ) -> Result<Response<FeePerGramEstimateResponse>, RpcStatus>
+    {
+        let message = request.into_message();
+
+        let state_machine = self.state_machine();
+        // Determine if we are synced
+        let status_watch = state_machine.get_status_info_watch();
+        let is_synced = status_watch.borrow().state_info.is_synced();
+
+        let estimate = self
+            .fee_estimator
+            .estimate_fee_per_gram(message.confirmation_target)
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+
+        Ok(Response::new(FeePerGramEstimateResponse {
+            fee_per_gram: estimate.fee_per_gram.into(),
+            confirmation_target: estimate.confirmation_target,
+            tip_height: estimate.tip_height,
+            is_synced,
+        }))
+    }
 }
@@ -30,7 +30,6 @@ use crate::{
             NodeCommsResponse,
         },
         service::error::BaseNodeServiceError,
-        state_machine_service::states::StateInfo,
         StateMachineHandle,
     },
     blocks::{Block, NewBlock},
@@ -401,10 +400,7 @@ async fn handle_incoming_request<B: BlockchainBackend + 'static>(
 
     // Determine if we are synced
     let status_watch = state_machine_handle.get_status_info_watch();
-    let is_synced = match (*status_watch.borrow()).state_info {
-        StateInfo::Listening(li) => li.is_synced(),
-        _ => false,
-    };
+    let is_synced = status_watch.borrow().state_info.is_synced();
 
     let message = proto::BaseNodeServiceResponse {
         request_key: inner_msg.request_key,
@@ -0,0 +1,77 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    base_node::sync::BlockHeaderSyncError,
+    chain_storage::{ChainStorageError, MmrTree},
+    transactions::transaction::TransactionError,
+    validation::ValidationError,
+};
+use tari_mmr::error::MerkleMountainRangeError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Chain storage error: {0}")]
+    ChainStorageError(#[from] ChainStorageError),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] bincode::Error),
+    #[error("Header validation failed: {0}")]
+    HeaderValidationFailed(#[from] BlockHeaderSyncError),
+    #[error("MerkleMountainRangeError: {0}")]
+    MerkleMountainRangeError(#[from] MerkleMountainRangeError),
+    #[error("The file is not a chain snapshot")]
+    InvalidMagic,
+    #[error("Snapshot format version {0} is not supported by this node")]
+    UnsupportedVersion(u32),
+    #[error("The snapshot file is truncated")]
+    Truncated,
+    #[error("The snapshot checksum does not match its contents")]
+    ChecksumMismatch,
+    #[error("The snapshot is malformed: {0}")]
+    Malformed(String),
+    #[error("A snapshot cannot be exported at height {height}: {reason}")]
+    HeightNotAvailable { height: u64, reason: String },
+    #[error("The chain was reorganized past height {0} while the snapshot was being exported")]
+    ChainReorgedDuringExport(u64),
+    #[error("A snapshot can only be imported into an empty database, but this database has headers up to height {0}")]
+    DatabaseNotEmpty(u64),
+    #[error("The snapshot was created from a chain with genesis block {snapshot}, but this node uses {local}")]
+    GenesisMismatch { snapshot: String, local: String },
+    #[error("The accumulated data in the snapshot does not match the header at height {0}")]
+    AccumulatedDataMismatch(u64),
+    #[error("MMR did not match for {mmr_tree} at height {at_height}. {expected_hex} did not equal {actual_hex}")]
+    InvalidMmrRoot {
+        mmr_tree: MmrTree,
+        at_height: u64,
+        expected_hex: String,
+        actual_hex: String,
+    },
+    #[error("Invalid kernel signature: {0}")]
+    InvalidKernelSignature(TransactionError),
+    #[error("Invalid range proof for output:{0} : {1}")]
+    InvalidRangeProof(String, String),
+    #[error("Final state validation failed: {0}")]
+    FinalStateValidationFailed(ValidationError),
+}
@@ -0,0 +1,181 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{SnapshotBlock, SnapshotError, SnapshotInfo, SnapshotRecord, SnapshotWriter};
+use crate::chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, PrunedOutput};
+use log::*;
+use std::{
+    fs,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+const LOG_TARGET: &str = "c::bn::snapshot::export";
+
+/// Writes a snapshot of the chain up to and including `height` to `path`.
+///
+/// The snapshot is written to a temporary file next to `path` and only renamed once it is complete, so an interrupted
+/// export never leaves a partial snapshot behind. A pruned node can only export from its pruned height onwards, since
+/// outputs spent before then are no longer available.
+pub async fn export_snapshot<B: BlockchainBackend + 'static>(
+    db: &AsyncBlockchainDb<B>,
+    height: u64,
+    path: &Path,
+) -> Result<SnapshotInfo, SnapshotError>
+{
+    let metadata = db.get_chain_metadata().await?;
+    if height > metadata.height_of_longest_chain() {
+        return Err(SnapshotError::HeightNotAvailable {
+            height,
+            reason: format!("the chain tip is at height {}", metadata.height_of_longest_chain()),
+        });
+    }
+    if height < metadata.pruned_height() {
+        return Err(SnapshotError::HeightNotAvailable {
+            height,
+            reason: format!("outputs have been pruned up to height {}", metadata.pruned_height()),
+        });
+    }
+
+    let genesis = db.fetch_chain_header(0).await?;
+    let tip = db.fetch_chain_header(height).await?;
+    let info = SnapshotInfo {
+        genesis_hash: genesis.hash().clone(),
+        height,
+        tip_hash: tip.hash().clone(),
+        accumulated_difficulty: tip.accumulated_data.total_accumulated_difficulty,
+        num_kernels: tip.header.kernel_mmr_size,
+        num_outputs: tip.header.output_mmr_size,
+    };
+
+    let partial_path = partial_path(path);
+    match write_snapshot(db, &info, &partial_path).await {
+        Ok(_) => {
+            fs::rename(&partial_path, path)?;
+            info!(target: LOG_TARGET, "{} written to {}", info, path.display());
+            Ok(info)
+        },
+        Err(err) => {
+            let _ = fs::remove_file(&partial_path);
+            Err(err)
+        },
+    }
+}
+
+async fn write_snapshot<B: BlockchainBackend + 'static>(
+    db: &AsyncBlockchainDb<B>,
+    info: &SnapshotInfo,
+    path: &Path,
+) -> Result<(), SnapshotError>
+{
+    let mut writer = SnapshotWriter::new(BufWriter::new(File::create(path)?))?;
+    writer.write_record(&SnapshotRecord::Info(info.clone()))?;
+
+    let deleted_at_tip = db
+        .fetch_block_accumulated_data(info.tip_hash.clone())
+        .await?
+        .dissolve()
+        .3;
+    let mut prev_header = db.fetch_chain_header(0).await?;
+    let mut prev_deleted = db
+        .fetch_block_accumulated_data(prev_header.hash().clone())
+        .await?
+        .dissolve()
+        .3;
+    for height in 1..=info.height {
+        let current_header = db.fetch_chain_header(height).await?;
+        let (start_kernel, end_kernel) = (
+            prev_header.header.kernel_mmr_size,
+            current_header.header.kernel_mmr_size,
+        );
+        let kernels = if end_kernel > start_kernel {
+            db.fetch_kernels_by_mmr_position(start_kernel, end_kernel - 1).await?
+        } else {
+            vec![]
+        };
+
+        let (start_output, end_output) = (
+            prev_header.header.output_mmr_size,
+            current_header.header.output_mmr_size,
+        );
+        let outputs = if end_output > start_output {
+            db.fetch_utxos_by_mmr_position(start_output, end_output - 1, info.tip_hash.clone())
+                .await?
+                .0
+        } else {
+            vec![]
+        };
+        if outputs.len() as u64 != end_output - start_output || kernels.len() as u64 != end_kernel - start_kernel {
+            return Err(SnapshotError::HeightNotAvailable {
+                height: info.height,
+                reason: format!("the block body at height {} is incomplete", height),
+            });
+        }
+        for (mmr_position, output) in (start_output..).zip(outputs.iter()) {
+            if let PrunedOutput::Pruned { .. } = output {
+                if !deleted_at_tip.contains(mmr_position as u32) {
+                    return Err(SnapshotError::HeightNotAvailable {
+                        height: info.height,
+                        reason: format!("the unspent output at MMR position {} has been pruned", mmr_position),
+                    });
+                }
+            }
+        }
+
+        let deleted = db
+            .fetch_block_accumulated_data(current_header.hash().clone())
+            .await?
+            .dissolve()
+            .3;
+        let spent = deleted.andnot(&prev_deleted);
+        writer.write_record(&SnapshotRecord::Block(Box::new(SnapshotBlock {
+            header: current_header.header.clone(),
+            accumulated_data: current_header.accumulated_data.clone(),
+            kernels,
+            outputs,
+            spent: spent.serialize(),
+        })))?;
+
+        if height % 1000 == 0 {
+            debug!(target: LOG_TARGET, "Exported {} of {} blocks", height, info.height);
+        }
+        prev_header = current_header;
+        prev_deleted = deleted;
+    }
+
+    // The export reads the chain in many steps, so make sure none of the exported blocks were reorged out meanwhile
+    let tip = db.fetch_chain_header(info.height).await?;
+    if tip.hash() != &info.tip_hash {
+        return Err(SnapshotError::ChainReorgedDuringExport(info.height));
+    }
+
+    let file = writer.finish()?;
+    file.get_ref().sync_all()?;
+    Ok(())
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    file_name.push(".partial");
+    path.with_file_name(file_name)
+}
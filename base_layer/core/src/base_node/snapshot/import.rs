@@ -0,0 +1,361 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{verify_snapshot, SnapshotBlock, SnapshotError, SnapshotInfo, SnapshotReader, SnapshotRecord};
+use crate::{
+    base_node::sync::{BlockHeaderSyncValidator, SyncValidators},
+    blocks::BlockHeader,
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, MmrTree, PrunedOutput},
+    consensus::ConsensusManager,
+    proof_of_work::randomx_factory::RandomXFactory,
+    transactions::types::{Commitment, CryptoFactories, HashDigest},
+};
+use croaring::Bitmap;
+use log::*;
+use std::{fs::File, io::BufReader, path::Path};
+use tari_crypto::tari_utilities::{hex::Hex, Hashable};
+use tari_mmr::{pruned_hashset::PrunedHashSet, MerkleMountainRange, MutableMmr};
+
+const LOG_TARGET: &str = "c::bn::snapshot::import";
+
+/// Imports a snapshot written by [export_snapshot](super::export_snapshot) into a database that only contains the
+/// genesis block.
+///
+/// Every header is validated as in header sync, and the kernels and outputs of every block are checked against the
+/// MMR roots in its header as in horizon sync. Once all blocks have been imported, the chain balance is validated at
+/// the snapshot height. Only then is the chain tip moved to the snapshot height. As after horizon sync, blocks before
+/// the snapshot height have no inputs and spent outputs are kept as hashes.
+pub struct SnapshotImporter<B: BlockchainBackend> {
+    db: AsyncBlockchainDb<B>,
+    rules: ConsensusManager,
+    randomx_factory: RandomXFactory,
+    validators: SyncValidators<B>,
+    factories: CryptoFactories,
+}
+
+/// The pruned MMR state and running sums carried from one block to the next during an import
+struct ImportState {
+    prev_header: BlockHeader,
+    kernel_hash_set: PrunedHashSet,
+    output_hash_set: PrunedHashSet,
+    range_proof_hash_set: PrunedHashSet,
+    deleted: Bitmap,
+    kernel_sum: Commitment,
+    unspent: Vec<(u32, Commitment)>,
+}
+
+impl<B: BlockchainBackend + 'static> SnapshotImporter<B> {
+    pub fn new(
+        db: AsyncBlockchainDb<B>,
+        rules: ConsensusManager,
+        randomx_factory: RandomXFactory,
+        validators: SyncValidators<B>,
+        factories: CryptoFactories,
+    ) -> Self
+    {
+        Self {
+            db,
+            rules,
+            randomx_factory,
+            validators,
+            factories,
+        }
+    }
+
+    pub async fn import(&self, path: &Path) -> Result<SnapshotInfo, SnapshotError> {
+        info!(target: LOG_TARGET, "Verifying snapshot checksum");
+        let info = verify_snapshot(path)?;
+        info!(target: LOG_TARGET, "Importing {}", info);
+
+        let genesis = self.db.fetch_chain_header(0).await?;
+        if genesis.hash() != &info.genesis_hash {
+            return Err(SnapshotError::GenesisMismatch {
+                snapshot: info.genesis_hash.to_hex(),
+                local: genesis.hash().to_hex(),
+            });
+        }
+        let last_header = self.db.fetch_last_header().await?;
+        if last_header.height > 0 {
+            return Err(SnapshotError::DatabaseNotEmpty(last_header.height));
+        }
+
+        let mut reader = SnapshotReader::new(BufReader::new(File::open(path)?))?;
+        match reader.next_record()? {
+            Some(SnapshotRecord::Info(ref snapshot_info)) if *snapshot_info == info => {},
+            _ => {
+                return Err(SnapshotError::Malformed(
+                    "the snapshot changed while it was being read".to_string(),
+                ))
+            },
+        }
+
+        let mut validator =
+            BlockHeaderSyncValidator::new(self.db.clone(), self.rules.clone(), self.randomx_factory.clone());
+        validator.initialize_state(genesis.hash().clone()).await?;
+        let mut state = self.genesis_state(genesis.header).await?;
+
+        while let Some(record) = reader.next_record()? {
+            match record {
+                SnapshotRecord::Block(block) => {
+                    self.import_block(&mut validator, &mut state, *block).await?;
+                    if state.prev_header.height % 1000 == 0 {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Imported {} of {} blocks", state.prev_header.height, info.height
+                        );
+                    }
+                },
+                SnapshotRecord::Info(_) => {
+                    return Err(SnapshotError::Malformed(
+                        "the snapshot has more than one info record".to_string(),
+                    ))
+                },
+            }
+        }
+        if state.prev_header.height != info.height || state.prev_header.hash() != info.tip_hash {
+            return Err(SnapshotError::Malformed(format!(
+                "the snapshot ends at height {} but claims to end at height {}",
+                state.prev_header.height, info.height
+            )));
+        }
+
+        self.finalize(&info, state).await?;
+        info!(
+            target: LOG_TARGET,
+            "Snapshot imported. The chain tip is now at height {}", info.height
+        );
+        Ok(info)
+    }
+
+    async fn genesis_state(&self, genesis: BlockHeader) -> Result<ImportState, SnapshotError> {
+        let (kernel_hash_set, output_hash_set, range_proof_hash_set, deleted) =
+            self.db.fetch_block_accumulated_data(genesis.hash()).await?.dissolve();
+
+        let mut kernel_sum = Commitment::default();
+        if genesis.kernel_mmr_size > 0 {
+            for kernel in self
+                .db
+                .fetch_kernels_by_mmr_position(0, genesis.kernel_mmr_size - 1)
+                .await?
+            {
+                kernel_sum = &kernel.excess + &kernel_sum;
+            }
+        }
+        let mut unspent = Vec::new();
+        if genesis.output_mmr_size > 0 {
+            let (outputs, _) = self
+                .db
+                .fetch_utxos_by_mmr_position(0, genesis.output_mmr_size - 1, genesis.hash())
+                .await?;
+            for (mmr_position, output) in (0u32..).zip(outputs) {
+                if let PrunedOutput::NotPruned { output } = output {
+                    unspent.push((mmr_position, output.commitment));
+                }
+            }
+        }
+
+        Ok(ImportState {
+            prev_header: genesis,
+            kernel_hash_set,
+            output_hash_set,
+            range_proof_hash_set,
+            deleted,
+            kernel_sum,
+            unspent,
+        })
+    }
+
+    async fn import_block(
+        &self,
+        validator: &mut BlockHeaderSyncValidator<B>,
+        state: &mut ImportState,
+        block: SnapshotBlock,
+    ) -> Result<(), SnapshotError>
+    {
+        let SnapshotBlock {
+            header,
+            accumulated_data,
+            kernels,
+            outputs,
+            spent,
+        } = block;
+        let chain_header = validator.validate_and_calculate_metadata(header)?;
+        let height = chain_header.height();
+        if chain_header.accumulated_data != accumulated_data {
+            return Err(SnapshotError::AccumulatedDataMismatch(height));
+        }
+        let header = &chain_header.header;
+        let header_hash = chain_header.hash().clone();
+
+        let first_kernel = state.prev_header.kernel_mmr_size;
+        let first_output = state.prev_header.output_mmr_size;
+        if kernels.len() as u64 != header.kernel_mmr_size.saturating_sub(first_kernel) ||
+            outputs.len() as u64 != header.output_mmr_size.saturating_sub(first_output)
+        {
+            return Err(SnapshotError::Malformed(format!(
+                "the number of kernels or outputs at height {} does not match the header",
+                height
+            )));
+        }
+
+        let mut txn = self.db.write_transaction();
+        txn.insert_header(chain_header.header.clone(), chain_header.accumulated_data.clone());
+
+        let mut kernel_mmr = MerkleMountainRange::<HashDigest, _>::new(state.kernel_hash_set.clone());
+        let mut block_kernel_sum = Commitment::default();
+        for (mmr_position, kernel) in (first_kernel..).zip(kernels) {
+            kernel
+                .verify_signature()
+                .map_err(SnapshotError::InvalidKernelSignature)?;
+            block_kernel_sum = &kernel.excess + &block_kernel_sum;
+            kernel_mmr.push(kernel.hash())?;
+            txn.insert_kernel_via_horizon_sync(kernel, header_hash.clone(), mmr_position as u32);
+        }
+        let mmr_root = kernel_mmr.get_merkle_root()?;
+        if mmr_root != header.kernel_mr {
+            return Err(SnapshotError::InvalidMmrRoot {
+                mmr_tree: MmrTree::Kernel,
+                at_height: height,
+                expected_hex: header.kernel_mr.to_hex(),
+                actual_hex: mmr_root.to_hex(),
+            });
+        }
+
+        let mut output_mmr = MerkleMountainRange::<HashDigest, _>::new(state.output_hash_set.clone());
+        let mut proof_mmr = MerkleMountainRange::<HashDigest, _>::new(state.range_proof_hash_set.clone());
+        let mut unpruned_outputs = Vec::new();
+        for (mmr_position, output) in (first_output..).zip(outputs) {
+            let mmr_position = mmr_position as u32;
+            match output {
+                PrunedOutput::NotPruned { output } => {
+                    output_mmr.push(output.hash())?;
+                    proof_mmr.push(output.proof().hash())?;
+                    unpruned_outputs.push((mmr_position, output));
+                },
+                PrunedOutput::Pruned {
+                    output_hash,
+                    range_proof_hash,
+                } => {
+                    output_mmr.push(output_hash.clone())?;
+                    proof_mmr.push(range_proof_hash.clone())?;
+                    txn.insert_pruned_output_via_horizon_sync(
+                        output_hash,
+                        range_proof_hash,
+                        header_hash.clone(),
+                        mmr_position,
+                    );
+                },
+            }
+        }
+
+        let spent = Bitmap::deserialize(&spent);
+        if let Some(max) = spent.maximum() {
+            if u64::from(max) >= header.output_mmr_size {
+                return Err(SnapshotError::Malformed(format!(
+                    "an output spent at height {} does not exist",
+                    height
+                )));
+            }
+        }
+        let output_hash_set = output_mmr.get_pruned_hash_set()?;
+        let output_mmr = MutableMmr::<HashDigest, _>::new(output_hash_set.clone(), state.deleted.or(&spent))?;
+        let mmr_root = output_mmr.get_merkle_root()?;
+        if mmr_root != header.output_mr {
+            return Err(SnapshotError::InvalidMmrRoot {
+                mmr_tree: MmrTree::Utxo,
+                at_height: height,
+                expected_hex: header.output_mr.to_hex(),
+                actual_hex: mmr_root.to_hex(),
+            });
+        }
+        let mmr_root = proof_mmr.get_merkle_root()?;
+        if mmr_root != header.range_proof_mr {
+            return Err(SnapshotError::InvalidMmrRoot {
+                mmr_tree: MmrTree::RangeProof,
+                at_height: height,
+                expected_hex: header.range_proof_mr.to_hex(),
+                actual_hex: mmr_root.to_hex(),
+            });
+        }
+
+        // Validate range proofs once the MMR roots match
+        for (mmr_position, output) in unpruned_outputs {
+            let is_valid = output
+                .verify_range_proof(&self.factories.range_proof)
+                .map_err(|err| SnapshotError::InvalidRangeProof(output.hash().to_hex(), err.to_string()))?;
+            if !is_valid {
+                return Err(SnapshotError::InvalidRangeProof(
+                    output.hash().to_hex(),
+                    "the range proof is not valid".to_string(),
+                ));
+            }
+            state.unspent.push((mmr_position, output.commitment.clone()));
+            txn.insert_output_via_horizon_sync(output, header_hash.clone(), mmr_position);
+        }
+
+        let kernel_hash_set = kernel_mmr.get_pruned_hash_set()?;
+        let range_proof_hash_set = proof_mmr.get_pruned_hash_set()?;
+        let deleted = output_mmr.deleted().clone();
+        txn.update_pruned_hash_set(MmrTree::Kernel, header_hash.clone(), kernel_hash_set.clone())
+            .update_pruned_hash_set(MmrTree::Utxo, header_hash.clone(), output_hash_set.clone())
+            .update_pruned_hash_set(MmrTree::RangeProof, header_hash.clone(), range_proof_hash_set.clone())
+            .update_deleted(header_hash.clone(), deleted.clone())
+            .update_kernel_sum(header_hash, block_kernel_sum.clone());
+        txn.commit().await?;
+
+        state.prev_header = chain_header.header;
+        state.kernel_hash_set = kernel_hash_set;
+        state.output_hash_set = output_hash_set;
+        state.range_proof_hash_set = range_proof_hash_set;
+        state.deleted = deleted;
+        state.kernel_sum = &block_kernel_sum + &state.kernel_sum;
+        Ok(())
+    }
+
+    // Validates the chain balance at the snapshot height and moves the chain tip there
+    async fn finalize(&self, info: &SnapshotInfo, state: ImportState) -> Result<(), SnapshotError> {
+        let mut utxo_sum = Commitment::default();
+        for (mmr_position, commitment) in &state.unspent {
+            if !state.deleted.contains(*mmr_position) {
+                utxo_sum = commitment + &utxo_sum;
+            }
+        }
+
+        self.validators
+            .final_horizon_state
+            .validate(
+                info.height,
+                &utxo_sum,
+                &state.kernel_sum,
+                &*self.db.inner().db_read_access()?,
+            )
+            .map_err(SnapshotError::FinalStateValidationFailed)?;
+
+        self.db
+            .write_transaction()
+            .set_best_block(info.height, info.tip_hash.clone(), info.accumulated_difficulty)
+            .set_pruned_height(info.height, state.kernel_sum, utxo_sum)
+            .commit()
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Chain snapshots.
+//!
+//! A snapshot is a portable copy of the blockchain state up to a chosen height. Importing one lets a new base node
+//! skip header, horizon and block sync up to that height, which saves hours when provisioning many nodes.
+//!
+//! A snapshot file starts with [SNAPSHOT_MAGIC] and the format version. This is followed by length-prefixed,
+//! bincode-encoded [SnapshotRecord]s: one `Info` record, then one `Block` record for every block after the genesis
+//! block up to and including the snapshot height. A zero length marks the end of the records and is followed by a
+//! Blake256 checksum of everything before it.
+//!
+//! Each block record holds the header and its accumulated data, the block's kernels, and its outputs. Outputs that
+//! are unspent at the snapshot height are stored in full and spent outputs as output and range proof hashes. The
+//! record also holds the MMR positions of the outputs spent in that block. The importer rebuilds the pruned MMR hash
+//! sets from these and checks them against the MMR roots in every header, in the same way as horizon sync.
+
+mod error;
+pub use error::SnapshotError;
+
+mod export;
+pub use export::export_snapshot;
+
+mod import;
+pub use import::SnapshotImporter;
+
+use crate::{
+    blocks::BlockHeader,
+    chain_storage::{BlockHeaderAccumulatedData, PrunedOutput},
+    transactions::{
+        transaction::TransactionKernel,
+        types::{HashDigest, HashOutput},
+    },
+};
+use digest::Digest;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::File,
+    io,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
+use tari_crypto::tari_utilities::hex::Hex;
+
+/// The bytes every snapshot file starts with
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"TARISNAP";
+/// The version of the snapshot file format written by this node
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// Records larger than this are rejected rather than allocated
+const MAX_RECORD_SIZE: usize = 256 * 1024 * 1024;
+
+/// Summary of the chain state contained in a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub genesis_hash: HashOutput,
+    pub height: u64,
+    pub tip_hash: HashOutput,
+    pub accumulated_difficulty: u128,
+    pub num_kernels: u64,
+    pub num_outputs: u64,
+}
+
+impl fmt::Display for SnapshotInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Snapshot at height {} (block {}, accumulated difficulty {}) with {} kernel(s) and {} output(s)",
+            self.height,
+            self.tip_hash.to_hex(),
+            self.accumulated_difficulty,
+            self.num_kernels,
+            self.num_outputs
+        )
+    }
+}
+
+/// The chain data for a single block in a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotBlock {
+    pub header: BlockHeader,
+    pub accumulated_data: BlockHeaderAccumulatedData,
+    pub kernels: Vec<TransactionKernel>,
+    pub outputs: Vec<PrunedOutput>,
+    /// Serialized bitmap of the MMR positions of the outputs spent in this block
+    pub spent: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum SnapshotRecord {
+    Info(SnapshotInfo),
+    Block(Box<SnapshotBlock>),
+}
+
+/// Reads the whole snapshot at `path`, checking its format version and checksum, and returns its summary.
+pub fn verify_snapshot(path: &Path) -> Result<SnapshotInfo, SnapshotError> {
+    let mut reader = SnapshotReader::new(BufReader::new(File::open(path)?))?;
+    let info = match reader.next_record()? {
+        Some(SnapshotRecord::Info(info)) => info,
+        _ => {
+            return Err(SnapshotError::Malformed(
+                "the snapshot does not start with an info record".to_string(),
+            ))
+        },
+    };
+    while reader.next_record_bytes()?.is_some() {}
+    Ok(info)
+}
+
+struct SnapshotWriter<W> {
+    writer: W,
+    hasher: HashDigest,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    fn new(writer: W) -> Result<Self, SnapshotError> {
+        let mut snapshot_writer = Self {
+            writer,
+            hasher: HashDigest::new(),
+        };
+        snapshot_writer.write_all(SNAPSHOT_MAGIC)?;
+        snapshot_writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+        Ok(snapshot_writer)
+    }
+
+    fn write_record(&mut self, record: &SnapshotRecord) -> Result<(), SnapshotError> {
+        let bytes = bincode::serialize(record)?;
+        if bytes.len() > MAX_RECORD_SIZE {
+            return Err(SnapshotError::Malformed(format!(
+                "a record of {} bytes exceeds the maximum record size",
+                bytes.len()
+            )));
+        }
+        self.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Writes the end marker and checksum, and returns the underlying writer
+    fn finish(mut self) -> Result<W, SnapshotError> {
+        self.write_all(&0u32.to_le_bytes())?;
+        let checksum = self.hasher.result();
+        self.writer.write_all(&checksum)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.hasher.input(buf);
+        self.writer.write_all(buf)
+    }
+}
+
+struct SnapshotReader<R> {
+    reader: R,
+    hasher: HashDigest,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    fn new(reader: R) -> Result<Self, SnapshotError> {
+        let mut snapshot_reader = Self {
+            reader,
+            hasher: HashDigest::new(),
+        };
+        let mut magic = [0u8; 8];
+        snapshot_reader.read_exact(&mut magic)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+        let mut version = [0u8; 4];
+        snapshot_reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        Ok(snapshot_reader)
+    }
+
+    /// Returns the next record, or `None` once the end marker has been read and the checksum verified
+    fn next_record(&mut self) -> Result<Option<SnapshotRecord>, SnapshotError> {
+        match self.next_record_bytes()? {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn next_record_bytes(&mut self) -> Result<Option<Vec<u8>>, SnapshotError> {
+        let mut len = [0u8; 4];
+        self.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len == 0 {
+            let expected = self.hasher.clone().result();
+            let mut checksum = vec![0u8; expected.len()];
+            self.reader.read_exact(&mut checksum).map_err(map_eof)?;
+            if checksum.as_slice() != expected.as_slice() {
+                return Err(SnapshotError::ChecksumMismatch);
+            }
+            return Ok(None);
+        }
+        if len > MAX_RECORD_SIZE {
+            return Err(SnapshotError::Malformed(format!(
+                "a record of {} bytes exceeds the maximum record size",
+                len
+            )));
+        }
+        let mut buf = vec![0u8; len];
+        self.read_exact(&mut buf)?;
+        Ok(Some(buf))
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), SnapshotError> {
+        self.reader.read_exact(buf).map_err(map_eof)?;
+        self.hasher.input(&*buf);
+        Ok(())
+    }
+}
+
+fn map_eof(err: io::Error) -> SnapshotError {
+    match err.kind() {
+        io::ErrorKind::UnexpectedEof => SnapshotError::Truncated,
+        _ => SnapshotError::IoError(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    fn info() -> SnapshotInfo {
+        SnapshotInfo {
+            genesis_hash: vec![1; 32],
+            height: 10,
+            tip_hash: vec![2; 32],
+            accumulated_difficulty: 12345,
+            num_kernels: 11,
+            num_outputs: 22,
+        }
+    }
+
+    fn write_snapshot() -> Vec<u8> {
+        let mut writer = SnapshotWriter::new(Vec::new()).unwrap();
+        writer.write_record(&SnapshotRecord::Info(info())).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn it_reads_back_what_was_written() {
+        let bytes = write_snapshot();
+        let mut reader = SnapshotReader::new(Cursor::new(bytes)).unwrap();
+        match reader.next_record().unwrap() {
+            Some(SnapshotRecord::Info(read_info)) => assert_eq!(read_info, info()),
+            _ => panic!("Expected the info record"),
+        }
+        assert!(reader.next_record().unwrap().is_none());
+    }
+
+    #[test]
+    fn it_detects_corruption() {
+        let mut bytes = write_snapshot();
+        // Flip a bit in the info record
+        let pos = bytes.len() - 40;
+        bytes[pos] ^= 1;
+        let mut reader = SnapshotReader::new(Cursor::new(bytes)).unwrap();
+        let mut result = reader.next_record_bytes();
+        while let Ok(Some(_)) = result {
+            result = reader.next_record_bytes();
+        }
+        assert!(matches!(result, Err(SnapshotError::ChecksumMismatch)));
+    }
+
+    #[test]
+    fn it_detects_truncation_and_foreign_files() {
+        let bytes = write_snapshot();
+        let mut reader = SnapshotReader::new(Cursor::new(bytes[..bytes.len() - 1].to_vec())).unwrap();
+        assert!(reader.next_record_bytes().unwrap().is_some());
+        assert!(matches!(reader.next_record_bytes(), Err(SnapshotError::Truncated)));
+
+        assert!(matches!(
+            SnapshotReader::new(Cursor::new(b"NOTASNAPSHOT".to_vec())),
+            Err(SnapshotError::InvalidMagic)
+        ));
+    }
+}
@@ -139,6 +139,9 @@ impl<B: BlockchainBackend + 'static> BaseNodeStateMachine<B> {
             (BlockSync(s), BlockSyncFailed) => Waiting(s.into()),
             (Listening(_), FallenBehind(Lagging(_, sync_peers))) => HeaderSync(sync_peers.into()),
             (Listening(_), FallenBehind(LaggingBehindHorizon(_, sync_peers))) => HeaderSync(sync_peers.into()),
+            (Listening(_), BackfillRequired(sync_peers)) => BackfillSync(sync_peers.into()),
+            (BackfillSync(s), BackfillSynchronized) => Listening(s.into()),
+            (BackfillSync(s), BackfillFailed) => Waiting(s.into()),
             (Waiting(s), Continue) => Listening(s.into()),
             (_, FatalError(s)) => Shutdown(states::Shutdown::with_reason(s)),
             (_, UserQuit) => Shutdown(states::Shutdown::with_reason("Shutdown initiated by user".to_string())),
@@ -217,6 +220,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeStateMachine<B> {
             HeaderSync(s) => s.next_event(shared_state).await,
             HorizonStateSync(s) => s.next_event(shared_state).await,
             BlockSync(s) => s.next_event(shared_state).await,
+            BackfillSync(s) => s.next_event(shared_state).await,
             Listening(s) => s.next_event(shared_state).await,
             Waiting(s) => s.next_event().await,
             Shutdown(_) => unreachable!("called get_next_state_event while in Shutdown state"),
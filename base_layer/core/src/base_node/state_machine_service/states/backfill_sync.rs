@@ -0,0 +1,92 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    base_node::{
+        state_machine_service::states::{BackfillSyncInfo, StateEvent, StateInfo, StatusInfo},
+        sync::{BackfillSynchronizer, SyncPeers},
+        BaseNodeStateMachine,
+    },
+    chain_storage::BlockchainBackend,
+};
+use log::*;
+use std::time::Instant;
+use tari_comms::peer_manager::NodeId;
+
+const LOG_TARGET: &str = "c::bn::state_machine_service::states::backfill_sync";
+
+/// Restores pruned block data below the pruning horizon when the pruning horizon has been widened, or the node has been
+/// switched from pruned to archival mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackfillSync {
+    sync_peers: Vec<NodeId>,
+}
+
+impl BackfillSync {
+    pub fn new(sync_peers: Vec<NodeId>) -> Self {
+        Self { sync_peers }
+    }
+
+    pub async fn next_event<B: BlockchainBackend + 'static>(
+        &mut self,
+        shared: &mut BaseNodeStateMachine<B>,
+    ) -> StateEvent
+    {
+        let mut synchronizer =
+            BackfillSynchronizer::new(shared.db.clone(), shared.connectivity.clone(), self.sync_peers.clone());
+
+        let status_event_sender = shared.status_event_sender.clone();
+        let bootstrapped = shared.is_bootstrapped();
+        synchronizer.on_progress(move |pruned_height, target_height, sync_peers| {
+            let _ = status_event_sender.broadcast(StatusInfo {
+                bootstrapped,
+                state_info: StateInfo::BackfillSync(BackfillSyncInfo::new(
+                    pruned_height,
+                    target_height,
+                    sync_peers.to_vec(),
+                )),
+            });
+        });
+
+        let timer = Instant::now();
+        match synchronizer.synchronize().await {
+            Ok(()) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Pruned blocks backfilled in {:.0?}",
+                    timer.elapsed()
+                );
+                StateEvent::BackfillSynchronized
+            },
+            Err(err) => {
+                warn!(target: LOG_TARGET, "Backfill of pruned blocks failed: {}", err);
+                StateEvent::BackfillFailed
+            },
+        }
+    }
+}
+
+impl From<SyncPeers> for BackfillSync {
+    fn from(sync_peers: SyncPeers) -> Self {
+        Self::new(sync_peers.into_iter().map(|p| p.node_id).collect())
+    }
+}
@@ -57,6 +57,7 @@ impl BlockSync {
     ) -> StateEvent
     {
         let mut synchronizer = BlockSynchronizer::new(
+            shared.config.block_sync_config.clone(),
             shared.db.clone(),
             shared.connectivity.clone(),
             self.sync_peer.take(),
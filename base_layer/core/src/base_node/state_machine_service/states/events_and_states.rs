@@ -22,6 +22,7 @@
 
 use crate::base_node::{
     state_machine_service::states::{
+        BackfillSync,
         BlockSync,
         HeaderSync,
         HorizonStateSync,
@@ -43,6 +44,8 @@ pub enum BaseNodeState {
     HeaderSync(HeaderSync),
     HorizonStateSync(HorizonStateSync),
     BlockSync(BlockSync),
+    // Restoring pruned block data below the pruning horizon
+    BackfillSync(BackfillSync),
     // The best network chain metadata
     Listening(Listening),
     // We're in a paused state, and will return to Listening after a timeout
@@ -61,6 +64,9 @@ pub enum StateEvent {
     BlocksSynchronized,
     BlockSyncFailed,
     FallenBehind(SyncStatus),
+    BackfillRequired(SyncPeers),
+    BackfillSynchronized,
+    BackfillFailed,
     NetworkSilence,
     FatalError(String),
     Continue,
@@ -132,6 +138,9 @@ impl Display for StateEvent {
             HorizonStateSyncFailure => f.write_str("Horizon State Synchronization Failed"),
             BlockSyncFailed => f.write_str("Block Synchronization Failed"),
             FallenBehind(s) => write!(f, "Fallen behind main chain - {}", s),
+            BackfillRequired(peers) => write!(f, "Pruned block data required ({} archival peer(s))", peers.len()),
+            BackfillSynchronized => f.write_str("Pruned Block Data Backfilled"),
+            BackfillFailed => f.write_str("Backfill of Pruned Block Data Failed"),
             NetworkSilence => f.write_str("Network Silence"),
             Continue => f.write_str("Continuing"),
             FatalError(e) => write!(f, "Fatal Error - {}", e),
@@ -148,6 +157,7 @@ impl Display for BaseNodeState {
             HeaderSync(_) => "Synchronizing block headers",
             HorizonStateSync(_) => "Synchronizing horizon state",
             BlockSync(_) => "Synchronizing blocks",
+            BackfillSync(_) => "Backfilling pruned blocks",
             Listening(_) => "Listening",
             Shutdown(_) => "Shutting down",
             Waiting(_) => "Waiting",
@@ -163,6 +173,7 @@ pub enum StateInfo {
     HeaderSync(BlockSyncInfo),
     HorizonSync(HorizonSyncInfo),
     BlockSync(BlockSyncInfo),
+    BackfillSync(BackfillSyncInfo),
     Listening(ListeningInfo),
 }
 
@@ -198,6 +209,10 @@ impl StateInfo {
                 info.tip_height,
                 info.local_height as f64 / info.tip_height as f64 * 100.0
             ),
+            Self::BackfillSync(info) => format!(
+                "Backfilling pruned blocks: pruned height {} (target {})",
+                info.pruned_height, info.target_height
+            ),
             Self::Listening(_) => "Listening".to_string(),
         }
     }
@@ -213,6 +228,8 @@ impl StateInfo {
         use StateInfo::*;
         match self {
             StartUp | HeaderSync(_) | HorizonSync(_) | BlockSync(_) => false,
+            // Backfill is only started once the chain tip is in sync
+            BackfillSync(_) => true,
             Listening(info) => info.is_synced(),
         }
     }
@@ -225,6 +242,7 @@ impl Display for StateInfo {
             Self::HeaderSync(info) => write!(f, "Synchronizing block headers: {}", info),
             Self::HorizonSync(info) => write!(f, "Synchronizing horizon state: {}", info),
             Self::BlockSync(info) => write!(f, "Synchronizing blocks: {}", info),
+            Self::BackfillSync(info) => write!(f, "Backfilling pruned blocks: {}", info),
             Self::Listening(info) => write!(f, "Listening: {}", info),
         }
     }
@@ -287,6 +305,37 @@ impl Display for BlockSyncInfo {
     }
 }
 
+/// Info about the progress of restoring pruned block data
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackfillSyncInfo {
+    pub pruned_height: u64,
+    pub target_height: u64,
+    pub sync_peers: Vec<NodeId>,
+}
+
+impl BackfillSyncInfo {
+    pub fn new(pruned_height: u64, target_height: u64, sync_peers: Vec<NodeId>) -> BackfillSyncInfo {
+        BackfillSyncInfo {
+            pruned_height,
+            target_height,
+            sync_peers,
+        }
+    }
+}
+
+impl Display for BackfillSyncInfo {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.write_str("Backfilling pruned blocks from the following peers: \n")?;
+        for peer in &self.sync_peers {
+            fmt.write_str(&format!("{}\n", peer))?;
+        }
+        fmt.write_str(&format!(
+            "Effective pruned height {} (target {})\n",
+            self.pruned_height, self.target_height
+        ))
+    }
+}
+
 /// Info about the state of horizon sync
 #[derive(Clone, Debug, PartialEq)]
 pub struct HorizonSyncInfo {
@@ -24,10 +24,19 @@ use crate::{
     base_node::{
         chain_metadata_service::{ChainMetadataEvent, PeerChainMetadata},
         state_machine_service::{
-            states::{BlockSync, HeaderSync, StateEvent, StateEvent::FatalError, StateInfo, SyncStatus, Waiting},
+            states::{
+                BackfillSync,
+                BlockSync,
+                HeaderSync,
+                StateEvent,
+                StateEvent::FatalError,
+                StateInfo,
+                SyncStatus,
+                Waiting,
+            },
             BaseNodeStateMachine,
         },
-        sync::SyncPeers,
+        sync::{SyncPeer, SyncPeers},
     },
     chain_storage::BlockchainBackend,
 };
@@ -36,10 +45,12 @@ use log::*;
 use num_format::{Locale, ToFormattedString};
 use serde::{Deserialize, Serialize};
 use std::{
+    cmp,
     fmt::{Display, Formatter},
     ops::Deref,
 };
 use tari_common_types::chain_metadata::ChainMetadata;
+use tari_comms::peer_manager::PeerManager;
 use tari_crypto::tari_utilities::epoch_time::EpochTime;
 use tokio::sync::broadcast;
 
@@ -182,9 +193,10 @@ impl Listening {
                     let local_tip_height = local.height_of_longest_chain();
                     // If we have configured sync peers, they are already filtered at this point
                     let sync_peers = if configured_sync_peers.is_empty() {
-                        select_sync_peers(local_tip_height, &best_metadata, &peer_metadata_list)
+                        let sync_peers = select_sync_peers(local_tip_height, &best_metadata, &peer_metadata_list);
+                        order_by_reputation(&shared.peer_manager, sync_peers).await
                     } else {
-                        peer_metadata_list
+                        peer_metadata_list.clone()
                     };
                     let sync_mode = determine_sync_mode(
                         shared.config.blocks_behind_before_considered_lagging,
@@ -197,6 +209,21 @@ impl Listening {
                         return StateEvent::FallenBehind(sync_mode);
                     }
 
+                    // The pruning horizon has been widened (or set to archival) since the data was pruned
+                    let backfill_height = local.horizon_block(local_tip_height);
+                    if local.pruned_height() > backfill_height {
+                        let backfill_peers = select_backfill_peers(backfill_height, &peer_metadata_list);
+                        if backfill_peers.is_empty() {
+                            debug!(
+                                target: LOG_TARGET,
+                                "Blocks below height {} are pruned, however no peers are able to provide them",
+                                local.pruned_height()
+                            );
+                        } else {
+                            return StateEvent::BackfillRequired(backfill_peers);
+                        }
+                    }
+
                     if !self.is_synced {
                         self.is_synced = true;
                         debug!(target: LOG_TARGET, "Initial sync achieved");
@@ -241,6 +268,13 @@ impl From<BlockSync> for Listening {
     }
 }
 
+impl From<BackfillSync> for Listening {
+    fn from(_: BackfillSync) -> Self {
+        // Backfill only starts from a synced listening state
+        Self { is_synced: true }
+    }
+}
+
 // Finds the set of sync peers that have the best tip on their main chain and have all the data required to update the
 // local node.
 fn select_sync_peers(
@@ -260,6 +294,30 @@ fn select_sync_peers(
         .collect()
 }
 
+// Orders the sync peers so that the peers with the highest reputation are tried first
+async fn order_by_reputation(peer_manager: &PeerManager, sync_peers: Vec<SyncPeer>) -> Vec<SyncPeer> {
+    let mut scored_peers = Vec::with_capacity(sync_peers.len());
+    for sync_peer in sync_peers {
+        let reputation = peer_manager
+            .find_by_node_id(&sync_peer.node_id)
+            .await
+            .map(|peer| peer.reputation)
+            .unwrap_or_default();
+        scored_peers.push((reputation, sync_peer));
+    }
+    scored_peers.sort_by_key(|(reputation, _)| cmp::Reverse(*reputation));
+    scored_peers.into_iter().map(|(_, sync_peer)| sync_peer).collect()
+}
+
+// Finds the set of peers that have not pruned the blocks above the given height
+fn select_backfill_peers(backfill_height: u64, peer_metadata_list: &[PeerChainMetadata]) -> Vec<PeerChainMetadata> {
+    peer_metadata_list
+        .iter()
+        .filter(|peer| peer.chain_metadata.pruned_height() <= backfill_height)
+        .cloned()
+        .collect()
+}
+
 /// Determine the best metadata from a set of metadata received from the network.
 fn best_metadata(metadata_list: &[PeerChainMetadata]) -> Option<&ChainMetadata> {
     // TODO: Use heuristics to weed out outliers / dishonest nodes.
@@ -474,4 +532,18 @@ mod test {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn backfill_peer_selection() {
+        let archival = PeerChainMetadata::new(random_node_id(), ChainMetadata::new(5000, vec![], 0, 0, 1));
+        let wide_horizon = PeerChainMetadata::new(random_node_id(), ChainMetadata::new(5000, vec![], 3000, 2000, 1));
+        let narrow_horizon = PeerChainMetadata::new(random_node_id(), ChainMetadata::new(5000, vec![], 500, 4500, 1));
+        let peers = vec![archival.clone(), wide_horizon.clone(), narrow_horizon];
+
+        let backfill_peers = select_backfill_peers(0, &peers);
+        assert_eq!(backfill_peers, vec![archival.clone()]);
+
+        let backfill_peers = select_backfill_peers(2000, &peers);
+        assert_eq!(backfill_peers, vec![archival, wide_horizon]);
+    }
 }
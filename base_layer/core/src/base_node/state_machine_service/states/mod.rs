@@ -51,13 +51,31 @@
 //!
 //! Full blocks received while in this state can be stored in the orphan pool until they are needed.
 //!
+//! ## BackfillSync
+//!
+//! If the effective pruned height of the local chain is above the horizon for the configured pruning horizon (i.e. the
+//! pruning horizon was widened or the node was switched back to archival mode), the pruned outputs, range proofs and
+//! inputs below the horizon are downloaded from peers that still have them, and the pruned height is lowered as each
+//! batch of blocks is restored. Once complete, switch back to `Listening`.
+//!
 //! ## Shutdown
 //!
 //! Reject all new requests with a `Shutdown` message, complete current validations / tasks, flush all state if
 //! required, and then shutdown.
 
 mod events_and_states;
-pub use events_and_states::{BaseNodeState, BlockSyncInfo, StateEvent, StateInfo, StatusInfo, SyncStatus};
+pub use events_and_states::{
+    BackfillSyncInfo,
+    BaseNodeState,
+    BlockSyncInfo,
+    StateEvent,
+    StateInfo,
+    StatusInfo,
+    SyncStatus,
+};
+
+mod backfill_sync;
+pub use backfill_sync::BackfillSync;
 
 mod block_sync;
 pub use block_sync::BlockSync;
@@ -20,7 +20,13 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::base_node::state_machine_service::states::{BlockSync, HeaderSync, HorizonStateSync, StateEvent};
+use crate::base_node::state_machine_service::states::{
+    BackfillSync,
+    BlockSync,
+    HeaderSync,
+    HorizonStateSync,
+    StateEvent,
+};
 use log::info;
 use std::time::Duration;
 use tokio::time::delay_for;
@@ -78,3 +84,9 @@ impl From<HorizonStateSync> for Waiting {
         Default::default()
     }
 }
+
+impl From<BackfillSync> for Waiting {
+    fn from(_: BackfillSync) -> Self {
+        Default::default()
+    }
+}
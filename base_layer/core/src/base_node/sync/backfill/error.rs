@@ -0,0 +1,49 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::chain_storage::ChainStorageError;
+use tari_comms::{
+    connectivity::ConnectivityError,
+    protocol::rpc::{RpcError, RpcStatus},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackfillError {
+    #[error("RPC error: {0}")]
+    RpcError(#[from] RpcError),
+    #[error("RPC request failed: {0}")]
+    RpcRequestError(#[from] RpcStatus),
+    #[error("Chain storage error: {0}")]
+    ChainStorageError(#[from] ChainStorageError),
+    #[error("Peer sent invalid block body: {0}")]
+    ReceivedInvalidBlockBody(String),
+    #[error("Peer sent a block that did not form a chain. Expected hash = {expected}, got = {got}")]
+    PeerSentBlockThatDidNotFormAChain { expected: String, got: String },
+    #[error("Peer ended the block stream at height {got}, expected blocks up to height {expected}")]
+    PeerSentIncompleteBlockRange { expected: u64, got: u64 },
+    #[error("Connectivity Error: {0}")]
+    ConnectivityError(#[from] ConnectivityError),
+    #[error("No archival peers available to backfill from")]
+    NoSyncPeers,
+    #[error("Backfill failed for all sync peers")]
+    SyncFailedAllPeers,
+}
@@ -0,0 +1,338 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::error::BackfillError;
+use crate::{
+    base_node::sync::{hooks::Hooks, rpc},
+    blocks::BlockHeader,
+    chain_storage::{
+        async_db::AsyncBlockchainDb,
+        BlockchainBackend,
+        ChainStorageError,
+        DbTransaction,
+        MmrTree,
+        OrNotFound,
+        PrunedOutput,
+    },
+    proto::base_node::SyncBlocksRequest,
+    tari_utilities::{hex::Hex, Hashable},
+    transactions::{aggregated_body::AggregateBody, types::HashOutput},
+};
+use croaring::Bitmap;
+use futures::StreamExt;
+use log::*;
+use std::{cmp, collections::HashMap, convert::TryFrom, time::Duration};
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, PeerConnection};
+use tokio::task;
+
+const LOG_TARGET: &str = "c::bn::backfill";
+
+/// The maximum number of blocks requested from a peer in a single `sync_blocks` call
+const BATCH_SIZE: u64 = 1000;
+
+/// Restores the outputs, range proofs and inputs that were pruned from blocks below the pruning horizon, by downloading
+/// the full blocks from peers that still have them. The effective pruned height is lowered as each batch of blocks is
+/// restored, until it reaches the horizon of the configured pruning horizon (zero for archival nodes).
+pub struct BackfillSynchronizer<B> {
+    db: AsyncBlockchainDb<B>,
+    connectivity: ConnectivityRequester,
+    sync_peers: Vec<NodeId>,
+    hooks: Hooks,
+}
+
+impl<B: BlockchainBackend + 'static> BackfillSynchronizer<B> {
+    pub fn new(db: AsyncBlockchainDb<B>, connectivity: ConnectivityRequester, sync_peers: Vec<NodeId>) -> Self {
+        Self {
+            db,
+            connectivity,
+            sync_peers,
+            hooks: Default::default(),
+        }
+    }
+
+    /// Called after each batch of blocks is restored with the new pruned height and the target pruned height
+    pub fn on_progress<H>(&mut self, hook: H)
+    where H: FnMut(u64, u64, &[NodeId]) + Send + Sync + 'static {
+        self.hooks.add_on_progress_backfill_hook(hook);
+    }
+
+    pub async fn synchronize(&mut self) -> Result<(), BackfillError> {
+        if self.sync_peers.is_empty() {
+            return Err(BackfillError::NoSyncPeers);
+        }
+
+        for node_id in self.sync_peers.clone() {
+            let conn = match self.connectivity.dial_peer(node_id.clone()).await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to dial backfill peer `{}`: {}", node_id, err
+                    );
+                    continue;
+                },
+            };
+
+            info!(
+                target: LOG_TARGET,
+                "Attempting to backfill pruned blocks from `{}`", node_id
+            );
+            match self.attempt_backfill(conn).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to backfill pruned blocks from peer `{}`: {}", node_id, err
+                    );
+                },
+            }
+        }
+
+        Err(BackfillError::SyncFailedAllPeers)
+    }
+
+    async fn attempt_backfill(&mut self, mut conn: PeerConnection) -> Result<(), BackfillError> {
+        let mut client = conn
+            .connect_rpc_using_builder(rpc::BaseNodeSyncRpcClient::builder().with_deadline(Duration::from_secs(60)))
+            .await?;
+
+        loop {
+            let metadata = self.db.get_chain_metadata().await?;
+            let target_height = metadata.horizon_block(metadata.height_of_longest_chain());
+            let pruned_height = metadata.pruned_height();
+            if pruned_height <= target_height {
+                debug!(
+                    target: LOG_TARGET,
+                    "Backfill complete. Effective pruned height is {}", pruned_height
+                );
+                return Ok(());
+            }
+
+            // Blocks at and below the pruned height may have pruned data
+            let start = cmp::max(target_height + 1, pruned_height.saturating_sub(BATCH_SIZE - 1));
+            self.backfill_blocks(conn.peer_node_id(), &mut client, start, pruned_height)
+                .await?;
+
+            let horizon_data = self
+                .db
+                .fetch_horizon_data()
+                .await
+                .or_not_found("HorizonData", "", "".to_string())?;
+            let new_pruned_height = start - 1;
+            self.db
+                .write_transaction()
+                .set_pruned_height(
+                    new_pruned_height,
+                    horizon_data.kernel_sum().clone(),
+                    horizon_data.utxo_sum().clone(),
+                )
+                .commit()
+                .await?;
+
+            info!(
+                target: LOG_TARGET,
+                "Backfilled blocks #{} to #{}. Effective pruned height is now {} (target {})",
+                start,
+                pruned_height,
+                new_pruned_height,
+                target_height
+            );
+            self.hooks
+                .call_on_progress_backfill_hooks(new_pruned_height, target_height, &[conn.peer_node_id().clone()]);
+        }
+    }
+
+    async fn backfill_blocks(
+        &self,
+        peer: &NodeId,
+        client: &mut rpc::BaseNodeSyncRpcClient,
+        start: u64,
+        end: u64,
+    ) -> Result<(), BackfillError>
+    {
+        let prev_header = self.db.fetch_chain_header(start - 1).await?;
+        let end_header = self.db.fetch_chain_header(end).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Requesting pruned blocks #{} to #{} from peer `{}`", start, end, peer
+        );
+
+        let request = SyncBlocksRequest {
+            start_hash: prev_header.hash().clone(),
+            end_hash: end_header.hash().clone(),
+        };
+        let mut block_stream = client.sync_blocks(request).await?;
+        let mut prev_hash = prev_header.hash().clone();
+        let mut last_height = start - 1;
+        while let Some(block) = block_stream.next().await {
+            let block = block?;
+            let header = self
+                .db
+                .fetch_header_by_block_hash(block.hash.clone())
+                .await?
+                .ok_or_else(|| {
+                    BackfillError::ReceivedInvalidBlockBody("Peer sent hash for block header we do not have".into())
+                })?;
+
+            if header.prev_hash != prev_hash {
+                return Err(BackfillError::PeerSentBlockThatDidNotFormAChain {
+                    expected: prev_hash.to_hex(),
+                    got: header.prev_hash.to_hex(),
+                });
+            }
+            prev_hash = header.hash();
+            last_height = header.height;
+
+            let body = block
+                .body
+                .map(AggregateBody::try_from)
+                .ok_or_else(|| BackfillError::ReceivedInvalidBlockBody("Block body was empty".to_string()))?
+                .map_err(BackfillError::ReceivedInvalidBlockBody)?;
+
+            let db = self.db.clone();
+            let txn = task::spawn_blocking(move || {
+                let db = db.inner().db_read_access()?;
+                prepare_restore_transaction(&*db, &header, body)
+            })
+            .await
+            .expect("backfill task panicked")?;
+
+            if !txn.operations().is_empty() {
+                trace!(
+                    target: LOG_TARGET,
+                    "Restoring {} pruned item(s) in block #{}",
+                    txn.operations().len(),
+                    last_height
+                );
+                self.db.write(txn).await?;
+            }
+        }
+
+        if last_height != end {
+            return Err(BackfillError::PeerSentIncompleteBlockRange {
+                expected: end,
+                got: last_height,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the block body against the output hashes, range proof hashes and spent output positions that were retained
+/// when the block was pruned, and returns a transaction that restores the data that is missing.
+fn prepare_restore_transaction<B: BlockchainBackend>(
+    db: &B,
+    header: &BlockHeader,
+    body: AggregateBody,
+) -> Result<DbTransaction, BackfillError>
+{
+    let header_hash = header.hash();
+    let mut txn = DbTransaction::new();
+
+    let stored_outputs = db.fetch_outputs_in_block(&header_hash)?;
+    if stored_outputs.len() != body.outputs().len() {
+        return Err(BackfillError::ReceivedInvalidBlockBody(format!(
+            "Block #{} contains {} output(s) but {} were received",
+            header.height,
+            stored_outputs.len(),
+            body.outputs().len()
+        )));
+    }
+    let stored_outputs = stored_outputs
+        .into_iter()
+        .map(|output| match output {
+            PrunedOutput::Pruned {
+                output_hash,
+                range_proof_hash,
+            } => (output_hash, (range_proof_hash, true)),
+            PrunedOutput::NotPruned { output } => (output.hash(), (output.proof.hash(), false)),
+        })
+        .collect::<HashMap<HashOutput, _>>();
+
+    let num_stored_inputs = db.fetch_inputs_in_block(&header_hash)?.len();
+    let (inputs, outputs, _) = body.dissolve();
+
+    for output in outputs {
+        let output_hash = output.hash();
+        match stored_outputs.get(&output_hash) {
+            Some((range_proof_hash, is_pruned)) if *range_proof_hash == output.proof.hash() => {
+                if *is_pruned {
+                    txn.restore_pruned_output(output, header_hash.clone());
+                }
+            },
+            _ => {
+                return Err(BackfillError::ReceivedInvalidBlockBody(format!(
+                    "Output {} does not match any output in block #{}",
+                    output_hash.to_hex(),
+                    header.height
+                )))
+            },
+        }
+    }
+
+    if num_stored_inputs == inputs.len() {
+        return Ok(txn);
+    }
+    if num_stored_inputs != 0 {
+        return Err(ChainStorageError::CorruptedDatabase(format!(
+            "Block #{} has {} of {} inputs stored",
+            header.height,
+            num_stored_inputs,
+            inputs.len()
+        ))
+        .into());
+    }
+
+    // The inputs must spend exactly the outputs that were marked as deleted by this block
+    let deleted = db
+        .fetch_block_accumulated_data_by_height(header.height)?
+        .map(|data| data.deleted().clone())
+        .unwrap_or_else(Bitmap::create);
+    let prev_deleted = db
+        .fetch_block_accumulated_data_by_height(header.height.saturating_sub(1))?
+        .map(|data| data.deleted().clone())
+        .unwrap_or_else(Bitmap::create);
+    let expected_spent = deleted - prev_deleted;
+
+    let mut spent = Bitmap::create();
+    let mut restored_inputs = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let mmr_position = db.fetch_mmr_leaf_index(MmrTree::Utxo, &input.hash())?.ok_or_else(|| {
+            BackfillError::ReceivedInvalidBlockBody(format!("Input {} spends an unknown output", input.hash().to_hex()))
+        })?;
+        spent.add(mmr_position);
+        restored_inputs.push((input, mmr_position));
+    }
+    if spent != expected_spent {
+        return Err(BackfillError::ReceivedInvalidBlockBody(format!(
+            "Inputs for block #{} do not match the outputs spent in the block",
+            header.height
+        )));
+    }
+
+    for (input, mmr_position) in restored_inputs {
+        txn.insert_input(input, header_hash.clone(), mmr_position);
+    }
+
+    Ok(txn)
+}
@@ -0,0 +1,147 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use super::BackfillSynchronizer;
+use crate::{
+    base_node::sync::rpc::{BaseNodeSyncRpcServer, BaseNodeSyncRpcService},
+    blocks::Block,
+    chain_storage::{BlockchainDatabase, DbTransaction},
+    tari_utilities::Hashable,
+    test_helpers::{
+        blockchain::{create_new_blockchain, TempDatabase},
+        create_block,
+    },
+    transactions::types::Commitment,
+};
+use std::sync::{Arc, Mutex};
+use tari_comms::{
+    peer_manager::{NodeId, PeerFeatures},
+    protocol::rpc::{mock::MockRpcServer, NamedProtocolService},
+    test_utils::{mocks::create_connectivity_mock, node_identity::build_node_identity},
+    Substream,
+};
+
+const NUM_BLOCKS: u64 = 10;
+
+struct TestHarness {
+    pruned_db: BlockchainDatabase<TempDatabase>,
+    synchronizer: BackfillSynchronizer<TempDatabase>,
+    progress: Arc<Mutex<Vec<(u64, u64)>>>,
+    _archival_db: BlockchainDatabase<TempDatabase>,
+    _rpc_server: MockRpcServer<BaseNodeSyncRpcServer<BaseNodeSyncRpcService<TempDatabase>>, Substream>,
+}
+
+fn add_chained_blocks(dbs: &[&BlockchainDatabase<TempDatabase>], num_blocks: u64) {
+    let mut prev_block = dbs[0].fetch_block(0).unwrap().block().clone();
+    for height in 1..=num_blocks {
+        let mut block = create_block(1, height, vec![]);
+        block.header.prev_hash = prev_block.hash();
+        block.header.output_mmr_size = prev_block.header.output_mmr_size + block.body.outputs().len() as u64;
+        block.header.kernel_mmr_size = prev_block.header.kernel_mmr_size + block.body.kernels().len() as u64;
+        let block = Arc::new(block);
+        for db in dbs {
+            db.add_block(block.clone()).unwrap().assert_added();
+        }
+        prev_block = Block::clone(&block);
+    }
+}
+
+/// Sets up an archival node that serves the sync RPC and a node that has pruned its blocks up to `pruned_height`
+async fn setup(pruning_horizon: u64, pruned_height: u64) -> TestHarness {
+    let archival_db = create_new_blockchain();
+    let pruned_db = create_new_blockchain();
+    add_chained_blocks(&[&archival_db, &pruned_db], NUM_BLOCKS);
+
+    let mut txn = DbTransaction::new();
+    txn.set_pruning_horizon(pruning_horizon).set_pruned_height(
+        pruned_height,
+        Commitment::default(),
+        Commitment::default(),
+    );
+    pruned_db.write(txn).unwrap();
+
+    let (connectivity, connectivity_mock) = create_connectivity_mock();
+    let connectivity_mock_state = connectivity_mock.get_shared_state();
+    tokio::spawn(connectivity_mock.run());
+
+    let server = BaseNodeSyncRpcServer::new(BaseNodeSyncRpcService::new(archival_db.clone().into()));
+    let protocol_name = server.as_protocol_name();
+    let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let mut rpc_server = MockRpcServer::new(server, server_node_identity.clone());
+    rpc_server.serve();
+    let connection = rpc_server
+        .create_connection(server_node_identity.to_peer(), protocol_name.into())
+        .await;
+    connectivity_mock_state.add_active_connection(connection).await;
+
+    let mut synchronizer =
+        BackfillSynchronizer::new(pruned_db.clone().into(), connectivity, vec![server_node_identity
+            .node_id()
+            .clone()]);
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let progress_hook = progress.clone();
+    synchronizer.on_progress(move |pruned_height, target_height, _: &[NodeId]| {
+        progress_hook.lock().unwrap().push((pruned_height, target_height));
+    });
+
+    TestHarness {
+        pruned_db,
+        synchronizer,
+        progress,
+        _archival_db: archival_db,
+        _rpc_server: rpc_server,
+    }
+}
+
+#[tokio_macros::test]
+async fn it_lowers_the_pruned_height_to_zero_for_an_archival_node() {
+    let mut harness = setup(0, 8).await;
+    assert_eq!(harness.pruned_db.get_chain_metadata().unwrap().pruned_height(), 8);
+
+    harness.synchronizer.synchronize().await.unwrap();
+
+    assert_eq!(harness.pruned_db.get_chain_metadata().unwrap().pruned_height(), 0);
+    assert_eq!(*harness.progress.lock().unwrap(), vec![(0, 0)]);
+}
+
+#[tokio_macros::test]
+async fn it_lowers_the_pruned_height_to_the_pruning_horizon() {
+    let mut harness = setup(4, 9).await;
+
+    harness.synchronizer.synchronize().await.unwrap();
+
+    let metadata = harness.pruned_db.get_chain_metadata().unwrap();
+    assert_eq!(metadata.pruned_height(), NUM_BLOCKS - 4);
+    assert_eq!(*harness.progress.lock().unwrap(), vec![(
+        NUM_BLOCKS - 4,
+        NUM_BLOCKS - 4
+    )]);
+}
+
+#[tokio_macros::test]
+async fn it_does_nothing_if_the_pruned_height_is_within_the_pruning_horizon() {
+    let mut harness = setup(4, 3).await;
+
+    harness.synchronizer.synchronize().await.unwrap();
+
+    assert_eq!(harness.pruned_db.get_chain_metadata().unwrap().pruned_height(), 3);
+    assert!(harness.progress.lock().unwrap().is_empty());
+}
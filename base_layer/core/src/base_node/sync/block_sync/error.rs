@@ -49,3 +49,14 @@ pub enum BlockSyncError {
     #[error("Block validation failed: {0}")]
     ValidationError(#[from] ValidationError),
 }
+
+impl BlockSyncError {
+    /// Returns true if the sync peer did not respond in time
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            BlockSyncError::RpcError(err) => err.is_timeout(),
+            BlockSyncError::RpcRequestError(status) => status.status_code().is_timeout(),
+            _ => false,
+        }
+    }
+}
@@ -22,7 +22,7 @@
 
 use super::error::BlockSyncError;
 use crate::{
-    base_node::sync::{hooks::Hooks, rpc},
+    base_node::sync::{hooks::Hooks, rpc, BlockSyncConfig},
     blocks::Block,
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainBlock},
     proto::base_node::SyncBlocksRequest,
@@ -40,7 +40,7 @@ use std::{
 };
 use tari_comms::{
     connectivity::{ConnectivityRequester, ConnectivitySelection},
-    peer_manager::NodeId,
+    peer_manager::{NodeId, ReputationEvent},
     PeerConnection,
 };
 use tokio::task;
@@ -48,6 +48,7 @@ use tokio::task;
 const LOG_TARGET: &str = "c::bn::block_sync";
 
 pub struct BlockSynchronizer<B> {
+    config: BlockSyncConfig,
     db: AsyncBlockchainDb<B>,
     connectivity: ConnectivityRequester,
     sync_peer: Option<PeerConnection>,
@@ -57,6 +58,7 @@ pub struct BlockSynchronizer<B> {
 
 impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
     pub fn new(
+        config: BlockSyncConfig,
         db: AsyncBlockchainDb<B>,
         connectivity: ConnectivityRequester,
         sync_peer: Option<PeerConnection>,
@@ -64,6 +66,7 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
     ) -> Self
     {
         Self {
+            config,
             db,
             connectivity,
             sync_peer,
@@ -89,12 +92,33 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
             target: LOG_TARGET,
             "Attempting to synchronize blocks with `{}`", node_id
         );
-        self.attempt_block_sync(peer_conn).await?;
+        let result = self.attempt_block_sync(peer_conn).await;
+        let reputation_event = match &result {
+            Ok(()) => Some(ReputationEvent::UsefulContribution),
+            Err(BlockSyncError::ValidationError(_)) |
+            Err(BlockSyncError::ReceivedInvalidBlockBody(_)) |
+            Err(BlockSyncError::PeerSentBlockThatDidNotFormAChain { .. }) => Some(ReputationEvent::ValidationFailed),
+            Err(err) if err.is_timeout() => Some(ReputationEvent::RpcTimeout),
+            Err(_) => None,
+        };
+        if let Some(event) = reputation_event {
+            self.adjust_peer_reputation(node_id, event).await;
+        }
+        result?;
 
         self.db.cleanup_all_orphans().await?;
         Ok(())
     }
 
+    async fn adjust_peer_reputation(&mut self, node_id: NodeId, event: ReputationEvent) {
+        if event.score_delta() < 0 && self.config.sync_peers.contains(&node_id) {
+            return;
+        }
+        if let Err(err) = self.connectivity.adjust_peer_reputation(node_id, event).await {
+            warn!(target: LOG_TARGET, "Failed to adjust sync peer reputation: {}", err);
+        }
+    }
+
     async fn get_next_sync_peer(&mut self) -> Result<PeerConnection, BlockSyncError> {
         match self.sync_peer {
             Some(ref peer) => Ok(peer.clone()),
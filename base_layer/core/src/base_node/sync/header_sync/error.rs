@@ -60,3 +60,14 @@ pub enum BlockHeaderSyncError {
     #[error("Node could not find any other node with which to sync. Silence.")]
     NetworkSilence,
 }
+
+impl BlockHeaderSyncError {
+    /// Returns true if the sync peer did not respond in time
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            BlockHeaderSyncError::RpcError(err) => err.is_timeout(),
+            BlockHeaderSyncError::RpcRequestError(status) => status.status_code().is_timeout(),
+            _ => false,
+        }
+    }
+}
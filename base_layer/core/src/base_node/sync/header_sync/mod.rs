@@ -30,3 +30,4 @@ mod synchronizer;
 pub use synchronizer::HeaderSynchronizer;
 
 mod validator;
+pub use validator::BlockHeaderSyncValidator;
@@ -40,7 +40,7 @@ use log::*;
 use std::{convert::TryFrom, sync::Arc, time::Duration};
 use tari_comms::{
     connectivity::{ConnectivityError, ConnectivityRequester, ConnectivitySelection},
-    peer_manager::NodeId,
+    peer_manager::{NodeId, ReputationEvent},
     protocol::rpc::RpcError,
     PeerConnection,
 };
@@ -102,7 +102,11 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
                 "Attempting to synchronize headers with `{}`", node_id
             );
             match self.attempt_sync(peer_conn.clone()).await {
-                Ok(()) => return Ok(peer_conn),
+                Ok(()) => {
+                    self.adjust_peer_reputation(node_id, ReputationEvent::UsefulContribution)
+                        .await;
+                    return Ok(peer_conn);
+                },
                 // Try another peer
                 Err(err @ BlockHeaderSyncError::NotInSync) => {
                     debug!(target: LOG_TARGET, "{}", err);
@@ -110,10 +114,14 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
 
                 Err(err @ BlockHeaderSyncError::RpcError(RpcError::HandshakeTimedOut)) => {
                     debug!(target: LOG_TARGET, "{}", err);
+                    self.adjust_peer_reputation(node_id.clone(), ReputationEvent::RpcTimeout)
+                        .await;
                     self.ban_peer_short(node_id, BanReason::RpcNegotiationTimedOut).await?;
                 },
                 Err(BlockHeaderSyncError::ValidationFailed(err)) => {
                     debug!(target: LOG_TARGET, "Block header validation failed: {}", err);
+                    self.adjust_peer_reputation(node_id.clone(), ReputationEvent::ValidationFailed)
+                        .await;
                     self.ban_peer_long(node_id, err.into()).await?;
                 },
                 Err(err) => {
@@ -121,6 +129,10 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
                         target: LOG_TARGET,
                         "Failed to synchronize headers from peer `{}`: {}", node_id, err
                     );
+                    if err.is_timeout() {
+                        self.adjust_peer_reputation(node_id.clone(), ReputationEvent::RpcTimeout)
+                            .await;
+                    }
                     self.ban_peer_long(node_id, BanReason::GeneralHeaderSyncFailure(err))
                         .await?;
                 },
@@ -193,7 +205,7 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             })
             .collect::<FuturesUnordered<_>>();
 
-        let connections = tasks
+        let mut connections = tasks
             .filter_map(|r| match r {
                 Ok(conn) => future::ready(Some(conn)),
                 Err(err) => {
@@ -203,6 +215,9 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             })
             .collect::<Vec<_>>()
             .await;
+        // Dials complete in any order, attempt to sync with the peers in the order they were given (highest reputation
+        // first)
+        connections.sort_by_key(|conn| self.sync_peers.iter().position(|n| n == conn.peer_node_id()));
         debug!(
             target: LOG_TARGET,
             "Successfully dialed {} of {} sync peer(s)",
@@ -212,6 +227,16 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         Ok(connections)
     }
 
+    /// Adjusts the reputation of a sync peer. Peers that are allowlisted for sync are never penalised.
+    async fn adjust_peer_reputation(&mut self, node_id: NodeId, event: ReputationEvent) {
+        if event.score_delta() < 0 && self.config.sync_peers.contains(&node_id) {
+            return;
+        }
+        if let Err(err) = self.connectivity.adjust_peer_reputation(node_id, event).await {
+            warn!(target: LOG_TARGET, "Failed to adjust sync peer reputation: {}", err);
+        }
+    }
+
     async fn ban_peer_long(&mut self, node_id: NodeId, reason: BanReason) -> Result<(), BlockHeaderSyncError> {
         self.ban_peer_for(node_id, reason, self.config.ban_period).await
     }
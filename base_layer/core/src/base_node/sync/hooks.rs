@@ -32,6 +32,7 @@ pub(super) struct Hooks {
     on_progress_block: Vec<Box<dyn FnMut(Arc<ChainBlock>, u64, &[NodeId]) + Send + Sync>>,
     on_complete: Vec<Box<dyn FnMut(Arc<ChainBlock>) + Send + Sync>>,
     on_rewind: Vec<Box<dyn FnMut(Vec<Arc<ChainBlock>>) + Send + Sync>>,
+    on_progress_backfill: Vec<Box<dyn FnMut(u64, u64, &[NodeId]) + Send + Sync>>,
 }
 
 impl Hooks {
@@ -80,4 +81,15 @@ impl Hooks {
     pub fn call_on_rewind_hooks(&mut self, blocks: Vec<Arc<ChainBlock>>) {
         self.on_rewind.iter_mut().for_each(|f| (*f)(blocks.clone()));
     }
+
+    pub fn add_on_progress_backfill_hook<H>(&mut self, hook: H)
+    where H: FnMut(u64, u64, &[NodeId]) + Send + Sync + 'static {
+        self.on_progress_backfill.push(Box::new(hook));
+    }
+
+    pub fn call_on_progress_backfill_hooks(&mut self, pruned_height: u64, target_height: u64, sync_peers: &[NodeId]) {
+        self.on_progress_backfill
+            .iter_mut()
+            .for_each(|f| (*f)(pruned_height, target_height, sync_peers));
+    }
 }
@@ -20,21 +20,37 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "base_node")]
 mod config;
+#[cfg(feature = "base_node")]
 pub use self::config::BlockSyncConfig;
 
+#[cfg(feature = "base_node")]
+mod backfill;
+#[cfg(feature = "base_node")]
+pub use backfill::{BackfillError, BackfillSynchronizer};
+
+#[cfg(feature = "base_node")]
 mod block_sync;
+#[cfg(feature = "base_node")]
 pub use block_sync::{BlockSyncError, BlockSynchronizer};
 
+#[cfg(feature = "base_node")]
 mod header_sync;
-pub use header_sync::{BlockHeaderSyncError, HeaderSynchronizer};
+#[cfg(feature = "base_node")]
+pub use header_sync::{BlockHeaderSyncError, BlockHeaderSyncValidator, HeaderSynchronizer};
 
+#[cfg(feature = "base_node")]
 mod hooks;
 
 pub mod rpc;
 
+#[cfg(feature = "base_node")]
 mod sync_peers;
+#[cfg(feature = "base_node")]
 pub use sync_peers::{SyncPeer, SyncPeers};
 
+#[cfg(feature = "base_node")]
 mod validators;
+#[cfg(feature = "base_node")]
 pub use validators::SyncValidators;
@@ -31,18 +31,20 @@ use crate::{
         ChainBlock,
         ChainHeader,
         ChainStorageError,
+        CommitmentHistory,
         DbTransaction,
         HistoricalBlock,
         HorizonData,
         MmrTree,
         PrunedOutput,
+        Reorg,
         TargetDifficulties,
     },
     common::rolling_vec::RollingVec,
     proof_of_work::{PowAlgorithm, TargetDifficultyWindow},
     tari_utilities::epoch_time::EpochTime,
     transactions::{
-        transaction::{TransactionKernel, TransactionOutput},
+        transaction::{TransactionInput, TransactionKernel, TransactionOutput},
         types::{Commitment, HashOutput, Signature},
     },
 };
@@ -144,6 +146,12 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_utxos_by_mmr_position(start: u64, end: u64, end_header_hash: HashOutput) -> (Vec<PrunedOutput>, Vec<Bitmap>), "fetch_utxos_by_mmr_position");
 
+    make_async_fn!(fetch_outputs_in_block(header_hash: HashOutput, offset: usize, limit: usize) -> Vec<PrunedOutput>, "fetch_outputs_in_block");
+
+    make_async_fn!(fetch_inputs_in_block(header_hash: HashOutput, offset: usize, limit: usize) -> Vec<TransactionInput>, "fetch_inputs_in_block");
+
+    make_async_fn!(fetch_commitment_history(commitment: Commitment) -> Option<CommitmentHistory>, "fetch_commitment_history");
+
     //---------------------------------- Kernel --------------------------------------------//
     make_async_fn!(fetch_kernel_by_excess_sig(excess_sig: Signature) -> Option<(TransactionKernel, HashOutput)>, "fetch_kernel_by_excess_sig");
 
@@ -216,6 +224,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_block_accumulated_data_by_height(height: u64) -> BlockAccumulatedData, "fetch_block_accumulated_data_by_height");
 
+    make_async_fn!(fetch_all_reorgs() -> Vec<Reorg>, "fetch_all_reorgs");
+
     //---------------------------------- Misc. --------------------------------------------//
     make_async_fn!(fetch_block_timestamps(start_hash: HashOutput) -> RollingVec<EpochTime>, "fetch_block_timestamps");
 
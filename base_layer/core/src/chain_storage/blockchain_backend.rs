@@ -6,15 +6,17 @@ use crate::{
         BlockHeaderAccumulatedData,
         ChainHeader,
         ChainStorageError,
+        CommitmentHistory,
         DbKey,
         DbTransaction,
         DbValue,
         HorizonData,
         MmrTree,
+        Reorg,
     },
     transactions::{
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
-        types::{HashOutput, Signature},
+        types::{Commitment, HashOutput, Signature},
     },
 };
 use croaring::Bitmap;
@@ -109,9 +111,30 @@ pub trait BlockchainBackend: Send + Sync {
     /// Fetch all outputs in a block
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError>;
 
+    /// Fetch at most `take` outputs in a block, after skipping the first `skip` outputs in MMR order
+    fn fetch_outputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<PrunedOutput>, ChainStorageError>;
+
     /// Fetch all inputs in a block
     fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError>;
 
+    /// Fetch at most `take` inputs in a block, after skipping the first `skip` inputs
+    fn fetch_inputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<TransactionInput>, ChainStorageError>;
+
+    /// Fetches where an output commitment was created and spent. An `IndexNotEnabled` error is returned if the backend
+    /// is not maintaining the commitment history index.
+    fn fetch_commitment_history(&self, commitment: &Commitment)
+        -> Result<Option<CommitmentHistory>, ChainStorageError>;
+
     /// Fetches the total merkle mountain range node count upto the specified height.
     fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError>;
 
@@ -152,4 +175,7 @@ pub trait BlockchainBackend: Send + Sync {
     fn fetch_monero_seed_first_seen_height(&self, seed: &str) -> Result<u64, ChainStorageError>;
 
     fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError>;
+
+    /// Fetches the reorg history, oldest first
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError>;
 }
@@ -30,14 +30,17 @@ use crate::{
         },
         db_transaction::{DbKey, DbTransaction, DbValue},
         error::ChainStorageError,
+        metrics,
         pruned_output::PrunedOutput,
         BlockchainBackend,
         ChainBlock,
         ChainHeader,
+        CommitmentHistory,
         HistoricalBlock,
         HorizonData,
         MmrTree,
         OrNotFound,
+        Reorg,
         TargetDifficulties,
     },
     common::rolling_vec::RollingVec,
@@ -45,7 +48,7 @@ use crate::{
     proof_of_work::{monero_rx::MoneroData, PowAlgorithm, TargetDifficultyWindow},
     tari_utilities::epoch_time::EpochTime,
     transactions::{
-        transaction::{TransactionKernel, TransactionOutput},
+        transaction::{TransactionInput, TransactionKernel, TransactionOutput},
         types::{Commitment, HashDigest, HashOutput, Signature},
     },
     validation::{HeaderValidation, OrphanValidation, PostOrphanBodyValidation, ValidationError},
@@ -423,6 +426,41 @@ where B: BlockchainBackend
         db.fetch_utxos_by_mmr_position(start, end, accum_data.deleted())
     }
 
+    /// Returns at most `limit` of the outputs in the given block, skipping the first `offset` outputs
+    pub fn fetch_outputs_in_block(
+        &self,
+        header_hash: HashOutput,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<PrunedOutput>, ChainStorageError>
+    {
+        let db = self.db_read_access()?;
+        db.fetch_outputs_in_block_paged(&header_hash, offset, limit)
+    }
+
+    /// Returns at most `limit` of the inputs in the given block, skipping the first `offset` inputs
+    pub fn fetch_inputs_in_block(
+        &self,
+        header_hash: HashOutput,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<TransactionInput>, ChainStorageError>
+    {
+        let db = self.db_read_access()?;
+        db.fetch_inputs_in_block_paged(&header_hash, offset, limit)
+    }
+
+    /// Returns where the given output commitment was created and spent, if the backend maintains the commitment
+    /// history index
+    pub fn fetch_commitment_history(
+        &self,
+        commitment: Commitment,
+    ) -> Result<Option<CommitmentHistory>, ChainStorageError>
+    {
+        let db = self.db_read_access()?;
+        db.fetch_commitment_history(&commitment)
+    }
+
     /// Returns the block header at the given block height.
     pub fn fetch_header(&self, height: u64) -> Result<Option<BlockHeader>, ChainStorageError> {
         let db = self.db_read_access()?;
@@ -805,7 +843,10 @@ where B: BlockchainBackend
     pub fn add_block(&self, block: Arc<Block>) -> Result<BlockAddResult, ChainStorageError> {
         let new_height = block.header.height;
         // Perform orphan block validation.
-        if let Err(e) = self.validators.orphan.validate(&block) {
+        let timer = metrics::orphan_validation_timer();
+        let validation_result = self.validators.orphan.validate(&block);
+        timer.observe_duration();
+        if let Err(e) = validation_result {
             warn!(
                 target: LOG_TARGET,
                 "Block #{} ({}) failed validation - {}",
@@ -967,15 +1008,28 @@ where B: BlockchainBackend
     /// The operation will fail if
     /// * The block height is in the future
     /// * The block height is before the horizon block height determined by the pruning horizon
+    /// A rewind that removes blocks is recorded in the reorg history in the same transaction.
     pub fn rewind_to_height(&self, height: u64) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
         let mut db = self.db_write_access()?;
-        rewind_to_height(&mut *db, height)
+        let mut txn = DbTransaction::new();
+        let removed_blocks = prepare_rewind_to_height(&*db, height, &mut txn)?;
+        if !removed_blocks.is_empty() {
+            txn.insert_reorg(Reorg::from_reorged_blocks(&removed_blocks, &[]));
+        }
+        db.write(txn)?;
+        Ok(removed_blocks)
     }
 
     pub fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
         let db = self.db_read_access()?;
         db.fetch_horizon_data()
     }
+
+    /// Returns the most recent reorgs of the main chain, oldest first
+    pub fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.fetch_all_reorgs()
+    }
 }
 
 fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, ChainStorageError> {
@@ -1427,9 +1481,20 @@ fn check_for_valid_height<T: BlockchainBackend>(db: &T, height: u64) -> Result<(
 }
 
 fn rewind_to_height<T: BlockchainBackend>(db: &mut T, height: u64) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError> {
-    let last_header = db.fetch_last_header()?;
-
     let mut txn = DbTransaction::new();
+    let removed_blocks = prepare_rewind_to_height(db, height, &mut txn)?;
+    db.write(txn)?;
+    Ok(removed_blocks)
+}
+
+// Adds the operations that rewind the chain to the given height to `txn`, returning the blocks that are removed.
+fn prepare_rewind_to_height<T: BlockchainBackend>(
+    db: &T,
+    height: u64,
+    txn: &mut DbTransaction,
+) -> Result<Vec<Arc<ChainBlock>>, ChainStorageError>
+{
+    let last_header = db.fetch_last_header()?;
 
     // Delete headers
     let last_header_height = last_header.height;
@@ -1460,7 +1525,6 @@ fn rewind_to_height<T: BlockchainBackend>(db: &mut T, height: u64) -> Result<Vec
     let steps_back = last_block_height.saturating_sub(height);
     // No blocks to remove
     if steps_back == 0 {
-        db.write(txn)?;
         return Ok(vec![]);
     }
 
@@ -1491,7 +1555,6 @@ fn rewind_to_height<T: BlockchainBackend>(db: &mut T, height: u64) -> Result<Vec
         header_accumulated_data.hash.clone(),
         header_accumulated_data.total_accumulated_difficulty,
     );
-    db.write(txn)?;
 
     Ok(removed_blocks)
 }
@@ -1665,11 +1728,17 @@ fn reorganize_chain<T: BlockchainBackend>(
             .join(", ")
     );
 
-    for block in chain {
+    // A reorg removes blocks or adds more than one, and is recorded in the reorg history in the same transaction as
+    // the block that completes it
+    let is_reorg = !removed_blocks.is_empty() || chain.len() > 1;
+    for (i, block) in chain.iter().enumerate() {
         let mut txn = DbTransaction::new();
         let block_hash_hex = block.accumulated_data.hash.to_hex();
         txn.delete(DbKey::OrphanBlock(block.accumulated_data.hash.clone()));
-        if let Err(e) = block_validator.validate_body_for_valid_orphan(&block, backend) {
+        let timer = metrics::body_validation_timer();
+        let validation_result = block_validator.validate_body_for_valid_orphan(&block, backend);
+        timer.observe_duration();
+        if let Err(e) = validation_result {
             warn!(
                 target: LOG_TARGET,
                 "Orphan block {} ({}) failed validation during chain reorg: {}",
@@ -1685,6 +1754,10 @@ fn reorganize_chain<T: BlockchainBackend>(
         }
 
         insert_block(&mut txn, block.clone())?;
+        if is_reorg && i == chain.len() - 1 {
+            let added_blocks = chain.iter().cloned().collect::<Vec<_>>();
+            txn.insert_reorg(Reorg::from_reorged_blocks(&removed_blocks, &added_blocks));
+        }
         // Failed to store the block - this should typically never happen unless there is a bug in the validator
         // (e.g. does not catch a double spend). In any case, we still need to restore the chain to a
         // good state before returning.
@@ -0,0 +1,83 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::transactions::{
+    transaction::{KernelFeatures, TransactionKernel},
+    types::{Commitment, HashOutput, Signature},
+};
+use serde::{Deserialize, Serialize};
+
+/// The lifetime of an output commitment on the main chain: the block that created it and, once it has been spent, the
+/// block that spent it. This is maintained by the optional commitment history index.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommitmentHistory {
+    pub commitment: Commitment,
+    pub created_height: u64,
+    pub created_in_block: HashOutput,
+    pub spent_height: Option<u64>,
+    pub spent_in_block: Option<HashOutput>,
+    /// The excess signature of the kernel that spent the output. Transactions are aggregated once they are mined, so
+    /// this is only known when the spending block contains exactly one non-coinbase kernel.
+    pub spending_kernel: Option<Signature>,
+}
+
+impl CommitmentHistory {
+    pub fn new(commitment: Commitment, created_height: u64, created_in_block: HashOutput) -> Self {
+        Self {
+            commitment,
+            created_height,
+            created_in_block,
+            spent_height: None,
+            spent_in_block: None,
+            spending_kernel: None,
+        }
+    }
+
+    pub fn is_spent(&self) -> bool {
+        self.spent_height.is_some()
+    }
+
+    pub fn set_spent(&mut self, height: u64, block_hash: HashOutput, spending_kernel: Option<Signature>) {
+        self.spent_height = Some(height);
+        self.spent_in_block = Some(block_hash);
+        self.spending_kernel = spending_kernel;
+    }
+
+    pub fn clear_spent(&mut self) {
+        self.spent_height = None;
+        self.spent_in_block = None;
+        self.spending_kernel = None;
+    }
+}
+
+/// Returns the excess signature of the only non-coinbase kernel in a block, if there is exactly one. This is the only
+/// case in which an input can be attributed to a kernel.
+pub(crate) fn unambiguous_spending_kernel<'a, I>(kernels: I) -> Option<Signature>
+where I: IntoIterator<Item = &'a TransactionKernel> {
+    let mut non_coinbase = kernels
+        .into_iter()
+        .filter(|k| !k.features.contains(KernelFeatures::COINBASE_KERNEL));
+    match (non_coinbase.next(), non_coinbase.next()) {
+        (Some(kernel), None) => Some(kernel.excess_sig.clone()),
+        _ => None,
+    }
+}
@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use crate::{
     blocks::{Block, BlockHeader},
-    chain_storage::{error::ChainStorageError, BlockHeaderAccumulatedData, ChainBlock, ChainHeader, MmrTree},
+    chain_storage::{error::ChainStorageError, BlockHeaderAccumulatedData, ChainBlock, ChainHeader, MmrTree, Reorg},
     transactions::{
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
         types::{Commitment, HashOutput},
@@ -146,6 +146,16 @@ impl DbTransaction {
         self
     }
 
+    /// Restores the full output (including the range proof) of an output that was previously pruned from the given
+    /// block. The output must hash to the stored output hash and range proof hash.
+    pub fn restore_pruned_output(&mut self, output: TransactionOutput, header_hash: HashOutput) -> &mut Self {
+        self.operations.push(WriteOperation::RestorePrunedOutput {
+            header_hash,
+            output: Box::new(output),
+        });
+        self
+    }
+
     pub fn insert_input(&mut self, input: TransactionInput, header_hash: HashOutput, mmr_leaf_index: u32) -> &mut Self {
         self.operations.push(WriteOperation::InsertInput {
             header_hash,
@@ -248,6 +258,12 @@ impl DbTransaction {
         self
     }
 
+    /// Records a chain reorg in the reorg history
+    pub fn insert_reorg(&mut self, reorg: Reorg) -> &mut Self {
+        self.operations.push(WriteOperation::InsertReorg(Box::new(reorg)));
+        self
+    }
+
     pub(crate) fn operations(&self) -> &[WriteOperation] {
         &self.operations
     }
@@ -297,6 +313,10 @@ pub enum WriteOperation {
         proof_hash: HashOutput,
         mmr_position: u32,
     },
+    RestorePrunedOutput {
+        header_hash: HashOutput,
+        output: Box<TransactionOutput>,
+    },
     Delete(DbKey),
     DeleteBlock(HashOutput),
     DeleteOrphanChainTip(HashOutput),
@@ -330,6 +350,7 @@ pub enum WriteOperation {
         kernel_sum: Commitment,
         utxo_sum: Commitment,
     },
+    InsertReorg(Box<Reorg>),
 }
 
 impl fmt::Display for WriteOperation {
@@ -411,6 +432,12 @@ impl fmt::Display for WriteOperation {
                 proof_hash: _,
                 mmr_position: _,
             } => write!(f, "Insert pruned output"),
+            RestorePrunedOutput { header_hash, output } => write!(
+                f,
+                "Restore pruned output {} in block {}",
+                output.hash().to_hex(),
+                header_hash.to_hex()
+            ),
             UpdateDeletedBlockAccumulatedData {
                 header_hash: _,
                 deleted: _,
@@ -438,6 +465,7 @@ impl fmt::Display for WriteOperation {
             ),
             SetPruningHorizonConfig(pruning_horizon) => write!(f, "Set config: pruning horizon to {}", pruning_horizon),
             SetPrunedHeight { height, .. } => write!(f, "Set pruned height to {}", height),
+            InsertReorg(reorg) => write!(f, "Insert reorg: {}", reorg),
         }
     }
 }
@@ -105,6 +105,13 @@ pub enum ChainStorageError {
     CannotAcquireFileLock,
     #[error("IO Error: `{0}`")]
     IoError(#[from] std::io::Error),
+    #[error(
+        "The blockchain database has schema version {db_version} but this node only supports up to version \
+         {supported_version}. Upgrade the node software, or remove the database and resync"
+    )]
+    IncompatibleDatabaseVersion { db_version: u32, supported_version: u32 },
+    #[error("The {0} index is not enabled on this node")]
+    IndexNotEnabled(String),
 }
 
 impl ChainStorageError {
@@ -102,6 +102,10 @@ impl HistoricalBlock {
         self.pruned_outputs.as_slice()
     }
 
+    pub fn pruned_input_count(&self) -> u64 {
+        self.pruned_input_count
+    }
+
     pub fn dissolve(self) -> (Block, BlockHeaderAccumulatedData, u64, u64) {
         (
             self.block,
@@ -258,6 +258,20 @@ where V: DeserializeOwned {
     }
 }
 
+/// Returns the key of the last entry in the database
+pub fn lmdb_last_key(txn: &ConstTransaction<'_>, db: &Database) -> Result<Option<Vec<u8>>, ChainStorageError> {
+    let mut cursor = txn.cursor(db)?;
+    let access = txn.access();
+    match cursor.last::<[u8], [u8]>(&access).to_opt() {
+        Err(e) => {
+            error!(target: LOG_TARGET, "Could not get key from lmdb: {:?}", e);
+            Err(ChainStorageError::AccessError(e.to_string()))
+        },
+        Ok(None) => Ok(None),
+        Ok(Some((k, _v))) => Ok(Some(k.to_vec())),
+    }
+}
+
 pub fn lmdb_exists<K>(txn: &ConstTransaction<'_>, db: &Database, key: &K) -> Result<bool, ChainStorageError>
 where K: AsLmdbBytes + ?Sized {
     let access = txn.access();
@@ -279,11 +293,32 @@ pub fn lmdb_len(txn: &ConstTransaction<'_>, db: &Database) -> Result<usize, Chai
     Ok(stats.entries)
 }
 
+/// Deletes every entry in the database
+pub fn lmdb_clear(txn: &WriteTransaction<'_>, db: &Database) -> Result<(), ChainStorageError> {
+    txn.access().clear_db(db)?;
+    Ok(())
+}
+
 pub fn lmdb_fetch_keys_starting_with<V>(
     key: &str,
     txn: &ConstTransaction<'_>,
     db: &Database,
 ) -> Result<Vec<V>, ChainStorageError>
+where
+    V: DeserializeOwned,
+{
+    lmdb_fetch_keys_starting_with_paged(key, txn, db, 0, usize::MAX)
+}
+
+/// Fetches at most `take` of the rows whose keys start with `key`, after skipping the first `skip` matching rows.
+/// Skipped rows are not deserialized.
+pub fn lmdb_fetch_keys_starting_with_paged<V>(
+    key: &str,
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    skip: usize,
+    take: usize,
+) -> Result<Vec<V>, ChainStorageError>
 where
     V: DeserializeOwned,
 {
@@ -301,9 +336,14 @@ where
     };
     trace!(target: LOG_TARGET, "Key: {}", row.0);
     let mut result = vec![];
-    while row.0.starts_with(key) {
-        let val = deserialize::<V>(row.1)?;
-        result.push(val);
+    let mut skipped = 0;
+    while row.0.starts_with(key) && result.len() < take {
+        if skipped < skip {
+            skipped += 1;
+        } else {
+            let val = deserialize::<V>(row.1)?;
+            result.push(val);
+        }
         row = match cursor.next(&access) {
             Ok(r) => r,
             Err(_) => break,
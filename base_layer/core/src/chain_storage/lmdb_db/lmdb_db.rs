@@ -23,15 +23,19 @@ use crate::{
     blocks::{block_header::BlockHeader, Block},
     chain_storage::{
         accumulated_data::{BlockAccumulatedData, BlockHeaderAccumulatedData, DeletedBitmap},
+        commitment_history::{unambiguous_spending_kernel, CommitmentHistory},
+        consts::BLOCKCHAIN_DATABASE_REORG_HISTORY_CAPACITY,
         db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation},
         error::{ChainStorageError, OrNotFound},
         lmdb_db::{
             lmdb::{
+                lmdb_clear,
                 lmdb_delete,
                 lmdb_delete_key_value,
                 lmdb_delete_keys_starting_with,
                 lmdb_exists,
                 lmdb_fetch_keys_starting_with,
+                lmdb_fetch_keys_starting_with_paged,
                 lmdb_filter_map_values,
                 lmdb_first_after,
                 lmdb_get,
@@ -39,14 +43,17 @@ use crate::{
                 lmdb_insert,
                 lmdb_insert_dup,
                 lmdb_last,
+                lmdb_last_key,
                 lmdb_len,
                 lmdb_replace,
             },
+            migrations::{self, LMDB_DB_SCHEMA_VERSION},
             TransactionInputRowData,
             TransactionKernelRowData,
             TransactionOutputRowData,
             LMDB_DB_BLOCK_ACCUMULATED_DATA,
             LMDB_DB_BLOCK_HASHES,
+            LMDB_DB_COMMITMENT_HISTORY_INDEX,
             LMDB_DB_HEADERS,
             LMDB_DB_HEADER_ACCUMULATED_DATA,
             LMDB_DB_INPUTS,
@@ -60,6 +67,7 @@ use crate::{
             LMDB_DB_ORPHAN_CHAIN_TIPS,
             LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA,
             LMDB_DB_ORPHAN_PARENT_MAP_INDEX,
+            LMDB_DB_REORGS,
             LMDB_DB_TXOS_HASH_TO_INDEX,
             LMDB_DB_UTXOS,
             LMDB_DB_UTXO_MMR_SIZE_INDEX,
@@ -69,6 +77,7 @@ use crate::{
         HorizonData,
         MmrTree,
         PrunedOutput,
+        Reorg,
     },
     crypto::tari_utilities::hex::to_hex,
     transactions::{
@@ -125,6 +134,9 @@ pub struct LMDBDatabase {
     orphan_header_accumulated_data_db: DatabaseRef,
     orphan_chain_tips_db: DatabaseRef,
     orphan_parent_map_index: DatabaseRef,
+    commitment_history_index: DatabaseRef,
+    is_commitment_history_index_enabled: bool,
+    reorgs_db: DatabaseRef,
     is_mem_metadata_dirty: bool,
     _file_lock: Arc<File>,
 }
@@ -153,17 +165,275 @@ impl LMDBDatabase {
             monero_seed_height_db: get_database(&store, LMDB_DB_MONERO_SEED_HEIGHT)?,
             orphan_chain_tips_db: get_database(&store, LMDB_DB_ORPHAN_CHAIN_TIPS)?,
             orphan_parent_map_index: get_database(&store, LMDB_DB_ORPHAN_PARENT_MAP_INDEX)?,
+            commitment_history_index: get_database(&store, LMDB_DB_COMMITMENT_HISTORY_INDEX)?,
+            is_commitment_history_index_enabled: false,
+            reorgs_db: get_database(&store, LMDB_DB_REORGS)?,
             env,
             env_config: store.env_config(),
             is_mem_metadata_dirty: false,
             _file_lock: Arc::new(file_lock),
         };
+        res.migrate_schema(&store)?;
+        res.is_commitment_history_index_enabled = {
+            let txn = ReadTransaction::new(&*res.env)?;
+            fetch_commitment_history_index_enabled(&txn, &res.metadata_db)?
+        };
         if !res.is_empty()? {
             res.refresh_chain_metadata()?;
         }
         Ok(res)
     }
 
+    /// Returns the schema version recorded in the database, or `None` if the database predates schema versioning
+    pub fn fetch_schema_version(&self) -> Result<Option<u32>, ChainStorageError> {
+        let txn = ReadTransaction::new(&*self.env)?;
+        fetch_schema_version(&txn, &self.metadata_db)
+    }
+
+    pub(super) fn write_schema_version(&mut self, version: u32) -> Result<(), ChainStorageError> {
+        let write_txn =
+            WriteTransaction::new(self.env.clone()).map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        self.set_metadata(
+            &write_txn,
+            MetadataKey::SchemaVersion,
+            MetadataValue::SchemaVersion(version),
+        )?;
+        write_txn
+            .commit()
+            .map_err(|e| ChainStorageError::AccessError(e.to_string()))
+    }
+
+    /// Upgrades a database written with an older schema to the current version. A new database is stamped with the
+    /// current version and a database written by a newer node is refused.
+    fn migrate_schema(&mut self, store: &LMDBStore) -> Result<(), ChainStorageError> {
+        let db_version = match self.fetch_schema_version()? {
+            Some(version) => version,
+            None if self.is_empty()? => return self.write_schema_version(LMDB_DB_SCHEMA_VERSION),
+            None => 0,
+        };
+        if db_version > LMDB_DB_SCHEMA_VERSION {
+            return Err(ChainStorageError::IncompatibleDatabaseVersion {
+                db_version,
+                supported_version: LMDB_DB_SCHEMA_VERSION,
+            });
+        }
+
+        for migration in migrations::pending_migrations(db_version) {
+            info!(
+                target: LOG_TARGET,
+                "Migrating blockchain database to schema version {}: {}", migration.to_version, migration.description
+            );
+            LMDBStore::resize_if_required(&self.env, &self.env_config)?;
+            let write_txn =
+                WriteTransaction::new(self.env.clone()).map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+            (migration.apply)(&write_txn, store)?;
+            self.set_metadata(
+                &write_txn,
+                MetadataKey::SchemaVersion,
+                MetadataValue::SchemaVersion(migration.to_version),
+            )?;
+            write_txn
+                .commit()
+                .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the commitment history index is being maintained
+    pub fn is_commitment_history_index_enabled(&self) -> bool {
+        self.is_commitment_history_index_enabled
+    }
+
+    /// Enables or disables the commitment history index. When the index is enabled for the first time it is built
+    /// from the block bodies already in the database, which can take some time on a large chain. Disabling the index
+    /// removes it. Only blocks that are stored with their full bodies are indexed, so the index is only complete on an
+    /// archival node.
+    pub fn set_commitment_history_index(&mut self, enabled: bool) -> Result<(), ChainStorageError> {
+        if enabled == self.is_commitment_history_index_enabled {
+            return Ok(());
+        }
+
+        if enabled {
+            self.build_commitment_history_index()?;
+        } else {
+            info!(target: LOG_TARGET, "Removing the commitment history index");
+            let write_txn =
+                WriteTransaction::new(self.env.clone()).map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+            lmdb_clear(&write_txn, &self.commitment_history_index)?;
+            self.set_metadata(
+                &write_txn,
+                MetadataKey::CommitmentHistoryIndex,
+                MetadataValue::CommitmentHistoryIndex(false),
+            )?;
+            write_txn
+                .commit()
+                .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        }
+        self.is_commitment_history_index_enabled = enabled;
+        Ok(())
+    }
+
+    // Each block is indexed in its own transaction. The index is only flagged as enabled once every block has been
+    // indexed, so an interrupted build starts again from the beginning the next time the node starts.
+    fn build_commitment_history_index(&mut self) -> Result<(), ChainStorageError> {
+        let timer = Instant::now();
+        if !self.is_empty()? {
+            let tip_height = {
+                let txn = ReadTransaction::new(&*self.env)?;
+                fetch_chain_height(&txn, &self.metadata_db)?
+            };
+            info!(
+                target: LOG_TARGET,
+                "Building the commitment history index for {} block(s)",
+                tip_height + 1
+            );
+            for height in 0..=tip_height {
+                LMDBStore::resize_if_required(&self.env, &self.env_config)?;
+                let write_txn = WriteTransaction::new(self.env.clone())
+                    .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+                self.index_block_commitment_history(&write_txn, height)?;
+                write_txn
+                    .commit()
+                    .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+            }
+        }
+
+        let write_txn =
+            WriteTransaction::new(self.env.clone()).map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        self.set_metadata(
+            &write_txn,
+            MetadataKey::CommitmentHistoryIndex,
+            MetadataValue::CommitmentHistoryIndex(true),
+        )?;
+        write_txn
+            .commit()
+            .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        info!(
+            target: LOG_TARGET,
+            "Commitment history index built in {:.2?}",
+            timer.elapsed()
+        );
+        Ok(())
+    }
+
+    fn index_block_commitment_history(&self, txn: &WriteTransaction<'_>, height: u64) -> Result<(), ChainStorageError> {
+        let header: BlockHeader =
+            lmdb_get(&**txn, &self.headers_db, &height).or_not_found("BlockHeader", "height", height.to_string())?;
+        let block_hash = header.hash();
+        let hash_hex = block_hash.to_hex();
+
+        let kernels: Vec<TransactionKernelRowData> =
+            lmdb_fetch_keys_starting_with(&hash_hex, &**txn, &self.kernels_db)?;
+        let spending_kernel = unambiguous_spending_kernel(kernels.iter().map(|row| &row.kernel));
+
+        let outputs: Vec<TransactionOutputRowData> = lmdb_fetch_keys_starting_with(&hash_hex, &**txn, &self.utxos_db)?;
+        for output in outputs.iter().filter_map(|row| row.output.as_ref()) {
+            self.insert_commitment_history(txn, &output.commitment, height, &block_hash)?;
+        }
+
+        let inputs: Vec<TransactionInputRowData> = lmdb_fetch_keys_starting_with(&hash_hex, &**txn, &self.inputs_db)?;
+        for row in inputs {
+            self.mark_commitment_spent(txn, &row.input.commitment, height, &block_hash, spending_kernel.clone())?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::ptr_arg)]
+    fn insert_commitment_history(
+        &self,
+        txn: &WriteTransaction<'_>,
+        commitment: &Commitment,
+        height: u64,
+        block_hash: &HashOutput,
+    ) -> Result<(), ChainStorageError>
+    {
+        lmdb_replace(
+            txn,
+            &self.commitment_history_index,
+            commitment.as_bytes(),
+            &CommitmentHistory::new(commitment.clone(), height, block_hash.clone()),
+        )
+    }
+
+    #[allow(clippy::ptr_arg)]
+    fn mark_commitment_spent(
+        &self,
+        txn: &WriteTransaction<'_>,
+        commitment: &Commitment,
+        height: u64,
+        block_hash: &HashOutput,
+        spending_kernel: Option<Signature>,
+    ) -> Result<(), ChainStorageError>
+    {
+        // Outputs created in blocks that were never stored with their full bodies (e.g. before the horizon of a pruned
+        // node) are not in the index
+        let history: Option<CommitmentHistory> =
+            lmdb_get(&**txn, &self.commitment_history_index, commitment.as_bytes())?;
+        if let Some(mut history) = history {
+            history.set_spent(height, block_hash.clone(), spending_kernel);
+            lmdb_replace(txn, &self.commitment_history_index, commitment.as_bytes(), &history)?;
+        }
+        Ok(())
+    }
+
+    // Reverses the changes made to the commitment history index when the given block was added
+    #[allow(clippy::ptr_arg)]
+    fn remove_block_commitment_history(
+        &self,
+        txn: &WriteTransaction<'_>,
+        block_hash: &HashOutput,
+        outputs: &[TransactionOutputRowData],
+        inputs: &[TransactionInputRowData],
+    ) -> Result<(), ChainStorageError>
+    {
+        for row in inputs {
+            let key = row.input.commitment.as_bytes();
+            let history: Option<CommitmentHistory> = lmdb_get(&**txn, &self.commitment_history_index, key)?;
+            if let Some(mut history) = history {
+                if history.spent_in_block.as_ref() == Some(block_hash) {
+                    history.clear_spent();
+                    lmdb_replace(txn, &self.commitment_history_index, key, &history)?;
+                }
+            }
+        }
+        for output in outputs.iter().filter_map(|row| row.output.as_ref()) {
+            let key = output.commitment.as_bytes();
+            let history: Option<CommitmentHistory> = lmdb_get(&**txn, &self.commitment_history_index, key)?;
+            if history.map(|h| &h.created_in_block == block_hash).unwrap_or(false) {
+                lmdb_delete(txn, &self.commitment_history_index, key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a reorg to the reorg history. Reorgs are keyed by a big-endian sequence number so that they are
+    /// iterated in the order they occurred, and the oldest reorg is dropped once the history is full.
+    fn insert_reorg(&self, txn: &WriteTransaction<'_>, reorg: &Reorg) -> Result<(), ChainStorageError> {
+        let index = match lmdb_last_key(txn, &self.reorgs_db)? {
+            Some(key) => {
+                let mut buf = [0u8; 8];
+                if key.len() != buf.len() {
+                    return Err(ChainStorageError::CriticalError(format!(
+                        "Invalid reorg history key length {}",
+                        key.len()
+                    )));
+                }
+                buf.copy_from_slice(&key);
+                u64::from_be_bytes(buf) + 1
+            },
+            None => 0,
+        };
+        lmdb_insert(txn, &self.reorgs_db, &index.to_be_bytes(), reorg, "reorgs_db")?;
+
+        if let Some(expired) = index.checked_sub(BLOCKCHAIN_DATABASE_REORG_HISTORY_CAPACITY) {
+            let expired_key = expired.to_be_bytes();
+            if lmdb_exists(txn, &self.reorgs_db, &expired_key)? {
+                lmdb_delete(txn, &self.reorgs_db, &expired_key)?;
+            }
+        }
+        Ok(())
+    }
+
     fn apply_db_transaction(&mut self, txn: DbTransaction) -> Result<(), ChainStorageError> {
         use WriteOperation::*;
         let write_txn =
@@ -219,6 +489,9 @@ impl LMDBDatabase {
                 } => {
                     self.insert_pruned_output(&write_txn, header_hash, output_hash, proof_hash, mmr_position)?;
                 },
+                RestorePrunedOutput { header_hash, output } => {
+                    self.restore_pruned_output(&write_txn, &header_hash, *output)?;
+                },
                 InsertInput {
                     header_hash,
                     input,
@@ -250,7 +523,7 @@ impl LMDBDatabase {
                         &hash_hex,
                     )?;
 
-                    for utxo in rows {
+                    for utxo in &rows {
                         trace!(target: LOG_TARGET, "Deleting UTXO `{}`", to_hex(&utxo.hash));
                         lmdb_delete(&write_txn, &self.txos_hash_to_index_db, utxo.hash.as_slice())?;
                     }
@@ -278,7 +551,14 @@ impl LMDBDatabase {
                         lmdb_delete(&write_txn, &self.kernel_excess_sig_index, excess_sig_key.as_slice())?;
                     }
                     debug!(target: LOG_TARGET, "Deleting Inputs...");
-                    lmdb_delete_keys_starting_with::<TransactionInputRowData>(&write_txn, &self.inputs_db, &hash_hex)?;
+                    let inputs = lmdb_delete_keys_starting_with::<TransactionInputRowData>(
+                        &write_txn,
+                        &self.inputs_db,
+                        &hash_hex,
+                    )?;
+                    if self.is_commitment_history_index_enabled {
+                        self.remove_block_commitment_history(&write_txn, &hash, &rows, &inputs)?;
+                    }
                 },
                 WriteOperation::InsertMoneroSeedHeight(data, height) => {
                     let current_height =
@@ -415,6 +695,9 @@ impl LMDBDatabase {
                         MetadataValue::HorizonData(HorizonData::new(kernel_sum, utxo_sum)),
                     )?;
                 },
+                InsertReorg(reorg) => {
+                    self.insert_reorg(&write_txn, &reorg)?;
+                },
             }
         }
         write_txn
@@ -448,6 +731,38 @@ impl LMDBDatabase {
         Ok(result)
     }
 
+    fn restore_pruned_output(
+        &mut self,
+        txn: &WriteTransaction<'_>,
+        header_hash: &HashOutput,
+        output: TransactionOutput,
+    ) -> Result<(), ChainStorageError>
+    {
+        let output_hash = output.hash();
+        let (_, key) = lmdb_get::<_, (u32, String)>(txn, &self.txos_hash_to_index_db, output_hash.as_slice())
+            .or_not_found("TransactionOutput", "hash", output_hash.to_hex())?;
+        let mut row: TransactionOutputRowData =
+            lmdb_get(txn, &self.utxos_db, key.as_str()).or_not_found("TransactionOutput", "key", key.clone())?;
+        if &row.header_hash != header_hash {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Output {} is not contained in block {}",
+                output_hash.to_hex(),
+                header_hash.to_hex()
+            )));
+        }
+        if row.range_proof_hash != output.proof.hash() {
+            return Err(ChainStorageError::InvalidOperation(format!(
+                "Range proof for output {} does not match the stored range proof hash",
+                output_hash.to_hex()
+            )));
+        }
+        if row.output.is_some() {
+            return Ok(());
+        }
+        row.output = Some(output);
+        lmdb_replace(txn, &self.utxos_db, key.as_str(), &row)
+    }
+
     fn insert_output(
         &mut self,
         txn: &WriteTransaction<'_>,
@@ -765,6 +1080,11 @@ impl LMDBDatabase {
 
         let mut kernel_mmr = MerkleMountainRange::<HashDigest, _>::new(pruned_kernel_set);
 
+        let spending_kernel = if self.is_commitment_history_index_enabled {
+            unambiguous_spending_kernel(&kernels)
+        } else {
+            None
+        };
         for kernel in kernels {
             total_kernel_sum = &total_kernel_sum + &kernel.excess;
             let pos = kernel_mmr.push(kernel.hash())?;
@@ -787,6 +1107,9 @@ impl LMDBDatabase {
                 "Inserting output `{}`",
                 to_hex(&output.commitment.as_bytes())
             );
+            if self.is_commitment_history_index_enabled {
+                self.insert_commitment_history(txn, &output.commitment, header.height, &block_hash)?;
+            }
             self.insert_output(
                 txn,
                 block_hash.clone(),
@@ -811,6 +1134,15 @@ impl LMDBDatabase {
                 "Inserting input `{}`",
                 to_hex(&input.commitment.as_bytes())
             );
+            if self.is_commitment_history_index_enabled {
+                self.mark_commitment_spent(
+                    txn,
+                    &input.commitment,
+                    header.height,
+                    &block_hash,
+                    spending_kernel.clone(),
+                )?;
+            }
             self.insert_input(txn, block_hash.clone(), input, index)?;
         }
         output_mmr.compress();
@@ -921,6 +1253,8 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .add_database(LMDB_DB_MONERO_SEED_HEIGHT, flags)
         .add_database(LMDB_DB_ORPHAN_CHAIN_TIPS, flags)
         .add_database(LMDB_DB_ORPHAN_PARENT_MAP_INDEX, flags | db::DUPSORT)
+        .add_database(LMDB_DB_COMMITMENT_HISTORY_INDEX, flags)
+        .add_database(LMDB_DB_REORGS, flags)
         .build()
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
     LMDBDatabase::new(lmdb_store, file_lock)
@@ -1434,9 +1768,19 @@ impl BlockchainBackend for LMDBDatabase {
     }
 
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
+        self.fetch_outputs_in_block_paged(header_hash, 0, usize::MAX)
+    }
+
+    fn fetch_outputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<PrunedOutput>, ChainStorageError>
+    {
         let txn = ReadTransaction::new(&*self.env)?;
         Ok(
-            lmdb_fetch_keys_starting_with(header_hash.to_hex().as_str(), &txn, &self.utxos_db)?
+            lmdb_fetch_keys_starting_with_paged(header_hash.to_hex().as_str(), &txn, &self.utxos_db, skip, take)?
                 .into_iter()
                 .map(|f: TransactionOutputRowData| match f.output {
                     Some(o) => PrunedOutput::NotPruned { output: o },
@@ -1450,15 +1794,37 @@ impl BlockchainBackend for LMDBDatabase {
     }
 
     fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
+        self.fetch_inputs_in_block_paged(header_hash, 0, usize::MAX)
+    }
+
+    fn fetch_inputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<TransactionInput>, ChainStorageError>
+    {
         let txn = ReadTransaction::new(&*self.env)?;
         Ok(
-            lmdb_fetch_keys_starting_with(header_hash.to_hex().as_str(), &txn, &self.inputs_db)?
+            lmdb_fetch_keys_starting_with_paged(header_hash.to_hex().as_str(), &txn, &self.inputs_db, skip, take)?
                 .into_iter()
                 .map(|f: TransactionInputRowData| f.input)
                 .collect(),
         )
     }
 
+    fn fetch_commitment_history(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<CommitmentHistory>, ChainStorageError>
+    {
+        if !self.is_commitment_history_index_enabled {
+            return Err(ChainStorageError::IndexNotEnabled("commitment history".to_string()));
+        }
+        let txn = ReadTransaction::new(&*self.env)?;
+        lmdb_get(&txn, &self.commitment_history_index, commitment.as_bytes())
+    }
+
     fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
         let txn = ReadTransaction::new(&*self.env)?;
         match tree {
@@ -1653,6 +2019,11 @@ impl BlockchainBackend for LMDBDatabase {
         let txn = ReadTransaction::new(&*self.env)?;
         fetch_horizon_data(&txn, &self.metadata_db)
     }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        let txn = ReadTransaction::new(&*self.env)?;
+        lmdb_filter_map_values(&txn, &self.reorgs_db, |reorg: Reorg| Ok(Some(reorg)))
+    }
 }
 
 // Fetch the chain metadata
@@ -1731,6 +2102,35 @@ fn fetch_accumulated_work(txn: &ConstTransaction<'_>, db: &Database) -> Result<u
     }
 }
 
+// Fetches the schema version from the provided metadata db.
+fn fetch_schema_version(txn: &ConstTransaction<'_>, db: &Database) -> Result<Option<u32>, ChainStorageError> {
+    let k = MetadataKey::SchemaVersion;
+    let val: Option<MetadataValue> = lmdb_get(&txn, &db, &(k as u32))?;
+    match val {
+        Some(MetadataValue::SchemaVersion(version)) => Ok(Some(version)),
+        None => Ok(None),
+        _ => Err(ChainStorageError::ValueNotFound {
+            entity: "ChainMetadata".to_string(),
+            field: "SchemaVersion".to_string(),
+            value: "".to_string(),
+        }),
+    }
+}
+
+// Fetches whether the commitment history index has been built from the provided metadata db.
+fn fetch_commitment_history_index_enabled(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+) -> Result<bool, ChainStorageError>
+{
+    let k = MetadataKey::CommitmentHistoryIndex;
+    let val: Option<MetadataValue> = lmdb_get(&txn, &db, &(k as u32))?;
+    match val {
+        Some(MetadataValue::CommitmentHistoryIndex(enabled)) => Ok(enabled),
+        _ => Ok(false),
+    }
+}
+
 // Fetches the pruning horizon from the provided metadata db.
 fn fetch_pruning_horizon(txn: &ConstTransaction<'_>, db: &Database) -> Result<u64, ChainStorageError> {
     let k = MetadataKey::PruningHorizon;
@@ -1756,6 +2156,8 @@ enum MetadataKey {
     PruningHorizon,
     PrunedHeight,
     HorizonData,
+    SchemaVersion,
+    CommitmentHistoryIndex,
 }
 
 impl fmt::Display for MetadataKey {
@@ -1767,6 +2169,8 @@ impl fmt::Display for MetadataKey {
             MetadataKey::PrunedHeight => f.write_str("Effective pruned height"),
             MetadataKey::BestBlock => f.write_str("Chain tip block hash"),
             MetadataKey::HorizonData => f.write_str("Database info"),
+            MetadataKey::SchemaVersion => f.write_str("Database schema version"),
+            MetadataKey::CommitmentHistoryIndex => f.write_str("Commitment history index"),
         }
     }
 }
@@ -1780,6 +2184,8 @@ enum MetadataValue {
     PruningHorizon(u64),
     PrunedHeight(u64),
     HorizonData(HorizonData),
+    SchemaVersion(u32),
+    CommitmentHistoryIndex(bool),
 }
 
 impl fmt::Display for MetadataValue {
@@ -1791,6 +2197,10 @@ impl fmt::Display for MetadataValue {
             MetadataValue::PrunedHeight(height) => write!(f, "Effective pruned height is {}", height),
             MetadataValue::BestBlock(hash) => write!(f, "Chain tip block hash is {}", hash.to_hex()),
             MetadataValue::HorizonData(_) => write!(f, "Horizon data"),
+            MetadataValue::SchemaVersion(version) => write!(f, "Database schema version is {}", version),
+            MetadataValue::CommitmentHistoryIndex(enabled) => {
+                write!(f, "Commitment history index enabled: {}", enabled)
+            },
         }
     }
 }
@@ -0,0 +1,95 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The blockchain database records the version of its schema in the metadata table. When a node opens a database
+//! written with an older schema, the pending migrations are applied in order to upgrade it in place, each in its own
+//! write transaction together with the new version so that an interrupted upgrade resumes where it stopped.
+//!
+//! Any change to the encoding of a table must bump [LMDB_DB_SCHEMA_VERSION] and add a [Migration] to [MIGRATIONS]
+//! that converts the existing records.
+
+use crate::chain_storage::ChainStorageError;
+use lmdb_zero::WriteTransaction;
+use tari_storage::lmdb_store::LMDBStore;
+
+/// The version of the blockchain database schema written by this node
+pub const LMDB_DB_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades a database from the preceding schema version to `to_version`
+pub(super) struct Migration {
+    pub to_version: u32,
+    pub description: &'static str,
+    pub apply: fn(&WriteTransaction<'_>, &LMDBStore) -> Result<(), ChainStorageError>,
+}
+
+/// Every migration, in ascending version order. Databases created before the schema version was recorded are at
+/// version 0.
+const MIGRATIONS: &[Migration] = &[Migration {
+    to_version: 1,
+    description: "Record the database schema version",
+    apply: |_, _| Ok(()),
+}];
+
+/// The migrations that upgrade a database at `db_version` to the current schema version
+pub(super) fn pending_migrations(db_version: u32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.to_version > db_version)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain_storage::{create_lmdb_database, BlockchainBackend};
+    use std::fs;
+    use tari_storage::lmdb_store::LMDBConfig;
+    use tari_test_utils::paths::create_temporary_data_path;
+
+    #[test]
+    fn migrations_reach_current_version() {
+        let versions = pending_migrations(0).map(|m| m.to_version).collect::<Vec<_>>();
+        assert_eq!(versions, (1..=LMDB_DB_SCHEMA_VERSION).collect::<Vec<_>>());
+        assert_eq!(pending_migrations(LMDB_DB_SCHEMA_VERSION).count(), 0);
+    }
+
+    #[test]
+    fn it_refuses_to_open_a_newer_database() {
+        let path = create_temporary_data_path();
+        {
+            let mut db = create_lmdb_database(&path, LMDBConfig::default()).unwrap();
+            assert!(db.is_empty().unwrap());
+            assert_eq!(db.fetch_schema_version().unwrap(), Some(LMDB_DB_SCHEMA_VERSION));
+            db.write_schema_version(LMDB_DB_SCHEMA_VERSION + 1).unwrap();
+        }
+
+        let result = create_lmdb_database(&path, LMDBConfig::default());
+        fs::remove_dir_all(&path).unwrap();
+        match result {
+            Err(ChainStorageError::IncompatibleDatabaseVersion {
+                db_version,
+                supported_version,
+            }) => {
+                assert_eq!(db_version, LMDB_DB_SCHEMA_VERSION + 1);
+                assert_eq!(supported_version, LMDB_DB_SCHEMA_VERSION);
+            },
+            _ => panic!("Expected the database to be refused"),
+        }
+    }
+}
@@ -23,12 +23,14 @@
 mod lmdb;
 #[allow(clippy::module_inception)]
 mod lmdb_db;
+mod migrations;
 
 use crate::transactions::{
     transaction::{TransactionInput, TransactionKernel, TransactionOutput},
     types::HashOutput,
 };
 pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
+pub use migrations::LMDB_DB_SCHEMA_VERSION;
 use serde::{Deserialize, Serialize};
 
 pub const LMDB_DB_METADATA: &str = "metadata";
@@ -49,6 +51,8 @@ pub const LMDB_DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
 pub const LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
 pub const LMDB_DB_ORPHAN_CHAIN_TIPS: &str = "orphan_chain_tips";
 pub const LMDB_DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
+pub const LMDB_DB_COMMITMENT_HISTORY_INDEX: &str = "commitment_history_index";
+pub const LMDB_DB_REORGS: &str = "reorgs";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct TransactionOutputRowData {
@@ -0,0 +1,43 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use lazy_static::lazy_static;
+use tari_metrics::{HistogramTimer, HistogramVec};
+
+lazy_static! {
+    static ref BLOCK_VALIDATION_TIME: HistogramVec = tari_metrics::register_histogram_vec(
+        "base_node_block_validation_seconds",
+        "The time taken to validate a block, by validation stage",
+        &["stage"],
+    )
+    .expect("metric is registered once");
+}
+
+/// Starts timing the stateless validation of a block that is not yet known to connect to the chain
+pub fn orphan_validation_timer() -> HistogramTimer {
+    BLOCK_VALIDATION_TIME.with_label_values(&["orphan"]).start_timer()
+}
+
+/// Starts timing the validation of a block body against the chain state
+pub fn body_validation_timer() -> HistogramTimer {
+    BLOCK_VALIDATION_TIME.with_label_values(&["body"]).start_timer()
+}
@@ -55,11 +55,17 @@ pub use blockchain_database::{
 mod blockchain_backend;
 pub use blockchain_backend::BlockchainBackend;
 
+mod commitment_history;
+pub(crate) use commitment_history::unambiguous_spending_kernel;
+pub use commitment_history::CommitmentHistory;
+
 mod consts;
 
 mod db_transaction;
 pub use db_transaction::{DbKey, DbTransaction, DbValue, WriteOperation};
 
+mod metrics;
+
 mod mmr_tree;
 pub use mmr_tree::*;
 
@@ -75,6 +81,9 @@ pub use horizon_data::HorizonData;
 mod pruned_output;
 pub use pruned_output::PrunedOutput;
 
+mod reorg;
+pub use reorg::Reorg;
+
 mod lmdb_db;
 pub use lmdb_db::{
     create_lmdb_database,
@@ -86,6 +95,7 @@ pub use lmdb_db::{
     LMDB_DB_METADATA,
     LMDB_DB_MONERO_SEED_HEIGHT,
     LMDB_DB_ORPHANS,
+    LMDB_DB_SCHEMA_VERSION,
     LMDB_DB_UTXOS,
 };
 
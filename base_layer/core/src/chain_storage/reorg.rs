@@ -0,0 +1,92 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{chain_storage::ChainBlock, transactions::types::HashOutput};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+use tari_crypto::tari_utilities::{epoch_time::EpochTime, hex::Hex};
+
+/// A record of a reorg of the main chain, kept in the reorg history of the blockchain database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reorg {
+    /// The height of the last block that the old and new chains have in common
+    pub fork_height: u64,
+    pub prev_height: u64,
+    pub prev_hash: HashOutput,
+    pub new_height: u64,
+    pub new_hash: HashOutput,
+    /// The hashes of the blocks that were removed from the main chain, from the old tip down to the fork
+    pub removed_blocks: Vec<HashOutput>,
+    /// The hashes of the blocks that were added to the main chain, from the fork up to the new tip
+    pub added_blocks: Vec<HashOutput>,
+    /// The local time at which the reorg took place
+    pub local_time: EpochTime,
+}
+
+impl Reorg {
+    /// Creates a reorg record from the removed and added blocks of a `BlockAddResult::ChainReorg`, or from the blocks
+    /// removed by a rewind, in which case `added` is empty. At least one block must have been removed or added.
+    pub fn from_reorged_blocks(removed: &[Arc<ChainBlock>], added: &[Arc<ChainBlock>]) -> Self {
+        let fork_child = added
+            .first()
+            .or_else(|| removed.last())
+            .map(|b| &b.block.header)
+            .expect("a reorg removes or adds at least one block");
+        let fork_height = fork_child.height.saturating_sub(1);
+        let fork_hash = fork_child.prev_hash.clone();
+        let (prev_height, prev_hash) = match removed.first() {
+            Some(old_tip) => (old_tip.block.header.height, old_tip.accumulated_data.hash.clone()),
+            None => (fork_height, fork_hash.clone()),
+        };
+        let (new_height, new_hash) = match added.last() {
+            Some(new_tip) => (new_tip.block.header.height, new_tip.accumulated_data.hash.clone()),
+            None => (fork_height, fork_hash),
+        };
+
+        Self {
+            fork_height,
+            prev_height,
+            prev_hash,
+            new_height,
+            new_hash,
+            removed_blocks: removed.iter().map(|b| b.accumulated_data.hash.clone()).collect(),
+            added_blocks: added.iter().map(|b| b.accumulated_data.hash.clone()).collect(),
+            local_time: EpochTime::now(),
+        }
+    }
+}
+
+impl fmt::Display for Reorg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reorg at fork height {} from #{} ({}) to #{} ({}): {} block(s) removed, {} block(s) added",
+            self.fork_height,
+            self.prev_height,
+            self.prev_hash.to_hex(),
+            self.new_height,
+            self.new_hash.to_hex(),
+            self.removed_blocks.len(),
+            self.added_blocks.len()
+        )
+    }
+}
@@ -22,12 +22,17 @@
 
 use crate::{
     blocks::Block,
-    chain_storage::BlockchainDatabase,
+    chain_storage::{BlockchainDatabase, ChainStorageError},
     tari_utilities::Hashable,
     test_helpers::{
-        blockchain::{create_new_blockchain, TempDatabase},
+        blockchain::{create_new_blockchain, create_new_blockchain_with_backend, create_test_db, TempDatabase},
         create_block,
     },
+    transactions::{
+        helpers::create_test_kernel,
+        transaction::{Transaction, TransactionInput},
+        types::BlindingFactor,
+    },
 };
 use std::sync::Arc;
 use tari_test_utils::unpack_enum;
@@ -322,3 +327,46 @@ mod fetch_block_hashes_from_header_tip {
         assert_eq!(&hashes[5], genesis.hash());
     }
 }
+
+mod fetch_commitment_history {
+    use super::*;
+
+    #[test]
+    fn it_errors_if_the_index_is_not_enabled() {
+        let db = setup();
+        let blocks = add_many_chained_blocks(1, &db);
+        let commitment = blocks[0].body.outputs()[0].commitment.clone();
+        let err = db.fetch_commitment_history(commitment).unwrap_err();
+        unpack_enum!(ChainStorageError::IndexNotEnabled(index) = err);
+        assert_eq!(index, "commitment history");
+    }
+
+    #[test]
+    fn it_records_where_an_output_was_created_and_spent() {
+        let mut backend = create_test_db();
+        backend.set_commitment_history_index(true).unwrap();
+        let db = create_new_blockchain_with_backend(backend);
+        let blocks = add_many_chained_blocks(1, &db);
+        let output = blocks[0].body.outputs()[0].clone();
+
+        let history = db.fetch_commitment_history(output.commitment.clone()).unwrap().unwrap();
+        assert_eq!(history.created_height, 1);
+        assert_eq!(history.created_in_block, blocks[0].hash());
+        assert!(!history.is_spent());
+
+        let kernel = create_test_kernel(0.into(), 0);
+        let input = TransactionInput::new(output.features.clone(), output.commitment.clone());
+        let tx = Transaction::new(vec![input], vec![], vec![kernel.clone()], BlindingFactor::default());
+        let mut block = create_block(1, 2, vec![tx]);
+        block.header.prev_hash = blocks[0].hash();
+        block.header.output_mmr_size = blocks[0].header.output_mmr_size;
+        block.header.kernel_mmr_size = blocks[0].header.kernel_mmr_size + 1;
+        db.add_block(Arc::new(block.clone())).unwrap().assert_added();
+
+        let history = db.fetch_commitment_history(output.commitment).unwrap().unwrap();
+        assert_eq!(history.created_height, 1);
+        assert_eq!(history.spent_height, Some(2));
+        assert_eq!(history.spent_in_block, Some(block.hash()));
+        assert_eq!(history.spending_kernel, Some(kernel.excess_sig));
+    }
+}
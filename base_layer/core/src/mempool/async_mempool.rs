@@ -22,7 +22,15 @@
 
 use crate::{
     blocks::Block,
-    mempool::{error::MempoolError, Mempool, StateResponse, StatsResponse, TxStorageResponse},
+    mempool::{
+        error::MempoolError,
+        BlockTemplatePolicy,
+        Mempool,
+        StateResponse,
+        StatsResponse,
+        TemplateSelection,
+        TxStorageResponse,
+    },
     transactions::{transaction::Transaction, types::Signature},
 };
 use std::sync::Arc;
@@ -63,6 +71,8 @@ make_async!(process_published_block(published_block: Arc<Block>) -> ());
 make_async!(process_reorg(removed_blocks: Vec<Arc<Block>>, new_blocks: Vec<Arc<Block>>) -> ());
 make_async!(snapshot() -> Vec<Arc<Transaction>>);
 make_async!(retrieve(total_weight: u64) -> Vec<Arc<Transaction>>);
+make_async!(retrieve_for_template(total_weight: u64, policy: Option<BlockTemplatePolicy>) -> TemplateSelection);
+make_async!(fee_per_gram_distribution() -> Vec<(f64, u64)>);
 make_async!(has_tx_with_excess_sig(excess_sig: Signature) -> TxStorageResponse);
 make_async!(stats() -> StatsResponse);
 make_async!(state() -> StateResponse);
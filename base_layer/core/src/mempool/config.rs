@@ -20,16 +20,24 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::mempool::{consts, reorg_pool::ReorgPoolConfig, unconfirmed_pool::UnconfirmedPoolConfig};
+use crate::mempool::{
+    consts,
+    reorg_pool::ReorgPoolConfig,
+    template_policy::BlockTemplatePolicy,
+    unconfirmed_pool::UnconfirmedPoolConfig,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tari_common::{configuration::seconds, NetworkConfigPath};
 
 /// Configuration for the Mempool.
-#[derive(Clone, Copy, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct MempoolConfig {
     pub unconfirmed_pool: UnconfirmedPoolConfig,
     pub reorg_pool: ReorgPoolConfig,
+    /// The policy used to select transactions for new block templates when a request does not provide its own
+    #[serde(default)]
+    pub block_template_policy: BlockTemplatePolicy,
 }
 
 impl Default for MempoolConfig {
@@ -37,6 +45,7 @@ impl Default for MempoolConfig {
         Self {
             unconfirmed_pool: UnconfirmedPoolConfig::default(),
             reorg_pool: ReorgPoolConfig::default(),
+            block_template_policy: BlockTemplatePolicy::default(),
         }
     }
 }
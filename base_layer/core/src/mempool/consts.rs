@@ -28,6 +28,11 @@ pub const MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY: usize = 40_000;
 /// skipping over large transactions are performed in an attempt to fit more transactions into the remaining space.
 pub const MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT: usize = 20;
 
+/// The number of transaction events that are buffered for each subscriber to the Mempool transaction events. A full
+/// block holds at most 1,147 of the smallest possible transactions, this leaves room for the transactions that are
+/// discarded by the same block.
+pub const MEMPOOL_TRANSACTION_EVENT_CHANNEL_CAPACITY: usize = 2_000;
+
 /// The maximum number of transactions that can be stored in the Reorg pool
 pub const MEMPOOL_REORG_POOL_STORAGE_CAPACITY: usize = 5_000;
 /// The time-to-live duration used for transactions stored in the ReorgPool
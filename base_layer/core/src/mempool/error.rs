@@ -46,4 +46,8 @@ pub enum MempoolError {
     BackendError(String),
     #[error("Internal reply channel error: `{0}`")]
     TransportChannelError(#[from] TransportChannelError),
+    #[error("Mempool persistence IO error: `{0}`")]
+    PersistenceIoError(#[from] std::io::Error),
+    #[error("Mempool persistence file could not be (de)serialized: `{0}`")]
+    PersistenceSerializationError(String),
 }
@@ -23,9 +23,12 @@
 use crate::{
     blocks::Block,
     mempool::{
+        consts::MEMPOOL_TRANSACTION_EVENT_CHANNEL_CAPACITY,
         error::MempoolError,
         mempool_storage::MempoolStorage,
+        template_policy::{BlockTemplatePolicy, TemplateSelection},
         MempoolConfig,
+        MempoolTransactionEvent,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -34,6 +37,7 @@ use crate::{
     validation::MempoolTransactionValidation,
 };
 use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
 
 /// The Mempool consists of an Unconfirmed Transaction Pool, Pending Pool, Orphan Pool and Reorg Pool and is responsible
 /// for managing and maintaining all unconfirmed transactions have not yet been included in a block, and transactions
@@ -41,16 +45,29 @@ use std::sync::{Arc, RwLock};
 #[derive(Clone)]
 pub struct Mempool {
     pool_storage: Arc<RwLock<MempoolStorage>>,
+    transaction_event_publisher: broadcast::Sender<MempoolTransactionEvent>,
 }
 
 impl Mempool {
     /// Create a new Mempool with an UnconfirmedPool, OrphanPool, PendingPool and ReOrgPool.
     pub fn new(config: MempoolConfig, validator: Arc<dyn MempoolTransactionValidation>) -> Self {
+        let (transaction_event_publisher, _) = broadcast::channel(MEMPOOL_TRANSACTION_EVENT_CHANNEL_CAPACITY);
         Self {
-            pool_storage: Arc::new(RwLock::new(MempoolStorage::new(config, validator))),
+            pool_storage: Arc::new(RwLock::new(MempoolStorage::new(
+                config,
+                validator,
+                transaction_event_publisher.clone(),
+            ))),
+            transaction_event_publisher,
         }
     }
 
+    /// Returns the publisher of the transactions that are added to and removed from the unconfirmed pool. Events are
+    /// published while the pool is updated, so that subscribers see every change in the order it was made.
+    pub fn get_transaction_event_publisher(&self) -> broadcast::Sender<MempoolTransactionEvent> {
+        self.transaction_event_publisher.clone()
+    }
+
     /// Insert an unconfirmed transaction into the Mempool. The transaction *MUST* have passed through the validation
     /// pipeline already and will thus always be internally consistent by this stage
     pub fn insert(&self, tx: Arc<Transaction>) -> Result<TxStorageResponse, MempoolError> {
@@ -100,6 +117,29 @@ impl Mempool {
             .retrieve(total_weight)
     }
 
+    /// Selects the transactions for a new block template up to a given weight according to the provided policy, or
+    /// the configured policy if none is provided, and reports the transactions that were left out.
+    pub fn retrieve_for_template(
+        &self,
+        total_weight: u64,
+        policy: Option<BlockTemplatePolicy>,
+    ) -> Result<TemplateSelection, MempoolError>
+    {
+        self.pool_storage
+            .read()
+            .map_err(|e| MempoolError::BackendError(e.to_string()))?
+            .retrieve_for_template(total_weight, policy)
+    }
+
+    /// Returns the fee per gram and weight of the unconfirmed transactions, ordered from the highest to the lowest
+    /// priority.
+    pub fn fee_per_gram_distribution(&self) -> Result<Vec<(f64, u64)>, MempoolError> {
+        self.pool_storage
+            .read()
+            .map_err(|e| MempoolError::BackendError(e.to_string()))?
+            .fee_per_gram_distribution()
+    }
+
     /// Check if the specified transaction is stored in the Mempool.
     pub fn has_tx_with_excess_sig(&self, excess_sig: Signature) -> Result<TxStorageResponse, MempoolError> {
         self.pool_storage
@@ -25,8 +25,10 @@ use crate::{
     mempool::{
         error::MempoolError,
         reorg_pool::ReorgPool,
-        unconfirmed_pool::UnconfirmedPool,
+        template_policy::{BlockTemplatePolicy, TemplateSelection},
+        unconfirmed_pool::{UnconfirmedPool, UnconfirmedPoolError},
         MempoolConfig,
+        MempoolTransactionEvent,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -37,6 +39,7 @@ use crate::{
 use log::*;
 use std::sync::Arc;
 use tari_crypto::tari_utilities::{hex::Hex, Hashable};
+use tokio::sync::broadcast;
 
 pub const LOG_TARGET: &str = "c::mp::mempool";
 
@@ -47,15 +50,26 @@ pub struct MempoolStorage {
     unconfirmed_pool: UnconfirmedPool,
     reorg_pool: ReorgPool,
     validator: Arc<dyn MempoolTransactionValidation>,
+    block_template_policy: BlockTemplatePolicy,
 }
 
 impl MempoolStorage {
-    /// Create a new Mempool with an UnconfirmedPool, OrphanPool, PendingPool and ReOrgPool.
-    pub fn new(config: MempoolConfig, validators: Arc<dyn MempoolTransactionValidation>) -> Self {
+    /// Create a new Mempool with an UnconfirmedPool, OrphanPool, PendingPool and ReOrgPool. The transactions added to
+    /// and removed from the UnconfirmedPool are published on the provided transaction event publisher.
+    pub fn new(
+        config: MempoolConfig,
+        validators: Arc<dyn MempoolTransactionValidation>,
+        transaction_event_publisher: broadcast::Sender<MempoolTransactionEvent>,
+    ) -> Self
+    {
         Self {
-            unconfirmed_pool: UnconfirmedPool::new(config.unconfirmed_pool),
+            unconfirmed_pool: UnconfirmedPool::with_event_publisher(
+                config.unconfirmed_pool,
+                transaction_event_publisher,
+            ),
             reorg_pool: ReorgPool::new(config.reorg_pool),
             validator: validators,
+            block_template_policy: config.block_template_policy,
         }
     }
 
@@ -72,10 +86,23 @@ impl MempoolStorage {
                 .unwrap_or_else(|| "None".into())
         );
 
-        match self.validator.validate(&tx) {
-            Ok(()) => {
-                self.unconfirmed_pool.insert(tx)?;
-                Ok(TxStorageResponse::UnconfirmedPool)
+        // A transaction that spends the outputs of unconfirmed transactions is validated as part of the package it
+        // forms with its unconfirmed ancestors, so that the outputs it spends are known to the validators
+        let validation_result = match self.unconfirmed_pool.create_package(&tx) {
+            Some(package) => self.validator.validate(&package),
+            None => self.validator.validate(&tx),
+        };
+        match validation_result {
+            Ok(()) => match self.unconfirmed_pool.insert(tx) {
+                Ok(()) => Ok(TxStorageResponse::UnconfirmedPool),
+                Err(UnconfirmedPoolError::ConflictingTransaction) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Transaction conflicts with unconfirmed transactions that it does not replace"
+                    );
+                    Ok(TxStorageResponse::NotStoredAlreadySpent)
+                },
+                Err(e) => Err(e.into()),
             },
             Err(ValidationError::UnknownInputs) => {
                 warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
@@ -196,6 +223,24 @@ impl MempoolStorage {
         Ok(self.unconfirmed_pool.highest_priority_txs(total_weight)?)
     }
 
+    /// Selects the transactions for a new block template up to a given weight, using the provided policy or the
+    /// configured policy if none is provided. The transactions that were left out are reported with the reason.
+    pub fn retrieve_for_template(
+        &self,
+        total_weight: u64,
+        policy: Option<BlockTemplatePolicy>,
+    ) -> Result<TemplateSelection, MempoolError>
+    {
+        let policy = policy.as_ref().unwrap_or(&self.block_template_policy);
+        Ok(self.unconfirmed_pool.select_template_txs(total_weight, policy)?)
+    }
+
+    /// Returns the fee per gram and weight of the unconfirmed transactions, ordered from the highest to the lowest
+    /// priority.
+    pub fn fee_per_gram_distribution(&self) -> Result<Vec<(f64, u64)>, MempoolError> {
+        Ok(self.unconfirmed_pool.fee_per_gram_distribution())
+    }
+
     /// Check if the specified transaction is stored in the Mempool.
     pub fn has_tx_with_excess_sig(&self, excess_sig: Signature) -> Result<TxStorageResponse, MempoolError> {
         if self.unconfirmed_pool.has_tx_with_excess_sig(&excess_sig) {
@@ -35,6 +35,10 @@ mod mempool;
 #[cfg(feature = "base_node")]
 mod mempool_storage;
 #[cfg(feature = "base_node")]
+mod persistence;
+#[cfg(feature = "base_node")]
+pub use persistence::MempoolPersistence;
+#[cfg(feature = "base_node")]
 mod priority;
 #[cfg(feature = "base_node")]
 mod reorg_pool;
@@ -45,6 +49,10 @@ pub use rpc::create_mempool_rpc_service;
 #[cfg(feature = "base_node")]
 pub use rpc::{MempoolRpcClient, MempoolRpcServer, MempoolRpcService, MempoolService};
 #[cfg(feature = "base_node")]
+mod template_policy;
+#[cfg(feature = "base_node")]
+pub use template_policy::{BlockTemplatePolicy, ExcludedTransaction, ExclusionReason, TemplateSelection};
+#[cfg(feature = "base_node")]
 mod unconfirmed_pool;
 
 // public modules
@@ -75,6 +83,7 @@ pub use sync_protocol::MempoolSyncInitializer;
 use crate::transactions::{transaction::Transaction, types::Signature};
 use core::fmt::{Display, Error, Formatter};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tari_crypto::tari_utilities::hex::Hex;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -160,3 +169,30 @@ impl Display for TxStorageResponse {
 pub enum MempoolStateEvent {
     Updated,
 }
+
+/// Events that are published by the Mempool as transactions enter and leave the unconfirmed pool
+#[derive(Debug, Clone)]
+pub enum MempoolTransactionEvent {
+    /// A transaction was added to the unconfirmed pool
+    Added(Arc<Transaction>),
+    /// A transaction left the unconfirmed pool
+    Removed(Arc<Transaction>, TransactionRemovalReason),
+}
+
+/// The reason a transaction was removed from the unconfirmed pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionRemovalReason {
+    /// The transaction was included in a block added to the main chain
+    Mined,
+    /// The transaction was evicted, or became invalid, double spent or otherwise unminable
+    Discarded,
+}
+
+impl Display for TransactionRemovalReason {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            TransactionRemovalReason::Mined => fmt.write_str("Mined"),
+            TransactionRemovalReason::Discarded => fmt.write_str("Discarded"),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    mempool::{error::MempoolError, Mempool, TxStorageResponse},
+    transactions::transaction::Transaction,
+};
+use futures::FutureExt;
+use log::*;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::{task, time};
+
+const LOG_TARGET: &str = "c::mp::persistence";
+
+/// Incremented whenever the layout of the persisted mempool file changes
+const PERSISTENCE_VERSION: u8 = 1;
+
+/// Saves the unconfirmed pool of the mempool to a file periodically and on shutdown, so that transactions are not lost
+/// when the base node restarts.
+pub struct MempoolPersistence {
+    mempool: Mempool,
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl MempoolPersistence {
+    pub fn new(mempool: Mempool, path: PathBuf, interval: Duration) -> Self {
+        Self {
+            mempool,
+            path,
+            interval,
+        }
+    }
+
+    /// Re-validates and re-inserts the transactions saved in the persistence file, returning the number of
+    /// transactions that were restored. Transactions that are no longer valid or that fail to be inserted are dropped.
+    pub fn restore(&self) -> Result<usize, MempoolError> {
+        let transactions = match load_transactions(&self.path) {
+            Ok(txs) => txs,
+            Err(MempoolError::PersistenceIoError(err)) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        let num_saved = transactions.len();
+
+        // A transaction that spends the outputs of another unconfirmed transaction can only be inserted once its
+        // parent is in the pool, so orphans are retried until no more transactions can be inserted
+        let mut pending = transactions.into_iter().map(Arc::new).collect::<Vec<_>>();
+        let mut num_restored = 0;
+        let mut num_rejected = 0;
+        loop {
+            let num_pending = pending.len();
+            let mut orphans = Vec::new();
+            for tx in pending {
+                match self.mempool.insert(tx.clone()) {
+                    Ok(TxStorageResponse::UnconfirmedPool) => num_restored += 1,
+                    Ok(TxStorageResponse::NotStoredOrphan) => orphans.push(tx),
+                    Ok(_) => num_rejected += 1,
+                    Err(err) => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Failed to restore saved mempool transaction: {}", err
+                        );
+                        num_rejected += 1;
+                    },
+                }
+            }
+            if orphans.is_empty() || orphans.len() == num_pending {
+                num_rejected += orphans.len();
+                break;
+            }
+            pending = orphans;
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "Restored {} of {} saved mempool transaction(s) from '{}', {} rejected",
+            num_restored,
+            num_saved,
+            self.path.display(),
+            num_rejected
+        );
+        Ok(num_restored)
+    }
+
+    /// Writes the current unconfirmed pool to the persistence file, returning the number of transactions saved
+    pub fn save(&self) -> Result<usize, MempoolError> {
+        let transactions = self.mempool.snapshot()?;
+        save_transactions(&self.path, &transactions)?;
+        debug!(
+            target: LOG_TARGET,
+            "Saved {} mempool transaction(s) to '{}'",
+            transactions.len(),
+            self.path.display()
+        );
+        Ok(transactions.len())
+    }
+
+    /// Saves the mempool every `interval` until the shutdown signal is triggered, and once more before exiting
+    pub async fn run(self, mut shutdown_signal: ShutdownSignal) {
+        let persistence = Arc::new(self);
+        loop {
+            let mut delay = time::delay_for(persistence.interval).fuse();
+            let is_shutdown = futures::select! {
+                () = delay => false,
+                _ = shutdown_signal => true,
+            };
+
+            let p = persistence.clone();
+            match task::spawn_blocking(move || p.save()).await {
+                Ok(Ok(_)) => {},
+                Ok(Err(err)) => warn!(target: LOG_TARGET, "Failed to save mempool transactions: {}", err),
+                Err(err) => warn!(target: LOG_TARGET, "Mempool persistence task failed: {}", err),
+            }
+
+            if is_shutdown {
+                info!(target: LOG_TARGET, "Mempool persistence shutting down");
+                break;
+            }
+        }
+    }
+}
+
+/// Atomically replaces the file at `path` with the given transactions
+fn save_transactions(path: &Path, transactions: &[Arc<Transaction>]) -> Result<(), MempoolError> {
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        let transactions = transactions.iter().map(|tx| &**tx).collect::<Vec<_>>();
+        bincode::serialize_into(&mut writer, &(PERSISTENCE_VERSION, transactions))
+            .map_err(|err| MempoolError::PersistenceSerializationError(err.to_string()))?;
+        writer.flush()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn load_transactions(path: &Path) -> Result<Vec<Transaction>, MempoolError> {
+    let reader = BufReader::new(File::open(path)?);
+    let (version, transactions): (u8, Vec<Transaction>) = bincode::deserialize_from(reader)
+        .map_err(|err| MempoolError::PersistenceSerializationError(err.to_string()))?;
+    if version != PERSISTENCE_VERSION {
+        return Err(MempoolError::PersistenceSerializationError(format!(
+            "Unsupported mempool persistence version {}",
+            version
+        )));
+    }
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{mempool::MempoolConfig, transactions::tari_amount::MicroTari, tx, validation::mocks::MockValidator};
+
+    fn create_mempool(validator: MockValidator) -> Mempool {
+        Mempool::new(MempoolConfig::default(), Arc::new(validator))
+    }
+
+    #[test]
+    fn it_restores_saved_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mempool.dat");
+        let mempool = create_mempool(MockValidator::new(true));
+        for _ in 0..3 {
+            let tx = tx!(MicroTari(10_000), fee: MicroTari(50), inputs: 2, outputs: 1).0;
+            mempool.insert(Arc::new(tx)).unwrap();
+        }
+
+        let persistence = MempoolPersistence::new(mempool, path.clone(), Duration::from_secs(60));
+        assert_eq!(persistence.save().unwrap(), 3);
+
+        let mempool = create_mempool(MockValidator::new(true));
+        let persistence = MempoolPersistence::new(mempool.clone(), path, Duration::from_secs(60));
+        assert_eq!(persistence.restore().unwrap(), 3);
+        assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 3);
+    }
+
+    #[test]
+    fn it_drops_transactions_that_are_no_longer_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mempool.dat");
+        let mempool = create_mempool(MockValidator::new(true));
+        let tx = tx!(MicroTari(10_000), fee: MicroTari(50), inputs: 2, outputs: 1).0;
+        mempool.insert(Arc::new(tx)).unwrap();
+        MempoolPersistence::new(mempool, path.clone(), Duration::from_secs(60))
+            .save()
+            .unwrap();
+
+        let mempool = create_mempool(MockValidator::new(false));
+        let persistence = MempoolPersistence::new(mempool.clone(), path, Duration::from_secs(60));
+        assert_eq!(persistence.restore().unwrap(), 0);
+        assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 0);
+    }
+
+    #[test]
+    fn it_continues_restoring_after_a_transaction_fails_to_insert() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mempool.dat");
+        let tx = tx!(MicroTari(10_000), fee: MicroTari(50), inputs: 2, outputs: 1).0;
+        let no_kernels = Transaction::new(
+            tx.body.inputs().clone(),
+            tx.body.outputs().clone(),
+            Vec::new(),
+            tx.offset.clone(),
+        );
+        let transactions = vec![
+            Arc::new(no_kernels),
+            Arc::new(tx),
+            Arc::new(tx!(MicroTari(10_000), fee: MicroTari(50), inputs: 2, outputs: 1).0),
+        ];
+        save_transactions(&path, &transactions).unwrap();
+
+        let mempool = create_mempool(MockValidator::new(true));
+        let persistence = MempoolPersistence::new(mempool.clone(), path, Duration::from_secs(60));
+        assert_eq!(persistence.restore().unwrap(), 2);
+        assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 2);
+    }
+
+    #[test]
+    fn it_restores_nothing_if_there_is_no_saved_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mempool = create_mempool(MockValidator::new(true));
+        let persistence = MempoolPersistence::new(mempool, dir.path().join("mempool.dat"), Duration::from_secs(60));
+        assert_eq!(persistence.restore().unwrap(), 0);
+    }
+}
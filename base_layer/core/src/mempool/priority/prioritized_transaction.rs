@@ -20,7 +20,10 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::{mempool::priority::PriorityError, transactions::transaction::Transaction};
+use crate::{
+    mempool::priority::PriorityError,
+    transactions::{tari_amount::MicroTari, transaction::Transaction},
+};
 use std::{convert::TryFrom, sync::Arc};
 use tari_crypto::tari_utilities::message_format::MessageFormat;
 
@@ -33,7 +36,13 @@ pub struct FeePriority(Vec<u8>);
 impl FeePriority {
     pub fn try_from(transaction: &Transaction) -> Result<Self, PriorityError> {
         // The weights have been normalised, so the fee priority is now equal to the fee per gram ± a few pct points
-        let fee_per_byte = (transaction.calculate_ave_fee_per_gram() * 1000.0) as usize; // Include 3 decimal places before flooring
+        Self::with_fee_per_gram(transaction, transaction.calculate_ave_fee_per_gram())
+    }
+
+    /// Create the priority of a transaction using the provided fee per gram rather than the fee per gram of the
+    /// transaction on its own, this is used to rank a transaction by the fee rate of its package.
+    pub fn with_fee_per_gram(transaction: &Transaction, fee_per_gram: f64) -> Result<Self, PriorityError> {
+        let fee_per_byte = (fee_per_gram * 1000.0) as usize; // Include 3 decimal places before flooring
         let mut fee_priority = fee_per_byte.to_binary()?;
         fee_priority.reverse(); // Requires Big-endian for BtreeMap sorting
 
@@ -58,6 +67,28 @@ pub struct PrioritizedTransaction {
     pub transaction: Arc<Transaction>,
     pub priority: FeePriority,
     pub weight: u64,
+    /// The fee per gram the transaction is ranked by
+    pub fee_per_gram: f64,
+}
+
+impl PrioritizedTransaction {
+    /// Create a prioritized transaction that is ranked by the fee rate of its package. The package consists of the
+    /// transaction and all of its unconfirmed ancestors, with package_fee and package_weight being their combined fees
+    /// and weights.
+    pub fn with_package(
+        transaction: Arc<Transaction>,
+        package_fee: MicroTari,
+        package_weight: u64,
+    ) -> Result<Self, PriorityError>
+    {
+        let package_fee_per_gram = (package_fee.0 as f64) / package_weight as f64;
+        Ok(Self {
+            priority: FeePriority::with_fee_per_gram(&transaction, package_fee_per_gram)?,
+            weight: transaction.calculate_weight(),
+            fee_per_gram: package_fee_per_gram,
+            transaction,
+        })
+    }
 }
 
 impl TryFrom<Transaction> for PrioritizedTransaction {
@@ -67,6 +98,7 @@ impl TryFrom<Transaction> for PrioritizedTransaction {
         Ok(Self {
             priority: FeePriority::try_from(&transaction)?,
             weight: transaction.calculate_weight(),
+            fee_per_gram: transaction.calculate_ave_fee_per_gram(),
             transaction: Arc::new(transaction),
         })
     }
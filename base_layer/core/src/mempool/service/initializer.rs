@@ -155,8 +155,11 @@ impl ServiceInitializer for MempoolServiceInitializer {
         let (mempool_state_event_publisher, _) = broadcast::channel(100);
         let outbound_mp_interface =
             OutboundMempoolServiceInterface::new(outbound_request_sender_service, outbound_tx_sender);
-        let local_mp_interface =
-            LocalMempoolService::new(local_request_sender_service, mempool_state_event_publisher.clone());
+        let local_mp_interface = LocalMempoolService::new(
+            local_request_sender_service,
+            mempool_state_event_publisher.clone(),
+            self.mempool.get_transaction_event_publisher(),
+        );
         let config = self.config;
         let inbound_handlers = MempoolInboundHandlers::new(
             mempool_state_event_publisher,
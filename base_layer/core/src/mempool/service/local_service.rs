@@ -24,6 +24,7 @@ use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse, MempoolServiceError},
         MempoolStateEvent,
+        MempoolTransactionEvent,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -46,6 +47,7 @@ pub type LocalMempoolRequester = SenderService<MempoolRequest, Result<MempoolRes
 pub struct LocalMempoolService {
     request_sender: LocalMempoolRequester,
     mempool_state_event_stream: broadcast::Sender<MempoolStateEvent>,
+    mempool_transaction_event_stream: broadcast::Sender<MempoolTransactionEvent>,
 }
 
 impl LocalMempoolService {
@@ -58,11 +60,13 @@ impl LocalMempoolService {
     pub fn new(
         request_sender: LocalMempoolRequester,
         mempool_state_event_stream: broadcast::Sender<MempoolStateEvent>,
+        mempool_transaction_event_stream: broadcast::Sender<MempoolTransactionEvent>,
     ) -> Self
     {
         LocalMempoolService {
             request_sender,
             mempool_state_event_stream,
+            mempool_transaction_event_stream,
         }
     }
 
@@ -70,6 +74,11 @@ impl LocalMempoolService {
         self.mempool_state_event_stream.subscribe()
     }
 
+    /// Returns a stream of the transactions that are added to and removed from the unconfirmed pool
+    pub fn get_mempool_transaction_event_stream(&self) -> broadcast::Receiver<MempoolTransactionEvent> {
+        self.mempool_transaction_event_stream.subscribe()
+    }
+
     /// Returns a future that resolves to the current mempool statistics
     pub async fn get_mempool_stats(&mut self) -> Result<StatsResponse, MempoolServiceError> {
         match self.request_sender.call(MempoolRequest::GetStats).await?? {
@@ -152,8 +161,9 @@ mod test {
     #[tokio_macros::test]
     async fn mempool_stats() {
         let (event_publisher, _) = broadcast::channel(100);
+        let (transaction_event_publisher, _) = broadcast::channel(100);
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx, event_publisher);
+        let mut service = LocalMempoolService::new(tx, event_publisher, transaction_event_publisher);
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
         let stats = stats.expect("get_mempool_stats should have succeeded");
@@ -163,8 +173,9 @@ mod test {
     #[tokio_macros::test]
     async fn mempool_stats_from_multiple() {
         let (event_publisher, _) = broadcast::channel(100);
+        let (transaction_event_publisher, _) = broadcast::channel(100);
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx, event_publisher);
+        let mut service = LocalMempoolService::new(tx, event_publisher, transaction_event_publisher);
         let mut service2 = service.clone();
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
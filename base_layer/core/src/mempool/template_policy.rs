@@ -0,0 +1,89 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::transactions::{tari_amount::MicroTari, transaction::Transaction, types::Signature};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Error, Formatter},
+    sync::Arc,
+};
+
+/// The policy used to select the unconfirmed transactions that are included in a new block template.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlockTemplatePolicy {
+    /// Transactions, together with their unconfirmed ancestors, that pay less than this fee per gram are left out
+    pub min_fee_per_gram: MicroTari,
+    /// The weight that is kept free for the transactions in `always_include`, other transactions can only use the
+    /// remaining weight of the template
+    pub reserved_priority_weight: u64,
+    /// The excess signatures of transactions that are selected ahead of all other transactions, regardless of their
+    /// fee
+    pub always_include: Vec<Signature>,
+    /// The excess signatures of transactions that are never selected, this takes precedence over `always_include`
+    pub always_exclude: Vec<Signature>,
+}
+
+/// The reason an unconfirmed transaction was left out of a block template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExclusionReason {
+    /// The transaction is in the exclude list of the policy
+    ExcludedByPolicy,
+    /// One of the unconfirmed ancestors of the transaction is in the exclude list of the policy
+    AncestorExcluded,
+    /// The fee per gram of the transaction and its unconfirmed ancestors is below the policy minimum
+    BelowMinimumFee,
+    /// There was not enough weight left in the template for the transaction and its unconfirmed ancestors
+    InsufficientWeight,
+    /// The transaction spends an input that is already spent by a selected transaction
+    DoubleSpend,
+    /// The transaction is in the include list of the policy but is not in the unconfirmed pool
+    NotFound,
+}
+
+impl Display for ExclusionReason {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        use ExclusionReason::*;
+        match self {
+            ExcludedByPolicy => write!(f, "Excluded by policy"),
+            AncestorExcluded => write!(f, "Unconfirmed ancestor excluded by policy"),
+            BelowMinimumFee => write!(f, "Fee per gram below policy minimum"),
+            InsufficientWeight => write!(f, "Insufficient weight left in template"),
+            DoubleSpend => write!(f, "Double spend of a selected transaction"),
+            NotFound => write!(f, "Not found in unconfirmed pool"),
+        }
+    }
+}
+
+/// An unconfirmed transaction that was left out of a block template.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExcludedTransaction {
+    pub excess_sig: Signature,
+    pub reason: ExclusionReason,
+}
+
+/// The transactions selected for a block template, together with the transactions that were left out.
+#[derive(Debug, Default)]
+pub struct TemplateSelection {
+    pub transactions: Vec<Arc<Transaction>>,
+    pub excluded: Vec<ExcludedTransaction>,
+}
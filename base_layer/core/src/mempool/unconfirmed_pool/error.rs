@@ -31,4 +31,6 @@ pub enum UnconfirmedPoolError {
     PriorityError(#[from] PriorityError),
     #[error("Transaction has no kernels")]
     TransactionNoKernels,
+    #[error("Transaction conflicts with stored transactions without paying enough to replace them")]
+    ConflictingTransaction,
 }
@@ -25,18 +25,25 @@ use crate::{
     mempool::{
         consts::{MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY, MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT},
         priority::{FeePriority, PrioritizedTransaction},
+        template_policy::{BlockTemplatePolicy, ExcludedTransaction, ExclusionReason, TemplateSelection},
         unconfirmed_pool::UnconfirmedPoolError,
+        MempoolTransactionEvent,
+        TransactionRemovalReason,
+    },
+    transactions::{
+        tari_amount::MicroTari,
+        transaction::Transaction,
+        types::{HashOutput, Signature},
     },
-    transactions::{transaction::Transaction, types::Signature},
 };
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
-    convert::TryFrom,
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
 };
-use tari_crypto::tari_utilities::hex::Hex;
+use tari_crypto::tari_utilities::{hex::Hex, Hashable};
+use tokio::sync::broadcast;
 
 pub const LOG_TARGET: &str = "c::mp::unconfirmed_pool::unconfirmed_pool_storage";
 
@@ -59,6 +66,14 @@ impl Default for UnconfirmedPoolConfig {
     }
 }
 
+// The transactions that have been selected for a block template so far
+#[derive(Default)]
+struct PackageSelection {
+    txs: Vec<Arc<Transaction>>,
+    tx_keys: HashSet<Signature>,
+    weight: u64,
+}
+
 /// The Unconfirmed Transaction Pool consists of all unconfirmed transactions that are ready to be included in a block
 /// and they are prioritised according to the priority metric.
 /// The txs_by_signature HashMap is used to find a transaction using its excess_sig, this functionality is used to match
@@ -67,10 +82,18 @@ impl Default for UnconfirmedPoolConfig {
 /// priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority transactions that can
 /// be included in a block. The excess_sig of a transaction is used a key to uniquely identify a specific transaction in
 /// these containers.
+/// The txs_by_output and txs_by_input HashMaps track the dependency chains between unconfirmed transactions, they map
+/// the hash of every output created and every input spent by the stored transactions to their keys. A transaction that
+/// spends the outputs of other unconfirmed transactions is prioritised by the fee rate of the package formed by the
+/// transaction and all of its unconfirmed ancestors, allowing a child transaction to pay for its parents.
+/// Every transaction that is added to or removed from the pool is published on the optional event publisher.
 pub struct UnconfirmedPool {
     config: UnconfirmedPoolConfig,
     txs_by_signature: HashMap<Signature, PrioritizedTransaction>,
     txs_by_priority: BTreeMap<FeePriority, Signature>,
+    txs_by_output: HashMap<HashOutput, Signature>,
+    txs_by_input: HashMap<HashOutput, Vec<Signature>>,
+    event_publisher: Option<broadcast::Sender<MempoolTransactionEvent>>,
 }
 
 impl UnconfirmedPool {
@@ -80,6 +103,29 @@ impl UnconfirmedPool {
             config,
             txs_by_signature: HashMap::new(),
             txs_by_priority: BTreeMap::new(),
+            txs_by_output: HashMap::new(),
+            txs_by_input: HashMap::new(),
+            event_publisher: None,
+        }
+    }
+
+    /// Create a new UnconfirmedPool with the specified configuration that publishes the transactions that are added
+    /// and removed on the provided event publisher
+    pub fn with_event_publisher(
+        config: UnconfirmedPoolConfig,
+        event_publisher: broadcast::Sender<MempoolTransactionEvent>,
+    ) -> Self
+    {
+        Self {
+            event_publisher: Some(event_publisher),
+            ..Self::new(config)
+        }
+    }
+
+    fn publish_event(&self, event: MempoolTransactionEvent) {
+        if let Some(event_publisher) = self.event_publisher.as_ref() {
+            // Sending only fails when there are no subscribers
+            let _ = event_publisher.send(event);
         }
     }
 
@@ -88,41 +134,266 @@ impl UnconfirmedPool {
     }
 
     fn remove_lowest_priority_tx(&mut self) {
-        if let Some((priority, sig)) = self.txs_by_priority.iter().next().map(|(p, s)| (p.clone(), s.clone())) {
-            self.txs_by_signature.remove(&sig);
-            self.txs_by_priority.remove(&priority);
+        if let Some(tx_key) = self.txs_by_priority.iter().next().map(|(_, s)| s.clone()) {
+            self.remove_tx_and_descendants(&tx_key);
         }
     }
 
     /// Insert a new transaction into the UnconfirmedPool. Low priority transactions will be removed to make space for
     /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity is
     /// reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
-    #[allow(clippy::map_entry)]
+    /// If the new transaction conflicts with stored transactions, by spending the same inputs or sharing the same
+    /// first kernel, and pays a higher fee per gram as well as a higher total fee than all of them then the
+    /// conflicting transactions, and the transactions that depend on them, are evicted and replaced by the new
+    /// transaction. A conflicting transaction that does not outbid the stored transactions is rejected.
     pub fn insert(&mut self, tx: Arc<Transaction>) -> Result<(), UnconfirmedPoolError> {
         let tx_key = tx
             .first_kernel_excess_sig()
             .ok_or_else(|| UnconfirmedPoolError::TransactionNoKernels)?;
-        if !self.txs_by_signature.contains_key(tx_key) {
-            debug!(
-                target: LOG_TARGET,
-                "Inserting tx into unconfirmed pool: {}",
-                tx_key.get_signature().to_hex()
-            );
-            trace!(target: LOG_TARGET, "Transaction inserted: {}", tx);
-            let prioritized_tx = PrioritizedTransaction::try_from((*tx).clone())?;
-            if self.txs_by_signature.len() >= self.config.storage_capacity {
-                if prioritized_tx.priority < *self.lowest_priority() {
-                    return Ok(());
-                }
-                self.remove_lowest_priority_tx();
+        if let Some(ptx) = self.txs_by_signature.get(tx_key) {
+            if ptx.transaction == tx {
+                return Ok(());
+            }
+        }
+
+        let conflicting_tx_keys = self.find_conflicting_txs(&tx);
+        if !conflicting_tx_keys.is_empty() {
+            if !self.is_replacement(&tx, &conflicting_tx_keys) {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejecting tx {} that conflicts with {} stored transaction(s) without outbidding them",
+                    tx_key.get_signature().to_hex(),
+                    conflicting_tx_keys.len()
+                );
+                return Err(UnconfirmedPoolError::ConflictingTransaction);
+            }
+            for conflicting_tx_key in &conflicting_tx_keys {
+                debug!(
+                    target: LOG_TARGET,
+                    "Replacing tx {} in unconfirmed pool with higher fee tx {}",
+                    conflicting_tx_key.get_signature().to_hex(),
+                    tx_key.get_signature().to_hex()
+                );
+                self.remove_tx(conflicting_tx_key, TransactionRemovalReason::Discarded);
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Inserting tx into unconfirmed pool: {}",
+            tx_key.get_signature().to_hex()
+        );
+        trace!(target: LOG_TARGET, "Transaction inserted: {}", tx);
+        let ancestor_keys = self.find_ancestors(&tx);
+        let prioritized_tx = self.create_prioritized_tx(tx.clone(), &ancestor_keys)?;
+        if self.txs_by_signature.len() >= self.config.storage_capacity {
+            if prioritized_tx.priority < *self.lowest_priority() {
+                return Ok(());
             }
-            self.txs_by_priority
-                .insert(prioritized_tx.priority.clone(), tx_key.clone());
-            self.txs_by_signature.insert(tx_key.clone(), prioritized_tx);
+            self.remove_lowest_priority_tx();
+            // The transaction cannot be included in a block without its parents
+            if ancestor_keys
+                .iter()
+                .any(|ancestor_key| !self.txs_by_signature.contains_key(ancestor_key))
+            {
+                return Ok(());
+            }
+        }
+        self.txs_by_priority
+            .insert(prioritized_tx.priority.clone(), tx_key.clone());
+        self.txs_by_signature.insert(tx_key.clone(), prioritized_tx);
+        for input in tx.body.inputs() {
+            self.txs_by_input.entry(input.hash()).or_default().push(tx_key.clone());
+        }
+        for output in tx.body.outputs() {
+            self.txs_by_output.insert(output.hash(), tx_key.clone());
         }
+        // Stored transactions that spend the outputs of the new transaction now form a package with it
+        let descendant_keys = self.find_descendants(tx_key);
+        self.update_priorities(&descendant_keys);
+        self.publish_event(MempoolTransactionEvent::Added(tx));
         Ok(())
     }
 
+    // Returns the keys of all stored transactions that spend any of the inputs of the provided transaction or that
+    // share its first kernel, as well as the keys of all transactions that depend on them
+    fn find_conflicting_txs(&self, tx: &Transaction) -> Vec<Signature> {
+        let mut conflicting_tx_keys: Vec<Signature> = self
+            .txs_by_signature
+            .iter()
+            .filter(|(tx_key, ptx)| {
+                Some(*tx_key) == tx.first_kernel_excess_sig() ||
+                    ptx.transaction
+                        .body
+                        .inputs()
+                        .iter()
+                        .any(|input| tx.body.inputs().contains(input))
+            })
+            .map(|(tx_key, _)| tx_key.clone())
+            .collect();
+        for tx_key in conflicting_tx_keys.clone() {
+            for descendant_key in self.find_descendants(&tx_key) {
+                if !conflicting_tx_keys.contains(&descendant_key) {
+                    conflicting_tx_keys.push(descendant_key);
+                }
+            }
+        }
+        conflicting_tx_keys
+    }
+
+    // A transaction can only replace the conflicting transactions if it pays a higher fee per gram than each of them
+    // and a higher total fee than all of them combined
+    fn is_replacement(&self, tx: &Transaction, conflicting_tx_keys: &[Signature]) -> bool {
+        let fee_per_gram = tx.calculate_ave_fee_per_gram();
+        let mut conflicting_fees = MicroTari::from(0);
+        for tx_key in conflicting_tx_keys {
+            match self.txs_by_signature.get(tx_key) {
+                Some(ptx) => {
+                    if ptx.transaction.calculate_ave_fee_per_gram() >= fee_per_gram {
+                        return false;
+                    }
+                    conflicting_fees += ptx.transaction.body.get_total_fee();
+                },
+                None => return false,
+            }
+        }
+        tx.body.get_total_fee() > conflicting_fees
+    }
+
+    fn remove_tx(&mut self, tx_key: &Signature, reason: TransactionRemovalReason) -> Option<Arc<Transaction>> {
+        let ptx = self.txs_by_signature.remove(tx_key)?;
+        self.txs_by_priority.remove(&ptx.priority);
+        for input in ptx.transaction.body.inputs() {
+            let input_hash = input.hash();
+            if let Some(tx_keys) = self.txs_by_input.get_mut(&input_hash) {
+                tx_keys.retain(|k| k != tx_key);
+                if tx_keys.is_empty() {
+                    self.txs_by_input.remove(&input_hash);
+                }
+            }
+        }
+        for output in ptx.transaction.body.outputs() {
+            let output_hash = output.hash();
+            if self.txs_by_output.get(&output_hash) == Some(tx_key) {
+                self.txs_by_output.remove(&output_hash);
+            }
+        }
+        self.publish_event(MempoolTransactionEvent::Removed(ptx.transaction.clone(), reason));
+        Some(ptx.transaction)
+    }
+
+    // Removes the transaction together with all the transactions that depend on it, as they can no longer be included
+    // in a block
+    fn remove_tx_and_descendants(&mut self, tx_key: &Signature) -> Vec<Arc<Transaction>> {
+        let mut tx_keys = vec![tx_key.clone()];
+        tx_keys.append(&mut self.find_descendants(tx_key));
+        tx_keys
+            .iter()
+            .filter_map(|tx_key| self.remove_tx(tx_key, TransactionRemovalReason::Discarded))
+            .collect()
+    }
+
+    // Returns the keys of the stored transactions that created the outputs spent by the provided transaction
+    fn find_parents(&self, tx: &Transaction) -> Vec<Signature> {
+        let mut parent_keys = Vec::new();
+        for input in tx.body.inputs() {
+            if let Some(parent_key) = self.txs_by_output.get(&input.hash()) {
+                if Some(parent_key) != tx.first_kernel_excess_sig() && !parent_keys.contains(parent_key) {
+                    parent_keys.push(parent_key.clone());
+                }
+            }
+        }
+        parent_keys
+    }
+
+    // Returns the keys of all unconfirmed ancestors of the provided transaction, ordered so that every transaction is
+    // preceded by its parents
+    fn find_ancestors(&self, tx: &Transaction) -> Vec<Signature> {
+        let mut ancestor_keys = Vec::new();
+        self.collect_ancestors(tx, &mut ancestor_keys);
+        ancestor_keys
+    }
+
+    fn collect_ancestors(&self, tx: &Transaction, ancestor_keys: &mut Vec<Signature>) {
+        for parent_key in self.find_parents(tx) {
+            if ancestor_keys.contains(&parent_key) {
+                continue;
+            }
+            if let Some(ptx) = self.txs_by_signature.get(&parent_key) {
+                self.collect_ancestors(&ptx.transaction, ancestor_keys);
+                ancestor_keys.push(parent_key);
+            }
+        }
+    }
+
+    // Returns the keys of all stored transactions that spend the outputs of the specified transaction, either directly
+    // or through other unconfirmed transactions
+    fn find_descendants(&self, tx_key: &Signature) -> Vec<Signature> {
+        let mut descendant_keys: Vec<Signature> = Vec::new();
+        let mut pending_keys = vec![tx_key.clone()];
+        while let Some(parent_key) = pending_keys.pop() {
+            let ptx = match self.txs_by_signature.get(&parent_key) {
+                Some(ptx) => ptx,
+                None => continue,
+            };
+            for output in ptx.transaction.body.outputs() {
+                for child_key in self.txs_by_input.get(&output.hash()).into_iter().flatten() {
+                    if child_key != tx_key && !descendant_keys.contains(child_key) {
+                        descendant_keys.push(child_key.clone());
+                        pending_keys.push(child_key.clone());
+                    }
+                }
+            }
+        }
+        descendant_keys
+    }
+
+    // The priority of a transaction is based on the combined fee rate of the transaction and its unconfirmed ancestors
+    fn create_prioritized_tx(
+        &self,
+        tx: Arc<Transaction>,
+        ancestor_keys: &[Signature],
+    ) -> Result<PrioritizedTransaction, UnconfirmedPoolError>
+    {
+        let mut package_fee = tx.body.get_total_fee();
+        let mut package_weight = tx.calculate_weight();
+        for ptx in ancestor_keys
+            .iter()
+            .filter_map(|ancestor_key| self.txs_by_signature.get(ancestor_key))
+        {
+            package_fee += ptx.transaction.body.get_total_fee();
+            package_weight += ptx.weight;
+        }
+        Ok(PrioritizedTransaction::with_package(tx, package_fee, package_weight)?)
+    }
+
+    // Recalculates the priorities of the specified transactions after the set of their unconfirmed ancestors changed
+    fn update_priorities(&mut self, tx_keys: &[Signature]) {
+        for tx_key in tx_keys {
+            let tx = match self.txs_by_signature.get(tx_key) {
+                Some(ptx) => ptx.transaction.clone(),
+                None => continue,
+            };
+            let ancestor_keys = self.find_ancestors(&tx);
+            match self.create_prioritized_tx(tx, &ancestor_keys) {
+                Ok(prioritized_tx) => {
+                    if let Some(ptx) = self.txs_by_signature.remove(tx_key) {
+                        self.txs_by_priority.remove(&ptx.priority);
+                    }
+                    self.txs_by_priority
+                        .insert(prioritized_tx.priority.clone(), tx_key.clone());
+                    self.txs_by_signature.insert(tx_key.clone(), prioritized_tx);
+                },
+                Err(e) => warn!(
+                    target: LOG_TARGET,
+                    "Could not update the priority of tx {}: {}",
+                    tx_key.get_signature().to_hex(),
+                    e
+                ),
+            }
+        }
+    }
+
     /// Insert a set of new transactions into the UnconfirmedPool
     #[cfg(test)]
     pub fn insert_txs(&mut self, txs: Vec<Arc<Transaction>>) -> Result<(), UnconfirmedPoolError> {
@@ -137,31 +408,193 @@ impl UnconfirmedPool {
         self.txs_by_signature.contains_key(excess_sig)
     }
 
-    /// Returns a set of the highest priority unconfirmed transactions, that can be included in a block
+    /// Returns the package formed by aggregating the provided transaction with all of its unconfirmed ancestors, the
+    /// outputs created and spent within the package are cut-through. None is returned if the transaction does not
+    /// spend the outputs of any unconfirmed transactions.
+    pub fn create_package(&self, tx: &Transaction) -> Option<Transaction> {
+        let ancestor_keys = self.find_ancestors(tx);
+        if ancestor_keys.is_empty() {
+            return None;
+        }
+        let mut package = ancestor_keys
+            .iter()
+            .filter_map(|ancestor_key| self.txs_by_signature.get(ancestor_key))
+            .fold(tx.clone(), |package, ptx| {
+                package.add_no_cut_through((*ptx.transaction).clone())
+            });
+        package.body.do_cut_through();
+        package.body.sort();
+        Some(package)
+    }
+
+    /// Returns a set of the highest priority unconfirmed transactions, that can be included in a block. Transactions
+    /// are selected by the fee rate of their package, the unconfirmed ancestors of a selected transaction are always
+    /// included ahead of it.
     pub fn highest_priority_txs(&self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, UnconfirmedPoolError> {
-        let mut selected_txs: Vec<Arc<Transaction>> = Vec::new();
-        let mut curr_weight: u64 = 0;
+        Ok(self
+            .select_template_txs(total_weight, &BlockTemplatePolicy::default())?
+            .transactions)
+    }
+
+    /// Selects the unconfirmed transactions for a block template according to the provided policy. The transactions in
+    /// the include list of the policy are selected first and may use all of the available weight, the remaining
+    /// transactions are then selected by the fee rate of their package without using the reserved priority weight.
+    /// Every unconfirmed transaction that is not selected is reported together with the reason it was left out.
+    pub fn select_template_txs(
+        &self,
+        total_weight: u64,
+        policy: &BlockTemplatePolicy,
+    ) -> Result<TemplateSelection, UnconfirmedPoolError>
+    {
+        let excluded_tx_keys: HashSet<&Signature> = policy.always_exclude.iter().collect();
+        let mut selection = PackageSelection::default();
+        let mut excluded: Vec<ExcludedTransaction> = Vec::new();
+        let mut reported_tx_keys: HashSet<Signature> = HashSet::new();
+
+        for tx_key in &policy.always_include {
+            if selection.tx_keys.contains(tx_key) || reported_tx_keys.contains(tx_key) {
+                continue;
+            }
+            let reason = if self.txs_by_signature.contains_key(tx_key) {
+                self.select_package(
+                    tx_key,
+                    total_weight,
+                    MicroTari::from(0),
+                    &excluded_tx_keys,
+                    &mut selection,
+                )?
+            } else {
+                Some(ExclusionReason::NotFound)
+            };
+            if let Some(reason) = reason {
+                reported_tx_keys.insert(tx_key.clone());
+                excluded.push(ExcludedTransaction {
+                    excess_sig: tx_key.clone(),
+                    reason,
+                });
+            }
+        }
+
+        // The part of the reserved weight that was not used by the priority transactions is kept free
+        let unused_reserved_weight = policy.reserved_priority_weight.saturating_sub(selection.weight);
+        let weight_limit = total_weight.saturating_sub(unused_reserved_weight);
         let mut curr_skip_count: usize = 0;
         for (_, tx_key) in self.txs_by_priority.iter().rev() {
-            let ptx = self
+            if selection.tx_keys.contains(tx_key) || reported_tx_keys.contains(tx_key) {
+                continue;
+            }
+            if let Some(reason) = self.select_package(
+                tx_key,
+                weight_limit,
+                policy.min_fee_per_gram,
+                &excluded_tx_keys,
+                &mut selection,
+            )? {
+                reported_tx_keys.insert(tx_key.clone());
+                excluded.push(ExcludedTransaction {
+                    excess_sig: tx_key.clone(),
+                    reason,
+                });
+                if reason == ExclusionReason::InsufficientWeight {
+                    // Check if some the next few txs with slightly lower priority wont fit in the remaining space.
+                    curr_skip_count += 1;
+                    if curr_skip_count >= self.config.weight_tx_skip_count {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Transactions that were not considered once the skip count was reached did not fit into the template, and
+        // transactions that were left out on their own could still have been selected as the ancestor of another
+        for (_, tx_key) in self.txs_by_priority.iter().rev() {
+            if !selection.tx_keys.contains(tx_key) && !reported_tx_keys.contains(tx_key) {
+                excluded.push(ExcludedTransaction {
+                    excess_sig: tx_key.clone(),
+                    reason: ExclusionReason::InsufficientWeight,
+                });
+            }
+        }
+        excluded.retain(|excluded_tx| !selection.tx_keys.contains(&excluded_tx.excess_sig));
+
+        Ok(TemplateSelection {
+            transactions: selection.txs,
+            excluded,
+        })
+    }
+
+    // Adds the specified transaction, together with its unconfirmed ancestors that have not been selected yet, to the
+    // selection. The reason the package was left out is returned if it could not be selected.
+    fn select_package(
+        &self,
+        tx_key: &Signature,
+        weight_limit: u64,
+        min_fee_per_gram: MicroTari,
+        excluded_tx_keys: &HashSet<&Signature>,
+        selection: &mut PackageSelection,
+    ) -> Result<Option<ExclusionReason>, UnconfirmedPoolError>
+    {
+        if excluded_tx_keys.contains(tx_key) {
+            return Ok(Some(ExclusionReason::ExcludedByPolicy));
+        }
+        let ptx = self
+            .txs_by_signature
+            .get(tx_key)
+            .ok_or_else(|| UnconfirmedPoolError::StorageOutofSync)?;
+
+        let mut package_keys: Vec<Signature> = self
+            .find_ancestors(&ptx.transaction)
+            .into_iter()
+            .filter(|ancestor_key| !selection.tx_keys.contains(ancestor_key))
+            .collect();
+        if package_keys
+            .iter()
+            .any(|ancestor_key| excluded_tx_keys.contains(ancestor_key))
+        {
+            return Ok(Some(ExclusionReason::AncestorExcluded));
+        }
+        package_keys.push(tx_key.clone());
+        let mut package_txs: Vec<Arc<Transaction>> = Vec::with_capacity(package_keys.len());
+        let mut package_weight: u64 = 0;
+        let mut package_fee = MicroTari::from(0);
+        for package_key in &package_keys {
+            let package_ptx = self
                 .txs_by_signature
-                .get(tx_key)
+                .get(package_key)
                 .ok_or_else(|| UnconfirmedPoolError::StorageOutofSync)?;
+            package_weight += package_ptx.weight;
+            package_fee += package_ptx.transaction.body.get_total_fee();
+            package_txs.push(package_ptx.transaction.clone());
+        }
 
-            if curr_weight + ptx.weight <= total_weight {
-                if !UnconfirmedPool::find_duplicate_input(&selected_txs, &ptx.transaction) {
-                    curr_weight += ptx.weight;
-                    selected_txs.push(ptx.transaction.clone());
-                }
-            } else {
-                // Check if some the next few txs with slightly lower priority wont fit in the remaining space.
-                curr_skip_count += 1;
-                if curr_skip_count >= self.config.weight_tx_skip_count {
-                    break;
-                }
-            }
+        if package_fee < package_weight * min_fee_per_gram {
+            return Ok(Some(ExclusionReason::BelowMinimumFee));
         }
-        Ok(selected_txs)
+        if selection.weight + package_weight > weight_limit {
+            return Ok(Some(ExclusionReason::InsufficientWeight));
+        }
+        let has_duplicate_input = package_txs.iter().enumerate().any(|(i, tx)| {
+            UnconfirmedPool::find_duplicate_input(&selection.txs, tx) ||
+                UnconfirmedPool::find_duplicate_input(&package_txs[..i], tx)
+        });
+        if has_duplicate_input {
+            return Ok(Some(ExclusionReason::DoubleSpend));
+        }
+        selection.weight += package_weight;
+        selection.tx_keys.extend(package_keys);
+        selection.txs.append(&mut package_txs);
+        Ok(None)
+    }
+
+    /// Returns the fee per gram that each unconfirmed transaction is ranked by together with the weight of the
+    /// transaction, ordered from the highest to the lowest priority.
+    pub fn fee_per_gram_distribution(&self) -> Vec<(f64, u64)> {
+        self.txs_by_priority
+            .iter()
+            .rev()
+            .filter_map(|(_, tx_key)| self.txs_by_signature.get(tx_key))
+            .map(|ptx| (ptx.fee_per_gram, ptx.weight))
+            .collect()
     }
 
     // This will search a Vec<Arc<Transaction>> for duplicate inputs of a tx
@@ -176,16 +609,19 @@ impl UnconfirmedPool {
         false
     }
 
-    /// Remove all published transactions from the UnconfirmedPool and discard all double spend transactions.
-    /// Returns a list of all transactions that were removed the unconfirmed pool as a result of appearing in the block.
+    /// Remove all published transactions from the UnconfirmedPool and discard all double spend transactions, together
+    /// with the transactions that depend on them.
     fn discard_double_spends(&mut self, published_block: &Block) {
         let mut removed_tx_keys = Vec::new();
         for (tx_key, ptx) in self.txs_by_signature.iter() {
-            for input in ptx.transaction.body.inputs() {
-                if published_block.body.inputs().contains(input) {
-                    self.txs_by_priority.remove(&ptx.priority);
-                    removed_tx_keys.push(tx_key.clone());
-                }
+            if ptx
+                .transaction
+                .body
+                .inputs()
+                .iter()
+                .any(|input| published_block.body.inputs().contains(input))
+            {
+                removed_tx_keys.push(tx_key.clone());
             }
         }
 
@@ -195,34 +631,36 @@ impl UnconfirmedPool {
                 "Removing double spends from unconfirmed pool: {:?}",
                 tx_key
             );
-            self.txs_by_signature.remove(&tx_key);
+            self.remove_tx_and_descendants(tx_key);
         }
     }
 
     /// Remove all published transactions from the UnconfirmedPoolStorage and discard double spends
     pub fn remove_published_and_discard_double_spends(&mut self, published_block: &Block) -> Vec<Arc<Transaction>> {
         let mut removed_txs = Vec::new();
+        let mut descendant_keys = Vec::new();
         published_block.body.kernels().iter().for_each(|kernel| {
-            if let Some(ptx) = self.txs_by_signature.get(&kernel.excess_sig) {
-                self.txs_by_priority.remove(&ptx.priority);
-                if let Some(ptx) = self.txs_by_signature.remove(&kernel.excess_sig) {
-                    removed_txs.push(ptx.transaction);
+            if self.txs_by_signature.contains_key(&kernel.excess_sig) {
+                descendant_keys.append(&mut self.find_descendants(&kernel.excess_sig));
+                if let Some(tx) = self.remove_tx(&kernel.excess_sig, TransactionRemovalReason::Mined) {
+                    removed_txs.push(tx);
                 }
             }
         });
         // First remove published transactions before discarding double spends
         self.discard_double_spends(published_block);
+        // The children of published transactions now spend confirmed outputs, so they no longer pay for those parents
+        self.update_priorities(&descendant_keys);
 
         removed_txs
     }
 
-    /// Remove all unconfirmed transactions that have become time locked. This can happen when the chain height was
-    /// reduced on some reorgs.
+    /// Remove all unconfirmed transactions that have become time locked, together with the transactions that depend on
+    /// them. This can happen when the chain height was reduced on some reorgs.
     pub fn remove_timelocked(&mut self, tip_height: u64) -> Vec<Arc<Transaction>> {
         let mut removed_tx_keys: Vec<Signature> = Vec::new();
         for (tx_key, ptx) in self.txs_by_signature.iter() {
             if ptx.transaction.min_spendable_height() > tip_height + 1 {
-                self.txs_by_priority.remove(&ptx.priority);
                 removed_tx_keys.push(tx_key.clone());
             }
         }
@@ -233,9 +671,7 @@ impl UnconfirmedPool {
                 "Removing time locked transaction from unconfirmed pool: {:?}",
                 tx_key
             );
-            if let Some(ptx) = self.txs_by_signature.remove(&tx_key) {
-                removed_txs.push(ptx.transaction);
-            }
+            removed_txs.append(&mut self.remove_tx_and_descendants(&tx_key));
         }
         removed_txs
     }
@@ -268,7 +704,13 @@ impl UnconfirmedPool {
         }
         self.txs_by_priority
             .iter()
-            .all(|(_, tx_key)| self.txs_by_signature.contains_key(tx_key))
+            .all(|(_, tx_key)| self.txs_by_signature.contains_key(tx_key)) &&
+            self.txs_by_output
+                .iter()
+                .all(|(_, tx_key)| self.txs_by_signature.contains_key(tx_key)) &&
+            self.txs_by_input
+                .iter()
+                .all(|(_, tx_keys)| tx_keys.iter().all(|tx_key| self.txs_by_signature.contains_key(tx_key)))
     }
 }
 
@@ -280,13 +722,14 @@ mod test {
         test_helpers::create_orphan_block,
         transactions::{
             fee::Fee,
-            helpers::TestParams,
+            helpers::{create_test_input, spend_utxos, TestParams},
             tari_amount::MicroTari,
-            transaction::{KernelFeatures, UnblindedOutput},
+            transaction::{KernelFeatures, TransactionInput, UnblindedOutput},
             types::{CryptoFactories, HashDigest},
             SenderTransactionProtocol,
         },
         tx,
+        txn_schema,
     };
 
     #[test]
@@ -352,6 +795,59 @@ mod test {
         assert!(unconfirmed_pool.check_status());
     }
 
+    #[test]
+    fn test_select_template_txs_with_policy() {
+        let tx_low_fee = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 1, outputs: 1).0);
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(500), inputs: 2, outputs: 1).0);
+        let tx2 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(400), inputs: 2, outputs: 1).0);
+        let tx3 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(300), inputs: 2, outputs: 1).0);
+        let tx_missing = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(300), inputs: 2, outputs: 1).0);
+        let excess_sig = |tx: &Arc<Transaction>| tx.body.kernels()[0].excess_sig.clone();
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool
+            .insert_txs(vec![tx_low_fee.clone(), tx1.clone(), tx2.clone(), tx3.clone()])
+            .unwrap();
+
+        let policy = BlockTemplatePolicy {
+            min_fee_per_gram: MicroTari(2),
+            reserved_priority_weight: tx3.calculate_weight(),
+            always_include: vec![excess_sig(&tx3), excess_sig(&tx_missing)],
+            always_exclude: vec![excess_sig(&tx2)],
+        };
+        let total_weight = tx1.calculate_weight() + tx3.calculate_weight() + tx_low_fee.calculate_weight();
+        let selection = unconfirmed_pool.select_template_txs(total_weight, &policy).unwrap();
+        // The priority transaction is selected ahead of the higher fee transaction
+        assert_eq!(selection.transactions, vec![tx3.clone(), tx1.clone()]);
+        let reason = |selection: &TemplateSelection, tx: &Arc<Transaction>| {
+            selection
+                .excluded
+                .iter()
+                .find(|excluded_tx| excluded_tx.excess_sig == excess_sig(tx))
+                .map(|excluded_tx| excluded_tx.reason)
+        };
+        assert_eq!(selection.excluded.len(), 3);
+        assert_eq!(reason(&selection, &tx_missing), Some(ExclusionReason::NotFound));
+        assert_eq!(reason(&selection, &tx2), Some(ExclusionReason::ExcludedByPolicy));
+        assert_eq!(reason(&selection, &tx_low_fee), Some(ExclusionReason::BelowMinimumFee));
+
+        // The reserved weight is kept free when no priority transactions are selected
+        let policy = BlockTemplatePolicy {
+            reserved_priority_weight: tx1.calculate_weight(),
+            ..Default::default()
+        };
+        let selection = unconfirmed_pool
+            .select_template_txs(tx1.calculate_weight(), &policy)
+            .unwrap();
+        assert!(selection.transactions.is_empty());
+        assert_eq!(selection.excluded.len(), 4);
+        assert_eq!(reason(&selection, &tx1), Some(ExclusionReason::InsufficientWeight));
+        assert!(unconfirmed_pool.check_status());
+    }
+
     #[test]
     fn test_double_spend_inputs() {
         let (tx1, _, _) = tx!(MicroTari(5_000), fee: MicroTari(50), inputs: 1, outputs: 1);
@@ -393,19 +889,130 @@ mod test {
             weight_tx_skip_count: 3,
         });
 
-        unconfirmed_pool
-            .insert_txs(vec![tx1.clone(), tx2.clone(), tx3.clone()])
-            .unwrap();
-        assert_eq!(unconfirmed_pool.len(), 3);
+        unconfirmed_pool.insert_txs(vec![tx1.clone(), tx2.clone()]).unwrap();
+        // tx3 spends the same input as tx2 without paying a higher fee so it is rejected
+        assert!(matches!(
+            unconfirmed_pool.insert(tx3.clone()),
+            Err(UnconfirmedPoolError::ConflictingTransaction)
+        ));
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(tx3.first_kernel_excess_sig().unwrap()));
 
         let desired_weight = tx1.calculate_weight() + tx2.calculate_weight() + tx3.calculate_weight() + 1000;
         let selected_txs = unconfirmed_pool.highest_priority_txs(desired_weight).unwrap();
         assert!(selected_txs.contains(&tx1));
-        // Whether tx2 or tx3 is selected is non-deterministic
-        assert!(selected_txs.contains(&tx2) ^ selected_txs.contains(&tx3));
+        assert!(selected_txs.contains(&tx2));
         assert_eq!(selected_txs.len(), 2);
     }
 
+    fn create_double_spend_tx(
+        input_amount: MicroTari,
+        utxo: TransactionInput,
+        input: UnblindedOutput,
+        fee_per_gram: MicroTari,
+    ) -> Transaction
+    {
+        let test_params = TestParams::new();
+        let mut stx_builder = SenderTransactionProtocol::builder(0);
+        stx_builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(Default::default())
+            .with_private_nonce(test_params.nonce.clone())
+            .with_change_secret(test_params.change_key.clone());
+
+        let estimated_fee = Fee::calculate(fee_per_gram, 1, 1, 1);
+        let output = UnblindedOutput::new(input_amount - estimated_fee, test_params.spend_key, None);
+        stx_builder.with_input(utxo, input).with_output(output);
+
+        let factories = CryptoFactories::default();
+        let mut stx_protocol = stx_builder.build::<HashDigest>(&factories).unwrap();
+        stx_protocol.finalize(KernelFeatures::empty(), &factories).unwrap();
+        stx_protocol.get_transaction().unwrap().clone()
+    }
+
+    #[test]
+    fn test_replace_by_fee() {
+        const INPUT_AMOUNT: MicroTari = MicroTari(5_000);
+        let (tx1, inputs, _) = tx!(INPUT_AMOUNT, fee: MicroTari(20), inputs: 1, outputs: 1);
+        let double_spend_utxo = tx1.body.inputs().first().unwrap().clone();
+        let double_spend_input = inputs.first().unwrap().clone();
+        let tx2 = create_double_spend_tx(
+            INPUT_AMOUNT,
+            double_spend_utxo.clone(),
+            double_spend_input.clone(),
+            MicroTari(50),
+        );
+        let tx3 = create_double_spend_tx(INPUT_AMOUNT, double_spend_utxo, double_spend_input, MicroTari(30));
+
+        let tx1 = Arc::new(tx1);
+        let tx2 = Arc::new(tx2);
+        let tx3 = Arc::new(tx3);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 4,
+            weight_tx_skip_count: 3,
+        });
+
+        unconfirmed_pool.insert(tx1.clone()).unwrap();
+        // tx2 pays a higher fee for the same input so it replaces tx1
+        unconfirmed_pool.insert(tx2.clone()).unwrap();
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(tx1.first_kernel_excess_sig().unwrap()));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(tx2.first_kernel_excess_sig().unwrap()));
+
+        // tx3 does not outbid tx2 so it is rejected
+        assert!(matches!(
+            unconfirmed_pool.insert(tx3.clone()),
+            Err(UnconfirmedPoolError::ConflictingTransaction)
+        ));
+        assert_eq!(unconfirmed_pool.len(), 1);
+        let desired_weight = tx2.calculate_weight() + tx3.calculate_weight() + 1000;
+        let selected_txs = unconfirmed_pool.highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(selected_txs, vec![tx2.clone()]);
+
+        // Re-submitting the replaced transaction does not evict the replacement
+        assert!(matches!(
+            unconfirmed_pool.insert(tx1),
+            Err(UnconfirmedPoolError::ConflictingTransaction)
+        ));
+        assert_eq!(unconfirmed_pool.snapshot(), vec![tx2]);
+        assert!(unconfirmed_pool.check_status());
+    }
+
+    #[test]
+    fn test_replace_by_fee_with_aggregated_tx() {
+        let tx1 = tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 2, outputs: 1).0;
+        let bump_tx = tx!(MicroTari(5_000), fee: MicroTari(200), inputs: 1, outputs: 1).0;
+        // The replacement contains the original kernel so it can be built without the original counterparty
+        let mut tx2 = tx1.clone().add_no_cut_through(bump_tx);
+        tx2.body.sort();
+        let tx1 = Arc::new(tx1);
+        let tx2 = Arc::new(tx2);
+        assert!(tx2.calculate_ave_fee_per_gram() > tx1.calculate_ave_fee_per_gram());
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 4,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool.insert(tx1.clone()).unwrap();
+        unconfirmed_pool.insert(tx2.clone()).unwrap();
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert_eq!(unconfirmed_pool.snapshot(), vec![tx2.clone()]);
+
+        // The original transaction cannot evict the replacement
+        assert!(matches!(
+            unconfirmed_pool.insert(tx1),
+            Err(UnconfirmedPoolError::ConflictingTransaction)
+        ));
+        assert_eq!(unconfirmed_pool.snapshot(), vec![tx2.clone()]);
+        let selected_txs = unconfirmed_pool
+            .highest_priority_txs(tx2.calculate_weight() * 2)
+            .unwrap();
+        assert_eq!(selected_txs, vec![tx2]);
+        assert!(unconfirmed_pool.check_status());
+    }
+
     #[test]
     fn test_remove_reorg_txs() {
         let network = Network::LocalNet;
@@ -529,4 +1136,102 @@ mod test {
 
         assert!(unconfirmed_pool.check_status());
     }
+
+    #[test]
+    fn test_child_pays_for_parent() {
+        let network = Network::LocalNet;
+        let consensus = ConsensusManagerBuilder::new(network).build();
+        let factories = CryptoFactories::default();
+        let (_, input) = create_test_input(MicroTari(10_000), 0, &factories.commitment);
+        let (parent, parent_outputs, _) =
+            spend_utxos(txn_schema!(from: vec![input], to: vec![MicroTari(8_000)], fee: MicroTari(5)));
+        let (child, _, _) =
+            spend_utxos(txn_schema!(from: vec![parent_outputs[0].clone()], to: vec![], fee: MicroTari(100)));
+        let tx = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1).0);
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool
+            .insert_txs(vec![parent.clone(), tx.clone(), child.clone()])
+            .unwrap();
+        assert_eq!(unconfirmed_pool.len(), 3);
+        assert!(unconfirmed_pool.create_package(&tx).is_none());
+        let package = unconfirmed_pool.create_package(&child).unwrap();
+        assert_eq!(package.body.kernels().len(), 2);
+        assert_eq!(package.body.inputs(), parent.body.inputs());
+
+        // The package of the parent and child pays a higher fee rate than tx, even though the parent on its own does
+        // not, and the parent is included ahead of its child
+        let desired_weight = parent.calculate_weight() + child.calculate_weight();
+        let selected_txs = unconfirmed_pool.highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(selected_txs, vec![parent.clone(), child.clone()]);
+
+        // The child is not selected without its parent
+        let selected_txs = unconfirmed_pool
+            .highest_priority_txs(child.calculate_weight() + tx.calculate_weight())
+            .unwrap();
+        assert_eq!(selected_txs, vec![tx.clone()]);
+
+        // Once the parent is published the child no longer depends on any unconfirmed transactions
+        let published_block = create_orphan_block(0, vec![(*parent).clone()], &consensus);
+        let _ = unconfirmed_pool.remove_published_and_discard_double_spends(&published_block);
+        assert_eq!(unconfirmed_pool.len(), 2);
+        assert!(unconfirmed_pool.create_package(&child).is_none());
+        let selected_txs = unconfirmed_pool
+            .highest_priority_txs(child.calculate_weight() + tx.calculate_weight())
+            .unwrap();
+        assert_eq!(selected_txs, vec![child, tx]);
+        assert!(unconfirmed_pool.check_status());
+    }
+
+    #[test]
+    fn test_discard_descendants_of_double_spend_txs() {
+        let network = Network::LocalNet;
+        let consensus = ConsensusManagerBuilder::new(network).build();
+        let factories = CryptoFactories::default();
+        const INPUT_AMOUNT: MicroTari = MicroTari(10_000);
+        let (utxo, input) = create_test_input(INPUT_AMOUNT, 0, &factories.commitment);
+        let (parent, parent_outputs, _) = spend_utxos(txn_schema!(
+            from: vec![input.clone()],
+            to: vec![MicroTari(4_000), MicroTari(4_000)],
+            fee: MicroTari(5)
+        ));
+        let (child, child_outputs, _) =
+            spend_utxos(txn_schema!(from: vec![parent_outputs[0].clone()], to: vec![], fee: MicroTari(50)));
+        let (grandchild, _, _) = spend_utxos(txn_schema!(
+            from: vec![child_outputs[0].clone(), parent_outputs[1].clone()],
+            to: vec![],
+            fee: MicroTari(100)
+        ));
+        let double_spend_tx = create_double_spend_tx(INPUT_AMOUNT, utxo, input, MicroTari(20));
+        let tx = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1).0);
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+        let grandchild = Arc::new(grandchild);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool
+            .insert_txs(vec![parent.clone(), child.clone(), grandchild.clone(), tx.clone()])
+            .unwrap();
+        assert_eq!(unconfirmed_pool.len(), 4);
+        let desired_weight = parent.calculate_weight() + child.calculate_weight() + grandchild.calculate_weight();
+        let selected_txs = unconfirmed_pool.highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(selected_txs, vec![parent.clone(), child.clone(), grandchild.clone()]);
+
+        // The parent is double spent by the published block, so none of its descendants can be mined anymore
+        let published_block = create_orphan_block(0, vec![double_spend_tx], &consensus);
+        let _ = unconfirmed_pool.remove_published_and_discard_double_spends(&published_block);
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(parent.first_kernel_excess_sig().unwrap()));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(child.first_kernel_excess_sig().unwrap()));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(grandchild.first_kernel_excess_sig().unwrap()));
+        assert_eq!(unconfirmed_pool.snapshot(), vec![tx]);
+        assert!(unconfirmed_pool.check_status());
+    }
 }
@@ -31,6 +31,7 @@ use crate::{
         BlockchainDatabaseConfig,
         ChainHeader,
         ChainStorageError,
+        CommitmentHistory,
         DbKey,
         DbTransaction,
         DbValue,
@@ -38,6 +39,7 @@ use crate::{
         LMDBDatabase,
         MmrTree,
         PrunedOutput,
+        Reorg,
         Validators,
     },
     consensus::{
@@ -49,7 +51,7 @@ use crate::{
     },
     transactions::{
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
-        types::{CryptoFactories, HashOutput, Signature},
+        types::{Commitment, CryptoFactories, HashOutput, Signature},
     },
     validation::{
         block_validators::{BodyOnlyValidator, OrphanBlockValidator},
@@ -59,7 +61,7 @@ use crate::{
 use croaring::Bitmap;
 use std::{
     fs,
-    ops::Deref,
+    ops::{Deref, DerefMut},
     path::{Path, PathBuf},
 };
 use tari_common_types::chain_metadata::ChainMetadata;
@@ -68,6 +70,11 @@ use tari_test_utils::paths::create_temporary_data_path;
 
 /// Create a new blockchain database containing no blocks.
 pub fn create_new_blockchain() -> BlockchainDatabase<TempDatabase> {
+    create_new_blockchain_with_backend(create_test_db())
+}
+
+/// Create a new blockchain database containing no blocks, using the given backend
+pub fn create_new_blockchain_with_backend(backend: TempDatabase) -> BlockchainDatabase<TempDatabase> {
     let network = Network::Stibbons;
     let consensus_constants = ConsensusConstantsBuilder::new(network).build();
     let genesis = get_ridcully_genesis_block();
@@ -81,7 +88,14 @@ pub fn create_new_blockchain() -> BlockchainDatabase<TempDatabase> {
         MockValidator::new(true),
         MockValidator::new(true),
     );
-    create_store_with_consensus_and_validators(&consensus_manager, validators)
+    BlockchainDatabase::new(
+        backend,
+        &consensus_manager,
+        validators,
+        BlockchainDatabaseConfig::default(),
+        false,
+    )
+    .unwrap()
 }
 
 pub fn create_store_with_consensus_and_validators(
@@ -145,6 +159,12 @@ impl Deref for TempDatabase {
     }
 }
 
+impl DerefMut for TempDatabase {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.db
+    }
+}
+
 impl Drop for TempDatabase {
     fn drop(&mut self) {
         if Path::new(&self.path).exists() {
@@ -258,10 +278,38 @@ impl BlockchainBackend for TempDatabase {
         self.db.fetch_outputs_in_block(header_hash)
     }
 
+    fn fetch_outputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<PrunedOutput>, ChainStorageError>
+    {
+        self.db.fetch_outputs_in_block_paged(header_hash, skip, take)
+    }
+
     fn fetch_inputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<TransactionInput>, ChainStorageError> {
         self.db.fetch_inputs_in_block(header_hash)
     }
 
+    fn fetch_inputs_in_block_paged(
+        &self,
+        header_hash: &HashOutput,
+        skip: usize,
+        take: usize,
+    ) -> Result<Vec<TransactionInput>, ChainStorageError>
+    {
+        self.db.fetch_inputs_in_block_paged(header_hash, skip, take)
+    }
+
+    fn fetch_commitment_history(
+        &self,
+        commitment: &Commitment,
+    ) -> Result<Option<CommitmentHistory>, ChainStorageError>
+    {
+        self.db.fetch_commitment_history(commitment)
+    }
+
     fn fetch_mmr_size(&self, tree: MmrTree) -> Result<u64, ChainStorageError> {
         self.db.fetch_mmr_size(tree)
     }
@@ -326,4 +374,8 @@ impl BlockchainBackend for TempDatabase {
     fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError> {
         self.db.fetch_horizon_data()
     }
+
+    fn fetch_all_reorgs(&self) -> Result<Vec<Reorg>, ChainStorageError> {
+        self.db.fetch_all_reorgs()
+    }
 }
@@ -80,6 +80,9 @@
 //!   end
 //! </div>
 
+pub mod multi_receiver;
+pub mod multisig;
+pub mod partially_signed;
 pub mod proto;
 pub mod recipient;
 pub mod sender;
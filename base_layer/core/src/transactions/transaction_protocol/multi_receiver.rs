@@ -0,0 +1,219 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::transactions::{
+    transaction::OutputFeatures,
+    transaction_protocol::{
+        build_challenge,
+        recipient::{MultiRoundRecipientData, RecipientSignedMessage},
+        sender::{MultiRoundSenderData, MultiRoundSignatureRequest},
+        single_receiver::SingleReceiverTransactionProtocol,
+        RewindData,
+        TransactionProtocolError as TPE,
+    },
+    types::{CryptoFactories, PrivateKey as SK, PublicKey, Signature},
+};
+use tari_crypto::keys::PublicKey as PK;
+
+/// MultiReceiverTransactionProtocol represents the actions taken by one of the receivers in the multi-round Tari
+/// transaction protocol. In the first round the receiver:
+/// * Checks the input for validity
+/// * Constructs his output and range proof
+/// * Returns his public spend key and public nonce to the sender
+/// Once the sender has collected the public keys of all the receivers, the receiver signs the aggregated challenge
+/// with the keys committed to in the first round. If any step fails, an error is returned.
+pub struct MultiReceiverTransactionProtocol {}
+
+impl MultiReceiverTransactionProtocol {
+    pub fn create(
+        sender_info: &MultiRoundSenderData,
+        nonce: SK,
+        spending_key: SK,
+        features: OutputFeatures,
+        factories: &CryptoFactories,
+        rewind_data: Option<&RewindData>,
+    ) -> Result<MultiRoundRecipientData, TPE>
+    {
+        MultiReceiverTransactionProtocol::validate_sender_data(sender_info)?;
+        let output = SingleReceiverTransactionProtocol::build_output(
+            sender_info.amount,
+            &spending_key,
+            features,
+            factories,
+            rewind_data,
+        )?;
+        Ok(MultiRoundRecipientData {
+            tx_id: sender_info.tx_id,
+            metadata: sender_info.metadata.clone(),
+            output,
+            nonce,
+            spending_key,
+        })
+    }
+
+    /// Produce the receiver's partial signature over the aggregated public nonce sent by the sender
+    pub fn sign(
+        data: &MultiRoundRecipientData,
+        request: &MultiRoundSignatureRequest,
+    ) -> Result<RecipientSignedMessage, TPE>
+    {
+        if request.tx_id != data.tx_id {
+            return Err(TPE::ValidationError(
+                "Signature request is for a different transaction".into(),
+            ));
+        }
+        // The sender must not be able to change the terms of the transaction after the recipient has committed to it
+        if request.metadata != data.metadata {
+            return Err(TPE::ValidationError(
+                "Transaction metadata changed since the public keys were sent".into(),
+            ));
+        }
+        let public_nonce = PublicKey::from_secret_key(&data.nonce);
+        if request.public_nonce_sum == public_nonce {
+            return Err(TPE::ValidationError(
+                "Aggregated public nonce does not include any other parties".into(),
+            ));
+        }
+        let e = build_challenge(&request.public_nonce_sum, &request.metadata);
+        let partial_signature =
+            Signature::sign(data.spending_key.clone(), data.nonce.clone(), &e).map_err(TPE::SigningError)?;
+        Ok(RecipientSignedMessage {
+            tx_id: data.tx_id,
+            output: data.output.clone(),
+            public_spend_key: PublicKey::from_secret_key(&data.spending_key),
+            partial_signature,
+        })
+    }
+
+    /// Validates the sender info
+    fn validate_sender_data(sender_info: &MultiRoundSenderData) -> Result<(), TPE> {
+        if sender_info.amount == 0.into() {
+            return Err(TPE::ValidationError("Cannot send zero microTari".into()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::transactions::{
+        tari_amount::*,
+        transaction::OutputFeatures,
+        transaction_protocol::{
+            build_challenge,
+            multi_receiver::MultiReceiverTransactionProtocol,
+            sender::{MultiRoundSenderData, MultiRoundSignatureRequest},
+            TransactionMetadata,
+            TransactionProtocolError,
+        },
+        types::{CryptoFactories, PrivateKey, PublicKey},
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::{
+        commitment::HomomorphicCommitmentFactory,
+        keys::{PublicKey as PK, SecretKey as SK},
+    };
+
+    fn sender_data() -> MultiRoundSenderData {
+        MultiRoundSenderData {
+            tx_id: 500,
+            amount: MicroTari(1500),
+            metadata: TransactionMetadata {
+                fee: MicroTari(100),
+                lock_height: 0,
+            },
+            message: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn zero_amount_fails() {
+        let factories = CryptoFactories::default();
+        let info = MultiRoundSenderData::default();
+        let r = PrivateKey::random(&mut OsRng);
+        let k = PrivateKey::random(&mut OsRng);
+        match MultiReceiverTransactionProtocol::create(&info, r, k, OutputFeatures::default(), &factories, None) {
+            Ok(_) => panic!("Zero amounts should fail"),
+            Err(TransactionProtocolError::ValidationError(s)) => assert_eq!(s, "Cannot send zero microTari"),
+            Err(_) => panic!("Protocol fails for the wrong reason"),
+        };
+    }
+
+    #[test]
+    fn valid_request() {
+        let factories = CryptoFactories::default();
+        let info = sender_data();
+        let r = PrivateKey::random(&mut OsRng);
+        let k = PrivateKey::random(&mut OsRng);
+        let data = MultiReceiverTransactionProtocol::create(
+            &info,
+            r.clone(),
+            k.clone(),
+            OutputFeatures::default(),
+            &factories,
+            None,
+        )
+        .unwrap();
+        let keys = data.public_keys();
+        assert_eq!(keys.tx_id, 500);
+        assert_eq!(keys.public_spend_key, PublicKey::from_secret_key(&k));
+        assert_eq!(keys.public_nonce, PublicKey::from_secret_key(&r));
+        assert!(factories
+            .commitment
+            .open_value(&k, info.amount.into(), &data.output.commitment));
+
+        let (_, pub_rs) = PublicKey::random_keypair(&mut OsRng);
+        let (_, pub_xs) = PublicKey::random_keypair(&mut OsRng);
+        let request = MultiRoundSignatureRequest {
+            tx_id: 500,
+            public_nonce_sum: &pub_rs + &keys.public_nonce,
+            public_excess: &pub_xs + &keys.public_spend_key,
+            metadata: info.metadata.clone(),
+        };
+        let signed = MultiReceiverTransactionProtocol::sign(&data, &request).unwrap();
+        assert_eq!(signed.output, data.output);
+        let e = build_challenge(&request.public_nonce_sum, &request.metadata);
+        assert!(signed.partial_signature.verify_challenge(&keys.public_spend_key, &e));
+    }
+
+    #[test]
+    fn changed_metadata_fails() {
+        let factories = CryptoFactories::default();
+        let info = sender_data();
+        let r = PrivateKey::random(&mut OsRng);
+        let k = PrivateKey::random(&mut OsRng);
+        let data =
+            MultiReceiverTransactionProtocol::create(&info, r, k, OutputFeatures::default(), &factories, None).unwrap();
+        let keys = data.public_keys();
+        let (_, pub_rs) = PublicKey::random_keypair(&mut OsRng);
+        let request = MultiRoundSignatureRequest {
+            tx_id: 500,
+            public_nonce_sum: &pub_rs + &keys.public_nonce,
+            public_excess: keys.public_spend_key,
+            metadata: TransactionMetadata {
+                fee: MicroTari(50),
+                lock_height: 0,
+            },
+        };
+        assert!(MultiReceiverTransactionProtocol::sign(&data, &request).is_err());
+    }
+}
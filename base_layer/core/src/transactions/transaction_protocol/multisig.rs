@@ -0,0 +1,588 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Outputs owned jointly by `n` parties, every one of which must take part in spending them.
+//!
+//! The spending key of a multi-signature output is the sum of `n` key shares, so the output commitment is
+//! $$ C = (\sum k_i) \cdot G + v \cdot H $$
+//! and the public key it is spent under is the [AggregatedKey] of the share public keys.
+//!
+//! Every participant generates its own key share and only ever publishes the public key of it, together with a
+//! proof of possession of the secret key (see [prove_key_share]). The proof stops a participant from choosing its
+//! public key as a function of the others' so as to cancel them out of the aggregated key. The commitment of the
+//! output is built from the aggregated public key alone, so no party ever knows the spending key.
+//!
+//! Funding such an output needs a range proof over the commitment and a kernel signature that every participant
+//! contributes its share to, since the excess of the funding transaction includes the spending key. The signature can
+//! be produced with a [MultiSigSession], but the range proof service does not support multi-party range proofs yet.
+//!
+//! Spending the output takes three rounds of communication between all the participants, driven by a
+//! [MultiSigSession] held by each of them:
+//!
+//! 1. Every party sends its public excess and a commitment to its public nonce.
+//! 1. Once all commitments have been received, every party reveals its public nonce.
+//! 1. Once all nonces have been received and checked against their commitments, every party sends its partial
+//!    signature. The partial signatures are verified and summed into the kernel signature.
+//!
+//! Committing to the nonces before revealing them prevents the last party to reveal from choosing its nonce based on
+//! the nonces of the others.
+
+use crate::transactions::{
+    tari_amount::MicroTari,
+    transaction::{
+        KernelBuilder,
+        KernelFeatures,
+        OutputFeatures,
+        Transaction,
+        TransactionBuilder,
+        TransactionInput,
+        TransactionOutput,
+    },
+    transaction_protocol::{build_challenge, TransactionMetadata, TransactionProtocolError as TPE},
+    types::{BlindingFactor, Challenge, Commitment, CryptoFactories, PrivateKey, PublicKey, Signature},
+};
+use digest::Digest;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{PublicKey as PK, SecretKey},
+    tari_utilities::ByteArray,
+};
+
+/// The sum of the public keys of every party to a multi-signature output
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedKey {
+    participants: Vec<PublicKey>,
+    public_key: PublicKey,
+}
+
+impl AggregatedKey {
+    /// Aggregate the public keys of at least two distinct participants. The keys are sorted so that every participant
+    /// arrives at the same set regardless of the order they were provided in.
+    pub fn new(mut participants: Vec<PublicKey>) -> Result<Self, TPE> {
+        if participants.len() < 2 {
+            return Err(TPE::ValidationError(
+                "A multi-signature output needs at least two participants".into(),
+            ));
+        }
+        participants.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        if participants.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(TPE::ValidationError(
+                "Multi-signature participants must be distinct".into(),
+            ));
+        }
+        let public_key = participants
+            .iter()
+            .skip(1)
+            .fold(participants[0].clone(), |acc, key| &acc + key);
+        Ok(Self {
+            participants,
+            public_key,
+        })
+    }
+
+    /// The public key the output is spent under
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// The sorted public keys of the participants
+    pub fn participants(&self) -> &[PublicKey] {
+        &self.participants
+    }
+
+    /// The commitment to `value` under the aggregated key, built without knowledge of any of the key shares
+    pub fn commitment(&self, value: MicroTari, factories: &CryptoFactories) -> Commitment {
+        &Commitment::from_public_key(&self.public_key) +
+            &factories.commitment.commit_value(&PrivateKey::default(), value.into())
+    }
+
+    /// Check that `commitment` commits to `value` under the aggregated key
+    pub fn opens(&self, commitment: &Commitment, value: MicroTari, factories: &CryptoFactories) -> bool {
+        &self.commitment(value, factories) == commitment
+    }
+}
+
+/// Prove possession of the secret key of a key share. The proof is bound to the key setup it is made for, so that it
+/// cannot be replayed in another one.
+pub fn prove_key_share(key_share: &PrivateKey, setup_id: u64) -> Result<Signature, TPE> {
+    let nonce = PrivateKey::random(&mut OsRng);
+    let e = key_share_challenge(
+        &PublicKey::from_secret_key(key_share),
+        &PublicKey::from_secret_key(&nonce),
+        setup_id,
+    );
+    Signature::sign(key_share.clone(), nonce, &e).map_err(TPE::SigningError)
+}
+
+/// Verify the proof of possession of the secret key of `share_public_key` made for the key setup `setup_id`
+pub fn verify_key_share(share_public_key: &PublicKey, proof: &Signature, setup_id: u64) -> bool {
+    let e = key_share_challenge(share_public_key, proof.get_public_nonce(), setup_id);
+    proof.verify_challenge(share_public_key, &e)
+}
+
+fn key_share_challenge(share_public_key: &PublicKey, public_nonce: &PublicKey, setup_id: u64) -> Vec<u8> {
+    Challenge::new()
+        .chain(share_public_key.as_bytes())
+        .chain(public_nonce.as_bytes())
+        .chain(&setup_id.to_le_bytes())
+        .result()
+        .to_vec()
+}
+
+/// The public parts of a transaction spending a multi-signature output, which every participant checks before
+/// signing for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSigSpendData {
+    /// The multi-signature output being spent
+    pub input: TransactionInput,
+    /// The outputs the value is spent to
+    pub outputs: Vec<TransactionOutput>,
+    /// The fee and lock height of the kernel
+    pub metadata: TransactionMetadata,
+    /// The transaction offset chosen by the party coordinating the spend
+    pub offset: BlindingFactor,
+}
+
+impl MultiSigSpendData {
+    /// The kernel excess that the participants' public excesses must sum to
+    pub fn excess(&self, factories: &CryptoFactories) -> Commitment {
+        let sum_outputs = self.outputs.iter().map(|o| &o.commitment).sum::<Commitment>();
+        let fee = factories
+            .commitment
+            .commit_value(&PrivateKey::default(), self.metadata.fee.into());
+        let offset = factories.commitment.commit_value(&self.offset, 0);
+        &(&(&sum_outputs - &self.input.commitment) + &fee) - &offset
+    }
+
+    /// Build the transaction from the aggregated kernel signature
+    pub fn build_transaction(&self, signature: &Signature, factories: &CryptoFactories) -> Result<Transaction, TPE> {
+        let kernel = KernelBuilder::new()
+            .with_fee(self.metadata.fee)
+            .with_features(KernelFeatures::empty())
+            .with_lock_height(self.metadata.lock_height)
+            .with_excess(&self.excess(factories))
+            .with_signature(signature)
+            .build()?;
+        let mut builder = TransactionBuilder::new();
+        builder
+            .add_input(self.input.clone())
+            .add_outputs(&mut self.outputs.clone())
+            .add_offset(self.offset.clone())
+            .with_kernel(kernel);
+        Ok(builder.build(factories)?)
+    }
+}
+
+/// A party to a multi-signature output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiSigParticipant {
+    /// The public key the party is reached at
+    pub comms_public_key: PublicKey,
+    /// The public key of the party's share of the spending key
+    pub share_public_key: PublicKey,
+    /// The party's proof of possession of the secret key of its share
+    pub share_proof: Signature,
+}
+
+/// The request of the party funding a multi-signature output for every participant to generate a key share for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSigKeySetup {
+    /// The value of the output
+    pub value: MicroTari,
+    /// The features of the output
+    pub features: OutputFeatures,
+    /// The public keys every party to the output is reached at
+    pub participants: Vec<PublicKey>,
+}
+
+/// A funded multi-signature output, announced to the participants by the party that funded it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSigOutputInfo {
+    /// The commitment of the output
+    pub commitment: Commitment,
+    /// The value of the output
+    pub value: MicroTari,
+    /// The features of the output
+    pub features: OutputFeatures,
+    /// Every party to the output with the public key of its share
+    pub participants: Vec<MultiSigParticipant>,
+}
+
+/// A message exchanged between the parties to a multi-signature output
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSigMessage {
+    /// The key setup or spend the message belongs to
+    pub session_id: u64,
+    pub body: MultiSigMessageBody,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultiSigMessageBody {
+    /// The funder of a new output asks the participants to generate their key shares
+    KeySetupRequest(Box<MultiSigKeySetup>),
+    /// The public key of the sender's key share and its proof of possession
+    PublicKeyShare(PublicKey, Signature),
+    /// The funder announces the output once it has been funded
+    OutputFunded(Box<MultiSigOutputInfo>),
+    /// The coordinator of a spend asks the other parties to sign for it, with a message describing the spend
+    SpendRequest(Box<MultiSigSpendData>, String),
+    /// Round 1: the public excess of the sender and the commitment to its public nonce
+    NonceCommitment(PublicKey, Vec<u8>),
+    /// Round 2: the public excess and the public nonce of the sender
+    PublicNonce(PublicKey, PublicKey),
+    /// Round 3: the public excess and the partial signature of the sender
+    PartialSignature(PublicKey, Signature),
+}
+
+#[derive(Debug, Clone, Default)]
+struct SignerState {
+    nonce_commitment: Option<Vec<u8>>,
+    public_nonce: Option<PublicKey>,
+    partial_signature: Option<Signature>,
+}
+
+/// One party's view of the signing of a multi-signature spend. Each party holds a session keyed by its own public
+/// excess and adds the messages of the other parties to it as they arrive.
+#[derive(Debug, Clone)]
+pub struct MultiSigSession {
+    num_signers: usize,
+    metadata: TransactionMetadata,
+    expected_excess: Commitment,
+    secret_excess: PrivateKey,
+    public_excess: PublicKey,
+    nonce: PrivateKey,
+    public_nonce: PublicKey,
+    signers: HashMap<PublicKey, SignerState>,
+}
+
+impl MultiSigSession {
+    /// Start a session for a spend signed by `num_signers` parties. `secret_excess` is this party's share of the
+    /// kernel excess and `expected_excess` is the excess of the whole transaction.
+    pub fn new(
+        secret_excess: PrivateKey,
+        num_signers: usize,
+        metadata: TransactionMetadata,
+        expected_excess: Commitment,
+    ) -> Result<Self, TPE>
+    {
+        if num_signers < 2 {
+            return Err(TPE::ValidationError(
+                "A multi-signature spend needs at least two signers".into(),
+            ));
+        }
+        let public_excess = PublicKey::from_secret_key(&secret_excess);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let public_nonce = PublicKey::from_secret_key(&nonce);
+        let mut signers = HashMap::new();
+        signers.insert(public_excess.clone(), SignerState {
+            nonce_commitment: Some(hash_nonce(&public_nonce)),
+            public_nonce: Some(public_nonce.clone()),
+            partial_signature: None,
+        });
+        Ok(Self {
+            num_signers,
+            metadata,
+            expected_excess,
+            secret_excess,
+            public_excess,
+            nonce,
+            public_nonce,
+            signers,
+        })
+    }
+
+    /// This party's share of the kernel excess
+    pub fn public_excess(&self) -> &PublicKey {
+        &self.public_excess
+    }
+
+    /// Round 1: the commitment to this party's public nonce
+    pub fn nonce_commitment(&self) -> Vec<u8> {
+        hash_nonce(&self.public_nonce)
+    }
+
+    /// Add the public excess and nonce commitment of another party. Once every party's commitment is known the sum of
+    /// the public excesses is checked against the excess of the transaction.
+    pub fn add_nonce_commitment(&mut self, public_excess: PublicKey, commitment: Vec<u8>) -> Result<(), TPE> {
+        if self.signers.contains_key(&public_excess) {
+            return Err(TPE::InvalidTransitionError);
+        }
+        if self.signers.len() == self.num_signers {
+            return Err(TPE::ValidationError(
+                "All signers have already joined the session".into(),
+            ));
+        }
+        self.signers.insert(public_excess, SignerState {
+            nonce_commitment: Some(commitment),
+            ..Default::default()
+        });
+        if self.signers.len() == self.num_signers {
+            let sum = self
+                .signers
+                .keys()
+                .skip(1)
+                .fold(self.signers.keys().next().unwrap().clone(), |acc, key| &acc + key);
+            if Commitment::from_public_key(&sum) != self.expected_excess {
+                return Err(TPE::ValidationError(
+                    "The public excesses of the signers do not sum to the transaction excess".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the nonce commitments of all the parties have been received
+    pub fn has_all_nonce_commitments(&self) -> bool {
+        self.signers.len() == self.num_signers
+    }
+
+    /// Round 2: this party's public nonce, which may only be revealed once every commitment has been received
+    pub fn public_nonce(&self) -> Result<PublicKey, TPE> {
+        if !self.has_all_nonce_commitments() {
+            return Err(TPE::IncompleteStateError("Waiting for nonce commitments".into()));
+        }
+        Ok(self.public_nonce.clone())
+    }
+
+    /// Add the public nonce of another party, which must match the commitment it sent in the first round
+    pub fn add_public_nonce(&mut self, public_excess: &PublicKey, public_nonce: PublicKey) -> Result<(), TPE> {
+        let signer = self.signers.get_mut(public_excess).ok_or(TPE::InvalidStateError)?;
+        if signer.nonce_commitment.as_ref() != Some(&hash_nonce(&public_nonce)) {
+            return Err(TPE::ValidationError(
+                "Public nonce does not match its commitment".into(),
+            ));
+        }
+        signer.public_nonce = Some(public_nonce);
+        Ok(())
+    }
+
+    /// Whether the public nonces of all the parties have been received
+    pub fn has_all_public_nonces(&self) -> bool {
+        self.has_all_nonce_commitments() && self.signers.values().all(|s| s.public_nonce.is_some())
+    }
+
+    /// Round 3: this party's partial signature over the kernel
+    pub fn partial_signature(&self) -> Result<Signature, TPE> {
+        let e = build_challenge(&self.sum_public_nonces()?, &self.metadata);
+        Signature::sign(self.secret_excess.clone(), self.nonce.clone(), &e).map_err(TPE::SigningError)
+    }
+
+    /// Add the partial signature of another party
+    pub fn add_partial_signature(&mut self, public_excess: &PublicKey, signature: Signature) -> Result<(), TPE> {
+        let signer = self.signers.get_mut(public_excess).ok_or(TPE::InvalidStateError)?;
+        signer.partial_signature = Some(signature);
+        Ok(())
+    }
+
+    /// Whether the partial signatures of all the other parties have been received
+    pub fn has_all_partial_signatures(&self) -> bool {
+        self.has_all_public_nonces() &&
+            self.signers
+                .iter()
+                .all(|(key, s)| key == &self.public_excess || s.partial_signature.is_some())
+    }
+
+    /// Verify every partial signature and sum them into the kernel signature
+    pub fn aggregate_signature(&self) -> Result<Signature, TPE> {
+        if !self.has_all_partial_signatures() {
+            return Err(TPE::IncompleteStateError("Waiting for partial signatures".into()));
+        }
+        let e = build_challenge(&self.sum_public_nonces()?, &self.metadata);
+        let mut signature = self.partial_signature()?;
+        for (public_excess, signer) in self.signers.iter().filter(|(key, _)| *key != &self.public_excess) {
+            let partial = signer.partial_signature.as_ref().ok_or(TPE::InvalidStateError)?;
+            if signer.public_nonce.as_ref() != Some(partial.get_public_nonce()) ||
+                !partial.verify_challenge(public_excess, &e)
+            {
+                return Err(TPE::InvalidSignatureError);
+            }
+            signature = &signature + partial;
+        }
+        Ok(signature)
+    }
+
+    fn sum_public_nonces(&self) -> Result<PublicKey, TPE> {
+        if !self.has_all_public_nonces() {
+            return Err(TPE::IncompleteStateError("Waiting for public nonces".into()));
+        }
+        Ok(self
+            .signers
+            .values()
+            .filter_map(|s| s.public_nonce.as_ref())
+            .fold(PublicKey::default(), |acc, nonce| &acc + nonce))
+    }
+}
+
+fn hash_nonce(public_nonce: &PublicKey) -> Vec<u8> {
+    Challenge::new().chain(public_nonce.as_bytes()).result().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::transactions::{
+        tari_amount::*,
+        transaction::{OutputFeatures, UnblindedOutput},
+        transaction_protocol::{
+            multisig::{prove_key_share, verify_key_share, AggregatedKey, MultiSigSession, MultiSigSpendData},
+            TransactionMetadata,
+            TransactionProtocolError,
+        },
+        types::{CryptoFactories, PrivateKey, PublicKey},
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::{
+        commitment::HomomorphicCommitmentFactory,
+        keys::{PublicKey as PK, SecretKey as SK},
+    };
+
+    #[test]
+    fn aggregated_key_requires_distinct_participants() {
+        let (_, p) = PublicKey::random_keypair(&mut OsRng);
+        assert!(AggregatedKey::new(vec![p.clone()]).is_err());
+        assert!(AggregatedKey::new(vec![p.clone(), p]).is_err());
+    }
+
+    fn key_shares(n: usize) -> (Vec<PrivateKey>, PrivateKey) {
+        let shares = (0..n).map(|_| PrivateKey::random(&mut OsRng)).collect::<Vec<_>>();
+        let k = shares.iter().fold(PrivateKey::default(), |acc, share| &acc + share);
+        (shares, k)
+    }
+
+    #[test]
+    fn key_share_proofs() {
+        let (k, p) = PublicKey::random_keypair(&mut OsRng);
+        let (_, other) = PublicKey::random_keypair(&mut OsRng);
+        let proof = prove_key_share(&k, 1).unwrap();
+        assert!(verify_key_share(&p, &proof, 1));
+        assert!(!verify_key_share(&p, &proof, 2));
+        assert!(!verify_key_share(&other, &proof, 1));
+    }
+
+    #[test]
+    fn key_shares_open_the_output() {
+        let factories = CryptoFactories::default();
+        let (shares, k) = key_shares(3);
+        let output = UnblindedOutput::new(MicroTari(5_000), k, None)
+            .as_transaction_output(&factories)
+            .unwrap();
+        let key = AggregatedKey::new(shares.iter().rev().map(PublicKey::from_secret_key).collect()).unwrap();
+        let same_key = AggregatedKey::new(shares.iter().map(PublicKey::from_secret_key).collect()).unwrap();
+        assert_eq!(key, same_key);
+        assert!(key.opens(&output.commitment, MicroTari(5_000), &factories));
+        assert!(!key.opens(&output.commitment, MicroTari(5_001), &factories));
+        assert_eq!(key.commitment(MicroTari(5_000), &factories), output.commitment);
+    }
+
+    #[test]
+    fn three_party_spend() {
+        let factories = CryptoFactories::default();
+        let (shares, k) = key_shares(3);
+        let input = UnblindedOutput::new(MicroTari(5_000), k, None)
+            .as_transaction_input(&factories.commitment, OutputFeatures::default());
+        let k_out = PrivateKey::random(&mut OsRng);
+        let output = UnblindedOutput::new(MicroTari(4_900), k_out.clone(), None)
+            .as_transaction_output(&factories)
+            .unwrap();
+        let offset = PrivateKey::random(&mut OsRng);
+        let spend = MultiSigSpendData {
+            input,
+            outputs: vec![output],
+            metadata: TransactionMetadata {
+                fee: MicroTari(100),
+                lock_height: 0,
+            },
+            offset: offset.clone(),
+        };
+        let excess = spend.excess(&factories);
+
+        let secrets = vec![
+            &(&k_out - &shares[0]) - &offset,
+            &PrivateKey::default() - &shares[1],
+            &PrivateKey::default() - &shares[2],
+        ];
+        let mut sessions = secrets
+            .into_iter()
+            .map(|secret| MultiSigSession::new(secret, 3, spend.metadata.clone(), excess.clone()).unwrap())
+            .collect::<Vec<_>>();
+
+        let commitments = sessions
+            .iter()
+            .map(|s| (s.public_excess().clone(), s.nonce_commitment()))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            sessions[0].public_nonce(),
+            Err(TransactionProtocolError::IncompleteStateError(_))
+        ));
+        for (i, session) in sessions.iter_mut().enumerate() {
+            for (j, (public_excess, commitment)) in commitments.iter().enumerate() {
+                if i != j {
+                    session
+                        .add_nonce_commitment(public_excess.clone(), commitment.clone())
+                        .unwrap();
+                }
+            }
+        }
+
+        let nonces = sessions
+            .iter()
+            .map(|s| (s.public_excess().clone(), s.public_nonce().unwrap()))
+            .collect::<Vec<_>>();
+        assert!(sessions[0].add_public_nonce(&nonces[1].0, nonces[2].1.clone()).is_err());
+        for (i, session) in sessions.iter_mut().enumerate() {
+            for (j, (public_excess, nonce)) in nonces.iter().enumerate() {
+                if i != j {
+                    session.add_public_nonce(public_excess, nonce.clone()).unwrap();
+                }
+            }
+        }
+
+        let partials = sessions
+            .iter()
+            .map(|s| (s.public_excess().clone(), s.partial_signature().unwrap()))
+            .collect::<Vec<_>>();
+        for (i, session) in sessions.iter_mut().enumerate() {
+            for (j, (public_excess, signature)) in partials.iter().enumerate() {
+                if i != j {
+                    session.add_partial_signature(public_excess, signature.clone()).unwrap();
+                }
+            }
+        }
+
+        let signature = sessions[1].aggregate_signature().unwrap();
+        assert_eq!(signature, sessions[0].aggregate_signature().unwrap());
+        let tx = spend.build_transaction(&signature, &factories).unwrap();
+        assert_eq!(tx.body.kernels()[0].excess, excess);
+    }
+
+    #[test]
+    fn session_rejects_wrong_excess() {
+        let factories = CryptoFactories::default();
+        let (a, _) = PublicKey::random_keypair(&mut OsRng);
+        let (_, b) = PublicKey::random_keypair(&mut OsRng);
+        let excess = factories.commitment.commit_value(&PrivateKey::random(&mut OsRng), 0);
+        let mut session = MultiSigSession::new(a, 2, TransactionMetadata::default(), excess).unwrap();
+        assert!(session.add_nonce_commitment(b, vec![0u8; 32]).is_err());
+    }
+}
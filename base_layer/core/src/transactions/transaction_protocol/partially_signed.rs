@@ -0,0 +1,156 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A single round transaction can be negotiated without the sender's spending keys ever being online. The stages of
+//! the negotiation are captured in a [PartiallySignedTransaction] which can be written to a file and carried between
+//! the wallets involved:
+//!
+//! 1. An online wallet that does not hold the spending keys selects the inputs and exports an `Unsigned` transaction.
+//! 1. An offline wallet holding the spending keys rewinds the inputs, builds the sender's half of the transaction and
+//!    exports the `SenderMessage` for the recipient.
+//! 1. The recipient adds its output and partial signature and exports the `RecipientSigned` transaction.
+//! 1. The offline wallet signs and exports the `Finalized` transaction, which the online wallet broadcasts.
+
+use crate::transactions::{
+    tari_amount::MicroTari,
+    transaction::{Transaction, TransactionOutput},
+    transaction_protocol::{recipient::RecipientSignedMessage, sender::SingleRoundSenderData},
+    types::PublicKey,
+};
+use std::fmt;
+
+/// The version of the partially signed transaction format written by this library
+pub const PARTIALLY_SIGNED_TRANSACTION_VERSION: u32 = 1;
+
+/// The inputs and parameters of a transaction chosen by the online wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsignedTransaction {
+    /// The fee per gram the transaction pays
+    pub fee_per_gram: MicroTari,
+    /// The earliest block this transaction can be mined
+    pub lock_height: u64,
+    /// The outputs to spend. The signer rewinds these to recover their values and spending keys, which also proves
+    /// that the online wallet did not misrepresent their values.
+    pub inputs: Vec<TransactionOutput>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PartiallySignedTransactionStage {
+    /// Created by the online wallet, to be signed by the wallet holding the spending keys
+    Unsigned(UnsignedTransaction),
+    /// Created by the signer, to be answered by the recipient
+    SenderMessage(Box<SingleRoundSenderData>),
+    /// Created by the recipient, to be finalized by the signer
+    RecipientSigned(Box<RecipientSignedMessage>),
+    /// Created by the signer, to be broadcast by the online wallet. The id is the protocol id of the transaction that
+    /// the recipient knows it by.
+    Finalized(u64, Box<Transaction>),
+}
+
+impl fmt::Display for PartiallySignedTransactionStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartiallySignedTransactionStage::Unsigned(_) => write!(f, "Unsigned"),
+            PartiallySignedTransactionStage::SenderMessage(_) => write!(f, "SenderMessage"),
+            PartiallySignedTransactionStage::RecipientSigned(_) => write!(f, "RecipientSigned"),
+            PartiallySignedTransactionStage::Finalized(_, _) => write!(f, "Finalized"),
+        }
+    }
+}
+
+/// A single round transaction at one of the stages of its negotiation. Everything other than the stage stays the same
+/// from the moment the online wallet creates it.
+#[derive(Debug, Clone)]
+pub struct PartiallySignedTransaction {
+    /// The id the online wallet tracks the transaction with
+    pub tx_id: u64,
+    /// The amount being sent to the recipient
+    pub amount: MicroTari,
+    /// Plain text message to receiver
+    pub message: String,
+    /// The public key of the online wallet the transaction is sent from
+    pub sender_public_key: PublicKey,
+    /// The public key of the recipient wallet
+    pub recipient_public_key: PublicKey,
+    pub stage: PartiallySignedTransactionStage,
+}
+
+impl PartiallySignedTransaction {
+    /// Move the transaction on to its next stage
+    pub fn with_stage(self, stage: PartiallySignedTransactionStage) -> Self {
+        Self { stage, ..self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{
+        tari_amount::uT,
+        transaction::UnblindedOutput,
+        types::{CryptoFactories, PrivateKey},
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
+
+    #[test]
+    fn unsigned_transaction_round_trip() {
+        let factories = CryptoFactories::default();
+        let input = UnblindedOutput::new(10_000 * uT, PrivateKey::random(&mut OsRng), None)
+            .as_transaction_output(&factories)
+            .unwrap();
+        let (_, sender_public_key) = PublicKey::random_keypair(&mut OsRng);
+        let (_, recipient_public_key) = PublicKey::random_keypair(&mut OsRng);
+
+        let transaction = PartiallySignedTransaction {
+            tx_id: 42,
+            amount: 5_000 * uT,
+            message: "Cold storage".to_string(),
+            sender_public_key,
+            recipient_public_key,
+            stage: PartiallySignedTransactionStage::Unsigned(UnsignedTransaction {
+                fee_per_gram: 25 * uT,
+                lock_height: 0,
+                inputs: vec![input.clone()],
+            }),
+        };
+
+        let decoded = PartiallySignedTransaction::from_bytes(&transaction.to_bytes()).unwrap();
+        assert_eq!(decoded.tx_id, transaction.tx_id);
+        assert_eq!(decoded.amount, transaction.amount);
+        assert_eq!(decoded.message, transaction.message);
+        assert_eq!(decoded.sender_public_key, transaction.sender_public_key);
+        assert_eq!(decoded.recipient_public_key, transaction.recipient_public_key);
+        match decoded.stage {
+            PartiallySignedTransactionStage::Unsigned(unsigned) => {
+                assert_eq!(unsigned.fee_per_gram, 25 * uT);
+                assert_eq!(unsigned.inputs, vec![input]);
+            },
+            stage => panic!("Unexpected stage {}", stage),
+        }
+    }
+
+    #[test]
+    fn invalid_bytes_are_rejected() {
+        assert!(PartiallySignedTransaction::from_bytes(&[0xff, 0x01, 0x02]).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::protocol as proto;
+
+use crate::{
+    proto::types::Signature as ProtoSignature,
+    transactions::{
+        transaction::{TransactionInput, TransactionOutput},
+        transaction_protocol::multisig::{
+            MultiSigKeySetup,
+            MultiSigMessage,
+            MultiSigMessageBody,
+            MultiSigOutputInfo,
+            MultiSigParticipant,
+            MultiSigSpendData,
+        },
+        types::{BlindingFactor, Commitment, PublicKey, Signature},
+    },
+};
+use proto::multi_sig_message::Body as ProtoBody;
+use std::convert::{TryFrom, TryInto};
+use tari_crypto::tari_utilities::ByteArray;
+
+impl TryFrom<proto::MultiSigMessage> for MultiSigMessage {
+    type Error = String;
+
+    fn try_from(message: proto::MultiSigMessage) -> Result<Self, Self::Error> {
+        let body = match message
+            .body
+            .ok_or_else(|| "Multi-signature message body not provided".to_string())?
+        {
+            ProtoBody::KeySetupRequest(setup) => MultiSigMessageBody::KeySetupRequest(Box::new(setup.try_into()?)),
+            ProtoBody::PublicKeyShare(share) => MultiSigMessageBody::PublicKeyShare(
+                public_key_from_bytes(&share.share_public_key)?,
+                signature_from_proto(share.share_proof, "Key share proof not provided")?,
+            ),
+            ProtoBody::OutputFunded(output) => MultiSigMessageBody::OutputFunded(Box::new(output.try_into()?)),
+            ProtoBody::SpendRequest(request) => {
+                let message = request.message.clone();
+                MultiSigMessageBody::SpendRequest(Box::new(request.try_into()?), message)
+            },
+            ProtoBody::NonceCommitment(commitment) => MultiSigMessageBody::NonceCommitment(
+                public_key_from_bytes(&commitment.public_excess)?,
+                commitment.commitment,
+            ),
+            ProtoBody::PublicNonce(nonce) => MultiSigMessageBody::PublicNonce(
+                public_key_from_bytes(&nonce.public_excess)?,
+                public_key_from_bytes(&nonce.public_nonce)?,
+            ),
+            ProtoBody::PartialSignature(signature) => {
+                let public_excess = public_key_from_bytes(&signature.public_excess)?;
+                let signature = signature_from_proto(signature.signature, "Partial signature not provided")?;
+                MultiSigMessageBody::PartialSignature(public_excess, signature)
+            },
+        };
+
+        Ok(Self {
+            session_id: message.session_id,
+            body,
+        })
+    }
+}
+
+impl From<MultiSigMessage> for proto::MultiSigMessage {
+    fn from(message: MultiSigMessage) -> Self {
+        let body = match message.body {
+            MultiSigMessageBody::KeySetupRequest(setup) => ProtoBody::KeySetupRequest((*setup).into()),
+            MultiSigMessageBody::PublicKeyShare(share_public_key, share_proof) => {
+                ProtoBody::PublicKeyShare(proto::MultiSigPublicKeyShare {
+                    share_public_key: share_public_key.to_vec(),
+                    share_proof: Some(share_proof.into()),
+                })
+            },
+            MultiSigMessageBody::OutputFunded(output) => ProtoBody::OutputFunded((*output).into()),
+            MultiSigMessageBody::SpendRequest(spend, message) => {
+                let mut request = proto::MultiSigSpendRequest::from(*spend);
+                request.message = message;
+                ProtoBody::SpendRequest(request)
+            },
+            MultiSigMessageBody::NonceCommitment(public_excess, commitment) => {
+                ProtoBody::NonceCommitment(proto::MultiSigNonceCommitment {
+                    public_excess: public_excess.to_vec(),
+                    commitment,
+                })
+            },
+            MultiSigMessageBody::PublicNonce(public_excess, public_nonce) => {
+                ProtoBody::PublicNonce(proto::MultiSigPublicNonce {
+                    public_excess: public_excess.to_vec(),
+                    public_nonce: public_nonce.to_vec(),
+                })
+            },
+            MultiSigMessageBody::PartialSignature(public_excess, signature) => {
+                ProtoBody::PartialSignature(proto::MultiSigPartialSignature {
+                    public_excess: public_excess.to_vec(),
+                    signature: Some(signature.into()),
+                })
+            },
+        };
+
+        Self {
+            session_id: message.session_id,
+            body: Some(body),
+        }
+    }
+}
+
+impl TryFrom<proto::MultiSigKeySetup> for MultiSigKeySetup {
+    type Error = String;
+
+    fn try_from(setup: proto::MultiSigKeySetup) -> Result<Self, Self::Error> {
+        let features = setup
+            .features
+            .map(TryInto::try_into)
+            .ok_or_else(|| "Multi-signature output features not provided".to_string())??;
+        let participants = setup
+            .participants
+            .iter()
+            .map(|p| public_key_from_bytes(p))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            value: setup.value.into(),
+            features,
+            participants,
+        })
+    }
+}
+
+impl From<MultiSigKeySetup> for proto::MultiSigKeySetup {
+    fn from(setup: MultiSigKeySetup) -> Self {
+        Self {
+            value: setup.value.into(),
+            features: Some(setup.features.into()),
+            participants: setup.participants.iter().map(|p| p.to_vec()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::MultiSigOutputInfo> for MultiSigOutputInfo {
+    type Error = String;
+
+    fn try_from(output: proto::MultiSigOutputInfo) -> Result<Self, Self::Error> {
+        let commitment = output
+            .commitment
+            .map(Commitment::try_from)
+            .ok_or_else(|| "Multi-signature output commitment not provided".to_string())?
+            .map_err(|err| format!("{}", err))?;
+        let features = output
+            .features
+            .map(TryInto::try_into)
+            .ok_or_else(|| "Multi-signature output features not provided".to_string())??;
+        let participants = output
+            .participants
+            .into_iter()
+            .map(|p| {
+                Ok(MultiSigParticipant {
+                    comms_public_key: public_key_from_bytes(&p.comms_public_key)?,
+                    share_public_key: public_key_from_bytes(&p.share_public_key)?,
+                    share_proof: signature_from_proto(p.share_proof, "Key share proof not provided")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            commitment,
+            value: output.value.into(),
+            features,
+            participants,
+        })
+    }
+}
+
+impl From<MultiSigOutputInfo> for proto::MultiSigOutputInfo {
+    fn from(output: MultiSigOutputInfo) -> Self {
+        Self {
+            commitment: Some(output.commitment.into()),
+            value: output.value.into(),
+            features: Some(output.features.into()),
+            participants: output
+                .participants
+                .into_iter()
+                .map(|p| proto::MultiSigParticipant {
+                    comms_public_key: p.comms_public_key.to_vec(),
+                    share_public_key: p.share_public_key.to_vec(),
+                    share_proof: Some(p.share_proof.into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::MultiSigSpendRequest> for MultiSigSpendData {
+    type Error = String;
+
+    fn try_from(request: proto::MultiSigSpendRequest) -> Result<Self, Self::Error> {
+        let input = request
+            .input
+            .map(TransactionInput::try_from)
+            .ok_or_else(|| "Multi-signature input not provided".to_string())??;
+        let outputs = request
+            .outputs
+            .into_iter()
+            .map(TransactionOutput::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let metadata = request
+            .metadata
+            .map(Into::into)
+            .ok_or_else(|| "Transaction metadata not provided".to_string())?;
+        let offset = request
+            .offset
+            .map(BlindingFactor::try_from)
+            .ok_or_else(|| "Transaction offset not provided".to_string())?
+            .map_err(|err| format!("{}", err))?;
+
+        Ok(Self {
+            input,
+            outputs,
+            metadata,
+            offset,
+        })
+    }
+}
+
+impl From<MultiSigSpendData> for proto::MultiSigSpendRequest {
+    fn from(spend: MultiSigSpendData) -> Self {
+        Self {
+            input: Some(spend.input.into()),
+            outputs: spend.outputs.into_iter().map(Into::into).collect(),
+            metadata: Some(spend.metadata.into()),
+            offset: Some(spend.offset.into()),
+            message: String::new(),
+        }
+    }
+}
+
+fn public_key_from_bytes(bytes: &[u8]) -> Result<PublicKey, String> {
+    PublicKey::from_bytes(bytes).map_err(|err| format!("{}", err))
+}
+
+fn signature_from_proto(signature: Option<ProtoSignature>, missing: &str) -> Result<Signature, String> {
+    signature
+        .map(Signature::try_from)
+        .ok_or_else(|| missing.to_string())?
+        .map_err(|err| format!("{}", err))
+}
@@ -0,0 +1,152 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::protocol as proto;
+
+use crate::transactions::{
+    transaction::TransactionOutput,
+    transaction_protocol::partially_signed::{
+        PartiallySignedTransaction,
+        PartiallySignedTransactionStage,
+        UnsignedTransaction,
+        PARTIALLY_SIGNED_TRANSACTION_VERSION,
+    },
+    types::PublicKey,
+};
+use prost::Message;
+use proto::partially_signed_transaction::Stage as ProtoStage;
+use std::convert::{TryFrom, TryInto};
+use tari_comms::message::MessageExt;
+use tari_crypto::tari_utilities::ByteArray;
+
+impl PartiallySignedTransaction {
+    /// Encode the transaction in the protobuf file format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proto::PartiallySignedTransaction::from(self.clone()).to_encoded_bytes()
+    }
+
+    /// Decode a transaction from the protobuf file format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let message = proto::PartiallySignedTransaction::decode(bytes).map_err(|err| format!("{}", err))?;
+        Self::try_from(message)
+    }
+}
+
+impl TryFrom<proto::PartiallySignedTransaction> for PartiallySignedTransaction {
+    type Error = String;
+
+    fn try_from(message: proto::PartiallySignedTransaction) -> Result<Self, Self::Error> {
+        if message.version > PARTIALLY_SIGNED_TRANSACTION_VERSION {
+            return Err(format!(
+                "Partially signed transaction version {} is not supported",
+                message.version
+            ));
+        }
+
+        let sender_public_key = PublicKey::from_bytes(&message.sender_public_key).map_err(|err| format!("{}", err))?;
+        let recipient_public_key =
+            PublicKey::from_bytes(&message.recipient_public_key).map_err(|err| format!("{}", err))?;
+
+        let stage = match message
+            .stage
+            .ok_or_else(|| "Partially signed transaction stage not provided".to_string())?
+        {
+            ProtoStage::Unsigned(unsigned) => PartiallySignedTransactionStage::Unsigned(unsigned.try_into()?),
+            ProtoStage::SenderMessage(data) => {
+                PartiallySignedTransactionStage::SenderMessage(Box::new(data.try_into()?))
+            },
+            ProtoStage::RecipientSigned(data) => {
+                PartiallySignedTransactionStage::RecipientSigned(Box::new(data.try_into()?))
+            },
+            ProtoStage::Finalized(finalized) => {
+                let transaction = finalized
+                    .transaction
+                    .map(TryInto::try_into)
+                    .ok_or_else(|| "Finalized transaction not provided".to_string())??;
+                PartiallySignedTransactionStage::Finalized(finalized.tx_id, Box::new(transaction))
+            },
+        };
+
+        Ok(Self {
+            tx_id: message.tx_id,
+            amount: message.amount.into(),
+            message: message.message,
+            sender_public_key,
+            recipient_public_key,
+            stage,
+        })
+    }
+}
+
+impl From<PartiallySignedTransaction> for proto::PartiallySignedTransaction {
+    fn from(transaction: PartiallySignedTransaction) -> Self {
+        let stage = match transaction.stage {
+            PartiallySignedTransactionStage::Unsigned(unsigned) => ProtoStage::Unsigned(unsigned.into()),
+            PartiallySignedTransactionStage::SenderMessage(data) => ProtoStage::SenderMessage((*data).into()),
+            PartiallySignedTransactionStage::RecipientSigned(data) => ProtoStage::RecipientSigned((*data).into()),
+            PartiallySignedTransactionStage::Finalized(tx_id, tx) => {
+                ProtoStage::Finalized(proto::TransactionFinalizedMessage {
+                    tx_id,
+                    transaction: Some((*tx).into()),
+                })
+            },
+        };
+
+        Self {
+            version: PARTIALLY_SIGNED_TRANSACTION_VERSION,
+            tx_id: transaction.tx_id,
+            amount: transaction.amount.into(),
+            message: transaction.message,
+            sender_public_key: transaction.sender_public_key.to_vec(),
+            recipient_public_key: transaction.recipient_public_key.to_vec(),
+            stage: Some(stage),
+        }
+    }
+}
+
+impl TryFrom<proto::UnsignedTransaction> for UnsignedTransaction {
+    type Error = String;
+
+    fn try_from(unsigned: proto::UnsignedTransaction) -> Result<Self, Self::Error> {
+        let inputs = unsigned
+            .inputs
+            .into_iter()
+            .map(TransactionOutput::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            fee_per_gram: unsigned.fee_per_gram.into(),
+            lock_height: unsigned.lock_height,
+            inputs,
+        })
+    }
+}
+
+impl From<UnsignedTransaction> for proto::UnsignedTransaction {
+    fn from(unsigned: UnsignedTransaction) -> Self {
+        Self {
+            fee_per_gram: unsigned.fee_per_gram.into(),
+            lock_height: unsigned.lock_height,
+            inputs: unsigned.inputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
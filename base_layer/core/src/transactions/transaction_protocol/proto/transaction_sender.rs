@@ -21,7 +21,12 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use super::protocol as proto;
-use crate::transactions::transaction_protocol::sender::{SingleRoundSenderData, TransactionSenderMessage};
+use crate::transactions::transaction_protocol::sender::{
+    MultiRoundSenderData,
+    MultiRoundSignatureRequest,
+    SingleRoundSenderData,
+    TransactionSenderMessage,
+};
 
 use super::protocol::transaction_sender_message::Message as ProtoTransactionSenderMessage;
 use std::convert::{TryFrom, TryInto};
@@ -44,9 +49,9 @@ impl proto::TransactionSenderMessage {
         }
     }
 
-    pub fn multiple() -> Self {
+    pub fn multiple(data: proto::MultiRoundSenderData) -> Self {
         proto::TransactionSenderMessage {
-            message: Some(ProtoTxnSenderMessage::Multiple(true)),
+            message: Some(ProtoTxnSenderMessage::Multiple(data)),
         }
     }
 }
@@ -62,7 +67,7 @@ impl TryFrom<proto::TransactionSenderMessage> for TransactionSenderMessage {
         let sender_message = match inner_message {
             ProtoTxnSenderMessage::None(_) => TransactionSenderMessage::None,
             ProtoTxnSenderMessage::Single(data) => TransactionSenderMessage::Single(Box::new(data.try_into()?)),
-            ProtoTxnSenderMessage::Multiple(_) => TransactionSenderMessage::Multiple,
+            ProtoTxnSenderMessage::Multiple(data) => TransactionSenderMessage::Multiple(Box::new(data.try_into()?)),
         };
 
         Ok(sender_message)
@@ -76,7 +81,9 @@ impl From<TransactionSenderMessage> for proto::TransactionSenderMessage {
             TransactionSenderMessage::Single(sender_data) => {
                 ProtoTransactionSenderMessage::Single((*sender_data).into())
             },
-            TransactionSenderMessage::Multiple => ProtoTransactionSenderMessage::Multiple(true),
+            TransactionSenderMessage::Multiple(sender_data) => {
+                ProtoTransactionSenderMessage::Multiple((*sender_data).into())
+            },
         };
 
         Self { message: Some(message) }
@@ -122,3 +129,67 @@ impl From<SingleRoundSenderData> for proto::SingleRoundSenderData {
         }
     }
 }
+
+//---------------------------------- MultiRoundSenderData --------------------------------------------//
+
+impl TryFrom<proto::MultiRoundSenderData> for MultiRoundSenderData {
+    type Error = String;
+
+    fn try_from(data: proto::MultiRoundSenderData) -> Result<Self, Self::Error> {
+        let metadata = data
+            .metadata
+            .map(Into::into)
+            .ok_or_else(|| "Transaction metadata not provided".to_string())?;
+
+        Ok(Self {
+            tx_id: data.tx_id,
+            amount: data.amount.into(),
+            metadata,
+            message: data.message,
+        })
+    }
+}
+
+impl From<MultiRoundSenderData> for proto::MultiRoundSenderData {
+    fn from(sender_data: MultiRoundSenderData) -> Self {
+        Self {
+            tx_id: sender_data.tx_id,
+            amount: sender_data.amount.into(),
+            metadata: Some(sender_data.metadata.into()),
+            message: sender_data.message,
+        }
+    }
+}
+
+//---------------------------------- MultiRoundSignatureRequest --------------------------------------------//
+
+impl TryFrom<proto::MultiRoundSignatureRequest> for MultiRoundSignatureRequest {
+    type Error = String;
+
+    fn try_from(data: proto::MultiRoundSignatureRequest) -> Result<Self, Self::Error> {
+        let public_nonce_sum = PublicKey::from_bytes(&data.public_nonce_sum).map_err(|err| err.to_string())?;
+        let public_excess = PublicKey::from_bytes(&data.public_excess).map_err(|err| err.to_string())?;
+        let metadata = data
+            .metadata
+            .map(Into::into)
+            .ok_or_else(|| "Transaction metadata not provided".to_string())?;
+
+        Ok(Self {
+            tx_id: data.tx_id,
+            public_nonce_sum,
+            public_excess,
+            metadata,
+        })
+    }
+}
+
+impl From<MultiRoundSignatureRequest> for proto::MultiRoundSignatureRequest {
+    fn from(request: MultiRoundSignatureRequest) -> Self {
+        Self {
+            tx_id: request.tx_id,
+            public_nonce_sum: request.public_nonce_sum.to_vec(),
+            public_excess: request.public_excess.to_vec(),
+            metadata: Some(request.metadata.into()),
+        }
+    }
+}
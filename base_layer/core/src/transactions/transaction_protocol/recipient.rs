@@ -23,18 +23,29 @@
 use crate::transactions::{
     transaction::{OutputFeatures, TransactionOutput},
     transaction_protocol::{
-        sender::{SingleRoundSenderData as SD, TransactionSenderMessage},
+        multi_receiver::MultiReceiverTransactionProtocol,
+        sender::{
+            MultiRoundSenderData,
+            MultiRoundSignatureRequest,
+            SingleRoundSenderData as SD,
+            TransactionSenderMessage,
+        },
         single_receiver::SingleReceiverTransactionProtocol,
         RewindData,
+        TransactionMetadata,
         TransactionProtocolError,
     },
-    types::{CryptoFactories, MessageHash, PrivateKey, PublicKey, Signature},
+    types::{CryptoFactories, PrivateKey, PublicKey, Signature},
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt};
+use tari_crypto::keys::PublicKey as PublicKeyTrait;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum RecipientState {
+    /// The public keys have been sent to the sender in the multi-round protocol and the recipient is waiting for the
+    /// aggregated nonce and excess to sign with
+    AwaitingSignatureRequest(Box<MultiRoundRecipientData>),
     Finalized(Box<RecipientSignedMessage>),
     Failed(TransactionProtocolError),
 }
@@ -43,6 +54,7 @@ impl fmt::Display for RecipientState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use RecipientState::*;
         match self {
+            AwaitingSignatureRequest(data) => write!(f, "AwaitingSignatureRequest(tx_id = {})", data.tx_id),
             Finalized(signed_message) => write!(
                 f,
                 "Finalized({:?}, maturity = {})",
@@ -54,7 +66,7 @@ impl fmt::Display for RecipientState {
 }
 
 /// An enum describing the types of information that a recipient can send back to the receiver
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(super) enum RecipientInfo {
     None,
     Single(Option<Box<RecipientSignedMessage>>),
@@ -67,10 +79,41 @@ impl Default for RecipientInfo {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// The information the sender has collected from one of the recipients in the multi-round protocol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(super) struct MultiRecipientInfo {
-    pub commitment: MessageHash,
-    pub data: RecipientSignedMessage,
+    pub public_keys: RecipientPublicKeys,
+    pub signed_message: Option<RecipientSignedMessage>,
+}
+
+/// This is the message containing the public keys that a Receiver in the multi-round protocol will send back to the
+/// Sender before any signatures are produced
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecipientPublicKeys {
+    pub tx_id: u64,
+    pub public_spend_key: PublicKey,
+    pub public_nonce: PublicKey,
+}
+
+/// The data a Receiver in the multi-round protocol has to keep between sending its public keys and signing the
+/// transaction
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultiRoundRecipientData {
+    pub tx_id: u64,
+    pub metadata: TransactionMetadata,
+    pub output: TransactionOutput,
+    pub(super) nonce: PrivateKey,
+    pub(super) spending_key: PrivateKey,
+}
+
+impl MultiRoundRecipientData {
+    pub fn public_keys(&self) -> RecipientPublicKeys {
+        RecipientPublicKeys {
+            tx_id: self.tx_id,
+            public_spend_key: PublicKey::from_secret_key(&self.spending_key),
+            public_nonce: PublicKey::from_secret_key(&self.nonce),
+        }
+    }
 }
 
 /// This is the message containing the public data that the Receiver will send back to the Sender
@@ -111,7 +154,9 @@ impl ReceiverTransactionProtocol {
             TransactionSenderMessage::Single(v) => {
                 ReceiverTransactionProtocol::single_round(nonce, spending_key, features, &v, factories, None)
             },
-            TransactionSenderMessage::Multiple => Self::multi_round(),
+            TransactionSenderMessage::Multiple(v) => {
+                ReceiverTransactionProtocol::multi_round(nonce, spending_key, features, &v, factories, None)
+            },
         };
         ReceiverTransactionProtocol { state }
     }
@@ -137,7 +182,14 @@ impl ReceiverTransactionProtocol {
                 factories,
                 Some(rewind_data),
             ),
-            TransactionSenderMessage::Multiple => Self::multi_round(),
+            TransactionSenderMessage::Multiple(v) => ReceiverTransactionProtocol::multi_round(
+                nonce,
+                spending_key,
+                features,
+                &v,
+                factories,
+                Some(rewind_data),
+            ),
         };
         ReceiverTransactionProtocol { state }
     }
@@ -147,6 +199,12 @@ impl ReceiverTransactionProtocol {
         matches!(self.state, RecipientState::Finalized(_))
     }
 
+    /// Returns true if the recipient is taking part in the multi-round protocol and is waiting for the sender to
+    /// request its partial signature
+    pub fn is_awaiting_signature_request(&self) -> bool {
+        matches!(self.state, RecipientState::AwaitingSignatureRequest(_))
+    }
+
     /// Method to determine if the transaction protocol has failed
     pub fn is_failed(&self) -> bool {
         matches!(&self.state, RecipientState::Failed(_))
@@ -168,6 +226,37 @@ impl ReceiverTransactionProtocol {
         }
     }
 
+    /// Retrieve the public keys to be returned to the sender in the first round of the multi-round protocol
+    pub fn get_public_keys(&self) -> Result<RecipientPublicKeys, TransactionProtocolError> {
+        match &self.state {
+            RecipientState::AwaitingSignatureRequest(data) => Ok(data.public_keys()),
+            _ => Err(TransactionProtocolError::InvalidStateError),
+        }
+    }
+
+    /// Retrieve the output this recipient will receive, regardless of whether the protocol has been finalized yet
+    pub fn get_output(&self) -> Result<&TransactionOutput, TransactionProtocolError> {
+        match &self.state {
+            RecipientState::AwaitingSignatureRequest(data) => Ok(&data.output),
+            RecipientState::Finalized(data) => Ok(&data.output),
+            RecipientState::Failed(_) => Err(TransactionProtocolError::InvalidStateError),
+        }
+    }
+
+    /// Sign the aggregated transaction data sent by the sender in the second round of the multi-round protocol. If
+    /// the signature request is valid the protocol is finalized and the signed data can be retrieved with
+    /// `get_signed_data`.
+    pub fn sign_multi_round(&mut self, request: &MultiRoundSignatureRequest) -> Result<(), TransactionProtocolError> {
+        match &self.state {
+            RecipientState::AwaitingSignatureRequest(data) => {
+                let signed_data = MultiReceiverTransactionProtocol::sign(data, request)?;
+                self.state = RecipientState::Finalized(Box::new(signed_data));
+                Ok(())
+            },
+            _ => Err(TransactionProtocolError::InvalidStateError),
+        }
+    }
+
     /// Run the single-round recipient protocol, which can immediately construct an output and sign the data
     fn single_round(
         nonce: PrivateKey,
@@ -185,10 +274,21 @@ impl ReceiverTransactionProtocol {
         }
     }
 
-    fn multi_round() -> RecipientState {
-        RecipientState::Failed(TransactionProtocolError::UnsupportedError(
-            "Multiple recipients aren't supported yet".into(),
-        ))
+    /// Run the first round of the multi-round recipient protocol, which constructs the output and commits to the
+    /// public keys that will be used to sign once the sender has collected the keys of all the recipients
+    fn multi_round(
+        nonce: PrivateKey,
+        key: PrivateKey,
+        features: OutputFeatures,
+        data: &MultiRoundSenderData,
+        factories: &CryptoFactories,
+        rewind_data: Option<&RewindData>,
+    ) -> RecipientState
+    {
+        match MultiReceiverTransactionProtocol::create(data, nonce, key, features, factories, rewind_data) {
+            Ok(recipient_data) => RecipientState::AwaitingSignatureRequest(Box::new(recipient_data)),
+            Err(e) => RecipientState::Failed(e),
+        }
     }
 
     /// Create an empty SenderTransactionProtocol that can be used as a placeholder in data structures that do not
@@ -35,7 +35,7 @@ use crate::transactions::{
     },
     transaction_protocol::{
         build_challenge,
-        recipient::{RecipientInfo, RecipientSignedMessage},
+        recipient::{MultiRecipientInfo, RecipientInfo, RecipientPublicKeys, RecipientSignedMessage},
         transaction_initializer::SenderTransactionInitializer,
         TransactionMetadata,
         TransactionProtocolError as TPE,
@@ -45,7 +45,11 @@ use crate::transactions::{
 use digest::Digest;
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use tari_crypto::{ristretto::pedersen::PedersenCommitment, tari_utilities::ByteArray};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    ristretto::pedersen::PedersenCommitment,
+    tari_utilities::ByteArray,
+};
 
 //----------------------------------------   Local Data types     ----------------------------------------------------//
 
@@ -72,7 +76,7 @@ pub(super) struct RawTransactionInfo {
     pub public_nonce: PublicKey,
     // The sum of all public nonces
     pub public_nonce_sum: PublicKey,
-    #[serde(skip)]
+    #[serde(default)]
     pub recipient_info: RecipientInfo,
     pub signatures: Vec<Signature>,
     pub message: String,
@@ -94,12 +98,38 @@ pub struct SingleRoundSenderData {
     pub message: String,
 }
 
+/// The first message the sender sends to each of the recipients in the multi-round protocol
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MultiRoundSenderData {
+    /// The transaction id for the recipient
+    pub tx_id: u64,
+    /// The amount, in µT, being sent to the recipient
+    pub amount: MicroTari,
+    /// The transaction metadata
+    pub metadata: TransactionMetadata,
+    /// Plain text message to receiver
+    pub message: String,
+}
+
+/// The message the sender sends to each of the recipients in the multi-round protocol once the public keys of all the
+/// recipients have been collected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiRoundSignatureRequest {
+    /// The transaction id for the recipient
+    pub tx_id: u64,
+    /// The sum of the public nonces of the sender and all the recipients
+    pub public_nonce_sum: PublicKey,
+    /// The sum of the public excess of the sender and the public spend keys of all the recipients
+    pub public_excess: PublicKey,
+    /// The transaction metadata
+    pub metadata: TransactionMetadata,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransactionSenderMessage {
     None,
     Single(Box<SingleRoundSenderData>),
-    // TODO: Three round types
-    Multiple,
+    Multiple(Box<MultiRoundSenderData>),
 }
 
 impl TransactionSenderMessage {
@@ -107,12 +137,41 @@ impl TransactionSenderMessage {
         Self::Single(Box::new(single_round_data))
     }
 
+    pub fn new_multi_round_message(multi_round_data: MultiRoundSenderData) -> Self {
+        Self::Multiple(Box::new(multi_round_data))
+    }
+
     pub fn single(&self) -> Option<&SingleRoundSenderData> {
         match self {
             TransactionSenderMessage::Single(m) => Some(m),
             _ => None,
         }
     }
+
+    pub fn multiple(&self) -> Option<&MultiRoundSenderData> {
+        match self {
+            TransactionSenderMessage::Multiple(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// The transaction id for the recipient of this message, if there is one
+    pub fn tx_id(&self) -> Option<u64> {
+        match self {
+            TransactionSenderMessage::None => None,
+            TransactionSenderMessage::Single(m) => Some(m.tx_id),
+            TransactionSenderMessage::Multiple(m) => Some(m.tx_id),
+        }
+    }
+
+    /// The amount being sent to the recipient of this message, if there is one
+    pub fn amount(&self) -> Option<MicroTari> {
+        match self {
+            TransactionSenderMessage::None => None,
+            TransactionSenderMessage::Single(m) => Some(m.amount),
+            TransactionSenderMessage::Multiple(m) => Some(m.amount),
+        }
+    }
 }
 
 //----------------------------------------  Sender State Protocol ----------------------------------------------------//
@@ -138,6 +197,21 @@ impl SenderTransactionProtocol {
         matches!(&self.state, SenderState::SingleRoundMessageReady(_))
     }
 
+    /// Convenience method to check whether we're ready to send the first message to multiple recipients
+    pub fn is_multi_round_message_ready(&self) -> bool {
+        matches!(&self.state, SenderState::MultiRoundMessageReady(_))
+    }
+
+    /// Convenience method to check whether we're collecting the public keys of multiple recipients
+    pub fn is_collecting_public_keys(&self) -> bool {
+        matches!(&self.state, SenderState::CollectingPubKeys(_))
+    }
+
+    /// Convenience method to check whether we're collecting the partial signatures of multiple recipients
+    pub fn is_collecting_signatures(&self) -> bool {
+        matches!(&self.state, SenderState::CollectingSignatures(_))
+    }
+
     /// Method to determine if we are in the SenderState::Finalizing state
     pub fn is_finalizing(&self) -> bool {
         matches!(&self.state, SenderState::Finalizing(_))
@@ -178,12 +252,16 @@ impl SenderTransactionProtocol {
         }
     }
 
-    /// Method to check if the provided tx_id matches this transaction
+    /// Method to check if the provided tx_id matches this transaction. If there are multiple recipients, the tx_id of
+    /// any of the recipients will match.
     pub fn check_tx_id(&self, tx_id: u64) -> bool {
         match &self.state {
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => info.ids[0] == tx_id,
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => info.ids.contains(&tx_id),
             _ => false,
         }
     }
@@ -192,7 +270,24 @@ impl SenderTransactionProtocol {
         match &self.state {
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => Ok(info.ids[0]),
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.ids[0]),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Returns the tx_id used for each of the recipients, in the same order as the amounts were provided
+    pub fn get_recipient_tx_ids(&self) -> Result<Vec<u64>, TPE> {
+        match &self.state {
+            SenderState::Initializing(info) |
+            SenderState::Finalizing(info) |
+            SenderState::SingleRoundMessageReady(info) |
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.ids.iter().take(info.num_recipients).cloned().collect()),
             _ => Err(TPE::InvalidStateError),
         }
     }
@@ -202,7 +297,10 @@ impl SenderTransactionProtocol {
             SenderState::Initializing(info) |
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => Ok(info.amounts.iter().sum()),
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.amounts.iter().sum()),
             SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
             SenderState::Failed(_) => Err(TPE::InvalidStateError),
         }
@@ -214,7 +312,10 @@ impl SenderTransactionProtocol {
             SenderState::Initializing(info) |
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => Ok(info.amount_to_self),
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.amount_to_self),
             SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
             SenderState::Failed(_) => Err(TPE::InvalidStateError),
         }
@@ -226,7 +327,10 @@ impl SenderTransactionProtocol {
             SenderState::Initializing(info) |
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => Ok(info.change),
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.change),
             SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
             SenderState::Failed(_) => Err(TPE::InvalidStateError),
         }
@@ -238,7 +342,10 @@ impl SenderTransactionProtocol {
             SenderState::Initializing(info) |
             SenderState::Finalizing(info) |
             SenderState::SingleRoundMessageReady(info) |
-            SenderState::CollectingSingleSignature(info) => Ok(info.metadata.fee),
+            SenderState::CollectingSingleSignature(info) |
+            SenderState::MultiRoundMessageReady(info) |
+            SenderState::CollectingPubKeys(info) |
+            SenderState::CollectingSignatures(info) => Ok(info.metadata.fee),
             SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
             SenderState::Failed(_) => Err(TPE::InvalidStateError),
         }
@@ -300,6 +407,182 @@ impl SenderTransactionProtocol {
         }
     }
 
+    /// Build the sender's messages for each of the recipients in the multi-round protocol and move to the next state
+    pub fn build_multi_round_messages(&mut self) -> Result<Vec<MultiRoundSenderData>, TPE> {
+        match &self.state {
+            SenderState::MultiRoundMessageReady(info) => {
+                let result = self.get_multi_round_messages()?;
+                self.state = SenderState::CollectingPubKeys(info.clone());
+                Ok(result)
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Return the first round sender messages for each of the recipients in the multi-round protocol
+    pub fn get_multi_round_messages(&self) -> Result<Vec<MultiRoundSenderData>, TPE> {
+        match &self.state {
+            SenderState::MultiRoundMessageReady(info) | SenderState::CollectingPubKeys(info) => Ok(info
+                .ids
+                .iter()
+                .zip(info.amounts.iter())
+                .map(|(tx_id, amount)| MultiRoundSenderData {
+                    tx_id: *tx_id,
+                    amount: *amount,
+                    metadata: info.metadata.clone(),
+                    message: info.message.clone(),
+                })
+                .collect()),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Add the public keys of one of the recipients in the multi-round protocol. Once the keys of all the recipients
+    /// have been collected, the aggregate public nonce and excess are calculated and the protocol moves on to
+    /// collecting the partial signatures. Receiving the same keys more than once is not an error.
+    pub fn add_multi_recipient_public_keys(&mut self, keys: RecipientPublicKeys) -> Result<(), TPE> {
+        match &mut self.state {
+            SenderState::CollectingPubKeys(info) => {
+                if !info.ids.contains(&keys.tx_id) {
+                    return Err(TPE::ValidationError("Public keys are for an unknown recipient".into()));
+                }
+                let num_recipients = info.num_recipients;
+                let recipients = match &mut info.recipient_info {
+                    RecipientInfo::Multiple(recipients) => recipients,
+                    _ => return Err(TPE::InvalidStateError),
+                };
+                if let Some(existing) = recipients.get(&keys.tx_id) {
+                    if existing.public_keys == keys {
+                        return Ok(());
+                    }
+                    return Err(TPE::ValidationError(
+                        "Recipient has already provided different public keys".into(),
+                    ));
+                }
+                recipients.insert(keys.tx_id, MultiRecipientInfo {
+                    public_keys: keys,
+                    signed_message: None,
+                });
+                if recipients.len() < num_recipients {
+                    return Ok(());
+                }
+                let (public_excess, public_nonce_sum) = recipients.values().fold(
+                    (info.public_excess.clone(), info.public_nonce.clone()),
+                    |(excess, nonce), r| {
+                        (
+                            &excess + &r.public_keys.public_spend_key,
+                            &nonce + &r.public_keys.public_nonce,
+                        )
+                    },
+                );
+                info.public_excess = public_excess;
+                info.public_nonce_sum = public_nonce_sum;
+                self.state = SenderState::CollectingSignatures(info.clone());
+                Ok(())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Return the second round signature requests for each of the recipients in the multi-round protocol
+    pub fn get_multi_round_signature_requests(&self) -> Result<Vec<MultiRoundSignatureRequest>, TPE> {
+        match &self.state {
+            SenderState::CollectingSignatures(info) => Ok(info
+                .ids
+                .iter()
+                .take(info.num_recipients)
+                .map(|tx_id| MultiRoundSignatureRequest {
+                    tx_id: *tx_id,
+                    public_nonce_sum: info.public_nonce_sum.clone(),
+                    public_excess: info.public_excess.clone(),
+                    metadata: info.metadata.clone(),
+                })
+                .collect()),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Add the signed data from one of the recipients in the multi-round protocol. The output and partial signature
+    /// must match the public keys the recipient provided in the first round. Once all the recipients have signed, the
+    /// protocol moves to the Finalizing state.
+    pub fn add_multi_recipient_signature(
+        &mut self,
+        rec: RecipientSignedMessage,
+        factories: &CryptoFactories,
+    ) -> Result<(), TPE>
+    {
+        match &mut self.state {
+            SenderState::CollectingSignatures(info) => {
+                let amount = info
+                    .ids
+                    .iter()
+                    .position(|id| *id == rec.tx_id)
+                    .and_then(|i| info.amounts.get(i).cloned())
+                    .ok_or_else(|| TPE::ValidationError("Signature is for an unknown recipient".into()))?;
+                let e = build_challenge(&info.public_nonce_sum, &info.metadata);
+                let num_recipients = info.num_recipients;
+                let recipients = match &mut info.recipient_info {
+                    RecipientInfo::Multiple(recipients) => recipients,
+                    _ => return Err(TPE::InvalidStateError),
+                };
+                let recipient = recipients
+                    .get_mut(&rec.tx_id)
+                    .ok_or_else(|| TPE::ValidationError("Signature is for an unknown recipient".into()))?;
+                if let Some(existing) = &recipient.signed_message {
+                    if existing == &rec {
+                        return Ok(());
+                    }
+                    return Err(TPE::ValidationError(
+                        "Recipient has already provided a different signature".into(),
+                    ));
+                }
+                let keys = &recipient.public_keys;
+                if rec.public_spend_key != keys.public_spend_key {
+                    return Err(TPE::ValidationError(
+                        "Recipient public spend key does not match the first round".into(),
+                    ));
+                }
+                if !rec.output.verify_range_proof(&factories.range_proof)? {
+                    return Err(TPE::ValidationError(
+                        "Recipient output range proof failed to verify".into(),
+                    ));
+                }
+                let expected_commitment = &factories.commitment.commit_value(&PrivateKey::default(), amount.into()) +
+                    &PedersenCommitment::from_public_key(&keys.public_spend_key);
+                if rec.output.commitment != expected_commitment {
+                    return Err(TPE::ValidationError(
+                        "Recipient output does not commit to the expected amount".into(),
+                    ));
+                }
+                if rec.partial_signature.get_public_nonce() != &keys.public_nonce ||
+                    !rec.partial_signature.verify_challenge(&keys.public_spend_key, &e)
+                {
+                    return Err(TPE::ValidationError(
+                        "Recipient partial signature failed to verify".into(),
+                    ));
+                }
+                recipient.signed_message = Some(rec);
+                if recipients.values().filter(|r| r.signed_message.is_some()).count() < num_recipients {
+                    return Ok(());
+                }
+                // Consolidate transaction info in the same order as the recipients were provided
+                let mut signed_messages = Vec::with_capacity(num_recipients);
+                for tx_id in info.ids.iter().take(num_recipients) {
+                    if let Some(signed) = recipients.get(tx_id).and_then(|r| r.signed_message.clone()) {
+                        signed_messages.push(signed);
+                    }
+                }
+                for signed in signed_messages {
+                    info.outputs.push(signed.output);
+                    info.signatures.push(signed.partial_signature);
+                }
+                self.state = SenderState::Finalizing(info.clone());
+                Ok(())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
     /// Attempts to build the final transaction.
     fn build_transaction(
         info: &RawTransactionInfo,
@@ -429,6 +712,9 @@ impl SenderTransactionProtocol {
                 let data = serde_json::to_string(s).map_err(|_| TPE::SerializationError)?;
                 Ok(data)
             },
+            SenderState::MultiRoundMessageReady(_) => Err(TPE::InvalidStateError),
+            SenderState::CollectingPubKeys(_) => Err(TPE::InvalidStateError),
+            SenderState::CollectingSignatures(_) => Err(TPE::InvalidStateError),
             SenderState::Finalizing(_) => Err(TPE::InvalidStateError),
             SenderState::FinalizedTransaction(_) => Err(TPE::InvalidStateError),
             SenderState::Failed(_) => Err(TPE::InvalidStateError),
@@ -477,6 +763,12 @@ pub(super) enum SenderState {
     SingleRoundMessageReady(Box<RawTransactionInfo>),
     /// Waiting for the signed transaction data in the single-round protocol
     CollectingSingleSignature(Box<RawTransactionInfo>),
+    /// The first round messages for the recipients in a multi-round scheme are ready
+    MultiRoundMessageReady(Box<RawTransactionInfo>),
+    /// Waiting for the public keys of all the recipients in the multi-round protocol
+    CollectingPubKeys(Box<RawTransactionInfo>),
+    /// Waiting for the partial signatures of all the recipients in the multi-round protocol
+    CollectingSignatures(Box<RawTransactionInfo>),
     /// The final transaction state is being validated - it will automatically transition to Failed or Finalized from
     /// here
     Finalizing(Box<RawTransactionInfo>),
@@ -494,9 +786,7 @@ impl SenderState {
             SenderState::Initializing(info) => match info.num_recipients {
                 0 => Ok(SenderState::Finalizing(info)),
                 1 => Ok(SenderState::SingleRoundMessageReady(info)),
-                _ => Ok(SenderState::Failed(TPE::UnsupportedError(
-                    "Multiple recipients are not supported yet".into(),
-                ))),
+                _ => Ok(SenderState::MultiRoundMessageReady(info)),
             },
             _ => Err(TPE::InvalidTransitionError),
         }
@@ -525,6 +815,24 @@ impl fmt::Display for SenderState {
                 info.inputs.len(),
                 info.outputs.len()
             ),
+            MultiRoundMessageReady(info) => write!(
+                f,
+                "MultiRoundMessageReady({} input(s), {} output(s))",
+                info.inputs.len(),
+                info.outputs.len()
+            ),
+            CollectingPubKeys(info) => write!(
+                f,
+                "CollectingPubKeys({} input(s), {} output(s))",
+                info.inputs.len(),
+                info.outputs.len()
+            ),
+            CollectingSignatures(info) => write!(
+                f,
+                "CollectingSignatures({} input(s), {} output(s))",
+                info.inputs.len(),
+                info.outputs.len()
+            ),
             Finalizing(info) => write!(
                 f,
                 "Finalizing({} input(s), {} output(s))",
@@ -552,7 +860,8 @@ mod test {
         tari_amount::*,
         transaction::{KernelFeatures, OutputFeatures, UnblindedOutput},
         transaction_protocol::{
-            sender::SenderTransactionProtocol,
+            recipient::ReceiverTransactionProtocol,
+            sender::{SenderTransactionProtocol, TransactionSenderMessage},
             single_receiver::SingleReceiverTransactionProtocol,
             RewindData,
             TransactionProtocolError,
@@ -724,6 +1033,106 @@ mod test {
         assert!(tx.clone().validate_internal_consistency(&factories, None).is_ok());
     }
 
+    #[test]
+    fn multi_recipient_with_change() {
+        let factories = CryptoFactories::default();
+        // Alice's parameters
+        let a = TestParams::new();
+        // Bob's and Carol's parameters
+        let b = TestParams::new();
+        let c = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(25000), &factories.commitment);
+        let mut builder = SenderTransactionProtocol::builder(2);
+        let fee = Fee::calculate(MicroTari(20), 1, 1, 3);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(20))
+            .with_offset(a.offset.clone())
+            .with_private_nonce(a.nonce.clone())
+            .with_change_secret(a.change_key.clone())
+            .with_input(utxo.clone(), input)
+            .with_amount(0, MicroTari(5000))
+            .with_amount(1, MicroTari(3000));
+        let mut alice = builder.build::<Blake256>(&factories).unwrap();
+        assert!(alice.is_multi_round_message_ready());
+        let msgs = alice.build_multi_round_messages().unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].amount, MicroTari(5000));
+        assert_eq!(msgs[1].amount, MicroTari(3000));
+        assert_ne!(msgs[0].tx_id, msgs[1].tx_id);
+        assert!(alice.is_collecting_public_keys());
+        assert!(alice.check_tx_id(msgs[1].tx_id));
+
+        // Each receiver commits to its keys and output
+        let mut bob = ReceiverTransactionProtocol::new(
+            TransactionSenderMessage::new_multi_round_message(msgs[0].clone()),
+            b.nonce,
+            b.spend_key,
+            OutputFeatures::default(),
+            &factories,
+        );
+        let mut carol = ReceiverTransactionProtocol::new(
+            TransactionSenderMessage::new_multi_round_message(msgs[1].clone()),
+            c.nonce,
+            c.spend_key,
+            OutputFeatures::default(),
+            &factories,
+        );
+        assert!(bob.is_awaiting_signature_request());
+        assert!(carol.is_awaiting_signature_request());
+
+        // Signature requests are not available until all the public keys have been collected
+        alice
+            .add_multi_recipient_public_keys(bob.get_public_keys().unwrap())
+            .unwrap();
+        assert!(alice.is_collecting_public_keys());
+        assert!(alice.get_multi_round_signature_requests().is_err());
+        // Receiving the same keys twice is fine
+        alice
+            .add_multi_recipient_public_keys(bob.get_public_keys().unwrap())
+            .unwrap();
+        alice
+            .add_multi_recipient_public_keys(carol.get_public_keys().unwrap())
+            .unwrap();
+        assert!(alice.is_collecting_signatures());
+
+        let requests = alice.get_multi_round_signature_requests().unwrap();
+        assert_eq!(requests.len(), 2);
+        // A receiver can't sign a request meant for someone else
+        assert!(bob.sign_multi_round(&requests[1]).is_err());
+        bob.sign_multi_round(&requests[0]).unwrap();
+        carol.sign_multi_round(&requests[1]).unwrap();
+        assert!(bob.is_finalized());
+        assert!(carol.is_finalized());
+
+        // Carol's output can't be passed off as Bob's
+        let mut forged = bob.get_signed_data().unwrap().clone();
+        forged.output = carol.get_signed_data().unwrap().output.clone();
+        assert!(alice.add_multi_recipient_signature(forged, &factories).is_err());
+
+        alice
+            .add_multi_recipient_signature(carol.get_signed_data().unwrap().clone(), &factories)
+            .unwrap();
+        assert!(alice.is_collecting_signatures());
+        alice
+            .add_multi_recipient_signature(bob.get_signed_data().unwrap().clone(), &factories)
+            .unwrap();
+        assert!(alice.is_finalizing());
+        match alice.finalize(KernelFeatures::empty(), &factories) {
+            Ok(_) => (),
+            Err(e) => panic!("{:?}", e),
+        };
+
+        assert!(alice.is_finalized());
+        let tx = alice.get_transaction().unwrap();
+        assert_eq!(tx.offset, a.offset);
+        assert_eq!(tx.body.kernels()[0].fee, fee);
+        assert_eq!(tx.body.inputs().len(), 1);
+        assert_eq!(tx.body.inputs()[0], utxo);
+        assert_eq!(tx.body.outputs().len(), 3);
+        assert!(tx.clone().validate_internal_consistency(&factories, None).is_ok());
+    }
+
     #[test]
     fn single_recipient_range_proof_fail() {
         let factories = CryptoFactories::new(32);
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::transactions::{
+    tari_amount::MicroTari,
     transaction::{OutputFeatures, TransactionOutput},
     transaction_protocol::{
         build_challenge,
@@ -58,7 +59,7 @@ impl SingleReceiverTransactionProtocol {
     {
         SingleReceiverTransactionProtocol::validate_sender_data(sender_info)?;
         let output = SingleReceiverTransactionProtocol::build_output(
-            sender_info,
+            sender_info.amount,
             &spending_key,
             features,
             factories,
@@ -85,30 +86,28 @@ impl SingleReceiverTransactionProtocol {
         Ok(())
     }
 
-    fn build_output(
-        sender_info: &SD,
+    /// Construct the recipient's output for the given amount, with a rewindable range proof if `rewind_data` is
+    /// provided
+    pub(super) fn build_output(
+        amount: MicroTari,
         spending_key: &SK,
         features: OutputFeatures,
         factories: &CryptoFactories,
         rewind_data: Option<&RewindData>,
     ) -> Result<TransactionOutput, TPE>
     {
-        let commitment = factories
-            .commitment
-            .commit_value(&spending_key, sender_info.amount.into());
+        let commitment = factories.commitment.commit_value(&spending_key, amount.into());
 
         let proof = if let Some(rewind_data) = rewind_data {
             factories.range_proof.construct_proof_with_rewind_key(
                 &spending_key,
-                sender_info.amount.into(),
+                amount.into(),
                 &rewind_data.rewind_key,
                 &rewind_data.rewind_blinding_key,
                 &rewind_data.proof_message,
             )?
         } else {
-            factories
-                .range_proof
-                .construct_proof(&spending_key, sender_info.amount.into())?
+            factories.range_proof.construct_proof(&spending_key, amount.into())?
         };
         Ok(TransactionOutput::new(
             features,
@@ -401,11 +401,7 @@ mod test {
             helpers::{make_input, TestParams},
             tari_amount::*,
             transaction::{UnblindedOutput, MAX_TRANSACTION_INPUTS},
-            transaction_protocol::{
-                sender::SenderState,
-                transaction_initializer::SenderTransactionInitializer,
-                TransactionProtocolError,
-            },
+            transaction_protocol::{sender::SenderState, transaction_initializer::SenderTransactionInitializer},
             types::CryptoFactories,
         },
     };
@@ -616,10 +612,16 @@ mod test {
             .with_fee_per_gram(MicroTari(20));
         let result = builder.build::<Blake256>(&factories).unwrap();
         // Peek inside and check the results
-        if let SenderState::Failed(TransactionProtocolError::UnsupportedError(s)) = result.state {
-            assert_eq!(s, "Multiple recipients are not supported yet")
+        if let SenderState::MultiRoundMessageReady(info) = result.state {
+            assert_eq!(info.num_recipients, 2, "Number of receivers");
+            assert_eq!(info.ids.len(), 2, "Number of tx_ids");
+            assert_eq!(
+                info.amounts,
+                vec![MicroTari(1200), MicroTari(1100)],
+                "External payment amounts"
+            );
         } else {
-            panic!("We should not allow multiple recipients at this time");
+            panic!("There were two recipients, so we should be ready to send multi-round messages");
         }
     }
 
@@ -53,6 +53,7 @@ use tari_comms::protocol::rpc::mock::RpcRequestMock;
 use tari_core::{
     base_node::{
         comms_interface::Broadcast,
+        fee_estimator::MAX_CONFIRMATION_TARGET,
         proto::wallet_rpc::{
             TxLocation,
             TxQueryBatchResponse,
@@ -62,12 +63,13 @@ use tari_core::{
         },
         rpc::{BaseNodeWalletRpcService, BaseNodeWalletService},
         state_machine_service::states::{ListeningInfo, StateInfo, StatusInfo},
+        FeeEstimator,
     },
     chain_storage::ChainBlock,
     consensus::{ConsensusManager, ConsensusManagerBuilder, Network},
     crypto::tari_utilities::Hashable,
     proto::{
-        base_node::{FetchMatchingUtxos, Signatures as SignaturesProto},
+        base_node::{FeePerGramEstimateRequest, FetchMatchingUtxos, Signatures as SignaturesProto},
         types::{Signature as SignatureProto, Transaction as TransactionProto},
     },
     test_helpers::blockchain::TempDatabase,
@@ -114,10 +116,16 @@ fn setup() -> (
     });
 
     let request_mock = runtime.enter(|| RpcRequestMock::new(base_node.comms.peer_manager()));
+    let fee_estimator = FeeEstimator::new(
+        base_node.blockchain_db.clone().into(),
+        base_node.mempool.clone(),
+        consensus_manager.clone(),
+    );
     let service = BaseNodeWalletRpcService::new(
         base_node.blockchain_db.clone().into(),
         base_node.mempool_handle.clone(),
         base_node.state_machine_handle.clone(),
+        fee_estimator,
     );
     (
         service,
@@ -314,3 +322,30 @@ fn test_base_node_wallet_rpc() {
             .any(|u| u.as_transaction_output(&factories).unwrap().commitment == output.commitment));
     }
 }
+
+#[test]
+fn test_get_fee_per_gram_estimate() {
+    let (service, _base_node, request_mock, _consensus_manager, _block0, _utxo0, mut runtime, _temp_dir) = setup();
+
+    // There is no competition for block space on a new chain with an empty mempool
+    let msg = FeePerGramEstimateRequest { confirmation_target: 0 };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let response = runtime
+        .block_on(service.get_fee_per_gram_estimate(req))
+        .unwrap()
+        .into_message();
+    assert_eq!(response.fee_per_gram, 1);
+    assert_eq!(response.confirmation_target, 1);
+    assert_eq!(response.tip_height, 0);
+    assert!(response.is_synced);
+
+    let msg = FeePerGramEstimateRequest {
+        confirmation_target: MAX_CONFIRMATION_TARGET + 10,
+    };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let response = runtime
+        .block_on(service.get_fee_per_gram_estimate(req))
+        .unwrap()
+        .into_message();
+    assert_eq!(response.confirmation_target, MAX_CONFIRMATION_TARGET);
+}
@@ -22,14 +22,23 @@
 
 use crate::helpers::database::create_orphan_block;
 use tari_core::{
-    chain_storage::{create_lmdb_database, BlockchainBackend, ChainStorageError, DbKey, DbTransaction, DbValue},
+    chain_storage::{
+        create_lmdb_database,
+        BlockchainBackend,
+        ChainStorageError,
+        DbKey,
+        DbTransaction,
+        DbValue,
+        PrunedOutput,
+    },
     consensus::{ConsensusManagerBuilder, Network},
     test_helpers::blockchain::create_test_db,
+    transactions::{helpers::create_utxo, tari_amount::MicroTari, types::CryptoFactories},
     tx,
 };
 use tari_crypto::tari_utilities::Hashable;
 use tari_storage::lmdb_store::LMDBConfig;
-use tari_test_utils::paths::create_temporary_data_path;
+use tari_test_utils::{paths::create_temporary_data_path, unpack_enum};
 
 #[test]
 #[ignore = "Required for pruned mode"]
@@ -319,3 +328,52 @@ fn lmdb_file_lock() {
         }
     }
 }
+
+#[test]
+fn lmdb_restore_pruned_output() {
+    let factories = CryptoFactories::default();
+    let mut db = create_test_db();
+    let (output, _) = create_utxo(MicroTari(10_000), &factories, None);
+    let header_hash = vec![1u8; 32];
+
+    let mut txn = DbTransaction::new();
+    txn.insert_pruned_utxo(output.hash(), output.proof.hash(), header_hash.clone(), 0);
+    db.write(txn).unwrap();
+    let mut outputs = db.fetch_outputs_in_block(&header_hash).unwrap();
+    assert_eq!(outputs.len(), 1);
+    unpack_enum!(
+        PrunedOutput::Pruned {
+            output_hash,
+            range_proof_hash
+        } = outputs.remove(0)
+    );
+    assert_eq!(output_hash, output.hash());
+    assert_eq!(range_proof_hash, output.proof.hash());
+
+    // The output must be restored into the block that contains it
+    let mut txn = DbTransaction::new();
+    txn.restore_pruned_output(output.clone(), vec![2u8; 32]);
+    let err = db.write(txn).unwrap_err();
+    unpack_enum!(ChainStorageError::InvalidOperation(_s) = err);
+
+    // The range proof must match the stored range proof hash
+    let (other_output, _) = create_utxo(MicroTari(10_000), &factories, None);
+    let mut invalid_output = output.clone();
+    invalid_output.proof = other_output.proof;
+    assert_eq!(invalid_output.hash(), output.hash());
+    let mut txn = DbTransaction::new();
+    txn.restore_pruned_output(invalid_output, header_hash.clone());
+    let err = db.write(txn).unwrap_err();
+    unpack_enum!(ChainStorageError::InvalidOperation(_s) = err);
+
+    let mut outputs = db.fetch_outputs_in_block(&header_hash).unwrap();
+    unpack_enum!(PrunedOutput::Pruned { .. } = outputs.remove(0));
+
+    let mut txn = DbTransaction::new();
+    txn.restore_pruned_output(output.clone(), header_hash.clone());
+    db.write(txn).unwrap();
+    let mut outputs = db.fetch_outputs_in_block(&header_hash).unwrap();
+    assert_eq!(outputs.len(), 1);
+    unpack_enum!(PrunedOutput::NotPruned { output: restored } = outputs.remove(0));
+    assert_eq!(restored, output);
+}
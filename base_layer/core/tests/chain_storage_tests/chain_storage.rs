@@ -239,6 +239,14 @@ fn rewind_to_height() {
     assert_eq!(db.get_height().unwrap(), 3);
     db.rewind_to_height(1).unwrap();
     assert_eq!(db.get_height().unwrap(), 1);
+
+    // Each rewind is recorded in the reorg history
+    let reorgs = db.fetch_all_reorgs().unwrap();
+    assert_eq!(reorgs.len(), 2);
+    assert_eq!(reorgs[1].fork_height, 1);
+    assert_eq!(reorgs[1].new_height, 1);
+    assert_eq!(reorgs[1].removed_blocks.len(), 2);
+    assert!(reorgs[1].added_blocks.is_empty());
 }
 
 #[test]
@@ -536,6 +544,23 @@ fn handle_reorg() {
         .assert_reorg(3, 3); // B3
     assert_eq!(store.fetch_tip_header().unwrap().header, orphan2_blocks[4].block.header);
 
+    // Check that the reorg was recorded in the reorg history
+    let reorgs = store.fetch_all_reorgs().unwrap();
+    assert_eq!(reorgs.len(), 1);
+    assert_eq!(reorgs[0].fork_height, 1);
+    assert_eq!(&reorgs[0].prev_hash, blocks[4].hash());
+    assert_eq!(&reorgs[0].new_hash, orphan2_blocks[4].hash());
+    assert_eq!(reorgs[0].removed_blocks, vec![
+        blocks[4].hash().clone(),
+        blocks[3].hash().clone(),
+        blocks[2].hash().clone()
+    ]);
+    assert_eq!(reorgs[0].added_blocks, vec![
+        orphan1_blocks[2].hash().clone(),
+        orphan1_blocks[3].hash().clone(),
+        orphan2_blocks[4].hash().clone()
+    ]);
+
     // Check that B2,B3 and C4 were removed from the block orphans and A2,A3,A4 and B4 has been orphaned.
     assert!(store.fetch_orphan(orphan1_blocks[2].hash().clone()).is_err()); // B2
     assert!(store.fetch_orphan(orphan1_blocks[3].hash().clone()).is_err()); // B3
@@ -616,6 +641,14 @@ fn handle_reorg_with_no_removed_blocks() {
         _ => panic!(),
     }
 
+    let reorgs = store.fetch_all_reorgs().unwrap();
+    assert_eq!(reorgs.len(), 1);
+    assert_eq!(reorgs[0].fork_height, 1);
+    assert_eq!(reorgs[0].prev_height, 1);
+    assert_eq!(&reorgs[0].prev_hash, blocks[1].hash());
+    assert!(reorgs[0].removed_blocks.is_empty());
+    assert_eq!(reorgs[0].new_height, 3);
+
     assert_eq!(store.fetch_tip_header().unwrap().header, orphan1_blocks[3].block.header);
 }
 
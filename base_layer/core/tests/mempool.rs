@@ -32,7 +32,12 @@ use helpers::{
         generate_block,
         generate_new_block,
     },
-    nodes::{create_network_with_2_base_nodes_with_config, create_network_with_3_base_nodes_with_config},
+    event_stream::event_stream_next,
+    nodes::{
+        create_network_with_2_base_nodes_with_config,
+        create_network_with_3_base_nodes_with_config,
+        BaseNodeBuilder,
+    },
     sample_blockchains::create_new_blockchain,
 };
 // use crate::helpers::database::create_store;
@@ -46,7 +51,15 @@ use tari_core::{
     },
     chain_storage::BlockchainDatabaseConfig,
     consensus::{ConsensusConstantsBuilder, ConsensusManagerBuilder, Network},
-    mempool::{Mempool, MempoolConfig, MempoolServiceConfig, MempoolServiceError, TxStorageResponse},
+    mempool::{
+        Mempool,
+        MempoolConfig,
+        MempoolServiceConfig,
+        MempoolServiceError,
+        MempoolTransactionEvent,
+        TransactionRemovalReason,
+        TxStorageResponse,
+    },
     proof_of_work::Difficulty,
     proto,
     transactions::{
@@ -327,6 +340,48 @@ fn test_retrieve() {
     assert!(retrieved_txs.contains(&tx2[1]));
 }
 
+#[test]
+#[allow(clippy::identity_op)]
+fn test_insert_child_of_unconfirmed_parent() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(store.clone());
+    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(mempool_validator));
+    let txs = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![1 * T, 1 * T])];
+    generate_new_block(&mut store, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+    mempool.process_published_block(blocks[1].block.clone().into()).unwrap();
+
+    let (parent, parent_outputs, _) =
+        spend_utxos(txn_schema!(from: vec![outputs[1][0].clone()], to: vec![500_000 * uT], fee: 5 * uT));
+    let (child, _, _) = spend_utxos(txn_schema!(from: vec![parent_outputs[0].clone()], to: vec![], fee: 100 * uT));
+    let (tx, _, _) = spend_utxos(txn_schema!(from: vec![outputs[1][1].clone()], to: vec![], fee: 20 * uT));
+    let parent = Arc::new(parent);
+    let child = Arc::new(child);
+    let tx = Arc::new(tx);
+
+    // The child is an orphan until its parent is in the mempool
+    assert_eq!(
+        mempool.insert(child.clone()).unwrap(),
+        TxStorageResponse::NotStoredOrphan
+    );
+    assert_eq!(
+        mempool.insert(parent.clone()).unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(
+        mempool.insert(child.clone()).unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(mempool.insert(tx.clone()).unwrap(), TxStorageResponse::UnconfirmedPool);
+    let stats = mempool.stats().unwrap();
+    assert_eq!(stats.unconfirmed_txs, 3);
+
+    // The child pays for its parent, so the package is selected ahead of tx
+    let weight = parent.calculate_weight() + child.calculate_weight();
+    let retrieved_txs = mempool.retrieve(weight).unwrap();
+    assert_eq!(retrieved_txs, vec![parent, child]);
+}
+
 #[test]
 #[allow(clippy::identity_op)]
 fn test_reorg() {
@@ -666,6 +721,132 @@ fn service_request_timeout() {
     });
 }
 
+#[test]
+#[allow(clippy::identity_op)]
+fn transaction_added_and_mined_events() {
+    let factories = CryptoFactories::default();
+    let network = Network::LocalNet;
+    let consensus_constants = network.create_consensus_constants();
+    let mut runtime = Runtime::new().unwrap();
+    let temp_dir = tempdir().unwrap();
+    let (block0, utxos0) =
+        create_genesis_block_with_coinbase_value(&factories, 100_000_000.into(), &consensus_constants[0]);
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_consensus_constants(consensus_constants[0].clone())
+        .with_block(block0.clone())
+        .build();
+    let (mut node, consensus_manager) = BaseNodeBuilder::new(network)
+        .with_consensus_manager(consensus_manager)
+        .start(&mut runtime, temp_dir.path().to_str().unwrap());
+    node.mock_base_node_state_machine.publish_status(StatusInfo {
+        bootstrapped: true,
+        state_info: StateInfo::Listening(ListeningInfo::new(true)),
+    });
+
+    let (txs, _) = schema_to_transaction(&[txn_schema!(from: vec![utxos0], to: vec![1 * T, 1 * T])]);
+    let tx = (*txs[0]).clone();
+    let tx_excess_sig = tx.body.kernels()[0].excess_sig.clone();
+    let mut block1 = node
+        .blockchain_db
+        .prepare_block_merkle_roots(chain_block(&block0.block, vec![tx.clone()], &consensus_manager))
+        .unwrap();
+    find_header_with_achieved_difficulty(&mut block1.header, Difficulty::from(1));
+
+    let mut mempool_events = node.local_mp_interface.get_mempool_transaction_event_stream();
+    runtime.block_on(async {
+        let tx_storage = node.local_mp_interface.submit_transaction(tx).await.unwrap();
+        assert_eq!(tx_storage, TxStorageResponse::UnconfirmedPool);
+        match event_stream_next(&mut mempool_events, Duration::from_secs(10)).await {
+            Some(Ok(MempoolTransactionEvent::Added(added))) => {
+                assert_eq!(added.body.kernels()[0].excess_sig, tx_excess_sig);
+            },
+            event => panic!("Unexpected mempool event: {:?}", event),
+        }
+
+        node.local_nci
+            .submit_block(block1, Broadcast::from(true))
+            .await
+            .unwrap();
+        match event_stream_next(&mut mempool_events, Duration::from_secs(10)).await {
+            Some(Ok(MempoolTransactionEvent::Removed(removed, reason))) => {
+                assert_eq!(removed.body.kernels()[0].excess_sig, tx_excess_sig);
+                assert_eq!(reason, TransactionRemovalReason::Mined);
+            },
+            event => panic!("Unexpected mempool event: {:?}", event),
+        }
+    });
+}
+
+#[test]
+fn transaction_events_of_a_large_block_are_not_missed() {
+    let factories = CryptoFactories::default();
+    let network = Network::LocalNet;
+    let consensus_constants = network.create_consensus_constants();
+    let mut runtime = Runtime::new().unwrap();
+    let temp_dir = tempdir().unwrap();
+    let (block0, utxos0) =
+        create_genesis_block_with_coinbase_value(&factories, 100_000_000.into(), &consensus_constants[0]);
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_consensus_constants(consensus_constants[0].clone())
+        .with_block(block0.clone())
+        .build();
+    let (mut node, consensus_manager) = BaseNodeBuilder::new(network)
+        .with_consensus_manager(consensus_manager)
+        .start(&mut runtime, temp_dir.path().to_str().unwrap());
+    node.mock_base_node_state_machine.publish_status(StatusInfo {
+        bootstrapped: true,
+        state_info: StateInfo::Listening(ListeningInfo::new(true)),
+    });
+
+    // More transactions than the mempool state events are buffered for
+    let num_txs = 150;
+    let (txs, outputs) = schema_to_transaction(&[txn_schema!(from: vec![utxos0], to: vec![100_000 * uT; num_txs])]);
+    let mut block1 = node
+        .blockchain_db
+        .prepare_block_merkle_roots(chain_block(&block0.block, vec![(*txs[0]).clone()], &consensus_manager))
+        .unwrap();
+    find_header_with_achieved_difficulty(&mut block1.header, Difficulty::from(1));
+    let (txs, _) = schema_to_transaction(
+        &outputs[..num_txs]
+            .iter()
+            .map(|output| txn_schema!(from: vec![output.clone()], to: vec![50_000 * uT]))
+            .collect::<Vec<_>>(),
+    );
+    let txs = txs.into_iter().map(|tx| (*tx).clone()).collect::<Vec<_>>();
+    let mut block2 = node
+        .blockchain_db
+        .prepare_block_merkle_roots(chain_block(&block1, txs.clone(), &consensus_manager))
+        .unwrap();
+    find_header_with_achieved_difficulty(&mut block2.header, Difficulty::from(1));
+
+    let mut mempool_events = node.local_mp_interface.get_mempool_transaction_event_stream();
+    runtime.block_on(async {
+        node.local_nci
+            .submit_block(block1, Broadcast::from(false))
+            .await
+            .unwrap();
+        for tx in txs {
+            let tx_storage = node.local_mp_interface.submit_transaction(tx).await.unwrap();
+            assert_eq!(tx_storage, TxStorageResponse::UnconfirmedPool);
+        }
+        node.local_nci
+            .submit_block(block2, Broadcast::from(false))
+            .await
+            .unwrap();
+
+        let mut num_added = 0;
+        let mut num_mined = 0;
+        while num_mined < num_txs {
+            match event_stream_next(&mut mempool_events, Duration::from_secs(10)).await {
+                Some(Ok(MempoolTransactionEvent::Added(_))) => num_added += 1,
+                Some(Ok(MempoolTransactionEvent::Removed(_, TransactionRemovalReason::Mined))) => num_mined += 1,
+                event => panic!("Unexpected mempool event: {:?}", event),
+            }
+        }
+        assert_eq!(num_added, num_txs);
+    });
+}
+
 #[test]
 #[ignore = "Flaky test that needs to be fixed"]
 #[allow(clippy::identity_op)]
@@ -133,6 +133,28 @@ where
         self.derive_key(self.primary_key_index)
     }
 
+    /// Derive a new private key from master key on this key manager's branch:
+    /// derived_key=SHA256(master_key||branch_seed||index). Unlike `derive_key` the branch seed is part of the
+    /// derivation, so each branch of the same master key produces an independent sequence of keys.
+    pub fn derive_branch_key(&self, key_index: u64) -> Result<DerivedKey<K>, ByteArrayError> {
+        let concatenated = format!(
+            "{}{}{}",
+            self.master_key.to_hex(),
+            self.branch_seed,
+            key_index.to_string()
+        );
+        match K::from_bytes(D::digest(&concatenated.into_bytes()).as_slice()) {
+            Ok(k) => Ok(DerivedKey { k, key_index }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Generate next deterministic private key derived from master key on this key manager's branch
+    pub fn next_branch_key(&mut self) -> Result<DerivedKey<K>, ByteArrayError> {
+        self.primary_key_index += 1;
+        self.derive_branch_key(self.primary_key_index)
+    }
+
     pub fn master_key(&self) -> &K {
         &self.master_key
     }
@@ -216,6 +238,20 @@ mod test {
         assert_eq!(next_key2.key_index, desired_key_index2);
     }
 
+    #[test]
+    fn test_derive_and_next_branch_key() {
+        let km = KeyManager::<RistrettoSecretKey, Sha256>::new(&mut OsRng);
+        let mut km_a = KeyManager::<RistrettoSecretKey, Sha256>::from(km.master_key.clone(), "a".to_string(), 0);
+        let km_b = KeyManager::<RistrettoSecretKey, Sha256>::from(km.master_key.clone(), "b".to_string(), 0);
+        let next_key = km_a.next_branch_key().unwrap();
+        assert_eq!(next_key.key_index, 1);
+        assert_eq!(next_key.k, km_a.derive_branch_key(1).unwrap().k);
+        assert_ne!(next_key.k, km_b.derive_branch_key(1).unwrap().k);
+        assert_ne!(next_key.k, km.derive_key(1).unwrap().k);
+        // The branch seed is not part of the original derivation
+        assert_eq!(km_a.derive_key(1).unwrap().k, km_b.derive_key(1).unwrap().k);
+    }
+
     #[test]
     fn test_to_file_and_from_file() {
         let desired_km = KeyManager::<RistrettoSecretKey, Sha256>::new(&mut OsRng);
@@ -0,0 +1,2 @@
+ALTER TABLE outputs
+    ADD COLUMN received_in_tx_id INTEGER NULL DEFAULT NULL;
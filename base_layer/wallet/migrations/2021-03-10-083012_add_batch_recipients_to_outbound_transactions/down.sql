@@ -0,0 +1,2 @@
+ALTER TABLE outbound_transactions
+    DROP COLUMN batch_recipients;
@@ -0,0 +1,2 @@
+ALTER TABLE outbound_transactions
+    ADD COLUMN batch_recipients TEXT NULL DEFAULT NULL;
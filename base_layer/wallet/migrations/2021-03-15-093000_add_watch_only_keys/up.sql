@@ -0,0 +1,6 @@
+CREATE TABLE watch_only_keys (
+    id INTEGER PRIMARY KEY,
+    rewind_key BLOB NOT NULL,
+    rewind_blinding_key BLOB NOT NULL,
+    timestamp DATETIME NOT NULL
+);
@@ -0,0 +1,6 @@
+ALTER TABLE outputs
+    DROP COLUMN label;
+ALTER TABLE outputs
+    DROP COLUMN note;
+ALTER TABLE outputs
+    DROP COLUMN frozen;
@@ -0,0 +1,6 @@
+ALTER TABLE outputs
+    ADD COLUMN label TEXT NULL DEFAULT NULL;
+ALTER TABLE outputs
+    ADD COLUMN note TEXT NULL DEFAULT NULL;
+ALTER TABLE outputs
+    ADD COLUMN frozen INTEGER NOT NULL DEFAULT 0;
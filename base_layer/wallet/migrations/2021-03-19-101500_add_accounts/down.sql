@@ -0,0 +1,4 @@
+DROP TABLE accounts;
+DROP TABLE account_transactions;
+ALTER TABLE outputs
+    DROP COLUMN account_id;
@@ -0,0 +1,14 @@
+CREATE TABLE accounts (
+    id BIGINT PRIMARY KEY NOT NULL,
+    name TEXT UNIQUE NOT NULL,
+    primary_key_index BIGINT NOT NULL,
+    active INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE account_transactions (
+    tx_id BIGINT PRIMARY KEY NOT NULL,
+    account_id BIGINT NOT NULL
+);
+
+ALTER TABLE outputs
+    ADD COLUMN account_id BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+ALTER TABLE pending_transaction_outputs
+    DROP COLUMN fee_bump_of;
+ALTER TABLE pending_transaction_outputs
+    DROP COLUMN fee_bump_excess;
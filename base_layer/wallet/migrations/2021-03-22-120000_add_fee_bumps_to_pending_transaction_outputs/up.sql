@@ -0,0 +1,4 @@
+ALTER TABLE pending_transaction_outputs
+    ADD COLUMN fee_bump_of BIGINT NULL DEFAULT NULL;
+ALTER TABLE pending_transaction_outputs
+    ADD COLUMN fee_bump_excess BLOB NULL DEFAULT NULL;
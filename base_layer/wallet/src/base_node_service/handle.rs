@@ -26,6 +26,7 @@ use std::sync::Arc;
 use tari_comms::peer_manager::Peer;
 
 use tari_common_types::chain_metadata::ChainMetadata;
+use tari_core::transactions::tari_amount::MicroTari;
 use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
@@ -37,12 +38,14 @@ pub type BaseNodeEventReceiver = broadcast::Receiver<Arc<BaseNodeEvent>>;
 pub enum BaseNodeServiceRequest {
     GetChainMetadata,
     SetBaseNodePeer(Box<Peer>),
+    GetFeePerGramEstimate(u64),
 }
 /// API Response enum
 #[derive(Debug)]
 pub enum BaseNodeServiceResponse {
     ChainMetadata(Option<ChainMetadata>),
     BaseNodePeerSet,
+    FeePerGramEstimate(MicroTari),
 }
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum BaseNodeEvent {
@@ -91,4 +94,20 @@ impl BaseNodeServiceHandle {
             _ => Err(BaseNodeServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Asks the connected base node for the fee per gram needed to be mined within `confirmation_target` blocks
+    pub async fn get_fee_per_gram_estimate(
+        &mut self,
+        confirmation_target: u64,
+    ) -> Result<MicroTari, BaseNodeServiceError>
+    {
+        match self
+            .handle
+            .call(BaseNodeServiceRequest::GetFeePerGramEstimate(confirmation_target))
+            .await??
+        {
+            BaseNodeServiceResponse::FeePerGramEstimate(fee_per_gram) => Ok(fee_per_gram),
+            _ => Err(BaseNodeServiceError::UnexpectedApiResponse),
+        }
+    }
 }
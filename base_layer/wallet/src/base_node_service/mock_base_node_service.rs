@@ -28,6 +28,7 @@ use crate::base_node_service::{
 use futures::{pin_mut, StreamExt};
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::peer_manager::Peer;
+use tari_core::transactions::tari_amount::MicroTari;
 use tari_service_framework::reply_channel::Receiver;
 use tari_shutdown::ShutdownSignal;
 
@@ -36,6 +37,7 @@ pub struct MockBaseNodeService {
     request_stream: Option<Receiver<BaseNodeServiceRequest, Result<BaseNodeServiceResponse, BaseNodeServiceError>>>,
     pub base_node_peer: Option<Peer>,
     pub state: BaseNodeState,
+    pub fee_per_gram_estimate: MicroTari,
     shutdown_signal: Option<ShutdownSignal>,
 }
 
@@ -51,6 +53,7 @@ impl MockBaseNodeService {
             request_stream: Some(request_stream),
             base_node_peer,
             state,
+            fee_per_gram_estimate: MicroTari(25),
             shutdown_signal: Some(shutdown_signal),
         }
     }
@@ -129,6 +132,9 @@ impl MockBaseNodeService {
                 self.set_base_node_peer(*peer);
                 Ok(BaseNodeServiceResponse::BaseNodePeerSet)
             },
+            BaseNodeServiceRequest::GetFeePerGramEstimate(_) => {
+                Ok(BaseNodeServiceResponse::FeePerGramEstimate(self.fee_per_gram_estimate))
+            },
             BaseNodeServiceRequest::GetChainMetadata => Ok(BaseNodeServiceResponse::ChainMetadata(
                 self.state.chain_metadata.clone(),
             )),
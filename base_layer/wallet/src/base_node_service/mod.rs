@@ -34,6 +34,7 @@ use crate::{
 use futures::{future, Future, Stream, StreamExt};
 use log::*;
 use std::sync::Arc;
+use tari_comms::connectivity::ConnectivityRequester;
 use tari_comms_dht::Dht;
 use tari_core::proto::base_node as proto;
 use tari_p2p::{
@@ -114,12 +115,14 @@ where T: WalletBackend + 'static
         context.spawn_when_ready(move |handles| async move {
             let dht = handles.expect_handle::<Dht>();
             let outbound_messaging = dht.outbound_requester();
+            let connectivity = handles.expect_handle::<ConnectivityRequester>();
 
             let service = BaseNodeService::new(
                 config,
                 base_node_response_stream,
                 request_stream,
                 outbound_messaging,
+                connectivity,
                 event_publisher,
                 handles.get_shutdown_signal(),
                 db,
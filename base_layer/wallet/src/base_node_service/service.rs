@@ -40,9 +40,16 @@ use tari_common_types::{
     chain_metadata::ChainMetadata,
     waiting_requests::{generate_request_key, RequestKey},
 };
-use tari_comms::peer_manager::Peer;
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::Peer};
 use tari_comms_dht::{domain_message::OutboundDomainMessage, outbound::OutboundMessageRequester};
-use tari_core::proto::{base_node as proto, base_node::base_node_service_request::Request as BaseNodeRequestProto};
+use tari_core::{
+    base_node::rpc::BaseNodeWalletRpcClient,
+    proto::{
+        base_node as proto,
+        base_node::{base_node_service_request::Request as BaseNodeRequestProto, FeePerGramEstimateRequest},
+    },
+    transactions::tari_amount::MicroTari,
+};
 
 use crate::storage::database::WalletBackend;
 use tari_p2p::{domain_message::DomainMessage, tari_message::TariMessageType};
@@ -105,6 +112,7 @@ where T: WalletBackend + 'static
     base_node_response_stream: Option<BNResponseStream>,
     request_stream: Option<Receiver<BaseNodeServiceRequest, Result<BaseNodeServiceResponse, BaseNodeServiceError>>>,
     outbound_messaging: OutboundMessageRequester,
+    connectivity: ConnectivityRequester,
     event_publisher: BaseNodeEventSender,
     base_node_peer: Option<Peer>,
     shutdown_signal: Option<ShutdownSignal>,
@@ -123,6 +131,7 @@ where
         base_node_response_stream: BNResponseStream,
         request_stream: Receiver<BaseNodeServiceRequest, Result<BaseNodeServiceResponse, BaseNodeServiceError>>,
         outbound_messaging: OutboundMessageRequester,
+        connectivity: ConnectivityRequester,
         event_publisher: BaseNodeEventSender,
         shutdown_signal: ShutdownSignal,
         db: WalletDatabase<T>,
@@ -133,6 +142,7 @@ where
             base_node_response_stream: Some(base_node_response_stream),
             request_stream: Some(request_stream),
             outbound_messaging,
+            connectivity,
             event_publisher,
             base_node_peer: None,
             shutdown_signal: Some(shutdown_signal),
@@ -262,6 +272,37 @@ where
         Ok(())
     }
 
+    /// Requests a fee per gram estimate for the given confirmation target from the connected base node
+    async fn get_fee_per_gram_estimate(&mut self, confirmation_target: u64) -> Result<MicroTari, BaseNodeServiceError> {
+        let base_node_peer = self
+            .base_node_peer
+            .clone()
+            .ok_or_else(|| BaseNodeServiceError::NoBaseNodePeer)?;
+
+        let mut connection = self.connectivity.dial_peer(base_node_peer.node_id).await?;
+        let mut client = connection
+            .connect_rpc_using_builder(BaseNodeWalletRpcClient::builder().with_deadline(self.config.request_max_age))
+            .await?;
+        let response = client
+            .get_fee_per_gram_estimate(FeePerGramEstimateRequest { confirmation_target })
+            .await?;
+        if !response.is_synced {
+            warn!(
+                target: LOG_TARGET,
+                "Fee per gram estimate was provided by a base node that is not synced"
+            );
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Base node estimated a fee per gram of {} for a confirmation target of {} blocks (tip height = {})",
+            response.fee_per_gram,
+            response.confirmation_target,
+            response.tip_height
+        );
+
+        Ok(response.fee_per_gram.into())
+    }
+
     fn check_online_status(&mut self, discarded: usize) {
         // if we are discarding old requests and have never received a response
         let never_connected = discarded > 0 && self.state.updated.is_none();
@@ -391,6 +432,10 @@ where
                 self.set_base_node_peer(*peer);
                 Ok(BaseNodeServiceResponse::BaseNodePeerSet)
             },
+            BaseNodeServiceRequest::GetFeePerGramEstimate(confirmation_target) => {
+                let fee_per_gram = self.get_fee_per_gram_estimate(confirmation_target).await?;
+                Ok(BaseNodeServiceResponse::FeePerGramEstimate(fee_per_gram))
+            },
             BaseNodeServiceRequest::GetChainMetadata => match self.state.chain_metadata.clone() {
                 Some(metadata) => Ok(BaseNodeServiceResponse::ChainMetadata(Some(metadata))),
                 None => {
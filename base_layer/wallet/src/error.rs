@@ -32,6 +32,7 @@ use log::SetLoggerError;
 use serde_json::Error as SerdeJsonError;
 use tari_comms::{connectivity::ConnectivityError, multiaddr, peer_manager::PeerManagerError};
 use tari_comms_dht::store_forward::StoreAndForwardError;
+use tari_core::transactions::transaction_protocol::TransactionProtocolError;
 use tari_crypto::tari_utilities::{hex::HexError, ByteArrayError};
 use tari_p2p::{initialization::CommsInitializationError, services::liveness::error::LivenessError};
 use tari_service_framework::ServiceInitializationError;
@@ -65,6 +66,12 @@ pub enum WalletError {
     ServiceInitializationError(#[from] ServiceInitializationError),
     #[error("Base Node Service error: {0}")]
     BaseNodeServiceError(#[from] BaseNodeServiceError),
+    #[error("The wallet database already contains spending keys and cannot be opened as a watch-only wallet")]
+    WatchOnlyKeysConflict,
+    #[error("Transaction protocol error: `{0}`")]
+    TransactionProtocolError(#[from] TransactionProtocolError),
+    #[error("Partially signed transaction cannot be signed: `{0}`")]
+    InvalidPartiallySignedTransaction(String),
 }
 
 #[derive(Debug, Error)]
@@ -14,12 +14,14 @@ mod macros;
 pub mod base_node_service;
 pub mod contacts_service;
 pub mod error;
+pub mod multisig_service;
 pub mod output_manager_service;
 pub mod storage;
 pub mod test_utils;
 pub mod transaction_service;
 pub mod types;
 pub mod util;
+pub mod utxo_scanner_service;
 pub mod wallet;
 
 #[cfg(feature = "test_harness")]
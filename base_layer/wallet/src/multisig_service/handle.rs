@@ -0,0 +1,171 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    error::MultiSigError,
+    service::{MultiSigOutput, MultiSigSpendRequest},
+};
+use futures::{stream::Fuse, StreamExt};
+use std::{fmt, sync::Arc};
+use tari_comms::types::CommsPublicKey;
+use tari_core::transactions::{tari_amount::MicroTari, types::Commitment};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_service_framework::reply_channel::SenderService;
+use tokio::sync::broadcast;
+use tower::Service;
+
+pub type MultiSigEventSender = broadcast::Sender<Arc<MultiSigEvent>>;
+pub type MultiSigEventReceiver = broadcast::Receiver<Arc<MultiSigEvent>>;
+
+/// API Request enum
+#[derive(Debug)]
+pub enum MultiSigRequest {
+    CreateOutput((Vec<CommsPublicKey>, MicroTari)),
+    GetOutputs,
+    SpendOutput((Commitment, MicroTari, String)),
+    GetSpendRequests,
+    ApproveSpend(u64),
+}
+
+impl fmt::Display for MultiSigRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateOutput((participants, amount)) => f.write_str(&format!(
+                "CreateOutput ({} participants, {})",
+                participants.len(),
+                amount
+            )),
+            Self::GetOutputs => f.write_str("GetOutputs"),
+            Self::SpendOutput((commitment, _, _)) => f.write_str(&format!("SpendOutput ({})", commitment.to_hex())),
+            Self::GetSpendRequests => f.write_str("GetSpendRequests"),
+            Self::ApproveSpend(session_id) => f.write_str(&format!("ApproveSpend ({})", session_id)),
+        }
+    }
+}
+
+/// API Response enum
+#[derive(Debug)]
+pub enum MultiSigResponse {
+    KeySetupStarted(u64),
+    Outputs(Vec<MultiSigOutput>),
+    SpendStarted(u64),
+    SpendRequests(Vec<MultiSigSpendRequest>),
+    SpendApproved,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultiSigEvent {
+    /// A new multi-signature output was announced by the wallet that set it up and funded it
+    OutputReceived(Commitment),
+    /// Another participant asked this wallet to sign for the spend of a multi-signature output
+    SpendRequested(u64),
+    /// All the participants signed for the spend session
+    SpendCompleted(u64),
+    Error(String),
+}
+
+/// The Multi-signature Handle contains the interfaces used to communicate with a running Multi-signature Service
+#[derive(Clone)]
+pub struct MultiSigHandle {
+    handle: SenderService<MultiSigRequest, Result<MultiSigResponse, MultiSigError>>,
+    event_stream_sender: MultiSigEventSender,
+}
+
+impl MultiSigHandle {
+    pub fn new(
+        handle: SenderService<MultiSigRequest, Result<MultiSigResponse, MultiSigError>>,
+        event_stream_sender: MultiSigEventSender,
+    ) -> Self
+    {
+        Self {
+            handle,
+            event_stream_sender,
+        }
+    }
+
+    pub fn get_event_stream_fused(&self) -> Fuse<MultiSigEventReceiver> {
+        self.event_stream_sender.subscribe().fuse()
+    }
+
+    /// Start the setup of an output of `amount` that can only be spent with the cooperation of every one of
+    /// `participants`, which may include this wallet. Each participant generates its own share of the spending key.
+    /// Returns the id of the key setup.
+    pub async fn create_output(
+        &mut self,
+        participants: Vec<CommsPublicKey>,
+        amount: MicroTari,
+    ) -> Result<u64, MultiSigError>
+    {
+        match self
+            .handle
+            .call(MultiSigRequest::CreateOutput((participants, amount)))
+            .await??
+        {
+            MultiSigResponse::KeySetupStarted(setup_id) => Ok(setup_id),
+            _ => Err(MultiSigError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the multi-signature outputs this wallet holds a key share of
+    pub async fn get_outputs(&mut self) -> Result<Vec<MultiSigOutput>, MultiSigError> {
+        match self.handle.call(MultiSigRequest::GetOutputs).await?? {
+            MultiSigResponse::Outputs(outputs) => Ok(outputs),
+            _ => Err(MultiSigError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Start a session to spend a multi-signature output to this wallet. The other participants are asked to approve
+    /// the spend and the transaction is broadcast once they have all signed. Returns the id of the spend session,
+    /// which is also the transaction id the spend is tracked under.
+    pub async fn spend_output(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, MultiSigError>
+    {
+        match self
+            .handle
+            .call(MultiSigRequest::SpendOutput((commitment, fee_per_gram, message)))
+            .await??
+        {
+            MultiSigResponse::SpendStarted(session_id) => Ok(session_id),
+            _ => Err(MultiSigError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the spend sessions in progress, including those waiting for this wallet's approval
+    pub async fn get_spend_requests(&mut self) -> Result<Vec<MultiSigSpendRequest>, MultiSigError> {
+        match self.handle.call(MultiSigRequest::GetSpendRequests).await?? {
+            MultiSigResponse::SpendRequests(requests) => Ok(requests),
+            _ => Err(MultiSigError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Approve a spend requested by another participant, after which this wallet signs for it
+    pub async fn approve_spend(&mut self, session_id: u64) -> Result<(), MultiSigError> {
+        match self.handle.call(MultiSigRequest::ApproveSpend(session_id)).await?? {
+            MultiSigResponse::SpendApproved => Ok(()),
+            _ => Err(MultiSigError::UnexpectedApiResponse),
+        }
+    }
+}
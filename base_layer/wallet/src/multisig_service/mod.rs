@@ -0,0 +1,144 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The multi-signature service manages outputs that need the signatures of every one of several wallets to be spent,
+//! see [tari_core::transactions::transaction_protocol::multisig] for the protocol. Public key shares and signing
+//! messages are exchanged with the other participants over the DHT as `TariMessageType::MultiSig` messages.
+
+pub mod error;
+pub mod handle;
+pub mod service;
+
+use crate::{
+    multisig_service::{
+        handle::MultiSigHandle,
+        service::{MultiSigResources, MultiSigService},
+    },
+    output_manager_service::handle::OutputManagerHandle,
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+};
+use futures::{future, Future, Stream, StreamExt};
+use log::*;
+use std::sync::Arc;
+use tari_comms::peer_manager::NodeIdentity;
+use tari_comms_dht::Dht;
+use tari_core::transactions::{transaction_protocol::proto, types::CryptoFactories};
+use tari_p2p::{
+    comms_connector::SubscriptionFactory,
+    domain_message::DomainMessage,
+    services::utils::{map_decode, ok_or_skip_result},
+    tari_message::TariMessageType,
+};
+use tari_service_framework::{
+    reply_channel,
+    ServiceInitializationError,
+    ServiceInitializer,
+    ServiceInitializerContext,
+};
+use tokio::sync::broadcast;
+
+const LOG_TARGET: &str = "wallet::multisig_service";
+const SUBSCRIPTION_LABEL: &str = "Multi-signature Service";
+
+pub struct MultiSigServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    db: WalletDatabase<T>,
+    subscription_factory: Arc<SubscriptionFactory>,
+    node_identity: Arc<NodeIdentity>,
+    factories: CryptoFactories,
+}
+
+impl<T> MultiSigServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(
+        db: WalletDatabase<T>,
+        subscription_factory: Arc<SubscriptionFactory>,
+        node_identity: Arc<NodeIdentity>,
+        factories: CryptoFactories,
+    ) -> Self
+    {
+        Self {
+            db,
+            subscription_factory,
+            node_identity,
+            factories,
+        }
+    }
+
+    /// Get a stream of inbound multi-signature messages
+    fn multisig_stream(&self) -> impl Stream<Item = DomainMessage<proto::MultiSigMessage>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::MultiSig
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::MultiSig, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::MultiSigMessage>)
+            .filter_map(ok_or_skip_result)
+    }
+}
+
+impl<T> ServiceInitializer for MultiSigServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    type Future = impl Future<Output = Result<(), ServiceInitializationError>>;
+
+    fn initialize(&mut self, context: ServiceInitializerContext) -> Self::Future {
+        info!(target: LOG_TARGET, "Wallet multi-signature service initializing.");
+
+        let (sender, request_stream) = reply_channel::unbounded();
+        let (event_publisher, _) = broadcast::channel(200);
+        let message_stream = self.multisig_stream();
+
+        // Register handle before waiting for handles to be ready
+        context.register_handle(MultiSigHandle::new(sender, event_publisher.clone()));
+
+        let db = self.db.clone();
+        let node_identity = self.node_identity.clone();
+        let factories = self.factories.clone();
+
+        context.spawn_when_ready(move |handles| async move {
+            let resources = MultiSigResources {
+                db,
+                output_manager_service: handles.expect_handle::<OutputManagerHandle>(),
+                transaction_service: handles.expect_handle::<TransactionServiceHandle>(),
+                outbound_message_service: handles.expect_handle::<Dht>().outbound_requester(),
+                node_identity,
+                factories,
+                event_publisher,
+            };
+
+            let service =
+                MultiSigService::new(request_stream, message_stream, resources, handles.get_shutdown_signal()).start();
+            futures::pin_mut!(service);
+            future::select(service, handles.get_shutdown_signal()).await;
+            info!(target: LOG_TARGET, "Wallet Multi-signature Service shutdown");
+        });
+
+        future::ready(Ok(()))
+    }
+}
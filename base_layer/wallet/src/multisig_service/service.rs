@@ -0,0 +1,984 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    error::MultiSigError,
+    handle::{MultiSigEvent, MultiSigEventSender, MultiSigRequest, MultiSigResponse},
+};
+use crate::{
+    output_manager_service::handle::OutputManagerHandle,
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+};
+use futures::{pin_mut, Stream, StreamExt};
+use log::*;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
+use tari_comms::{
+    peer_manager::{NodeId, NodeIdentity},
+    types::CommsPublicKey,
+};
+use tari_comms_dht::{
+    domain_message::OutboundDomainMessage,
+    outbound::{OutboundEncryption, OutboundMessageRequester, SendMessageParams},
+};
+use tari_core::transactions::{
+    fee::Fee,
+    tari_amount::MicroTari,
+    transaction::{OutputFeatures, TransactionInput},
+    transaction_protocol::{
+        multisig::{
+            prove_key_share,
+            verify_key_share,
+            AggregatedKey,
+            MultiSigKeySetup,
+            MultiSigMessage,
+            MultiSigMessageBody,
+            MultiSigOutputInfo,
+            MultiSigParticipant,
+            MultiSigSession,
+            MultiSigSpendData,
+        },
+        proto,
+        TransactionMetadata,
+        TransactionProtocolError,
+    },
+    types::{Commitment, CryptoFactories, PrivateKey, PublicKey, Signature},
+};
+use tari_crypto::{
+    keys::{PublicKey as PK, SecretKey},
+    tari_utilities::hex::Hex,
+};
+use tari_p2p::{domain_message::DomainMessage, tari_message::TariMessageType};
+use tari_service_framework::reply_channel::Receiver;
+use tari_shutdown::ShutdownSignal;
+
+const LOG_TARGET: &str = "wallet::multisig_service::service";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MultiSigOutputStatus {
+    Unspent,
+    /// A spend of the output is being signed in the given session
+    Spending(u64),
+    /// Every participant has signed for a spend of the output
+    Spent,
+}
+
+/// A multi-signature output that this wallet holds a share of the spending key of
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultiSigOutput {
+    pub commitment: Commitment,
+    pub value: MicroTari,
+    pub features: OutputFeatures,
+    pub spending_key_share: PrivateKey,
+    pub participants: Vec<MultiSigParticipant>,
+    pub status: MultiSigOutputStatus,
+}
+
+/// This wallet's share of the spending key of a multi-signature output whose key setup is in progress. It is stored
+/// before its public key is published, since the output cannot be spent without it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultiSigKeySetupShare {
+    pub setup_id: u64,
+    /// The party funding the output, which announces it once it is funded
+    pub coordinator: CommsPublicKey,
+    pub value: MicroTari,
+    pub features: OutputFeatures,
+    pub spending_key_share: PrivateKey,
+}
+
+/// A spend session as presented for review before this wallet approves it
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiSigSpendRequest {
+    pub session_id: u64,
+    pub commitment: Commitment,
+    pub value: MicroTari,
+    pub fee: MicroTari,
+    /// The participant that requested the spend and receives the value of the output
+    pub coordinator: CommsPublicKey,
+    pub message: String,
+    pub approved: bool,
+}
+
+struct SpendSession {
+    output: MultiSigOutput,
+    spend: MultiSigSpendData,
+    message: String,
+    coordinator: CommsPublicKey,
+    signing: MultiSigSession,
+    approved: bool,
+    nonce_revealed: bool,
+    signature_sent: bool,
+    /// Nonces and signatures of participants whose nonce commitment has not arrived yet
+    deferred: Vec<MultiSigMessageBody>,
+}
+
+/// A key setup coordinated by this wallet, collecting the public key shares of the participants
+struct KeySetup {
+    setup: MultiSigKeySetup,
+    shares: Vec<MultiSigParticipant>,
+}
+
+impl SpendSession {
+    fn summary(&self, session_id: u64) -> MultiSigSpendRequest {
+        MultiSigSpendRequest {
+            session_id,
+            commitment: self.output.commitment.clone(),
+            value: self.output.value,
+            fee: self.spend.metadata.fee,
+            coordinator: self.coordinator.clone(),
+            message: self.message.clone(),
+            approved: self.approved,
+        }
+    }
+
+    /// Add another participant's signing message to the session. Messages can arrive out of order when they are
+    /// delivered by store and forward, so a nonce or signature from a participant that has not yet been seen is held
+    /// back until its nonce commitment arrives.
+    fn apply(&mut self, body: MultiSigMessageBody) -> Result<(), MultiSigError> {
+        let result = match body.clone() {
+            MultiSigMessageBody::NonceCommitment(public_excess, commitment) => {
+                self.signing.add_nonce_commitment(public_excess, commitment)?;
+                for deferred in std::mem::take(&mut self.deferred) {
+                    self.apply(deferred)?;
+                }
+                return Ok(());
+            },
+            MultiSigMessageBody::PublicNonce(public_excess, public_nonce) => {
+                self.signing.add_public_nonce(&public_excess, public_nonce)
+            },
+            MultiSigMessageBody::PartialSignature(public_excess, signature) => {
+                self.signing.add_partial_signature(&public_excess, signature)
+            },
+            _ => {
+                return Err(MultiSigError::InvalidMessage(
+                    "Unexpected message in a spend session".to_string(),
+                ))
+            },
+        };
+        match result {
+            Err(TransactionProtocolError::InvalidStateError) => {
+                self.deferred.push(body);
+                Ok(())
+            },
+            result => result.map_err(MultiSigError::from),
+        }
+    }
+}
+
+pub struct MultiSigResources<T>
+where T: WalletBackend + 'static
+{
+    pub db: WalletDatabase<T>,
+    pub output_manager_service: OutputManagerHandle,
+    pub transaction_service: TransactionServiceHandle,
+    pub outbound_message_service: OutboundMessageRequester,
+    pub node_identity: Arc<NodeIdentity>,
+    pub factories: CryptoFactories,
+    pub event_publisher: MultiSigEventSender,
+}
+
+/// The multi-signature service funds, tracks and spends outputs that are owned jointly by several wallets. It holds
+/// this wallet's key shares and takes part in the signing of spends with the other participants over the DHT.
+pub struct MultiSigService<T, MStream>
+where T: WalletBackend + 'static
+{
+    request_stream: Option<Receiver<MultiSigRequest, Result<MultiSigResponse, MultiSigError>>>,
+    message_stream: Option<MStream>,
+    resources: MultiSigResources<T>,
+    key_setups: HashMap<u64, KeySetup>,
+    sessions: HashMap<u64, SpendSession>,
+    /// Signing messages that arrived before the spend request of their session
+    pending_messages: HashMap<u64, Vec<(CommsPublicKey, MultiSigMessageBody)>>,
+    shutdown_signal: Option<ShutdownSignal>,
+}
+
+impl<T, MStream> MultiSigService<T, MStream>
+where
+    T: WalletBackend + 'static,
+    MStream: Stream<Item = DomainMessage<proto::MultiSigMessage>>,
+{
+    pub fn new(
+        request_stream: Receiver<MultiSigRequest, Result<MultiSigResponse, MultiSigError>>,
+        message_stream: MStream,
+        resources: MultiSigResources<T>,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self
+    {
+        Self {
+            request_stream: Some(request_stream),
+            message_stream: Some(message_stream),
+            resources,
+            key_setups: HashMap::new(),
+            sessions: HashMap::new(),
+            pending_messages: HashMap::new(),
+            shutdown_signal: Some(shutdown_signal),
+        }
+    }
+
+    /// Starts the service.
+    pub async fn start(mut self) -> Result<(), MultiSigError> {
+        let request_stream = self
+            .request_stream
+            .take()
+            .expect("Multi-signature Service initialized without request_stream")
+            .fuse();
+        pin_mut!(request_stream);
+        let message_stream = self
+            .message_stream
+            .take()
+            .expect("Multi-signature Service initialized without message_stream")
+            .fuse();
+        pin_mut!(message_stream);
+
+        let mut shutdown_signal = self
+            .shutdown_signal
+            .take()
+            .expect("Multi-signature Service initialized without shutdown signal");
+
+        info!(target: LOG_TARGET, "Multi-signature Service started");
+        loop {
+            futures::select! {
+                // Incoming requests
+                request_context = request_stream.select_next_some() => {
+                    trace!(target: LOG_TARGET, "Handling Multi-signature Service API Request");
+                    let (request, reply_tx) = request_context.split();
+                    let response = self.handle_request(request).await.map_err(|e| {
+                        error!(target: LOG_TARGET, "Error handling request: {:?}", e);
+                        e
+                    });
+                    let _ = reply_tx.send(response).map_err(|e| {
+                        warn!(target: LOG_TARGET, "Failed to send reply");
+                        e
+                    });
+                },
+
+                // Incoming messages from the other participants
+                msg = message_stream.select_next_some() => {
+                    let (origin_public_key, message) = msg.into_origin_and_inner();
+                    if let Err(e) = self.handle_message(origin_public_key, message).await {
+                        warn!(target: LOG_TARGET, "Error handling multi-signature message: {}", e);
+                        self.publish_event(MultiSigEvent::Error(e.to_string()));
+                    }
+                },
+
+                // Shutdown
+                _ = shutdown_signal => {
+                    info!(target: LOG_TARGET, "Multi-signature Service shutting down because the shutdown signal was received");
+                    break Ok(());
+                }
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, request: MultiSigRequest) -> Result<MultiSigResponse, MultiSigError> {
+        debug!(target: LOG_TARGET, "Handling Service Request: {}", request);
+        match request {
+            MultiSigRequest::CreateOutput((participants, amount)) => self
+                .create_output(participants, amount)
+                .await
+                .map(MultiSigResponse::KeySetupStarted),
+            MultiSigRequest::GetOutputs => Ok(MultiSigResponse::Outputs(
+                self.resources.db.get_multisig_outputs().await?,
+            )),
+            MultiSigRequest::SpendOutput((commitment, fee_per_gram, message)) => self
+                .spend_output(commitment, fee_per_gram, message)
+                .await
+                .map(MultiSigResponse::SpendStarted),
+            MultiSigRequest::GetSpendRequests => Ok(MultiSigResponse::SpendRequests(
+                self.sessions.iter().map(|(id, s)| s.summary(*id)).collect(),
+            )),
+            MultiSigRequest::ApproveSpend(session_id) => self
+                .approve_spend(session_id)
+                .await
+                .map(|_| MultiSigResponse::SpendApproved),
+        }
+    }
+
+    /// Start the key setup of a new multi-signature output. Every participant generates its own share of the spending
+    /// key and sends back only its public key, with a proof of possession. The setup id is returned.
+    async fn create_output(
+        &mut self,
+        participants: Vec<CommsPublicKey>,
+        amount: MicroTari,
+    ) -> Result<u64, MultiSigError>
+    {
+        if participants.len() < 2 {
+            return Err(MultiSigError::InvalidParticipants(
+                "A multi-signature output needs at least two participants".to_string(),
+            ));
+        }
+        if participants
+            .iter()
+            .enumerate()
+            .any(|(i, p)| participants[..i].contains(p))
+        {
+            return Err(MultiSigError::InvalidParticipants(
+                "Participants must be distinct".to_string(),
+            ));
+        }
+        let setup_id = OsRng.next_u64();
+        let setup = MultiSigKeySetup {
+            value: amount,
+            features: OutputFeatures::default(),
+            participants,
+        };
+
+        let own_public_key = self.resources.node_identity.public_key().clone();
+        let mut shares = Vec::new();
+        if setup.participants.contains(&own_public_key) {
+            let (share_public_key, share_proof) = self
+                .generate_key_share(setup_id, own_public_key.clone(), &setup)
+                .await?;
+            shares.push(MultiSigParticipant {
+                comms_public_key: own_public_key.clone(),
+                share_public_key,
+                share_proof,
+            });
+        }
+        for participant in setup.participants.iter().filter(|p| *p != &own_public_key) {
+            self.send_message(participant.clone(), MultiSigMessage {
+                session_id: setup_id,
+                body: MultiSigMessageBody::KeySetupRequest(Box::new(setup.clone())),
+            });
+        }
+        info!(
+            target: LOG_TARGET,
+            "Started key setup {} of multi-signature output of {} with {} participants",
+            setup_id,
+            amount,
+            setup.participants.len()
+        );
+        self.key_setups.insert(setup_id, KeySetup { setup, shares });
+
+        Ok(setup_id)
+    }
+
+    /// Generate and store this wallet's share of the spending key of an output being set up, returning its public key
+    /// and proof of possession. A repeated request for the same setup is answered with the stored share.
+    async fn generate_key_share(
+        &mut self,
+        setup_id: u64,
+        coordinator: CommsPublicKey,
+        setup: &MultiSigKeySetup,
+    ) -> Result<(PublicKey, Signature), MultiSigError>
+    {
+        let mut setups = self.resources.db.get_multisig_key_setups().await?;
+        let spending_key_share = match setups
+            .iter()
+            .find(|s| s.setup_id == setup_id && s.coordinator == coordinator)
+        {
+            Some(share) => share.spending_key_share.clone(),
+            None => {
+                let spending_key_share = PrivateKey::random(&mut OsRng);
+                setups.push(MultiSigKeySetupShare {
+                    setup_id,
+                    coordinator,
+                    value: setup.value,
+                    features: setup.features.clone(),
+                    spending_key_share: spending_key_share.clone(),
+                });
+                self.resources.db.set_multisig_key_setups(setups).await?;
+                spending_key_share
+            },
+        };
+        let share_proof = prove_key_share(&spending_key_share, setup_id)?;
+        Ok((PublicKey::from_secret_key(&spending_key_share), share_proof))
+    }
+
+    /// Generate a key share for an output being set up by another participant and send its public key back
+    async fn receive_key_setup_request(
+        &mut self,
+        setup_id: u64,
+        origin_public_key: CommsPublicKey,
+        setup: MultiSigKeySetup,
+    ) -> Result<(), MultiSigError>
+    {
+        if !setup.participants.contains(self.resources.node_identity.public_key()) {
+            return Err(MultiSigError::InvalidMessage(
+                "This wallet is not a participant".to_string(),
+            ));
+        }
+        let (share_public_key, share_proof) = self
+            .generate_key_share(setup_id, origin_public_key.clone(), &setup)
+            .await?;
+        self.send_message(origin_public_key, MultiSigMessage {
+            session_id: setup_id,
+            body: MultiSigMessageBody::PublicKeyShare(share_public_key, share_proof),
+        });
+        info!(
+            target: LOG_TARGET,
+            "Sent key share for key setup {} of multi-signature output of {}", setup_id, setup.value
+        );
+
+        Ok(())
+    }
+
+    /// Add the public key share of a participant to a key setup coordinated by this wallet
+    async fn add_public_key_share(
+        &mut self,
+        setup_id: u64,
+        origin_public_key: CommsPublicKey,
+        share_public_key: PublicKey,
+        share_proof: Signature,
+    ) -> Result<(), MultiSigError>
+    {
+        let key_setup = self
+            .key_setups
+            .get_mut(&setup_id)
+            .ok_or(MultiSigError::SessionNotFound(setup_id))?;
+        if !key_setup.setup.participants.contains(&origin_public_key) {
+            return Err(MultiSigError::InvalidMessage(
+                "Key share from a party that is not a participant".to_string(),
+            ));
+        }
+        if key_setup.shares.iter().any(|p| p.comms_public_key == origin_public_key) {
+            return Ok(());
+        }
+        if !verify_key_share(&share_public_key, &share_proof, setup_id) {
+            return Err(MultiSigError::InvalidMessage(
+                "Invalid proof of possession of a key share".to_string(),
+            ));
+        }
+        key_setup.shares.push(MultiSigParticipant {
+            comms_public_key: origin_public_key,
+            share_public_key,
+            share_proof,
+        });
+
+        if key_setup.shares.len() == key_setup.setup.participants.len() {
+            self.complete_key_setup(setup_id)?;
+        }
+        Ok(())
+    }
+
+    /// Build the commitment of an output once every participant has published its key share. The commitment is built
+    /// from the aggregated key alone, so nobody knows the spending key of the output. Its range proof and the signature
+    /// of the funding transaction, the excess of which includes the spending key, can therefore only be produced by
+    /// the participants together. The range proof service does not support that yet, so the output cannot be funded.
+    fn complete_key_setup(&mut self, setup_id: u64) -> Result<(), MultiSigError> {
+        let key_setup = self
+            .key_setups
+            .remove(&setup_id)
+            .ok_or(MultiSigError::SessionNotFound(setup_id))?;
+        let key = AggregatedKey::new(key_setup.shares.iter().map(|p| p.share_public_key.clone()).collect())?;
+        let commitment = key.commitment(key_setup.setup.value, &self.resources.factories);
+        info!(
+            target: LOG_TARGET,
+            "Key setup {} of multi-signature output {} completed",
+            setup_id,
+            commitment.to_hex()
+        );
+        Err(MultiSigError::FundingUnsupported(commitment.to_hex()))
+    }
+
+    /// Start spending a multi-signature output to a new output of this wallet. The session id is the transaction id
+    /// the new output is pending under.
+    async fn spend_output(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, MultiSigError>
+    {
+        let output = self.find_unspent_output(&commitment).await?;
+        let fee = Fee::calculate(fee_per_gram, 1, 1, 1);
+        let amount = output
+            .value
+            .checked_sub(fee)
+            .filter(|amount| *amount > MicroTari::from(0))
+            .ok_or(MultiSigError::InsufficientValue)?;
+
+        let (session_id, receive_output, transaction_output) = self
+            .resources
+            .output_manager_service
+            .create_multisig_spend_output(amount)
+            .await?;
+        let offset = PrivateKey::random(&mut OsRng);
+        let spend = MultiSigSpendData {
+            input: TransactionInput::new(output.features.clone(), output.commitment.clone()),
+            outputs: vec![transaction_output],
+            metadata: TransactionMetadata { fee, lock_height: 0 },
+            offset: offset.clone(),
+        };
+        let secret_excess = &(&receive_output.spending_key - &output.spending_key_share) - &offset;
+
+        let own_public_key = self.resources.node_identity.public_key().clone();
+        for participant in output
+            .participants
+            .iter()
+            .filter(|p| p.comms_public_key != own_public_key)
+        {
+            self.send_message(participant.comms_public_key.clone(), MultiSigMessage {
+                session_id,
+                body: MultiSigMessageBody::SpendRequest(Box::new(spend.clone()), message.clone()),
+            });
+        }
+        self.start_session(session_id, own_public_key, output, spend, message, secret_excess)
+            .await?;
+        self.approve_spend(session_id).await?;
+
+        Ok(session_id)
+    }
+
+    /// Approve a spend session, after which this wallet signs for it as soon as the other participants have joined
+    async fn approve_spend(&mut self, session_id: u64) -> Result<(), MultiSigError> {
+        let session = self
+            .sessions
+            .get(&session_id)
+            .ok_or(MultiSigError::SessionNotFound(session_id))?;
+        if session.approved {
+            return Ok(());
+        }
+        let commitment = session.output.commitment.clone();
+
+        // Only one spend of an output may be signed for
+        let mut outputs = self.resources.db.get_multisig_outputs().await?;
+        let output = outputs
+            .iter_mut()
+            .find(|o| o.commitment == commitment)
+            .ok_or_else(|| MultiSigError::OutputNotFound(commitment.to_hex()))?;
+        if output.status != MultiSigOutputStatus::Unspent {
+            return Err(MultiSigError::OutputNotUnspent(commitment.to_hex()));
+        }
+        output.status = MultiSigOutputStatus::Spending(session_id);
+        self.resources.db.set_multisig_outputs(outputs).await?;
+
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(MultiSigError::SessionNotFound(session_id))?;
+        session.approved = true;
+        let body = MultiSigMessageBody::NonceCommitment(
+            session.signing.public_excess().clone(),
+            session.signing.nonce_commitment(),
+        );
+        let participants = session.output.participants.clone();
+        self.send_to_participants(&participants, session_id, body);
+
+        self.advance_session(session_id).await
+    }
+
+    async fn handle_message(
+        &mut self,
+        origin_public_key: CommsPublicKey,
+        message: proto::MultiSigMessage,
+    ) -> Result<(), MultiSigError>
+    {
+        let message = MultiSigMessage::try_from(message).map_err(MultiSigError::InvalidMessage)?;
+        trace!(
+            target: LOG_TARGET,
+            "Multi-signature message for session {} received from {}",
+            message.session_id,
+            origin_public_key
+        );
+        match message.body {
+            MultiSigMessageBody::KeySetupRequest(setup) => {
+                self.receive_key_setup_request(message.session_id, origin_public_key, *setup)
+                    .await
+            },
+            MultiSigMessageBody::PublicKeyShare(share_public_key, share_proof) => {
+                self.add_public_key_share(message.session_id, origin_public_key, share_public_key, share_proof)
+                    .await
+            },
+            MultiSigMessageBody::OutputFunded(output) => {
+                self.store_output(message.session_id, origin_public_key, *output).await
+            },
+            MultiSigMessageBody::SpendRequest(spend, description) => {
+                self.receive_spend_request(message.session_id, origin_public_key, *spend, description)
+                    .await
+            },
+            body => {
+                if self.sessions.contains_key(&message.session_id) {
+                    self.handle_signing_message(message.session_id, origin_public_key, body)
+                        .await
+                } else {
+                    // Only hold on to messages from the parties of this wallet's outputs
+                    let outputs = self.resources.db.get_multisig_outputs().await?;
+                    if outputs
+                        .iter()
+                        .any(|o| o.participants.iter().any(|p| p.comms_public_key == origin_public_key))
+                    {
+                        self.pending_messages
+                            .entry(message.session_id)
+                            .or_insert_with(Vec::new)
+                            .push((origin_public_key, body));
+                    }
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    /// Validate and store a multi-signature output announced by the party that set it up and funded it
+    async fn store_output(
+        &mut self,
+        setup_id: u64,
+        origin_public_key: CommsPublicKey,
+        output: MultiSigOutputInfo,
+    ) -> Result<(), MultiSigError>
+    {
+        let mut setups = self.resources.db.get_multisig_key_setups().await?;
+        let index = setups
+            .iter()
+            .position(|s| s.setup_id == setup_id && s.coordinator == origin_public_key)
+            .ok_or_else(|| MultiSigError::InvalidMessage("Output announced for an unknown key setup".to_string()))?;
+        if setups[index].value != output.value || setups[index].features != output.features {
+            return Err(MultiSigError::InvalidMessage(
+                "Announced output does not match its key setup".to_string(),
+            ));
+        }
+        let participant = output
+            .participants
+            .iter()
+            .find(|p| &p.comms_public_key == self.resources.node_identity.public_key())
+            .ok_or_else(|| MultiSigError::InvalidMessage("This wallet is not a participant".to_string()))?;
+        if participant.share_public_key != PublicKey::from_secret_key(&setups[index].spending_key_share) {
+            return Err(MultiSigError::InvalidMessage(
+                "Announced key share of this wallet does not match it".to_string(),
+            ));
+        }
+        if output
+            .participants
+            .iter()
+            .any(|p| !verify_key_share(&p.share_public_key, &p.share_proof, setup_id))
+        {
+            return Err(MultiSigError::InvalidMessage(
+                "Invalid proof of possession of a key share".to_string(),
+            ));
+        }
+        let key = AggregatedKey::new(output.participants.iter().map(|p| p.share_public_key.clone()).collect())?;
+        if !key.opens(&output.commitment, output.value, &self.resources.factories) {
+            return Err(MultiSigError::InvalidMessage(
+                "The key shares of the participants do not open the output".to_string(),
+            ));
+        }
+
+        let mut outputs = self.resources.db.get_multisig_outputs().await?;
+        let share = setups.remove(index);
+        let commitment = output.commitment.clone();
+        if !outputs.iter().any(|o| o.commitment == commitment) {
+            outputs.push(MultiSigOutput {
+                commitment: output.commitment,
+                value: output.value,
+                features: output.features,
+                spending_key_share: share.spending_key_share,
+                participants: output.participants,
+                status: MultiSigOutputStatus::Unspent,
+            });
+            self.resources.db.set_multisig_outputs(outputs).await?;
+        }
+        self.resources.db.set_multisig_key_setups(setups).await?;
+        info!(
+            target: LOG_TARGET,
+            "Received multi-signature output {} of key setup {}",
+            commitment.to_hex(),
+            setup_id
+        );
+        self.publish_event(MultiSigEvent::OutputReceived(commitment));
+
+        Ok(())
+    }
+
+    /// Start a session for a spend requested by another participant. It is only signed once it is approved.
+    async fn receive_spend_request(
+        &mut self,
+        session_id: u64,
+        origin_public_key: CommsPublicKey,
+        spend: MultiSigSpendData,
+        message: String,
+    ) -> Result<(), MultiSigError>
+    {
+        if self.sessions.contains_key(&session_id) {
+            return Ok(());
+        }
+        let output = self.find_unspent_output(&spend.input.commitment).await?;
+        if output.features != spend.input.features {
+            return Err(MultiSigError::InvalidMessage(
+                "Spend request input does not match the output".to_string(),
+            ));
+        }
+        if !output
+            .participants
+            .iter()
+            .any(|p| p.comms_public_key == origin_public_key)
+        {
+            return Err(MultiSigError::InvalidMessage(
+                "Spend requested by a party that is not a participant".to_string(),
+            ));
+        }
+        let secret_excess = &PrivateKey::default() - &output.spending_key_share;
+        self.start_session(session_id, origin_public_key, output, spend, message, secret_excess)
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Spend of multi-signature output requested (Session: {})", session_id
+        );
+        self.publish_event(MultiSigEvent::SpendRequested(session_id));
+
+        Ok(())
+    }
+
+    async fn start_session(
+        &mut self,
+        session_id: u64,
+        coordinator: CommsPublicKey,
+        output: MultiSigOutput,
+        spend: MultiSigSpendData,
+        message: String,
+        secret_excess: PrivateKey,
+    ) -> Result<(), MultiSigError>
+    {
+        let signing = MultiSigSession::new(
+            secret_excess,
+            output.participants.len(),
+            spend.metadata.clone(),
+            spend.excess(&self.resources.factories),
+        )?;
+        self.sessions.insert(session_id, SpendSession {
+            output,
+            spend,
+            message,
+            coordinator,
+            signing,
+            approved: false,
+            nonce_revealed: false,
+            signature_sent: false,
+            deferred: Vec::new(),
+        });
+
+        for (origin_public_key, body) in self.pending_messages.remove(&session_id).unwrap_or_default() {
+            self.handle_signing_message(session_id, origin_public_key, body).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_signing_message(
+        &mut self,
+        session_id: u64,
+        origin_public_key: CommsPublicKey,
+        body: MultiSigMessageBody,
+    ) -> Result<(), MultiSigError>
+    {
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(MultiSigError::SessionNotFound(session_id))?;
+        if !session
+            .output
+            .participants
+            .iter()
+            .any(|p| p.comms_public_key == origin_public_key)
+        {
+            return Err(MultiSigError::InvalidMessage(
+                "Signing message from a party that is not a participant".to_string(),
+            ));
+        }
+        session.apply(body)?;
+        self.advance_session(session_id).await
+    }
+
+    /// Send this wallet's nonce and partial signature as soon as the other participants' messages allow it, and
+    /// complete the spend once everyone has signed
+    async fn advance_session(&mut self, session_id: u64) -> Result<(), MultiSigError> {
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(MultiSigError::SessionNotFound(session_id))?;
+        if !session.approved {
+            return Ok(());
+        }
+
+        let public_excess = session.signing.public_excess().clone();
+        let mut outgoing = Vec::new();
+        if !session.nonce_revealed && session.signing.has_all_nonce_commitments() {
+            outgoing.push(MultiSigMessageBody::PublicNonce(
+                public_excess.clone(),
+                session.signing.public_nonce()?,
+            ));
+            session.nonce_revealed = true;
+        }
+        if !session.signature_sent && session.signing.has_all_public_nonces() {
+            outgoing.push(MultiSigMessageBody::PartialSignature(
+                public_excess,
+                session.signing.partial_signature()?,
+            ));
+            session.signature_sent = true;
+        }
+        let completed = session.signing.has_all_partial_signatures();
+        let participants = session.output.participants.clone();
+
+        for body in outgoing {
+            self.send_to_participants(&participants, session_id, body);
+        }
+        if completed {
+            self.complete_spend(session_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Aggregate the partial signatures. The coordinator builds and submits the transaction, every participant marks
+    /// the output as spent.
+    async fn complete_spend(&mut self, session_id: u64) -> Result<(), MultiSigError> {
+        let session = self
+            .sessions
+            .remove(&session_id)
+            .ok_or(MultiSigError::SessionNotFound(session_id))?;
+        let is_coordinator = &session.coordinator == self.resources.node_identity.public_key();
+
+        let signature = match session.signing.aggregate_signature() {
+            Ok(signature) => signature,
+            Err(e) => {
+                // The output can be spent again in a new session
+                self.set_output_status(&session.output.commitment, MultiSigOutputStatus::Unspent)
+                    .await?;
+                if is_coordinator {
+                    self.resources
+                        .output_manager_service
+                        .cancel_transaction(session_id)
+                        .await?;
+                }
+                return Err(e.into());
+            },
+        };
+
+        if is_coordinator {
+            let tx = session.spend.build_transaction(&signature, &self.resources.factories)?;
+            let fee = session.spend.metadata.fee;
+            self.resources
+                .transaction_service
+                .submit_transaction(session_id, tx, fee, session.output.value - fee, session.message.clone())
+                .await?;
+        }
+        self.set_output_status(&session.output.commitment, MultiSigOutputStatus::Spent)
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Spend of multi-signature output {} signed (Session: {})",
+            session.output.commitment.to_hex(),
+            session_id
+        );
+        self.publish_event(MultiSigEvent::SpendCompleted(session_id));
+
+        Ok(())
+    }
+
+    async fn find_unspent_output(&self, commitment: &Commitment) -> Result<MultiSigOutput, MultiSigError> {
+        let output = self
+            .resources
+            .db
+            .get_multisig_outputs()
+            .await?
+            .into_iter()
+            .find(|o| &o.commitment == commitment)
+            .ok_or_else(|| MultiSigError::OutputNotFound(commitment.to_hex()))?;
+        if output.status != MultiSigOutputStatus::Unspent {
+            return Err(MultiSigError::OutputNotUnspent(commitment.to_hex()));
+        }
+        Ok(output)
+    }
+
+    async fn set_output_status(
+        &self,
+        commitment: &Commitment,
+        status: MultiSigOutputStatus,
+    ) -> Result<(), MultiSigError>
+    {
+        let mut outputs = self.resources.db.get_multisig_outputs().await?;
+        if let Some(output) = outputs.iter_mut().find(|o| &o.commitment == commitment) {
+            output.status = status;
+        }
+        self.resources.db.set_multisig_outputs(outputs).await?;
+        Ok(())
+    }
+
+    fn send_to_participants(&self, participants: &[MultiSigParticipant], session_id: u64, body: MultiSigMessageBody) {
+        for participant in participants
+            .iter()
+            .filter(|p| &p.comms_public_key != self.resources.node_identity.public_key())
+        {
+            self.send_message(participant.comms_public_key.clone(), MultiSigMessage {
+                session_id,
+                body: body.clone(),
+            });
+        }
+    }
+
+    /// Send a message to a participant in the background, directly if they are online and otherwise via store and
+    /// forward
+    fn send_message(&self, destination: CommsPublicKey, message: MultiSigMessage) {
+        let mut outbound_message_service = self.resources.outbound_message_service.clone();
+        let message = proto::MultiSigMessage::from(message);
+        tokio::spawn(async move {
+            if let Err(e) = send_multisig_message(destination, message, &mut outbound_message_service).await {
+                warn!(target: LOG_TARGET, "Failed to send multi-signature message: {}", e);
+            }
+        });
+    }
+
+    fn publish_event(&self, event: MultiSigEvent) {
+        let _ = self.resources.event_publisher.send(Arc::new(event)).map_err(|e| {
+            trace!(
+                target: LOG_TARGET,
+                "Error sending event because there are no subscribers: {:?}",
+                e
+            );
+            e
+        });
+    }
+}
+
+async fn send_multisig_message(
+    destination: CommsPublicKey,
+    message: proto::MultiSigMessage,
+    outbound_message_service: &mut OutboundMessageRequester,
+) -> Result<(), MultiSigError>
+{
+    let direct = outbound_message_service
+        .send_message(
+            SendMessageParams::new()
+                .direct_public_key(destination.clone())
+                .with_encryption(OutboundEncryption::EncryptFor(Box::new(destination.clone())))
+                .with_discovery(true)
+                .finish(),
+            OutboundDomainMessage::new(TariMessageType::MultiSig, message.clone()),
+        )
+        .await?;
+    if direct.resolve().await.is_ok() {
+        return Ok(());
+    }
+
+    debug!(
+        target: LOG_TARGET,
+        "Direct send of multi-signature message to {} failed, sending it for store and forward", destination
+    );
+    outbound_message_service
+        .closest_broadcast(
+            NodeId::from_public_key(&destination),
+            OutboundEncryption::EncryptFor(Box::new(destination)),
+            vec![],
+            OutboundDomainMessage::new(TariMessageType::MultiSig, message),
+        )
+        .await?;
+    Ok(())
+}
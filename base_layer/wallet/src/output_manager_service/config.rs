@@ -20,7 +20,8 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::time::Duration;
+use crate::output_manager_service::utxo_selection::{DefaultUtxoSelector, UtxoSelector};
+use std::{sync::Arc, time::Duration};
 
 #[derive(Clone, Debug)]
 pub struct OutputManagerServiceConfig {
@@ -28,6 +29,8 @@ pub struct OutputManagerServiceConfig {
     pub max_utxo_query_size: usize,
     pub prevent_fee_gt_amount: bool,
     pub peer_dial_retry_timeout: Duration,
+    /// The UTXO selector used when a transaction does not specify a selection strategy
+    pub utxo_selector: Arc<dyn UtxoSelector>,
 }
 
 impl Default for OutputManagerServiceConfig {
@@ -37,6 +40,7 @@ impl Default for OutputManagerServiceConfig {
             max_utxo_query_size: 5000,
             prevent_fee_gt_amount: true,
             peer_dial_retry_timeout: Duration::from_secs(20),
+            utxo_selector: Arc::new(DefaultUtxoSelector),
         }
     }
 }
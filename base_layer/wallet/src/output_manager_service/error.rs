@@ -63,6 +63,22 @@ pub enum OutputManagerError {
     IncompleteTransaction(&'static str),
     #[error("Not enough funds to fulfil transaction")]
     NotEnoughFunds,
+    #[error("Not enough funds from a single source to fulfil transaction without merging outputs")]
+    NotEnoughFundsFromSingleSource,
+    #[error("The selected input `{0}` is not an unspent, mature and unfrozen output of this wallet")]
+    InputNotSpendable(String),
+    #[error("The new fee per gram does not increase the fee of the transaction")]
+    FeeBumpTooLow,
+    #[error("The input `{0}` of the unsigned transaction does not belong to this wallet")]
+    UnknownInput(String),
+    #[error("This is a watch-only wallet, it cannot sign transactions")]
+    WatchOnlyWallet,
+    #[error("The account `{0}` does not exist")]
+    AccountNotFound(String),
+    #[error("An account named `{0}` already exists")]
+    DuplicateAccount(String),
+    #[error("A request can only name one account")]
+    NestedAccountRequest,
     #[error("Output already exists")]
     DuplicateOutput,
     #[error("Error sending a message to the public API")]
@@ -119,6 +135,8 @@ pub enum OutputManagerStorageError {
     ConversionError,
     #[error("Output has already been spent")]
     OutputAlreadySpent,
+    #[error("Tried to insert an account that already exists in the database")]
+    DuplicateAccount,
     #[error("Key Manager not initialized")]
     KeyManagerNotInitialized,
     #[error("Out of range error: `{0}`")]
@@ -25,7 +25,12 @@ use crate::{
         error::OutputManagerError,
         protocols::txo_validation_protocol::TxoValidationType,
         service::Balance,
-        storage::database::PendingTransactionOutputs,
+        storage::{
+            database::{Account, OutputMetadataUpdate, PendingTransactionOutputs, WatchOnlyKeys},
+            models::DbUnblindedOutput,
+        },
+        utxo_selection::UTXOSelectionStrategy,
+        AccountId,
         TxId,
     },
     types::ValidationRetryStrategy,
@@ -37,12 +42,12 @@ use tari_comms::types::CommsPublicKey;
 use tari_core::transactions::{
     tari_amount::MicroTari,
     transaction::{Transaction, TransactionInput, TransactionOutput, UnblindedOutput},
-    transaction_protocol::sender::TransactionSenderMessage,
-    types::PublicKey,
+    transaction_protocol::{partially_signed::UnsignedTransaction, sender::TransactionSenderMessage},
+    types::{Commitment, PublicKey},
     ReceiverTransactionProtocol,
     SenderTransactionProtocol,
 };
-use tari_service_framework::reply_channel::SenderService;
+use tari_service_framework::reply_channel::{SenderService, TransportResponseFuture};
 use tokio::sync::broadcast;
 use tower::Service;
 
@@ -54,23 +59,52 @@ pub enum OutputManagerRequest {
     GetCoinbaseTransaction((u64, MicroTari, MicroTari, u64)),
     ConfirmPendingTransaction(u64),
     ConfirmTransaction((u64, Vec<TransactionInput>, Vec<TransactionOutput>)),
-    PrepareToSendTransaction((MicroTari, MicroTari, Option<u64>, String)),
-    CreatePayToSelfTransaction((MicroTari, MicroTari, Option<u64>, String)),
+    PrepareToSendTransaction((MicroTari, MicroTari, Option<u64>, String, Option<UTXOSelectionStrategy>)),
+    PrepareToSendBatchTransaction(
+        (
+            Vec<MicroTari>,
+            MicroTari,
+            Option<u64>,
+            String,
+            Option<UTXOSelectionStrategy>,
+        ),
+    ),
+    PrepareToSendTransactionWithInputs((MicroTari, MicroTari, Option<u64>, String, Vec<Commitment>)),
+    CreatePayToSelfTransaction((MicroTari, MicroTari, Option<u64>, String, Option<UTXOSelectionStrategy>)),
+    CreatePayToSelfTransactionWithInputs((MicroTari, MicroTari, Option<u64>, String, Vec<Commitment>)),
+    CreateFeeBumpTransaction((TxId, Box<Transaction>, MicroTari)),
+    CreateMultiSigSpendOutput(MicroTari),
+    PrepareUnsignedTransaction((TxId, MicroTari, MicroTari)),
+    CreateSenderProtocolFromUnsigned((MicroTari, Box<UnsignedTransaction>, String)),
+    CompleteUnsignedTransaction((TxId, Box<Transaction>)),
     CancelTransaction(u64),
+    ReleaseFeeBumps((TxId, Vec<Commitment>)),
     TimeoutTransactions(Duration),
     GetPendingTransactions,
     GetSpentOutputs,
     GetUnspentOutputs,
+    GetUnspentOutputDetails,
     GetInvalidOutputs,
+    UpdateOutputMetadata((Commitment, OutputMetadataUpdate)),
     GetSeedWords,
     SetBaseNodePublicKey(CommsPublicKey),
     ValidateUtxos(TxoValidationType, ValidationRetryStrategy),
     CreateCoinSplit((MicroTari, usize, MicroTari, Option<u64>)),
+    CreateCoinSplitWithInputs((MicroTari, usize, MicroTari, Option<u64>, Vec<Commitment>)),
     ApplyEncryption(Box<Aes256Gcm>),
     RemoveEncryption,
     GetPublicRewindKeys,
+    GetWatchOnlyKeys,
     FeeEstimate((MicroTari, MicroTari, u64, u64)),
     RewindOutputs(Vec<TransactionOutput>),
+    /// A request that applies to the named account instead of the active account
+    WithAccount((String, Box<OutputManagerRequest>)),
+    CreateAccount(String),
+    GetAccounts,
+    GetActiveAccount,
+    SetActiveAccount(String),
+    GetAccountBalance(String),
+    GetTransactionAccounts,
 }
 
 impl fmt::Display for OutputManagerRequest {
@@ -82,24 +116,68 @@ impl fmt::Display for OutputManagerRequest {
             GetRecipientTransaction(_) => write!(f, "GetRecipientTransaction"),
             ConfirmTransaction(v) => write!(f, "ConfirmTransaction ({})", v.0),
             ConfirmPendingTransaction(v) => write!(f, "ConfirmPendingTransaction ({})", v),
-            PrepareToSendTransaction((_, _, _, msg)) => write!(f, "PrepareToSendTransaction ({})", msg),
-            CreatePayToSelfTransaction((_, _, _, msg)) => write!(f, "CreatePayToSelfTransaction ({})", msg),
+            PrepareToSendTransaction((_, _, _, msg, _)) => write!(f, "PrepareToSendTransaction ({})", msg),
+            PrepareToSendBatchTransaction((amounts, _, _, msg, _)) => write!(
+                f,
+                "PrepareToSendBatchTransaction ({} recipients, {})",
+                amounts.len(),
+                msg
+            ),
+            PrepareToSendTransactionWithInputs((_, _, _, msg, inputs)) => write!(
+                f,
+                "PrepareToSendTransactionWithInputs ({} inputs, {})",
+                inputs.len(),
+                msg
+            ),
+            CreatePayToSelfTransaction((_, _, _, msg, _)) => write!(f, "CreatePayToSelfTransaction ({})", msg),
+            CreatePayToSelfTransactionWithInputs((_, _, _, msg, inputs)) => write!(
+                f,
+                "CreatePayToSelfTransactionWithInputs ({} inputs, {})",
+                inputs.len(),
+                msg
+            ),
+            CreateFeeBumpTransaction((tx_id, _, fee_per_gram)) => {
+                write!(f, "CreateFeeBumpTransaction ({}, {})", tx_id, fee_per_gram)
+            },
+            CreateMultiSigSpendOutput(amount) => write!(f, "CreateMultiSigSpendOutput ({})", amount),
+            PrepareUnsignedTransaction((tx_id, amount, _)) => {
+                write!(f, "PrepareUnsignedTransaction ({}, {})", tx_id, amount)
+            },
+            CreateSenderProtocolFromUnsigned((amount, unsigned, _)) => write!(
+                f,
+                "CreateSenderProtocolFromUnsigned ({}, {} inputs)",
+                amount,
+                unsigned.inputs.len()
+            ),
+            CompleteUnsignedTransaction((tx_id, _)) => write!(f, "CompleteUnsignedTransaction ({})", tx_id),
             CancelTransaction(v) => write!(f, "CancelTransaction ({})", v),
+            ReleaseFeeBumps((tx_id, _)) => write!(f, "ReleaseFeeBumps ({})", tx_id),
             TimeoutTransactions(d) => write!(f, "TimeoutTransactions ({}s)", d.as_secs()),
             GetPendingTransactions => write!(f, "GetPendingTransactions"),
             GetSpentOutputs => write!(f, "GetSpentOutputs"),
             GetUnspentOutputs => write!(f, "GetUnspentOutputs"),
+            GetUnspentOutputDetails => write!(f, "GetUnspentOutputDetails"),
             GetInvalidOutputs => write!(f, "GetInvalidOutputs"),
+            UpdateOutputMetadata((_, update)) => write!(f, "UpdateOutputMetadata ({:?})", update),
             GetSeedWords => write!(f, "GetSeedWords"),
             SetBaseNodePublicKey(k) => write!(f, "SetBaseNodePublicKey ({})", k),
             ValidateUtxos(validation_type, retry) => write!(f, "{} ({:?})", validation_type, retry),
             CreateCoinSplit(v) => write!(f, "CreateCoinSplit ({})", v.0),
+            CreateCoinSplitWithInputs(v) => write!(f, "CreateCoinSplitWithInputs ({}, {} inputs)", v.0, v.4.len()),
             ApplyEncryption(_) => write!(f, "ApplyEncryption"),
             RemoveEncryption => write!(f, "RemoveEncryption"),
             GetCoinbaseTransaction(_) => write!(f, "GetCoinbaseTransaction"),
             GetPublicRewindKeys => write!(f, "GetPublicRewindKeys"),
+            GetWatchOnlyKeys => write!(f, "GetWatchOnlyKeys"),
             FeeEstimate(_) => write!(f, "FeeEstimate"),
             RewindOutputs(_) => write!(f, "RewindAndImportOutputs"),
+            WithAccount((name, request)) => write!(f, "{} (Account: {})", request, name),
+            CreateAccount(name) => write!(f, "CreateAccount ({})", name),
+            GetAccounts => write!(f, "GetAccounts"),
+            GetActiveAccount => write!(f, "GetActiveAccount"),
+            SetActiveAccount(name) => write!(f, "SetActiveAccount ({})", name),
+            GetAccountBalance(name) => write!(f, "GetAccountBalance ({})", name),
+            GetTransactionAccounts => write!(f, "GetTransactionAccounts"),
         }
     }
 }
@@ -114,13 +192,20 @@ pub enum OutputManagerResponse {
     OutputConfirmed,
     PendingTransactionConfirmed,
     PayToSelfTransaction((TxId, MicroTari, Transaction)),
+    FeeBumpTransaction((MicroTari, Transaction)),
+    MultiSigSpendOutput((TxId, UnblindedOutput, TransactionOutput)),
+    UnsignedTransactionInputs(Vec<TransactionOutput>),
+    UnsignedTransactionCompleted,
     TransactionConfirmed,
     TransactionToSend(SenderTransactionProtocol),
     TransactionCancelled,
+    FeeBumpsReleased,
     TransactionsTimedOut,
     PendingTransactions(HashMap<u64, PendingTransactionOutputs>),
     SpentOutputs(Vec<UnblindedOutput>),
     UnspentOutputs(Vec<UnblindedOutput>),
+    UnspentOutputDetails(Vec<DbUnblindedOutput>),
+    OutputMetadataUpdated,
     InvalidOutputs(Vec<UnblindedOutput>),
     SeedWords(Vec<String>),
     BaseNodePublicKeySet,
@@ -129,8 +214,13 @@ pub enum OutputManagerResponse {
     EncryptionApplied,
     EncryptionRemoved,
     PublicRewindKeys(Box<PublicRewindKeys>),
+    WatchOnlyKeys(Box<WatchOnlyKeys>),
     FeeEstimate(MicroTari),
     RewindOutputs(Vec<UnblindedOutput>),
+    AccountCreated(Account),
+    Accounts(Vec<Account>),
+    ActiveAccount(Account),
+    TransactionAccounts(HashMap<TxId, AccountId>),
 }
 
 pub type OutputManagerEventSender = broadcast::Sender<Arc<OutputManagerEvent>>;
@@ -157,6 +247,7 @@ pub struct PublicRewindKeys {
 pub struct OutputManagerHandle {
     handle: SenderService<OutputManagerRequest, Result<OutputManagerResponse, OutputManagerError>>,
     event_stream_sender: OutputManagerEventSender,
+    account: Option<String>,
 }
 
 impl OutputManagerHandle {
@@ -168,34 +259,105 @@ impl OutputManagerHandle {
         OutputManagerHandle {
             handle,
             event_stream_sender,
+            account: None,
+        }
+    }
+
+    /// Returns a handle whose requests apply to the named account instead of the active account. The account is sent
+    /// with every request so handles for different accounts can be used concurrently.
+    pub fn with_account(&self, account: String) -> Self {
+        OutputManagerHandle {
+            account: Some(account),
+            ..self.clone()
         }
     }
 
+    fn call(
+        &mut self,
+        request: OutputManagerRequest,
+    ) -> TransportResponseFuture<Result<OutputManagerResponse, OutputManagerError>>
+    {
+        let request = match self.account.clone() {
+            Some(account) => OutputManagerRequest::WithAccount((account, Box::new(request))),
+            None => request,
+        };
+        self.handle.call(request)
+    }
+
     pub fn get_event_stream_fused(&self) -> Fuse<OutputManagerEventReceiver> {
         self.event_stream_sender.subscribe().fuse()
     }
 
     pub async fn add_output(&mut self, output: UnblindedOutput) -> Result<(), OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::AddOutput(output)).await?? {
+        match self.call(OutputManagerRequest::AddOutput(output)).await?? {
             OutputManagerResponse::OutputAdded => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Returns the balance of the account of this handle, which is the active account unless one was selected with
+    /// `with_account`
     pub async fn get_balance(&mut self) -> Result<Balance, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetBalance).await?? {
+        match self.call(OutputManagerRequest::GetBalance).await?? {
+            OutputManagerResponse::Balance(b) => Ok(b),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_account_balance(&mut self, name: String) -> Result<Balance, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetAccountBalance(name)).await?? {
             OutputManagerResponse::Balance(b) => Ok(b),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Create a new named account with its own key branch, outputs and balance
+    pub async fn create_account(&mut self, name: String) -> Result<Account, OutputManagerError> {
+        match self.call(OutputManagerRequest::CreateAccount(name)).await?? {
+            OutputManagerResponse::AccountCreated(a) => Ok(a),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns all of the accounts of the wallet, starting with the default account
+    pub async fn get_accounts(&mut self) -> Result<Vec<Account>, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetAccounts).await?? {
+            OutputManagerResponse::Accounts(a) => Ok(a),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_active_account(&mut self) -> Result<Account, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetActiveAccount).await?? {
+            OutputManagerResponse::ActiveAccount(a) => Ok(a),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Select the account that is used by the requests that do not name an account, which includes receiving incoming
+    /// transactions. Only the outputs of the account of a request are spent.
+    pub async fn set_active_account(&mut self, name: String) -> Result<Account, OutputManagerError> {
+        match self.call(OutputManagerRequest::SetActiveAccount(name)).await?? {
+            OutputManagerResponse::ActiveAccount(a) => Ok(a),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the account that each transaction was created for. Transactions that are not included belong to the
+    /// default account.
+    pub async fn get_transaction_accounts(&mut self) -> Result<HashMap<TxId, AccountId>, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetTransactionAccounts).await?? {
+            OutputManagerResponse::TransactionAccounts(t) => Ok(t),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_recipient_transaction(
         &mut self,
         sender_message: TransactionSenderMessage,
     ) -> Result<ReceiverTransactionProtocol, OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::GetRecipientTransaction(sender_message))
             .await??
         {
@@ -213,7 +375,6 @@ impl OutputManagerHandle {
     ) -> Result<Transaction, OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::GetCoinbaseTransaction((
                 tx_id,
                 reward,
@@ -234,14 +395,81 @@ impl OutputManagerHandle {
         lock_height: Option<u64>,
         message: String,
     ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        self.prepare_transaction_to_send_with_utxo_selection(amount, fee_per_gram, lock_height, message, None)
+            .await
+    }
+
+    /// As `prepare_transaction_to_send` but overrides the configured UTXO selector with the specified selection
+    /// strategy
+    pub async fn prepare_transaction_to_send_with_utxo_selection(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::PrepareToSendTransaction((
                 amount,
                 fee_per_gram,
                 lock_height,
                 message,
+                utxo_selection_strategy,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// As `prepare_transaction_to_send` but spends exactly the unspent outputs with the given commitments instead of
+    /// selecting them. A change output is added if the inputs cover more than the amount and fee.
+    pub async fn prepare_transaction_to_send_with_inputs(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        inputs: Vec<Commitment>,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::PrepareToSendTransactionWithInputs((
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                inputs,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a single transaction that pays each of the provided amounts to a different recipient. The returned
+    /// protocol uses the multi-round negotiation if there is more than one amount.
+    pub async fn prepare_batch_transaction_to_send(
+        &mut self,
+        amounts: Vec<MicroTari>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::PrepareToSendBatchTransaction((
+                amounts,
+                fee_per_gram,
+                lock_height,
+                message,
+                utxo_selection_strategy,
             )))
             .await??
         {
@@ -261,7 +489,6 @@ impl OutputManagerHandle {
     ) -> Result<MicroTari, OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::FeeEstimate((
                 amount,
                 fee_per_gram,
@@ -277,7 +504,6 @@ impl OutputManagerHandle {
 
     pub async fn confirm_pending_transaction(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
         match self
-            .handle
             .call(OutputManagerRequest::ConfirmPendingTransaction(tx_id))
             .await??
         {
@@ -294,7 +520,6 @@ impl OutputManagerHandle {
     ) -> Result<(), OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::ConfirmTransaction((
                 tx_id,
                 spent_outputs,
@@ -308,22 +533,31 @@ impl OutputManagerHandle {
     }
 
     pub async fn cancel_transaction(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
-        match self
-            .handle
-            .call(OutputManagerRequest::CancelTransaction(tx_id))
-            .await??
-        {
+        match self.call(OutputManagerRequest::CancelTransaction(tx_id)).await?? {
             OutputManagerResponse::TransactionCancelled => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
-    pub async fn timeout_transactions(&mut self, period: Duration) -> Result<(), OutputManagerError> {
+    /// Release the inputs and change of the fee bumps of transaction `tx_id` whose kernels were not mined. The
+    /// excesses of the kernels of the transaction that were mined are provided.
+    pub async fn release_fee_bumps(
+        &mut self,
+        tx_id: TxId,
+        mined_kernel_excesses: Vec<Commitment>,
+    ) -> Result<(), OutputManagerError>
+    {
         match self
-            .handle
-            .call(OutputManagerRequest::TimeoutTransactions(period))
+            .call(OutputManagerRequest::ReleaseFeeBumps((tx_id, mined_kernel_excesses)))
             .await??
         {
+            OutputManagerResponse::FeeBumpsReleased => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn timeout_transactions(&mut self, period: Duration) -> Result<(), OutputManagerError> {
+        match self.call(OutputManagerRequest::TimeoutTransactions(period)).await?? {
             OutputManagerResponse::TransactionsTimedOut => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -332,14 +566,14 @@ impl OutputManagerHandle {
     pub async fn get_pending_transactions(
         &mut self,
     ) -> Result<HashMap<u64, PendingTransactionOutputs>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetPendingTransactions).await?? {
+        match self.call(OutputManagerRequest::GetPendingTransactions).await?? {
             OutputManagerResponse::PendingTransactions(p) => Ok(p),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_spent_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetSpentOutputs).await?? {
+        match self.call(OutputManagerRequest::GetSpentOutputs).await?? {
             OutputManagerResponse::SpentOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -347,36 +581,95 @@ impl OutputManagerHandle {
 
     /// Sorted from lowest value to highest
     pub async fn get_unspent_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetUnspentOutputs).await?? {
+        match self.call(OutputManagerRequest::GetUnspentOutputs).await?? {
             OutputManagerResponse::UnspentOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// The unspent outputs together with their labels, notes and frozen state. Sorted from lowest value to highest
+    pub async fn get_unspent_output_details(&mut self) -> Result<Vec<DbUnblindedOutput>, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetUnspentOutputDetails).await?? {
+            OutputManagerResponse::UnspentOutputDetails(s) => Ok(s),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Set or clear the label of the output with the given commitment
+    pub async fn set_output_label(
+        &mut self,
+        commitment: Commitment,
+        label: Option<String>,
+    ) -> Result<(), OutputManagerError>
+    {
+        self.update_output_metadata(commitment, OutputMetadataUpdate::Label(label))
+            .await
+    }
+
+    /// Set or clear the note of the output with the given commitment
+    pub async fn set_output_note(
+        &mut self,
+        commitment: Commitment,
+        note: Option<String>,
+    ) -> Result<(), OutputManagerError>
+    {
+        self.update_output_metadata(commitment, OutputMetadataUpdate::Note(note))
+            .await
+    }
+
+    /// Freeze or unfreeze the output with the given commitment. Frozen outputs are never selected to fund a
+    /// transaction.
+    pub async fn set_output_frozen(&mut self, commitment: Commitment, frozen: bool) -> Result<(), OutputManagerError> {
+        self.update_output_metadata(commitment, OutputMetadataUpdate::Frozen(frozen))
+            .await
+    }
+
+    async fn update_output_metadata(
+        &mut self,
+        commitment: Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::UpdateOutputMetadata((commitment, update)))
+            .await??
+        {
+            OutputManagerResponse::OutputMetadataUpdated => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_invalid_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetInvalidOutputs).await?? {
+        match self.call(OutputManagerRequest::GetInvalidOutputs).await?? {
             OutputManagerResponse::InvalidOutputs(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_seed_words(&mut self) -> Result<Vec<String>, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetSeedWords).await?? {
+        match self.call(OutputManagerRequest::GetSeedWords).await?? {
             OutputManagerResponse::SeedWords(s) => Ok(s),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
     pub async fn get_rewind_public_keys(&mut self) -> Result<PublicRewindKeys, OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::GetPublicRewindKeys).await?? {
+        match self.call(OutputManagerRequest::GetPublicRewindKeys).await?? {
             OutputManagerResponse::PublicRewindKeys(rk) => Ok(*rk),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
 
+    /// Returns the keys needed to construct a watch-only wallet that tracks the outputs of this wallet
+    pub async fn get_watch_only_keys(&mut self) -> Result<WatchOnlyKeys, OutputManagerError> {
+        match self.call(OutputManagerRequest::GetWatchOnlyKeys).await?? {
+            OutputManagerResponse::WatchOnlyKeys(keys) => Ok(*keys),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn set_base_node_public_key(&mut self, public_key: CommsPublicKey) -> Result<(), OutputManagerError> {
         match self
-            .handle
             .call(OutputManagerRequest::SetBaseNodePublicKey(public_key))
             .await??
         {
@@ -392,7 +685,6 @@ impl OutputManagerHandle {
     ) -> Result<u64, OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::ValidateUtxos(validation_type, retries))
             .await??
         {
@@ -412,7 +704,6 @@ impl OutputManagerHandle {
     ) -> Result<(u64, Transaction, MicroTari, MicroTari), OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::CreateCoinSplit((
                 amount_per_split,
                 split_count,
@@ -426,9 +717,34 @@ impl OutputManagerHandle {
         }
     }
 
+    /// As `create_coin_split` but splits exactly the unspent outputs with the given commitments.
+    /// Returns (tx_id, tx, fee, utxo_total_value).
+    pub async fn create_coin_split_with_inputs(
+        &mut self,
+        amount_per_split: MicroTari,
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        inputs: Vec<Commitment>,
+    ) -> Result<(u64, Transaction, MicroTari, MicroTari), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CreateCoinSplitWithInputs((
+                amount_per_split,
+                split_count,
+                fee_per_gram,
+                lock_height,
+                inputs,
+            )))
+            .await??
+        {
+            OutputManagerResponse::Transaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn apply_encryption(&mut self, cipher: Aes256Gcm) -> Result<(), OutputManagerError> {
         match self
-            .handle
             .call(OutputManagerRequest::ApplyEncryption(Box::new(cipher)))
             .await??
         {
@@ -438,7 +754,7 @@ impl OutputManagerHandle {
     }
 
     pub async fn remove_encryption(&mut self) -> Result<(), OutputManagerError> {
-        match self.handle.call(OutputManagerRequest::RemoveEncryption).await?? {
+        match self.call(OutputManagerRequest::RemoveEncryption).await?? {
             OutputManagerResponse::EncryptionRemoved => Ok(()),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -449,7 +765,7 @@ impl OutputManagerHandle {
         outputs: Vec<TransactionOutput>,
     ) -> Result<Vec<UnblindedOutput>, OutputManagerError>
     {
-        match self.handle.call(OutputManagerRequest::RewindOutputs(outputs)).await?? {
+        match self.call(OutputManagerRequest::RewindOutputs(outputs)).await?? {
             OutputManagerResponse::RewindOutputs(outputs) => Ok(outputs),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
@@ -462,14 +778,116 @@ impl OutputManagerHandle {
         lock_height: Option<u64>,
         message: String,
     ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
+    {
+        self.create_pay_to_self_transaction_with_utxo_selection(amount, fee_per_gram, lock_height, message, None)
+            .await
+    }
+
+    /// Create a replacement for the completed transaction `tx_id` that pays the specified fee per gram by spending
+    /// additional unspent outputs. Returns the total fee and the replacement transaction.
+    pub async fn create_fee_bump_transaction(
+        &mut self,
+        tx_id: TxId,
+        transaction: Transaction,
+        fee_per_gram: MicroTari,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CreateFeeBumpTransaction((
+                tx_id,
+                Box::new(transaction),
+                fee_per_gram,
+            )))
+            .await??
+        {
+            OutputManagerResponse::FeeBumpTransaction(result) => Ok(result),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Select and encumber the inputs for an unsigned transaction with the id `tx_id` that will be signed by another
+    /// wallet holding this wallet's spending keys. The inputs are returned as rewindable outputs so that the signing
+    /// wallet can recover their values and spending keys. This is available to a watch-only wallet.
+    pub async fn prepare_unsigned_transaction(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+    ) -> Result<Vec<TransactionOutput>, OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::PrepareUnsignedTransaction((
+                tx_id,
+                amount,
+                fee_per_gram,
+            )))
+            .await??
+        {
+            OutputManagerResponse::UnsignedTransactionInputs(inputs) => Ok(inputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a sender transaction protocol that spends the inputs of an unsigned transaction prepared by another
+    /// wallet sharing this wallet's keys. Nothing is recorded in this wallet's database.
+    pub async fn create_sender_protocol_from_unsigned(
+        &mut self,
+        amount: MicroTari,
+        unsigned: UnsignedTransaction,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CreateSenderProtocolFromUnsigned((
+                amount,
+                Box::new(unsigned),
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Record the change outputs of a transaction that was signed elsewhere against the inputs encumbered by
+    /// `prepare_unsigned_transaction`
+    pub async fn complete_unsigned_transaction(
+        &mut self,
+        tx_id: TxId,
+        transaction: Transaction,
+    ) -> Result<(), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CompleteUnsignedTransaction((
+                tx_id,
+                Box::new(transaction),
+            )))
+            .await??
+        {
+            OutputManagerResponse::UnsignedTransactionCompleted => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// As `create_pay_to_self_transaction` but overrides the configured UTXO selector with the specified selection
+    /// strategy
+    pub async fn create_pay_to_self_transaction_with_utxo_selection(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+    ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
     {
         match self
-            .handle
             .call(OutputManagerRequest::CreatePayToSelfTransaction((
                 amount,
                 fee_per_gram,
                 lock_height,
                 message,
+                utxo_selection_strategy,
             )))
             .await??
         {
@@ -477,4 +895,45 @@ impl OutputManagerHandle {
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
+
+    /// As `create_pay_to_self_transaction` but spends exactly the unspent outputs with the given commitments
+    pub async fn create_pay_to_self_transaction_with_inputs(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        inputs: Vec<Commitment>,
+    ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CreatePayToSelfTransactionWithInputs((
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                inputs,
+            )))
+            .await??
+        {
+            OutputManagerResponse::PayToSelfTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a new output of `amount` that receives the value of a multi-signature output being spent by this wallet.
+    /// The output is pending under the returned transaction id until the spending transaction is mined or cancelled.
+    pub async fn create_multisig_spend_output(
+        &mut self,
+        amount: MicroTari,
+    ) -> Result<(TxId, UnblindedOutput, TransactionOutput), OutputManagerError>
+    {
+        match self
+            .call(OutputManagerRequest::CreateMultiSigSpendOutput(amount))
+            .await??
+        {
+            OutputManagerResponse::MultiSigSpendOutput(output) => Ok(output),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
 }
@@ -52,10 +52,12 @@ pub mod protocols;
 #[allow(unused_assignments)]
 pub mod service;
 pub mod storage;
+pub mod utxo_selection;
 
 const LOG_TARGET: &str = "wallet::output_manager_service::initializer";
 
 pub type TxId = u64;
+pub type AccountId = u64;
 
 pub struct OutputManagerServiceInitializer<T>
 where T: OutputManagerBackend
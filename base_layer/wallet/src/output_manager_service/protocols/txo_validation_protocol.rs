@@ -32,7 +32,7 @@ use crate::{
 use futures::{FutureExt, StreamExt};
 use log::*;
 use std::{cmp, collections::HashMap, convert::TryFrom, fmt, sync::Arc, time::Duration};
-use tari_comms::{peer_manager::NodeId, types::CommsPublicKey, PeerConnection};
+use tari_comms::{peer_manager::NodeId, protocol::rpc::RpcClientLease, types::CommsPublicKey};
 use tari_core::{
     base_node::rpc::BaseNodeWalletRpcClient,
     proto::base_node::FetchMatchingUtxos,
@@ -179,7 +179,7 @@ where TBackend: OutputManagerBackend + 'static
 
             let base_node_node_id = NodeId::from_key(&self.base_node_public_key.clone())
                 .map_err(|e| OutputManagerProtocolError::new(self.id, OutputManagerError::from(e)))?;
-            let mut connection: Option<PeerConnection> = None;
+            let mut client: Option<RpcClientLease<BaseNodeWalletRpcClient>> = None;
 
             let delay = delay_for(self.resources.config.peer_dial_retry_timeout);
 
@@ -188,10 +188,13 @@ where TBackend: OutputManagerBackend + 'static
                 "Connecting to Base Node (Public Key: {})", self.base_node_public_key,
             );
             futures::select! {
-                dial_result = self.resources.connectivity_manager.dial_peer(base_node_node_id.clone()).fuse() => {
-                    match dial_result {
-                        Ok(base_node_connection) => {
-                            connection = Some(base_node_connection);
+                client_result = self.resources.connectivity_manager.get_rpc_client(
+                    base_node_node_id.clone(),
+                    BaseNodeWalletRpcClient::builder().with_deadline(self.resources.config.base_node_query_timeout),
+                ).fuse() => {
+                    match client_result {
+                        Ok(base_node_client) => {
+                            client = Some(base_node_client);
                         },
                         Err(e) => {
                             info!(target: LOG_TARGET, "Problem connecting to base node: {} for Output TXO Validation Validation Protocol: {}", e, self.id);
@@ -235,7 +238,7 @@ where TBackend: OutputManagerBackend + 'static
                 },
             }
 
-            let mut base_node_connection = match connection {
+            let mut client = match client {
                 None => {
                     futures::select! {
                         _ = delay.fuse() => {
@@ -262,21 +265,6 @@ where TBackend: OutputManagerBackend + 'static
                 },
                 Some(c) => c,
             };
-
-            let mut client = match base_node_connection
-                .connect_rpc_using_builder(
-                    BaseNodeWalletRpcClient::builder().with_deadline(self.resources.config.base_node_query_timeout),
-                )
-                .await
-            {
-                Ok(c) => c,
-                Err(e) => {
-                    warn!(target: LOG_TARGET, "Problem establishing RPC connection: {}", e);
-                    delay.await;
-                    retries += 1;
-                    continue;
-                },
-            };
             let mut batch_num = 0;
             debug!(target: LOG_TARGET, "RPC client connected");
             'per_batch: loop {
@@ -28,9 +28,20 @@ use crate::{
         handle::{OutputManagerEventSender, OutputManagerRequest, OutputManagerResponse, PublicRewindKeys},
         protocols::txo_validation_protocol::{TxoValidationProtocol, TxoValidationType},
         storage::{
-            database::{KeyManagerState, OutputManagerBackend, OutputManagerDatabase, PendingTransactionOutputs},
+            database::{
+                Account,
+                FeeBump,
+                KeyManagerState,
+                OutputManagerBackend,
+                OutputManagerDatabase,
+                OutputMetadataUpdate,
+                PendingTransactionOutputs,
+                WatchOnlyKeys,
+                DEFAULT_ACCOUNT_ID,
+            },
             models::DbUnblindedOutput,
         },
+        utxo_selection::{ManualUtxoSelector, UTXOSelectionStrategy, UtxoSelection, UtxoSelectionParams, UtxoSelector},
         TxId,
     },
     transaction_service::handle::TransactionServiceHandle,
@@ -39,12 +50,7 @@ use crate::{
 use futures::{pin_mut, stream::FuturesUnordered, StreamExt};
 use log::*;
 use rand::{rngs::OsRng, RngCore};
-use std::{
-    cmp::Ordering,
-    collections::HashMap,
-    fmt::{self, Display},
-    time::Duration,
-};
+use std::{collections::HashMap, fmt, time::Duration};
 use tari_comms::{connectivity::ConnectivityRequester, types::CommsPublicKey};
 use tari_core::{
     consensus::ConsensusConstants,
@@ -59,16 +65,18 @@ use tari_core::{
             TransactionOutput,
             UnblindedOutput,
         },
-        transaction_protocol::{sender::TransactionSenderMessage, RewindData},
-        types::{CryptoFactories, PrivateKey, PublicKey},
+        transaction_protocol::{partially_signed::UnsignedTransaction, sender::TransactionSenderMessage, RewindData},
+        types::{Commitment, CryptoFactories, PrivateKey, PublicKey},
         CoinbaseBuilder,
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
     },
 };
 use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
     keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait},
     range_proof::REWIND_USER_MESSAGE_LENGTH,
+    tari_utilities::hex::Hex,
 };
 use tari_key_manager::{
     key_manager::KeyManager,
@@ -87,6 +95,7 @@ const LOG_TARGET_STRESS: &str = "stress_test::output_manager_service";
 const KEY_MANAGER_COINBASE_BRANCH_KEY: &str = "coinbase";
 const KEY_MANAGER_RECOVERY_VIEWONLY_BRANCH_KEY: &str = "recovery_viewonly";
 const KEY_MANAGER_RECOVERY_BLINDING_BRANCH_KEY: &str = "recovery_blinding";
+const KEY_MANAGER_ACCOUNT_BRANCH_KEY: &str = "account";
 
 /// This service will manage a wallet's available outputs and the key manager that produces the keys for these outputs.
 /// The service will assemble transactions to be sent from the wallets available outputs and provide keys to receive
@@ -96,12 +105,16 @@ pub struct OutputManagerService<TBackend>
 where TBackend: OutputManagerBackend + 'static
 {
     resources: OutputManagerResources<TBackend>,
+    /// The key manager of the default account, the keys of the other accounts are derived from its master key
     key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
+    /// The account of the requests that do not name an account
+    active_account: Account,
     coinbase_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
     request_stream:
         Option<reply_channel::Receiver<OutputManagerRequest, Result<OutputManagerResponse, OutputManagerError>>>,
     base_node_update_publisher: broadcast::Sender<CommsPublicKey>,
     base_node_service: BaseNodeServiceHandle,
+    watch_only: bool,
 }
 
 impl<TBackend> OutputManagerService<TBackend>
@@ -124,6 +137,9 @@ where TBackend: OutputManagerBackend + 'static
         connectivity_manager: ConnectivityRequester,
     ) -> Result<OutputManagerService<TBackend>, OutputManagerError>
     {
+        // A watch-only wallet only holds the keys required to rewind its outputs, it has no master key
+        let watch_only_keys = db.get_watch_only_keys().await?;
+
         // Check to see if there is any persisted state, otherwise start fresh
         let key_manager_state = match db.get_key_manager_state().await? {
             None => {
@@ -132,7 +148,11 @@ where TBackend: OutputManagerBackend + 'static
                     branch_seed: "".to_string(),
                     primary_key_index: 0,
                 };
-                db.set_key_manager_state(starting_state.clone()).await?;
+                // The key managers of a watch-only wallet are never used to derive keys so their state is not
+                // persisted
+                if watch_only_keys.is_none() {
+                    db.set_key_manager_state(starting_state.clone()).await?;
+                }
                 starting_state
             },
             Some(km) => km,
@@ -144,25 +164,36 @@ where TBackend: OutputManagerBackend + 'static
             0,
         );
 
+        let active_account_id = db.get_active_account_id().await?;
+        let active_account = match db.get_accounts().await?.into_iter().find(|a| a.id == active_account_id) {
+            Some(account) => account,
+            None => Account::default_account(),
+        };
         let key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
             key_manager_state.master_key.clone(),
-            key_manager_state.branch_seed,
+            key_manager_state.branch_seed.clone(),
             key_manager_state.primary_key_index,
         );
 
-        let rewind_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
-            key_manager_state.master_key.clone(),
-            KEY_MANAGER_RECOVERY_VIEWONLY_BRANCH_KEY.to_string(),
-            0,
-        );
-        let rewind_key = rewind_key_manager.derive_key(0)?.k;
-
-        let rewind_blinding_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
-            key_manager_state.master_key,
-            KEY_MANAGER_RECOVERY_BLINDING_BRANCH_KEY.to_string(),
-            0,
-        );
-        let rewind_blinding_key = rewind_blinding_key_manager.derive_key(0)?.k;
+        let (rewind_key, rewind_blinding_key) = match watch_only_keys.as_ref() {
+            Some(keys) => (keys.rewind_key.clone(), keys.rewind_blinding_key.clone()),
+            None => {
+                let rewind_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+                    key_manager_state.master_key.clone(),
+                    KEY_MANAGER_RECOVERY_VIEWONLY_BRANCH_KEY.to_string(),
+                    0,
+                );
+                let rewind_blinding_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+                    key_manager_state.master_key,
+                    KEY_MANAGER_RECOVERY_BLINDING_BRANCH_KEY.to_string(),
+                    0,
+                );
+                (
+                    rewind_key_manager.derive_key(0)?.k,
+                    rewind_blinding_key_manager.derive_key(0)?.k,
+                )
+            },
+        };
 
         let rewind_data = RewindData {
             rewind_key,
@@ -192,10 +223,12 @@ where TBackend: OutputManagerBackend + 'static
         Ok(OutputManagerService {
             resources,
             key_manager: Mutex::new(key_manager),
+            active_account,
             coinbase_key_manager: Mutex::new(coinbase_key_manager),
             request_stream: Some(request_stream),
             base_node_update_publisher,
             base_node_service,
+            watch_only: watch_only_keys.is_some(),
         })
     }
 
@@ -256,37 +289,143 @@ where TBackend: OutputManagerBackend + 'static
     ) -> Result<OutputManagerResponse, OutputManagerError>
     {
         trace!(target: LOG_TARGET, "Handling Service Request: {}", request);
+        // The account is resolved for every request so that requests for different accounts do not depend on the
+        // order in which they arrive
+        let (account, request) = match request {
+            OutputManagerRequest::WithAccount((name, request)) => (self.find_account(&name).await?, *request),
+            request => (self.active_account.clone(), request),
+        };
+        if self.watch_only && OutputManagerService::<TBackend>::requires_spending_keys(&request) {
+            warn!(
+                target: LOG_TARGET,
+                "Refusing request {} as this is a watch-only wallet", request
+            );
+            return Err(OutputManagerError::WatchOnlyWallet);
+        }
         match request {
-            OutputManagerRequest::AddOutput(uo) => {
-                self.add_output(uo).await.map(|_| OutputManagerResponse::OutputAdded)
-            },
+            OutputManagerRequest::AddOutput(uo) => self
+                .add_output(&account, uo)
+                .await
+                .map(|_| OutputManagerResponse::OutputAdded),
             OutputManagerRequest::GetBalance => {
                 let current_chain_tip = match self.base_node_service.get_chain_metadata().await {
                     Ok(metadata) => metadata.map(|m| m.height_of_longest_chain()),
                     Err(_) => None,
                 };
-                self.get_balance(current_chain_tip)
+                self.get_balance(&account, current_chain_tip)
                     .await
                     .map(OutputManagerResponse::Balance)
             },
             OutputManagerRequest::GetRecipientTransaction(tsm) => self
-                .get_recipient_transaction(tsm)
+                .get_recipient_transaction(&account, tsm)
                 .await
                 .map(OutputManagerResponse::RecipientTransactionGenerated),
             OutputManagerRequest::GetCoinbaseTransaction((tx_id, reward, fees, block_height)) => self
-                .get_coinbase_transaction(tx_id, reward, fees, block_height)
+                .get_coinbase_transaction(&account, tx_id, reward, fees, block_height)
                 .await
                 .map(OutputManagerResponse::CoinbaseTransaction),
-            OutputManagerRequest::PrepareToSendTransaction((amount, fee_per_gram, lock_height, message)) => self
-                .prepare_transaction_to_send(amount, fee_per_gram, lock_height, message)
+            OutputManagerRequest::PrepareToSendTransaction((amount, fee_per_gram, lock_height, message, strategy)) => {
+                self.prepare_transaction_to_send(
+                    &account,
+                    amount,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    strategy.as_ref().map(|s| s as &dyn UtxoSelector),
+                )
+                .await
+                .map(OutputManagerResponse::TransactionToSend)
+            },
+            OutputManagerRequest::PrepareToSendTransactionWithInputs((
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                inputs,
+            )) => self
+                .prepare_transaction_to_send(
+                    &account,
+                    amount,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    Some(&ManualUtxoSelector::new(inputs)),
+                )
                 .await
                 .map(OutputManagerResponse::TransactionToSend),
-            OutputManagerRequest::CreatePayToSelfTransaction((amount, fee_per_gram, lock_height, message)) => self
-                .create_pay_to_self_transaction(amount, fee_per_gram, lock_height, message)
+            OutputManagerRequest::PrepareToSendBatchTransaction((
+                amounts,
+                fee_per_gram,
+                lock_height,
+                message,
+                strategy,
+            )) => self
+                .prepare_batch_transaction_to_send(
+                    &account,
+                    amounts,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    strategy.as_ref().map(|s| s as &dyn UtxoSelector),
+                )
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::CreatePayToSelfTransaction((
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                strategy,
+            )) => self
+                .create_pay_to_self_transaction(
+                    &account,
+                    amount,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    strategy.as_ref().map(|s| s as &dyn UtxoSelector),
+                )
+                .await
+                .map(OutputManagerResponse::PayToSelfTransaction),
+            OutputManagerRequest::CreatePayToSelfTransactionWithInputs((
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                inputs,
+            )) => self
+                .create_pay_to_self_transaction(
+                    &account,
+                    amount,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    Some(&ManualUtxoSelector::new(inputs)),
+                )
                 .await
                 .map(OutputManagerResponse::PayToSelfTransaction),
+            OutputManagerRequest::CreateMultiSigSpendOutput(amount) => self
+                .create_multisig_spend_output(&account, amount)
+                .await
+                .map(OutputManagerResponse::MultiSigSpendOutput),
+            OutputManagerRequest::CreateFeeBumpTransaction((tx_id, transaction, fee_per_gram)) => self
+                .create_fee_bump_transaction(&account, tx_id, *transaction, fee_per_gram)
+                .await
+                .map(OutputManagerResponse::FeeBumpTransaction),
+            OutputManagerRequest::PrepareUnsignedTransaction((tx_id, amount, fee_per_gram)) => self
+                .prepare_unsigned_transaction(&account, tx_id, amount, fee_per_gram)
+                .await
+                .map(OutputManagerResponse::UnsignedTransactionInputs),
+            OutputManagerRequest::CreateSenderProtocolFromUnsigned((amount, unsigned, message)) => self
+                .create_sender_protocol_from_unsigned(&account, amount, *unsigned, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::CompleteUnsignedTransaction((tx_id, transaction)) => self
+                .complete_unsigned_transaction(tx_id, *transaction)
+                .await
+                .map(|_| OutputManagerResponse::UnsignedTransactionCompleted),
             OutputManagerRequest::FeeEstimate((amount, fee_per_gram, num_kernels, num_outputs)) => self
-                .fee_estimate(amount, fee_per_gram, num_kernels, num_outputs)
+                .fee_estimate(&account, amount, fee_per_gram, num_kernels, num_outputs)
                 .await
                 .map(OutputManagerResponse::FeeEstimate),
             OutputManagerRequest::ConfirmPendingTransaction(tx_id) => self
@@ -301,6 +440,10 @@ where TBackend: OutputManagerBackend + 'static
                 .cancel_transaction(tx_id)
                 .await
                 .map(|_| OutputManagerResponse::TransactionCancelled),
+            OutputManagerRequest::ReleaseFeeBumps((tx_id, mined_kernel_excesses)) => self
+                .release_fee_bumps(tx_id, &mined_kernel_excesses)
+                .await
+                .map(|_| OutputManagerResponse::FeeBumpsReleased),
             OutputManagerRequest::TimeoutTransactions(period) => self
                 .timeout_pending_transactions(period)
                 .await
@@ -327,6 +470,14 @@ where TBackend: OutputManagerBackend + 'static
                     .collect();
                 Ok(OutputManagerResponse::UnspentOutputs(outputs))
             },
+            OutputManagerRequest::GetUnspentOutputDetails => self
+                .fetch_unspent_outputs()
+                .await
+                .map(OutputManagerResponse::UnspentOutputDetails),
+            OutputManagerRequest::UpdateOutputMetadata((commitment, update)) => self
+                .update_output_metadata(commitment, update)
+                .await
+                .map(|_| OutputManagerResponse::OutputMetadataUpdated),
             OutputManagerRequest::GetSeedWords => self.get_seed_words().await.map(OutputManagerResponse::SeedWords),
             OutputManagerRequest::SetBaseNodePublicKey(pk) => self
                 .set_base_node_public_key(pk)
@@ -345,7 +496,31 @@ where TBackend: OutputManagerBackend + 'static
                 Ok(OutputManagerResponse::InvalidOutputs(outputs))
             },
             OutputManagerRequest::CreateCoinSplit((amount_per_split, split_count, fee_per_gram, lock_height)) => self
-                .create_coin_split(amount_per_split, split_count, fee_per_gram, lock_height)
+                .create_coin_split(
+                    &account,
+                    amount_per_split,
+                    split_count,
+                    fee_per_gram,
+                    lock_height,
+                    &UTXOSelectionStrategy::Largest,
+                )
+                .await
+                .map(OutputManagerResponse::Transaction),
+            OutputManagerRequest::CreateCoinSplitWithInputs((
+                amount_per_split,
+                split_count,
+                fee_per_gram,
+                lock_height,
+                inputs,
+            )) => self
+                .create_coin_split(
+                    &account,
+                    amount_per_split,
+                    split_count,
+                    fee_per_gram,
+                    lock_height,
+                    &ManualUtxoSelector::new(inputs),
+                )
                 .await
                 .map(OutputManagerResponse::Transaction),
             OutputManagerRequest::ApplyEncryption(cipher) => self
@@ -365,13 +540,66 @@ where TBackend: OutputManagerBackend + 'static
             OutputManagerRequest::GetPublicRewindKeys => Ok(OutputManagerResponse::PublicRewindKeys(Box::new(
                 self.get_rewind_public_keys(),
             ))),
+            OutputManagerRequest::GetWatchOnlyKeys => Ok(OutputManagerResponse::WatchOnlyKeys(Box::new(
+                self.get_watch_only_keys(),
+            ))),
             OutputManagerRequest::RewindOutputs(outputs) => self
                 .rewind_outputs(outputs)
                 .await
                 .map(OutputManagerResponse::RewindOutputs),
+            OutputManagerRequest::CreateAccount(name) => self
+                .create_account(name)
+                .await
+                .map(OutputManagerResponse::AccountCreated),
+            OutputManagerRequest::GetAccounts => self.get_accounts().await.map(OutputManagerResponse::Accounts),
+            OutputManagerRequest::GetActiveAccount => {
+                Ok(OutputManagerResponse::ActiveAccount(self.active_account.clone()))
+            },
+            OutputManagerRequest::SetActiveAccount(name) => self
+                .set_active_account(&name)
+                .await
+                .map(OutputManagerResponse::ActiveAccount),
+            OutputManagerRequest::GetAccountBalance(name) => {
+                let current_chain_tip = match self.base_node_service.get_chain_metadata().await {
+                    Ok(metadata) => metadata.map(|m| m.height_of_longest_chain()),
+                    Err(_) => None,
+                };
+                self.get_account_balance(&name, current_chain_tip)
+                    .await
+                    .map(OutputManagerResponse::Balance)
+            },
+            OutputManagerRequest::GetTransactionAccounts => self
+                .resources
+                .db
+                .get_transaction_accounts()
+                .await
+                .map(OutputManagerResponse::TransactionAccounts)
+                .map_err(OutputManagerError::from),
+            OutputManagerRequest::WithAccount(_) => Err(OutputManagerError::NestedAccountRequest),
         }
     }
 
+    /// Returns true for the requests that need the master key to derive spending keys or to sign, these are refused by
+    /// a watch-only wallet.
+    fn requires_spending_keys(request: &OutputManagerRequest) -> bool {
+        matches!(
+            request,
+            OutputManagerRequest::GetRecipientTransaction(_) |
+                OutputManagerRequest::GetCoinbaseTransaction(_) |
+                OutputManagerRequest::PrepareToSendTransaction(_) |
+                OutputManagerRequest::PrepareToSendTransactionWithInputs(_) |
+                OutputManagerRequest::PrepareToSendBatchTransaction(_) |
+                OutputManagerRequest::CreatePayToSelfTransaction(_) |
+                OutputManagerRequest::CreatePayToSelfTransactionWithInputs(_) |
+                OutputManagerRequest::CreateFeeBumpTransaction(_) |
+                OutputManagerRequest::CreateMultiSigSpendOutput(_) |
+                OutputManagerRequest::CreateSenderProtocolFromUnsigned(_) |
+                OutputManagerRequest::CreateCoinSplit(_) |
+                OutputManagerRequest::CreateCoinSplitWithInputs(_) |
+                OutputManagerRequest::GetSeedWords
+        )
+    }
+
     fn validate_outputs(
         &mut self,
         validation_type: TxoValidationType,
@@ -419,33 +647,110 @@ where TBackend: OutputManagerBackend + 'static
     }
 
     /// Add an unblinded output to the unspent outputs list
-    pub async fn add_output(&mut self, output: UnblindedOutput) -> Result<(), OutputManagerError> {
+    pub async fn add_output(&mut self, account: &Account, output: UnblindedOutput) -> Result<(), OutputManagerError> {
         debug!(
             target: LOG_TARGET,
             "Add output of value {} to Output Manager", output.value
         );
-        let output = DbUnblindedOutput::from_unblinded_output(output, &self.resources.factories)?;
+        let mut output = DbUnblindedOutput::from_unblinded_output(output, &self.resources.factories)?;
+        output.account_id = account.id;
         Ok(self.resources.db.add_unspent_output(output).await?)
     }
 
-    async fn get_balance(&self, current_chain_tip: Option<u64>) -> Result<Balance, OutputManagerError> {
-        let balance = self.resources.db.get_balance(current_chain_tip).await?;
+    /// Returns the balance of the given account
+    async fn get_balance(
+        &self,
+        account: &Account,
+        current_chain_tip: Option<u64>,
+    ) -> Result<Balance, OutputManagerError>
+    {
+        let balance = self
+            .resources
+            .db
+            .get_account_balance(account.id, current_chain_tip)
+            .await?;
         trace!(target: LOG_TARGET, "Balance: {:?}", balance);
         Ok(balance)
     }
 
+    async fn get_account_balance(
+        &self,
+        name: &str,
+        current_chain_tip: Option<u64>,
+    ) -> Result<Balance, OutputManagerError>
+    {
+        let account = self.find_account(name).await?;
+        Ok(self
+            .resources
+            .db
+            .get_account_balance(account.id, current_chain_tip)
+            .await?)
+    }
+
+    /// Returns the default account followed by the created accounts
+    async fn get_accounts(&self) -> Result<Vec<Account>, OutputManagerError> {
+        let mut accounts = vec![Account::default_account()];
+        accounts.append(&mut self.resources.db.get_accounts().await?);
+        Ok(accounts)
+    }
+
+    async fn find_account(&self, name: &str) -> Result<Account, OutputManagerError> {
+        self.get_accounts()
+            .await?
+            .into_iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| OutputManagerError::AccountNotFound(name.to_string()))
+    }
+
+    /// Create a new named account. The keys of the account are derived from its own branch of the master key.
+    async fn create_account(&mut self, name: String) -> Result<Account, OutputManagerError> {
+        let accounts = self.get_accounts().await?;
+        if accounts.iter().any(|a| a.name == name) {
+            return Err(OutputManagerError::DuplicateAccount(name));
+        }
+        let account = Account {
+            id: accounts.iter().map(|a| a.id).max().unwrap_or(DEFAULT_ACCOUNT_ID) + 1,
+            name,
+            primary_key_index: 0,
+        };
+        self.resources.db.add_account(account.clone()).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Created account {} (Id: {})", account.name, account.id
+        );
+        Ok(account)
+    }
+
+    /// Select the account of the requests that do not name an account, which includes receiving incoming transactions
+    async fn set_active_account(&mut self, name: &str) -> Result<Account, OutputManagerError> {
+        let account = self.find_account(name).await?;
+        self.resources.db.set_active_account_id(account.id).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Active account set to {} (Id: {})", account.name, account.id
+        );
+        self.active_account = account.clone();
+        Ok(account)
+    }
+
+    /// Record that a transaction was created for the given account
+    async fn set_transaction_account(&self, account: &Account, tx_id: TxId) -> Result<(), OutputManagerError> {
+        Ok(self.resources.db.set_transaction_account(tx_id, account.id).await?)
+    }
+
     /// Request a receiver transaction be generated from the supplied Sender Message
     async fn get_recipient_transaction(
         &mut self,
+        account: &Account,
         sender_message: TransactionSenderMessage,
     ) -> Result<ReceiverTransactionProtocol, OutputManagerError>
     {
-        let (tx_id, amount) = match sender_message.single() {
-            Some(data) => (data.tx_id, data.amount),
+        let (tx_id, amount) = match (sender_message.tx_id(), sender_message.amount()) {
+            (Some(tx_id), Some(amount)) => (tx_id, amount),
             _ => return Err(OutputManagerError::InvalidSenderMessage),
         };
 
-        let key = self.get_next_spend_key().await?;
+        let key = self.get_next_spend_key(account).await?;
         self.resources
             .db
             .accept_incoming_pending_transaction(
@@ -455,8 +760,10 @@ where TBackend: OutputManagerBackend + 'static
                 OutputFeatures::default(),
                 &self.resources.factories,
                 None,
+                account.id,
             )
             .await?;
+        self.set_transaction_account(account, tx_id).await?;
 
         self.confirm_encumberance(tx_id).await?;
 
@@ -480,6 +787,7 @@ where TBackend: OutputManagerBackend + 'static
     /// keychain is based on the wallets master_key and the "coinbase" branch.
     async fn get_coinbase_transaction(
         &mut self,
+        account: &Account,
         tx_id: TxId,
         reward: MicroTari,
         fees: MicroTari,
@@ -513,8 +821,10 @@ where TBackend: OutputManagerBackend + 'static
                 ),
                 &self.resources.factories,
                 Some(block_height),
+                account.id,
             )
             .await?;
+        self.set_transaction_account(account, tx_id).await?;
 
         self.confirm_encumberance(tx_id).await?;
         Ok(tx)
@@ -565,6 +875,7 @@ where TBackend: OutputManagerBackend + 'static
     /// outputs.
     async fn fee_estimate(
         &mut self,
+        account: &Account,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         num_kernels: u64,
@@ -580,12 +891,17 @@ where TBackend: OutputManagerBackend + 'static
             num_outputs
         );
 
-        let (utxos, _) = self
-            .select_utxos(amount, fee_per_gram, num_outputs as usize, None)
+        let selection = self
+            .select_utxos(account, amount, fee_per_gram, num_outputs as usize, None)
             .await?;
-        debug!(target: LOG_TARGET, "{} utxos selected.", utxos.len());
+        debug!(target: LOG_TARGET, "{} utxos selected.", selection.utxos.len());
 
-        let fee = Fee::calculate_with_minimum(fee_per_gram, num_kernels as usize, utxos.len(), num_outputs as usize);
+        let fee = Fee::calculate_with_minimum(
+            fee_per_gram,
+            num_kernels as usize,
+            selection.utxos.len(),
+            num_outputs as usize,
+        );
 
         debug!(target: LOG_TARGET, "Fee calculated: {}", fee);
         Ok(fee)
@@ -595,33 +911,61 @@ where TBackend: OutputManagerBackend + 'static
     /// will be produced.
     pub async fn prepare_transaction_to_send(
         &mut self,
+        account: &Account,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
+        utxo_selector: Option<&dyn UtxoSelector>,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        self.prepare_batch_transaction_to_send(account, vec![amount], fee_per_gram, lock_height, message, utxo_selector)
+            .await
+    }
+
+    /// Prepare a transaction paying each of `amounts` to a different recipient, encumbering the selected outputs
+    pub async fn prepare_batch_transaction_to_send(
+        &mut self,
+        account: &Account,
+        amounts: Vec<MicroTari>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+        utxo_selector: Option<&dyn UtxoSelector>,
     ) -> Result<SenderTransactionProtocol, OutputManagerError>
     {
+        if amounts.is_empty() {
+            return Err(OutputManagerError::BuildError(
+                "A transaction to send requires at least one recipient".to_string(),
+            ));
+        }
+        let amount: MicroTari = amounts.iter().sum();
         debug!(
             target: LOG_TARGET,
-            "Preparing to send transaction. Amount: {}. Fee per gram: {}. ", amount, fee_per_gram,
+            "Preparing to send transaction. Amount: {}. Recipients: {}. Fee per gram: {}. ",
+            amount,
+            amounts.len(),
+            fee_per_gram,
         );
-        let (outputs, _) = self.select_utxos(amount, fee_per_gram, 1, None).await?;
-        let total = outputs
-            .iter()
-            .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
+        let selection = self
+            .select_utxos(account, amount, fee_per_gram, amounts.len(), utxo_selector)
+            .await?;
+        let outputs = selection.utxos;
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
 
-        let mut builder = SenderTransactionProtocol::builder(1);
+        let mut builder = SenderTransactionProtocol::builder(amounts.len());
         builder
             .with_lock_height(lock_height.unwrap_or(0))
             .with_fee_per_gram(fee_per_gram)
             .with_offset(offset.clone())
             .with_private_nonce(nonce.clone())
-            .with_amount(0, amount)
             .with_message(message)
             .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount);
+        for (i, a) in amounts.iter().enumerate() {
+            builder.with_amount(i, *a);
+        }
 
         for uo in outputs.iter() {
             builder.with_input(
@@ -638,12 +982,11 @@ where TBackend: OutputManagerBackend + 'static
             amount,
             outputs.len()
         );
-        let fee_without_change = Fee::calculate(fee_per_gram, 1, outputs.len(), 1);
         let mut change_key: Option<PrivateKey> = None;
-        // If the input values > the amount to be sent + fee_without_change then we will need to include a change
-        // output
-        if total > amount + fee_without_change {
-            let key = self.get_next_spend_key().await?;
+        // If the selected input values cover more than the amount to be sent, the fee and the cost of a change output
+        // then we will need to include a change output
+        if selection.requires_change_output {
+            let key = self.get_next_spend_key(account).await?;
             change_key = Some(key.clone());
             builder.with_rewindable_change_secret(key, self.resources.rewind_data.clone());
         }
@@ -655,10 +998,12 @@ where TBackend: OutputManagerBackend + 'static
         // If a change output was created add it to the pending_outputs list.
         let mut change_output = Vec::<DbUnblindedOutput>::new();
         if let Some(key) = change_key {
-            change_output.push(DbUnblindedOutput::from_unblinded_output(
+            let mut output = DbUnblindedOutput::from_unblinded_output(
                 UnblindedOutput::new(stp.get_amount_to_self()?, key, None),
                 &self.resources.factories,
-            )?);
+            )?;
+            output.account_id = account.id;
+            change_output.push(output);
         }
 
         let tx_id = stp.get_tx_id()?;
@@ -668,6 +1013,7 @@ where TBackend: OutputManagerBackend + 'static
             .db
             .encumber_outputs(tx_id, outputs, change_output)
             .await?;
+        self.set_transaction_account(account, tx_id).await?;
 
         debug!(target: LOG_TARGET, "Prepared transaction (TxId: {}) to send", tx_id);
         debug!(
@@ -680,14 +1026,18 @@ where TBackend: OutputManagerBackend + 'static
 
     async fn create_pay_to_self_transaction(
         &mut self,
+        account: &Account,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
+        utxo_selector: Option<&dyn UtxoSelector>,
     ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
     {
-        let (inputs, _) = self.select_utxos(amount, fee_per_gram, 1, None).await?;
-        let total = inputs.iter().map(|x| x.unblinded_output.value).sum::<MicroTari>();
+        let selection = self
+            .select_utxos(account, amount, fee_per_gram, 1, utxo_selector)
+            .await?;
+        let inputs = selection.utxos;
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
@@ -713,21 +1063,20 @@ where TBackend: OutputManagerBackend + 'static
             );
         }
 
-        let spend_key = self.get_next_spend_key().await?;
-        let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+        let spend_key = self.get_next_spend_key(account).await?;
+        let mut utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
             UnblindedOutput::new(amount, spend_key, None),
             &self.resources.factories,
             &self.resources.rewind_data,
         )?;
+        utxo.account_id = account.id;
         builder.with_output(utxo.unblinded_output.clone());
 
         let mut outputs = vec![utxo];
         let mut change_key = None;
 
-        let fee = Fee::calculate(fee_per_gram, 1, inputs.len(), 1);
-        let change_value = total.saturating_sub(amount).saturating_sub(fee);
-        if change_value > 0.into() {
-            let key = self.get_next_spend_key().await?;
+        if selection.requires_change_output {
+            let key = self.get_next_spend_key(account).await?;
             change_key = Some(key.clone());
             builder.with_rewindable_change_secret(key, self.resources.rewind_data.clone());
         }
@@ -739,11 +1088,12 @@ where TBackend: OutputManagerBackend + 'static
 
         if let Some(key) = change_key {
             let change_amount = stp.get_change_amount()?;
-            let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
+            let mut change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
                 UnblindedOutput::new(change_amount, key, None),
                 &self.resources.factories,
                 &self.resources.rewind_data,
             )?;
+            change_output.account_id = account.id;
 
             outputs.push(change_output);
         }
@@ -755,6 +1105,7 @@ where TBackend: OutputManagerBackend + 'static
             tx_id
         );
         self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.set_transaction_account(account, tx_id).await?;
         self.confirm_encumberance(tx_id).await?;
         let fee = stp.get_fee_amount()?;
         trace!(target: LOG_TARGET, "Finalize send-to-self transaction ({}).", tx_id);
@@ -764,6 +1115,301 @@ where TBackend: OutputManagerBackend + 'static
         Ok((tx_id, fee, tx))
     }
 
+    /// Create the output that receives the value of a multi-signature output spent by this wallet. The output is
+    /// encumbered as a pending incoming output of a new transaction id, so it is confirmed once the spending
+    /// transaction is mined and released if it is cancelled.
+    async fn create_multisig_spend_output(
+        &mut self,
+        account: &Account,
+        amount: MicroTari,
+    ) -> Result<(TxId, UnblindedOutput, TransactionOutput), OutputManagerError>
+    {
+        let tx_id = OsRng.next_u64();
+        let spend_key = self.get_next_spend_key(account).await?;
+        let mut utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+            UnblindedOutput::new(amount, spend_key, None),
+            &self.resources.factories,
+            &self.resources.rewind_data,
+        )?;
+        utxo.account_id = account.id;
+        let output = utxo
+            .unblinded_output
+            .as_rewindable_transaction_output(&self.resources.factories, &self.resources.rewind_data)?;
+        let unblinded_output = utxo.unblinded_output.clone();
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, Vec::new(), vec![utxo])
+            .await?;
+        self.set_transaction_account(account, tx_id).await?;
+        self.confirm_encumberance(tx_id).await?;
+
+        Ok((tx_id, unblinded_output, output))
+    }
+
+    /// Create a replacement for a completed transaction that pays the specified fee per gram. The replacement is the
+    /// original transaction aggregated with a send-to-self transaction that spends additional unspent outputs, so the
+    /// original kernel and its signatures remain valid and the counterparty does not need to be involved. The
+    /// additional inputs and change output, if one is required, are encumbered against a pending transaction of their
+    /// own that is linked to the original transaction by the kernel excess of the bump, so that they can be confirmed
+    /// if the replacement is mined and released if the original transaction is mined instead.
+    async fn create_fee_bump_transaction(
+        &mut self,
+        account: &Account,
+        tx_id: TxId,
+        transaction: Transaction,
+        fee_per_gram: MicroTari,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError>
+    {
+        let original_fee = transaction.body.get_total_fee();
+        let original_weight = transaction.calculate_weight();
+        // The fee the original transaction is short of at the new fee per gram
+        let fee_shortfall = (original_weight * fee_per_gram)
+            .checked_sub(original_fee)
+            .filter(|shortfall| *shortfall > MicroTari::from(0))
+            .ok_or(OutputManagerError::FeeBumpTooLow)?;
+        debug!(
+            target: LOG_TARGET,
+            "Bumping fee of transaction (TxId: {}) to {} per gram, shortfall of {}", tx_id, fee_per_gram, fee_shortfall
+        );
+
+        // The bump transaction pays for its own weight and the shortfall of the original transaction at a whole fee per
+        // gram. The shortfall is spread over the weight of the smallest possible bump transaction (a single input and
+        // no outputs) so that the fee per gram covers it whatever the inputs and change turn out to be, and the UTXOs
+        // are selected against the final fee of the bump transaction.
+        let min_bump_weight = Fee::calculate_weight(1, 1, 0);
+        let bump_fee_per_gram =
+            fee_per_gram + MicroTari::from((u64::from(fee_shortfall) + min_bump_weight - 1) / min_bump_weight);
+        let selection = self
+            .select_utxos(
+                account,
+                MicroTari::from(0),
+                bump_fee_per_gram,
+                0,
+                Some(&UTXOSelectionStrategy::BranchAndBound),
+            )
+            .await?;
+        let requires_change_output = selection.requires_change_output;
+        let inputs = selection.utxos;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(bump_fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce);
+        for uo in inputs.iter() {
+            builder.with_input(
+                uo.unblinded_output.as_transaction_input(
+                    &self.resources.factories.commitment,
+                    uo.unblinded_output.features.clone(),
+                ),
+                uo.unblinded_output.clone(),
+            );
+        }
+        // Without a change output any excess, which is less than the cost of a change output, is added to the fee
+        let mut change_key: Option<PrivateKey> = None;
+        if requires_change_output {
+            let key = self.get_next_spend_key(account).await?;
+            change_key = Some(key.clone());
+            builder.with_rewindable_change_secret(key, self.resources.rewind_data.clone());
+        }
+
+        let mut stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let mut change_output = Vec::<DbUnblindedOutput>::new();
+        if let Some(key) = change_key {
+            let mut output = DbUnblindedOutput::rewindable_from_unblinded_output(
+                UnblindedOutput::new(stp.get_amount_to_self()?, key, None),
+                &self.resources.factories,
+                &self.resources.rewind_data,
+            )?;
+            output.account_id = account.id;
+            change_output.push(output);
+        }
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let bump_tx = stp.take_transaction()?;
+        let kernel_excess = bump_tx
+            .body
+            .kernels()
+            .first()
+            .map(|k| k.excess.clone())
+            .ok_or_else(|| OutputManagerError::BuildError("Fee bump transaction has no kernel".to_string()))?;
+
+        let bump_tx_id = OsRng.next_u64();
+        self.resources
+            .db
+            .encumber_fee_bump_outputs(
+                bump_tx_id,
+                FeeBump {
+                    bumped_tx_id: tx_id,
+                    kernel_excess,
+                },
+                inputs,
+                change_output,
+            )
+            .await?;
+        self.set_transaction_account(account, bump_tx_id).await?;
+
+        let mut replacement_tx = transaction + bump_tx;
+        replacement_tx.body.sort();
+        let fee = replacement_tx.body.get_total_fee();
+        trace!(
+            target: LOG_TARGET,
+            "Fee bump transaction (TxId: {}) built for transaction (TxId: {}) with total fee {}",
+            bump_tx_id,
+            tx_id,
+            fee
+        );
+
+        Ok((fee, replacement_tx))
+    }
+
+    /// Select the inputs of a transaction that will be signed by a wallet holding the spending keys, such as an
+    /// offline copy of this wallet. The selected outputs are encumbered against `tx_id` straight away because the
+    /// negotiation happens outside of this wallet. The inputs are returned as outputs with rewindable range proofs so
+    /// that the signing wallet can recover their values and spending keys.
+    async fn prepare_unsigned_transaction(
+        &mut self,
+        account: &Account,
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+    ) -> Result<Vec<TransactionOutput>, OutputManagerError>
+    {
+        let selection = self.select_utxos(account, amount, fee_per_gram, 1, None).await?;
+        let inputs = selection
+            .utxos
+            .iter()
+            .map(|uo| {
+                uo.unblinded_output
+                    .as_rewindable_transaction_output(&self.resources.factories, &self.resources.rewind_data)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, selection.utxos, Vec::new())
+            .await?;
+        self.confirm_encumberance(tx_id).await?;
+        self.set_transaction_account(account, tx_id).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Prepared unsigned transaction (TxId: {}) for {} with {} inputs",
+            tx_id,
+            amount,
+            inputs.len()
+        );
+
+        Ok(inputs)
+    }
+
+    /// Build a sender transaction protocol for an unsigned transaction prepared by `prepare_unsigned_transaction`. Each
+    /// input is rewound to recover its value and spending key, which fails for any input that does not belong to this
+    /// wallet. The outputs are not tracked by this wallet, the wallet that prepared the transaction tracks them.
+    async fn create_sender_protocol_from_unsigned(
+        &mut self,
+        account: &Account,
+        amount: MicroTari,
+        unsigned: UnsignedTransaction,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        let factories = &self.resources.factories;
+        let rewind_data = &self.resources.rewind_data;
+        let mut builder = SenderTransactionProtocol::builder(1);
+        builder
+            .with_lock_height(unsigned.lock_height)
+            .with_fee_per_gram(unsigned.fee_per_gram)
+            .with_offset(PrivateKey::random(&mut OsRng))
+            .with_private_nonce(PrivateKey::random(&mut OsRng))
+            .with_amount(0, amount)
+            .with_message(message);
+        for input in unsigned.inputs.iter() {
+            let rewound = input
+                .full_rewind_range_proof(
+                    &factories.range_proof,
+                    &rewind_data.rewind_key,
+                    &rewind_data.rewind_blinding_key,
+                )
+                .ok()
+                .filter(|r| {
+                    factories
+                        .commitment
+                        .open_value(&r.blinding_factor, r.committed_value.into(), &input.commitment)
+                })
+                .ok_or_else(|| OutputManagerError::UnknownInput(input.commitment.to_hex()))?;
+            let uo = UnblindedOutput::new(
+                rewound.committed_value,
+                rewound.blinding_factor,
+                Some(input.features.clone()),
+            );
+            builder.with_input(
+                uo.as_transaction_input(&factories.commitment, input.features.clone()),
+                uo,
+            );
+        }
+        let change_key = self.get_next_spend_key(account).await?;
+        builder.with_rewindable_change_secret(change_key, self.resources.rewind_data.clone());
+
+        let stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        Ok(stp)
+    }
+
+    /// Complete a transaction prepared by `prepare_unsigned_transaction` once it has been signed elsewhere. The
+    /// transaction must spend the encumbered inputs, and its change output, found by rewinding the outputs, is added to
+    /// the pending transaction so it is received when the transaction is mined.
+    async fn complete_unsigned_transaction(
+        &mut self,
+        tx_id: TxId,
+        transaction: Transaction,
+    ) -> Result<(), OutputManagerError>
+    {
+        let pending = self.resources.db.fetch_pending_transaction_outputs(tx_id).await?;
+        let inputs = transaction.body.inputs();
+        if pending
+            .outputs_to_be_spent
+            .iter()
+            .any(|o| !inputs.iter().any(|i| i.commitment == o.commitment))
+        {
+            return Err(OutputManagerError::IncompleteTransaction(
+                "The signed transaction does not spend the encumbered inputs",
+            ));
+        }
+
+        let change_outputs = self
+            .rewind_outputs(transaction.body.outputs().clone())
+            .await?
+            .into_iter()
+            .map(|uo| {
+                DbUnblindedOutput::rewindable_from_unblinded_output(
+                    uo,
+                    &self.resources.factories,
+                    &self.resources.rewind_data,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        debug!(
+            target: LOG_TARGET,
+            "Completed unsigned transaction (TxId: {}) with {} change outputs",
+            tx_id,
+            change_outputs.len()
+        );
+        self.resources
+            .db
+            .add_outputs_to_pending_transaction(tx_id, Vec::new(), change_outputs)
+            .await?;
+
+        Ok(())
+    }
+
     /// Confirm that a transaction has finished being negotiated between parties so the short-term encumberance can be
     /// made official
     async fn confirm_encumberance(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
@@ -783,7 +1429,45 @@ where TBackend: OutputManagerBackend + 'static
     ) -> Result<(), OutputManagerError>
     {
         let pending_transaction = self.resources.db.fetch_pending_transaction_outputs(tx_id).await?;
+        self.check_pending_outputs_in_transaction(&pending_transaction, inputs, outputs)?;
 
+        self.resources
+            .db
+            .confirm_pending_transaction_outputs(pending_transaction.tx_id)
+            .await?;
+
+        // The fee bumps whose inputs and change made it into the mined transaction are confirmed along with it, the
+        // others were never mined and their outputs are released
+        for fee_bump in self.fetch_fee_bumps(tx_id).await? {
+            if self
+                .check_pending_outputs_in_transaction(&fee_bump, inputs, outputs)
+                .is_ok()
+            {
+                self.resources
+                    .db
+                    .confirm_pending_transaction_outputs(fee_bump.tx_id)
+                    .await?;
+            } else {
+                self.resources
+                    .db
+                    .cancel_pending_transaction_outputs(fee_bump.tx_id)
+                    .await?;
+            }
+        }
+
+        trace!(target: LOG_TARGET, "Confirm transaction (TxId: {})", tx_id);
+
+        Ok(())
+    }
+
+    /// Check that the outputs of a pending transaction can all be found in the provided transaction inputs and outputs
+    fn check_pending_outputs_in_transaction(
+        &self,
+        pending_transaction: &PendingTransactionOutputs,
+        inputs: &[TransactionInput],
+        outputs: &[TransactionOutput],
+    ) -> Result<(), OutputManagerError>
+    {
         // Check that outputs to be spent can all be found in the provided transaction inputs
         for output_to_spend in pending_transaction.outputs_to_be_spent.iter() {
             let input_to_check = output_to_spend
@@ -813,23 +1497,60 @@ where TBackend: OutputManagerBackend + 'static
             }
         }
 
-        self.resources
-            .db
-            .confirm_pending_transaction_outputs(pending_transaction.tx_id)
-            .await?;
-
-        trace!(target: LOG_TARGET, "Confirm transaction (TxId: {})", tx_id);
-
         Ok(())
     }
 
-    /// Cancel a pending transaction and place the encumbered outputs back into the unspent pool
+    /// Cancel a pending transaction and place the encumbered outputs back into the unspent pool. The outputs of any
+    /// fee bumps of the transaction are released as well.
     pub async fn cancel_transaction(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
         debug!(
             target: LOG_TARGET,
             "Cancelling pending transaction outputs for TxId: {}", tx_id
         );
-        Ok(self.resources.db.cancel_pending_transaction_outputs(tx_id).await?)
+        self.resources.db.cancel_pending_transaction_outputs(tx_id).await?;
+        self.release_fee_bumps(tx_id, &[]).await
+    }
+
+    /// Release the outputs of the fee bumps of transaction `tx_id` whose kernels are not among the provided mined
+    /// kernel excesses. This is used when the original transaction, or an earlier replacement, was mined instead of
+    /// the latest replacement.
+    async fn release_fee_bumps(
+        &mut self,
+        tx_id: TxId,
+        mined_kernel_excesses: &[Commitment],
+    ) -> Result<(), OutputManagerError>
+    {
+        for fee_bump in self.fetch_fee_bumps(tx_id).await? {
+            let is_mined = fee_bump
+                .fee_bump
+                .as_ref()
+                .map(|f| mined_kernel_excesses.contains(&f.kernel_excess))
+                .unwrap_or(false);
+            if !is_mined {
+                debug!(
+                    target: LOG_TARGET,
+                    "Releasing outputs of fee bump (TxId: {}) of transaction (TxId: {})", fee_bump.tx_id, tx_id
+                );
+                self.resources
+                    .db
+                    .cancel_pending_transaction_outputs(fee_bump.tx_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch the pending fee bumps of transaction `tx_id`
+    async fn fetch_fee_bumps(&self, tx_id: TxId) -> Result<Vec<PendingTransactionOutputs>, OutputManagerError> {
+        Ok(self
+            .resources
+            .db
+            .fetch_all_pending_transaction_outputs()
+            .await?
+            .into_iter()
+            .map(|(_, p)| p)
+            .filter(|p| p.fee_bump.as_ref().map(|f| f.bumped_tx_id) == Some(tx_id))
+            .collect())
     }
 
     /// Go through the pending transaction and if any have existed longer than the specified duration, cancel them
@@ -838,123 +1559,79 @@ where TBackend: OutputManagerBackend + 'static
     }
 
     /// Select which unspent transaction outputs to use to send a transaction of the specified amount. Use the specified
-    /// selection strategy to choose the outputs. It also determines if a change output is required.
+    /// selection strategy to choose the outputs, or the configured UTXO selector if no strategy is specified. It also
+    /// determines if a change output is required.
     async fn select_utxos(
         &mut self,
+        account: &Account,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         output_count: usize,
-        strategy: Option<UTXOSelectionStrategy>,
-    ) -> Result<(Vec<DbUnblindedOutput>, bool), OutputManagerError>
+        utxo_selector: Option<&dyn UtxoSelector>,
+    ) -> Result<UtxoSelection, OutputManagerError>
     {
         debug!(
             target: LOG_TARGET,
-            "select_utxos amount: {}, fee_per_gram: {}, output_count: {}, strategy: {:?}",
+            "select_utxos amount: {}, fee_per_gram: {}, output_count: {}, selector: {:?}",
             amount,
             fee_per_gram,
             output_count,
-            strategy
+            utxo_selector
         );
-        let mut utxos = Vec::new();
-        let mut total = MicroTari::from(0);
-        let mut fee_without_change = MicroTari::from(0);
-        let mut fee_with_change = MicroTari::from(0);
-
-        let uo = self.resources.db.fetch_sorted_unspent_outputs().await?;
+        // Only the outputs of the given account are spent, and frozen outputs are never spent until they are unfrozen
+        let uo = self
+            .resources
+            .db
+            .fetch_sorted_unspent_outputs()
+            .await?
+            .into_iter()
+            .filter(|u| u.account_id == account.id && !u.frozen)
+            .collect::<Vec<DbUnblindedOutput>>();
 
         // Attempt to get the chain tip height
         let chain_metadata = self.base_node_service.get_chain_metadata().await?;
-        let (connected, tip_height) = match chain_metadata {
-            Some(metadata) => (true, metadata.height_of_longest_chain()),
-            None => (false, 0),
-        };
-
-        // If no strategy was specified and no metadata is available, then make sure to use MaturitythenSmallest
-        let strategy = match (strategy, connected) {
-            (Some(s), _) => Some(s),
-            (None, false) => Some(UTXOSelectionStrategy::MaturityThenSmallest),
-            (None, true) => None, // use the selection heuristic next
-        };
+        let tip_height = chain_metadata.map(|metadata| metadata.height_of_longest_chain());
 
         // If we know the chain height then filter out unspendable UTXOs
         let num_utxos = uo.len();
-        let uo = if connected {
-            let mature_utxos = uo
-                .into_iter()
-                .filter(|u| u.unblinded_output.features.maturity <= tip_height)
-                .collect::<Vec<DbUnblindedOutput>>();
-
-            trace!(
-                target: LOG_TARGET,
-                "Some UTXOs have not matured yet at height {}, filtered {} UTXOs",
-                tip_height,
-                num_utxos - mature_utxos.len()
-            );
+        let uo = match tip_height {
+            Some(tip_height) => {
+                let mature_utxos = uo
+                    .into_iter()
+                    .filter(|u| u.unblinded_output.features.maturity <= tip_height)
+                    .collect::<Vec<DbUnblindedOutput>>();
 
-            mature_utxos
-        } else {
-            uo
-        };
+                trace!(
+                    target: LOG_TARGET,
+                    "Some UTXOs have not matured yet at height {}, filtered {} UTXOs",
+                    tip_height,
+                    num_utxos - mature_utxos.len()
+                );
 
-        // Heuristic for selection strategy: Default to MaturityThenSmallest, but if the amount is greater than
-        // the largest UTXO, use Largest UTXOs first.
-        let strategy = match (strategy, uo.is_empty()) {
-            (Some(s), _) => s,
-            (None, true) => UTXOSelectionStrategy::Smallest,
-            (None, false) => {
-                let largest_utxo = &uo[uo.len() - 1];
-                if amount > largest_utxo.unblinded_output.value {
-                    UTXOSelectionStrategy::Largest
-                } else {
-                    UTXOSelectionStrategy::MaturityThenSmallest
-                }
+                mature_utxos
             },
-        };
-        debug!(target: LOG_TARGET, "select_utxos selection strategy: {}", strategy);
-
-        let uo = match strategy {
-            UTXOSelectionStrategy::Smallest => uo,
-            UTXOSelectionStrategy::MaturityThenSmallest => {
-                let mut uo = uo;
-                uo.sort_by(|a, b| {
-                    match a
-                        .unblinded_output
-                        .features
-                        .maturity
-                        .cmp(&b.unblinded_output.features.maturity)
-                    {
-                        Ordering::Equal => a.unblinded_output.value.cmp(&b.unblinded_output.value),
-                        Ordering::Less => Ordering::Less,
-                        Ordering::Greater => Ordering::Greater,
-                    }
-                });
-                uo
-            },
-            UTXOSelectionStrategy::Largest => uo.into_iter().rev().collect(),
+            None => uo,
         };
         trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo.len());
 
-        let mut require_change_output = false;
-        for o in uo.iter() {
-            utxos.push(o.clone());
-            total += o.unblinded_output.value;
-            // The assumption here is that the only output will be the payment output and change if required
-            fee_without_change = Fee::calculate(fee_per_gram, 1, utxos.len(), output_count);
-            if total == amount + fee_without_change {
-                break;
-            }
-            fee_with_change = Fee::calculate(fee_per_gram, 1, utxos.len(), output_count + 1);
-            if total >= amount + fee_with_change {
-                require_change_output = true;
-                break;
-            }
-        }
-
-        if (total != amount + fee_without_change) && (total < amount + fee_with_change) {
-            return Err(OutputManagerError::NotEnoughFunds);
-        }
+        let params = UtxoSelectionParams {
+            amount,
+            fee_per_gram,
+            output_count,
+            tip_height,
+        };
+        let selection = match utxo_selector {
+            Some(utxo_selector) => utxo_selector.select(uo, &params)?,
+            None => self.resources.config.utxo_selector.select(uo, &params)?,
+        };
+        debug!(
+            target: LOG_TARGET,
+            "{} UTXOs selected, change output required: {}",
+            selection.utxos.len(),
+            selection.requires_change_output
+        );
 
-        Ok((utxos, require_change_output))
+        Ok(selection)
     }
 
     /// Set the base node public key to the list that will be used to check the status of UTXO's on the base chain. If
@@ -1000,12 +1677,29 @@ where TBackend: OutputManagerBackend + 'static
         Ok(self.resources.db.get_invalid_outputs().await?)
     }
 
+    async fn update_output_metadata(
+        &self,
+        commitment: Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerError>
+    {
+        debug!(
+            target: LOG_TARGET,
+            "Updating metadata of output {}: {:?}",
+            commitment.to_hex(),
+            update
+        );
+        Ok(self.resources.db.update_output_metadata(commitment, update).await?)
+    }
+
     async fn create_coin_split(
         &mut self,
+        account: &Account,
         amount_per_split: MicroTari,
         split_count: usize,
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
+        utxo_selector: &dyn UtxoSelector,
     ) -> Result<(u64, Transaction, MicroTari, MicroTari), OutputManagerError>
     {
         trace!(
@@ -1014,19 +1708,19 @@ where TBackend: OutputManagerBackend + 'static
         );
         let mut output_count = split_count;
         let total_split_amount = amount_per_split * split_count as u64;
-        let (inputs, require_change_output) = self
+        let selection = self
             .select_utxos(
+                account,
                 total_split_amount,
                 fee_per_gram,
                 output_count,
-                Some(UTXOSelectionStrategy::Largest),
+                Some(utxo_selector),
             )
             .await?;
-        let utxo_total_value = inputs
-            .iter()
-            .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
+        let utxo_total_value = selection.total_value();
+        let inputs = selection.utxos;
         let input_count = inputs.len();
-        if require_change_output {
+        if selection.requires_change_output {
             output_count = split_count + 1
         };
         let fee = Fee::calculate(fee_per_gram, 1, input_count, output_count);
@@ -1064,11 +1758,12 @@ where TBackend: OutputManagerBackend + 'static
                 change_output
             };
 
-            let spend_key = self.get_next_spend_key().await?;
-            let utxo = DbUnblindedOutput::from_unblinded_output(
+            let spend_key = self.get_next_spend_key(account).await?;
+            let mut utxo = DbUnblindedOutput::from_unblinded_output(
                 UnblindedOutput::new(output_amount, spend_key, None),
                 &self.resources.factories,
             )?;
+            utxo.account_id = account.id;
             outputs.push(utxo.clone());
             builder.with_output(utxo.unblinded_output);
         }
@@ -1086,6 +1781,7 @@ where TBackend: OutputManagerBackend + 'static
             tx_id
         );
         self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.set_transaction_account(account, tx_id).await?;
         self.confirm_encumberance(tx_id).await?;
         trace!(target: LOG_TARGET, "Finalize coin split transaction ({}).", tx_id);
         stp.finalize(KernelFeatures::empty(), &factories)?;
@@ -1109,6 +1805,14 @@ where TBackend: OutputManagerBackend + 'static
         }
     }
 
+    /// Return the private rewind keys, these allow a watch-only wallet to track the outputs of this wallet
+    fn get_watch_only_keys(&self) -> WatchOnlyKeys {
+        WatchOnlyKeys {
+            rewind_key: self.resources.rewind_data.rewind_key.clone(),
+            rewind_blinding_key: self.resources.rewind_data.rewind_blinding_key.clone(),
+        }
+    }
+
     /// Attempt to rewind all of the given transaction outputs into unblinded outputs
     async fn rewind_outputs(
         &mut self,
@@ -1134,11 +1838,27 @@ where TBackend: OutputManagerBackend + 'static
         Ok(rewound_outputs)
     }
 
-    async fn get_next_spend_key(&self) -> Result<PrivateKey, OutputManagerError> {
+    async fn get_next_spend_key(&self, account: &Account) -> Result<PrivateKey, OutputManagerError> {
         let mut km = self.key_manager.lock().await;
-        let key = km.next_key()?;
-        self.resources.db.increment_key_index().await?;
-        Ok(key.k)
+        if account.is_default() {
+            let key = km.next_key()?;
+            self.resources.db.increment_key_index().await?;
+            Ok(key.k)
+        } else {
+            // The key index of the account is read from the database because it advances with every key that is
+            // derived, including those derived earlier in the same request
+            let account = self
+                .resources
+                .db
+                .get_accounts()
+                .await?
+                .into_iter()
+                .find(|a| a.id == account.id)
+                .ok_or_else(|| OutputManagerError::AccountNotFound(account.name.clone()))?;
+            let key = account_key_manager(km.master_key(), &account).next_branch_key()?;
+            self.resources.db.increment_account_key_index(account.id).await?;
+            Ok(key.k)
+        }
     }
 
     async fn get_next_coinbase_key(&self) -> Result<PrivateKey, OutputManagerError> {
@@ -1148,27 +1868,15 @@ where TBackend: OutputManagerBackend + 'static
     }
 }
 
-/// Different UTXO selection strategies for choosing which UTXO's are used to fulfill a transaction
-/// TODO Investigate and implement more optimal strategies
-#[derive(Debug)]
-pub enum UTXOSelectionStrategy {
-    // Start from the smallest UTXOs and work your way up until the amount is covered. Main benefit
-    // is removing small UTXOs from the blockchain, con is that it costs more in fees
-    Smallest,
-    // Start from oldest maturity to reduce the likelihood of grabbing locked up UTXOs
-    MaturityThenSmallest,
-    // A strategy that selects the largest UTXOs first. Preferred when the amount is large
-    Largest,
-}
-
-impl Display for UTXOSelectionStrategy {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            UTXOSelectionStrategy::Smallest => write!(f, "Smallest"),
-            UTXOSelectionStrategy::MaturityThenSmallest => write!(f, "MaturityThenSmallest"),
-            UTXOSelectionStrategy::Largest => write!(f, "Largest"),
-        }
-    }
+/// Construct the key manager of a named account, which derives its keys from its own branch of the master key. The
+/// branch seed ends with a separator so that the seed and key index of one account never concatenate to those of
+/// another, e.g. key 11 of account 1 and key 1 of account 11.
+fn account_key_manager(master_key: &PrivateKey, account: &Account) -> KeyManager<PrivateKey, KeyDigest> {
+    KeyManager::<PrivateKey, KeyDigest>::from(
+        master_key.clone(),
+        format!("{}_{}/", KEY_MANAGER_ACCOUNT_BRANCH_KEY, account.id),
+        account.primary_key_index,
+    )
 }
 
 /// This struct holds the detailed balance of the Output Manager Service.
@@ -1220,3 +1928,26 @@ where TBackend: OutputManagerBackend + 'static
     pub connectivity_manager: ConnectivityRequester,
     pub shutdown_signal: ShutdownSignal,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::output_manager_service::AccountId;
+
+    #[test]
+    fn account_key_branches_do_not_overlap() {
+        let master_key = PrivateKey::random(&mut OsRng);
+        let account = |id: AccountId| Account {
+            id,
+            name: id.to_string(),
+            primary_key_index: 0,
+        };
+        let key_1_11 = account_key_manager(&master_key, &account(1))
+            .derive_branch_key(11)
+            .unwrap();
+        let key_11_1 = account_key_manager(&master_key, &account(11))
+            .derive_branch_key(1)
+            .unwrap();
+        assert_ne!(key_1_11.k, key_11_1.k);
+    }
+}
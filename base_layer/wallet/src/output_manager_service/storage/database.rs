@@ -24,6 +24,7 @@ use crate::output_manager_service::{
     error::OutputManagerStorageError,
     service::Balance,
     storage::models::DbUnblindedOutput,
+    AccountId,
     TxId,
 };
 use aes_gcm::Aes256Gcm;
@@ -68,6 +69,24 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     /// This method confirms that a transaction negotiation is complete and outputs can be fully encumbered. This
     /// reserves these outputs until the transaction is confirmed or cancelled
     fn confirm_encumbered_outputs(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError>;
+    /// This method encumbers the specified outputs against an existing `PendingTransactionOutputs` record. This is used
+    /// when additional inputs and outputs are added to a transaction that has already been negotiated.
+    fn add_outputs_to_pending_transaction(
+        &self,
+        tx_id: TxId,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>;
+    /// This method encumbers the inputs and change of a fee bump into a new `PendingTransactionOutputs` record that is
+    /// linked to the pending transaction whose fee was bumped. The encumberance is not short term because the bumped
+    /// transaction has already been negotiated.
+    fn encumber_fee_bump_outputs(
+        &self,
+        tx_id: TxId,
+        fee_bump: FeeBump,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>;
     /// Clear all pending transaction encumberances marked as short term. These are the result of an unfinished
     /// transaction negotiation
     fn clear_short_term_encumberances(&self) -> Result<(), OutputManagerStorageError>;
@@ -98,6 +117,15 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
         &self,
         commitment: &Commitment,
     ) -> Result<DbUnblindedOutput, OutputManagerStorageError>;
+    /// Change the user assigned metadata of the output with the given commitment
+    fn update_output_metadata(
+        &self,
+        commitment: &Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerStorageError>;
+    /// This method will increment the currently stored key index of the specified account. Increment this after each
+    /// key is generated on the account's key branch
+    fn increment_account_key_index(&self, account_id: AccountId) -> Result<(), OutputManagerStorageError>;
 }
 
 /// Holds the outputs that have been selected for a given pending transaction waiting for confirmation
@@ -108,6 +136,23 @@ pub struct PendingTransactionOutputs {
     pub outputs_to_be_received: Vec<DbUnblindedOutput>,
     pub timestamp: NaiveDateTime,
     pub coinbase_block_height: Option<u64>,
+    pub fee_bump: Option<FeeBump>,
+}
+
+/// Links the outputs encumbered by a fee bump to the transaction whose fee was bumped. The kernel excess of the bump
+/// identifies whether the replacement, rather than the original transaction, was mined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBump {
+    pub bumped_tx_id: TxId,
+    pub kernel_excess: Commitment,
+}
+
+/// A change to the user assigned metadata of an output
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputMetadataUpdate {
+    Label(Option<String>),
+    Note(Option<String>),
+    Frozen(bool),
 }
 
 /// Holds the state of the KeyManager being used by the Output Manager Service
@@ -118,6 +163,43 @@ pub struct KeyManagerState {
     pub primary_key_index: u64,
 }
 
+/// The id of the account that holds all of the wallet's outputs until other accounts are created. Its keys are derived
+/// using the `KeyManagerState` and it is never stored as an `Account`.
+pub const DEFAULT_ACCOUNT_ID: AccountId = 0;
+pub const DEFAULT_ACCOUNT_NAME: &str = "default";
+
+/// A named account of the wallet. Each account derives its keys from its own branch of the master key and has its own
+/// outputs, balance and transaction history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Account {
+    pub id: AccountId,
+    pub name: String,
+    /// The index of the last key that was derived on the account's key branch
+    pub primary_key_index: u64,
+}
+
+impl Account {
+    pub fn default_account() -> Self {
+        Self {
+            id: DEFAULT_ACCOUNT_ID,
+            name: DEFAULT_ACCOUNT_NAME.to_string(),
+            primary_key_index: 0,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_ACCOUNT_ID
+    }
+}
+
+/// Holds the keys of a watch-only wallet. A watch-only wallet has no master key, it can only rewind and track the
+/// outputs created with these keys and is not able to sign any transactions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchOnlyKeys {
+    pub rewind_key: PrivateKey,
+    pub rewind_blinding_key: PrivateKey,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DbKey {
     SpentOutput(BlindingFactor),
@@ -129,6 +211,10 @@ pub enum DbKey {
     AllPendingTransactionOutputs,
     KeyManagerState,
     InvalidOutputs,
+    WatchOnlyKeys,
+    Accounts,
+    ActiveAccount,
+    TransactionAccounts,
 }
 
 #[derive(Debug)]
@@ -141,6 +227,10 @@ pub enum DbValue {
     InvalidOutputs(Vec<DbUnblindedOutput>),
     AllPendingTransactionOutputs(HashMap<TxId, PendingTransactionOutputs>),
     KeyManagerState(KeyManagerState),
+    WatchOnlyKeys(WatchOnlyKeys),
+    Accounts(Vec<Account>),
+    ActiveAccount(AccountId),
+    TransactionAccounts(HashMap<TxId, AccountId>),
 }
 
 pub enum DbKeyValuePair {
@@ -148,6 +238,10 @@ pub enum DbKeyValuePair {
     UnspentOutput(BlindingFactor, Box<DbUnblindedOutput>),
     PendingTransactionOutputs(TxId, Box<PendingTransactionOutputs>),
     KeyManagerState(KeyManagerState),
+    WatchOnlyKeys(WatchOnlyKeys),
+    Account(Account),
+    ActiveAccount(AccountId),
+    TransactionAccount(TxId, AccountId),
 }
 
 pub enum WriteOperation {
@@ -208,6 +302,19 @@ where T: OutputManagerBackend + 'static
         Ok(())
     }
 
+    pub async fn get_watch_only_keys(&self) -> Result<Option<WatchOnlyKeys>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::WatchOnlyKeys) {
+            Ok(None) => Ok(None),
+            Ok(Some(DbValue::WatchOnlyKeys(k))) => Ok(Some(k)),
+            Ok(Some(other)) => unexpected_result(DbKey::WatchOnlyKeys, other),
+            Err(e) => log_error(DbKey::WatchOnlyKeys, e),
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
     pub async fn increment_key_index(&self) -> Result<(), OutputManagerStorageError> {
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || db_clone.increment_key_index())
@@ -230,7 +337,118 @@ where T: OutputManagerBackend + 'static
         Ok(())
     }
 
+    pub async fn get_accounts(&self) -> Result<Vec<Account>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::Accounts) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::Accounts(a))) => Ok(a),
+            Ok(Some(other)) => unexpected_result(DbKey::Accounts, other),
+            Err(e) => log_error(DbKey::Accounts, e),
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
+    pub async fn add_account(&self, account: Account) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.write(WriteOperation::Insert(DbKeyValuePair::Account(account))))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    pub async fn increment_account_key_index(&self, account_id: AccountId) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.increment_account_key_index(account_id))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Returns the id of the account that is used to send and receive funds, this is the default account unless
+    /// another account was selected
+    pub async fn get_active_account_id(&self) -> Result<AccountId, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ActiveAccount) {
+            Ok(None) => Ok(DEFAULT_ACCOUNT_ID),
+            Ok(Some(DbValue::ActiveAccount(id))) => Ok(id),
+            Ok(Some(other)) => unexpected_result(DbKey::ActiveAccount, other),
+            Err(e) => log_error(DbKey::ActiveAccount, e),
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
+    pub async fn set_active_account_id(&self, account_id: AccountId) -> Result<(), OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::ActiveAccount(account_id)))
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    /// Record the account that a transaction was created for
+    pub async fn set_transaction_account(
+        &self,
+        tx_id: TxId,
+        account_id: AccountId,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::TransactionAccount(
+                tx_id, account_id,
+            )))
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))??;
+
+        Ok(())
+    }
+
+    /// Returns the recorded account of each transaction. Transactions without a recorded account belong to the
+    /// default account.
+    pub async fn get_transaction_accounts(&self) -> Result<HashMap<TxId, AccountId>, OutputManagerStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::TransactionAccounts) {
+            Ok(None) => Ok(HashMap::new()),
+            Ok(Some(DbValue::TransactionAccounts(t))) => Ok(t),
+            Ok(Some(other)) => unexpected_result(DbKey::TransactionAccounts, other),
+            Err(e) => log_error(DbKey::TransactionAccounts, e),
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
+    /// Returns the balance of all the outputs of the wallet, regardless of the account they belong to
     pub async fn get_balance(&self, current_chain_tip: Option<u64>) -> Result<Balance, OutputManagerStorageError> {
+        self.calculate_balance(current_chain_tip, None).await
+    }
+
+    /// Returns the balance of the outputs that belong to the specified account
+    pub async fn get_account_balance(
+        &self,
+        account_id: AccountId,
+        current_chain_tip: Option<u64>,
+    ) -> Result<Balance, OutputManagerStorageError>
+    {
+        self.calculate_balance(current_chain_tip, Some(account_id)).await
+    }
+
+    async fn calculate_balance(
+        &self,
+        current_chain_tip: Option<u64>,
+        account_id: Option<AccountId>,
+    ) -> Result<Balance, OutputManagerStorageError>
+    {
+        let in_account = move |o: &&DbUnblindedOutput| account_id.map_or(true, |id| o.account_id == id);
         let db_clone = self.db.clone();
         let db_clone2 = self.db.clone();
         let db_clone3 = self.db.clone();
@@ -257,6 +475,7 @@ where T: OutputManagerBackend + 'static
             if let DbValue::AllPendingTransactionOutputs(pto) = pending_txs {
                 let available_balance = uo
                     .iter()
+                    .filter(in_account)
                     .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                 let time_locked_balance = if let Some(tip) = current_chain_tip {
                     let time_locked_outputs = tokio::task::spawn_blocking(move || {
@@ -272,6 +491,7 @@ where T: OutputManagerBackend + 'static
                         Some(
                             time_locked_uo
                                 .iter()
+                                .filter(in_account)
                                 .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value),
                         )
                     } else {
@@ -287,10 +507,12 @@ where T: OutputManagerBackend + 'static
                     pending_incoming += v
                         .outputs_to_be_received
                         .iter()
+                        .filter(in_account)
                         .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                     pending_outgoing += v
                         .outputs_to_be_spent
                         .iter()
+                        .filter(in_account)
                         .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                 }
 
@@ -349,8 +571,9 @@ where T: OutputManagerBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
-    /// This method accepts and stores a pending inbound transaction and creates the `output_to_be_received` from the
-    /// amount and provided spending key.
+    /// This method accepts and stores a pending inbound transaction and creates the `output_to_be_received` of the
+    /// specified account from the amount and provided spending key.
+    #[allow(clippy::too_many_arguments)]
     pub async fn accept_incoming_pending_transaction(
         &self,
         tx_id: TxId,
@@ -359,13 +582,19 @@ where T: OutputManagerBackend + 'static
         output_features: OutputFeatures,
         factory: &CryptoFactories,
         coinbase_block_height: Option<u64>,
+        account_id: AccountId,
     ) -> Result<(), OutputManagerStorageError>
     {
         let db_clone = self.db.clone();
-        let output = DbUnblindedOutput::from_unblinded_output(
+        let mut output = DbUnblindedOutput::from_unblinded_output(
             UnblindedOutput::new(amount, spending_key.clone(), Some(output_features)),
             factory,
         )?;
+        // Coinbase outputs are the wallet's own funds, all other incoming outputs are tagged with their source
+        if coinbase_block_height.is_none() {
+            output.received_in_tx_id = Some(tx_id);
+        }
+        output.account_id = account_id;
         tokio::task::spawn_blocking(move || {
             db_clone.write(WriteOperation::Insert(DbKeyValuePair::PendingTransactionOutputs(
                 tx_id,
@@ -375,6 +604,7 @@ where T: OutputManagerBackend + 'static
                     outputs_to_be_received: vec![output],
                     timestamp: Utc::now().naive_utc(),
                     coinbase_block_height,
+                    fee_bump: None,
                 }),
             )))
         })
@@ -401,6 +631,45 @@ where T: OutputManagerBackend + 'static
         .and_then(|inner_result| inner_result)
     }
 
+    /// This method is called when additional inputs and outputs are added to a transaction that has already been
+    /// negotiated, such as the change of a completed unsigned transaction. The outputs are encumbered against the
+    /// existing pending transaction.
+    pub async fn add_outputs_to_pending_transaction(
+        &self,
+        tx_id: TxId,
+        outputs_to_send: Vec<DbUnblindedOutput>,
+        outputs_to_receive: Vec<DbUnblindedOutput>,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.add_outputs_to_pending_transaction(tx_id, &outputs_to_send, &outputs_to_receive)
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
+    /// This method is called when the fee of a pending transaction is bumped. The inputs and change of the fee bump are
+    /// encumbered against their own pending transaction so that they can be confirmed or released depending on
+    /// whether the replacement or the original transaction is mined.
+    pub async fn encumber_fee_bump_outputs(
+        &self,
+        tx_id: TxId,
+        fee_bump: FeeBump,
+        outputs_to_send: Vec<DbUnblindedOutput>,
+        outputs_to_receive: Vec<DbUnblindedOutput>,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.encumber_fee_bump_outputs(tx_id, fee_bump, &outputs_to_send, &outputs_to_receive)
+        })
+        .await
+        .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
     /// This method is called when a transaction is finished being negotiated. This will fully encumber the outputs
     /// against a pending transaction.
     pub async fn confirm_encumbered_outputs(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError> {
@@ -599,6 +868,19 @@ where T: OutputManagerBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    pub async fn update_output_metadata(
+        &self,
+        commitment: Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.update_output_metadata(&commitment, update))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+            .and_then(|inner_result| inner_result)
+    }
+
     pub async fn cancel_pending_transaction_at_block_height(
         &self,
         block_height: u64,
@@ -648,6 +930,10 @@ impl Display for DbKey {
             DbKey::KeyManagerState => f.write_str(&"Key Manager State".to_string()),
             DbKey::InvalidOutputs => f.write_str(&"Invalid Outputs Key"),
             DbKey::TimeLockedUnspentOutputs(_t) => f.write_str(&"Timelocked Outputs"),
+            DbKey::WatchOnlyKeys => f.write_str(&"Watch Only Keys"),
+            DbKey::Accounts => f.write_str(&"Accounts"),
+            DbKey::ActiveAccount => f.write_str(&"Active Account"),
+            DbKey::TransactionAccounts => f.write_str(&"Transaction Accounts"),
         }
     }
 }
@@ -663,6 +949,10 @@ impl Display for DbValue {
             DbValue::AllPendingTransactionOutputs(_) => f.write_str("All Pending Transaction Outputs"),
             DbValue::KeyManagerState(_) => f.write_str("Key Manager State"),
             DbValue::InvalidOutputs(_) => f.write_str("Invalid Outputs"),
+            DbValue::WatchOnlyKeys(_) => f.write_str("Watch Only Keys"),
+            DbValue::Accounts(_) => f.write_str("Accounts"),
+            DbValue::ActiveAccount(_) => f.write_str("Active Account"),
+            DbValue::TransactionAccounts(_) => f.write_str("Transaction Accounts"),
         }
     }
 }
@@ -24,16 +24,21 @@ use crate::output_manager_service::{
     error::OutputManagerStorageError,
     storage::{
         database::{
+            Account,
             DbKey,
             DbKeyValuePair,
             DbValue,
+            FeeBump,
             KeyManagerState,
             OutputManagerBackend,
+            OutputMetadataUpdate,
             PendingTransactionOutputs,
+            WatchOnlyKeys,
             WriteOperation,
         },
         models::DbUnblindedOutput,
     },
+    AccountId,
     TxId,
 };
 use aes_gcm::Aes256Gcm;
@@ -55,6 +60,10 @@ pub struct InnerDatabase {
     pending_transactions: HashMap<TxId, PendingTransactionOutputs>,
     short_term_pending_transactions: HashMap<TxId, PendingTransactionOutputs>,
     key_manager_state: Option<KeyManagerState>,
+    watch_only_keys: Option<WatchOnlyKeys>,
+    accounts: Vec<Account>,
+    active_account: Option<AccountId>,
+    transaction_accounts: HashMap<TxId, AccountId>,
 }
 
 impl InnerDatabase {
@@ -66,6 +75,10 @@ impl InnerDatabase {
             pending_transactions: HashMap::new(),
             short_term_pending_transactions: Default::default(),
             key_manager_state: None,
+            watch_only_keys: None,
+            accounts: Vec::new(),
+            active_account: None,
+            transaction_accounts: HashMap::new(),
         }
     }
 }
@@ -139,6 +152,13 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
                 .key_manager_state
                 .as_ref()
                 .map(|km| DbValue::KeyManagerState(km.clone())),
+            DbKey::WatchOnlyKeys => db
+                .watch_only_keys
+                .as_ref()
+                .map(|keys| DbValue::WatchOnlyKeys(keys.clone())),
+            DbKey::Accounts => Some(DbValue::Accounts(db.accounts.clone())),
+            DbKey::ActiveAccount => db.active_account.map(DbValue::ActiveAccount),
+            DbKey::TransactionAccounts => Some(DbValue::TransactionAccounts(db.transaction_accounts.clone())),
             DbKey::InvalidOutputs => Some(DbValue::InvalidOutputs(
                 db.invalid_outputs
                     .iter()
@@ -184,6 +204,17 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
                     db.short_term_pending_transactions.insert(t, *p);
                 },
                 DbKeyValuePair::KeyManagerState(km) => db.key_manager_state = Some(km),
+                DbKeyValuePair::WatchOnlyKeys(keys) => db.watch_only_keys = Some(keys),
+                DbKeyValuePair::Account(a) => {
+                    if db.accounts.iter().any(|v| v.id == a.id || v.name == a.name) {
+                        return Err(OutputManagerStorageError::DuplicateAccount);
+                    }
+                    db.accounts.push(a);
+                },
+                DbKeyValuePair::ActiveAccount(id) => db.active_account = Some(id),
+                DbKeyValuePair::TransactionAccount(tx_id, id) => {
+                    db.transaction_accounts.insert(tx_id, id);
+                },
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::SpentOutput(k) => match db
@@ -225,6 +256,10 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
                 DbKey::KeyManagerState => return Err(OutputManagerStorageError::OperationNotSupported),
                 DbKey::InvalidOutputs => return Err(OutputManagerStorageError::OperationNotSupported),
                 DbKey::TimeLockedUnspentOutputs(_) => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::WatchOnlyKeys => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::Accounts => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::ActiveAccount => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::TransactionAccounts => return Err(OutputManagerStorageError::OperationNotSupported),
             },
         }
         Ok(None)
@@ -287,6 +322,7 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
             outputs_to_be_received: Vec::new(),
             timestamp: Utc::now().naive_utc(),
             coinbase_block_height: None,
+            fee_bump: None,
         };
 
         for co in outputs_to_receive {
@@ -298,6 +334,83 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
         Ok(())
     }
 
+    fn add_outputs_to_pending_transaction(
+        &self,
+        tx_id: TxId,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let mut db = acquire_write_lock!(self.db);
+        if !db.pending_transactions.contains_key(&tx_id) {
+            return Err(OutputManagerStorageError::ValueNotFound(
+                DbKey::PendingTransactionOutputs(tx_id),
+            ));
+        }
+
+        let mut outputs_to_be_spent = Vec::new();
+        for i in outputs_to_send {
+            if let Some(pos) = db
+                .unspent_outputs
+                .iter()
+                .position(|v| v.output.unblinded_output.spending_key == i.unblinded_output.spending_key)
+            {
+                outputs_to_be_spent.push(DbUnblindedOutput::from(db.unspent_outputs.remove(pos)));
+            } else {
+                return Err(OutputManagerStorageError::ValuesNotFound);
+            }
+        }
+
+        if let Some(pending_transaction) = db.pending_transactions.get_mut(&tx_id) {
+            pending_transaction.outputs_to_be_spent.append(&mut outputs_to_be_spent);
+            pending_transaction
+                .outputs_to_be_received
+                .extend(outputs_to_receive.iter().cloned());
+        }
+
+        Ok(())
+    }
+
+    fn encumber_fee_bump_outputs(
+        &self,
+        tx_id: TxId,
+        fee_bump: FeeBump,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let mut db = acquire_write_lock!(self.db);
+        if !db.pending_transactions.contains_key(&fee_bump.bumped_tx_id) {
+            return Err(OutputManagerStorageError::ValueNotFound(
+                DbKey::PendingTransactionOutputs(fee_bump.bumped_tx_id),
+            ));
+        }
+
+        let mut outputs_to_be_spent = Vec::new();
+        for i in outputs_to_send {
+            if let Some(pos) = db
+                .unspent_outputs
+                .iter()
+                .position(|v| v.output.unblinded_output.spending_key == i.unblinded_output.spending_key)
+            {
+                outputs_to_be_spent.push(DbUnblindedOutput::from(db.unspent_outputs.remove(pos)));
+            } else {
+                return Err(OutputManagerStorageError::ValuesNotFound);
+            }
+        }
+
+        db.pending_transactions.insert(tx_id, PendingTransactionOutputs {
+            tx_id,
+            outputs_to_be_spent,
+            outputs_to_be_received: outputs_to_receive.to_vec(),
+            timestamp: Utc::now().naive_utc(),
+            coinbase_block_height: None,
+            fee_bump: Some(fee_bump),
+        });
+
+        Ok(())
+    }
+
     fn confirm_encumbered_outputs(&self, tx_id: u64) -> Result<(), OutputManagerStorageError> {
         let mut db = acquire_write_lock!(self.db);
 
@@ -379,6 +492,19 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
         Ok(())
     }
 
+    fn increment_account_key_index(&self, account_id: AccountId) -> Result<(), OutputManagerStorageError> {
+        let mut db = acquire_write_lock!(self.db);
+
+        let account = db
+            .accounts
+            .iter_mut()
+            .find(|a| a.id == account_id)
+            .ok_or(OutputManagerStorageError::ValueNotFound(DbKey::Accounts))?;
+        account.primary_key_index += 1;
+
+        Ok(())
+    }
+
     fn invalidate_unspent_output(&self, output: &DbUnblindedOutput) -> Result<Option<TxId>, OutputManagerStorageError> {
         let mut db = acquire_write_lock!(self.db);
         match db
@@ -445,6 +571,46 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
         Ok(())
     }
 
+    fn update_output_metadata(
+        &self,
+        commitment: &Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let mut db = acquire_write_lock!(self.db);
+        let db = &mut *db;
+        let pending_outputs = db
+            .pending_transactions
+            .values_mut()
+            .chain(db.short_term_pending_transactions.values_mut())
+            .flat_map(|p| {
+                p.outputs_to_be_spent
+                    .iter_mut()
+                    .chain(p.outputs_to_be_received.iter_mut())
+            });
+        let mut found = false;
+        for output in db
+            .unspent_outputs
+            .iter_mut()
+            .chain(db.spent_outputs.iter_mut())
+            .chain(db.invalid_outputs.iter_mut())
+            .map(|o| &mut o.output)
+            .chain(pending_outputs)
+            .filter(|o| o.commitment == *commitment)
+        {
+            found = true;
+            match &update {
+                OutputMetadataUpdate::Label(label) => output.label = label.clone(),
+                OutputMetadataUpdate::Note(note) => output.note = note.clone(),
+                OutputMetadataUpdate::Frozen(frozen) => output.frozen = *frozen,
+            }
+        }
+        if !found {
+            return Err(OutputManagerStorageError::ValuesNotFound);
+        }
+        Ok(())
+    }
+
     fn apply_encryption(&self, _: Aes256Gcm) -> Result<(), OutputManagerStorageError> {
         Ok(())
     }
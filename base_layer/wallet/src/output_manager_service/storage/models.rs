@@ -20,7 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::output_manager_service::error::OutputManagerStorageError;
+use crate::output_manager_service::{
+    error::OutputManagerStorageError,
+    storage::database::DEFAULT_ACCOUNT_ID,
+    AccountId,
+    TxId,
+};
 use std::cmp::Ordering;
 use tari_core::{
     tari_utilities::hash::Hashable,
@@ -36,6 +41,17 @@ pub struct DbUnblindedOutput {
     pub commitment: Commitment,
     pub unblinded_output: UnblindedOutput,
     pub hash: HashOutput,
+    /// The transaction in which this output was received from another party. This is `None` for the wallet's own
+    /// change, coinbase and imported outputs.
+    pub received_in_tx_id: Option<TxId>,
+    /// A short user assigned label used to identify the output
+    pub label: Option<String>,
+    /// A free form user assigned note about the output
+    pub note: Option<String>,
+    /// A frozen output is never selected to fund a transaction, it can only be spent once it is unfrozen
+    pub frozen: bool,
+    /// The account this output belongs to. Only the outputs of the active account are spent when sending funds.
+    pub account_id: AccountId,
 }
 
 impl DbUnblindedOutput {
@@ -49,6 +65,11 @@ impl DbUnblindedOutput {
             hash: tx_out.hash(),
             commitment: tx_out.commitment,
             unblinded_output: output,
+            received_in_tx_id: None,
+            label: None,
+            note: None,
+            frozen: false,
+            account_id: DEFAULT_ACCOUNT_ID,
         })
     }
 
@@ -63,6 +84,11 @@ impl DbUnblindedOutput {
             hash: tx_out.hash(),
             commitment: tx_out.commitment,
             unblinded_output: output,
+            received_in_tx_id: None,
+            label: None,
+            note: None,
+            frozen: false,
+            account_id: DEFAULT_ACCOUNT_ID,
         })
     }
 }
@@ -25,19 +25,32 @@ use crate::{
         error::OutputManagerStorageError,
         storage::{
             database::{
+                Account,
                 DbKey,
                 DbKeyValuePair,
                 DbValue,
+                FeeBump,
                 KeyManagerState,
                 OutputManagerBackend,
+                OutputMetadataUpdate,
                 PendingTransactionOutputs,
+                WatchOnlyKeys,
                 WriteOperation,
+                DEFAULT_ACCOUNT_ID,
             },
             models::DbUnblindedOutput,
         },
+        AccountId,
         TxId,
     },
-    schema::{key_manager_states, outputs, pending_transaction_outputs},
+    schema::{
+        account_transactions,
+        accounts,
+        key_manager_states,
+        outputs,
+        pending_transaction_outputs,
+        watch_only_keys,
+    },
     storage::sqlite_utilities::WalletDbConnection,
     util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, Encryptable},
 };
@@ -146,6 +159,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                             &p.timestamp,
                             outputs,
                             p.coinbase_block_height.map(|h| h as u64),
+                            p.fee_bump()?,
                         )?,
                     )))
                 },
@@ -213,6 +227,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                             &p_tx.timestamp,
                             outputs,
                             p_tx.coinbase_block_height.map(|h| h as u64),
+                            p_tx.fee_bump()?,
                         )?,
                     );
                 }
@@ -226,6 +241,14 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                     Some(DbValue::KeyManagerState(KeyManagerState::try_from(km)?))
                 },
             },
+            DbKey::WatchOnlyKeys => match WatchOnlyKeysSql::get(&(*conn)).ok() {
+                None => None,
+                Some(mut keys) => {
+                    self.decrypt_if_necessary(&mut keys)?;
+
+                    Some(DbValue::WatchOnlyKeys(WatchOnlyKeys::try_from(keys)?))
+                },
+            },
             DbKey::InvalidOutputs => {
                 let mut outputs = OutputSql::index_status(OutputStatus::Invalid, &(*conn))?;
                 for o in outputs.iter_mut() {
@@ -239,6 +262,16 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                         .collect::<Result<Vec<_>, _>>()?,
                 ))
             },
+            DbKey::Accounts => Some(DbValue::Accounts(
+                AccountSql::index(&(*conn))?.into_iter().map(Account::from).collect(),
+            )),
+            DbKey::ActiveAccount => AccountSql::find_active(&(*conn))?.map(|a| DbValue::ActiveAccount(a.id as u64)),
+            DbKey::TransactionAccounts => Some(DbValue::TransactionAccounts(
+                AccountTransactionSql::index(&(*conn))?
+                    .into_iter()
+                    .map(|t| (t.tx_id as u64, t.account_id as u64))
+                    .collect(),
+            )),
         };
 
         Ok(result)
@@ -272,13 +305,16 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                         return Err(OutputManagerStorageError::DuplicateOutput);
                     }
 
-                    PendingTransactionOutputSql::new(
+                    let mut pending_tx = PendingTransactionOutputSql::new(
                         p.tx_id,
                         true,
                         p.timestamp,
                         p.coinbase_block_height.map(|h| h as i64),
-                    )
-                    .commit(&(*conn))?;
+                    );
+                    if let Some(fee_bump) = p.fee_bump.as_ref() {
+                        pending_tx = pending_tx.with_fee_bump(fee_bump);
+                    }
+                    pending_tx.commit(&(*conn))?;
                     for o in p.outputs_to_be_spent {
                         let mut new_output = NewOutputSql::new(o, OutputStatus::EncumberedToBeSpent, Some(p.tx_id));
                         self.encrypt_if_necessary(&mut new_output)?;
@@ -295,6 +331,26 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                     self.encrypt_if_necessary(&mut km_sql)?;
                     km_sql.set_state(&(*conn))?
                 },
+                DbKeyValuePair::WatchOnlyKeys(keys) => {
+                    let mut keys_sql = WatchOnlyKeysSql::from(keys);
+                    self.encrypt_if_necessary(&mut keys_sql)?;
+                    keys_sql.set(&(*conn))?
+                },
+                DbKeyValuePair::Account(a) => {
+                    if AccountSql::index(&(*conn))?
+                        .iter()
+                        .any(|v| v.id == a.id as i64 || v.name == a.name)
+                    {
+                        return Err(OutputManagerStorageError::DuplicateAccount);
+                    }
+                    AccountSql::from(a).commit(&(*conn))?
+                },
+                DbKeyValuePair::ActiveAccount(id) => AccountSql::set_active(id, &(*conn))?,
+                DbKeyValuePair::TransactionAccount(tx_id, id) => AccountTransactionSql {
+                    tx_id: tx_id as i64,
+                    account_id: id as i64,
+                }
+                .commit(&(*conn))?,
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::SpentOutput(s) => match OutputSql::find_status(&s.to_vec(), OutputStatus::Spent, &(*conn)) {
@@ -336,6 +392,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                                 &p.timestamp,
                                 outputs,
                                 p.coinbase_block_height.map(|h| h as u64),
+                                p.fee_bump()?,
                             )?,
                         ))));
                     },
@@ -352,6 +409,10 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                 DbKey::KeyManagerState => return Err(OutputManagerStorageError::OperationNotSupported),
                 DbKey::InvalidOutputs => return Err(OutputManagerStorageError::OperationNotSupported),
                 DbKey::TimeLockedUnspentOutputs(_) => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::WatchOnlyKeys => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::Accounts => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::ActiveAccount => return Err(OutputManagerStorageError::OperationNotSupported),
+                DbKey::TransactionAccounts => return Err(OutputManagerStorageError::OperationNotSupported),
             },
         }
 
@@ -440,6 +501,109 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         Ok(())
     }
 
+    fn add_outputs_to_pending_transaction(
+        &self,
+        tx_id: u64,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+
+        if let Err(e) = PendingTransactionOutputSql::find(tx_id, &(*conn)) {
+            match e {
+                OutputManagerStorageError::DieselError(DieselError::NotFound) => {
+                    return Err(OutputManagerStorageError::ValueNotFound(
+                        DbKey::PendingTransactionOutputs(tx_id),
+                    ))
+                },
+                e => return Err(e),
+            };
+        }
+
+        let mut outputs_to_be_spent = Vec::with_capacity(outputs_to_send.len());
+        for i in outputs_to_send {
+            let output = OutputSql::find_by_commitment(i.commitment.as_bytes(), &(*conn))?;
+            if output.status != (OutputStatus::Unspent as i32) {
+                return Err(OutputManagerStorageError::OutputAlreadySpent);
+            }
+            outputs_to_be_spent.push(output);
+        }
+
+        for o in outputs_to_be_spent {
+            o.update(
+                UpdateOutput {
+                    status: Some(OutputStatus::EncumberedToBeSpent),
+                    tx_id: Some(tx_id),
+                    spending_key: None,
+                },
+                &(*conn),
+            )?;
+        }
+
+        for co in outputs_to_receive {
+            let mut new_output = NewOutputSql::new(co.clone(), OutputStatus::EncumberedToBeReceived, Some(tx_id));
+            self.encrypt_if_necessary(&mut new_output)?;
+            new_output.commit(&(*conn))?;
+        }
+
+        Ok(())
+    }
+
+    fn encumber_fee_bump_outputs(
+        &self,
+        tx_id: TxId,
+        fee_bump: FeeBump,
+        outputs_to_send: &[DbUnblindedOutput],
+        outputs_to_receive: &[DbUnblindedOutput],
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+
+        if let Err(e) = PendingTransactionOutputSql::find(fee_bump.bumped_tx_id, &(*conn)) {
+            match e {
+                OutputManagerStorageError::DieselError(DieselError::NotFound) => {
+                    return Err(OutputManagerStorageError::ValueNotFound(
+                        DbKey::PendingTransactionOutputs(fee_bump.bumped_tx_id),
+                    ))
+                },
+                e => return Err(e),
+            };
+        }
+
+        let mut outputs_to_be_spent = Vec::with_capacity(outputs_to_send.len());
+        for i in outputs_to_send {
+            let output = OutputSql::find_by_commitment(i.commitment.as_bytes(), &(*conn))?;
+            if output.status != (OutputStatus::Unspent as i32) {
+                return Err(OutputManagerStorageError::OutputAlreadySpent);
+            }
+            outputs_to_be_spent.push(output);
+        }
+
+        PendingTransactionOutputSql::new(tx_id, false, Utc::now().naive_utc(), None)
+            .with_fee_bump(&fee_bump)
+            .commit(&(*conn))?;
+
+        for o in outputs_to_be_spent {
+            o.update(
+                UpdateOutput {
+                    status: Some(OutputStatus::EncumberedToBeSpent),
+                    tx_id: Some(tx_id),
+                    spending_key: None,
+                },
+                &(*conn),
+            )?;
+        }
+
+        for co in outputs_to_receive {
+            let mut new_output = NewOutputSql::new(co.clone(), OutputStatus::EncumberedToBeReceived, Some(tx_id));
+            self.encrypt_if_necessary(&mut new_output)?;
+            new_output.commit(&(*conn))?;
+        }
+
+        Ok(())
+    }
+
     fn confirm_encumbered_outputs(&self, tx_id: TxId) -> Result<(), OutputManagerStorageError> {
         let conn = self.database_connection.acquire_lock();
 
@@ -544,6 +708,14 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         Ok(())
     }
 
+    fn increment_account_key_index(&self, account_id: AccountId) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.acquire_lock();
+
+        AccountSql::increment_index(account_id, &(*conn))?;
+
+        Ok(())
+    }
+
     fn invalidate_unspent_output(&self, output: &DbUnblindedOutput) -> Result<Option<TxId>, OutputManagerStorageError> {
         let conn = self.database_connection.acquire_lock();
         let output = OutputSql::find_by_commitment(&output.commitment.to_vec(), &conn)?;
@@ -603,6 +775,23 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
         Ok(DbUnblindedOutput::try_from(o)?)
     }
 
+    fn update_output_metadata(
+        &self,
+        commitment: &Commitment,
+        update: OutputMetadataUpdate,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        let output = match OutputSql::find_by_commitment(&commitment.to_vec(), &conn) {
+            Ok(o) => o,
+            Err(OutputManagerStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(OutputManagerStorageError::ValuesNotFound)
+            },
+            Err(e) => return Err(e),
+        };
+        output.update_metadata(update, &conn)
+    }
+
     fn cancel_pending_transaction_at_block_height(&self, block_height: u64) -> Result<(), OutputManagerStorageError> {
         let pending_txs;
         {
@@ -640,20 +829,38 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             o.update_encryption(&conn)?;
         }
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
+        match KeyManagerStateSql::get_state(&conn) {
+            Ok(mut key_manager_state) => {
+                let _ = PrivateKey::from_vec(&key_manager_state.master_key).map_err(|_| {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not create PrivateKey from stored bytes, They might already be encrypted"
+                    );
+                    OutputManagerStorageError::AlreadyEncrypted
+                })?;
+
+                key_manager_state
+                    .encrypt(&cipher)
+                    .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+                key_manager_state.set_state(&conn)?;
+            },
+            Err(e) => {
+                // A watch-only wallet has no key manager state, so its watch-only keys are encrypted instead
+                let mut keys = WatchOnlyKeysSql::get(&conn).map_err(|_| e)?;
 
-        let _ = PrivateKey::from_vec(&key_manager_state.master_key).map_err(|_| {
-            error!(
-                target: LOG_TARGET,
-                "Could not create PrivateKey from stored bytes, They might already be encrypted"
-            );
-            OutputManagerStorageError::AlreadyEncrypted
-        })?;
+                let _ = PrivateKey::from_vec(&keys.rewind_key).map_err(|_| {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not create PrivateKey from stored bytes, They might already be encrypted"
+                    );
+                    OutputManagerStorageError::AlreadyEncrypted
+                })?;
 
-        key_manager_state
-            .encrypt(&cipher)
-            .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+                keys.encrypt(&cipher)
+                    .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+                keys.set(&conn)?;
+            },
+        }
 
         (*current_cipher) = Some(cipher);
 
@@ -676,11 +883,20 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             o.update_encryption(&conn)?;
         }
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
-        key_manager_state
-            .decrypt(&cipher)
-            .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+        match KeyManagerStateSql::get_state(&conn) {
+            Ok(mut key_manager_state) => {
+                key_manager_state
+                    .decrypt(&cipher)
+                    .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+                key_manager_state.set_state(&conn)?;
+            },
+            Err(e) => {
+                let mut keys = WatchOnlyKeysSql::get(&conn).map_err(|_| e)?;
+                keys.decrypt(&cipher)
+                    .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+                keys.set(&conn)?;
+            },
+        }
 
         // Now that all the decryption has been completed we can safely remove the cipher fully
         let _ = (*current_cipher).take();
@@ -694,6 +910,7 @@ fn pending_transaction_outputs_from_sql_outputs(
     timestamp: &NaiveDateTime,
     outputs: Vec<OutputSql>,
     coinbase_block_height: Option<u64>,
+    fee_bump: Option<FeeBump>,
 ) -> Result<PendingTransactionOutputs, OutputManagerStorageError>
 {
     let mut outputs_to_be_spent = Vec::new();
@@ -712,6 +929,7 @@ fn pending_transaction_outputs_from_sql_outputs(
         outputs_to_be_received,
         timestamp: *timestamp,
         coinbase_block_height,
+        fee_bump,
     })
 }
 
@@ -755,6 +973,11 @@ struct NewOutputSql {
     status: i32,
     tx_id: Option<i64>,
     hash: Option<Vec<u8>>,
+    received_in_tx_id: Option<i64>,
+    label: Option<String>,
+    note: Option<String>,
+    frozen: i32,
+    account_id: i64,
 }
 
 impl NewOutputSql {
@@ -768,6 +991,11 @@ impl NewOutputSql {
             status: status as i32,
             tx_id: tx_id.map(|i| i as i64),
             hash: Some(output.hash),
+            received_in_tx_id: output.received_in_tx_id.map(|i| i as i64),
+            label: output.label,
+            note: output.note,
+            frozen: output.frozen as i32,
+            account_id: output.account_id as i64,
         }
     }
 
@@ -802,6 +1030,11 @@ struct OutputSql {
     status: i32,
     tx_id: Option<i64>,
     hash: Option<Vec<u8>>,
+    received_in_tx_id: Option<i64>,
+    label: Option<String>,
+    note: Option<String>,
+    frozen: i32,
+    account_id: i64,
 }
 
 impl OutputSql {
@@ -926,6 +1159,31 @@ impl OutputSql {
         Ok(OutputSql::find(&self.spending_key, conn)?)
     }
 
+    /// Update the user assigned metadata of this record
+    pub fn update_metadata(
+        &self,
+        update: OutputMetadataUpdate,
+        conn: &SqliteConnection,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let target = outputs::table.filter(outputs::id.eq(&self.id));
+        let num_updated = match update {
+            OutputMetadataUpdate::Label(label) => diesel::update(target).set(outputs::label.eq(label)).execute(conn)?,
+            OutputMetadataUpdate::Note(note) => diesel::update(target).set(outputs::note.eq(note)).execute(conn)?,
+            OutputMetadataUpdate::Frozen(frozen) => diesel::update(target)
+                .set(outputs::frozen.eq(frozen as i32))
+                .execute(conn)?,
+        };
+
+        if num_updated == 0 {
+            return Err(OutputManagerStorageError::UnexpectedResult(
+                "Database update error".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Update the changed fields of this record after encryption/decryption is performed
     pub fn update_encryption(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
         let _ = self.update(
@@ -981,6 +1239,11 @@ impl TryFrom<OutputSql> for DbUnblindedOutput {
             commitment,
             unblinded_output,
             hash,
+            received_in_tx_id: o.received_in_tx_id.map(|i| i as u64),
+            label: o.label,
+            note: o.note,
+            frozen: o.frozen != 0,
+            account_id: o.account_id as u64,
         })
     }
 }
@@ -1008,6 +1271,11 @@ impl From<OutputSql> for NewOutputSql {
             status: o.status,
             tx_id: o.tx_id,
             hash: o.hash,
+            received_in_tx_id: o.received_in_tx_id,
+            label: o.label,
+            note: o.note,
+            frozen: o.frozen,
+            account_id: o.account_id,
         }
     }
 }
@@ -1061,6 +1329,8 @@ struct PendingTransactionOutputSql {
     short_term: i32,
     timestamp: NaiveDateTime,
     coinbase_block_height: Option<i64>,
+    fee_bump_of: Option<i64>,
+    fee_bump_excess: Option<Vec<u8>>,
 }
 impl PendingTransactionOutputSql {
     pub fn new(tx_id: TxId, short_term: bool, timestamp: NaiveDateTime, coinbase_block_height: Option<i64>) -> Self {
@@ -1069,6 +1339,26 @@ impl PendingTransactionOutputSql {
             short_term: short_term as i32,
             timestamp,
             coinbase_block_height,
+            fee_bump_of: None,
+            fee_bump_excess: None,
+        }
+    }
+
+    /// Link this pending transaction to the transaction whose fee it bumps
+    pub fn with_fee_bump(mut self, fee_bump: &FeeBump) -> Self {
+        self.fee_bump_of = Some(fee_bump.bumped_tx_id as i64);
+        self.fee_bump_excess = Some(fee_bump.kernel_excess.to_vec());
+        self
+    }
+
+    pub fn fee_bump(&self) -> Result<Option<FeeBump>, OutputManagerStorageError> {
+        match (self.fee_bump_of, self.fee_bump_excess.as_ref()) {
+            (Some(bumped_tx_id), Some(excess)) => Ok(Some(FeeBump {
+                bumped_tx_id: bumped_tx_id as u64,
+                kernel_excess: Commitment::from_bytes(excess)
+                    .map_err(|_| OutputManagerStorageError::ConversionError)?,
+            })),
+            _ => Ok(None),
         }
     }
 
@@ -1293,6 +1583,194 @@ impl Encryptable<Aes256Gcm> for KeyManagerStateSql {
     }
 }
 
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[table_name = "watch_only_keys"]
+struct WatchOnlyKeysSql {
+    id: Option<i64>,
+    rewind_key: Vec<u8>,
+    rewind_blinding_key: Vec<u8>,
+    timestamp: NaiveDateTime,
+}
+
+impl From<WatchOnlyKeys> for WatchOnlyKeysSql {
+    fn from(keys: WatchOnlyKeys) -> Self {
+        Self {
+            id: None,
+            rewind_key: keys.rewind_key.to_vec(),
+            rewind_blinding_key: keys.rewind_blinding_key.to_vec(),
+            timestamp: Utc::now().naive_utc(),
+        }
+    }
+}
+
+impl TryFrom<WatchOnlyKeysSql> for WatchOnlyKeys {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(keys: WatchOnlyKeysSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            rewind_key: PrivateKey::from_vec(&keys.rewind_key)
+                .map_err(|_| OutputManagerStorageError::ConversionError)?,
+            rewind_blinding_key: PrivateKey::from_vec(&keys.rewind_blinding_key)
+                .map_err(|_| OutputManagerStorageError::ConversionError)?,
+        })
+    }
+}
+
+impl WatchOnlyKeysSql {
+    fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(watch_only_keys::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn get(conn: &SqliteConnection) -> Result<WatchOnlyKeysSql, OutputManagerStorageError> {
+        Ok(watch_only_keys::table.first::<WatchOnlyKeysSql>(conn)?)
+    }
+
+    pub fn set(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        match WatchOnlyKeysSql::get(conn) {
+            Ok(keys) => {
+                let update = WatchOnlyKeysUpdateSql {
+                    rewind_key: Some(self.rewind_key.clone()),
+                    rewind_blinding_key: Some(self.rewind_blinding_key.clone()),
+                };
+
+                let num_updated = diesel::update(watch_only_keys::table.filter(watch_only_keys::id.eq(&keys.id)))
+                    .set(update)
+                    .execute(conn)?;
+                if num_updated == 0 {
+                    return Err(OutputManagerStorageError::UnexpectedResult(
+                        "Database update error".to_string(),
+                    ));
+                }
+            },
+            Err(_) => self.commit(conn)?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(AsChangeset)]
+#[table_name = "watch_only_keys"]
+struct WatchOnlyKeysUpdateSql {
+    rewind_key: Option<Vec<u8>>,
+    rewind_blinding_key: Option<Vec<u8>>,
+}
+
+impl Encryptable<Aes256Gcm> for WatchOnlyKeysSql {
+    fn encrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), Error> {
+        self.rewind_key = encrypt_bytes_integral_nonce(&cipher, self.rewind_key.clone())?;
+        self.rewind_blinding_key = encrypt_bytes_integral_nonce(&cipher, self.rewind_blinding_key.clone())?;
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), Error> {
+        self.rewind_key = decrypt_bytes_integral_nonce(&cipher, self.rewind_key.clone())?;
+        self.rewind_blinding_key = decrypt_bytes_integral_nonce(&cipher, self.rewind_blinding_key.clone())?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[table_name = "accounts"]
+struct AccountSql {
+    id: i64,
+    name: String,
+    primary_key_index: i64,
+    active: i32,
+}
+
+impl From<Account> for AccountSql {
+    fn from(a: Account) -> Self {
+        Self {
+            id: a.id as i64,
+            name: a.name,
+            primary_key_index: a.primary_key_index as i64,
+            active: 0,
+        }
+    }
+}
+
+impl From<AccountSql> for Account {
+    fn from(a: AccountSql) -> Self {
+        Self {
+            id: a.id as u64,
+            name: a.name,
+            primary_key_index: a.primary_key_index as u64,
+        }
+    }
+}
+
+impl AccountSql {
+    fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(accounts::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<AccountSql>, OutputManagerStorageError> {
+        Ok(accounts::table.order(accounts::id.asc()).load::<AccountSql>(conn)?)
+    }
+
+    pub fn find_active(conn: &SqliteConnection) -> Result<Option<AccountSql>, OutputManagerStorageError> {
+        Ok(accounts::table
+            .filter(accounts::active.eq(1))
+            .first::<AccountSql>(conn)
+            .optional()?)
+    }
+
+    /// Mark the specified account as the active account. The default account is not stored so it is active when no
+    /// stored account is marked as active.
+    pub fn set_active(account_id: AccountId, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        conn.transaction::<_, OutputManagerStorageError, _>(|| {
+            diesel::update(accounts::table)
+                .set(accounts::active.eq(0))
+                .execute(conn)?;
+            if account_id != DEFAULT_ACCOUNT_ID {
+                let num_updated = diesel::update(accounts::table.filter(accounts::id.eq(account_id as i64)))
+                    .set(accounts::active.eq(1))
+                    .execute(conn)?;
+                if num_updated == 0 {
+                    return Err(OutputManagerStorageError::ValueNotFound(DbKey::Accounts));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    pub fn increment_index(account_id: AccountId, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        let num_updated = diesel::update(accounts::table.filter(accounts::id.eq(account_id as i64)))
+            .set(accounts::primary_key_index.eq(accounts::primary_key_index + 1))
+            .execute(conn)?;
+        if num_updated == 0 {
+            return Err(OutputManagerStorageError::ValueNotFound(DbKey::Accounts));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable)]
+#[table_name = "account_transactions"]
+struct AccountTransactionSql {
+    tx_id: i64,
+    account_id: i64,
+}
+
+impl AccountTransactionSql {
+    fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::replace_into(account_transactions::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<AccountTransactionSql>, OutputManagerStorageError> {
+        Ok(account_transactions::table.load::<AccountTransactionSql>(conn)?)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -0,0 +1,578 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::output_manager_service::{error::OutputManagerError, storage::models::DbUnblindedOutput, TxId};
+use log::*;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Display},
+};
+use tari_core::transactions::{fee::Fee, tari_amount::MicroTari, types::Commitment};
+use tari_crypto::tari_utilities::hex::Hex;
+
+const LOG_TARGET: &str = "wallet::output_manager_service::utxo_selection";
+
+/// The default maximum number of branches the branch-and-bound search will try before it gives up
+pub const BRANCH_AND_BOUND_DEFAULT_MAX_TRIES: usize = 100_000;
+
+/// The parameters of the transaction that UTXOs are being selected for
+#[derive(Debug, Clone)]
+pub struct UtxoSelectionParams {
+    /// The total amount to be sent, excluding fees
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    /// The number of outputs in the transaction, excluding a possible change output
+    pub output_count: usize,
+    /// The height of the chain tip, if it is known
+    pub tip_height: Option<u64>,
+}
+
+impl UtxoSelectionParams {
+    /// The fee of the transaction with the given number of inputs and no change output
+    pub fn fee_without_change(&self, num_inputs: usize) -> MicroTari {
+        Fee::calculate(self.fee_per_gram, 1, num_inputs, self.output_count)
+    }
+
+    /// The fee of the transaction with the given number of inputs and a change output
+    pub fn fee_with_change(&self, num_inputs: usize) -> MicroTari {
+        Fee::calculate(self.fee_per_gram, 1, num_inputs, self.output_count + 1)
+    }
+}
+
+/// The outputs chosen by a [UtxoSelector]
+#[derive(Debug, Clone, Default)]
+pub struct UtxoSelection {
+    pub utxos: Vec<DbUnblindedOutput>,
+    pub requires_change_output: bool,
+}
+
+impl UtxoSelection {
+    pub fn total_value(&self) -> MicroTari {
+        self.utxos.iter().map(|o| o.unblinded_output.value).sum()
+    }
+}
+
+/// Chooses which unspent outputs are used to fund a transaction. The wallet is configured with a default selector in
+/// the `OutputManagerServiceConfig` which can be overridden per transaction with a [UTXOSelectionStrategy].
+pub trait UtxoSelector: fmt::Debug + Send + Sync {
+    /// Select the outputs to spend from `candidates`. The candidates only contain outputs that are spendable at the
+    /// current chain tip (if it is known) and are sorted from the lowest to the highest value.
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>;
+}
+
+/// Different UTXO selection strategies for choosing which UTXO's are used to fulfill a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UTXOSelectionStrategy {
+    // Start from the smallest UTXOs and work your way up until the amount is covered. Main benefit
+    // is removing small UTXOs from the blockchain, con is that it costs more in fees
+    Smallest,
+    // Start from oldest maturity to reduce the likelihood of grabbing locked up UTXOs
+    MaturityThenSmallest,
+    // A strategy that selects the largest UTXOs first. Preferred when the amount is large
+    Largest,
+    // Search for a set of UTXOs that covers the amount and fee exactly so that no change output is created. Falls
+    // back to the default selection heuristic if no such set can be found
+    BranchAndBound,
+    // Only spend UTXOs that were received in the same transaction so that funds from different counterparties are
+    // never merged
+    PrivacyAware,
+}
+
+impl UtxoSelector for UTXOSelectionStrategy {
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>
+    {
+        match self {
+            UTXOSelectionStrategy::Smallest => select_in_order(candidates, params),
+            UTXOSelectionStrategy::MaturityThenSmallest => {
+                let mut candidates = candidates;
+                candidates.sort_by(|a, b| {
+                    match a
+                        .unblinded_output
+                        .features
+                        .maturity
+                        .cmp(&b.unblinded_output.features.maturity)
+                    {
+                        Ordering::Equal => a.unblinded_output.value.cmp(&b.unblinded_output.value),
+                        Ordering::Less => Ordering::Less,
+                        Ordering::Greater => Ordering::Greater,
+                    }
+                });
+                select_in_order(candidates, params)
+            },
+            UTXOSelectionStrategy::Largest => select_in_order(candidates.into_iter().rev(), params),
+            UTXOSelectionStrategy::BranchAndBound => BranchAndBoundSelector::default().select(candidates, params),
+            UTXOSelectionStrategy::PrivacyAware => PrivacyAwareSelector::default().select(candidates, params),
+        }
+    }
+}
+
+impl Display for UTXOSelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UTXOSelectionStrategy::Smallest => write!(f, "Smallest"),
+            UTXOSelectionStrategy::MaturityThenSmallest => write!(f, "MaturityThenSmallest"),
+            UTXOSelectionStrategy::Largest => write!(f, "Largest"),
+            UTXOSelectionStrategy::BranchAndBound => write!(f, "BranchAndBound"),
+            UTXOSelectionStrategy::PrivacyAware => write!(f, "PrivacyAware"),
+        }
+    }
+}
+
+/// The selection heuristic used when no strategy is specified for a transaction: Default to MaturityThenSmallest, but
+/// if the amount is greater than the largest UTXO, use Largest UTXOs first. If the chain tip is not known,
+/// MaturityThenSmallest is always used.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultUtxoSelector;
+
+impl UtxoSelector for DefaultUtxoSelector {
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>
+    {
+        let strategy = match (params.tip_height, candidates.last()) {
+            (None, _) => UTXOSelectionStrategy::MaturityThenSmallest,
+            (Some(_), None) => UTXOSelectionStrategy::Smallest,
+            (Some(_), Some(largest_utxo)) => {
+                if params.amount > largest_utxo.unblinded_output.value {
+                    UTXOSelectionStrategy::Largest
+                } else {
+                    UTXOSelectionStrategy::MaturityThenSmallest
+                }
+            },
+        };
+        debug!(target: LOG_TARGET, "Default UTXO selector chose strategy: {}", strategy);
+        strategy.select(candidates, params)
+    }
+}
+
+/// Searches for a subset of the candidates whose value covers the amount and fee without leaving enough over to
+/// justify a change output. Any excess (which is always less than the cost of adding a change output) is added to the
+/// fee. If no such subset exists, or the search exceeds `max_tries`, the `fallback` selector is used.
+#[derive(Debug, Clone)]
+pub struct BranchAndBoundSelector {
+    pub max_tries: usize,
+    pub fallback: UTXOSelectionStrategy,
+}
+
+impl Default for BranchAndBoundSelector {
+    fn default() -> Self {
+        Self {
+            max_tries: BRANCH_AND_BOUND_DEFAULT_MAX_TRIES,
+            fallback: UTXOSelectionStrategy::MaturityThenSmallest,
+        }
+    }
+}
+
+impl BranchAndBoundSelector {
+    /// Depth-first search over the inclusion/omission tree of the `values`, which must be sorted from highest to
+    /// lowest. Returns the indexes of the selection with the least waste within `[target, upper_bound]`.
+    fn search(&self, values: &[i64], target: i64, upper_bound: i64) -> Option<Vec<usize>> {
+        let mut curr_available = values.iter().sum::<i64>();
+        if curr_available < target {
+            return None;
+        }
+
+        let mut curr_value = 0i64;
+        let mut curr_selection = Vec::<usize>::new();
+        let mut best_selection = None;
+        let mut best_waste = i64::MAX;
+        let mut index = 0usize;
+
+        for _ in 0..self.max_tries {
+            let mut backtrack = false;
+            if curr_value + curr_available < target || curr_value > upper_bound {
+                backtrack = true;
+            } else if curr_value >= target {
+                let waste = curr_value - target;
+                if waste < best_waste {
+                    best_waste = waste;
+                    best_selection = Some(curr_selection.clone());
+                    if waste == 0 {
+                        break;
+                    }
+                }
+                backtrack = true;
+            }
+
+            if backtrack {
+                // Walk back to the last included value and take its omission branch
+                let last_included = match curr_selection.pop() {
+                    Some(i) => i,
+                    None => break,
+                };
+                index -= 1;
+                while index > last_included {
+                    curr_available += values[index];
+                    index -= 1;
+                }
+                curr_value -= values[index];
+            } else {
+                curr_available -= values[index];
+                // Omitting a value and then including an equal one yields a branch that has already been searched
+                let is_equal_to_omitted = index > 0 &&
+                    !curr_selection.is_empty() &&
+                    curr_selection.last() != Some(&(index - 1)) &&
+                    values[index] == values[index - 1];
+                if !is_equal_to_omitted {
+                    curr_selection.push(index);
+                    curr_value += values[index];
+                }
+            }
+            index += 1;
+        }
+
+        best_selection
+    }
+}
+
+impl UtxoSelector for BranchAndBoundSelector {
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>
+    {
+        // Fees are linear in the number of inputs, so each input is valued at what it adds after paying for itself
+        let input_fee = u64::from(Fee::calculate(params.fee_per_gram, 0, 1, 0)) as i64;
+        let target = (u64::from(params.amount) + u64::from(params.fee_without_change(0))) as i64;
+        let cost_of_change = u64::from(params.fee_with_change(0) - params.fee_without_change(0)) as i64;
+
+        let mut effective = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, o)| (i, u64::from(o.unblinded_output.value) as i64 - input_fee))
+            .filter(|(_, v)| *v > 0)
+            .collect::<Vec<_>>();
+        effective.sort_by(|a, b| b.1.cmp(&a.1));
+        let values = effective.iter().map(|(_, v)| *v).collect::<Vec<_>>();
+
+        match self.search(&values, target, target + cost_of_change) {
+            Some(selected) => {
+                let mut selected = selected.into_iter().map(|i| effective[i].0).collect::<Vec<_>>();
+                selected.sort_unstable();
+                let utxos = candidates
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| selected.binary_search(i).is_ok())
+                    .map(|(_, o)| o)
+                    .collect::<Vec<_>>();
+                debug!(
+                    target: LOG_TARGET,
+                    "Branch and bound found a changeless selection of {} UTXOs",
+                    utxos.len()
+                );
+                Ok(UtxoSelection {
+                    utxos,
+                    requires_change_output: false,
+                })
+            },
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Branch and bound found no changeless selection, falling back to {}", self.fallback
+                );
+                self.fallback.select(candidates, params)
+            },
+        }
+    }
+}
+
+/// Groups the candidates by the transaction they were received in and funds the transaction from a single group, so
+/// that outputs from different counterparties are never merged. Outputs without a recorded origin (change, coinbase
+/// and imported outputs) are the wallet's own funds and form a group of their own. If several groups can fund the
+/// transaction, the one needing the fewest inputs is used, followed by the one spending the lowest total value.
+#[derive(Debug, Clone, Default)]
+pub struct PrivacyAwareSelector;
+
+impl UtxoSelector for PrivacyAwareSelector {
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>
+    {
+        let mut groups = HashMap::<Option<TxId>, Vec<DbUnblindedOutput>>::new();
+        for candidate in candidates {
+            groups
+                .entry(candidate.received_in_tx_id)
+                .or_insert_with(Vec::new)
+                .push(candidate);
+        }
+        let num_groups = groups.len();
+
+        let best = groups
+            .into_iter()
+            .filter_map(|(_, group)| DefaultUtxoSelector.select(group, params).ok())
+            .min_by(|a, b| {
+                a.utxos
+                    .len()
+                    .cmp(&b.utxos.len())
+                    .then_with(|| a.total_value().cmp(&b.total_value()))
+            });
+
+        trace!(
+            target: LOG_TARGET,
+            "Privacy aware selection considered {} source groups",
+            num_groups
+        );
+        best.ok_or(OutputManagerError::NotEnoughFundsFromSingleSource)
+    }
+}
+
+/// Spends exactly the outputs with the given commitments, in the given order, for manual coin control. Every
+/// commitment must belong to a different candidate output and the outputs must cover the amount and fee. Any excess
+/// that is too small to pay for a change output is added to the fee.
+#[derive(Debug, Clone, Default)]
+pub struct ManualUtxoSelector {
+    pub commitments: Vec<Commitment>,
+}
+
+impl ManualUtxoSelector {
+    pub fn new(commitments: Vec<Commitment>) -> Self {
+        Self { commitments }
+    }
+}
+
+impl UtxoSelector for ManualUtxoSelector {
+    fn select(
+        &self,
+        candidates: Vec<DbUnblindedOutput>,
+        params: &UtxoSelectionParams,
+    ) -> Result<UtxoSelection, OutputManagerError>
+    {
+        if self.commitments.is_empty() {
+            return Err(OutputManagerError::BuildError(
+                "At least one input must be selected".to_string(),
+            ));
+        }
+        let mut candidates = candidates;
+        let mut utxos = Vec::with_capacity(self.commitments.len());
+        for commitment in self.commitments.iter() {
+            match candidates.iter().position(|o| &o.commitment == commitment) {
+                Some(pos) => utxos.push(candidates.remove(pos)),
+                None => return Err(OutputManagerError::InputNotSpendable(commitment.to_hex())),
+            }
+        }
+
+        let selection = UtxoSelection {
+            utxos,
+            requires_change_output: false,
+        };
+        let total = selection.total_value();
+        let num_inputs = selection.utxos.len();
+        if total >= params.amount + params.fee_with_change(num_inputs) {
+            Ok(UtxoSelection {
+                requires_change_output: true,
+                ..selection
+            })
+        } else if total >= params.amount + params.fee_without_change(num_inputs) {
+            Ok(selection)
+        } else {
+            Err(OutputManagerError::NotEnoughFunds)
+        }
+    }
+}
+
+/// Add the UTXOs in the given order until the amount and fee are covered, with or without a change output.
+fn select_in_order<I>(utxos: I, params: &UtxoSelectionParams) -> Result<UtxoSelection, OutputManagerError>
+where I: IntoIterator<Item = DbUnblindedOutput> {
+    let mut selected = Vec::new();
+    let mut total = MicroTari::from(0);
+    let mut fee_without_change = MicroTari::from(0);
+    let mut fee_with_change = MicroTari::from(0);
+    let mut requires_change_output = false;
+
+    for o in utxos {
+        total += o.unblinded_output.value;
+        selected.push(o);
+        // The assumption here is that the only outputs will be the payment outputs and change if required
+        fee_without_change = params.fee_without_change(selected.len());
+        if total == params.amount + fee_without_change {
+            break;
+        }
+        fee_with_change = params.fee_with_change(selected.len());
+        if total >= params.amount + fee_with_change {
+            requires_change_output = true;
+            break;
+        }
+    }
+
+    if (total != params.amount + fee_without_change) && (total < params.amount + fee_with_change) {
+        return Err(OutputManagerError::NotEnoughFunds);
+    }
+
+    Ok(UtxoSelection {
+        utxos: selected,
+        requires_change_output,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{
+        transaction::UnblindedOutput,
+        types::{CryptoFactories, PrivateKey},
+    };
+    use tari_crypto::keys::SecretKey;
+
+    fn make_output(value: u64, received_in_tx_id: Option<TxId>) -> DbUnblindedOutput {
+        let factories = CryptoFactories::default();
+        let mut output = DbUnblindedOutput::from_unblinded_output(
+            UnblindedOutput::new(MicroTari::from(value), PrivateKey::random(&mut OsRng), None),
+            &factories,
+        )
+        .unwrap();
+        output.received_in_tx_id = received_in_tx_id;
+        output
+    }
+
+    fn test_params(amount: u64) -> UtxoSelectionParams {
+        UtxoSelectionParams {
+            amount: MicroTari::from(amount),
+            fee_per_gram: MicroTari::from(10),
+            output_count: 1,
+            tip_height: Some(100),
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_finds_changeless_selection() {
+        let params = test_params(10_000);
+        // Exactly two inputs can pay the amount and the fee for a two input transaction
+        let fee = u64::from(params.fee_without_change(2));
+        let candidates = vec![
+            make_output(1_000, None),
+            make_output(4_000, None),
+            make_output(6_000, None),
+            make_output(10_000 + fee - 6_000, None),
+            make_output(20_000, None),
+        ];
+
+        let selection = UTXOSelectionStrategy::BranchAndBound
+            .select(candidates, &params)
+            .unwrap();
+        assert!(!selection.requires_change_output);
+        assert_eq!(selection.utxos.len(), 2);
+        assert_eq!(selection.total_value(), MicroTari::from(10_000 + fee));
+    }
+
+    #[test]
+    fn branch_and_bound_falls_back_when_no_exact_match() {
+        let params = test_params(10_000);
+        let candidates = vec![make_output(50_000, None), make_output(60_000, None)];
+
+        let selection = UTXOSelectionStrategy::BranchAndBound
+            .select(candidates, &params)
+            .unwrap();
+        assert!(selection.requires_change_output);
+        assert_eq!(selection.utxos.len(), 1);
+        assert_eq!(selection.total_value(), MicroTari::from(50_000));
+    }
+
+    #[test]
+    fn branch_and_bound_not_enough_funds() {
+        let params = test_params(10_000);
+        let candidates = vec![make_output(2_000, None), make_output(3_000, None)];
+
+        let err = UTXOSelectionStrategy::BranchAndBound
+            .select(candidates, &params)
+            .unwrap_err();
+        assert!(matches!(err, OutputManagerError::NotEnoughFunds));
+    }
+
+    #[test]
+    fn privacy_aware_does_not_merge_sources() {
+        let params = test_params(10_000);
+        let candidates = vec![
+            make_output(6_000, Some(1)),
+            make_output(7_000, Some(2)),
+            make_output(8_000, Some(3)),
+            make_output(9_000, Some(3)),
+        ];
+
+        let selection = UTXOSelectionStrategy::PrivacyAware
+            .select(candidates.clone(), &params)
+            .unwrap();
+        assert!(selection.utxos.iter().all(|o| o.received_in_tx_id == Some(3)));
+        assert_eq!(selection.utxos.len(), 2);
+
+        let err = UTXOSelectionStrategy::PrivacyAware
+            .select(candidates, &test_params(20_000))
+            .unwrap_err();
+        assert!(matches!(err, OutputManagerError::NotEnoughFundsFromSingleSource));
+    }
+
+    #[test]
+    fn manual_selection_spends_exactly_the_given_outputs() {
+        let params = test_params(10_000);
+        let candidates = vec![
+            make_output(5_000, None),
+            make_output(8_000, None),
+            make_output(20_000, None),
+        ];
+
+        let selector =
+            ManualUtxoSelector::new(vec![candidates[0].commitment.clone(), candidates[1].commitment.clone()]);
+        let selection = selector.select(candidates.clone(), &params).unwrap();
+        assert!(selection.requires_change_output);
+        assert_eq!(selection.utxos.len(), 2);
+        assert_eq!(selection.total_value(), MicroTari::from(13_000));
+
+        let selector = ManualUtxoSelector::new(vec![candidates[0].commitment.clone()]);
+        let err = selector.select(candidates.clone(), &params).unwrap_err();
+        assert!(matches!(err, OutputManagerError::NotEnoughFunds));
+
+        // An excess that cannot pay for a change output goes to the fee
+        let fee_without_change = u64::from(params.fee_without_change(1));
+        let cost_of_change = u64::from(params.fee_with_change(1)) - fee_without_change;
+        for excess in &[0, cost_of_change - 1] {
+            let output = make_output(10_000 + fee_without_change + excess, None);
+            let selector = ManualUtxoSelector::new(vec![output.commitment.clone()]);
+            let selection = selector.select(vec![output], &params).unwrap();
+            assert!(!selection.requires_change_output);
+            assert_eq!(selection.utxos.len(), 1);
+        }
+
+        let unknown = make_output(50_000, None);
+        let selector = ManualUtxoSelector::new(vec![unknown.commitment]);
+        let err = selector.select(candidates.clone(), &params).unwrap_err();
+        assert!(matches!(err, OutputManagerError::InputNotSpendable(_)));
+
+        let selector =
+            ManualUtxoSelector::new(vec![candidates[2].commitment.clone(), candidates[2].commitment.clone()]);
+        let err = selector.select(candidates, &params).unwrap_err();
+        assert!(matches!(err, OutputManagerError::InputNotSpendable(_)));
+    }
+}
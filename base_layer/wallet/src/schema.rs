@@ -1,3 +1,19 @@
+table! {
+    account_transactions (tx_id) {
+        tx_id -> BigInt,
+        account_id -> BigInt,
+    }
+}
+
+table! {
+    accounts (id) {
+        id -> BigInt,
+        name -> Text,
+        primary_key_index -> BigInt,
+        active -> Integer,
+    }
+}
+
 table! {
     client_key_values (key) {
         key -> Text,
@@ -70,6 +86,7 @@ table! {
         direct_send_success -> Integer,
         send_count -> Integer,
         last_send_timestamp -> Nullable<Timestamp>,
+        batch_recipients -> Nullable<Text>,
     }
 }
 
@@ -84,6 +101,11 @@ table! {
         status -> Integer,
         tx_id -> Nullable<BigInt>,
         hash -> Nullable<Binary>,
+        received_in_tx_id -> Nullable<BigInt>,
+        label -> Nullable<Text>,
+        note -> Nullable<Text>,
+        frozen -> Integer,
+        account_id -> BigInt,
     }
 }
 
@@ -93,6 +115,8 @@ table! {
         short_term -> Integer,
         timestamp -> Timestamp,
         coinbase_block_height -> Nullable<BigInt>,
+        fee_bump_of -> Nullable<BigInt>,
+        fee_bump_excess -> Nullable<Binary>,
     }
 }
 
@@ -103,7 +127,18 @@ table! {
     }
 }
 
+table! {
+    watch_only_keys (id) {
+        id -> Nullable<BigInt>,
+        rewind_key -> Binary,
+        rewind_blinding_key -> Binary,
+        timestamp -> Timestamp,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
+    account_transactions,
+    accounts,
     client_key_values,
     completed_transactions,
     contacts,
@@ -113,4 +148,5 @@ allow_tables_to_appear_in_same_query!(
     outputs,
     pending_transaction_outputs,
     wallet_settings,
+    watch_only_keys,
 );
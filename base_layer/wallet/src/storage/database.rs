@@ -20,10 +20,16 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::error::WalletStorageError;
+use crate::{
+    error::WalletStorageError,
+    multisig_service::service::{MultiSigKeySetupShare, MultiSigOutput},
+    output_manager_service::TxId,
+    utxo_scanner_service::service::ScannedBlock,
+};
 use aes_gcm::Aes256Gcm;
 use log::*;
 use std::{
+    collections::HashMap,
     fmt::{Display, Error, Formatter},
     sync::Arc,
 };
@@ -34,6 +40,7 @@ use tari_comms::{
     tor::TorIdentity,
     types::{CommsPublicKey, CommsSecretKey},
 };
+use tari_core::transactions::SenderTransactionProtocol;
 
 const LOG_TARGET: &str = "wallet::database";
 
@@ -59,6 +66,10 @@ pub enum DbKey {
     TorId,
     BaseNodeChainMetadata,
     ClientKey(String),
+    ScannedBlocks,
+    SigningProtocols,
+    MultiSigOutputs,
+    MultiSigKeySetups,
 }
 
 pub enum DbValue {
@@ -71,6 +82,10 @@ pub enum DbValue {
     ClientValue(String),
     ValueCleared,
     BaseNodeChainMetadata(ChainMetadata),
+    ScannedBlocks(Vec<ScannedBlock>),
+    SigningProtocols(HashMap<TxId, SenderTransactionProtocol>),
+    MultiSigOutputs(Vec<MultiSigOutput>),
+    MultiSigKeySetups(Vec<MultiSigKeySetupShare>),
 }
 
 #[derive(Clone)]
@@ -80,6 +95,10 @@ pub enum DbKeyValuePair {
     Identity(Box<NodeIdentity>),
     TorId(TorIdentity),
     BaseNodeChainMetadata(ChainMetadata),
+    ScannedBlocks(Vec<ScannedBlock>),
+    SigningProtocols(HashMap<TxId, SenderTransactionProtocol>),
+    MultiSigOutputs(Vec<MultiSigOutput>),
+    MultiSigKeySetups(Vec<MultiSigKeySetupShare>),
 }
 
 pub enum WriteOperation {
@@ -174,6 +193,123 @@ where T: WalletBackend + 'static
         Ok(())
     }
 
+    /// Returns the blocks the UTXO scanner has scanned up to, most recent first
+    pub async fn get_scanned_blocks(&self) -> Result<Vec<ScannedBlock>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ScannedBlocks) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::ScannedBlocks(blocks))) => Ok(blocks),
+            Ok(Some(other)) => unexpected_result(DbKey::ScannedBlocks, other),
+            Err(e) => log_error(DbKey::ScannedBlocks, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_scanned_blocks(&self, blocks: Vec<ScannedBlock>) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::ScannedBlocks(blocks)))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn clear_scanned_blocks(&self) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.write(WriteOperation::Remove(DbKey::ScannedBlocks)))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Returns the sender protocols of the transactions this wallet has signed for an online wallet and that are
+    /// waiting for the recipient's reply, keyed by their protocol transaction id
+    pub async fn get_signing_protocols(&self) -> Result<HashMap<TxId, SenderTransactionProtocol>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::SigningProtocols) {
+            Ok(None) => Ok(HashMap::new()),
+            Ok(Some(DbValue::SigningProtocols(protocols))) => Ok(protocols),
+            Ok(Some(other)) => unexpected_result(DbKey::SigningProtocols, other),
+            Err(e) => log_error(DbKey::SigningProtocols, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_signing_protocols(
+        &self,
+        protocols: HashMap<TxId, SenderTransactionProtocol>,
+    ) -> Result<(), WalletStorageError>
+    {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::SigningProtocols(protocols)))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Returns the multi-signature outputs this wallet holds a key share of
+    pub async fn get_multisig_outputs(&self) -> Result<Vec<MultiSigOutput>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::MultiSigOutputs) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::MultiSigOutputs(outputs))) => Ok(outputs),
+            Ok(Some(other)) => unexpected_result(DbKey::MultiSigOutputs, other),
+            Err(e) => log_error(DbKey::MultiSigOutputs, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_multisig_outputs(&self, outputs: Vec<MultiSigOutput>) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::MultiSigOutputs(outputs)))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Returns this wallet's key shares of the multi-signature outputs whose key setup is in progress
+    pub async fn get_multisig_key_setups(&self) -> Result<Vec<MultiSigKeySetupShare>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::MultiSigKeySetups) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::MultiSigKeySetups(setups))) => Ok(setups),
+            Ok(Some(other)) => unexpected_result(DbKey::MultiSigKeySetups, other),
+            Err(e) => log_error(DbKey::MultiSigKeySetups, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_multisig_key_setups(&self, setups: Vec<MultiSigKeySetupShare>) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::MultiSigKeySetups(setups)))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
     pub async fn clear_comms_secret_key(&self) -> Result<(), WalletStorageError> {
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || db_clone.write(WriteOperation::Remove(DbKey::CommsSecretKey)))
@@ -251,6 +387,10 @@ impl Display for DbKey {
             DbKey::TorId => f.write_str(&"TorId".to_string()),
             DbKey::ClientKey(k) => f.write_str(&format!("ClientKey: {:?}", k)),
             DbKey::BaseNodeChainMetadata => f.write_str(&"Last seen Chain metadata from base node".to_string()),
+            DbKey::ScannedBlocks => f.write_str(&"ScannedBlocks".to_string()),
+            DbKey::SigningProtocols => f.write_str(&"SigningProtocols".to_string()),
+            DbKey::MultiSigOutputs => f.write_str(&"MultiSigOutputs".to_string()),
+            DbKey::MultiSigKeySetups => f.write_str(&"MultiSigKeySetups".to_string()),
         }
     }
 }
@@ -267,6 +407,10 @@ impl Display for DbValue {
             DbValue::TorId(v) => f.write_str(&format!("Tor ID: {}", v)),
             DbValue::Identity(v) => f.write_str(&format!("Node Identity: {}", v)),
             DbValue::BaseNodeChainMetadata(v) => f.write_str(&format!("Last seen Chain metadata from base node:{}", v)),
+            DbValue::ScannedBlocks(v) => f.write_str(&format!("{} scanned blocks", v.len())),
+            DbValue::SigningProtocols(v) => f.write_str(&format!("{} signing protocols", v.len())),
+            DbValue::MultiSigOutputs(v) => f.write_str(&format!("{} multi-signature outputs", v.len())),
+            DbValue::MultiSigKeySetups(v) => f.write_str(&format!("{} multi-signature key setups", v.len())),
         }
     }
 }
@@ -289,15 +433,25 @@ fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, WalletStorageErro
 
 #[cfg(test)]
 mod test {
-    use crate::storage::{
-        database::{WalletBackend, WalletDatabase},
-        memory_db::WalletMemoryDatabase,
-        sqlite_db::WalletSqliteDatabase,
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        multisig_service::service::{MultiSigKeySetupShare, MultiSigOutput, MultiSigOutputStatus},
+        storage::{
+            database::{WalletBackend, WalletDatabase},
+            memory_db::WalletMemoryDatabase,
+            sqlite_db::WalletSqliteDatabase,
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
+        utxo_scanner_service::service::ScannedBlock,
     };
     use rand::rngs::OsRng;
     use tari_comms::types::CommsSecretKey;
-    use tari_crypto::keys::SecretKey;
+    use tari_core::transactions::{
+        tari_amount::MicroTari,
+        transaction::OutputFeatures,
+        transaction_protocol::multisig::{prove_key_share, MultiSigParticipant},
+        types::{Commitment, PrivateKey, PublicKey},
+    };
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
     use tari_test_utils::random::string;
     use tempfile::tempdir;
     use tokio::runtime::Runtime;
@@ -354,6 +508,54 @@ mod test {
         assert!(!runtime
             .block_on(db.clear_client_value(client_key_values[0].0.clone()))
             .unwrap());
+
+        // Test UTXO scanner progress
+        assert!(runtime.block_on(db.get_scanned_blocks()).unwrap().is_empty());
+        let scanned_blocks = vec![
+            ScannedBlock {
+                height: 11,
+                header_hash: vec![11u8; 32],
+                output_mmr_size: 40,
+            },
+            ScannedBlock {
+                height: 10,
+                header_hash: vec![10u8; 32],
+                output_mmr_size: 35,
+            },
+        ];
+        runtime.block_on(db.set_scanned_blocks(scanned_blocks.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_scanned_blocks()).unwrap(), scanned_blocks);
+        runtime.block_on(db.clear_scanned_blocks()).unwrap();
+        assert!(runtime.block_on(db.get_scanned_blocks()).unwrap().is_empty());
+
+        // Test multi-signature key shares
+        assert!(runtime.block_on(db.get_multisig_outputs()).unwrap().is_empty());
+        let (share, share_public_key) = PublicKey::random_keypair(&mut OsRng);
+        let outputs = vec![MultiSigOutput {
+            commitment: Commitment::from_public_key(&PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng))),
+            value: MicroTari::from(1000),
+            features: OutputFeatures::default(),
+            spending_key_share: share,
+            participants: vec![MultiSigParticipant {
+                comms_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+                share_public_key,
+                share_proof: prove_key_share(&share, 5).unwrap(),
+            }],
+            status: MultiSigOutputStatus::Spending(5),
+        }];
+        runtime.block_on(db.set_multisig_outputs(outputs.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_multisig_outputs()).unwrap(), outputs);
+
+        assert!(runtime.block_on(db.get_multisig_key_setups()).unwrap().is_empty());
+        let setups = vec![MultiSigKeySetupShare {
+            setup_id: 5,
+            coordinator: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            value: MicroTari::from(1000),
+            features: OutputFeatures::default(),
+            spending_key_share: PrivateKey::random(&mut OsRng),
+        }];
+        runtime.block_on(db.set_multisig_key_setups(setups.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_multisig_key_setups()).unwrap(), setups);
     }
 
     #[test]
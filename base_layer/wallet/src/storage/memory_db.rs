@@ -22,7 +22,10 @@
 
 use crate::{
     error::WalletStorageError,
+    multisig_service::service::{MultiSigKeySetupShare, MultiSigOutput},
+    output_manager_service::TxId,
     storage::database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
+    utxo_scanner_service::service::ScannedBlock,
 };
 use aes_gcm::Aes256Gcm;
 use std::{
@@ -36,6 +39,7 @@ use tari_comms::{
     types::{CommsPublicKey, CommsSecretKey},
     NodeIdentity,
 };
+use tari_core::transactions::SenderTransactionProtocol;
 use tari_crypto::keys::PublicKey;
 
 #[derive(Default)]
@@ -47,6 +51,10 @@ pub struct InnerDatabase {
     identity: Option<NodeIdentity>,
     tor_id: Option<TorIdentity>,
     chain_metadata: Option<ChainMetadata>,
+    scanned_blocks: Vec<ScannedBlock>,
+    signing_protocols: HashMap<TxId, SenderTransactionProtocol>,
+    multisig_outputs: Vec<MultiSigOutput>,
+    multisig_key_setups: Vec<MultiSigKeySetupShare>,
 }
 
 impl InnerDatabase {
@@ -59,6 +67,10 @@ impl InnerDatabase {
             identity: None,
             tor_id: None,
             chain_metadata: None,
+            scanned_blocks: Vec::new(),
+            signing_protocols: HashMap::new(),
+            multisig_outputs: Vec::new(),
+            multisig_key_setups: Vec::new(),
         }
     }
 }
@@ -97,6 +109,10 @@ impl WalletBackend for WalletMemoryDatabase {
             DbKey::Identity => db.identity.clone().map(DbValue::Identity),
             DbKey::TorId => db.tor_id.clone().map(DbValue::TorId),
             DbKey::BaseNodeChainMetadata => db.chain_metadata.clone().map(DbValue::BaseNodeChainMetadata),
+            DbKey::ScannedBlocks => Some(DbValue::ScannedBlocks(db.scanned_blocks.clone())),
+            DbKey::SigningProtocols => Some(DbValue::SigningProtocols(db.signing_protocols.clone())),
+            DbKey::MultiSigOutputs => Some(DbValue::MultiSigOutputs(db.multisig_outputs.clone())),
+            DbKey::MultiSigKeySetups => Some(DbValue::MultiSigKeySetups(db.multisig_key_setups.clone())),
         };
 
         Ok(result)
@@ -121,6 +137,18 @@ impl WalletBackend for WalletMemoryDatabase {
                 DbKeyValuePair::BaseNodeChainMetadata(metadata) => {
                     db.chain_metadata = Some(metadata);
                 },
+                DbKeyValuePair::ScannedBlocks(blocks) => {
+                    db.scanned_blocks = blocks;
+                },
+                DbKeyValuePair::SigningProtocols(protocols) => {
+                    db.signing_protocols = protocols;
+                },
+                DbKeyValuePair::MultiSigOutputs(outputs) => {
+                    db.multisig_outputs = outputs;
+                },
+                DbKeyValuePair::MultiSigKeySetups(setups) => {
+                    db.multisig_key_setups = setups;
+                },
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::CommsSecretKey => {
@@ -151,6 +179,18 @@ impl WalletBackend for WalletMemoryDatabase {
                 DbKey::TorId => {
                     db.tor_id = None;
                 },
+                DbKey::ScannedBlocks => {
+                    db.scanned_blocks.clear();
+                },
+                DbKey::SigningProtocols => {
+                    db.signing_protocols.clear();
+                },
+                DbKey::MultiSigOutputs => {
+                    db.multisig_outputs.clear();
+                },
+                DbKey::MultiSigKeySetups => {
+                    db.multisig_key_setups.clear();
+                },
             },
         }
 
@@ -22,12 +22,15 @@
 
 use crate::{
     error::WalletStorageError,
+    multisig_service::service::{MultiSigKeySetupShare, MultiSigOutput},
+    output_manager_service::TxId,
     schema::{client_key_values, wallet_settings},
     storage::{
         database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
         sqlite_utilities::WalletDbConnection,
     },
     util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, Encryptable, AES_NONCE_BYTES},
+    utxo_scanner_service::service::ScannedBlock,
 };
 use aes_gcm::{
     aead::{generic_array::GenericArray, Aead},
@@ -37,6 +40,7 @@ use aes_gcm::{
 use diesel::{prelude::*, SqliteConnection};
 use log::*;
 use std::{
+    collections::HashMap,
     str::{from_utf8, FromStr},
     sync::{Arc, RwLock},
 };
@@ -47,6 +51,7 @@ use tari_comms::{
     tor::TorIdentity,
     types::{CommsPublicKey, CommsSecretKey},
 };
+use tari_core::transactions::SenderTransactionProtocol;
 use tari_crypto::{
     keys::PublicKey,
     tari_utilities::{
@@ -324,6 +329,115 @@ impl WalletSqliteDatabase {
             Ok(None)
         }
     }
+
+    fn set_scanned_blocks(&self, blocks: Vec<ScannedBlock>, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        let bytes = bincode::serialize(&blocks).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+        WalletSettingSql::new(DbKey::ScannedBlocks.to_string(), bytes.to_hex()).set(&conn)?;
+        Ok(())
+    }
+
+    fn get_scanned_blocks(&self, conn: &SqliteConnection) -> Result<Vec<ScannedBlock>, WalletStorageError> {
+        if let Some(key_str) = WalletSettingSql::get(DbKey::ScannedBlocks.to_string(), &conn)? {
+            let blocks = bincode::deserialize(&from_hex(&key_str)?)
+                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+            Ok(blocks)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Store a setting that holds spending secrets, encrypting it if a cipher is set
+    fn set_secret_setting(&self, key: DbKey, value: String, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        let cipher = acquire_read_lock!(self.cipher);
+        match cipher.as_ref() {
+            None => {
+                WalletSettingSql::new(key.to_string(), value).set(&conn)?;
+            },
+            Some(cipher) => {
+                let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, value.into_bytes())
+                    .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
+                WalletSettingSql::new(key.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a setting stored with `set_secret_setting`, decrypting it if a cipher is set
+    fn get_secret_setting(&self, key: DbKey, conn: &SqliteConnection) -> Result<Option<String>, WalletStorageError> {
+        let cipher = acquire_read_lock!(self.cipher);
+        match WalletSettingSql::get(key.to_string(), &conn)? {
+            None => Ok(None),
+            Some(value) => match cipher.as_ref() {
+                None => Ok(Some(value)),
+                Some(cipher) => {
+                    let decrypted_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(&value)?)
+                        .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
+                    Ok(Some(
+                        from_utf8(decrypted_bytes.as_slice())
+                            .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?
+                            .to_string(),
+                    ))
+                },
+            },
+        }
+    }
+
+    fn set_signing_protocols(
+        &self,
+        protocols: HashMap<TxId, SenderTransactionProtocol>,
+        conn: &SqliteConnection,
+    ) -> Result<(), WalletStorageError>
+    {
+        self.set_secret_setting(DbKey::SigningProtocols, serde_json::to_string(&protocols)?, conn)
+    }
+
+    fn get_signing_protocols(
+        &self,
+        conn: &SqliteConnection,
+    ) -> Result<HashMap<TxId, SenderTransactionProtocol>, WalletStorageError>
+    {
+        match self.get_secret_setting(DbKey::SigningProtocols, conn)? {
+            Some(protocols_string) => Ok(serde_json::from_str(&protocols_string)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    fn set_multisig_outputs(
+        &self,
+        outputs: Vec<MultiSigOutput>,
+        conn: &SqliteConnection,
+    ) -> Result<(), WalletStorageError>
+    {
+        self.set_secret_setting(DbKey::MultiSigOutputs, serde_json::to_string(&outputs)?, conn)
+    }
+
+    fn get_multisig_outputs(&self, conn: &SqliteConnection) -> Result<Vec<MultiSigOutput>, WalletStorageError> {
+        match self.get_secret_setting(DbKey::MultiSigOutputs, conn)? {
+            Some(outputs_string) => Ok(serde_json::from_str(&outputs_string)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn set_multisig_key_setups(
+        &self,
+        setups: Vec<MultiSigKeySetupShare>,
+        conn: &SqliteConnection,
+    ) -> Result<(), WalletStorageError>
+    {
+        self.set_secret_setting(DbKey::MultiSigKeySetups, serde_json::to_string(&setups)?, conn)
+    }
+
+    fn get_multisig_key_setups(
+        &self,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<MultiSigKeySetupShare>, WalletStorageError>
+    {
+        match self.get_secret_setting(DbKey::MultiSigKeySetups, conn)? {
+            Some(setups_string) => Ok(serde_json::from_str(&setups_string)?),
+            None => Ok(Vec::new()),
+        }
+    }
 }
 
 impl WalletBackend for WalletSqliteDatabase {
@@ -368,6 +482,10 @@ impl WalletBackend for WalletSqliteDatabase {
             DbKey::TorId => self.get_tor_id(&conn)?,
             DbKey::CommsFeatures => self.get_comms_features(&conn)?.map(DbValue::CommsFeatures),
             DbKey::BaseNodeChainMetadata => self.get_chain_metadata(&conn)?.map(DbValue::BaseNodeChainMetadata),
+            DbKey::ScannedBlocks => Some(DbValue::ScannedBlocks(self.get_scanned_blocks(&conn)?)),
+            DbKey::SigningProtocols => Some(DbValue::SigningProtocols(self.get_signing_protocols(&conn)?)),
+            DbKey::MultiSigOutputs => Some(DbValue::MultiSigOutputs(self.get_multisig_outputs(&conn)?)),
+            DbKey::MultiSigKeySetups => Some(DbValue::MultiSigKeySetups(self.get_multisig_key_setups(&conn)?)),
         };
 
         Ok(result)
@@ -389,6 +507,18 @@ impl WalletBackend for WalletSqliteDatabase {
                 DbKeyValuePair::BaseNodeChainMetadata(metadata) => {
                     self.set_chain_metadata(metadata, &(*conn))?;
                 },
+                DbKeyValuePair::ScannedBlocks(blocks) => {
+                    self.set_scanned_blocks(blocks, &(*conn))?;
+                },
+                DbKeyValuePair::SigningProtocols(protocols) => {
+                    self.set_signing_protocols(protocols, &(*conn))?;
+                },
+                DbKeyValuePair::MultiSigOutputs(outputs) => {
+                    self.set_multisig_outputs(outputs, &(*conn))?;
+                },
+                DbKeyValuePair::MultiSigKeySetups(setups) => {
+                    self.set_multisig_key_setups(setups, &(*conn))?;
+                },
                 DbKeyValuePair::ClientKeyValue(k, v) => {
                     // First see if we will overwrite a value so we can return the old value
                     let value_to_return = if let Some(mut found_value) = ClientKeyValueSql::get(&k, &conn)? {
@@ -431,6 +561,18 @@ impl WalletBackend for WalletSqliteDatabase {
                 DbKey::TorId => {
                     let _ = WalletSettingSql::clear(DbKey::TorId.to_string(), &conn)?;
                 },
+                DbKey::ScannedBlocks => {
+                    let _ = WalletSettingSql::clear(DbKey::ScannedBlocks.to_string(), &conn)?;
+                },
+                DbKey::SigningProtocols => {
+                    let _ = WalletSettingSql::clear(DbKey::SigningProtocols.to_string(), &conn)?;
+                },
+                DbKey::MultiSigOutputs => {
+                    let _ = WalletSettingSql::clear(DbKey::MultiSigOutputs.to_string(), &conn)?;
+                },
+                DbKey::MultiSigKeySetups => {
+                    let _ = WalletSettingSql::clear(DbKey::MultiSigKeySetups.to_string(), &conn)?;
+                },
             },
         }
 
@@ -472,6 +614,19 @@ impl WalletBackend for WalletSqliteDatabase {
             WalletSettingSql::new(DbKey::TorId.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
         }
 
+        // Encrypt the signing protocols and multi-signature key shares if present
+        for key in &[
+            DbKey::SigningProtocols,
+            DbKey::MultiSigOutputs,
+            DbKey::MultiSigKeySetups,
+        ] {
+            if let Some(v) = WalletSettingSql::get(key.to_string(), &conn)? {
+                let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, v.into_bytes())
+                    .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
+                WalletSettingSql::new(key.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
+            }
+        }
+
         (*current_cipher) = Some(cipher);
 
         Ok(())
@@ -516,6 +671,22 @@ impl WalletBackend for WalletSqliteDatabase {
             WalletSettingSql::new(DbKey::TorId.to_string(), tor_string).set(&conn)?;
         }
 
+        // Remove the signing protocols and multi-signature key shares encryption if present
+        for key in &[
+            DbKey::SigningProtocols,
+            DbKey::MultiSigOutputs,
+            DbKey::MultiSigKeySetups,
+        ] {
+            if let Some(v) = WalletSettingSql::get(key.to_string(), &conn)? {
+                let decrypted_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(v.as_str())?)
+                    .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
+                let value = from_utf8(decrypted_bytes.as_slice())
+                    .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?
+                    .to_string();
+                WalletSettingSql::new(key.to_string(), value).set(&conn)?;
+            }
+        }
+
         // Now that all the decryption has been completed we can safely remove the cipher fully
         let _ = (*current_cipher).take();
 
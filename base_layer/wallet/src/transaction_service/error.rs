@@ -100,6 +100,12 @@ pub enum TransactionServiceError {
     TransactionStorageError(#[from] TransactionStorageError),
     #[error("Invalid message error: `{0}`")]
     InvalidMessageError(String),
+    #[error("Invalid batch transaction recipients: `{0}`")]
+    InvalidBatchRecipients(String),
+    #[error("Transaction fee cannot be bumped: `{0}`")]
+    InvalidFeeBump(String),
+    #[error("Partially signed transaction cannot be imported: `{0}`")]
+    InvalidPartiallySignedTransaction(String),
     #[cfg(feature = "test_harness")]
     #[error("Test harness error: `{0}`")]
     TestHarnessError(String),
@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    output_manager_service::TxId,
+    output_manager_service::{utxo_selection::UTXOSelectionStrategy, TxId},
     transaction_service::{
         error::TransactionServiceError,
         storage::models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
@@ -31,8 +31,13 @@ use aes_gcm::Aes256Gcm;
 use futures::{stream::Fuse, StreamExt};
 use std::{collections::HashMap, fmt, sync::Arc};
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::{tari_amount::MicroTari, transaction::Transaction};
-use tari_service_framework::reply_channel::SenderService;
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction::Transaction,
+    transaction_protocol::partially_signed::PartiallySignedTransaction,
+    types::Commitment,
+};
+use tari_service_framework::reply_channel::{SenderService, TransportResponseFuture};
 use tokio::sync::broadcast;
 use tower::Service;
 
@@ -52,10 +57,29 @@ pub enum TransactionServiceRequest {
     GetCompletedTransaction(TxId),
     GetAnyTransaction(TxId),
     SetBaseNodePublicKey(CommsPublicKey),
-    SendTransaction((CommsPublicKey, MicroTari, MicroTari, String)),
+    SendTransaction(
+        (
+            CommsPublicKey,
+            MicroTari,
+            MicroTari,
+            String,
+            Option<UTXOSelectionStrategy>,
+        ),
+    ),
+    SendTransactionWithInputs((CommsPublicKey, MicroTari, MicroTari, String, Vec<Commitment>)),
+    SendBatchTransaction(
+        (
+            Vec<(CommsPublicKey, MicroTari)>,
+            MicroTari,
+            String,
+            Option<UTXOSelectionStrategy>,
+        ),
+    ),
     CancelTransaction(TxId),
+    BumpTransactionFee((TxId, MicroTari)),
     ImportUtxo(MicroTari, CommsPublicKey, String),
     SubmitTransaction((TxId, Transaction, MicroTari, MicroTari, String)),
+    ImportPartiallySignedTransaction(Box<PartiallySignedTransaction>),
     SetLowPowerMode,
     SetNormalPowerMode,
     ApplyEncryption(Box<Aes256Gcm>),
@@ -66,6 +90,8 @@ pub enum TransactionServiceRequest {
     GetNumConfirmationsRequired,
     SetNumConfirmationsRequired(u64),
     ValidateTransactions(ValidationRetryStrategy),
+    /// A request whose transaction is funded from the named account instead of the active account
+    WithAccount((String, Box<TransactionServiceRequest>)),
     #[cfg(feature = "test_harness")]
     CompletePendingOutboundTransaction(CompletedTransaction),
     #[cfg(feature = "test_harness")]
@@ -89,12 +115,31 @@ impl fmt::Display for TransactionServiceRequest {
             Self::GetCancelledCompletedTransactions => f.write_str("GetCancelledCompletedTransactions"),
             Self::GetCompletedTransaction(t) => f.write_str(&format!("GetCompletedTransaction({})", t)),
             Self::SetBaseNodePublicKey(k) => f.write_str(&format!("SetBaseNodePublicKey ({})", k)),
-            Self::SendTransaction((k, v, _, msg)) => {
+            Self::SendTransaction((k, v, _, msg, _)) => {
                 f.write_str(&format!("SendTransaction (to {}, {}, {})", k, v, msg))
             },
+            Self::SendTransactionWithInputs((k, v, _, msg, inputs)) => f.write_str(&format!(
+                "SendTransactionWithInputs (to {}, {}, {} inputs, {})",
+                k,
+                v,
+                inputs.len(),
+                msg
+            )),
+            Self::SendBatchTransaction((recipients, _, msg, _)) => f.write_str(&format!(
+                "SendBatchTransaction (to {} recipients, {})",
+                recipients.len(),
+                msg
+            )),
             Self::CancelTransaction(t) => f.write_str(&format!("CancelTransaction ({})", t)),
+            Self::BumpTransactionFee((t, fee_per_gram)) => {
+                f.write_str(&format!("BumpTransactionFee ({}, {})", t, fee_per_gram))
+            },
             Self::ImportUtxo(v, k, msg) => f.write_str(&format!("ImportUtxo (from {}, {}, {})", k, v, msg)),
             Self::SubmitTransaction((id, _, _, _, _)) => f.write_str(&format!("SubmitTransaction ({})", id)),
+            Self::ImportPartiallySignedTransaction(pst) => f.write_str(&format!(
+                "ImportPartiallySignedTransaction ({}, {})",
+                pst.tx_id, pst.stage
+            )),
             Self::SetLowPowerMode => f.write_str("SetLowPowerMode "),
             Self::SetNormalPowerMode => f.write_str("SetNormalPowerMode"),
             Self::ApplyEncryption(_) => f.write_str("ApplyEncryption"),
@@ -122,6 +167,7 @@ impl fmt::Display for TransactionServiceRequest {
             Self::BroadcastTransaction(id) => f.write_str(&format!("BroadcastTransaction ({})", id)),
             Self::GetAnyTransaction(t) => f.write_str(&format!("GetAnyTransaction({})", t)),
             TransactionServiceRequest::ValidateTransactions(t) => f.write_str(&format!("ValidateTransaction({:?})", t)),
+            Self::WithAccount((account, request)) => write!(f, "{} (Account: {})", request, account),
         }
     }
 }
@@ -131,6 +177,7 @@ impl fmt::Display for TransactionServiceRequest {
 pub enum TransactionServiceResponse {
     TransactionSent(TxId),
     TransactionCancelled,
+    TransactionFeeBumped(MicroTari),
     PendingInboundTransactions(HashMap<u64, InboundTransaction>),
     PendingOutboundTransactions(HashMap<u64, OutboundTransaction>),
     CompletedTransactions(HashMap<u64, CompletedTransaction>),
@@ -138,6 +185,7 @@ pub enum TransactionServiceResponse {
     BaseNodePublicKeySet,
     UtxoImported(TxId),
     TransactionSubmitted,
+    PartiallySignedTransactionImported(Box<Option<PartiallySignedTransaction>>),
     LowPowerModeSet,
     NormalPowerModeSet,
     EncryptionApplied,
@@ -172,6 +220,7 @@ pub enum TransactionEvent {
     TransactionCompletedImmediately(TxId),
     TransactionStoreForwardSendResult(TxId, bool),
     TransactionCancelled(TxId),
+    TransactionFeeBumped(TxId),
     TransactionBroadcast(TxId),
     TransactionMined(TxId),
     TransactionMinedRequestTimedOut(TxId),
@@ -193,6 +242,7 @@ pub type TransactionEventReceiver = broadcast::Receiver<Arc<TransactionEvent>>;
 pub struct TransactionServiceHandle {
     handle: SenderService<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     event_stream_sender: TransactionEventSender,
+    account: Option<String>,
 }
 
 impl TransactionServiceHandle {
@@ -204,9 +254,31 @@ impl TransactionServiceHandle {
         Self {
             handle,
             event_stream_sender,
+            account: None,
+        }
+    }
+
+    /// Returns a handle whose transactions are funded from the named account instead of the active account. The
+    /// account is sent with every request so handles for different accounts can be used concurrently.
+    pub fn with_account(&self, account: String) -> Self {
+        Self {
+            account: Some(account),
+            ..self.clone()
         }
     }
 
+    fn call(
+        &mut self,
+        request: TransactionServiceRequest,
+    ) -> TransportResponseFuture<Result<TransactionServiceResponse, TransactionServiceError>>
+    {
+        let request = match self.account.clone() {
+            Some(account) => TransactionServiceRequest::WithAccount((account, Box::new(request))),
+            None => request,
+        };
+        self.handle.call(request)
+    }
+
     pub fn get_event_stream_fused(&self) -> Fuse<TransactionEventReceiver> {
         self.event_stream_sender.subscribe().fuse()
     }
@@ -218,14 +290,29 @@ impl TransactionServiceHandle {
         fee_per_gram: MicroTari,
         message: String,
     ) -> Result<TxId, TransactionServiceError>
+    {
+        self.send_transaction_with_utxo_selection(dest_pubkey, amount, fee_per_gram, message, None)
+            .await
+    }
+
+    /// As `send_transaction` but overrides the output manager's configured UTXO selector with the specified selection
+    /// strategy
+    pub async fn send_transaction_with_utxo_selection(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+    ) -> Result<TxId, TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::SendTransaction((
                 dest_pubkey,
                 amount,
                 fee_per_gram,
                 message,
+                utxo_selection_strategy,
             )))
             .await??
         {
@@ -234,22 +321,85 @@ impl TransactionServiceHandle {
         }
     }
 
-    pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
+    /// As `send_transaction` but spends exactly the unspent outputs with the given commitments instead of letting the
+    /// output manager select them
+    pub async fn send_transaction_with_inputs(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        inputs: Vec<Commitment>,
+    ) -> Result<TxId, TransactionServiceError>
+    {
         match self
-            .handle
-            .call(TransactionServiceRequest::CancelTransaction(tx_id))
+            .call(TransactionServiceRequest::SendTransactionWithInputs((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                inputs,
+            )))
             .await??
         {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Send a single transaction that pays each of the given recipients. The transaction is negotiated with all the
+    /// recipients at once and is tracked under the returned `TxId`.
+    pub async fn send_batch_transaction(
+        &mut self,
+        recipients: Vec<(CommsPublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .call(TransactionServiceRequest::SendBatchTransaction((
+                recipients,
+                fee_per_gram,
+                message,
+                utxo_selection_strategy,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
+        match self.call(TransactionServiceRequest::CancelTransaction(tx_id)).await?? {
             TransactionServiceResponse::TransactionCancelled => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
 
+    /// Replace a broadcast outbound transaction with one that spends the same inputs, plus additional inputs if
+    /// required, at the specified fee per gram. The replacement is tracked under the original `TxId`. Returns the new
+    /// total fee of the transaction.
+    pub async fn bump_transaction_fee(
+        &mut self,
+        tx_id: TxId,
+        fee_per_gram: MicroTari,
+    ) -> Result<MicroTari, TransactionServiceError>
+    {
+        match self
+            .call(TransactionServiceRequest::BumpTransactionFee((tx_id, fee_per_gram)))
+            .await??
+        {
+            TransactionServiceResponse::TransactionFeeBumped(fee) => Ok(fee),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_pending_inbound_transactions(
         &mut self,
     ) -> Result<HashMap<u64, InboundTransaction>, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetPendingInboundTransactions)
             .await??
         {
@@ -262,7 +412,6 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<u64, InboundTransaction>, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetCancelledPendingInboundTransactions)
             .await??
         {
@@ -275,7 +424,6 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<u64, OutboundTransaction>, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetPendingOutboundTransactions)
             .await??
         {
@@ -288,7 +436,6 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<u64, OutboundTransaction>, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetCancelledPendingOutboundTransactions)
             .await??
         {
@@ -300,11 +447,7 @@ impl TransactionServiceHandle {
     pub async fn get_completed_transactions(
         &mut self,
     ) -> Result<HashMap<u64, CompletedTransaction>, TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::GetCompletedTransactions)
-            .await??
-        {
+        match self.call(TransactionServiceRequest::GetCompletedTransactions).await?? {
             TransactionServiceResponse::CompletedTransactions(c) => Ok(c),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -314,7 +457,6 @@ impl TransactionServiceHandle {
         &mut self,
     ) -> Result<HashMap<u64, CompletedTransaction>, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetCancelledCompletedTransactions)
             .await??
         {
@@ -329,7 +471,6 @@ impl TransactionServiceHandle {
     ) -> Result<CompletedTransaction, TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::GetCompletedTransaction(tx_id))
             .await??
         {
@@ -343,11 +484,7 @@ impl TransactionServiceHandle {
         tx_id: TxId,
     ) -> Result<Option<WalletTransaction>, TransactionServiceError>
     {
-        match self
-            .handle
-            .call(TransactionServiceRequest::GetAnyTransaction(tx_id))
-            .await??
-        {
+        match self.call(TransactionServiceRequest::GetAnyTransaction(tx_id)).await?? {
             TransactionServiceResponse::AnyTransaction(t) => Ok(*t),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -359,7 +496,6 @@ impl TransactionServiceHandle {
     ) -> Result<(), TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::SetBaseNodePublicKey(public_key))
             .await??
         {
@@ -376,7 +512,6 @@ impl TransactionServiceHandle {
     ) -> Result<TxId, TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::ImportUtxo(
                 amount,
                 source_public_key,
@@ -399,7 +534,6 @@ impl TransactionServiceHandle {
     ) -> Result<(), TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::SubmitTransaction((
                 tx_id, tx, fee, amount, message,
             )))
@@ -410,19 +544,35 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Import a partially signed transaction that was exported by another wallet. A sender message is accepted as the
+    /// recipient and the recipient signed stage is returned for the sender to finalize. A finalized transaction is
+    /// either recorded and broadcast, when this wallet prepared it, or completes the matching pending inbound
+    /// transaction.
+    pub async fn import_partially_signed_transaction(
+        &mut self,
+        pst: PartiallySignedTransaction,
+    ) -> Result<Option<PartiallySignedTransaction>, TransactionServiceError>
+    {
+        match self
+            .call(TransactionServiceRequest::ImportPartiallySignedTransaction(Box::new(
+                pst,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::PartiallySignedTransactionImported(next_stage) => Ok(*next_stage),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn set_low_power_mode(&mut self) -> Result<(), TransactionServiceError> {
-        match self.handle.call(TransactionServiceRequest::SetLowPowerMode).await?? {
+        match self.call(TransactionServiceRequest::SetLowPowerMode).await?? {
             TransactionServiceResponse::LowPowerModeSet => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
 
     pub async fn set_normal_power_mode(&mut self) -> Result<(), TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::SetNormalPowerMode)
-            .await??
-        {
+        match self.call(TransactionServiceRequest::SetNormalPowerMode).await?? {
             TransactionServiceResponse::NormalPowerModeSet => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -430,7 +580,6 @@ impl TransactionServiceHandle {
 
     pub async fn apply_encryption(&mut self, cipher: Aes256Gcm) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::ApplyEncryption(Box::new(cipher)))
             .await??
         {
@@ -440,7 +589,7 @@ impl TransactionServiceHandle {
     }
 
     pub async fn remove_encryption(&mut self) -> Result<(), TransactionServiceError> {
-        match self.handle.call(TransactionServiceRequest::RemoveEncryption).await?? {
+        match self.call(TransactionServiceRequest::RemoveEncryption).await?? {
             TransactionServiceResponse::EncryptionRemoved => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
@@ -448,7 +597,6 @@ impl TransactionServiceHandle {
 
     pub async fn get_num_confirmations_required(&mut self) -> Result<u64, TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::GetNumConfirmationsRequired)
             .await??
         {
@@ -459,7 +607,6 @@ impl TransactionServiceHandle {
 
     pub async fn set_num_confirmations_required(&mut self, number: u64) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::SetNumConfirmationsRequired(number))
             .await??
         {
@@ -476,7 +623,6 @@ impl TransactionServiceHandle {
     ) -> Result<Transaction, TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::GenerateCoinbaseTransaction(
                 rewards,
                 fees,
@@ -491,7 +637,6 @@ impl TransactionServiceHandle {
 
     pub async fn restart_transaction_protocols(&mut self) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::RestartTransactionProtocols)
             .await??
         {
@@ -502,7 +647,6 @@ impl TransactionServiceHandle {
 
     pub async fn restart_broadcast_protocols(&mut self) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::RestartBroadcastProtocols)
             .await??
         {
@@ -517,7 +661,6 @@ impl TransactionServiceHandle {
     ) -> Result<u64, TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::ValidateTransactions(retry_strategy))
             .await??
         {
@@ -533,7 +676,6 @@ impl TransactionServiceHandle {
     ) -> Result<(), TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::CompletePendingOutboundTransaction(
                 completed_tx,
             ))
@@ -554,7 +696,6 @@ impl TransactionServiceHandle {
     ) -> Result<(), TransactionServiceError>
     {
         match self
-            .handle
             .call(TransactionServiceRequest::AcceptTestTransaction((
                 tx_id,
                 amount,
@@ -571,7 +712,6 @@ impl TransactionServiceHandle {
     #[cfg(feature = "test_harness")]
     pub async fn test_finalize_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::FinalizePendingInboundTransaction(tx_id))
             .await??
         {
@@ -583,7 +723,6 @@ impl TransactionServiceHandle {
     #[cfg(feature = "test_harness")]
     pub async fn test_broadcast_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         match self
-            .handle
             .call(TransactionServiceRequest::BroadcastTransaction(tx_id))
             .await??
         {
@@ -594,11 +733,7 @@ impl TransactionServiceHandle {
 
     #[cfg(feature = "test_harness")]
     pub async fn test_mine_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
-        match self
-            .handle
-            .call(TransactionServiceRequest::MineTransaction(tx_id))
-            .await??
-        {
+        match self.call(TransactionServiceRequest::MineTransaction(tx_id)).await?? {
             TransactionServiceResponse::TransactionMined => Ok(()),
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
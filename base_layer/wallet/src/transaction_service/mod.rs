@@ -158,6 +158,34 @@ where T: TransactionBackend
             .map(map_decode::<proto::TransactionCancelledMessage>)
             .filter_map(ok_or_skip_result)
     }
+
+    fn transaction_public_keys_reply_stream(&self) -> impl Stream<Item = DomainMessage<proto::RecipientPublicKeys>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::ReceiverPublicKeysReply
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::ReceiverPublicKeysReply, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::RecipientPublicKeys>)
+            .filter_map(ok_or_skip_result)
+    }
+
+    fn transaction_signature_request_stream(
+        &self,
+    ) -> impl Stream<Item = DomainMessage<proto::MultiRoundSignatureRequest>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::SenderSignatureRequest
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::SenderSignatureRequest, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::MultiRoundSignatureRequest>)
+            .filter_map(ok_or_skip_result)
+    }
 }
 
 impl<T> ServiceInitializer for TransactionServiceInitializer<T>
@@ -172,6 +200,8 @@ where T: TransactionBackend + 'static
         let transaction_finalized_stream = self.transaction_finalized_stream();
         let base_node_response_stream = self.base_node_response_stream();
         let transaction_cancelled_stream = self.transaction_cancelled_stream();
+        let transaction_public_keys_reply_stream = self.transaction_public_keys_reply_stream();
+        let transaction_signature_request_stream = self.transaction_signature_request_stream();
 
         let (publisher, _) = broadcast::channel(200);
 
@@ -203,6 +233,8 @@ where T: TransactionBackend + 'static
                 transaction_finalized_stream,
                 base_node_response_stream,
                 transaction_cancelled_stream,
+                transaction_public_keys_reply_stream,
+                transaction_signature_request_stream,
                 output_manager_service,
                 outbound_message_service,
                 connectivity_manager,
@@ -20,6 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+pub mod transaction_batch_send_protocol;
 pub mod transaction_broadcast_protocol;
 pub mod transaction_coinbase_monitoring_protocol;
 pub mod transaction_receive_protocol;
@@ -0,0 +1,803 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use futures::{
+    channel::{mpsc::Receiver, oneshot},
+    FutureExt,
+    StreamExt,
+};
+use log::*;
+
+use crate::{
+    output_manager_service::TxId,
+    transaction_service::{
+        config::TransactionRoutingMechanism,
+        error::{TransactionServiceError, TransactionServiceProtocolError},
+        handle::TransactionEvent,
+        service::TransactionServiceResources,
+        storage::{
+            database::TransactionBackend,
+            models::{
+                BatchRecipient,
+                BatchRecipientStatus,
+                CompletedTransaction,
+                OutboundTransaction,
+                TransactionDirection,
+                TransactionStatus,
+            },
+        },
+        tasks::{
+            send_finalized_transaction::send_finalized_transaction_message,
+            send_transaction_cancelled::send_transaction_cancelled_message,
+            wait_on_dial::wait_on_dial,
+        },
+    },
+};
+use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
+use tari_comms_dht::{
+    domain_message::OutboundDomainMessage,
+    outbound::{OutboundEncryption, SendMessageResponse},
+};
+use tari_core::transactions::{
+    transaction::KernelFeatures,
+    transaction_protocol::{
+        proto,
+        recipient::{RecipientPublicKeys, RecipientSignedMessage},
+    },
+    SenderTransactionProtocol,
+};
+use tari_p2p::tari_message::TariMessageType;
+use tokio::time::delay_for;
+
+const LOG_TARGET: &str = "wallet::transaction_service::protocols::batch_send_protocol";
+
+#[derive(Debug, PartialEq)]
+pub enum TransactionBatchSendProtocolStage {
+    Initial,
+    WaitForReplies,
+}
+
+/// The Transaction Batch Send Protocol negotiates a single transaction with multiple recipients using the multi-round
+/// transaction protocol. Each recipient is sent its own `tx_id` and amount, replies with its public keys, is then sent
+/// the aggregated public nonce and excess and finally replies with its partial signature. The progress of every
+/// recipient is persisted with the pending outbound transaction so that the protocol can be restarted.
+pub struct TransactionBatchSendProtocol<TBackend>
+where TBackend: TransactionBackend + 'static
+{
+    id: TxId,
+    recipients: Vec<BatchRecipient>,
+    message: String,
+    sender_protocol: SenderTransactionProtocol,
+    stage: TransactionBatchSendProtocolStage,
+    resources: TransactionServiceResources<TBackend>,
+    public_keys_receiver: Option<Receiver<(CommsPublicKey, RecipientPublicKeys)>>,
+    transaction_reply_receiver: Option<Receiver<(CommsPublicKey, RecipientSignedMessage)>>,
+    cancellation_receiver: Option<oneshot::Receiver<()>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+impl<TBackend> TransactionBatchSendProtocol<TBackend>
+where TBackend: TransactionBackend + 'static
+{
+    pub fn new(
+        id: TxId,
+        resources: TransactionServiceResources<TBackend>,
+        public_keys_receiver: Receiver<(CommsPublicKey, RecipientPublicKeys)>,
+        transaction_reply_receiver: Receiver<(CommsPublicKey, RecipientSignedMessage)>,
+        cancellation_receiver: oneshot::Receiver<()>,
+        recipients: Vec<BatchRecipient>,
+        message: String,
+        sender_protocol: SenderTransactionProtocol,
+        stage: TransactionBatchSendProtocolStage,
+    ) -> Self
+    {
+        Self {
+            id,
+            recipients,
+            message,
+            sender_protocol,
+            stage,
+            resources,
+            public_keys_receiver: Some(public_keys_receiver),
+            transaction_reply_receiver: Some(transaction_reply_receiver),
+            cancellation_receiver: Some(cancellation_receiver),
+        }
+    }
+
+    /// Execute the Transaction Batch Send Protocol as an async task.
+    pub async fn execute(mut self) -> Result<u64, TransactionServiceProtocolError> {
+        info!(
+            target: LOG_TARGET,
+            "Starting Transaction Batch Send protocol for TxId: {} with {} recipients at Stage {:?}",
+            self.id,
+            self.recipients.len(),
+            self.stage
+        );
+
+        match self.stage {
+            TransactionBatchSendProtocolStage::Initial => {
+                self.initial_send_transaction().await?;
+                self.wait_for_replies().await?;
+            },
+            TransactionBatchSendProtocolStage::WaitForReplies => {
+                self.wait_for_replies().await?;
+            },
+        }
+
+        Ok(self.id)
+    }
+
+    async fn initial_send_transaction(&mut self) -> Result<(), TransactionServiceProtocolError> {
+        if !self.sender_protocol.is_multi_round_message_ready() {
+            error!(target: LOG_TARGET, "Sender Transaction Protocol is in an invalid state");
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::InvalidStateError,
+            ));
+        }
+
+        let messages = self
+            .sender_protocol
+            .build_multi_round_messages()
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        if messages.len() != self.recipients.len() ||
+            messages
+                .iter()
+                .zip(self.recipients.iter())
+                .any(|(m, r)| m.tx_id != r.tx_id)
+        {
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::InvalidStateError,
+            ));
+        }
+
+        let mut direct_send_result = true;
+        let mut store_and_forward_send_result = true;
+        let mut sent_to = Vec::new();
+        for (msg, recipient) in messages.into_iter().zip(self.recipients.iter()) {
+            let result = self
+                .send_message(
+                    recipient.tx_id,
+                    &recipient.public_key,
+                    TariMessageType::SenderPartialTransaction,
+                    proto::TransactionSenderMessage::multiple(msg.into()),
+                )
+                .await?;
+            direct_send_result &= result.direct_send_result;
+            store_and_forward_send_result &= result.store_and_forward_send_result;
+            if result.direct_send_result || result.store_and_forward_send_result {
+                sent_to.push(recipient.clone());
+            }
+        }
+
+        if sent_to.len() == self.recipients.len() {
+            self.resources
+                .output_manager_service
+                .confirm_pending_transaction(self.id)
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+            let fee = self
+                .sender_protocol
+                .get_fee_amount()
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            let amount = self
+                .sender_protocol
+                .get_total_amount()
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            let mut outbound_tx = OutboundTransaction::new(
+                self.id,
+                self.recipients[0].public_key.clone(),
+                amount,
+                fee,
+                self.sender_protocol.clone(),
+                TransactionStatus::Pending,
+                self.message.clone(),
+                Utc::now().naive_utc(),
+                direct_send_result,
+            );
+            outbound_tx.batch_recipients = self.recipients.clone();
+            info!(
+                target: LOG_TARGET,
+                "Pending Outbound Batch Transaction TxId: {:?} added. Waiting for Replies or Cancellation", self.id,
+            );
+            self.resources
+                .db
+                .add_pending_outbound_transaction(outbound_tx.tx_id, outbound_tx)
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+            self.resources
+                .db
+                .increment_send_count(self.id)
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        }
+
+        let _ = self
+            .resources
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionDirectSendResult(
+                self.id,
+                direct_send_result,
+            )));
+        let _ = self
+            .resources
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionStoreForwardSendResult(
+                self.id,
+                store_and_forward_send_result,
+            )));
+
+        if sent_to.len() != self.recipients.len() {
+            error!(
+                target: LOG_TARGET,
+                "Failed to Send Batch Transaction (TxId: {}) to all recipients either Directly or via Store and \
+                 Forward. Pending Transaction will be cancelled",
+                self.id
+            );
+            for recipient in sent_to {
+                self.send_cancellation(&recipient).await;
+            }
+            if let Err(e) = self.resources.output_manager_service.cancel_transaction(self.id).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to Cancel TX_ID: {} after failed sending attempt with error {:?}", self.id, e
+                );
+            };
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::OutboundSendFailure,
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn wait_for_replies(&mut self) -> Result<(), TransactionServiceProtocolError> {
+        let tx_id = self.id;
+        let mut public_keys_receiver = self
+            .public_keys_receiver
+            .take()
+            .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?;
+
+        let mut reply_receiver = self
+            .transaction_reply_receiver
+            .take()
+            .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?;
+
+        let mut cancellation_receiver = self
+            .cancellation_receiver
+            .take()
+            .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?
+            .fuse();
+
+        let mut outbound_tx = self
+            .resources
+            .db
+            .get_pending_outbound_transaction(tx_id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        if !outbound_tx.is_batch() ||
+            !(outbound_tx.sender_protocol.is_collecting_public_keys() ||
+                outbound_tx.sender_protocol.is_collecting_signatures())
+        {
+            error!(target: LOG_TARGET, "Pending Batch Transaction not in correct state");
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::InvalidStateError,
+            ));
+        }
+        self.recipients = outbound_tx.batch_recipients.clone();
+
+        // Determine the time remaining before this transaction times out
+        let elapsed_time = Utc::now()
+            .naive_utc()
+            .signed_duration_since(outbound_tx.timestamp)
+            .to_std()
+            .map_err(|_| {
+                TransactionServiceProtocolError::new(
+                    self.id,
+                    TransactionServiceError::ConversionError("duration::OutOfRangeError".to_string()),
+                )
+            })?;
+
+        let timeout_duration = match self
+            .resources
+            .config
+            .pending_transaction_cancellation_timeout
+            .checked_sub(elapsed_time)
+        {
+            None => {
+                // This will cancel the transaction and exit this protocol
+                return self.timeout_transaction().await;
+            },
+            Some(t) => t,
+        };
+        let mut timeout_delay = delay_for(timeout_duration).fuse();
+
+        // check to see if a resend is due
+        let resend = match outbound_tx.last_send_timestamp {
+            None => true,
+            Some(timestamp) => {
+                let elapsed_time = Utc::now()
+                    .naive_utc()
+                    .signed_duration_since(timestamp)
+                    .to_std()
+                    .map_err(|_| {
+                        TransactionServiceProtocolError::new(
+                            self.id,
+                            TransactionServiceError::ConversionError("duration::OutOfRangeError".to_string()),
+                        )
+                    })?;
+                elapsed_time > self.resources.config.transaction_resend_period
+            },
+        };
+
+        if resend {
+            self.resend_pending_messages(&outbound_tx.sender_protocol).await?;
+        }
+
+        let mut shutdown = self.resources.shutdown_signal.clone();
+        loop {
+            let mut resend_timeout = delay_for(self.resources.config.transaction_resend_period).fuse();
+            futures::select! {
+                (spk, keys) = public_keys_receiver.select_next_some() => {
+                    let index = match self.find_recipient(&spk, keys.tx_id, BatchRecipientStatus::AwaitingPublicKeys) {
+                        Some(i) => i,
+                        None => continue,
+                    };
+                    if let Err(e) = outbound_tx.sender_protocol.add_multi_recipient_public_keys(keys) {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Public keys from recipient (TxId: {}) of Batch Transaction (TxId: {}) were rejected: {:?}",
+                            self.recipients[index].tx_id,
+                            self.id,
+                            e
+                        );
+                        continue;
+                    }
+                    self.recipients[index].status = BatchRecipientStatus::AwaitingSignature;
+                    self.update_pending_transaction(&outbound_tx.sender_protocol).await?;
+
+                    if outbound_tx.sender_protocol.is_collecting_signatures() {
+                        info!(
+                            target: LOG_TARGET,
+                            "All public keys for Batch Transaction (TxId: {}) received, requesting signatures", self.id
+                        );
+                        self.resend_pending_messages(&outbound_tx.sender_protocol).await?;
+                    }
+                },
+                (spk, rr) = reply_receiver.select_next_some() => {
+                    let index = match self.find_recipient(&spk, rr.tx_id, BatchRecipientStatus::AwaitingSignature) {
+                        Some(i) => i,
+                        None => continue,
+                    };
+                    if let Err(e) = outbound_tx.sender_protocol.add_multi_recipient_signature(rr, &self.resources.factories) {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Signature from recipient (TxId: {}) of Batch Transaction (TxId: {}) was rejected: {:?}",
+                            self.recipients[index].tx_id,
+                            self.id,
+                            e
+                        );
+                        continue;
+                    }
+                    self.recipients[index].status = BatchRecipientStatus::Signed;
+                    self.update_pending_transaction(&outbound_tx.sender_protocol).await?;
+
+                    if outbound_tx.sender_protocol.is_finalizing() {
+                        break;
+                    }
+                },
+                result = cancellation_receiver => {
+                    if result.is_ok() {
+                        info!(target: LOG_TARGET, "Cancelling Transaction Batch Send Protocol (TxId: {})", self.id);
+                        for recipient in self.recipients.clone() {
+                            self.send_cancellation(&recipient).await;
+                        }
+                        self.resources
+                            .db
+                            .increment_send_count(self.id)
+                            .await
+                            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+                        return Err(TransactionServiceProtocolError::new(
+                            self.id,
+                            TransactionServiceError::TransactionCancelled,
+                        ));
+                    }
+                },
+                () = resend_timeout => {
+                    self.resend_pending_messages(&outbound_tx.sender_protocol).await?;
+                },
+                () = timeout_delay => {
+                    return self.timeout_transaction().await;
+                }
+                _ = shutdown => {
+                    info!(target: LOG_TARGET, "Transaction Batch Send Protocol (id: {}) shutting down because it received the shutdown signal", self.id);
+                    return Err(TransactionServiceProtocolError::new(self.id, TransactionServiceError::Shutdown))
+                }
+            }
+        }
+
+        outbound_tx
+            .sender_protocol
+            .finalize(KernelFeatures::empty(), &self.resources.factories)
+            .map_err(|e| {
+                error!(
+                    target: LOG_TARGET,
+                    "Batch Transaction (TxId: {}) could not be finalized. Failure error: {:?}", self.id, e,
+                );
+                TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e))
+            })?;
+
+        let tx = outbound_tx
+            .sender_protocol
+            .get_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        let completed_transaction = CompletedTransaction::new(
+            tx_id,
+            self.resources.node_identity.public_key().clone(),
+            outbound_tx.destination_public_key.clone(),
+            outbound_tx.amount,
+            outbound_tx.fee,
+            tx.clone(),
+            TransactionStatus::Completed,
+            outbound_tx.message.clone(),
+            Utc::now().naive_utc(),
+            TransactionDirection::Outbound,
+            None,
+        );
+
+        self.resources
+            .db
+            .complete_outbound_transaction(tx_id, completed_transaction.clone())
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        info!(
+            target: LOG_TARGET,
+            "All Transaction Recipient Replies for Batch TX_ID = {} received", tx_id,
+        );
+
+        for recipient in self.recipients.iter() {
+            send_finalized_transaction_message(
+                recipient.tx_id,
+                tx.clone(),
+                recipient.public_key.clone(),
+                self.resources.outbound_message_service.clone(),
+                self.resources.config.direct_send_timeout,
+                self.resources.config.transaction_routing_mechanism,
+            )
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
+        }
+
+        self.resources
+            .db
+            .increment_send_count(tx_id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        let _ = self
+            .resources
+            .event_publisher
+            .send(Arc::new(TransactionEvent::ReceivedTransactionReply(tx_id)))
+            .map_err(|e| {
+                trace!(
+                    target: LOG_TARGET,
+                    "Error sending event, usually because there are no subscribers: {:?}",
+                    e
+                );
+                e
+            });
+
+        Ok(())
+    }
+
+    /// Find the index of the recipient a reply belongs to, provided that it came from the expected public key and
+    /// that the recipient is at the stage of the negotiation where this reply is expected
+    fn find_recipient(
+        &self,
+        source_pubkey: &CommsPublicKey,
+        tx_id: TxId,
+        expected_status: BatchRecipientStatus,
+    ) -> Option<usize>
+    {
+        let index = match self.recipients.iter().position(|r| r.tx_id == tx_id) {
+            None => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Reply for Batch Transaction (TxId: {}) does not have the correct TxId", self.id
+                );
+                return None;
+            },
+            Some(i) => i,
+        };
+        let recipient = &self.recipients[index];
+        if &recipient.public_key != source_pubkey {
+            warn!(
+                target: LOG_TARGET,
+                "Reply for Batch Transaction (TxId: {}) did not come from the expected Public Key", self.id
+            );
+            return None;
+        }
+        if recipient.status != expected_status {
+            debug!(
+                target: LOG_TARGET,
+                "Repeated reply from recipient (TxId: {}) of Batch Transaction (TxId: {}) with status {} ignored",
+                recipient.tx_id,
+                self.id,
+                recipient.status
+            );
+            return None;
+        }
+        Some(index)
+    }
+
+    async fn update_pending_transaction(
+        &self,
+        sender_protocol: &SenderTransactionProtocol,
+    ) -> Result<(), TransactionServiceProtocolError>
+    {
+        self.resources
+            .db
+            .update_pending_batch_transaction(self.id, sender_protocol.clone(), self.recipients.clone())
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))
+    }
+
+    /// Resend the message for the current round to every recipient that has not yet replied to it
+    async fn resend_pending_messages(
+        &mut self,
+        sender_protocol: &SenderTransactionProtocol,
+    ) -> Result<(), TransactionServiceProtocolError>
+    {
+        if sender_protocol.is_collecting_public_keys() {
+            let messages = sender_protocol
+                .get_multi_round_messages()
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            for (msg, recipient) in messages.into_iter().zip(self.recipients.clone()) {
+                if recipient.status != BatchRecipientStatus::AwaitingPublicKeys {
+                    continue;
+                }
+                self.resend_message(
+                    &recipient,
+                    TariMessageType::SenderPartialTransaction,
+                    proto::TransactionSenderMessage::multiple(msg.into()),
+                )
+                .await;
+            }
+        } else if sender_protocol.is_collecting_signatures() {
+            let requests = sender_protocol
+                .get_multi_round_signature_requests()
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            for (request, recipient) in requests.into_iter().zip(self.recipients.clone()) {
+                if recipient.status != BatchRecipientStatus::AwaitingSignature {
+                    continue;
+                }
+                let proto_message: proto::MultiRoundSignatureRequest = request.into();
+                self.resend_message(&recipient, TariMessageType::SenderSignatureRequest, proto_message)
+                    .await;
+            }
+        }
+
+        self.resources
+            .db
+            .increment_send_count(self.id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))
+    }
+
+    async fn resend_message<T: prost::Message + Clone>(
+        &mut self,
+        recipient: &BatchRecipient,
+        message_type: TariMessageType,
+        msg: T,
+    )
+    {
+        if let Err(e) = self
+            .send_message(recipient.tx_id, &recipient.public_key, message_type, msg)
+            .await
+        {
+            warn!(
+                target: LOG_TARGET,
+                "Error resending to recipient (TxId: {}) of Batch Transaction (TxId: {}): {:?}",
+                recipient.tx_id,
+                self.id,
+                e
+            );
+        }
+    }
+
+    async fn send_cancellation(&self, recipient: &BatchRecipient) {
+        let _ = send_transaction_cancelled_message(
+            recipient.tx_id,
+            recipient.public_key.clone(),
+            self.resources.outbound_message_service.clone(),
+        )
+        .await
+        .map_err(|e| {
+            warn!(
+                target: LOG_TARGET,
+                "Error sending Transaction Cancelled (TxId: {}) message: {:?}", recipient.tx_id, e
+            )
+        });
+    }
+
+    /// Attempt to send a message to one of the recipients either directly, via Store-and-forward or both as per config
+    /// setting.
+    async fn send_message<T: prost::Message + Clone>(
+        &mut self,
+        tx_id: TxId,
+        dest_pubkey: &CommsPublicKey,
+        message_type: TariMessageType,
+        msg: T,
+    ) -> Result<SendResult, TransactionServiceProtocolError>
+    {
+        let mut result = SendResult {
+            direct_send_result: false,
+            store_and_forward_send_result: false,
+        };
+
+        if self.resources.config.transaction_routing_mechanism != TransactionRoutingMechanism::StoreAndForwardOnly {
+            match self
+                .resources
+                .outbound_message_service
+                .send_direct(
+                    dest_pubkey.clone(),
+                    OutboundDomainMessage::new(message_type, msg.clone()),
+                )
+                .await
+            {
+                Ok(SendMessageResponse::Queued(send_states)) => {
+                    result.direct_send_result = wait_on_dial(
+                        send_states,
+                        tx_id,
+                        dest_pubkey.clone(),
+                        "Batch Transaction",
+                        self.resources.config.direct_send_timeout,
+                    )
+                    .await;
+                },
+                Ok(SendMessageResponse::Failed(err)) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Batch Transaction Send Direct for TxID {} failed: {}", tx_id, err
+                    );
+                },
+                Ok(SendMessageResponse::PendingDiscovery(rx)) => {
+                    let _ = self
+                        .resources
+                        .event_publisher
+                        .send(Arc::new(TransactionEvent::TransactionDiscoveryInProgress(self.id)));
+                    // Store and forward is attempted below regardless, so only the outcome of the discovery is of
+                    // interest here
+                    if let Ok(SendMessageResponse::Queued(send_states)) = rx.await {
+                        result.direct_send_result = wait_on_dial(
+                            send_states,
+                            tx_id,
+                            dest_pubkey.clone(),
+                            "Batch Transaction",
+                            self.resources.config.direct_send_timeout,
+                        )
+                        .await;
+                    }
+                },
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Direct Batch Transaction Send failed: {:?}", e);
+                },
+            }
+        }
+
+        if self.resources.config.transaction_routing_mechanism != TransactionRoutingMechanism::DirectOnly {
+            result.store_and_forward_send_result = match self
+                .resources
+                .outbound_message_service
+                .closest_broadcast(
+                    NodeId::from_public_key(dest_pubkey),
+                    OutboundEncryption::EncryptFor(Box::new(dest_pubkey.clone())),
+                    vec![],
+                    OutboundDomainMessage::new(message_type, msg),
+                )
+                .await
+            {
+                Ok(send_states) if !send_states.is_empty() => {
+                    let (successful_sends, _) = send_states
+                        .wait_n_timeout(self.resources.config.broadcast_send_timeout, 1)
+                        .await;
+                    !successful_sends.is_empty()
+                },
+                Ok(_) => false,
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Batch Transaction Send (TxId: {}) to neighbours for Store and Forward failed: {:?}", tx_id, e
+                    );
+                    false
+                },
+            };
+        }
+
+        Ok(result)
+    }
+
+    async fn timeout_transaction(&mut self) -> Result<(), TransactionServiceProtocolError> {
+        info!(
+            target: LOG_TARGET,
+            "Cancelling Transaction Batch Send Protocol (TxId: {}) due to timeout after no counterparty response",
+            self.id
+        );
+        for recipient in self.recipients.clone() {
+            self.send_cancellation(&recipient).await;
+        }
+        self.resources
+            .db
+            .increment_send_count(self.id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        self.resources
+            .db
+            .cancel_pending_transaction(self.id)
+            .await
+            .map_err(|e| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Pending Transaction does not exist and could not be cancelled: {:?}", e
+                );
+                TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e))
+            })?;
+
+        self.resources
+            .output_manager_service
+            .cancel_transaction(self.id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+
+        let _ = self
+            .resources
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCancelled(self.id)));
+
+        info!(
+            target: LOG_TARGET,
+            "Pending Batch Transaction (TxId: {}) timed out after no response from all counterparties", self.id
+        );
+
+        Err(TransactionServiceProtocolError::new(
+            self.id,
+            TransactionServiceError::Timeout,
+        ))
+    }
+}
+
+struct SendResult {
+    direct_send_result: bool,
+    store_and_forward_send_result: bool,
+}
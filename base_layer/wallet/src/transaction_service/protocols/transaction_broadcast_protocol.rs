@@ -41,7 +41,7 @@ use tari_core::{
         proto::wallet_rpc::{TxLocation, TxQueryResponse, TxSubmissionRejectionReason, TxSubmissionResponse},
         rpc::BaseNodeWalletRpcClient,
     },
-    transactions::{transaction::Transaction, types::Signature},
+    transactions::{transaction::Transaction, types::Commitment},
 };
 use tari_crypto::tari_utilities::hex::Hex;
 use tokio::{sync::broadcast, time::delay_for};
@@ -59,6 +59,7 @@ where TBackend: TransactionBackend + 'static
     timeout_update_receiver: Option<broadcast::Receiver<Duration>>,
     base_node_update_receiver: Option<broadcast::Receiver<CommsPublicKey>>,
     first_rejection: bool,
+    mined_kernel_excesses: Vec<Commitment>,
 }
 
 impl<TBackend> TransactionBroadcastProtocol<TBackend>
@@ -82,6 +83,7 @@ where TBackend: TransactionBackend + 'static
             timeout_update_receiver: Some(timeout_update_receiver),
             base_node_update_receiver: Some(base_node_update_receiver),
             first_rejection: false,
+            mined_kernel_excesses: Vec::new(),
         }
     }
 
@@ -103,6 +105,16 @@ where TBackend: TransactionBackend + 'static
             })?
             .fuse();
 
+        // A transaction that was already accepted by the mempool, e.g. before the wallet was restarted, is queried
+        // rather than submitted again
+        if let Ok(completed_tx) = self.resources.db.get_completed_transaction(self.tx_id).await {
+            if completed_tx.status == TransactionStatus::Broadcast ||
+                completed_tx.status == TransactionStatus::MinedUnconfirmed
+            {
+                self.mode = TxBroadcastMode::TransactionQuery;
+            }
+        }
+
         let mut shutdown = self.resources.shutdown_signal.clone();
         // Main protocol loop
         loop {
@@ -229,6 +241,17 @@ where TBackend: TransactionBackend + 'static
                 return Ok(self.tx_id);
             }
 
+            // If the transaction was replaced, i.e. its fee was bumped, since it was submitted then the replacement is
+            // stored as not yet broadcast and needs to be submitted to the base node
+            if self.mode == TxBroadcastMode::TransactionQuery && completed_tx.status == TransactionStatus::Completed {
+                info!(
+                    target: LOG_TARGET,
+                    "Transaction (TxId: {}) has been replaced and the replacement will be submitted", self.tx_id
+                );
+                self.mode = TxBroadcastMode::TransactionSubmission;
+                self.first_rejection = false;
+            }
+
             let mut client = match base_node_connection
                 .connect_rpc_using_builder(
                     BaseNodeWalletRpcClient::builder()
@@ -277,7 +300,16 @@ where TBackend: TransactionBackend + 'static
                             },
                             TxBroadcastMode::TransactionQuery => {
                                 if result? {
-                                    // We are done!
+                                    // We are done! If only some of the kernels were mined then the original
+                                    // transaction, or an earlier replacement, was mined instead of the latest
+                                    // replacement and the outputs of the fee bumps that were not mined are released
+                                    if self.mined_kernel_excesses.len() < completed_tx.transaction.body.kernels().len() {
+                                        self.resources
+                                            .output_manager_service
+                                            .release_fee_bumps(completed_tx.tx_id, self.mined_kernel_excesses.clone())
+                                            .await
+                                            .map_err(|e| TransactionServiceProtocolError::new(self.tx_id, TransactionServiceError::from(e)))?;
+                                    }
                                     self.resources
                                         .output_manager_service
                                         .confirm_transaction(
@@ -446,7 +478,9 @@ where TBackend: TransactionBackend + 'static
         Ok(true)
     }
 
-    /// Attempt to query the location of the transaction from the base node via RPC.
+    /// Attempt to query the location of the transaction from the base node via RPC. Every kernel of the transaction is
+    /// queried because a replacement contains the kernels of the original transaction and of its fee bumps, and the
+    /// original transaction can be mined instead of the replacement.
     /// # Returns:
     /// `Ok(true)` => Transaction was successfully mined and confirmed
     /// `Ok(false)` => There was a problem with the RPC call or the transaction is not mined but still in the mempool
@@ -454,26 +488,48 @@ where TBackend: TransactionBackend + 'static
     /// end.
     async fn transaction_query(
         &mut self,
-        signature: Signature,
+        transaction: &Transaction,
         client: &mut BaseNodeWalletRpcClient,
     ) -> Result<bool, TransactionServiceProtocolError>
     {
-        let response = match client.transaction_query(signature.into()).await {
-            Ok(r) => match TxQueryResponse::try_from(r) {
-                Ok(r) => r,
-                Err(_) => {
-                    trace!(target: LOG_TARGET, "Could not convert proto TxQueryResponse");
+        let mut responses = Vec::with_capacity(transaction.body.kernels().len());
+        for kernel in transaction.body.kernels() {
+            let response = match client.transaction_query(kernel.excess_sig.clone().into()).await {
+                Ok(r) => match TxQueryResponse::try_from(r) {
+                    Ok(r) => r,
+                    Err(_) => {
+                        trace!(target: LOG_TARGET, "Could not convert proto TxQueryResponse");
+                        return Ok(false);
+                    },
+                },
+                Err(e) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Transaction Query RPC Call to Base Node failed: {}", e
+                    );
                     return Ok(false);
                 },
-            },
-            Err(e) => {
-                info!(
-                    target: LOG_TARGET,
-                    "Transaction Query RPC Call to Base Node failed: {}", e
-                );
-                return Ok(false);
-            },
-        };
+            };
+            responses.push((kernel.excess.clone(), response));
+        }
+
+        // The transaction is mined once any of its kernels is mined and is only as confirmed as its least confirmed
+        // mined kernel
+        self.mined_kernel_excesses = responses
+            .iter()
+            .filter(|(_, r)| r.location == TxLocation::Mined)
+            .map(|(excess, _)| excess.clone())
+            .collect();
+        let response = responses
+            .iter()
+            .filter(|(_, r)| r.location == TxLocation::Mined)
+            .min_by_key(|(_, r)| r.confirmations)
+            .or_else(|| responses.iter().find(|(_, r)| r.location == TxLocation::InMempool))
+            .or_else(|| responses.first())
+            .map(|(_, r)| r.clone())
+            .ok_or_else(|| {
+                TransactionServiceProtocolError::new(self.tx_id, TransactionServiceError::InvalidTransaction)
+            })?;
 
         if !(response.is_synced ||
             (response.location == TxLocation::Mined &&
@@ -593,7 +649,7 @@ where TBackend: TransactionBackend + 'static
                 target: LOG_TARGET,
                 "Querying Transaction (TxId: {}) status on Base Node", self.tx_id
             );
-            self.transaction_query(signature.clone(), client).await
+            self.transaction_query(&completed_transaction.transaction, client).await
         }
     }
 
@@ -45,7 +45,10 @@ use tari_comms::types::CommsPublicKey;
 
 use tari_core::transactions::{
     transaction::Transaction,
-    transaction_protocol::{recipient::RecipientState, sender::TransactionSenderMessage},
+    transaction_protocol::{
+        recipient::RecipientState,
+        sender::{MultiRoundSignatureRequest, TransactionSenderMessage},
+    },
 };
 use tokio::time::delay_for;
 
@@ -67,6 +70,7 @@ where TBackend: TransactionBackend + 'static
     stage: TransactionReceiveProtocolStage,
     resources: TransactionServiceResources<TBackend>,
     transaction_finalize_receiver: Option<mpsc::Receiver<(CommsPublicKey, TxId, Transaction)>>,
+    signature_request_receiver: Option<mpsc::Receiver<(CommsPublicKey, MultiRoundSignatureRequest)>>,
     cancellation_receiver: Option<oneshot::Receiver<()>>,
 }
 
@@ -80,6 +84,7 @@ where TBackend: TransactionBackend + 'static
         stage: TransactionReceiveProtocolStage,
         resources: TransactionServiceResources<TBackend>,
         transaction_finalize_receiver: mpsc::Receiver<(CommsPublicKey, TxId, Transaction)>,
+        signature_request_receiver: mpsc::Receiver<(CommsPublicKey, MultiRoundSignatureRequest)>,
         cancellation_receiver: oneshot::Receiver<()>,
     ) -> Self
     {
@@ -90,6 +95,7 @@ where TBackend: TransactionBackend + 'static
             stage,
             resources,
             transaction_finalize_receiver: Some(transaction_finalize_receiver),
+            signature_request_receiver: Some(signature_request_receiver),
             cancellation_receiver: Some(cancellation_receiver),
         }
     }
@@ -114,105 +120,104 @@ where TBackend: TransactionBackend + 'static
     }
 
     async fn accept_transaction(&mut self) -> Result<(), TransactionServiceProtocolError> {
-        // Currently we will only reply to a Single sender transaction protocol
-        if let TransactionSenderMessage::Single(data) = self.sender_message.clone() {
-            // Check this is not a repeat message i.e. tx_id doesn't already exist in our pending or completed
-            // transactions
-            if self
-                .resources
-                .db
-                .transaction_exists(data.tx_id)
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?
-            {
-                trace!(
-                    target: LOG_TARGET,
-                    "Received Transaction (TxId: {}) already present in database.",
-                    data.tx_id,
-                );
+        let (tx_id, amount, message) = match &self.sender_message {
+            TransactionSenderMessage::Single(data) => (data.tx_id, data.amount, data.message.clone()),
+            TransactionSenderMessage::Multiple(data) => (data.tx_id, data.amount, data.message.clone()),
+            TransactionSenderMessage::None => {
                 return Err(TransactionServiceProtocolError::new(
                     self.id,
-                    TransactionServiceError::RepeatedMessageError,
-                ));
-            }
-
-            let amount = data.amount;
-
-            let rtp = self
-                .resources
-                .output_manager_service
-                .get_recipient_transaction(self.sender_message.clone())
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+                    TransactionServiceError::InvalidStateError,
+                ))
+            },
+        };
 
-            let inbound_transaction = InboundTransaction::new(
-                data.tx_id,
-                self.source_pubkey.clone(),
-                amount,
-                rtp,
-                TransactionStatus::Pending,
-                data.message.clone(),
-                Utc::now().naive_utc(),
+        // Check this is not a repeat message i.e. tx_id doesn't already exist in our pending or completed
+        // transactions
+        if self
+            .resources
+            .db
+            .transaction_exists(tx_id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?
+        {
+            trace!(
+                target: LOG_TARGET,
+                "Received Transaction (TxId: {}) already present in database.",
+                tx_id,
             );
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::RepeatedMessageError,
+            ));
+        }
 
-            self.resources
-                .db
-                .add_pending_inbound_transaction(inbound_transaction.tx_id, inbound_transaction.clone())
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        let rtp = self
+            .resources
+            .output_manager_service
+            .get_recipient_transaction(self.sender_message.clone())
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            let send_result = send_transaction_reply(
-                inbound_transaction,
-                self.resources.outbound_message_service.clone(),
-                self.resources.config.direct_send_timeout,
-                self.resources.config.transaction_routing_mechanism,
-            )
+        let inbound_transaction = InboundTransaction::new(
+            tx_id,
+            self.source_pubkey.clone(),
+            amount,
+            rtp,
+            TransactionStatus::Pending,
+            message.clone(),
+            Utc::now().naive_utc(),
+        );
+
+        self.resources
+            .db
+            .add_pending_inbound_transaction(inbound_transaction.tx_id, inbound_transaction.clone())
             .await
-            .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            self.resources
-                .db
-                .increment_send_count(self.id)
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        let send_result = send_transaction_reply(
+            inbound_transaction,
+            self.resources.outbound_message_service.clone(),
+            self.resources.config.direct_send_timeout,
+            self.resources.config.transaction_routing_mechanism,
+        )
+        .await
+        .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
 
-            if !send_result {
-                error!(
-                    target: LOG_TARGET,
-                    "Transaction with TX_ID = {} received from {}. Reply could not be sent!",
-                    data.tx_id,
-                    self.source_pubkey,
-                );
-            } else {
-                info!(
-                    target: LOG_TARGET,
-                    "Transaction with TX_ID = {} received from {}. Reply Sent", data.tx_id, self.source_pubkey,
-                );
-            }
+        self.resources
+            .db
+            .increment_send_count(self.id)
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            trace!(
+        if !send_result {
+            error!(
                 target: LOG_TARGET,
-                "Transaction (TX_ID: {}) - Amount: {} - Message: {}",
-                data.tx_id,
-                amount,
-                data.message,
+                "Transaction with TX_ID = {} received from {}. Reply could not be sent!", tx_id, self.source_pubkey,
             );
-
-            let _ = self
-                .resources
-                .event_publisher
-                .send(Arc::new(TransactionEvent::ReceivedTransaction(data.tx_id)))
-                .map_err(|e| {
-                    trace!(target: LOG_TARGET, "Error sending event due to no subscribers: {:?}", e);
-                    e
-                });
-            Ok(())
         } else {
-            Err(TransactionServiceProtocolError::new(
-                self.id,
-                TransactionServiceError::InvalidStateError,
-            ))
+            info!(
+                target: LOG_TARGET,
+                "Transaction with TX_ID = {} received from {}. Reply Sent", tx_id, self.source_pubkey,
+            );
         }
+
+        trace!(
+            target: LOG_TARGET,
+            "Transaction (TX_ID: {}) - Amount: {} - Message: {}",
+            tx_id,
+            amount,
+            message,
+        );
+
+        let _ = self
+            .resources
+            .event_publisher
+            .send(Arc::new(TransactionEvent::ReceivedTransaction(tx_id)))
+            .map_err(|e| {
+                trace!(target: LOG_TARGET, "Error sending event due to no subscribers: {:?}", e);
+                e
+            });
+        Ok(())
     }
 
     async fn wait_for_finalization(&mut self) -> Result<(), TransactionServiceProtocolError> {
@@ -221,13 +226,18 @@ where TBackend: TransactionBackend + 'static
             .take()
             .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?;
 
+        let mut signature_request_receiver = self
+            .signature_request_receiver
+            .take()
+            .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?;
+
         let mut cancellation_receiver = self
             .cancellation_receiver
             .take()
             .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?
             .fuse();
 
-        let inbound_tx = match self.resources.db.get_pending_inbound_transaction(self.id).await {
+        let mut inbound_tx = match self.resources.db.get_pending_inbound_transaction(self.id).await {
             Ok(tx) => tx,
             Err(_e) => {
                 debug!(
@@ -325,6 +335,18 @@ where TBackend: TransactionBackend + 'static
                             break;
                         }
                     },
+                    (spk, request) = signature_request_receiver.select_next_some() => {
+                        if inbound_tx.source_public_key != spk {
+                            warn!(
+                                target: LOG_TARGET,
+                                "Signature Request did not come from the expected Public Key"
+                            );
+                        } else if request.tx_id != inbound_tx.tx_id || request.tx_id != self.id {
+                            debug!(target: LOG_TARGET, "Signature Request does not have the correct TxId");
+                        } else {
+                            self.handle_signature_request(&mut inbound_tx, request).await?;
+                        }
+                    },
                     result = cancellation_receiver => {
                         if result.is_ok() {
                             info!(target: LOG_TARGET, "Cancelling Transaction Receive Protocol for TxId: {}", self.id);
@@ -382,7 +404,7 @@ where TBackend: TransactionBackend + 'static
 
             let rtp_output = match inbound_tx.receiver_protocol.state.clone() {
                 RecipientState::Finalized(s) => s.output,
-                RecipientState::Failed(_) => {
+                RecipientState::AwaitingSignatureRequest(_) | RecipientState::Failed(_) => {
                     warn!(
                         target: LOG_TARGET,
                         "Finalized Transaction TxId: {} is not in the correct state to be completed", self.id
@@ -444,6 +466,62 @@ where TBackend: TransactionBackend + 'static
         Ok(())
     }
 
+    /// Sign the aggregated transaction data the sender has collected from all the recipients of a multi-round
+    /// transaction and reply with the signed data. A repeated request is answered with the same signed data.
+    async fn handle_signature_request(
+        &mut self,
+        inbound_tx: &mut InboundTransaction,
+        request: MultiRoundSignatureRequest,
+    ) -> Result<(), TransactionServiceProtocolError>
+    {
+        if inbound_tx.receiver_protocol.is_awaiting_signature_request() {
+            if let Err(e) = inbound_tx.receiver_protocol.sign_multi_round(&request) {
+                warn!(
+                    target: LOG_TARGET,
+                    "Signature Request for TxId: {} could not be signed: {:?}", self.id, e
+                );
+                return Ok(());
+            }
+            self.resources
+                .db
+                .update_pending_inbound_receiver_protocol(self.id, inbound_tx.receiver_protocol.clone())
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            info!(
+                target: LOG_TARGET,
+                "Signature Request for TxId: {} received from {} and signed", self.id, self.source_pubkey
+            );
+        } else if !inbound_tx.receiver_protocol.is_finalized() {
+            warn!(
+                target: LOG_TARGET,
+                "Signature Request received for TxId: {} which is not in the correct state to be signed", self.id
+            );
+            return Ok(());
+        }
+
+        match send_transaction_reply(
+            inbound_tx.clone(),
+            self.resources.outbound_message_service.clone(),
+            self.resources.config.direct_send_timeout,
+            self.resources.config.transaction_routing_mechanism,
+        )
+        .await
+        {
+            Ok(_) => self
+                .resources
+                .db
+                .increment_send_count(self.id)
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?,
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Error sending Transaction Reply (TxId: {}): {:?}", self.id, e
+            ),
+        }
+
+        Ok(())
+    }
+
     async fn timeout_transaction(&mut self) -> Result<(), TransactionServiceProtocolError> {
         info!(
             target: LOG_TARGET,
@@ -35,7 +35,7 @@ use crate::{
 use futures::{FutureExt, StreamExt};
 use log::*;
 use std::{cmp, convert::TryFrom, sync::Arc, time::Duration};
-use tari_comms::{peer_manager::NodeId, types::CommsPublicKey, PeerConnection};
+use tari_comms::{peer_manager::NodeId, protocol::rpc::RpcClientLease, types::CommsPublicKey};
 use tari_core::{
     base_node::{
         proto::wallet_rpc::{TxLocation, TxQueryBatchResponse},
@@ -158,7 +158,7 @@ where TBackend: TransactionBackend + 'static
 
             let base_node_node_id = NodeId::from_key(&self.base_node_public_key.clone())
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
-            let mut connection: Option<PeerConnection> = None;
+            let mut client: Option<RpcClientLease<BaseNodeWalletRpcClient>> = None;
 
             let delay = delay_for(self.timeout);
 
@@ -167,10 +167,13 @@ where TBackend: TransactionBackend + 'static
                 "Connecting to Base Node (Public Key: {})", self.base_node_public_key,
             );
             futures::select! {
-                dial_result = self.resources.connectivity_manager.dial_peer(base_node_node_id.clone()).fuse() => {
-                    match dial_result {
-                        Ok(base_node_connection) => {
-                            connection = Some(base_node_connection);
+                client_result = self.resources.connectivity_manager.get_rpc_client(
+                    base_node_node_id.clone(),
+                    BaseNodeWalletRpcClient::builder().with_deadline(self.timeout),
+                ).fuse() => {
+                    match client_result {
+                        Ok(base_node_client) => {
+                            client = Some(base_node_client);
                         },
                         Err(e) => {
                             info!(target: LOG_TARGET, "Problem connecting to base node: {} for Transaction Validation Protocol", e);
@@ -231,7 +234,7 @@ where TBackend: TransactionBackend + 'static
                 },
             }
 
-            let mut base_node_connection = match connection {
+            let mut client = match client {
                 None => {
                     futures::select! {
                         _ = delay.fuse() => {
@@ -259,19 +262,6 @@ where TBackend: TransactionBackend + 'static
                 Some(c) => c,
             };
 
-            let mut client = match base_node_connection
-                .connect_rpc_using_builder(BaseNodeWalletRpcClient::builder().with_deadline(self.timeout))
-                .await
-            {
-                Ok(c) => c,
-                Err(e) => {
-                    warn!(target: LOG_TARGET, "Problem establishing RPC connection: {}", e);
-                    delay.await;
-                    retries += 1;
-                    continue;
-                },
-            };
-
             debug!(target: LOG_TARGET, "RPC client connected");
 
             'per_tx: loop {
@@ -425,13 +415,16 @@ where TBackend: TransactionBackend + 'static
         client: &mut BaseNodeWalletRpcClient,
     ) -> Result<bool, TransactionServiceError>
     {
+        // Every kernel is queried because a replacement contains the kernels of the original transaction and of its
+        // fee bumps, and the original transaction can be mined instead of the replacement
         let mut batch_signatures = Vec::new();
         for tx in batch.iter() {
-            let signature = tx
-                .transaction
-                .first_kernel_excess_sig()
-                .ok_or_else(|| TransactionServiceError::InvalidTransaction)?;
-            batch_signatures.push(SignatureProto::from(signature.clone()));
+            if tx.transaction.body.kernels().is_empty() {
+                return Err(TransactionServiceError::InvalidTransaction);
+            }
+            for kernel in tx.transaction.body.kernels() {
+                batch_signatures.push(SignatureProto::from(kernel.excess_sig.clone()));
+            }
         }
 
         let batch_response = client
@@ -442,17 +435,41 @@ where TBackend: TransactionBackend + 'static
             return Ok(false);
         }
 
-        for response_proto in batch_response.responses {
-            let response = TxQueryBatchResponse::try_from(response_proto)
-                .map_err(TransactionServiceError::ProtobufConversionError)?;
+        let responses = batch_response
+            .responses
+            .into_iter()
+            .map(TxQueryBatchResponse::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(TransactionServiceError::ProtobufConversionError)?;
 
-            if let Some(queried_tx) = batch.iter().find(|tx| {
-                if let Some(sig) = tx.transaction.first_kernel_excess_sig() {
-                    sig == &response.signature
-                } else {
-                    false
-                }
-            }) {
+        for queried_tx in batch.iter() {
+            let kernel_responses = queried_tx
+                .transaction
+                .body
+                .kernels()
+                .iter()
+                .filter_map(|k| {
+                    responses
+                        .iter()
+                        .find(|r| r.signature == k.excess_sig)
+                        .map(|r| (k.excess.clone(), r))
+                })
+                .collect::<Vec<_>>();
+            // The transaction is mined once any of its kernels is mined and is only as confirmed as its least confirmed
+            // mined kernel
+            let mined_kernel_excesses = kernel_responses
+                .iter()
+                .filter(|(_, r)| r.location == TxLocation::Mined)
+                .map(|(excess, _)| excess.clone())
+                .collect::<Vec<_>>();
+            let response = kernel_responses
+                .iter()
+                .filter(|(_, r)| r.location == TxLocation::Mined)
+                .min_by_key(|(_, r)| r.confirmations)
+                .or_else(|| kernel_responses.first())
+                .map(|(_, r)| *r);
+
+            if let Some(response) = response {
                 // Mined?
                 if response.location == TxLocation::Mined {
                     if !queried_tx.valid {
@@ -492,6 +509,20 @@ where TBackend: TransactionBackend + 'static
                                     "Error confirming mined transaction (TxId: {}): {}", queried_tx.tx_id, e
                                 );
                             }
+                            // The outputs of the fee bumps that were not mined are released
+                            if mined_kernel_excesses.len() < queried_tx.transaction.body.kernels().len() {
+                                if let Err(e) = self
+                                    .resources
+                                    .output_manager_service
+                                    .release_fee_bumps(queried_tx.tx_id, mined_kernel_excesses.clone())
+                                    .await
+                                {
+                                    warn!(
+                                        target: LOG_TARGET,
+                                        "Error releasing fee bumps of transaction (TxId: {}): {}", queried_tx.tx_id, e
+                                    );
+                                }
+                            }
                             if let Err(e) = self
                                 .resources
                                 .output_manager_service
@@ -546,7 +577,7 @@ where TBackend: TransactionBackend + 'static
             } else {
                 debug!(
                     target: LOG_TARGET,
-                    "Could not find transaction corresponding to returned query response"
+                    "Could not find query response corresponding to transaction (TxId: {})", queried_tx.tx_id
                 );
             }
         }
@@ -21,12 +21,18 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    output_manager_service::{handle::OutputManagerHandle, TxId},
+    output_manager_service::{
+        error::OutputManagerError,
+        handle::OutputManagerHandle,
+        utxo_selection::UTXOSelectionStrategy,
+        TxId,
+    },
     transaction_service::{
         config::TransactionServiceConfig,
         error::{TransactionServiceError, TransactionServiceProtocolError},
         handle::{TransactionEvent, TransactionEventSender, TransactionServiceRequest, TransactionServiceResponse},
         protocols::{
+            transaction_batch_send_protocol::{TransactionBatchSendProtocol, TransactionBatchSendProtocolStage},
             transaction_broadcast_protocol::TransactionBroadcastProtocol,
             transaction_coinbase_monitoring_protocol::TransactionCoinbaseMonitoringProtocol,
             transaction_receive_protocol::{TransactionReceiveProtocol, TransactionReceiveProtocolStage},
@@ -35,7 +41,14 @@ use crate::{
         },
         storage::{
             database::{TransactionBackend, TransactionDatabase},
-            models::{CompletedTransaction, TransactionDirection, TransactionStatus},
+            models::{
+                BatchRecipient,
+                BatchRecipientStatus,
+                CompletedTransaction,
+                InboundTransaction,
+                TransactionDirection,
+                TransactionStatus,
+            },
         },
         tasks::{
             send_finalized_transaction::send_finalized_transaction_message,
@@ -71,8 +84,14 @@ use tari_core::{
     transactions::{
         tari_amount::MicroTari,
         transaction::Transaction,
-        transaction_protocol::{proto, recipient::RecipientSignedMessage, sender::TransactionSenderMessage},
-        types::{CryptoFactories, PrivateKey},
+        transaction_protocol::{
+            partially_signed::{PartiallySignedTransaction, PartiallySignedTransactionStage},
+            proto,
+            recipient::{RecipientPublicKeys, RecipientSignedMessage},
+            sender::{MultiRoundSignatureRequest, SingleRoundSenderData, TransactionSenderMessage},
+        },
+        types::{Commitment, CryptoFactories, PrivateKey},
+        SenderTransactionProtocol,
     },
 };
 use tari_p2p::domain_message::DomainMessage;
@@ -88,7 +107,8 @@ const LOG_TARGET: &str = "wallet::transaction_service::service";
 /// which are uniquely identified by a tx_id. The TransactionService generates and accepts the various protocol
 /// messages and applies them to the appropriate protocol instances based on the tx_id.
 /// The TransactionService allows for the sending of transactions to single receivers, when the appropriate recipient
-/// response is handled the transaction is completed and moved to the completed_transaction buffer.
+/// response is handled the transaction is completed and moved to the completed_transaction buffer. A batched
+/// transaction to multiple receivers is completed once the responses of all of its recipients have been handled.
 /// The TransactionService will accept inbound transactions and generate a reply. Received transactions will remain
 /// in the pending_inbound_transactions buffer.
 /// # Fields
@@ -104,6 +124,8 @@ pub struct TransactionService<
     BNResponseStream,
     TBackend,
     TTxCancelledStream,
+    TTxPublicKeysReplyStream,
+    TTxSignatureRequestStream,
 > where TBackend: TransactionBackend + 'static
 {
     config: TransactionServiceConfig,
@@ -114,6 +136,8 @@ pub struct TransactionService<
     transaction_finalized_stream: Option<TTxFinalizedStream>,
     base_node_response_stream: Option<BNResponseStream>,
     transaction_cancelled_stream: Option<TTxCancelledStream>,
+    transaction_public_keys_reply_stream: Option<TTxPublicKeysReplyStream>,
+    transaction_signature_request_stream: Option<TTxSignatureRequestStream>,
     request_stream: Option<
         reply_channel::Receiver<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     >,
@@ -122,9 +146,12 @@ pub struct TransactionService<
     base_node_public_key: Option<CommsPublicKey>,
     resources: TransactionServiceResources<TBackend>,
     pending_transaction_reply_senders: HashMap<TxId, Sender<(CommsPublicKey, RecipientSignedMessage)>>,
+    pending_public_keys_senders: HashMap<TxId, Sender<(CommsPublicKey, RecipientPublicKeys)>>,
+    batch_transaction_recipient_tx_ids: HashMap<TxId, Vec<TxId>>,
     base_node_response_senders: HashMap<u64, (TxId, Sender<base_node_proto::BaseNodeServiceResponse>)>,
     send_transaction_cancellation_senders: HashMap<u64, oneshot::Sender<()>>,
     finalized_transaction_senders: HashMap<u64, Sender<(CommsPublicKey, TxId, Transaction)>>,
+    signature_request_senders: HashMap<u64, Sender<(CommsPublicKey, MultiRoundSignatureRequest)>>,
     receiver_transaction_cancellation_senders: HashMap<u64, oneshot::Sender<()>>,
     active_transaction_broadcast_protocols: HashSet<u64>,
     active_coinbase_monitoring_protocols: HashSet<u64>,
@@ -134,14 +161,34 @@ pub struct TransactionService<
 }
 
 #[allow(clippy::too_many_arguments)]
-impl<TTxStream, TTxReplyStream, TTxFinalizedStream, BNResponseStream, TBackend, TTxCancelledStream>
-    TransactionService<TTxStream, TTxReplyStream, TTxFinalizedStream, BNResponseStream, TBackend, TTxCancelledStream>
+impl<
+        TTxStream,
+        TTxReplyStream,
+        TTxFinalizedStream,
+        BNResponseStream,
+        TBackend,
+        TTxCancelledStream,
+        TTxPublicKeysReplyStream,
+        TTxSignatureRequestStream,
+    >
+    TransactionService<
+        TTxStream,
+        TTxReplyStream,
+        TTxFinalizedStream,
+        BNResponseStream,
+        TBackend,
+        TTxCancelledStream,
+        TTxPublicKeysReplyStream,
+        TTxSignatureRequestStream,
+    >
 where
     TTxStream: Stream<Item = DomainMessage<proto::TransactionSenderMessage>>,
     TTxReplyStream: Stream<Item = DomainMessage<proto::RecipientSignedMessage>>,
     TTxFinalizedStream: Stream<Item = DomainMessage<proto::TransactionFinalizedMessage>>,
     BNResponseStream: Stream<Item = DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     TTxCancelledStream: Stream<Item = DomainMessage<proto::TransactionCancelledMessage>>,
+    TTxPublicKeysReplyStream: Stream<Item = DomainMessage<proto::RecipientPublicKeys>>,
+    TTxSignatureRequestStream: Stream<Item = DomainMessage<proto::MultiRoundSignatureRequest>>,
     TBackend: TransactionBackend + 'static,
 {
     pub fn new(
@@ -156,6 +203,8 @@ where
         transaction_finalized_stream: TTxFinalizedStream,
         base_node_response_stream: BNResponseStream,
         transaction_cancelled_stream: TTxCancelledStream,
+        transaction_public_keys_reply_stream: TTxPublicKeysReplyStream,
+        transaction_signature_request_stream: TTxSignatureRequestStream,
         output_manager_service: OutputManagerHandle,
         outbound_message_service: OutboundMessageRequester,
         connectivity_manager: ConnectivityRequester,
@@ -191,15 +240,20 @@ where
             transaction_finalized_stream: Some(transaction_finalized_stream),
             base_node_response_stream: Some(base_node_response_stream),
             transaction_cancelled_stream: Some(transaction_cancelled_stream),
+            transaction_public_keys_reply_stream: Some(transaction_public_keys_reply_stream),
+            transaction_signature_request_stream: Some(transaction_signature_request_stream),
             request_stream: Some(request_stream),
             event_publisher,
             node_identity,
             base_node_public_key: None,
             resources,
             pending_transaction_reply_senders: HashMap::new(),
+            pending_public_keys_senders: HashMap::new(),
+            batch_transaction_recipient_tx_ids: HashMap::new(),
             base_node_response_senders: HashMap::new(),
             send_transaction_cancellation_senders: HashMap::new(),
             finalized_transaction_senders: HashMap::new(),
+            signature_request_senders: HashMap::new(),
             receiver_transaction_cancellation_senders: HashMap::new(),
             active_transaction_broadcast_protocols: HashSet::new(),
             active_coinbase_monitoring_protocols: HashSet::new(),
@@ -247,6 +301,18 @@ where
             .expect("Transaction Service initialized without transaction_cancelled_stream")
             .fuse();
         pin_mut!(transaction_cancelled_stream);
+        let transaction_public_keys_reply_stream = self
+            .transaction_public_keys_reply_stream
+            .take()
+            .expect("Transaction Service initialized without transaction_public_keys_reply_stream")
+            .fuse();
+        pin_mut!(transaction_public_keys_reply_stream);
+        let transaction_signature_request_stream = self
+            .transaction_signature_request_stream
+            .take()
+            .expect("Transaction Service initialized without transaction_signature_request_stream")
+            .fuse();
+        pin_mut!(transaction_signature_request_stream);
 
         let mut shutdown = self.resources.shutdown_signal.clone();
 
@@ -337,6 +403,54 @@ where
                         Ok(_) => (),
                     }
                 },
+                // Incoming Public Keys replies from the recipients of a batched transaction
+                msg = transaction_public_keys_reply_stream.select_next_some() => {
+                    let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
+                    trace!(target: LOG_TARGET, "Handling Transaction Public Keys Reply Message, Trace: {}",
+                    msg.dht_header.message_tag);
+                    let result = self.accept_recipient_public_keys(origin_public_key, inner_msg).await;
+
+                    match result {
+                        Err(TransactionServiceError::TransactionDoesNotExistError) => {
+                            trace!(target: LOG_TARGET, "Unable to handle incoming Transaction Public Keys Reply \
+                            message from NodeId: {} due to Transaction not existing. This usually means the message \
+                            was a repeated message from Store and Forward, Trace: {}",
+                            self.node_identity.node_id().short_str(), msg.dht_header.message_tag);
+                        },
+                        Err(e) => {
+                            warn!(target: LOG_TARGET, "Failed to handle incoming Transaction Public Keys Reply \
+                            message: {:?} for NodeId: {}, Trace: {}", e, self.node_identity.node_id().short_str(),
+                            msg.dht_header.message_tag);
+                            let _ = self.event_publisher.send(Arc::new(TransactionEvent::Error("Error handling \
+                            Transaction Public Keys Reply message".to_string())));
+                        },
+                        Ok(_) => (),
+                    }
+                },
+                // Incoming Signature Requests from the sender of a batched transaction
+                msg = transaction_signature_request_stream.select_next_some() => {
+                    let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
+                    trace!(target: LOG_TARGET, "Handling Transaction Signature Request Message, Trace: {}",
+                    msg.dht_header.message_tag);
+                    let result = self.accept_signature_request(origin_public_key, inner_msg).await;
+
+                    match result {
+                        Err(TransactionServiceError::TransactionDoesNotExistError) => {
+                            trace!(target: LOG_TARGET, "Unable to handle incoming Transaction Signature Request \
+                            message from NodeId: {} due to Transaction not existing. This usually means the message \
+                            was a repeated message from Store and Forward, Trace: {}",
+                            self.node_identity.node_id().short_str(), msg.dht_header.message_tag);
+                        },
+                        Err(e) => {
+                            warn!(target: LOG_TARGET, "Failed to handle incoming Transaction Signature Request \
+                            message: {:?} for NodeId: {}, Trace: {}", e, self.node_identity.node_id().short_str(),
+                            msg.dht_header.message_tag);
+                            let _ = self.event_publisher.send(Arc::new(TransactionEvent::Error("Error handling \
+                            Transaction Signature Request message".to_string())));
+                        },
+                        Ok(_) => (),
+                    }
+                },
                // Incoming Finalized Transaction messages from the Comms layer
                 msg = transaction_finalized_stream.select_next_some() => {
                     let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
@@ -454,22 +568,77 @@ where
     ) -> Result<TransactionServiceResponse, TransactionServiceError>
     {
         trace!(target: LOG_TARGET, "Handling Service Request: {}", request);
+        // The outputs that fund a transaction are taken from the account named by the request, or from the active
+        // account of the output manager if the request does not name one
+        let (account, request) = match request {
+            TransactionServiceRequest::WithAccount((account, request)) => (Some(account), *request),
+            request => (None, request),
+        };
         match request {
-            TransactionServiceRequest::SendTransaction((dest_pubkey, amount, fee_per_gram, message)) => self
+            TransactionServiceRequest::SendTransaction((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                utxo_selection_strategy,
+            )) => self
                 .send_transaction(
+                    account,
                     dest_pubkey,
                     amount,
                     fee_per_gram,
                     message,
+                    utxo_selection_strategy,
+                    None,
                     send_transaction_join_handles,
                     transaction_broadcast_join_handles,
                 )
                 .await
                 .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendTransactionWithInputs((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                inputs,
+            )) => self
+                .send_transaction(
+                    account,
+                    dest_pubkey,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    None,
+                    Some(inputs),
+                    send_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendBatchTransaction((
+                recipients,
+                fee_per_gram,
+                message,
+                utxo_selection_strategy,
+            )) => self
+                .send_batch_transaction(
+                    account,
+                    recipients,
+                    fee_per_gram,
+                    message,
+                    utxo_selection_strategy,
+                    send_transaction_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
             TransactionServiceRequest::CancelTransaction(tx_id) => self
                 .cancel_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
+            TransactionServiceRequest::BumpTransactionFee((tx_id, fee_per_gram)) => self
+                .bump_transaction_fee(account, tx_id, fee_per_gram)
+                .await
+                .map(TransactionServiceResponse::TransactionFeeBumped),
             TransactionServiceRequest::GetPendingInboundTransactions => {
                 Ok(TransactionServiceResponse::PendingInboundTransactions(
                     self.db.get_pending_inbound_transactions().await?,
@@ -519,8 +688,16 @@ where
                 .submit_transaction(transaction_broadcast_join_handles, tx_id, tx, fee, amount, message)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionSubmitted),
+            TransactionServiceRequest::ImportPartiallySignedTransaction(pst) => self
+                .import_partially_signed_transaction(
+                    *pst,
+                    receive_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(|next_stage| TransactionServiceResponse::PartiallySignedTransactionImported(Box::new(next_stage))),
             TransactionServiceRequest::GenerateCoinbaseTransaction(reward, fees, block_height) => self
-                .generate_coinbase_transaction(reward, fees, block_height, coinbase_monitoring_join_handles)
+                .generate_coinbase_transaction(account, reward, fees, block_height, coinbase_monitoring_join_handles)
                 .await
                 .map(|tx| TransactionServiceResponse::CoinbaseTransactionGenerated(Box::new(tx))),
             #[cfg(feature = "test_harness")]
@@ -592,6 +769,15 @@ where
                 .start_transaction_validation_protocol(retry_strategy, transaction_validation_join_handles)
                 .await
                 .map(TransactionServiceResponse::ValidationStarted),
+            TransactionServiceRequest::WithAccount(_) => Err(OutputManagerError::NestedAccountRequest.into()),
+        }
+    }
+
+    /// Returns the output manager handle for the named account, or for the active account if no account is named
+    fn account_output_manager_service(&self, account: Option<String>) -> OutputManagerHandle {
+        match account {
+            Some(account) => self.output_manager_service.with_account(account),
+            None => self.output_manager_service.clone(),
         }
     }
 
@@ -600,18 +786,25 @@ where
     /// 'dest_pubkey': The Comms pubkey of the recipient node
     /// 'amount': The amount of Tari to send to the recipient
     /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    /// 'utxo_selection_strategy': Overrides the configured UTXO selector of the Output Manager if provided
+    /// 'inputs': The commitments of the unspent outputs to spend, if provided no UTXO selection is done
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_transaction(
         &mut self,
+        account: Option<String>,
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+        inputs: Option<Vec<Commitment>>,
         join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
         transaction_broadcast_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<u64, TransactionServiceProtocolError>>,
         >,
     ) -> Result<TxId, TransactionServiceError>
     {
+        let mut output_manager_service = self.account_output_manager_service(account);
         // If we're paying ourselves, let's complete and submit the transaction immediately
         if self.node_identity.public_key() == &dest_pubkey {
             debug!(
@@ -619,10 +812,24 @@ where
                 "Received transaction with spend-to-self transaction"
             );
 
-            let (tx_id, fee, transaction) = self
-                .output_manager_service
-                .create_pay_to_self_transaction(amount, fee_per_gram, None, message.clone())
-                .await?;
+            let (tx_id, fee, transaction) = match inputs {
+                Some(inputs) => {
+                    output_manager_service
+                        .create_pay_to_self_transaction_with_inputs(amount, fee_per_gram, None, message.clone(), inputs)
+                        .await?
+                },
+                None => {
+                    output_manager_service
+                        .create_pay_to_self_transaction_with_utxo_selection(
+                            amount,
+                            fee_per_gram,
+                            None,
+                            message.clone(),
+                            utxo_selection_strategy,
+                        )
+                        .await?
+                },
+            };
 
             // Notify that the transaction was successfully resolved.
             let _ = self
@@ -642,10 +849,24 @@ where
             return Ok(tx_id);
         }
 
-        let sender_protocol = self
-            .output_manager_service
-            .prepare_transaction_to_send(amount, fee_per_gram, None, message.clone())
-            .await?;
+        let sender_protocol = match inputs {
+            Some(inputs) => {
+                output_manager_service
+                    .prepare_transaction_to_send_with_inputs(amount, fee_per_gram, None, message.clone(), inputs)
+                    .await?
+            },
+            None => {
+                output_manager_service
+                    .prepare_transaction_to_send_with_utxo_selection(
+                        amount,
+                        fee_per_gram,
+                        None,
+                        message.clone(),
+                        utxo_selection_strategy,
+                    )
+                    .await?
+            },
+        };
 
         let tx_id = sender_protocol.get_tx_id()?;
 
@@ -673,6 +894,178 @@ where
         Ok(tx_id)
     }
 
+    /// Sends a single new transaction to multiple recipients. The transaction is negotiated with all of the recipients
+    /// using the multi-round transaction protocol and is tracked under one `TxId`.
+    /// # Arguments
+    /// 'recipients': The Comms pubkey of each recipient node and the amount of Tari to send to them
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    /// 'utxo_selection_strategy': Overrides the configured UTXO selector of the Output Manager if provided
+    pub async fn send_batch_transaction(
+        &mut self,
+        account: Option<String>,
+        recipients: Vec<(CommsPublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+        utxo_selection_strategy: Option<UTXOSelectionStrategy>,
+        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        if recipients.len() < 2 {
+            return Err(TransactionServiceError::InvalidBatchRecipients(
+                "A batched transaction requires at least two recipients".to_string(),
+            ));
+        }
+        if recipients.iter().any(|(pk, _)| pk == self.node_identity.public_key()) {
+            return Err(TransactionServiceError::InvalidBatchRecipients(
+                "A batched transaction cannot be sent to this wallet".to_string(),
+            ));
+        }
+        let unique_recipients: HashSet<&CommsPublicKey> = recipients.iter().map(|(pk, _)| pk).collect();
+        if unique_recipients.len() != recipients.len() {
+            return Err(TransactionServiceError::InvalidBatchRecipients(
+                "A batched transaction cannot contain the same recipient more than once".to_string(),
+            ));
+        }
+
+        let sender_protocol = self
+            .account_output_manager_service(account)
+            .prepare_batch_transaction_to_send(
+                recipients.iter().map(|(_, amount)| *amount).collect(),
+                fee_per_gram,
+                None,
+                message.clone(),
+                utxo_selection_strategy,
+            )
+            .await?;
+
+        let tx_id = sender_protocol.get_tx_id()?;
+        let batch_recipients = sender_protocol
+            .get_recipient_tx_ids()?
+            .into_iter()
+            .zip(recipients.into_iter())
+            .map(|(recipient_tx_id, (public_key, amount))| BatchRecipient {
+                tx_id: recipient_tx_id,
+                public_key,
+                amount,
+                status: BatchRecipientStatus::AwaitingPublicKeys,
+            })
+            .collect();
+
+        self.start_batch_send_protocol(
+            tx_id,
+            batch_recipients,
+            message,
+            sender_protocol,
+            TransactionBatchSendProtocolStage::Initial,
+            join_handles,
+        );
+
+        Ok(tx_id)
+    }
+
+    /// Register the reply channels for every recipient of a batched transaction and spawn its protocol
+    fn start_batch_send_protocol(
+        &mut self,
+        tx_id: TxId,
+        batch_recipients: Vec<BatchRecipient>,
+        message: String,
+        sender_protocol: SenderTransactionProtocol,
+        stage: TransactionBatchSendProtocolStage,
+        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+    )
+    {
+        let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
+        let (public_keys_sender, public_keys_receiver) = mpsc::channel(100);
+        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
+        let recipient_tx_ids: Vec<TxId> = batch_recipients.iter().map(|r| r.tx_id).collect();
+        for recipient_tx_id in recipient_tx_ids.iter() {
+            self.pending_transaction_reply_senders
+                .insert(*recipient_tx_id, tx_reply_sender.clone());
+            self.pending_public_keys_senders
+                .insert(*recipient_tx_id, public_keys_sender.clone());
+        }
+        self.batch_transaction_recipient_tx_ids.insert(tx_id, recipient_tx_ids);
+        self.send_transaction_cancellation_senders
+            .insert(tx_id, cancellation_sender);
+
+        let protocol = TransactionBatchSendProtocol::new(
+            tx_id,
+            self.resources.clone(),
+            public_keys_receiver,
+            tx_reply_receiver,
+            cancellation_receiver,
+            batch_recipients,
+            message,
+            sender_protocol,
+            stage,
+        );
+
+        let join_handle = tokio::spawn(protocol.execute());
+        join_handles.push(join_handle);
+    }
+
+    /// Remove the channels used to pass replies to a Send Transaction protocol, including those registered for each of
+    /// the recipients of a batched transaction
+    fn remove_pending_reply_senders(&mut self, tx_id: TxId) {
+        let _ = self.pending_transaction_reply_senders.remove(&tx_id);
+        if let Some(recipient_tx_ids) = self.batch_transaction_recipient_tx_ids.remove(&tx_id) {
+            for recipient_tx_id in recipient_tx_ids {
+                let _ = self.pending_transaction_reply_senders.remove(&recipient_tx_id);
+                let _ = self.pending_public_keys_senders.remove(&recipient_tx_id);
+            }
+        }
+    }
+
+    /// Accept the public keys sent by one of the recipients of a batched transaction and pass them on to the relevant
+    /// batch transaction protocol
+    pub async fn accept_recipient_public_keys(
+        &mut self,
+        source_pubkey: CommsPublicKey,
+        public_keys: proto::RecipientPublicKeys,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let public_keys: RecipientPublicKeys = public_keys
+            .try_into()
+            .map_err(TransactionServiceError::InvalidMessageError)?;
+
+        let sender = match self.pending_public_keys_senders.get_mut(&public_keys.tx_id) {
+            None => return Err(TransactionServiceError::TransactionDoesNotExistError),
+            Some(s) => s,
+        };
+
+        sender
+            .send((source_pubkey, public_keys))
+            .await
+            .map_err(|_| TransactionServiceError::ProtocolChannelError)?;
+
+        Ok(())
+    }
+
+    /// Accept the request for a partial signature from the sender of a batched transaction and pass it on to the
+    /// relevant receive transaction protocol
+    pub async fn accept_signature_request(
+        &mut self,
+        source_pubkey: CommsPublicKey,
+        signature_request: proto::MultiRoundSignatureRequest,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let signature_request: MultiRoundSignatureRequest = signature_request
+            .try_into()
+            .map_err(TransactionServiceError::InvalidMessageError)?;
+
+        let sender = match self.signature_request_senders.get_mut(&signature_request.tx_id) {
+            None => return Err(TransactionServiceError::TransactionDoesNotExistError),
+            Some(s) => s,
+        };
+
+        sender
+            .send((source_pubkey, signature_request))
+            .await
+            .map_err(|_| TransactionServiceError::ProtocolChannelError)?;
+
+        Ok(())
+    }
+
     /// Accept the public reply from a recipient and apply the reply to the relevant transaction protocol
     /// # Arguments
     /// 'recipient_reply' - The public response from a recipient with data required to complete the transaction
@@ -814,7 +1207,7 @@ where
     {
         match join_result {
             Ok(id) => {
-                let _ = self.pending_transaction_reply_senders.remove(&id);
+                self.remove_pending_reply_senders(id);
                 let _ = self.send_transaction_cancellation_senders.remove(&id);
                 let _ = self
                     .broadcast_completed_transaction(id, transaction_broadcast_join_handles)
@@ -833,7 +1226,7 @@ where
                 );
             },
             Err(TransactionServiceProtocolError { id, error }) => {
-                let _ = self.pending_transaction_reply_senders.remove(&id);
+                self.remove_pending_reply_senders(id);
                 let _ = self.send_transaction_cancellation_senders.remove(&id);
                 if let TransactionServiceError::Shutdown = error {
                     return;
@@ -864,12 +1257,13 @@ where
         if let Some(cancellation_sender) = self.send_transaction_cancellation_senders.remove(&tx_id) {
             let _ = cancellation_sender.send(());
         }
-        let _ = self.pending_transaction_reply_senders.remove(&tx_id);
+        self.remove_pending_reply_senders(tx_id);
 
         if let Some(cancellation_sender) = self.receiver_transaction_cancellation_senders.remove(&tx_id) {
             let _ = cancellation_sender.send(());
         }
         let _ = self.finalized_transaction_senders.remove(&tx_id);
+        let _ = self.signature_request_senders.remove(&tx_id);
 
         let _ = self
             .event_publisher
@@ -888,6 +1282,61 @@ where
         Ok(())
     }
 
+    /// Replace a completed outbound transaction that has not been mined yet with a transaction that pays the specified
+    /// fee per gram. The replacement spends all the inputs of the original transaction as well as additional inputs
+    /// from this wallet, so the mempool will evict the original in favour of it. The replacement is stored under the
+    /// original `TxId` and the running broadcast protocol will resubmit it to the base node.
+    async fn bump_transaction_fee(
+        &mut self,
+        account: Option<String>,
+        tx_id: TxId,
+        fee_per_gram: MicroTari,
+    ) -> Result<MicroTari, TransactionServiceError>
+    {
+        let completed_tx = self.db.get_completed_transaction(tx_id).await?;
+
+        if completed_tx.direction != TransactionDirection::Outbound || completed_tx.coinbase_block_height.is_some() {
+            return Err(TransactionServiceError::InvalidFeeBump(
+                "Only outbound transactions can have their fee bumped".to_string(),
+            ));
+        }
+        if !(completed_tx.status == TransactionStatus::Completed || completed_tx.status == TransactionStatus::Broadcast)
+        {
+            return Err(TransactionServiceError::InvalidFeeBump(format!(
+                "Transaction is {}",
+                completed_tx.status
+            )));
+        }
+
+        let (fee, replacement_tx) = self
+            .account_output_manager_service(account)
+            .create_fee_bump_transaction(tx_id, completed_tx.transaction, fee_per_gram)
+            .await?;
+
+        self.db
+            .replace_completed_transaction(tx_id, replacement_tx, fee)
+            .await?;
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionFeeBumped(tx_id)))
+            .map_err(|e| {
+                trace!(
+                    target: LOG_TARGET,
+                    "Error sending event because there are no subscribers: {:?}",
+                    e
+                );
+                e
+            });
+
+        info!(
+            target: LOG_TARGET,
+            "Transaction (TxId: {}) fee bumped from {} to {}", tx_id, completed_tx.fee, fee
+        );
+
+        Ok(fee)
+    }
+
     /// Handle a Transaction Cancelled message received from the Comms layer
     pub async fn handle_transaction_cancelled_message(
         &mut self,
@@ -927,6 +1376,17 @@ where
                     target: LOG_TARGET,
                     "Restarting listening for Reply for Pending Outbound Transaction TxId: {}", tx_id
                 );
+                if tx.is_batch() {
+                    self.start_batch_send_protocol(
+                        tx_id,
+                        tx.batch_recipients,
+                        tx.message,
+                        tx.sender_protocol,
+                        TransactionBatchSendProtocolStage::WaitForReplies,
+                        join_handles,
+                    );
+                    continue;
+                }
                 let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
                 let (cancellation_sender, cancellation_receiver) = oneshot::channel();
                 self.pending_transaction_reply_senders.insert(tx_id, tx_reply_sender);
@@ -968,18 +1428,18 @@ where
             .try_into()
             .map_err(TransactionServiceError::InvalidMessageError)?;
 
-        // Currently we will only reply to a Single sender transaction protocol
-        if let TransactionSenderMessage::Single(data) = sender_message.clone() {
+        // Reply to either a Single sender transaction protocol or to the first round of a Multiple sender protocol
+        if let Some(tx_id) = sender_message.tx_id() {
             trace!(
                 target: LOG_TARGET,
                 "Transaction (TxId: {}) received from {}, Trace: {}",
-                data.tx_id,
+                tx_id,
                 source_pubkey,
                 traced_message_tag
             );
 
             // Check if this transaction has already been received.
-            if let Ok(inbound_tx) = self.db.get_pending_inbound_transaction(data.tx_id).await {
+            if let Ok(inbound_tx) = self.db.get_pending_inbound_transaction(tx_id).await {
                 // Check that it is from the same person
                 if inbound_tx.source_public_key != source_pubkey {
                     return Err(TransactionServiceError::InvalidSourcePublicKey);
@@ -1007,7 +1467,6 @@ where
                     target: LOG_TARGET,
                     "A repeated Transaction (TxId: {}) has been received. Reply is being resent.", inbound_tx.tx_id
                 );
-                let tx_id = inbound_tx.tx_id;
                 // Ok we will resend the reply
                 tokio::spawn(send_transaction_reply(
                     inbound_tx,
@@ -1025,33 +1484,35 @@ where
                 return Ok(());
             }
 
-            if self.finalized_transaction_senders.contains_key(&data.tx_id) ||
-                self.receiver_transaction_cancellation_senders.contains_key(&data.tx_id)
+            if self.finalized_transaction_senders.contains_key(&tx_id) ||
+                self.receiver_transaction_cancellation_senders.contains_key(&tx_id)
             {
                 trace!(
                     target: LOG_TARGET,
                     "Transaction (TxId: {}) has already been received, this is probably a repeated message, Trace:
             {}.",
-                    data.tx_id,
+                    tx_id,
                     traced_message_tag
                 );
                 return Err(TransactionServiceError::RepeatedMessageError);
             }
 
             let (tx_finalized_sender, tx_finalized_receiver) = mpsc::channel(100);
+            let (signature_request_sender, signature_request_receiver) = mpsc::channel(100);
             let (cancellation_sender, cancellation_receiver) = oneshot::channel();
-            self.finalized_transaction_senders
-                .insert(data.tx_id, tx_finalized_sender);
+            self.finalized_transaction_senders.insert(tx_id, tx_finalized_sender);
+            self.signature_request_senders.insert(tx_id, signature_request_sender);
             self.receiver_transaction_cancellation_senders
-                .insert(data.tx_id, cancellation_sender);
+                .insert(tx_id, cancellation_sender);
 
             let protocol = TransactionReceiveProtocol::new(
-                data.tx_id,
+                tx_id,
                 source_pubkey,
                 sender_message,
                 TransactionReceiveProtocolStage::Initial,
                 self.resources.clone(),
                 tx_finalized_receiver,
+                signature_request_receiver,
                 cancellation_receiver,
             );
 
@@ -1112,6 +1573,7 @@ where
         match join_result {
             Ok(id) => {
                 let _ = self.finalized_transaction_senders.remove(&id);
+                let _ = self.signature_request_senders.remove(&id);
                 let _ = self.receiver_transaction_cancellation_senders.remove(&id);
 
                 let _ = self
@@ -1133,6 +1595,7 @@ where
             },
             Err(TransactionServiceProtocolError { id, error }) => {
                 let _ = self.finalized_transaction_senders.remove(&id);
+                let _ = self.signature_request_senders.remove(&id);
                 let _ = self.receiver_transaction_cancellation_senders.remove(&id);
                 match error {
                     TransactionServiceError::RepeatedMessageError => debug!(
@@ -1170,8 +1633,10 @@ where
                     "Restarting listening for Transaction Finalize for Pending Inbound Transaction TxId: {}", tx_id
                 );
                 let (tx_finalized_sender, tx_finalized_receiver) = mpsc::channel(100);
+                let (signature_request_sender, signature_request_receiver) = mpsc::channel(100);
                 let (cancellation_sender, cancellation_receiver) = oneshot::channel();
                 self.finalized_transaction_senders.insert(tx_id, tx_finalized_sender);
+                self.signature_request_senders.insert(tx_id, signature_request_sender);
                 self.receiver_transaction_cancellation_senders
                     .insert(tx_id, cancellation_sender);
                 let protocol = TransactionReceiveProtocol::new(
@@ -1181,6 +1646,7 @@ where
                     TransactionReceiveProtocolStage::WaitForFinalize,
                     self.resources.clone(),
                     tx_finalized_receiver,
+                    signature_request_receiver,
                     cancellation_receiver,
                 );
 
@@ -1548,8 +2014,173 @@ where
         Ok(())
     }
 
+    /// Import a partially signed transaction exported by another wallet. Only the stages that are completed by the
+    /// transaction service are accepted here, the signing stages are handled by the wallet that holds the keys.
+    async fn import_partially_signed_transaction(
+        &mut self,
+        pst: PartiallySignedTransaction,
+        receive_transaction_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<Option<PartiallySignedTransaction>, TransactionServiceError>
+    {
+        match pst.stage.clone() {
+            PartiallySignedTransactionStage::SenderMessage(data) => self
+                .receive_partially_signed_transaction(pst, *data, receive_transaction_join_handles)
+                .await
+                .map(Some),
+            PartiallySignedTransactionStage::Finalized(protocol_tx_id, tx) => {
+                if &pst.sender_public_key == self.node_identity.public_key() {
+                    self.complete_unsigned_transaction(pst, protocol_tx_id, *tx, transaction_broadcast_join_handles)
+                        .await?;
+                } else {
+                    let sender = self
+                        .finalized_transaction_senders
+                        .get_mut(&protocol_tx_id)
+                        .ok_or(TransactionServiceError::TransactionDoesNotExistError)?;
+                    sender
+                        .send((pst.sender_public_key, protocol_tx_id, *tx))
+                        .await
+                        .map_err(|_| TransactionServiceError::ProtocolChannelError)?;
+                }
+                Ok(None)
+            },
+            stage => Err(TransactionServiceError::InvalidPartiallySignedTransaction(format!(
+                "The {} stage must be signed by the sending wallet",
+                stage
+            ))),
+        }
+    }
+
+    /// Accept the sender message of a partially signed transaction as its recipient. The pending inbound transaction
+    /// is stored and waits for the finalized transaction as if the sender message had arrived over the network.
+    async fn receive_partially_signed_transaction(
+        &mut self,
+        pst: PartiallySignedTransaction,
+        sender_data: SingleRoundSenderData,
+        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+    ) -> Result<PartiallySignedTransaction, TransactionServiceError>
+    {
+        let tx_id = sender_data.tx_id;
+        if self.db.transaction_exists(tx_id).await? {
+            return Err(TransactionServiceError::RepeatedMessageError);
+        }
+
+        let rtp = self
+            .output_manager_service
+            .get_recipient_transaction(TransactionSenderMessage::Single(Box::new(sender_data)))
+            .await?;
+        let signed_data = rtp.get_signed_data()?.clone();
+        let inbound_transaction = InboundTransaction::new(
+            tx_id,
+            pst.sender_public_key.clone(),
+            pst.amount,
+            rtp,
+            TransactionStatus::Pending,
+            pst.message.clone(),
+            Utc::now().naive_utc(),
+        );
+        self.db
+            .add_pending_inbound_transaction(tx_id, inbound_transaction)
+            .await?;
+        // The reply is carried back in the exported file so it counts as sent
+        self.db.increment_send_count(tx_id).await?;
+
+        let (tx_finalized_sender, tx_finalized_receiver) = mpsc::channel(100);
+        let (signature_request_sender, signature_request_receiver) = mpsc::channel(100);
+        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
+        self.finalized_transaction_senders.insert(tx_id, tx_finalized_sender);
+        self.signature_request_senders.insert(tx_id, signature_request_sender);
+        self.receiver_transaction_cancellation_senders
+            .insert(tx_id, cancellation_sender);
+        let protocol = TransactionReceiveProtocol::new(
+            tx_id,
+            pst.sender_public_key.clone(),
+            TransactionSenderMessage::None,
+            TransactionReceiveProtocolStage::WaitForFinalize,
+            self.resources.clone(),
+            tx_finalized_receiver,
+            signature_request_receiver,
+            cancellation_receiver,
+        );
+        join_handles.push(tokio::spawn(protocol.execute()));
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::ReceivedTransaction(tx_id)));
+        info!(
+            target: LOG_TARGET,
+            "Partially signed transaction (TxId: {}) received from {}", tx_id, pst.sender_public_key
+        );
+
+        Ok(pst.with_stage(PartiallySignedTransactionStage::RecipientSigned(Box::new(signed_data))))
+    }
+
+    /// Record and broadcast a transaction that this wallet prepared unsigned and that was signed by another wallet
+    /// holding the spending keys. The finalized transaction is also sent to the recipient in case they are waiting for
+    /// it over the network.
+    async fn complete_unsigned_transaction(
+        &mut self,
+        pst: PartiallySignedTransaction,
+        protocol_tx_id: TxId,
+        tx: Transaction,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let tx_id = pst.tx_id;
+        if self.db.transaction_exists(tx_id).await? {
+            return Err(TransactionServiceError::RepeatedMessageError);
+        }
+        tx.validate_internal_consistency(&self.resources.factories, None)?;
+
+        self.output_manager_service
+            .complete_unsigned_transaction(tx_id, tx.clone())
+            .await?;
+        let fee = tx.body.get_total_fee();
+        self.db
+            .insert_completed_transaction(
+                tx_id,
+                CompletedTransaction::new(
+                    tx_id,
+                    self.node_identity.public_key().clone(),
+                    pst.recipient_public_key.clone(),
+                    pst.amount,
+                    fee,
+                    tx.clone(),
+                    TransactionStatus::Completed,
+                    pst.message,
+                    Utc::now().naive_utc(),
+                    TransactionDirection::Outbound,
+                    None,
+                ),
+            )
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Partially signed transaction (TxId: {}) to {} completed", tx_id, pst.recipient_public_key
+        );
+
+        tokio::spawn(send_finalized_transaction_message(
+            protocol_tx_id,
+            tx,
+            pst.recipient_public_key,
+            self.resources.outbound_message_service.clone(),
+            self.resources.config.direct_send_timeout,
+            self.resources.config.transaction_routing_mechanism,
+        ));
+        self.complete_send_transaction_protocol(Ok(tx_id), transaction_broadcast_join_handles)
+            .await;
+        Ok(())
+    }
+
     async fn generate_coinbase_transaction(
         &mut self,
+        account: Option<String>,
         reward: MicroTari,
         fees: MicroTari,
         block_height: u64,
@@ -1582,7 +2213,7 @@ where
                 // otherwise create a new coinbase tx
                 let tx_id = OsRng.next_u64();
                 let tx = self
-                    .output_manager_service
+                    .account_output_manager_service(account)
                     .get_coinbase_transaction(tx_id, reward, fees, block_height)
                     .await?;
 
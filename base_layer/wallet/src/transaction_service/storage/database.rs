@@ -25,6 +25,7 @@ use crate::{
     transaction_service::{
         error::TransactionStorageError,
         storage::models::{
+            BatchRecipient,
             CompletedTransaction,
             InboundTransaction,
             OutboundTransaction,
@@ -46,7 +47,13 @@ use std::{
     sync::Arc,
 };
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::{tari_amount::MicroTari, transaction::Transaction, types::BlindingFactor};
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction::Transaction,
+    types::BlindingFactor,
+    ReceiverTransactionProtocol,
+    SenderTransactionProtocol,
+};
 
 const LOG_TARGET: &str = "wallet::transaction_service::database";
 
@@ -99,6 +106,27 @@ pub trait TransactionBackend: Send + Sync + Clone {
     ) -> Result<CommsPublicKey, TransactionStorageError>;
     /// Mark a pending transaction direct send attempt as a success
     fn mark_direct_send_success(&self, tx_id: TxId) -> Result<(), TransactionStorageError>;
+    /// Update the sender protocol and the state of each recipient of a pending batched outbound transaction
+    fn update_pending_batch_transaction(
+        &self,
+        tx_id: TxId,
+        sender_protocol: SenderTransactionProtocol,
+        batch_recipients: Vec<BatchRecipient>,
+    ) -> Result<(), TransactionStorageError>;
+    /// Update the receiver protocol of a pending inbound transaction
+    fn update_pending_inbound_receiver_protocol(
+        &self,
+        tx_id: TxId,
+        receiver_protocol: ReceiverTransactionProtocol,
+    ) -> Result<(), TransactionStorageError>;
+    /// Replace the transaction and fee of a completed transaction, used when the fee of the transaction is bumped. The
+    /// status of the transaction is returned to `Completed` until the replacement is broadcast.
+    fn replace_completed_transaction(
+        &self,
+        tx_id: TxId,
+        transaction: Transaction,
+        fee: MicroTari,
+    ) -> Result<(), TransactionStorageError>;
     /// Cancel coinbase transactions at a specific block height
     fn cancel_coinbase_transaction_at_block_height(&self, block_height: u64) -> Result<(), TransactionStorageError>;
     /// Find coinbase transaction at a specific block height for a given amount
@@ -592,6 +620,52 @@ where T: TransactionBackend + 'static
         Ok(())
     }
 
+    pub async fn update_pending_batch_transaction(
+        &self,
+        tx_id: TxId,
+        sender_protocol: SenderTransactionProtocol,
+        batch_recipients: Vec<BatchRecipient>,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.update_pending_batch_transaction(tx_id, sender_protocol, batch_recipients)
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn update_pending_inbound_receiver_protocol(
+        &self,
+        tx_id: TxId,
+        receiver_protocol: ReceiverTransactionProtocol,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.update_pending_inbound_receiver_protocol(tx_id, receiver_protocol)
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// Replace the transaction of the specified completed transaction with a replacement that pays a higher fee
+    pub async fn replace_completed_transaction(
+        &self,
+        tx_id: TxId,
+        transaction: Transaction,
+        fee: MicroTari,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.replace_completed_transaction(tx_id, transaction, fee))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
     /// Indicated that the specified completed transaction has been broadcast into the mempool
     pub async fn broadcast_completed_transaction(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
         let db_clone = self.db.clone();
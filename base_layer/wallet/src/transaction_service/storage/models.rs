@@ -151,6 +151,8 @@ pub struct OutboundTransaction {
     pub direct_send_success: bool,
     pub send_count: u32,
     pub last_send_timestamp: Option<NaiveDateTime>,
+    /// The recipients of a batched transaction, empty if the transaction has a single recipient
+    pub batch_recipients: Vec<BatchRecipient>,
 }
 
 impl OutboundTransaction {
@@ -180,8 +182,45 @@ impl OutboundTransaction {
             direct_send_success,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         }
     }
+
+    /// Returns true if this transaction is being negotiated with more than one recipient
+    pub fn is_batch(&self) -> bool {
+        !self.batch_recipients.is_empty()
+    }
+}
+
+/// The progress of a single recipient through the multi-round negotiation of a batched transaction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BatchRecipientStatus {
+    /// The recipient has been sent the transaction and has not yet replied with their public keys
+    AwaitingPublicKeys,
+    /// The recipient has provided their public keys and has been asked for their partial signature
+    AwaitingSignature,
+    /// The recipient's output and partial signature have been added to the transaction
+    Signed,
+}
+
+impl Display for BatchRecipientStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            BatchRecipientStatus::AwaitingPublicKeys => write!(f, "Awaiting Public Keys"),
+            BatchRecipientStatus::AwaitingSignature => write!(f, "Awaiting Signature"),
+            BatchRecipientStatus::Signed => write!(f, "Signed"),
+        }
+    }
+}
+
+/// One of the recipients of a batched transaction. Each recipient negotiates using their own `tx_id`, which is
+/// different from the `tx_id` of the batched transaction itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchRecipient {
+    pub tx_id: TxId,
+    pub public_key: CommsPublicKey,
+    pub amount: MicroTari,
+    pub status: BatchRecipientStatus,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -305,6 +344,7 @@ impl From<CompletedTransaction> for OutboundTransaction {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         }
     }
 }
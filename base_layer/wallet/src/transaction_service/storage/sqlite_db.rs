@@ -29,6 +29,7 @@ use crate::{
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, TransactionBackend, WriteOperation},
             models::{
+                BatchRecipient,
                 CompletedTransaction,
                 InboundTransaction,
                 OutboundTransaction,
@@ -51,7 +52,13 @@ use std::{
     sync::{Arc, MutexGuard, RwLock},
 };
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::{tari_amount::MicroTari, types::PublicKey};
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction::Transaction,
+    types::PublicKey,
+    ReceiverTransactionProtocol,
+    SenderTransactionProtocol,
+};
 use tari_crypto::tari_utilities::{
     hex::{from_hex, Hex},
     ByteArray,
@@ -622,6 +629,7 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                                 sender_protocol: None,
                                 send_count: None,
                                 last_send_timestamp: None,
+                                batch_recipients: None,
                             },
                             &(*conn),
                         )?;
@@ -636,6 +644,114 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
         Ok(())
     }
 
+    fn update_pending_batch_transaction(
+        &self,
+        tx_id: u64,
+        sender_protocol: SenderTransactionProtocol,
+        batch_recipients: Vec<BatchRecipient>,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        let mut outbound_tx = match OutboundTransactionSql::find_by_cancelled(tx_id, false, &(*conn)) {
+            Ok(mut v) => {
+                self.decrypt_if_necessary(&mut v)?;
+                OutboundTransaction::try_from(v)?
+            },
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValuesNotFound);
+            },
+            Err(e) => return Err(e),
+        };
+        outbound_tx.sender_protocol = sender_protocol;
+        outbound_tx.batch_recipients = batch_recipients;
+        let mut outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx)?;
+        self.encrypt_if_necessary(&mut outbound_tx_sql)?;
+        outbound_tx_sql.update(
+            UpdateOutboundTransactionSql {
+                cancelled: None,
+                direct_send_success: None,
+                sender_protocol: Some(outbound_tx_sql.sender_protocol.clone()),
+                send_count: None,
+                last_send_timestamp: None,
+                batch_recipients: outbound_tx_sql.batch_recipients.clone(),
+            },
+            &(*conn),
+        )
+    }
+
+    fn update_pending_inbound_receiver_protocol(
+        &self,
+        tx_id: u64,
+        receiver_protocol: ReceiverTransactionProtocol,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        let mut inbound_tx = match InboundTransactionSql::find_by_cancelled(tx_id, false, &(*conn)) {
+            Ok(mut v) => {
+                self.decrypt_if_necessary(&mut v)?;
+                InboundTransaction::try_from(v)?
+            },
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValuesNotFound);
+            },
+            Err(e) => return Err(e),
+        };
+        inbound_tx.receiver_protocol = receiver_protocol;
+        let mut inbound_tx_sql = InboundTransactionSql::try_from(inbound_tx)?;
+        self.encrypt_if_necessary(&mut inbound_tx_sql)?;
+        inbound_tx_sql.update(
+            UpdateInboundTransactionSql {
+                cancelled: None,
+                direct_send_success: None,
+                receiver_protocol: Some(inbound_tx_sql.receiver_protocol.clone()),
+                send_count: None,
+                last_send_timestamp: None,
+            },
+            &(*conn),
+        )
+    }
+
+    fn replace_completed_transaction(
+        &self,
+        tx_id: u64,
+        transaction: Transaction,
+        fee: MicroTari,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        let mut completed_tx = match CompletedTransactionSql::find_by_cancelled(tx_id, false, &(*conn)) {
+            Ok(mut v) => {
+                self.decrypt_if_necessary(&mut v)?;
+                CompletedTransaction::try_from(v)?
+            },
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValueNotFound(DbKey::CompletedTransaction(
+                    tx_id,
+                )));
+            },
+            Err(e) => return Err(e),
+        };
+        completed_tx.transaction = transaction;
+        completed_tx.fee = fee;
+        let mut completed_tx_sql = CompletedTransactionSql::try_from(completed_tx)?;
+        self.encrypt_if_necessary(&mut completed_tx_sql)?;
+        completed_tx_sql.update(
+            UpdateCompletedTransactionSql {
+                // The replacement has not been broadcast yet
+                status: Some(TransactionStatus::Completed as i32),
+                timestamp: None,
+                cancelled: None,
+                direction: None,
+                transaction_protocol: Some(completed_tx_sql.transaction_protocol.clone()),
+                fee: Some(completed_tx_sql.fee),
+                send_count: None,
+                last_send_timestamp: None,
+                valid: None,
+            },
+            &(*conn),
+        )
+    }
+
     #[cfg(feature = "test_harness")]
     fn update_completed_transaction_timestamp(
         &self,
@@ -808,6 +924,7 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                 cancelled: None,
                 direction: None,
                 transaction_protocol: None,
+                fee: None,
                 send_count: Some(tx.send_count + 1),
                 last_send_timestamp: Some(Some(Utc::now().naive_utc())),
                 valid: None,
@@ -820,6 +937,7 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                 sender_protocol: None,
                 send_count: Some(tx.send_count + 1),
                 last_send_timestamp: Some(Some(Utc::now().naive_utc())),
+                batch_recipients: None,
             };
             tx.update(update, &conn)?;
         } else if let Ok(tx) = InboundTransactionSql::find_by_cancelled(tx_id, false, &conn) {
@@ -1100,6 +1218,7 @@ struct OutboundTransactionSql {
     direct_send_success: i32,
     send_count: i32,
     last_send_timestamp: Option<NaiveDateTime>,
+    batch_recipients: Option<String>,
 }
 
 impl OutboundTransactionSql {
@@ -1182,6 +1301,7 @@ impl OutboundTransactionSql {
                 sender_protocol: None,
                 send_count: None,
                 last_send_timestamp: None,
+                batch_recipients: None,
             },
             conn,
         )
@@ -1195,6 +1315,7 @@ impl OutboundTransactionSql {
                 sender_protocol: Some(self.sender_protocol.clone()),
                 send_count: None,
                 last_send_timestamp: None,
+                batch_recipients: None,
             },
             conn,
         )
@@ -1237,6 +1358,11 @@ impl TryFrom<OutboundTransaction> for OutboundTransactionSql {
             direct_send_success: o.direct_send_success as i32,
             send_count: o.send_count as i32,
             last_send_timestamp: o.last_send_timestamp,
+            batch_recipients: if o.batch_recipients.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&o.batch_recipients)?)
+            },
         })
     }
 }
@@ -1259,6 +1385,10 @@ impl TryFrom<OutboundTransactionSql> for OutboundTransaction {
             direct_send_success: o.direct_send_success != 0,
             send_count: o.send_count as u32,
             last_send_timestamp: o.last_send_timestamp,
+            batch_recipients: match o.batch_recipients {
+                None => Vec::new(),
+                Some(r) => serde_json::from_str(&r)?,
+            },
         })
     }
 }
@@ -1271,6 +1401,7 @@ pub struct UpdateOutboundTransactionSql {
     sender_protocol: Option<String>,
     send_count: Option<i32>,
     last_send_timestamp: Option<Option<NaiveDateTime>>,
+    batch_recipients: Option<String>,
 }
 
 /// A structure to represent a Sql compatible version of the CompletedTransaction struct
@@ -1385,6 +1516,7 @@ impl CompletedTransactionSql {
                 cancelled: Some(1i32),
                 direction: None,
                 transaction_protocol: None,
+                fee: None,
                 send_count: None,
                 last_send_timestamp: None,
                 valid: None,
@@ -1403,6 +1535,7 @@ impl CompletedTransactionSql {
                 cancelled: None,
                 direction: None,
                 transaction_protocol: None,
+                fee: None,
                 send_count: None,
                 last_send_timestamp: None,
                 valid: None,
@@ -1421,6 +1554,7 @@ impl CompletedTransactionSql {
                 cancelled: None,
                 direction: None,
                 transaction_protocol: None,
+                fee: None,
                 send_count: None,
                 last_send_timestamp: None,
                 valid: None,
@@ -1439,6 +1573,7 @@ impl CompletedTransactionSql {
                 cancelled: None,
                 direction: None,
                 transaction_protocol: None,
+                fee: None,
                 send_count: None,
                 last_send_timestamp: None,
                 valid: Some(valid as i32),
@@ -1457,6 +1592,7 @@ impl CompletedTransactionSql {
                 cancelled: None,
                 direction: None,
                 transaction_protocol: Some(self.transaction_protocol.clone()),
+                fee: None,
                 send_count: None,
                 last_send_timestamp: None,
                 valid: None,
@@ -1557,6 +1693,7 @@ pub struct UpdateCompletedTransactionSql {
     cancelled: Option<i32>,
     direction: Option<i32>,
     transaction_protocol: Option<String>,
+    fee: Option<i64>,
     send_count: Option<i32>,
     last_send_timestamp: Option<Option<NaiveDateTime>>,
     valid: Option<i32>,
@@ -1571,6 +1708,7 @@ impl From<UpdateCompletedTransaction> for UpdateCompletedTransactionSql {
             cancelled: u.cancelled.map(|c| c as i32),
             direction: u.direction.map(|d| d as i32),
             transaction_protocol: None,
+            fee: None,
             send_count: u.send_count.map(|c| c as i32),
             last_send_timestamp: u.last_send_timestamp,
             valid: u.valid.map(|c| c as i32),
@@ -1668,6 +1806,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         };
 
         let outbound_tx2 = OutboundTransactionSql::try_from(OutboundTransaction {
@@ -1683,6 +1822,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         })
         .unwrap();
 
@@ -1976,6 +2116,7 @@ mod test {
                     cancelled: None,
                     direction: None,
                     transaction_protocol: None,
+                    fee: None,
                     send_count: None,
                     last_send_timestamp: None,
                     valid: None,
@@ -2037,6 +2178,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         };
 
         let mut outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx.clone()).unwrap();
@@ -2117,6 +2259,7 @@ mod test {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         };
         let outbound_tx_sql = OutboundTransactionSql::try_from(outbound_tx).unwrap();
         outbound_tx_sql.commit(&conn).unwrap();
@@ -39,61 +39,100 @@ use tari_core::transactions::transaction_protocol::proto;
 const LOG_TARGET: &str = "wallet::transaction_service::tasks::send_transaction_reply";
 
 /// A task to resend a transaction reply message if a repeated Send Transaction is received from a Sender
-/// either directly, via Store-and-forward or both as per config setting.
+/// either directly, via Store-and-forward or both as per config setting. If the recipient is part of a multi-round
+/// transaction and is still waiting for the Sender's signature request the reply will contain its public keys,
+/// otherwise the reply contains the recipient's signed data.
 pub async fn send_transaction_reply(
     inbound_transaction: InboundTransaction,
-    mut outbound_message_service: OutboundMessageRequester,
+    outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
 ) -> Result<bool, TransactionServiceError>
 {
-    let send_result;
-    let recipient_reply = inbound_transaction.receiver_protocol.get_signed_data()?.clone();
-    let proto_message: proto::RecipientSignedMessage = recipient_reply.into();
+    let destination_pubkey = inbound_transaction.source_public_key.clone();
+    if inbound_transaction.receiver_protocol.is_awaiting_signature_request() {
+        let public_keys = inbound_transaction.receiver_protocol.get_public_keys()?;
+        let proto_message: proto::RecipientPublicKeys = public_keys.into();
+        send_reply(
+            inbound_transaction.tx_id,
+            destination_pubkey,
+            TariMessageType::ReceiverPublicKeysReply,
+            proto_message,
+            outbound_message_service,
+            direct_send_timeout,
+            transaction_routing_mechanism,
+        )
+        .await
+    } else {
+        let recipient_reply = inbound_transaction.receiver_protocol.get_signed_data()?.clone();
+        let proto_message: proto::RecipientSignedMessage = recipient_reply.into();
+        send_reply(
+            inbound_transaction.tx_id,
+            destination_pubkey,
+            TariMessageType::ReceiverPartialTransactionReply,
+            proto_message,
+            outbound_message_service,
+            direct_send_timeout,
+            transaction_routing_mechanism,
+        )
+        .await
+    }
+}
 
+async fn send_reply<T: prost::Message + Clone>(
+    tx_id: TxId,
+    destination_pubkey: CommsPublicKey,
+    message_type: TariMessageType,
+    proto_message: T,
+    mut outbound_message_service: OutboundMessageRequester,
+    direct_send_timeout: Duration,
+    transaction_routing_mechanism: TransactionRoutingMechanism,
+) -> Result<bool, TransactionServiceError>
+{
     match transaction_routing_mechanism {
         TransactionRoutingMechanism::DirectOnly | TransactionRoutingMechanism::DirectAndStoreAndForward => {
-            send_result = send_transaction_reply_direct(
-                inbound_transaction,
+            send_transaction_reply_direct(
+                tx_id,
+                destination_pubkey,
+                message_type,
+                proto_message,
                 outbound_message_service,
                 direct_send_timeout,
                 transaction_routing_mechanism,
             )
-            .await?;
+            .await
         },
         TransactionRoutingMechanism::StoreAndForwardOnly => {
-            send_result = send_transaction_reply_store_and_forward(
-                inbound_transaction.tx_id,
-                inbound_transaction.source_public_key,
-                proto_message.clone(),
+            send_transaction_reply_store_and_forward(
+                tx_id,
+                destination_pubkey,
+                message_type,
+                proto_message,
                 &mut outbound_message_service,
             )
-            .await?;
+            .await
         },
-    };
-
-    Ok(send_result)
+    }
 }
 
 /// A task to resend a transaction reply message if a repeated Send Transaction is received from a Sender
-pub async fn send_transaction_reply_direct(
-    inbound_transaction: InboundTransaction,
+async fn send_transaction_reply_direct<T: prost::Message + Clone>(
+    tx_id: TxId,
+    destination_pubkey: CommsPublicKey,
+    message_type: TariMessageType,
+    proto_message: T,
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
 ) -> Result<bool, TransactionServiceError>
 {
-    let recipient_reply = inbound_transaction.receiver_protocol.get_signed_data()?.clone();
-
     let mut store_and_forward_send_result = false;
     let mut direct_send_result = false;
 
-    let tx_id = inbound_transaction.tx_id;
-    let proto_message: proto::RecipientSignedMessage = recipient_reply.into();
     match outbound_message_service
         .send_direct(
-            inbound_transaction.source_public_key.clone(),
-            OutboundDomainMessage::new(TariMessageType::ReceiverPartialTransactionReply, proto_message.clone()),
+            destination_pubkey.clone(),
+            OutboundDomainMessage::new(message_type, proto_message.clone()),
         )
         .await
     {
@@ -102,7 +141,7 @@ pub async fn send_transaction_reply_direct(
                 if wait_on_dial(
                     send_states,
                     tx_id,
-                    inbound_transaction.source_public_key.clone(),
+                    destination_pubkey.clone(),
                     "Transaction Reply",
                     direct_send_timeout,
                 )
@@ -116,12 +155,13 @@ pub async fn send_transaction_reply_direct(
                     "Direct Send reply result was {}. Sending SAF for TxId: {} to recipient with Public Key: {}",
                     direct_send_result,
                     tx_id,
-                    inbound_transaction.source_public_key,
+                    destination_pubkey,
                 );
                 if transaction_routing_mechanism == TransactionRoutingMechanism::DirectAndStoreAndForward {
                     store_and_forward_send_result = send_transaction_reply_store_and_forward(
                         tx_id,
-                        inbound_transaction.source_public_key,
+                        destination_pubkey.clone(),
+                        message_type,
                         proto_message.clone(),
                         &mut outbound_message_service,
                     )
@@ -136,7 +176,8 @@ pub async fn send_transaction_reply_direct(
                 if transaction_routing_mechanism == TransactionRoutingMechanism::DirectAndStoreAndForward {
                     store_and_forward_send_result = send_transaction_reply_store_and_forward(
                         tx_id,
-                        inbound_transaction.source_public_key.clone(),
+                        destination_pubkey.clone(),
+                        message_type,
                         proto_message.clone(),
                         &mut outbound_message_service,
                     )
@@ -147,7 +188,8 @@ pub async fn send_transaction_reply_direct(
                 if transaction_routing_mechanism == TransactionRoutingMechanism::DirectAndStoreAndForward {
                     store_and_forward_send_result = send_transaction_reply_store_and_forward(
                         tx_id,
-                        inbound_transaction.source_public_key.clone(),
+                        destination_pubkey.clone(),
+                        message_type,
                         proto_message.clone(),
                         &mut outbound_message_service,
                     )
@@ -159,12 +201,12 @@ pub async fn send_transaction_reply_direct(
                         if let SendMessageResponse::Queued(send_states) = send_msg_response {
                             debug!(
                                 target: LOG_TARGET,
-                                "Discovery of {} completed for TxID: {}", inbound_transaction.source_public_key, tx_id
+                                "Discovery of {} completed for TxID: {}", destination_pubkey, tx_id
                             );
                             direct_send_result = wait_on_dial(
                                 send_states,
                                 tx_id,
-                                inbound_transaction.source_public_key.clone(),
+                                destination_pubkey.clone(),
                                 "Transaction Reply",
                                 direct_send_timeout,
                             )
@@ -187,10 +229,11 @@ pub async fn send_transaction_reply_direct(
     Ok(direct_send_result || store_and_forward_send_result)
 }
 
-async fn send_transaction_reply_store_and_forward(
+async fn send_transaction_reply_store_and_forward<T: prost::Message>(
     tx_id: TxId,
     destination_pubkey: CommsPublicKey,
-    msg: proto::RecipientSignedMessage,
+    message_type: TariMessageType,
+    msg: T,
     outbound_message_service: &mut OutboundMessageRequester,
 ) -> Result<bool, TransactionServiceError>
 {
@@ -199,7 +242,7 @@ async fn send_transaction_reply_store_and_forward(
             NodeId::from_public_key(&destination_pubkey),
             OutboundEncryption::EncryptFor(Box::new(destination_pubkey.clone())),
             vec![],
-            OutboundDomainMessage::new(TariMessageType::ReceiverPartialTransactionReply, msg),
+            OutboundDomainMessage::new(message_type, msg),
         )
         .await
     {
@@ -0,0 +1,107 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{error::UtxoScannerError, service::ScannedBlock};
+use futures::{stream::Fuse, StreamExt};
+use std::sync::Arc;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_service_framework::reply_channel::SenderService;
+use tokio::sync::broadcast;
+use tower::Service;
+
+pub type UtxoScannerEventSender = broadcast::Sender<Arc<UtxoScannerEvent>>;
+pub type UtxoScannerEventReceiver = broadcast::Receiver<Arc<UtxoScannerEvent>>;
+
+/// API Request enum
+#[derive(Debug)]
+pub enum UtxoScannerRequest {
+    GetLastScannedBlock,
+    RescanFromGenesis,
+}
+
+/// API Response enum
+#[derive(Debug)]
+pub enum UtxoScannerResponse {
+    LastScannedBlock(Option<ScannedBlock>),
+    RescanScheduled,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UtxoScannerEvent {
+    /// A scan has started from the given output MMR position up to the chain tip at `tip_height`
+    ScanningStarted { start_index: u64, tip_height: u64 },
+    /// The chain has reorged since the last scan, scanning will resume from the block at `fork_height`
+    ReorgDetected { fork_height: u64 },
+    /// Outputs belonging to this wallet were found and imported
+    OutputsRecovered { num_outputs: u64, value: MicroTari },
+    /// Progress through the output MMR of the current scan
+    Progress { current_index: u64, total_index: u64 },
+    /// The scan completed up to the block at `height`
+    ScanningCompleted {
+        height: u64,
+        num_recovered: u64,
+        value_recovered: MicroTari,
+    },
+    /// The scan failed, it will be retried when the base node state next changes
+    ScanningFailed(String),
+}
+
+/// The UTXO Scanner Handle is a struct that contains the interfaces used to communicate with a running UTXO Scanner
+/// Service
+#[derive(Clone)]
+pub struct UtxoScannerHandle {
+    handle: SenderService<UtxoScannerRequest, Result<UtxoScannerResponse, UtxoScannerError>>,
+    event_stream_sender: UtxoScannerEventSender,
+}
+
+impl UtxoScannerHandle {
+    pub fn new(
+        handle: SenderService<UtxoScannerRequest, Result<UtxoScannerResponse, UtxoScannerError>>,
+        event_stream_sender: UtxoScannerEventSender,
+    ) -> Self
+    {
+        Self {
+            handle,
+            event_stream_sender,
+        }
+    }
+
+    pub fn get_event_stream_fused(&self) -> Fuse<UtxoScannerEventReceiver> {
+        self.event_stream_sender.subscribe().fuse()
+    }
+
+    /// Returns the most recent block that the scanner has scanned the chain up to, if any
+    pub async fn get_last_scanned_block(&mut self) -> Result<Option<ScannedBlock>, UtxoScannerError> {
+        match self.handle.call(UtxoScannerRequest::GetLastScannedBlock).await?? {
+            UtxoScannerResponse::LastScannedBlock(block) => Ok(block),
+            _ => Err(UtxoScannerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Discard the scanning progress so that the next scan starts from the genesis block
+    pub async fn rescan_from_genesis(&mut self) -> Result<(), UtxoScannerError> {
+        match self.handle.call(UtxoScannerRequest::RescanFromGenesis).await?? {
+            UtxoScannerResponse::RescanScheduled => Ok(()),
+            _ => Err(UtxoScannerError::UnexpectedApiResponse),
+        }
+    }
+}
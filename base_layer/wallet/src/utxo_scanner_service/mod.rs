@@ -0,0 +1,107 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+pub mod error;
+pub mod handle;
+pub mod service;
+
+use crate::{
+    base_node_service::handle::BaseNodeServiceHandle,
+    output_manager_service::handle::OutputManagerHandle,
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+    utxo_scanner_service::{
+        handle::UtxoScannerHandle,
+        service::{UtxoScannerResources, UtxoScannerService},
+    },
+};
+use futures::{future, Future};
+use log::*;
+use std::sync::Arc;
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeIdentity};
+use tari_service_framework::{
+    reply_channel,
+    ServiceInitializationError,
+    ServiceInitializer,
+    ServiceInitializerContext,
+};
+use tokio::sync::broadcast;
+
+const LOG_TARGET: &str = "wallet::utxo_scanner_service";
+
+pub struct UtxoScannerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    db: WalletDatabase<T>,
+    node_identity: Arc<NodeIdentity>,
+}
+
+impl<T> UtxoScannerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(db: WalletDatabase<T>, node_identity: Arc<NodeIdentity>) -> Self {
+        Self { db, node_identity }
+    }
+}
+
+impl<T> ServiceInitializer for UtxoScannerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    type Future = impl Future<Output = Result<(), ServiceInitializationError>>;
+
+    fn initialize(&mut self, context: ServiceInitializerContext) -> Self::Future {
+        info!(target: LOG_TARGET, "Wallet UTXO scanner service initializing.");
+
+        let (sender, request_stream) = reply_channel::unbounded();
+        let (event_publisher, _) = broadcast::channel(200);
+
+        // Register handle before waiting for handles to be ready
+        context.register_handle(UtxoScannerHandle::new(sender, event_publisher.clone()));
+
+        let db = self.db.clone();
+        let node_identity = self.node_identity.clone();
+
+        context.spawn_when_ready(move |handles| async move {
+            let resources = UtxoScannerResources {
+                db,
+                connectivity: handles.expect_handle::<ConnectivityRequester>(),
+                output_manager_service: handles.expect_handle::<OutputManagerHandle>(),
+                transaction_service: handles.expect_handle::<TransactionServiceHandle>(),
+                node_identity,
+                event_publisher,
+            };
+
+            let service = UtxoScannerService::new(
+                request_stream,
+                resources,
+                handles.expect_handle::<BaseNodeServiceHandle>(),
+                handles.get_shutdown_signal(),
+            )
+            .start();
+            futures::pin_mut!(service);
+            future::select(service, handles.get_shutdown_signal()).await;
+            info!(target: LOG_TARGET, "Wallet UTXO Scanner Service shutdown");
+        });
+
+        future::ready(Ok(()))
+    }
+}
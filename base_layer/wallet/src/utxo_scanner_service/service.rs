@@ -0,0 +1,559 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    error::UtxoScannerError,
+    handle::{UtxoScannerEvent, UtxoScannerEventSender, UtxoScannerRequest, UtxoScannerResponse},
+};
+use crate::{
+    base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
+    output_manager_service::{
+        error::{OutputManagerError, OutputManagerStorageError},
+        handle::OutputManagerHandle,
+        protocols::txo_validation_protocol::TxoValidationType,
+    },
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+    types::ValidationRetryStrategy,
+};
+use chrono::Local;
+use futures::{pin_mut, stream::FuturesUnordered, StreamExt};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::{cmp, convert::TryFrom, sync::Arc};
+use tari_common_types::chain_metadata::ChainMetadata;
+use tari_comms::{
+    connectivity::ConnectivityRequester,
+    peer_manager::{NodeIdentity, Peer},
+    protocol::rpc::RpcError,
+};
+use tari_core::{
+    base_node::sync::rpc::BaseNodeSyncRpcClient,
+    blocks::BlockHeader,
+    proto::base_node::{FindChainSplitRequest, SyncUtxosRequest},
+    tari_utilities::{hex::Hex, Hashable},
+    transactions::{tari_amount::MicroTari, transaction::TransactionOutput, types::HashOutput},
+};
+use tari_service_framework::reply_channel::Receiver;
+use tari_shutdown::ShutdownSignal;
+use tokio::task::JoinHandle;
+
+const LOG_TARGET: &str = "wallet::utxo_scanner_service::service";
+
+/// The number of previously scanned blocks that are kept to find the fork point after a reorg. If a reorg is deeper
+/// than this history the chain is rescanned from the genesis block.
+const SCANNED_BLOCK_HISTORY_SIZE: usize = 500;
+/// The number of blocks that are scanned before the progress of the scan is saved
+const SCAN_CHUNK_SIZE: u64 = 1000;
+/// The number of times the validation of the wallet's unspent outputs is retried after a reorg
+const REORG_VALIDATION_RETRIES: u8 = 5;
+
+/// A block at the tip of the chain that the scanner has scanned up to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScannedBlock {
+    pub height: u64,
+    pub header_hash: HashOutput,
+    /// The size of the output MMR at this block, this is the output MMR position that the next scan starts from
+    pub output_mmr_size: u64,
+}
+
+/// The UTXO scanner service scans the blockchain for outputs that can be rewound with this wallet's rewind keys and
+/// imports them. A scan runs whenever the base node reports a new chain tip and resumes from the last scanned block
+/// stored in the wallet database, so funds sent to this wallet from other devices, or to a restored wallet, are found
+/// without any user interaction.
+pub struct UtxoScannerService<T>
+where T: WalletBackend + 'static
+{
+    request_stream: Option<Receiver<UtxoScannerRequest, Result<UtxoScannerResponse, UtxoScannerError>>>,
+    resources: UtxoScannerResources<T>,
+    base_node_service: BaseNodeServiceHandle,
+    base_node_peer: Option<Peer>,
+    shutdown_signal: Option<ShutdownSignal>,
+}
+
+impl<T> UtxoScannerService<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(
+        request_stream: Receiver<UtxoScannerRequest, Result<UtxoScannerResponse, UtxoScannerError>>,
+        resources: UtxoScannerResources<T>,
+        base_node_service: BaseNodeServiceHandle,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self
+    {
+        Self {
+            request_stream: Some(request_stream),
+            resources,
+            base_node_service,
+            base_node_peer: None,
+            shutdown_signal: Some(shutdown_signal),
+        }
+    }
+
+    /// Starts the service.
+    pub async fn start(mut self) -> Result<(), UtxoScannerError> {
+        let request_stream = self
+            .request_stream
+            .take()
+            .expect("UTXO Scanner Service initialized without request_stream")
+            .fuse();
+        pin_mut!(request_stream);
+
+        let mut base_node_events = self.base_node_service.get_event_stream_fused();
+
+        let mut shutdown_signal = self
+            .shutdown_signal
+            .take()
+            .expect("UTXO Scanner Service initialized without shutdown signal");
+
+        let mut scan_handles: FuturesUnordered<JoinHandle<Result<Option<ScannedBlock>, UtxoScannerError>>> =
+            FuturesUnordered::new();
+        // Set when the chain tip changes while a scan is in progress so that another scan follows it
+        let mut scan_pending = false;
+        let mut last_scanned_height = None;
+
+        info!(target: LOG_TARGET, "UTXO Scanner Service started");
+        loop {
+            futures::select! {
+                // Incoming requests
+                request_context = request_stream.select_next_some() => {
+                    trace!(target: LOG_TARGET, "Handling UTXO Scanner Service API Request");
+                    let (request, reply_tx) = request_context.split();
+                    let response = self.handle_request(request).await.map_err(|e| {
+                        error!(target: LOG_TARGET, "Error handling request: {:?}", e);
+                        e
+                    });
+                    let _ = reply_tx.send(response).map_err(|e| {
+                        warn!(target: LOG_TARGET, "Failed to send reply");
+                        e
+                    });
+                },
+
+                // Base Node events
+                event = base_node_events.select_next_some() => {
+                    let start_scan = match event {
+                        Ok(event) => match &*event {
+                            BaseNodeEvent::BaseNodePeerSet(peer) => {
+                                self.base_node_peer = Some((**peer).clone());
+                                true
+                            },
+                            BaseNodeEvent::BaseNodeState(state) => state
+                                .chain_metadata
+                                .as_ref()
+                                .map(ChainMetadata::height_of_longest_chain) != last_scanned_height,
+                        },
+                        Err(e) => {
+                            trace!(target: LOG_TARGET, "Lagging on base node event stream: {}", e);
+                            false
+                        },
+                    };
+                    if start_scan {
+                        if scan_handles.is_empty() {
+                            self.spawn_scan(&mut scan_handles);
+                        } else {
+                            scan_pending = true;
+                        }
+                    }
+                },
+
+                // Completed scans
+                join_result = scan_handles.select_next_some() => {
+                    match join_result {
+                        Ok(Ok(block)) => {
+                            last_scanned_height = block.map(|b| b.height);
+                        },
+                        Ok(Err(e)) => {
+                            warn!(target: LOG_TARGET, "UTXO scan failed: {}", e);
+                            self.publish_event(UtxoScannerEvent::ScanningFailed(e.to_string()));
+                        },
+                        Err(e) => error!(target: LOG_TARGET, "Error resolving UTXO scan join handle: {:?}", e),
+                    }
+                    if scan_pending {
+                        scan_pending = false;
+                        self.spawn_scan(&mut scan_handles);
+                    }
+                },
+
+                // Shutdown
+                _ = shutdown_signal => {
+                    info!(target: LOG_TARGET, "UTXO Scanner Service shutting down because the shutdown signal was received");
+                    break Ok(());
+                }
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, request: UtxoScannerRequest) -> Result<UtxoScannerResponse, UtxoScannerError> {
+        debug!(target: LOG_TARGET, "Handling Service Request: {:?}", request);
+        match request {
+            UtxoScannerRequest::GetLastScannedBlock => Ok(UtxoScannerResponse::LastScannedBlock(
+                self.resources.db.get_scanned_blocks().await?.into_iter().next(),
+            )),
+            UtxoScannerRequest::RescanFromGenesis => {
+                self.resources.db.clear_scanned_blocks().await?;
+                Ok(UtxoScannerResponse::RescanScheduled)
+            },
+        }
+    }
+
+    fn spawn_scan(
+        &self,
+        scan_handles: &mut FuturesUnordered<JoinHandle<Result<Option<ScannedBlock>, UtxoScannerError>>>,
+    )
+    {
+        let base_node_peer = match self.base_node_peer.clone() {
+            Some(peer) => peer,
+            None => {
+                debug!(target: LOG_TARGET, "No base node peer set, UTXO scan not started");
+                return;
+            },
+        };
+        let task = UtxoScannerTask {
+            resources: self.resources.clone(),
+            base_node_peer,
+        };
+        scan_handles.push(tokio::spawn(task.run()));
+    }
+
+    fn publish_event(&self, event: UtxoScannerEvent) {
+        let _ = self.resources.event_publisher.send(Arc::new(event)).map_err(|e| {
+            trace!(
+                target: LOG_TARGET,
+                "Error sending event because there are no subscribers: {:?}",
+                e
+            );
+            e
+        });
+    }
+}
+
+/// The resources that a scan task requires.
+#[derive(Clone)]
+pub struct UtxoScannerResources<T>
+where T: WalletBackend + 'static
+{
+    pub db: WalletDatabase<T>,
+    pub connectivity: ConnectivityRequester,
+    pub output_manager_service: OutputManagerHandle,
+    pub transaction_service: TransactionServiceHandle,
+    pub node_identity: Arc<NodeIdentity>,
+    pub event_publisher: UtxoScannerEventSender,
+}
+
+/// A single scan from the last scanned block up to the current chain tip of the base node peer
+struct UtxoScannerTask<T>
+where T: WalletBackend + 'static
+{
+    resources: UtxoScannerResources<T>,
+    base_node_peer: Peer,
+}
+
+impl<T> UtxoScannerTask<T>
+where T: WalletBackend + 'static
+{
+    /// Run the scan, returning the block that the chain has been scanned up to
+    async fn run(mut self) -> Result<Option<ScannedBlock>, UtxoScannerError> {
+        let mut client = self
+            .resources
+            .connectivity
+            .get_rpc_client::<BaseNodeSyncRpcClient>(self.base_node_peer.node_id.clone(), Default::default())
+            .await?;
+
+        let chain_metadata = ChainMetadata::try_from(client.get_chain_metadata().await?)
+            .map_err(UtxoScannerError::InvalidBaseNodeResponse)?;
+        let tip_height = chain_metadata.height_of_longest_chain();
+        let tip_hash = chain_metadata.best_block().clone();
+
+        let mut scanned_blocks = self.resources.db.get_scanned_blocks().await?;
+        if scanned_blocks.first().map(|b| &b.header_hash) == Some(&tip_hash) {
+            trace!(
+                target: LOG_TARGET,
+                "Already scanned up to the chain tip at height {}",
+                tip_height
+            );
+            return Ok(scanned_blocks.into_iter().next());
+        }
+
+        let (start_index, fork_height) = self.find_start_index(&mut client, &mut scanned_blocks).await?;
+        let start_height = scanned_blocks.first().map(|b| b.height).unwrap_or(0);
+        let tip_header = client.get_header_by_height(tip_height).await?;
+        let end_index = tip_header.output_mmr_size;
+
+        debug!(
+            target: LOG_TARGET,
+            "Scanning outputs {} to {} (height {}) from base node {}",
+            start_index,
+            end_index,
+            tip_height,
+            self.base_node_peer.public_key.to_hex()
+        );
+        self.publish_event(UtxoScannerEvent::ScanningStarted {
+            start_index,
+            tip_height,
+        });
+
+        // The validation is started before any scanned blocks are saved so that a failed scan detects the reorg again
+        if let Some(fork_height) = fork_height {
+            self.revalidate_outputs(fork_height).await?;
+        }
+
+        // The chain is scanned in chunks of blocks and the last block of every chunk is saved, so that an interrupted
+        // scan resumes from the last completed chunk
+        let mut num_recovered = 0;
+        let mut value_recovered = MicroTari::from(0);
+        let mut chunk_start_index = start_index;
+        let mut chunk_start_height = start_height;
+        let mut scanned_block;
+        loop {
+            let chunk_end_height = cmp::min(chunk_start_height + SCAN_CHUNK_SIZE, tip_height);
+            let (chunk_end_hash, chunk_end_index) = if chunk_end_height == tip_height {
+                (tip_hash.clone(), end_index)
+            } else {
+                let header = BlockHeader::try_from(client.get_header_by_height(chunk_end_height).await?)
+                    .map_err(UtxoScannerError::InvalidBaseNodeResponse)?;
+                (header.hash(), header.output_mmr_size)
+            };
+
+            let (num_outputs, value) = self
+                .scan_outputs(
+                    &mut client,
+                    chunk_start_index,
+                    chunk_end_index,
+                    end_index,
+                    chunk_end_hash.clone(),
+                )
+                .await?;
+            num_recovered += num_outputs;
+            value_recovered += value;
+
+            let block = ScannedBlock {
+                height: chunk_end_height,
+                header_hash: chunk_end_hash,
+                output_mmr_size: chunk_end_index,
+            };
+            scanned_blocks.insert(0, block.clone());
+            scanned_blocks.truncate(SCANNED_BLOCK_HISTORY_SIZE);
+            self.resources.db.set_scanned_blocks(scanned_blocks.clone()).await?;
+            trace!(
+                target: LOG_TARGET,
+                "Scanned blocks {} to {}",
+                chunk_start_height,
+                chunk_end_height
+            );
+
+            scanned_block = block;
+            if chunk_end_height >= tip_height {
+                break;
+            }
+            chunk_start_index = chunk_end_index;
+            chunk_start_height = chunk_end_height;
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "UTXO scan completed to height {}, {} outputs worth {} recovered",
+            tip_height,
+            num_recovered,
+            value_recovered
+        );
+        self.publish_event(UtxoScannerEvent::ScanningCompleted {
+            height: tip_height,
+            num_recovered,
+            value_recovered,
+        });
+
+        Ok(Some(scanned_block))
+    }
+
+    /// Find the output MMR position to resume scanning from. The most recent scanned block that is still part of the
+    /// base node's chain is the fork point, scanned blocks after it are discarded. If none of them are found the scan
+    /// starts from the genesis block. The height of the fork point is returned along with the position if the chain
+    /// has reorged since the last scan.
+    async fn find_start_index(
+        &self,
+        client: &mut BaseNodeSyncRpcClient,
+        scanned_blocks: &mut Vec<ScannedBlock>,
+    ) -> Result<(u64, Option<u64>), UtxoScannerError>
+    {
+        if scanned_blocks.is_empty() {
+            return Ok((0, None));
+        }
+
+        let request = FindChainSplitRequest {
+            block_hashes: scanned_blocks.iter().map(|b| b.header_hash.clone()).collect(),
+            header_count: 0,
+        };
+        let fork_index = match client.find_chain_split(request).await {
+            Ok(response) => response.fork_hash_index as usize,
+            Err(RpcError::RequestFailed(status)) if status.status_code().is_not_found() => {
+                warn!(
+                    target: LOG_TARGET,
+                    "None of the {} scanned blocks are in the base node's chain, rescanning from the genesis block",
+                    scanned_blocks.len()
+                );
+                self.publish_event(UtxoScannerEvent::ReorgDetected { fork_height: 0 });
+                scanned_blocks.clear();
+                return Ok((0, Some(0)));
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        if fork_index >= scanned_blocks.len() {
+            return Err(UtxoScannerError::InvalidBaseNodeResponse(format!(
+                "Fork hash index {} is out of range",
+                fork_index
+            )));
+        }
+        let mut reorg_height = None;
+        if fork_index > 0 {
+            let fork_height = scanned_blocks[fork_index].height;
+            warn!(
+                target: LOG_TARGET,
+                "Chain reorg detected, rescanning from the scanned block at height {}", fork_height
+            );
+            self.publish_event(UtxoScannerEvent::ReorgDetected { fork_height });
+            scanned_blocks.drain(..fork_index);
+            reorg_height = Some(fork_height);
+        }
+
+        Ok((scanned_blocks[0].output_mmr_size, reorg_height))
+    }
+
+    /// Outputs that were imported from blocks after the fork point are no longer spendable if they were not mined
+    /// again in the new chain. The unspent outputs of the wallet are validated against the base node's chain, which
+    /// invalidates the outputs that are not in its UTXO set.
+    async fn revalidate_outputs(&mut self, fork_height: u64) -> Result<(), UtxoScannerError> {
+        let request_key = self
+            .resources
+            .output_manager_service
+            .validate_txos(
+                TxoValidationType::Unspent,
+                ValidationRetryStrategy::Limited(REORG_VALIDATION_RETRIES),
+            )
+            .await?;
+        debug!(
+            target: LOG_TARGET,
+            "Validating unspent outputs after the reorg at height {} (RequestKey: {})", fork_height, request_key
+        );
+        Ok(())
+    }
+
+    /// Stream the outputs between the given output MMR positions from the base node and import the outputs that can be
+    /// rewound by this wallet. The progress is reported against the output MMR position of the chain tip. Returns the
+    /// number and total value of the imported outputs.
+    async fn scan_outputs(
+        &mut self,
+        client: &mut BaseNodeSyncRpcClient,
+        start_index: u64,
+        end_index: u64,
+        total_index: u64,
+        end_header_hash: HashOutput,
+    ) -> Result<(u64, MicroTari), UtxoScannerError>
+    {
+        let mut num_recovered = 0;
+        let mut value_recovered = MicroTari::from(0);
+        if start_index >= end_index {
+            return Ok((num_recovered, value_recovered));
+        }
+
+        let request = SyncUtxosRequest {
+            start: start_index,
+            end_header_hash,
+        };
+        let mut output_stream = client.sync_utxos(request).await?;
+        let mut current_index = start_index;
+
+        while let Some(response) = output_stream.next().await {
+            let response = response?;
+            // Every position in the output MMR is streamed, spent outputs are included without their output data
+            current_index += response.utxos.len() as u64;
+
+            let outputs = response
+                .utxos
+                .into_iter()
+                .filter_map(|utxo| utxo.output.and_then(|output| TransactionOutput::try_from(output).ok()))
+                .collect::<Vec<_>>();
+
+            let (num_outputs, value) = self.import_outputs(outputs).await?;
+            if num_outputs > 0 {
+                num_recovered += num_outputs;
+                value_recovered += value;
+                self.publish_event(UtxoScannerEvent::OutputsRecovered { num_outputs, value });
+            }
+            self.publish_event(UtxoScannerEvent::Progress {
+                current_index,
+                total_index,
+            });
+        }
+
+        Ok((num_recovered, value_recovered))
+    }
+
+    /// Rewind the given outputs and import those that belong to this wallet. Outputs that the wallet already knows
+    /// about are skipped.
+    async fn import_outputs(&mut self, outputs: Vec<TransactionOutput>) -> Result<(u64, MicroTari), UtxoScannerError> {
+        let unblinded_outputs = self.resources.output_manager_service.rewind_outputs(outputs).await?;
+
+        let mut num_outputs = 0;
+        let mut value = MicroTari::from(0);
+        for uo in unblinded_outputs {
+            match self.resources.output_manager_service.add_output(uo.clone()).await {
+                Ok(_) => (),
+                Err(OutputManagerError::OutputManagerStorageError(OutputManagerStorageError::DuplicateOutput)) => {
+                    trace!(target: LOG_TARGET, "Scanned output is already known to the wallet");
+                    continue;
+                },
+                Err(e) => return Err(e.into()),
+            }
+
+            let tx_id = self
+                .resources
+                .transaction_service
+                .import_utxo(
+                    uo.value,
+                    self.resources.node_identity.public_key().clone(),
+                    format!("Output found by UTXO scanner on {}.", Local::now()),
+                )
+                .await?;
+            debug!(
+                target: LOG_TARGET,
+                "Imported scanned output of value {} as TxId: {}", uo.value, tx_id
+            );
+
+            num_outputs += 1;
+            value += uo.value;
+        }
+
+        Ok((num_outputs, value))
+    }
+
+    fn publish_event(&self, event: UtxoScannerEvent) {
+        let _ = self.resources.event_publisher.send(Arc::new(event)).map_err(|e| {
+            trace!(
+                target: LOG_TARGET,
+                "Error sending event because there are no subscribers: {:?}",
+                e
+            );
+            e
+        });
+    }
+}
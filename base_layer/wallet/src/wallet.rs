@@ -24,10 +24,20 @@ use crate::{
     base_node_service::{config::BaseNodeServiceConfig, handle::BaseNodeServiceHandle, BaseNodeServiceInitializer},
     contacts_service::{handle::ContactsServiceHandle, storage::database::ContactsBackend, ContactsServiceInitializer},
     error::WalletError,
+    multisig_service::{handle::MultiSigHandle, MultiSigServiceInitializer},
     output_manager_service::{
         config::OutputManagerServiceConfig,
+        error::OutputManagerError,
         handle::OutputManagerHandle,
-        storage::database::OutputManagerBackend,
+        storage::database::{
+            DbKey,
+            DbKeyValuePair,
+            DbValue,
+            OutputManagerBackend,
+            WatchOnlyKeys,
+            WriteOperation,
+            DEFAULT_ACCOUNT_ID,
+        },
         OutputManagerServiceInitializer,
         TxId,
     },
@@ -35,9 +45,10 @@ use crate::{
     transaction_service::{
         config::TransactionServiceConfig,
         handle::TransactionServiceHandle,
-        storage::database::TransactionBackend,
+        storage::{database::TransactionBackend, models::CompletedTransaction},
         TransactionServiceInitializer,
     },
+    utxo_scanner_service::{handle::UtxoScannerHandle, UtxoScannerServiceInitializer},
 };
 use aes_gcm::{
     aead::{generic_array::GenericArray, NewAead},
@@ -45,7 +56,8 @@ use aes_gcm::{
 };
 use digest::Digest;
 use log::*;
-use std::{marker::PhantomData, sync::Arc};
+use rand::{rngs::OsRng, RngCore};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 use tari_comms::{
     multiaddr::Multiaddr,
     peer_manager::{NodeId, Peer, PeerFeatures, PeerFlags},
@@ -58,8 +70,13 @@ use tari_core::{
     consensus::Network,
     transactions::{
         tari_amount::MicroTari,
-        transaction::{OutputFeatures, UnblindedOutput},
-        types::{CryptoFactories, PrivateKey},
+        transaction::{KernelFeatures, OutputFeatures, Transaction, UnblindedOutput},
+        transaction_protocol::partially_signed::{
+            PartiallySignedTransaction,
+            PartiallySignedTransactionStage,
+            UnsignedTransaction,
+        },
+        types::{Commitment, CryptoFactories, PrivateKey},
     },
 };
 use tari_crypto::{
@@ -134,6 +151,8 @@ where
     pub transaction_service: TransactionServiceHandle,
     pub contacts_service: ContactsServiceHandle,
     pub base_node_service: BaseNodeServiceHandle,
+    pub utxo_scanner_service: UtxoScannerHandle,
+    pub multisig_service: MultiSigHandle,
     pub db: WalletDatabase<T>,
     pub factories: CryptoFactories,
     #[cfg(feature = "test_harness")]
@@ -164,6 +183,8 @@ where
         db.set_comms_secret_key(config.comms_config.node_identity.secret_key().clone())
             .await?;
         let bn_service_db = db.clone();
+        let utxo_scanner_db = db.clone();
+        let multisig_db = db.clone();
         #[cfg(feature = "test_harness")]
         let transaction_backend_handle = transaction_backend.clone();
 
@@ -211,8 +232,18 @@ where
             .add_initializer(ContactsServiceInitializer::new(contacts_backend))
             .add_initializer(BaseNodeServiceInitializer::new(
                 config.base_node_service_config,
-                peer_message_subscription_factory,
+                peer_message_subscription_factory.clone(),
                 bn_service_db,
+            ))
+            .add_initializer(UtxoScannerServiceInitializer::new(
+                utxo_scanner_db,
+                node_identity.clone(),
+            ))
+            .add_initializer(MultiSigServiceInitializer::new(
+                multisig_db,
+                peer_message_subscription_factory,
+                node_identity,
+                factories.clone(),
             ));
 
         let mut handles = stack.build().await?;
@@ -229,6 +260,8 @@ where
         let store_and_forward_requester = dht.store_and_forward_requester();
 
         let base_node_service_handle = handles.expect_handle::<BaseNodeServiceHandle>();
+        let utxo_scanner_handle = handles.expect_handle::<UtxoScannerHandle>();
+        let multisig_handle = handles.expect_handle::<MultiSigHandle>();
 
         Ok(Wallet {
             comms,
@@ -238,6 +271,8 @@ where
             transaction_service: transaction_service_handle,
             contacts_service: contacts_handle,
             base_node_service: base_node_service_handle,
+            utxo_scanner_service: utxo_scanner_handle,
+            multisig_service: multisig_handle,
             db,
             factories,
             #[cfg(feature = "test_harness")]
@@ -248,6 +283,51 @@ where
         })
     }
 
+    /// Construct a watch-only wallet. Only the rewind keys are stored in the Output Manager backend so the wallet can
+    /// recognise and track its incoming outputs but has no master key with which to spend them or sign transactions.
+    /// An existing wallet database that already holds spending keys cannot be reopened as a watch-only wallet.
+    pub async fn new_watch_only(
+        config: WalletConfig,
+        wallet_backend: T,
+        transaction_backend: U,
+        output_manager_backend: V,
+        contacts_backend: W,
+        shutdown_signal: ShutdownSignal,
+        watch_only_keys: WatchOnlyKeys,
+    ) -> Result<Wallet<T, U, V, W>, WalletError>
+    {
+        let existing_keys = output_manager_backend
+            .fetch(&DbKey::WatchOnlyKeys)
+            .map_err(OutputManagerError::from)?;
+        match existing_keys {
+            Some(DbValue::WatchOnlyKeys(keys)) if keys != watch_only_keys => {
+                return Err(WalletError::WatchOnlyKeysConflict)
+            },
+            Some(_) => (),
+            None => {
+                let key_manager_state = output_manager_backend
+                    .fetch(&DbKey::KeyManagerState)
+                    .map_err(OutputManagerError::from)?;
+                if key_manager_state.is_some() {
+                    return Err(WalletError::WatchOnlyKeysConflict);
+                }
+                output_manager_backend
+                    .write(WriteOperation::Insert(DbKeyValuePair::WatchOnlyKeys(watch_only_keys)))
+                    .map_err(OutputManagerError::from)?;
+            },
+        }
+
+        Wallet::new(
+            config,
+            wallet_backend,
+            transaction_backend,
+            output_manager_backend,
+            contacts_backend,
+            shutdown_signal,
+        )
+        .await
+    }
+
     /// This method consumes the wallet so that the handles are dropped which will result in the services async loops
     /// exiting.
     pub async fn wait_until_shutdown(self) {
@@ -367,7 +447,33 @@ where
             .output_manager_service
             .create_coin_split(amount_per_split, split_count, fee_per_gram, lock_height)
             .await;
+        self.submit_coin_split(coin_split_tx, message).await
+    }
+
+    /// Do a coin split of exactly the unspent outputs with the given commitments
+    pub async fn coin_split_with_inputs(
+        &mut self,
+        amount_per_split: MicroTari,
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        message: String,
+        lock_height: Option<u64>,
+        inputs: Vec<Commitment>,
+    ) -> Result<TxId, WalletError>
+    {
+        let coin_split_tx = self
+            .output_manager_service
+            .create_coin_split_with_inputs(amount_per_split, split_count, fee_per_gram, lock_height, inputs)
+            .await;
+        self.submit_coin_split(coin_split_tx, message).await
+    }
 
+    async fn submit_coin_split(
+        &mut self,
+        coin_split_tx: Result<(TxId, Transaction, MicroTari, MicroTari), OutputManagerError>,
+        message: String,
+    ) -> Result<TxId, WalletError>
+    {
         match coin_split_tx {
             Ok((tx_id, split_tx, amount, fee)) => {
                 let coin_tx = self
@@ -383,6 +489,117 @@ where
         }
     }
 
+    /// Prepare an unsigned transaction paying `amount` to `dest_pubkey` for signing by a wallet that holds this
+    /// wallet's spending keys, typically an offline copy of a watch-only wallet. The inputs are encumbered until the
+    /// finalized transaction is imported back with the transaction service.
+    pub async fn create_unsigned_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<PartiallySignedTransaction, WalletError>
+    {
+        let tx_id = OsRng.next_u64();
+        let inputs = self
+            .output_manager_service
+            .prepare_unsigned_transaction(tx_id, amount, fee_per_gram)
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Unsigned transaction (TxId: {}) of {} to {} created", tx_id, amount, dest_pubkey
+        );
+
+        Ok(PartiallySignedTransaction {
+            tx_id,
+            amount,
+            message,
+            sender_public_key: self.comms.node_identity().public_key().clone(),
+            recipient_public_key: dest_pubkey,
+            stage: PartiallySignedTransactionStage::Unsigned(UnsignedTransaction {
+                fee_per_gram,
+                lock_height: 0,
+                inputs,
+            }),
+        })
+    }
+
+    /// Apply the sender's signing step to a partially signed transaction. An unsigned transaction becomes the sender
+    /// message for the recipient, and the sender protocol is kept until the recipient signed stage is signed into the
+    /// finalized transaction.
+    pub async fn sign_partially_signed_transaction(
+        &mut self,
+        pst: PartiallySignedTransaction,
+    ) -> Result<PartiallySignedTransaction, WalletError>
+    {
+        match pst.stage.clone() {
+            PartiallySignedTransactionStage::Unsigned(unsigned) => {
+                let mut stp = self
+                    .output_manager_service
+                    .create_sender_protocol_from_unsigned(pst.amount, unsigned, pst.message.clone())
+                    .await?;
+                let protocol_tx_id = stp.get_tx_id()?;
+                let sender_message = stp.build_single_round_message()?;
+
+                let mut protocols = self.db.get_signing_protocols().await?;
+                protocols.insert(protocol_tx_id, stp);
+                self.db.set_signing_protocols(protocols).await?;
+                debug!(
+                    target: LOG_TARGET,
+                    "Unsigned transaction (TxId: {}) signed as protocol TxId {}", pst.tx_id, protocol_tx_id
+                );
+
+                Ok(pst.with_stage(PartiallySignedTransactionStage::SenderMessage(Box::new(sender_message))))
+            },
+            PartiallySignedTransactionStage::RecipientSigned(recipient_message) => {
+                let protocol_tx_id = recipient_message.tx_id;
+                let mut protocols = self.db.get_signing_protocols().await?;
+                let mut stp = protocols.remove(&protocol_tx_id).ok_or_else(|| {
+                    WalletError::InvalidPartiallySignedTransaction(format!(
+                        "No transaction with protocol TxId {} is waiting for the recipient's signature",
+                        protocol_tx_id
+                    ))
+                })?;
+                stp.add_single_recipient_info(*recipient_message, &self.factories.range_proof)?;
+                stp.finalize(KernelFeatures::empty(), &self.factories)?;
+                let tx = stp.take_transaction()?;
+                self.db.set_signing_protocols(protocols).await?;
+                debug!(
+                    target: LOG_TARGET,
+                    "Partially signed transaction (TxId: {}) finalized", pst.tx_id
+                );
+
+                Ok(pst.with_stage(PartiallySignedTransactionStage::Finalized(protocol_tx_id, Box::new(tx))))
+            },
+            stage => Err(WalletError::InvalidPartiallySignedTransaction(format!(
+                "The {} stage is not signed by the sending wallet",
+                stage
+            ))),
+        }
+    }
+
+    /// Returns the completed transactions that were created for the named account
+    pub async fn get_account_completed_transactions(
+        &mut self,
+        account: &str,
+    ) -> Result<HashMap<TxId, CompletedTransaction>, WalletError>
+    {
+        let account = self
+            .output_manager_service
+            .get_accounts()
+            .await?
+            .into_iter()
+            .find(|a| a.name == account)
+            .ok_or_else(|| OutputManagerError::AccountNotFound(account.to_string()))?;
+        let transaction_accounts = self.output_manager_service.get_transaction_accounts().await?;
+        let completed_transactions = self.transaction_service.get_completed_transactions().await?;
+
+        Ok(completed_transactions
+            .into_iter()
+            .filter(|(tx_id, _)| transaction_accounts.get(tx_id).copied().unwrap_or(DEFAULT_ACCOUNT_ID) == account.id)
+            .collect())
+    }
+
     /// Apply encryption to all the Wallet db backends. The Wallet backend will test if the db's are already encrypted
     /// in which case this will fail.
     pub async fn apply_encryption(&mut self, passphrase: String) -> Result<(), WalletError> {
@@ -27,4 +27,5 @@ pub mod support;
 // pub mod text_message_service;
 pub mod contacts_service;
 pub mod transaction_service;
+pub mod utxo_scanner_service;
 pub mod wallet;
@@ -44,15 +44,19 @@ use tari_core::{
         tari_amount::{uT, MicroTari},
         transaction::{KernelFeatures, OutputFeatures, Transaction, UnblindedOutput},
         transaction_protocol::{
+            partially_signed::UnsignedTransaction,
             recipient::RecipientState,
             sender::TransactionSenderMessage,
             single_receiver::SingleReceiverTransactionProtocol,
         },
-        types::{CryptoFactories, PrivateKey},
+        types::{CryptoFactories, PrivateKey, PublicKey},
         SenderTransactionProtocol,
     },
 };
-use tari_crypto::{hash::blake2::Blake256, keys::SecretKey};
+use tari_crypto::{
+    hash::blake2::Blake256,
+    keys::{PublicKey as PublicKeyTrait, SecretKey},
+};
 use tari_service_framework::reply_channel;
 use tari_shutdown::Shutdown;
 use tari_wallet::{
@@ -64,11 +68,22 @@ use tari_wallet::{
         protocols::txo_validation_protocol::TxoValidationType,
         service::OutputManagerService,
         storage::{
-            database::{DbKey, DbKeyValuePair, DbValue, OutputManagerBackend, OutputManagerDatabase, WriteOperation},
+            database::{
+                DbKey,
+                DbKeyValuePair,
+                DbValue,
+                OutputManagerBackend,
+                OutputManagerDatabase,
+                WatchOnlyKeys,
+                WriteOperation,
+                DEFAULT_ACCOUNT_ID,
+                DEFAULT_ACCOUNT_NAME,
+            },
             memory_db::OutputManagerMemoryDatabase,
             models::DbUnblindedOutput,
             sqlite_db::OutputManagerSqliteDatabase,
         },
+        utxo_selection::UTXOSelectionStrategy,
         TxId,
     },
     storage::sqlite_utilities::run_migration_and_create_sqlite_connection,
@@ -592,6 +607,75 @@ fn sending_transaction_and_confirmation_sqlite_db() {
     sending_transaction_and_confirmation(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+fn watch_only_wallet<T: OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let watch_only_keys = WatchOnlyKeys {
+        rewind_key: PrivateKey::random(&mut OsRng),
+        rewind_blinding_key: PrivateKey::random(&mut OsRng),
+    };
+    backend
+        .write(WriteOperation::Insert(DbKeyValuePair::WatchOnlyKeys(
+            watch_only_keys.clone(),
+        )))
+        .unwrap();
+
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    assert_eq!(runtime.block_on(oms.get_watch_only_keys()).unwrap(), watch_only_keys);
+    let rewind_public_keys = runtime.block_on(oms.get_rewind_public_keys()).unwrap();
+    assert_eq!(
+        rewind_public_keys.rewind_public_key,
+        PublicKey::from_secret_key(&watch_only_keys.rewind_key)
+    );
+
+    // Incoming outputs are still tracked
+    let (_ti, uo) = make_input(&mut OsRng.clone(), MicroTari::from(5000), &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+    assert_eq!(
+        runtime.block_on(oms.get_balance()).unwrap().available_balance,
+        MicroTari::from(5000)
+    );
+
+    // Anything that needs to sign or derive spending keys is refused
+    match runtime.block_on(oms.prepare_transaction_to_send(
+        MicroTari::from(1000),
+        MicroTari::from(20),
+        None,
+        "".to_string(),
+    )) {
+        Err(OutputManagerError::WatchOnlyWallet) => {},
+        _ => panic!("A watch-only wallet should not be able to send"),
+    }
+    let (_tx_id, sender_message) = generate_sender_transaction_message(MicroTari::from(1000));
+    match runtime.block_on(oms.get_recipient_transaction(sender_message)) {
+        Err(OutputManagerError::WatchOnlyWallet) => {},
+        _ => panic!("A watch-only wallet should not be able to sign as a recipient"),
+    }
+    match runtime.block_on(oms.get_seed_words()) {
+        Err(OutputManagerError::WatchOnlyWallet) => {},
+        _ => panic!("A watch-only wallet has no seed words"),
+    }
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 0);
+}
+
+#[test]
+fn watch_only_wallet_memory_db() {
+    watch_only_wallet(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn watch_only_wallet_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    watch_only_wallet(OutputManagerSqliteDatabase::new(connection, None));
+}
+
 fn send_not_enough_funds<T: OutputManagerBackend + 'static>(backend: T) {
     let factories = CryptoFactories::default();
 
@@ -717,6 +801,53 @@ fn send_no_change_sqlite_db() {
     send_no_change(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+fn send_branch_and_bound_no_change<T: OutputManagerBackend + 'static>(backend: T) {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let fee_per_gram = MicroTari::from(20);
+    let fee_without_change = Fee::calculate(fee_per_gram, 1, 2, 1);
+    for value in &[500, 800, 3000] {
+        let key = PrivateKey::random(&mut OsRng);
+        runtime
+            .block_on(oms.add_output(UnblindedOutput::new(MicroTari::from(*value), key, None)))
+            .unwrap();
+    }
+
+    // Spending the largest outputs first would need a change output, but 500 + 3000 pays the amount and fee exactly
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_utxo_selection(
+            MicroTari::from(500 + 3000) - fee_without_change,
+            fee_per_gram,
+            None,
+            "".to_string(),
+            Some(UTXOSelectionStrategy::BranchAndBound),
+        ))
+        .unwrap();
+
+    assert_eq!(stp.get_amount_to_self().unwrap(), MicroTari::from(0));
+    let unspent_outputs = runtime.block_on(oms.get_unspent_outputs()).unwrap();
+    assert_eq!(unspent_outputs.len(), 1);
+    assert_eq!(unspent_outputs[0].value, MicroTari::from(800));
+}
+
+#[test]
+fn send_branch_and_bound_no_change_memory_db() {
+    send_branch_and_bound_no_change(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn send_branch_and_bound_no_change_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    send_branch_and_bound_no_change(OutputManagerSqliteDatabase::new(connection, None));
+}
+
 fn send_not_enough_for_change<T: OutputManagerBackend + 'static>(backend: T) {
     let mut runtime = Runtime::new().unwrap();
 
@@ -799,6 +930,7 @@ fn receiving_and_confirmation<T: OutputManagerBackend + 'static>(backend: T) {
     let output = match rtp.state {
         RecipientState::Finalized(s) => s.output,
         RecipientState::Failed(_) => panic!("Should not be in Failed state"),
+        RecipientState::AwaitingSignatureRequest(_) => panic!("Should not be awaiting a signature request"),
     };
 
     runtime
@@ -996,6 +1128,7 @@ fn test_confirming_received_output<T: OutputManagerBackend + 'static>(backend: T
     let output = match rtp.state {
         RecipientState::Finalized(s) => s.output,
         RecipientState::Failed(_) => panic!("Should not be in Failed state"),
+        RecipientState::AwaitingSignatureRequest(_) => panic!("Should not be awaiting a signature request"),
     };
     runtime
         .block_on(oms.confirm_transaction(tx_id, vec![], vec![output.clone()]))
@@ -1192,6 +1325,569 @@ fn coin_split_no_change_sqlite_db() {
     coin_split_no_change(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+fn output_coin_control<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    for value in &[2_000, 5_000, 9_000] {
+        let (_ti, uo) = make_input(&mut OsRng.clone(), *value * uT, &factories.commitment);
+        runtime.block_on(oms.add_output(uo)).unwrap();
+    }
+    let outputs = runtime.block_on(oms.get_unspent_output_details()).unwrap();
+    let small = outputs[0].commitment.clone();
+    let medium = outputs[1].commitment.clone();
+    let large = outputs[2].commitment.clone();
+
+    runtime
+        .block_on(oms.set_output_label(large.clone(), Some("cold storage".to_string())))
+        .unwrap();
+    runtime
+        .block_on(oms.set_output_note(large.clone(), Some("Do not spend before audit".to_string())))
+        .unwrap();
+    runtime.block_on(oms.set_output_frozen(large.clone(), true)).unwrap();
+
+    let outputs = runtime.block_on(oms.get_unspent_output_details()).unwrap();
+    let frozen = outputs.iter().find(|o| o.commitment == large).unwrap();
+    assert_eq!(frozen.label, Some("cold storage".to_string()));
+    assert_eq!(frozen.note, Some("Do not spend before audit".to_string()));
+    assert!(frozen.frozen);
+    assert!(outputs.iter().filter(|o| o.commitment != large).all(|o| !o.frozen));
+
+    // The frozen output is never selected, even when it is the only way to fund the transaction
+    match runtime.block_on(oms.prepare_transaction_to_send(8_000 * uT, MicroTari::from(20), None, "".to_string())) {
+        Err(OutputManagerError::NotEnoughFunds) => {},
+        _ => panic!("Frozen output should not be selected"),
+    }
+    match runtime.block_on(oms.prepare_transaction_to_send_with_inputs(
+        1_000 * uT,
+        MicroTari::from(20),
+        None,
+        "".to_string(),
+        vec![large.clone()],
+    )) {
+        Err(OutputManagerError::InputNotSpendable(_)) => {},
+        _ => panic!("Frozen output should not be spendable"),
+    }
+
+    // Explicit inputs are spent even if a smaller selection would fund the transaction
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_inputs(
+            1_000 * uT,
+            MicroTari::from(20),
+            None,
+            "".to_string(),
+            vec![small.clone(), medium.clone()],
+        ))
+        .unwrap();
+    assert_eq!(
+        stp.get_amount_to_self().unwrap(),
+        6_000 * uT - Fee::calculate(MicroTari::from(20), 1, 2, 2)
+    );
+    let unspent = runtime.block_on(oms.get_unspent_output_details()).unwrap();
+    assert_eq!(unspent.len(), 1);
+    assert_eq!(unspent[0].commitment, large);
+
+    runtime.block_on(oms.set_output_frozen(large.clone(), false)).unwrap();
+    let (_tx_id, coin_split_tx, _fee, amount) = runtime
+        .block_on(oms.create_coin_split_with_inputs(1_000 * uT, 4, MicroTari::from(20), None, vec![large.clone()]))
+        .unwrap();
+    assert_eq!(coin_split_tx.body.inputs().len(), 1);
+    assert_eq!(coin_split_tx.body.outputs().len(), 5);
+    assert_eq!(amount, 9_000 * uT);
+}
+
+#[test]
+fn output_coin_control_memory_db() {
+    output_coin_control(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn output_coin_control_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    output_coin_control(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn fee_bump_transaction<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo1) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    let (_ti, uo2) = make_input(&mut OsRng.clone(), 10_000 * uT, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo1)).is_ok());
+    assert!(runtime.block_on(oms.add_output(uo2)).is_ok());
+
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_utxo_selection(
+            MicroTari::from(1000),
+            MicroTari::from(5),
+            None,
+            "".to_string(),
+            Some(UTXOSelectionStrategy::Smallest),
+        ))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+    let tx = runtime.block_on(complete_transaction(stp, oms.clone()));
+    runtime.block_on(oms.confirm_pending_transaction(tx_id)).unwrap();
+
+    match runtime.block_on(oms.create_fee_bump_transaction(tx_id, tx.clone(), MicroTari::from(5))) {
+        Err(OutputManagerError::FeeBumpTooLow) => {},
+        _ => panic!("A fee bump must increase the fee per gram"),
+    }
+
+    let fee_per_gram = MicroTari::from(25);
+    let (fee, bumped_tx) = runtime
+        .block_on(oms.create_fee_bump_transaction(tx_id, tx.clone(), fee_per_gram))
+        .unwrap();
+    bumped_tx.validate_internal_consistency(&factories, None).unwrap();
+    assert_eq!(bumped_tx.body.kernels().len(), 2);
+    assert_eq!(bumped_tx.body.inputs().len(), tx.body.inputs().len() + 1);
+    assert!(tx.body.inputs().iter().all(|i| bumped_tx.body.inputs().contains(i)));
+    assert!(tx.body.kernels().iter().all(|k| bumped_tx.body.kernels().contains(k)));
+    assert_eq!(fee, bumped_tx.body.get_total_fee());
+    assert!(bumped_tx.calculate_ave_fee_per_gram() >= u64::from(fee_per_gram) as f64);
+
+    // The additional input and change output are tracked under their own TxId, linked to the original by the kernel
+    // of the bump
+    let pending_txs = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    assert_eq!(pending_txs.len(), 2);
+    assert_eq!(
+        pending_txs.get(&tx_id).unwrap().outputs_to_be_spent.len(),
+        tx.body.inputs().len()
+    );
+    let bump = pending_txs.values().find(|p| p.tx_id != tx_id).unwrap();
+    let fee_bump = bump.fee_bump.as_ref().unwrap();
+    assert_eq!(fee_bump.bumped_tx_id, tx_id);
+    assert!(bumped_tx
+        .body
+        .kernels()
+        .iter()
+        .any(|k| k.excess == fee_bump.kernel_excess && !tx.body.kernels().contains(k)));
+    assert_eq!(bump.outputs_to_be_spent.len(), 1);
+    assert_eq!(bump.outputs_to_be_received.len(), 1);
+
+    runtime
+        .block_on(oms.confirm_transaction(tx_id, bumped_tx.body.inputs().clone(), bumped_tx.body.outputs().clone()))
+        .unwrap();
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 0);
+    assert_eq!(runtime.block_on(oms.get_unspent_outputs()).unwrap().len(), 2);
+}
+
+#[test]
+fn fee_bump_transaction_memory_db() {
+    fee_bump_transaction(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn fee_bump_transaction_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    fee_bump_transaction(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn fee_bump_transaction_partially_mined<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo1) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    let (_ti, uo2) = make_input(&mut OsRng.clone(), 10_000 * uT, &factories.commitment);
+    let (_ti, uo3) = make_input(&mut OsRng.clone(), 20_000 * uT, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo1)).is_ok());
+    assert!(runtime.block_on(oms.add_output(uo2)).is_ok());
+    assert!(runtime.block_on(oms.add_output(uo3)).is_ok());
+
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_utxo_selection(
+            MicroTari::from(1000),
+            MicroTari::from(5),
+            None,
+            "".to_string(),
+            Some(UTXOSelectionStrategy::Smallest),
+        ))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+    let tx = runtime.block_on(complete_transaction(stp, oms.clone()));
+    runtime.block_on(oms.confirm_pending_transaction(tx_id)).unwrap();
+
+    let (_, bumped_tx) = runtime
+        .block_on(oms.create_fee_bump_transaction(tx_id, tx.clone(), MicroTari::from(25)))
+        .unwrap();
+    let (_, bumped_again_tx) = runtime
+        .block_on(oms.create_fee_bump_transaction(tx_id, bumped_tx.clone(), MicroTari::from(50)))
+        .unwrap();
+    assert_eq!(bumped_again_tx.body.kernels().len(), 3);
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 3);
+
+    // The first replacement was mined, so the second fee bump is released while the first is confirmed
+    let mined_kernel_excesses = bumped_tx.body.kernels().iter().map(|k| k.excess.clone()).collect();
+    runtime
+        .block_on(oms.release_fee_bumps(tx_id, mined_kernel_excesses))
+        .unwrap();
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 2);
+    runtime
+        .block_on(oms.confirm_transaction(
+            tx_id,
+            bumped_again_tx.body.inputs().clone(),
+            bumped_again_tx.body.outputs().clone(),
+        ))
+        .unwrap();
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 0);
+    // The change of the original and of the first bump, and the input released by the second bump
+    assert_eq!(runtime.block_on(oms.get_unspent_outputs()).unwrap().len(), 3);
+}
+
+#[test]
+fn fee_bump_transaction_partially_mined_memory_db() {
+    fee_bump_transaction_partially_mined(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn fee_bump_transaction_partially_mined_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    fee_bump_transaction_partially_mined(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn fee_bump_transaction_cancelled<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo1) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    let (_ti, uo2) = make_input(&mut OsRng.clone(), 10_000 * uT, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo1)).is_ok());
+    assert!(runtime.block_on(oms.add_output(uo2)).is_ok());
+
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_utxo_selection(
+            MicroTari::from(1000),
+            MicroTari::from(5),
+            None,
+            "".to_string(),
+            Some(UTXOSelectionStrategy::Smallest),
+        ))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+    let tx = runtime.block_on(complete_transaction(stp, oms.clone()));
+    runtime.block_on(oms.confirm_pending_transaction(tx_id)).unwrap();
+    runtime
+        .block_on(oms.create_fee_bump_transaction(tx_id, tx, MicroTari::from(25)))
+        .unwrap();
+
+    // Cancelling the transaction releases the outputs of its fee bump as well
+    runtime.block_on(oms.cancel_transaction(tx_id)).unwrap();
+    assert_eq!(runtime.block_on(oms.get_pending_transactions()).unwrap().len(), 0);
+    assert_eq!(runtime.block_on(oms.get_unspent_outputs()).unwrap().len(), 2);
+}
+
+#[test]
+fn fee_bump_transaction_cancelled_memory_db() {
+    fee_bump_transaction_cancelled(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn fee_bump_transaction_cancelled_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    fee_bump_transaction_cancelled(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn fee_bump_transaction_without_change<T: Clone + OutputManagerBackend + 'static>(backend: T, leave_dust: bool) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo1) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo1)).is_ok());
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send(MicroTari::from(1000), MicroTari::from(5), None, "".to_string()))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+    let tx = runtime.block_on(complete_transaction(stp, oms.clone()));
+    runtime.block_on(oms.confirm_pending_transaction(tx_id)).unwrap();
+
+    // Fund the bump with an output that covers the bump fee without a change output, optionally leaving an excess
+    // that is too small to pay for a change output
+    let fee_per_gram = MicroTari::from(25);
+    let shortfall = tx.calculate_weight() * u64::from(fee_per_gram) - u64::from(tx.body.get_total_fee());
+    let min_bump_weight = Fee::calculate_weight(1, 1, 0);
+    let bump_fee_per_gram = fee_per_gram + MicroTari::from((shortfall + min_bump_weight - 1) / min_bump_weight);
+    let bump_fee = Fee::calculate(bump_fee_per_gram, 1, 1, 0);
+    let dust = if leave_dust {
+        Fee::calculate(bump_fee_per_gram, 0, 0, 1) - MicroTari::from(1)
+    } else {
+        MicroTari::from(0)
+    };
+    let (_ti, uo2) = make_input(&mut OsRng.clone(), bump_fee + dust, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo2)).is_ok());
+
+    let (fee, bumped_tx) = runtime
+        .block_on(oms.create_fee_bump_transaction(tx_id, tx.clone(), fee_per_gram))
+        .unwrap();
+    bumped_tx.validate_internal_consistency(&factories, None).unwrap();
+    assert_eq!(bumped_tx.body.inputs().len(), tx.body.inputs().len() + 1);
+    // No change output is added and the excess is paid as fee
+    assert_eq!(bumped_tx.body.outputs().len(), tx.body.outputs().len());
+    assert_eq!(fee, tx.body.get_total_fee() + bump_fee + dust);
+    assert!(bumped_tx.calculate_ave_fee_per_gram() >= u64::from(fee_per_gram) as f64);
+
+    let pending_txs = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    let bump = pending_txs.values().find(|p| p.tx_id != tx_id).unwrap();
+    assert_eq!(bump.outputs_to_be_spent.len(), 1);
+    assert!(bump.outputs_to_be_received.is_empty());
+}
+
+#[test]
+fn fee_bump_transaction_exact_amount_memory_db() {
+    fee_bump_transaction_without_change(OutputManagerMemoryDatabase::new(), false);
+}
+
+#[test]
+fn fee_bump_transaction_dust_change_memory_db() {
+    fee_bump_transaction_without_change(OutputManagerMemoryDatabase::new(), true);
+}
+
+#[test]
+fn fee_bump_transaction_dust_change_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    fee_bump_transaction_without_change(OutputManagerSqliteDatabase::new(connection, None), true);
+}
+
+fn unsigned_transaction<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+
+    let tx_id = 42;
+    let amount = MicroTari::from(1000);
+    let fee_per_gram = MicroTari::from(5);
+    let inputs = runtime
+        .block_on(oms.prepare_unsigned_transaction(tx_id, amount, fee_per_gram))
+        .unwrap();
+    assert_eq!(inputs.len(), 1);
+    let pending_txs = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    assert_eq!(pending_txs.get(&tx_id).unwrap().outputs_to_be_spent.len(), 1);
+
+    // An input that cannot be rewound with this wallet's keys is refused
+    let (_ti, foreign) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    let foreign_input = foreign.as_transaction_output(&factories).unwrap();
+    let unsigned = UnsignedTransaction {
+        fee_per_gram,
+        lock_height: 0,
+        inputs: vec![foreign_input],
+    };
+    match runtime.block_on(oms.create_sender_protocol_from_unsigned(amount, unsigned, "".to_string())) {
+        Err(OutputManagerError::UnknownInput(_)) => {},
+        _ => panic!("Inputs of another wallet must be refused"),
+    }
+
+    let unsigned = UnsignedTransaction {
+        fee_per_gram,
+        lock_height: 0,
+        inputs,
+    };
+    let mut stp = runtime
+        .block_on(oms.create_sender_protocol_from_unsigned(amount, unsigned, "".to_string()))
+        .unwrap();
+    let msg = stp.build_single_round_message().unwrap();
+    let b = TestParams::new(&mut OsRng);
+    let recv_info = SingleReceiverTransactionProtocol::create(
+        &msg,
+        b.nonce,
+        b.spend_key,
+        OutputFeatures::default(),
+        &factories,
+        None,
+    )
+    .unwrap();
+    stp.add_single_recipient_info(recv_info, &factories.range_proof)
+        .unwrap();
+    stp.finalize(KernelFeatures::empty(), &factories).unwrap();
+    let tx = stp.take_transaction().unwrap();
+
+    runtime
+        .block_on(oms.complete_unsigned_transaction(tx_id, tx.clone()))
+        .unwrap();
+    let pending_txs = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    let change = &pending_txs.get(&tx_id).unwrap().outputs_to_be_received;
+    assert_eq!(change.len(), 1);
+    assert_eq!(
+        change[0].unblinded_output.value,
+        MicroTari::from(5000) - amount - tx.body.get_total_fee()
+    );
+}
+
+#[test]
+fn unsigned_transaction_memory_db() {
+    unsigned_transaction(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn unsigned_transaction_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    unsigned_transaction(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn named_accounts<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let (_ti, uo) = make_input(&mut OsRng.clone(), 5_000 * uT, &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+    let default_output = runtime.block_on(oms.get_unspent_output_details()).unwrap()[0].clone();
+    assert_eq!(default_output.account_id, DEFAULT_ACCOUNT_ID);
+
+    let payroll = runtime.block_on(oms.create_account("payroll".to_string())).unwrap();
+    assert_ne!(payroll.id, DEFAULT_ACCOUNT_ID);
+    match runtime.block_on(oms.create_account("payroll".to_string())) {
+        Err(OutputManagerError::DuplicateAccount(_)) => {},
+        _ => panic!("Account names must be unique"),
+    }
+    match runtime.block_on(oms.set_active_account("unknown".to_string())) {
+        Err(OutputManagerError::AccountNotFound(_)) => {},
+        _ => panic!("Account should not exist"),
+    }
+    let accounts = runtime.block_on(oms.get_accounts()).unwrap();
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].name, DEFAULT_ACCOUNT_NAME);
+
+    runtime.block_on(oms.set_active_account("payroll".to_string())).unwrap();
+    assert_eq!(runtime.block_on(oms.get_active_account()).unwrap(), payroll);
+    let (_ti, uo) = make_input(&mut OsRng.clone(), 3_000 * uT, &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+
+    // Each account has its own balance
+    assert_eq!(
+        runtime.block_on(oms.get_balance()).unwrap().available_balance,
+        3_000 * uT
+    );
+    assert_eq!(
+        runtime
+            .block_on(oms.get_account_balance(DEFAULT_ACCOUNT_NAME.to_string()))
+            .unwrap()
+            .available_balance,
+        5_000 * uT
+    );
+
+    // Only the outputs of the active account are spent
+    match runtime.block_on(oms.prepare_transaction_to_send(4_000 * uT, MicroTari::from(20), None, "".to_string())) {
+        Err(OutputManagerError::NotEnoughFunds) => {},
+        _ => panic!("The outputs of the default account should not be selected"),
+    }
+    match runtime.block_on(oms.prepare_transaction_to_send_with_inputs(
+        1_000 * uT,
+        MicroTari::from(20),
+        None,
+        "".to_string(),
+        vec![default_output.commitment.clone()],
+    )) {
+        Err(OutputManagerError::InputNotSpendable(_)) => {},
+        _ => panic!("The outputs of the default account should not be spendable"),
+    }
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send(1_000 * uT, MicroTari::from(20), None, "".to_string()))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+
+    // The change output and the transaction belong to the active account
+    let pending_txs = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    let pending_tx = pending_txs.get(&tx_id).unwrap();
+    assert!(pending_tx
+        .outputs_to_be_received
+        .iter()
+        .all(|o| o.account_id == payroll.id));
+    let transaction_accounts = runtime.block_on(oms.get_transaction_accounts()).unwrap();
+    assert_eq!(transaction_accounts.get(&tx_id), Some(&payroll.id));
+
+    runtime
+        .block_on(oms.set_active_account(DEFAULT_ACCOUNT_NAME.to_string()))
+        .unwrap();
+    assert_eq!(
+        runtime.block_on(oms.get_balance()).unwrap().available_balance,
+        5_000 * uT
+    );
+
+    // A request can name its account instead of using the active account
+    let mut payroll_oms = oms.with_account("payroll".to_string());
+    let (_ti, uo) = make_input(&mut OsRng.clone(), 2_000 * uT, &factories.commitment);
+    runtime.block_on(payroll_oms.add_output(uo)).unwrap();
+    assert_eq!(
+        runtime.block_on(payroll_oms.get_balance()).unwrap().available_balance,
+        2_000 * uT
+    );
+    assert_eq!(
+        runtime.block_on(oms.get_balance()).unwrap().available_balance,
+        5_000 * uT
+    );
+    let stp = runtime
+        .block_on(payroll_oms.prepare_transaction_to_send(1_000 * uT, MicroTari::from(20), None, "".to_string()))
+        .unwrap();
+    let tx_id = stp.get_tx_id().unwrap();
+    let transaction_accounts = runtime.block_on(oms.get_transaction_accounts()).unwrap();
+    assert_eq!(transaction_accounts.get(&tx_id), Some(&payroll.id));
+    assert_eq!(
+        runtime.block_on(oms.get_balance()).unwrap().available_balance,
+        5_000 * uT
+    );
+    assert_eq!(
+        runtime.block_on(oms.get_active_account()).unwrap().name,
+        DEFAULT_ACCOUNT_NAME
+    );
+    match runtime.block_on(oms.with_account("unknown".to_string()).get_balance()) {
+        Err(OutputManagerError::AccountNotFound(_)) => {},
+        _ => panic!("Account should not exist"),
+    }
+}
+
+#[test]
+fn named_accounts_memory_db() {
+    named_accounts(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn named_accounts_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    named_accounts(OutputManagerSqliteDatabase::new(connection, None));
+}
+
 fn handle_coinbase<T: Clone + OutputManagerBackend + 'static>(backend: T) {
     let mut runtime = Runtime::new().unwrap();
     let factories = CryptoFactories::default();
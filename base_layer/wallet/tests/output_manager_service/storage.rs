@@ -40,7 +40,13 @@ use tari_wallet::{
         error::OutputManagerStorageError,
         service::Balance,
         storage::{
-            database::{KeyManagerState, OutputManagerBackend, OutputManagerDatabase, PendingTransactionOutputs},
+            database::{
+                KeyManagerState,
+                OutputManagerBackend,
+                OutputManagerDatabase,
+                PendingTransactionOutputs,
+                DEFAULT_ACCOUNT_ID,
+            },
             memory_db::OutputManagerMemoryDatabase,
             models::DbUnblindedOutput,
             sqlite_db::OutputManagerSqliteDatabase,
@@ -89,6 +95,7 @@ pub fn test_db_backend<T: OutputManagerBackend + 'static>(backend: T) {
             timestamp: Utc::now().naive_utc() -
                 ChronoDuration::from_std(Duration::from_millis(120_000_000 * i)).unwrap(),
             coinbase_block_height: None,
+            fee_bump: None,
         };
         for _ in 0..(OsRng.next_u64() % 5 + 1) {
             let (_ti, uo) = make_input(
@@ -241,6 +248,7 @@ pub fn test_db_backend<T: OutputManagerBackend + 'static>(backend: T) {
             OutputFeatures::default(),
             &factories,
             None,
+            DEFAULT_ACCOUNT_ID,
         ))
         .unwrap();
 
@@ -465,6 +473,7 @@ pub async fn test_short_term_encumberance<T: OutputManagerBackend + 'static>(bac
         outputs_to_be_received: vec![],
         timestamp: Utc::now().naive_utc() - ChronoDuration::from_std(Duration::from_millis(120_000_000)).unwrap(),
         coinbase_block_height: None,
+        fee_bump: None,
     };
     for i in 1..4 {
         let (_ti, uo) = make_input(&mut OsRng, MicroTari::from(1000 * i), &factories.commitment);
@@ -588,6 +597,7 @@ pub async fn test_no_duplicate_outputs<T: OutputManagerBackend + 'static>(backen
         outputs_to_be_received: vec![uo],
         timestamp: Utc::now().naive_utc() - ChronoDuration::from_std(Duration::from_millis(120_000_000)).unwrap(),
         coinbase_block_height: None,
+        fee_bump: None,
     };
 
     match db.add_pending_transaction_outputs(pending_tx.clone()).await {
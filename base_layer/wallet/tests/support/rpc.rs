@@ -20,30 +20,47 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use futures::channel::mpsc;
+use rand::{rngs::OsRng, RngCore};
 use std::{
     convert::TryFrom,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
-use tari_comms::protocol::rpc::{Request, Response, RpcStatus};
+use tari_common_types::chain_metadata::ChainMetadata;
+use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
 use tari_core::{
     base_node::{
         proto::wallet_rpc::{TxLocation, TxQueryResponse, TxSubmissionRejectionReason, TxSubmissionResponse},
         rpc::BaseNodeWalletService,
+        sync::rpc::BaseNodeSyncService,
     },
+    blocks::BlockHeader,
     proto::{
         base_node::{
+            BlockBodyResponse,
+            ChainMetadata as ChainMetadataProto,
             FetchMatchingUtxos,
             FetchUtxosResponse,
+            FindChainSplitRequest,
+            FindChainSplitResponse,
             Signatures as SignaturesProto,
+            SyncBlocksRequest,
+            SyncHeadersRequest,
+            SyncKernelsRequest,
+            SyncUtxo,
+            SyncUtxosRequest,
+            SyncUtxosResponse,
             TxQueryBatchResponse as TxQueryBatchResponseProto,
             TxQueryBatchResponses as TxQueryBatchResponsesProto,
             TxQueryResponse as TxQueryResponseProto,
             TxSubmissionResponse as TxSubmissionResponseProto,
         },
+        core::BlockHeader as BlockHeaderProto,
         types::{
             Signature as SignatureProto,
             Transaction as TransactionProto,
+            TransactionKernel as TransactionKernelProto,
             TransactionOutput as TransactionOutputProto,
         },
     },
@@ -428,6 +445,214 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
     }
 }
 
+/// A block of the chain served by the [BaseNodeSyncRpcMockService]
+#[derive(Clone, Debug)]
+pub struct MockBlock {
+    pub hash: Vec<u8>,
+    pub header: BlockHeader,
+    pub outputs: Vec<TransactionOutput>,
+}
+
+impl MockBlock {
+    /// Create a block with the given outputs. The header, and with it the hash, is completed when the block is added
+    /// to the chain.
+    pub fn new(outputs: Vec<TransactionOutput>) -> Self {
+        Self {
+            hash: Vec::new(),
+            // Blocks with the same outputs at the same height are told apart by their nonce
+            header: BlockHeader {
+                nonce: OsRng.next_u64(),
+                ..Default::default()
+            },
+            outputs,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BaseNodeSyncRpcMockState {
+    blocks: Arc<Mutex<Vec<MockBlock>>>,
+    sync_utxos_calls: Arc<Mutex<Vec<SyncUtxosRequest>>>,
+    sync_utxos_call_limit: Arc<Mutex<Option<usize>>>,
+}
+
+impl BaseNodeSyncRpcMockState {
+    pub fn new() -> Self {
+        Self {
+            blocks: Arc::new(Mutex::new(Vec::new())),
+            sync_utxos_calls: Arc::new(Mutex::new(Vec::new())),
+            sync_utxos_call_limit: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Replace the blocks of the chain from `height` onwards with the given blocks. The block at index 0 is the genesis
+    /// block, so this both extends the chain and simulates a reorg.
+    pub fn set_blocks_from_height(&self, height: usize, blocks: Vec<MockBlock>) {
+        let mut lock = acquire_lock!(self.blocks);
+        lock.truncate(height);
+        for mut block in blocks {
+            let prev_block = lock.last();
+            block.header.height = lock.len() as u64;
+            block.header.prev_hash = prev_block.map(|b| b.hash.clone()).unwrap_or_default();
+            block.header.output_mmr_size =
+                prev_block.map(|b| b.header.output_mmr_size).unwrap_or(0) + block.outputs.len() as u64;
+            block.hash = block.header.hash();
+            lock.push(block);
+        }
+    }
+
+    /// The hash of the block at the given height
+    pub fn block_hash(&self, height: usize) -> Vec<u8> {
+        acquire_lock!(self.blocks)[height].hash.clone()
+    }
+
+    /// Fail the `sync_utxos` calls after the given number of calls have been served
+    pub fn set_sync_utxos_call_limit(&self, limit: Option<usize>) {
+        *acquire_lock!(self.sync_utxos_call_limit) = limit;
+    }
+
+    /// The size of the output MMR at the block at the given height
+    pub fn output_mmr_size(&self, height: usize) -> u64 {
+        acquire_lock!(self.blocks)
+            .iter()
+            .take(height + 1)
+            .map(|b| b.outputs.len() as u64)
+            .sum()
+    }
+
+    pub fn take_sync_utxos_calls(&self) -> Vec<SyncUtxosRequest> {
+        acquire_lock!(self.sync_utxos_calls).drain(..).collect()
+    }
+}
+
+impl Default for BaseNodeSyncRpcMockState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct BaseNodeSyncRpcMockService {
+    state: BaseNodeSyncRpcMockState,
+}
+
+impl BaseNodeSyncRpcMockService {
+    pub fn new() -> Self {
+        Self {
+            state: BaseNodeSyncRpcMockState::new(),
+        }
+    }
+
+    pub fn get_state(&self) -> BaseNodeSyncRpcMockState {
+        self.state.clone()
+    }
+}
+
+impl Default for BaseNodeSyncRpcMockService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tari_comms::async_trait]
+impl BaseNodeSyncService for BaseNodeSyncRpcMockService {
+    async fn sync_blocks(&self, _: Request<SyncBlocksRequest>) -> Result<Streaming<BlockBodyResponse>, RpcStatus> {
+        Err(RpcStatus::not_implemented("sync_blocks is not implemented by the mock"))
+    }
+
+    async fn sync_headers(&self, _: Request<SyncHeadersRequest>) -> Result<Streaming<BlockHeaderProto>, RpcStatus> {
+        Err(RpcStatus::not_implemented(
+            "sync_headers is not implemented by the mock",
+        ))
+    }
+
+    async fn get_header_by_height(&self, request: Request<u64>) -> Result<Response<BlockHeaderProto>, RpcStatus> {
+        let height = request.into_message();
+        let blocks = acquire_lock!(self.state.blocks);
+        let block = blocks
+            .get(height as usize)
+            .ok_or_else(|| RpcStatus::not_found(format!("Header not found at height {}", height)))?;
+
+        Ok(Response::new(block.header.clone().into()))
+    }
+
+    async fn find_chain_split(
+        &self,
+        request: Request<FindChainSplitRequest>,
+    ) -> Result<Response<FindChainSplitResponse>, RpcStatus>
+    {
+        let message = request.into_message();
+        let blocks = acquire_lock!(self.state.blocks);
+        let fork_hash_index = message
+            .block_hashes
+            .iter()
+            .position(|hash| blocks.iter().any(|b| &b.hash == hash))
+            .ok_or_else(|| RpcStatus::not_found("None of the block hashes are in the chain"))?;
+
+        Ok(Response::new(FindChainSplitResponse {
+            headers: Vec::new(),
+            fork_hash_index: fork_hash_index as u32,
+            tip_height: blocks.len().saturating_sub(1) as u64,
+        }))
+    }
+
+    async fn get_chain_metadata(&self, _: Request<()>) -> Result<Response<ChainMetadataProto>, RpcStatus> {
+        let blocks = acquire_lock!(self.state.blocks);
+        let tip = blocks
+            .last()
+            .ok_or_else(|| RpcStatus::not_found("The chain is empty"))?;
+        let metadata = ChainMetadata::new((blocks.len() - 1) as u64, tip.hash.clone(), 0, 0, 1);
+
+        Ok(Response::new(metadata.into()))
+    }
+
+    async fn sync_kernels(
+        &self,
+        _: Request<SyncKernelsRequest>,
+    ) -> Result<Streaming<TransactionKernelProto>, RpcStatus>
+    {
+        Err(RpcStatus::not_implemented(
+            "sync_kernels is not implemented by the mock",
+        ))
+    }
+
+    async fn sync_utxos(&self, request: Request<SyncUtxosRequest>) -> Result<Streaming<SyncUtxosResponse>, RpcStatus> {
+        let message = request.into_message();
+        let mut calls = acquire_lock!(self.state.sync_utxos_calls);
+        if let Some(limit) = *acquire_lock!(self.state.sync_utxos_call_limit) {
+            if calls.len() >= limit {
+                return Err(RpcStatus::general("The sync_utxos call limit of the mock was reached"));
+            }
+        }
+        calls.push(message.clone());
+        drop(calls);
+
+        let blocks = acquire_lock!(self.state.blocks);
+        let end_height = blocks
+            .iter()
+            .position(|b| b.hash == message.end_header_hash)
+            .ok_or_else(|| RpcStatus::not_found("End header hash not found"))?;
+        let utxos = blocks
+            .iter()
+            .take(end_height + 1)
+            .flat_map(|b| b.outputs.iter())
+            .skip(message.start as usize)
+            .map(|output| SyncUtxo {
+                output: Some(output.clone().into()),
+                hash: Vec::new(),
+                rangeproof_hash: Vec::new(),
+            })
+            .collect();
+
+        let (mut tx, rx) = mpsc::channel(1);
+        tx.try_send(Ok(SyncUtxosResponse {
+            utxos,
+            deleted_bitmaps: Vec::new(),
+        }))
+        .map_err(|_| RpcStatus::general("Failed to send the mock response"))?;
+        Ok(Streaming::new(rx))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::support::rpc::BaseNodeWalletRpcMockService;
@@ -282,6 +282,8 @@ pub fn setup_transaction_service_no_comms_and_oms_backend<
     let (tx_finalized_sender, tx_finalized_receiver) = mpsc::channel(20);
     let (base_node_response_sender, base_node_response_receiver) = mpsc::channel(20);
     let (tx_cancelled_sender, tx_cancelled_receiver) = mpsc::channel(20);
+    let (_tx_public_keys_sender, tx_public_keys_receiver) = mpsc::channel(20);
+    let (_tx_signature_request_sender, tx_signature_request_receiver) = mpsc::channel(20);
 
     let outbound_mock_state = mock_outbound_service.get_state();
     runtime.spawn(mock_outbound_service.run());
@@ -361,6 +363,8 @@ pub fn setup_transaction_service_no_comms_and_oms_backend<
         tx_finalized_receiver,
         base_node_response_receiver,
         tx_cancelled_receiver,
+        tx_public_keys_receiver,
+        tx_signature_request_receiver,
         output_manager_service_handle.clone(),
         outbound_message_requester,
         connectivity_manager,
@@ -598,6 +602,181 @@ fn manage_single_transaction() {
     );
 }
 
+#[test]
+fn manage_batch_transaction() {
+    let mut runtime = create_runtime();
+
+    let factories = CryptoFactories::default();
+    let alice_node_identity = Arc::new(
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap(),
+    );
+    let bob_node_identity = Arc::new(
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap(),
+    );
+    let carol_node_identity = Arc::new(
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap(),
+    );
+    let base_node_identity = Arc::new(
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap(),
+    );
+
+    let temp_dir = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+
+    let create_backend = || {
+        let db_name = format!("{}.sqlite3", random_string(8).as_str());
+        let db_path = format!("{}/{}", temp_dir.path().to_str().unwrap(), db_name);
+        let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+        TransactionServiceSqliteDatabase::new(connection, None)
+    };
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, mut alice_oms, _alice_comms) = setup_transaction_service(
+        &mut runtime,
+        alice_node_identity.clone(),
+        vec![],
+        factories.clone(),
+        create_backend(),
+        database_path.clone(),
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    runtime
+        .block_on(alice_ts.set_base_node_public_key(base_node_identity.public_key().clone()))
+        .unwrap();
+
+    runtime.block_on(async { delay_for(Duration::from_secs(2)).await });
+
+    let (mut bob_ts, mut bob_oms, bob_comms) = setup_transaction_service(
+        &mut runtime,
+        bob_node_identity.clone(),
+        vec![alice_node_identity.clone()],
+        factories.clone(),
+        create_backend(),
+        database_path.clone(),
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    runtime
+        .block_on(bob_ts.set_base_node_public_key(base_node_identity.public_key().clone()))
+        .unwrap();
+    let mut bob_event_stream = bob_ts.get_event_stream_fused();
+
+    let (mut carol_ts, mut carol_oms, carol_comms) = setup_transaction_service(
+        &mut runtime,
+        carol_node_identity.clone(),
+        vec![alice_node_identity.clone()],
+        factories.clone(),
+        create_backend(),
+        database_path,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    runtime
+        .block_on(carol_ts.set_base_node_public_key(base_node_identity.public_key().clone()))
+        .unwrap();
+    let mut carol_event_stream = carol_ts.get_event_stream_fused();
+
+    let _ = runtime.block_on(
+        bob_comms
+            .connection_manager()
+            .dial_peer(alice_node_identity.node_id().clone()),
+    );
+    let _ = runtime.block_on(
+        carol_comms
+            .connection_manager()
+            .dial_peer(alice_node_identity.node_id().clone()),
+    );
+
+    let bob_value = MicroTari::from(1000);
+    let carol_value = MicroTari::from(1500);
+    let (_utxo, uo1) = make_input(&mut OsRng, MicroTari(5000), &factories.commitment);
+    runtime.block_on(alice_oms.add_output(uo1)).unwrap();
+
+    assert!(runtime
+        .block_on(alice_ts.send_batch_transaction(
+            vec![(bob_node_identity.public_key().clone(), bob_value)],
+            MicroTari::from(20),
+            "".to_string(),
+            None,
+        ))
+        .is_err());
+
+    let tx_id = runtime
+        .block_on(alice_ts.send_batch_transaction(
+            vec![
+                (bob_node_identity.public_key().clone(), bob_value),
+                (carol_node_identity.public_key().clone(), carol_value),
+            ],
+            MicroTari::from(20),
+            "Pay day".to_string(),
+            None,
+        ))
+        .expect("Alice sending batch tx");
+
+    let mut bob_tx_id = 0u64;
+    let mut carol_tx_id = 0u64;
+    runtime.block_on(async {
+        let mut delay = delay_for(Duration::from_secs(90)).fuse();
+        loop {
+            futures::select! {
+                event = bob_event_stream.select_next_some() => {
+                    if let TransactionEvent::ReceivedFinalizedTransaction(id) = &*event.unwrap() {
+                        bob_tx_id = *id;
+                    }
+                },
+                event = carol_event_stream.select_next_some() => {
+                    if let TransactionEvent::ReceivedFinalizedTransaction(id) = &*event.unwrap() {
+                        carol_tx_id = *id;
+                    }
+                },
+                () = delay => {
+                    break;
+                },
+            }
+            if bob_tx_id != 0 && carol_tx_id != 0 {
+                break;
+            }
+        }
+    });
+    assert_ne!(bob_tx_id, 0, "Bob did not receive the finalized transaction");
+    assert_ne!(carol_tx_id, 0, "Carol did not receive the finalized transaction");
+    assert_ne!(bob_tx_id, carol_tx_id);
+
+    let alice_completed_tx = runtime
+        .block_on(alice_ts.get_completed_transaction(tx_id))
+        .expect("Could not find Alice's tx");
+    assert_eq!(alice_completed_tx.amount, bob_value + carol_value);
+    let bob_completed_tx = runtime
+        .block_on(bob_ts.get_completed_transaction(bob_tx_id))
+        .expect("Could not find Bob's tx");
+    let carol_completed_tx = runtime
+        .block_on(carol_ts.get_completed_transaction(carol_tx_id))
+        .expect("Could not find Carol's tx");
+    assert_eq!(bob_completed_tx.transaction, alice_completed_tx.transaction);
+    assert_eq!(carol_completed_tx.transaction, alice_completed_tx.transaction);
+
+    runtime
+        .block_on(bob_oms.confirm_transaction(bob_tx_id, vec![], bob_completed_tx.transaction.body.outputs().clone()))
+        .unwrap();
+    runtime
+        .block_on(carol_oms.confirm_transaction(
+            carol_tx_id,
+            vec![],
+            carol_completed_tx.transaction.body.outputs().clone(),
+        ))
+        .unwrap();
+
+    assert_eq!(
+        runtime.block_on(bob_oms.get_balance()).unwrap().available_balance,
+        bob_value
+    );
+    assert_eq!(
+        runtime.block_on(carol_oms.get_balance()).unwrap().available_balance,
+        carol_value
+    );
+}
+
 #[test]
 fn single_transaction_to_self() {
     let mut runtime = create_runtime();
@@ -2403,6 +2582,7 @@ fn test_restarting_transaction_protocols() {
         direct_send_success: false,
         send_count: 0,
         last_send_timestamp: None,
+        batch_recipients: Vec::new(),
     };
     bob_backend
         .write(WriteOperation::Insert(DbKeyValuePair::PendingOutboundTransaction(
@@ -4339,6 +4519,8 @@ fn broadcast_all_completed_transactions_on_startup() {
         coinbase_block_height: None,
         send_count: 0,
         last_send_timestamp: None,
+        batch_recipients: Vec::new(),
+        batch_recipients: Vec::new(),
         valid: true,
     };
 
@@ -95,6 +95,7 @@ pub fn test_db_backend<T: TransactionBackend + 'static>(backend: T) {
             direct_send_success: false,
             send_count: 0,
             last_send_timestamp: None,
+            batch_recipients: Vec::new(),
         });
         assert!(
             !runtime.block_on(db.transaction_exists((i + 10) as u64)).unwrap(),
@@ -313,6 +314,24 @@ pub fn test_db_backend<T: TransactionBackend + 'static>(backend: T) {
             TransactionStatus::Broadcast
         );
 
+        // A replacement transaction must be broadcast again
+        let replacement = retrieved_completed_txs.get(&completed_txs[0].tx_id).unwrap();
+        runtime
+            .block_on(db.replace_completed_transaction(
+                replacement.tx_id,
+                replacement.transaction.clone(),
+                replacement.fee + MicroTari::from(100),
+            ))
+            .unwrap();
+        let retrieved_completed_tx = runtime
+            .block_on(db.get_completed_transaction(completed_txs[0].tx_id))
+            .unwrap();
+        assert_eq!(retrieved_completed_tx.status, TransactionStatus::Completed);
+        assert_eq!(retrieved_completed_tx.fee, replacement.fee + MicroTari::from(100));
+        runtime
+            .block_on(db.broadcast_completed_transaction(completed_txs[0].tx_id))
+            .unwrap();
+
         #[cfg(feature = "test_harness")]
         runtime
             .block_on(db.mine_completed_transaction(completed_txs[0].tx_id))
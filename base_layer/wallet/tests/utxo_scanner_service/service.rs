@@ -0,0 +1,426 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::support::{
+    rpc::{BaseNodeSyncRpcMockService, BaseNodeSyncRpcMockState, MockBlock},
+    utils::make_input,
+};
+use futures::{stream::Fuse, StreamExt};
+use rand::{rngs::OsRng, RngCore};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tari_comms::{
+    peer_manager::{Peer, PeerFeatures},
+    protocol::rpc::{mock::MockRpcServer, NamedProtocolService},
+    test_utils::{mocks::create_connectivity_mock, node_identity::build_node_identity},
+    Substream,
+};
+use tari_core::{
+    base_node::sync::rpc::BaseNodeSyncRpcServer,
+    transactions::{
+        tari_amount::MicroTari,
+        transaction::{TransactionOutput, UnblindedOutput},
+        types::CryptoFactories,
+    },
+};
+use tari_service_framework::reply_channel;
+use tari_shutdown::Shutdown;
+use tari_wallet::{
+    base_node_service::handle::{BaseNodeEvent, BaseNodeEventSender, BaseNodeServiceHandle},
+    output_manager_service::{
+        error::{OutputManagerError, OutputManagerStorageError},
+        handle::{OutputManagerHandle, OutputManagerRequest, OutputManagerResponse},
+        protocols::txo_validation_protocol::TxoValidationType,
+    },
+    storage::{database::WalletDatabase, memory_db::WalletMemoryDatabase},
+    transaction_service::{
+        error::TransactionServiceError,
+        handle::{TransactionServiceHandle, TransactionServiceRequest, TransactionServiceResponse},
+    },
+    utxo_scanner_service::{
+        handle::{UtxoScannerEvent, UtxoScannerEventReceiver, UtxoScannerHandle},
+        service::{UtxoScannerResources, UtxoScannerService},
+    },
+};
+use tokio::{sync::broadcast, time};
+
+/// The state of the mock output manager. The outputs in `rewindable` belong to the wallet and are recovered when they
+/// are rewound.
+#[derive(Clone, Default)]
+struct OutputManagerMockState {
+    rewindable: Arc<Mutex<Vec<(TransactionOutput, UnblindedOutput)>>>,
+    added: Arc<Mutex<Vec<UnblindedOutput>>>,
+    validations: Arc<Mutex<Vec<TxoValidationType>>>,
+}
+
+fn spawn_output_manager_mock(state: OutputManagerMockState) -> OutputManagerHandle {
+    let (sender, receiver) = reply_channel::unbounded();
+    let (event_publisher, _) = broadcast::channel(100);
+    tokio::spawn(async move {
+        futures::pin_mut!(receiver);
+        while let Some(request_context) = receiver.next().await {
+            let (request, reply_tx) = request_context.split();
+            let response = match request {
+                OutputManagerRequest::RewindOutputs(outputs) => {
+                    let rewindable = state.rewindable.lock().unwrap();
+                    Ok(OutputManagerResponse::RewindOutputs(
+                        outputs
+                            .iter()
+                            .filter_map(|o| {
+                                rewindable
+                                    .iter()
+                                    .find(|(output, _)| output.commitment == o.commitment)
+                                    .map(|(_, uo)| uo.clone())
+                            })
+                            .collect(),
+                    ))
+                },
+                OutputManagerRequest::AddOutput(uo) => {
+                    let mut added = state.added.lock().unwrap();
+                    if added.iter().any(|a| a.spending_key == uo.spending_key) {
+                        Err(OutputManagerError::OutputManagerStorageError(
+                            OutputManagerStorageError::DuplicateOutput,
+                        ))
+                    } else {
+                        added.push(uo);
+                        Ok(OutputManagerResponse::OutputAdded)
+                    }
+                },
+                OutputManagerRequest::ValidateUtxos(validation_type, _) => {
+                    state.validations.lock().unwrap().push(validation_type);
+                    Ok(OutputManagerResponse::UtxoValidationStarted(OsRng.next_u64()))
+                },
+                _ => Err(OutputManagerError::UnexpectedApiResponse),
+            };
+            let _ = reply_tx.send(response);
+        }
+    });
+    OutputManagerHandle::new(sender, event_publisher)
+}
+
+fn spawn_transaction_service_mock(num_imported: Arc<Mutex<usize>>) -> TransactionServiceHandle {
+    let (sender, receiver) = reply_channel::unbounded();
+    let (event_publisher, _) = broadcast::channel(100);
+    tokio::spawn(async move {
+        futures::pin_mut!(receiver);
+        while let Some(request_context) = receiver.next().await {
+            let (request, reply_tx) = request_context.split();
+            let response = match request {
+                TransactionServiceRequest::ImportUtxo(_, _, _) => {
+                    *num_imported.lock().unwrap() += 1;
+                    Ok(TransactionServiceResponse::UtxoImported(OsRng.next_u64()))
+                },
+                _ => Err(TransactionServiceError::UnexpectedApiResponse),
+            };
+            let _ = reply_tx.send(response);
+        }
+    });
+    TransactionServiceHandle::new(sender, event_publisher)
+}
+
+struct TestHarness {
+    scanner: UtxoScannerHandle,
+    scanner_events: Fuse<UtxoScannerEventReceiver>,
+    base_node_events: BaseNodeEventSender,
+    base_node_peer: Peer,
+    sync_state: BaseNodeSyncRpcMockState,
+    oms_state: OutputManagerMockState,
+    num_imported: Arc<Mutex<usize>>,
+    _rpc_server: MockRpcServer<BaseNodeSyncRpcServer<BaseNodeSyncRpcMockService>, Substream>,
+    _shutdown: Shutdown,
+}
+
+impl TestHarness {
+    /// Create an output that can be rewound by the wallet
+    fn create_wallet_output(&self, value: u64) -> TransactionOutput {
+        let factories = CryptoFactories::default();
+        let (_, uo) = make_input(&mut OsRng, MicroTari::from(value), &factories.commitment);
+        let output = uo.as_transaction_output(&factories).unwrap();
+        self.oms_state.rewindable.lock().unwrap().push((output.clone(), uo));
+        output
+    }
+
+    /// Tell the scanner that the base node peer has been set and wait for the scan that follows to complete. Returns
+    /// the events published during the scan.
+    async fn scan(&mut self) -> Vec<UtxoScannerEvent> {
+        let events = self.try_scan().await;
+        if let Some(UtxoScannerEvent::ScanningFailed(e)) = events.last() {
+            panic!("Scan failed: {}", e);
+        }
+        events
+    }
+
+    /// Tell the scanner that the base node peer has been set and wait for the scan that follows to complete or fail.
+    /// Returns the events published during the scan.
+    async fn try_scan(&mut self) -> Vec<UtxoScannerEvent> {
+        self.base_node_events
+            .send(Arc::new(BaseNodeEvent::BaseNodePeerSet(Box::new(
+                self.base_node_peer.clone(),
+            ))))
+            .unwrap();
+
+        let mut events = Vec::new();
+        loop {
+            let event = time::timeout(Duration::from_secs(10), self.scanner_events.next())
+                .await
+                .expect("Timed out waiting for the scan to complete")
+                .unwrap()
+                .unwrap();
+            events.push((*event).clone());
+            match &*event {
+                UtxoScannerEvent::ScanningCompleted { .. } | UtxoScannerEvent::ScanningFailed(_) => return events,
+                _ => {},
+            }
+        }
+    }
+}
+
+async fn setup() -> TestHarness {
+    let shutdown = Shutdown::new();
+
+    let (connectivity, connectivity_mock) = create_connectivity_mock();
+    let connectivity_mock_state = connectivity_mock.get_shared_state();
+    tokio::spawn(connectivity_mock.run());
+
+    let service = BaseNodeSyncRpcMockService::new();
+    let sync_state = service.get_state();
+    let server = BaseNodeSyncRpcServer::new(service);
+    let protocol_name = server.as_protocol_name();
+    let server_node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+    let mut rpc_server = MockRpcServer::new(server, server_node_identity.clone());
+    rpc_server.serve();
+    let connection = rpc_server
+        .create_connection(server_node_identity.to_peer(), protocol_name.into())
+        .await;
+    connectivity_mock_state.add_active_connection(connection).await;
+
+    let oms_state = OutputManagerMockState::default();
+    let num_imported = Arc::new(Mutex::new(0));
+    let (sender, receiver) = reply_channel::unbounded();
+    let (event_publisher, _) = broadcast::channel(200);
+    let mut scanner = UtxoScannerHandle::new(sender, event_publisher.clone());
+    let scanner_events = scanner.get_event_stream_fused();
+
+    let (base_node_sender, _) = reply_channel::unbounded();
+    let (base_node_events, _) = broadcast::channel(100);
+    let resources = UtxoScannerResources {
+        db: WalletDatabase::new(WalletMemoryDatabase::new()),
+        connectivity,
+        output_manager_service: spawn_output_manager_mock(oms_state.clone()),
+        transaction_service: spawn_transaction_service_mock(num_imported.clone()),
+        node_identity: build_node_identity(PeerFeatures::COMMUNICATION_NODE),
+        event_publisher,
+    };
+    let service = UtxoScannerService::new(
+        receiver,
+        resources,
+        BaseNodeServiceHandle::new(base_node_sender, base_node_events.clone()),
+        shutdown.to_signal(),
+    );
+    tokio::spawn(service.start());
+    // The service subscribes to the base node events before it handles requests
+    assert!(scanner.get_last_scanned_block().await.unwrap().is_none());
+
+    // The genesis block
+    sync_state.set_blocks_from_height(0, vec![block(Vec::new())]);
+
+    TestHarness {
+        scanner,
+        scanner_events,
+        base_node_events,
+        base_node_peer: server_node_identity.to_peer(),
+        sync_state,
+        oms_state,
+        num_imported,
+        _rpc_server: rpc_server,
+        _shutdown: shutdown,
+    }
+}
+
+fn block(outputs: Vec<TransactionOutput>) -> MockBlock {
+    MockBlock::new(outputs)
+}
+
+fn create_foreign_output(value: u64) -> TransactionOutput {
+    let factories = CryptoFactories::default();
+    let (_, uo) = make_input(&mut OsRng, MicroTari::from(value), &factories.commitment);
+    uo.as_transaction_output(&factories).unwrap()
+}
+
+#[tokio_macros::test]
+async fn scan_resumes_from_last_scanned_block() {
+    let mut harness = setup().await;
+
+    let output = harness.create_wallet_output(1000);
+    harness
+        .sync_state
+        .set_blocks_from_height(1, vec![block(vec![create_foreign_output(500), output])]);
+    let events = harness.scan().await;
+    assert!(events.contains(&UtxoScannerEvent::ScanningStarted {
+        start_index: 0,
+        tip_height: 1,
+    }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningCompleted {
+        height: 1,
+        num_recovered: 1,
+        value_recovered: MicroTari::from(1000),
+    }));
+
+    let output = harness.create_wallet_output(3000);
+    harness.sync_state.set_blocks_from_height(2, vec![block(vec![output])]);
+    let events = harness.scan().await;
+    // Only the outputs after the last scanned block are scanned
+    assert!(events.contains(&UtxoScannerEvent::ScanningStarted {
+        start_index: 2,
+        tip_height: 2,
+    }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningCompleted {
+        height: 2,
+        num_recovered: 1,
+        value_recovered: MicroTari::from(3000),
+    }));
+
+    let starts = harness
+        .sync_state
+        .take_sync_utxos_calls()
+        .into_iter()
+        .map(|req| req.start)
+        .collect::<Vec<_>>();
+    assert_eq!(starts, vec![0, 2]);
+    assert_eq!(*harness.num_imported.lock().unwrap(), 2);
+    assert!(harness.oms_state.validations.lock().unwrap().is_empty());
+
+    let last_scanned = harness.scanner.get_last_scanned_block().await.unwrap().unwrap();
+    assert_eq!(last_scanned.height, 2);
+    assert_eq!(last_scanned.output_mmr_size, 3);
+}
+
+#[tokio_macros::test]
+async fn reorg_revalidates_unspent_outputs() {
+    let mut harness = setup().await;
+
+    harness
+        .sync_state
+        .set_blocks_from_height(1, vec![block(vec![create_foreign_output(500)])]);
+    harness.scan().await;
+    let output = harness.create_wallet_output(1000);
+    harness.sync_state.set_blocks_from_height(2, vec![block(vec![output])]);
+    harness.scan().await;
+    assert!(harness.oms_state.validations.lock().unwrap().is_empty());
+
+    // The block containing the wallet's output is reorged out
+    let output = harness.create_wallet_output(2000);
+    harness
+        .sync_state
+        .set_blocks_from_height(2, vec![block(vec![create_foreign_output(500)]), block(vec![output])]);
+    let events = harness.scan().await;
+    assert!(events.contains(&UtxoScannerEvent::ReorgDetected { fork_height: 1 }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningStarted {
+        start_index: harness.sync_state.output_mmr_size(1),
+        tip_height: 3,
+    }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningCompleted {
+        height: 3,
+        num_recovered: 1,
+        value_recovered: MicroTari::from(2000),
+    }));
+    // The output imported from the orphaned block is invalidated by validating the unspent outputs
+    assert_eq!(*harness.oms_state.validations.lock().unwrap(), vec![
+        TxoValidationType::Unspent
+    ]);
+}
+
+#[tokio_macros::test]
+async fn duplicate_outputs_are_not_imported_again() {
+    let mut harness = setup().await;
+
+    let output = harness.create_wallet_output(1000);
+    harness.sync_state.set_blocks_from_height(1, vec![block(vec![output])]);
+    harness.scan().await;
+    assert_eq!(*harness.num_imported.lock().unwrap(), 1);
+
+    harness.scanner.rescan_from_genesis().await.unwrap();
+    let events = harness.scan().await;
+    assert!(events.contains(&UtxoScannerEvent::ScanningStarted {
+        start_index: 0,
+        tip_height: 1,
+    }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningCompleted {
+        height: 1,
+        num_recovered: 0,
+        value_recovered: MicroTari::from(0),
+    }));
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, UtxoScannerEvent::OutputsRecovered { .. })));
+    assert_eq!(*harness.num_imported.lock().unwrap(), 1);
+    assert_eq!(harness.oms_state.added.lock().unwrap().len(), 1);
+}
+
+#[tokio_macros::test]
+async fn scan_progress_is_saved_after_each_chunk() {
+    let mut harness = setup().await;
+
+    let mut blocks = (1..=2500).map(|_| block(Vec::new())).collect::<Vec<_>>();
+    blocks[499] = block(vec![harness.create_wallet_output(1000)]);
+    blocks[1499] = block(vec![harness.create_wallet_output(2000)]);
+    blocks[2499] = block(vec![harness.create_wallet_output(3000)]);
+    harness.sync_state.set_blocks_from_height(1, blocks);
+
+    // The scan of the third chunk fails
+    harness.sync_state.set_sync_utxos_call_limit(Some(2));
+    let events = harness.try_scan().await;
+    assert!(matches!(events.last(), Some(UtxoScannerEvent::ScanningFailed(_))));
+    let last_scanned = harness.scanner.get_last_scanned_block().await.unwrap().unwrap();
+    assert_eq!(last_scanned.height, 2000);
+    assert_eq!(last_scanned.header_hash, harness.sync_state.block_hash(2000));
+    assert_eq!(last_scanned.output_mmr_size, harness.sync_state.output_mmr_size(2000));
+    assert_eq!(*harness.num_imported.lock().unwrap(), 2);
+
+    // The next scan resumes from the last completed chunk
+    harness.sync_state.set_sync_utxos_call_limit(None);
+    let events = harness.scan().await;
+    assert!(events.contains(&UtxoScannerEvent::ScanningStarted {
+        start_index: harness.sync_state.output_mmr_size(2000),
+        tip_height: 2500,
+    }));
+    assert!(events.contains(&UtxoScannerEvent::ScanningCompleted {
+        height: 2500,
+        num_recovered: 1,
+        value_recovered: MicroTari::from(3000),
+    }));
+    assert_eq!(*harness.num_imported.lock().unwrap(), 3);
+
+    let end_header_hashes = harness
+        .sync_state
+        .take_sync_utxos_calls()
+        .into_iter()
+        .map(|req| req.end_header_hash)
+        .collect::<Vec<_>>();
+    assert_eq!(end_header_hashes, vec![
+        harness.sync_state.block_hash(1000),
+        harness.sync_state.block_hash(2000),
+        harness.sync_state.block_hash(2500),
+    ]);
+}
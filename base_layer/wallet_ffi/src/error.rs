@@ -172,6 +172,14 @@ impl From<WalletError> for LibWalletError {
                 code: 113,
                 message: format!("{:?}", w),
             },
+            WalletError::OutputManagerError(OutputManagerError::AccountNotFound(_)) => Self {
+                code: 115,
+                message: format!("{:?}", w),
+            },
+            WalletError::OutputManagerError(OutputManagerError::DuplicateAccount(_)) => Self {
+                code: 116,
+                message: format!("{:?}", w),
+            },
             WalletError::OutputManagerError(_) => Self {
                 code: 114,
                 message: format!("{:?}", w),
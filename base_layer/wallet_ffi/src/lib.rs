@@ -3996,6 +3996,228 @@ pub unsafe extern "C" fn wallet_get_completed_transactions(
     }
 }
 
+/// Creates a new named account in a TariWallet. Each account derives its keys from its own key branch and has its own
+/// outputs, balance and transaction history.
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `account_name` - The pointer to a Utf8 string representing the name of the new account
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Return a boolean value indicating the operation's success or failure. The error_ptr will hold the error
+/// code if there was a failure
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn wallet_create_account(
+    wallet: *mut TariWallet,
+    account_name: *const c_char,
+    error_out: *mut c_int,
+) -> bool
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+
+    let account_name_string;
+    if account_name.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("account_name".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    } else {
+        account_name_string = CStr::from_ptr(account_name).to_str().unwrap().to_owned();
+    }
+
+    match (*wallet).runtime.block_on(
+        (*wallet)
+            .wallet
+            .output_manager_service
+            .create_account(account_name_string),
+    ) {
+        Ok(_) => true,
+        Err(e) => {
+            error = LibWalletError::from(WalletError::OutputManagerError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            false
+        },
+    }
+}
+
+/// Selects the account of a TariWallet that new receiving keys are derived from and that outgoing transactions may
+/// spend from
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `account_name` - The pointer to a Utf8 string representing the name of the account to select
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Return a boolean value indicating the operation's success or failure. The error_ptr will hold the error
+/// code if there was a failure
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn wallet_set_active_account(
+    wallet: *mut TariWallet,
+    account_name: *const c_char,
+    error_out: *mut c_int,
+) -> bool
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+
+    let account_name_string;
+    if account_name.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("account_name".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    } else {
+        account_name_string = CStr::from_ptr(account_name).to_str().unwrap().to_owned();
+    }
+
+    match (*wallet).runtime.block_on(
+        (*wallet)
+            .wallet
+            .output_manager_service
+            .set_active_account(account_name_string),
+    ) {
+        Ok(_) => true,
+        Err(e) => {
+            error = LibWalletError::from(WalletError::OutputManagerError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            false
+        },
+    }
+}
+
+/// Gets the available balance of a single named account of a TariWallet
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `account_name` - The pointer to a Utf8 string representing the name of the account
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `c_ulonglong` - The available balance of the account, 0 if wallet is null or the account does not exist
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_account_available_balance(
+    wallet: *mut TariWallet,
+    account_name: *const c_char,
+    error_out: *mut c_int,
+) -> c_ulonglong
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return 0;
+    }
+
+    let account_name_string;
+    if account_name.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("account_name".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return 0;
+    } else {
+        account_name_string = CStr::from_ptr(account_name).to_str().unwrap().to_owned();
+    }
+
+    match (*wallet).runtime.block_on(
+        (*wallet)
+            .wallet
+            .output_manager_service
+            .get_account_balance(account_name_string),
+    ) {
+        Ok(b) => c_ulonglong::from(b.available_balance),
+        Err(e) => {
+            error = LibWalletError::from(WalletError::OutputManagerError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            0
+        },
+    }
+}
+
+/// Get the TariCompletedTransactions that belong to a single named account of a TariWallet
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `account_name` - The pointer to a Utf8 string representing the name of the account
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `*mut TariCompletedTransactions` - returns the transactions, note that it returns ptr::null_mut() if
+/// wallet is null or an error is encountered
+///
+/// # Safety
+/// The ```completed_transactions_destroy``` method must be called when finished with a TariCompletedTransactions to
+/// prevent a memory leak
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_account_completed_transactions(
+    wallet: *mut TariWallet,
+    account_name: *const c_char,
+    error_out: *mut c_int,
+) -> *mut TariCompletedTransactions
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return ptr::null_mut();
+    }
+
+    let account_name_string;
+    if account_name.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("account_name".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return ptr::null_mut();
+    } else {
+        account_name_string = CStr::from_ptr(account_name).to_str().unwrap().to_owned();
+    }
+
+    match (*wallet).runtime.block_on(
+        (*wallet)
+            .wallet
+            .get_account_completed_transactions(account_name_string.as_str()),
+    ) {
+        Ok(completed_transactions) => {
+            // As with wallet_get_completed_transactions, Completed and Broadcast transactions are reported as pending
+            let completed = completed_transactions
+                .values()
+                .filter(|ct| ct.status != TransactionStatus::Completed)
+                .filter(|ct| ct.status != TransactionStatus::Broadcast)
+                .cloned()
+                .collect();
+            Box::into_raw(Box::new(TariCompletedTransactions(completed)))
+        },
+        Err(e) => {
+            error = LibWalletError::from(e).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            ptr::null_mut()
+        },
+    }
+}
+
 /// Get the TariPendingInboundTransactions from a TariWallet
 ///
 /// Currently a CompletedTransaction with the Status of Completed and Broadcast is considered Pending by the frontend
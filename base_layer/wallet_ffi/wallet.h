@@ -502,6 +502,18 @@ struct TariContacts *wallet_get_contacts(struct TariWallet *wallet,int* error_ou
 // Get the TariCompletedTransactions from a TariWallet
 struct TariCompletedTransactions *wallet_get_completed_transactions(struct TariWallet *wallet,int* error_out);
 
+// Creates a new named account in a TariWallet with its own key branch, balance and transaction history
+bool wallet_create_account(struct TariWallet *wallet, const char* account_name, int* error_out);
+
+// Selects the account of a TariWallet that receives new outputs and that outgoing transactions spend from
+bool wallet_set_active_account(struct TariWallet *wallet, const char* account_name, int* error_out);
+
+// Gets the available balance of a named account of a TariWallet
+unsigned long long wallet_get_account_available_balance(struct TariWallet *wallet, const char* account_name, int* error_out);
+
+// Get the TariCompletedTransactions belonging to a named account of a TariWallet
+struct TariCompletedTransactions *wallet_get_account_completed_transactions(struct TariWallet *wallet, const char* account_name, int* error_out);
+
 // Get the TariPendingOutboundTransactions from a TariWallet
 struct TariPendingOutboundTransactions *wallet_get_pending_outbound_transactions(struct TariWallet *wallet,int* error_out);
 
@@ -105,6 +105,9 @@ pub struct ConfigBootstrap {
     /// This will rebuild the db, adding block for block in
     #[structopt(long, alias("rebuild_db"))]
     pub rebuild_db: bool,
+    /// Import a chain snapshot into an empty blockchain database before starting the node
+    #[structopt(long, alias("import_snapshot"), parse(from_os_str))]
+    pub import_snapshot: Option<PathBuf>,
     /// Path to input file of commands
     #[structopt(short, long, alias("input"), alias("script"), parse(from_os_str))]
     pub input_file: Option<PathBuf>,
@@ -123,6 +126,9 @@ pub struct ConfigBootstrap {
     /// Force wallet recovery
     #[structopt(long, alias("recover"))]
     pub recovery: bool,
+    /// Run the wallet in watch-only mode, tracking incoming funds with the wallet's rewind keys
+    #[structopt(long)]
+    pub watch_only: bool,
     /// Wallet notify script
     #[structopt(long, alias("notify"))]
     pub wallet_notify: Option<PathBuf>,
@@ -138,12 +144,14 @@ impl Default for ConfigBootstrap {
             create_id: false,
             daemon_mode: false,
             rebuild_db: false,
+            import_snapshot: None,
             input_file: None,
             command: None,
             clean_orphans_db: false,
             password: None,
             change_password: false,
             recovery: false,
+            watch_only: false,
             wallet_notify: None,
         }
     }
@@ -60,6 +60,10 @@ pub struct GlobalConfig {
     pub orphan_db_clean_out_threshold: usize,
     pub pruning_horizon: u64,
     pub pruned_mode_cleanup_interval: u64,
+    pub commitment_history_index: bool,
+    pub mempool_persistence: bool,
+    pub mempool_persistence_interval: u64,
+    pub mempool_persistence_file: PathBuf,
     pub core_threads: Option<usize>,
     pub max_threads: Option<usize>,
     pub base_node_identity_file: PathBuf,
@@ -67,6 +71,7 @@ pub struct GlobalConfig {
     pub grpc_enabled: bool,
     pub grpc_base_node_address: SocketAddr,
     pub grpc_console_wallet_address: SocketAddr,
+    pub metrics_server_address: Option<SocketAddr>,
     pub peer_seeds: Vec<String>,
     pub dns_seeds: Vec<String>,
     pub dns_seeds_name_server: SocketAddr,
@@ -115,6 +120,10 @@ pub struct GlobalConfig {
     pub auto_ping_interval: u64,
     pub blocks_behind_before_considered_lagging: u64,
     pub flood_ban_max_msg_count: usize,
+    pub rpc_max_sessions_per_peer: Option<usize>,
+    pub rpc_max_requests_per_second_per_method: Option<usize>,
+    pub rpc_max_bytes_per_peer: Option<u64>,
+    pub rpc_max_bytes_per_peer_window: u64,
 }
 
 impl GlobalConfig {
@@ -235,6 +244,21 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         .get_int(&key)
         .map_err(|e| ConfigurationError::new(&key, &e.to_string()))? as u64;
 
+    let key = config_string("base_node", &net_str, "commitment_history_index");
+    let commitment_history_index = cfg
+        .get_bool(&key)
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "mempool_persistence");
+    let mempool_persistence = cfg
+        .get_bool(&key)
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "mempool_persistence_interval");
+    let mempool_persistence_interval = cfg
+        .get_int(&key)
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))? as u64;
+
     // Thread counts
     let key = config_string("base_node", &net_str, "core_threads");
     let core_threads =
@@ -310,6 +334,15 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
                 .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
         })?;
 
+    let key = config_string("base_node", &net_str, "metrics_server_address");
+    let metrics_server_address = optional(cfg.get_str(&key))
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
+        .map(|addr| {
+            addr.parse::<SocketAddr>()
+                .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
+        })
+        .transpose()?;
+
     let key = config_string("base_node", &net_str, "grpc_console_wallet_address");
     let grpc_console_wallet_address = cfg
         .get_str(&key)
@@ -353,6 +386,7 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
 
     // Peer DB path
     let peer_db_path = data_dir.join("peer_db");
+    let mempool_persistence_file = data_dir.join("mempool.dat");
     let wallet_peer_db_path = data_dir.join("wallet_peer_db");
     let console_wallet_peer_db_path = data_dir.join("console_wallet_peer_db");
 
@@ -372,6 +406,24 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         .get_int(&key)
         .map_err(|e| ConfigurationError::new(&key, &e.to_string()))? as usize;
 
+    // RPC server quotas
+    let key = config_string("base_node", &net_str, "rpc_max_sessions_per_peer");
+    let rpc_max_sessions_per_peer =
+        optional(cfg.get_int(&key).map(|n| n as usize)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "rpc_max_requests_per_second_per_method");
+    let rpc_max_requests_per_second_per_method =
+        optional(cfg.get_int(&key).map(|n| n as usize)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "rpc_max_bytes_per_peer");
+    let rpc_max_bytes_per_peer =
+        optional(cfg.get_int(&key).map(|n| n as u64)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "rpc_max_bytes_per_peer_window");
+    let rpc_max_bytes_per_peer_window = optional(cfg.get_int(&key))
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
+        .unwrap_or(60) as u64;
+
     // block sync
     let key = config_string("base_node", &net_str, "force_sync_peers");
     let force_sync_peers = optional(
@@ -585,6 +637,10 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         orphan_db_clean_out_threshold,
         pruning_horizon,
         pruned_mode_cleanup_interval,
+        commitment_history_index,
+        mempool_persistence,
+        mempool_persistence_interval,
+        mempool_persistence_file,
         core_threads,
         max_threads,
         base_node_identity_file,
@@ -592,6 +648,7 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         grpc_enabled,
         grpc_base_node_address,
         grpc_console_wallet_address,
+        metrics_server_address,
         peer_seeds,
         dns_seeds,
         dns_seeds_name_server,
@@ -640,6 +697,10 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         auto_ping_interval,
         blocks_behind_before_considered_lagging,
         flood_ban_max_msg_count,
+        rpc_max_sessions_per_peer,
+        rpc_max_requests_per_second_per_method,
+        rpc_max_bytes_per_peer,
+        rpc_max_bytes_per_peer_window,
     })
 }
 
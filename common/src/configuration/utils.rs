@@ -113,6 +113,11 @@ pub fn default_config(bootstrap: &ConfigBootstrap) -> Config {
     cfg.set_default("base_node.mainnet.pruning_horizon", 0).unwrap();
     cfg.set_default("base_node.mainnet.pruned_mode_cleanup_interval", 50)
         .unwrap();
+    cfg.set_default("base_node.mainnet.commitment_history_index", false)
+        .unwrap();
+    cfg.set_default("base_node.mainnet.mempool_persistence", true).unwrap();
+    cfg.set_default("base_node.mainnet.mempool_persistence_interval", 60)
+        .unwrap();
     cfg.set_default("base_node.mainnet.peer_seeds", Vec::<String>::new())
         .unwrap();
     cfg.set_default("base_node.mainnet.dns_seeds", Vec::<String>::new())
@@ -172,6 +177,11 @@ pub fn default_config(bootstrap: &ConfigBootstrap) -> Config {
     cfg.set_default("base_node.stibbons.pruning_horizon", 0).unwrap();
     cfg.set_default("base_node.stibbons.pruned_mode_cleanup_interval", 50)
         .unwrap();
+    cfg.set_default("base_node.stibbons.commitment_history_index", false)
+        .unwrap();
+    cfg.set_default("base_node.stibbons.mempool_persistence", true).unwrap();
+    cfg.set_default("base_node.stibbons.mempool_persistence_interval", 60)
+        .unwrap();
     cfg.set_default("base_node.stibbons.flood_ban_max_msg_count", 1000)
         .unwrap();
     cfg.set_default("base_node.stibbons.peer_seeds", Vec::<String>::new())
@@ -34,7 +34,7 @@ use std::{sync::Arc, task::Poll, time::Duration};
 use tari_comms::{
     connectivity::ConnectivityRequester,
     message::EnvelopeBody,
-    peer_manager::NodeIdentity,
+    peer_manager::{NodeIdentity, ReputationEvent},
     pipeline::PipelineError,
     types::CommsPublicKey,
     utils::signature,
@@ -182,6 +182,15 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
                     source.node_id,
                     trace_id
                 );
+                if let Err(err) = connectivity
+                    .adjust_peer_reputation(source.node_id.clone(), ReputationEvent::InvalidMessage)
+                    .await
+                {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to adjust the reputation of peer {}: {}", source.node_id, err
+                    );
+                }
                 Ok(())
             },
             Err(err) => Err(err.into()),
@@ -378,8 +387,8 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
                 Ok(DecryptedDhtMessage::succeeded(deserialized, authenticated_pk, message))
             },
             Err(err) => {
-                // Message was not encrypted but failed to deserialize - immediately discard
-                // TODO: Bad node behaviour?
+                // Message was not encrypted but failed to deserialize - immediately discard. The reputation of the
+                // source peer is lowered by the caller.
                 debug!(
                     target: LOG_TARGET,
                     "Unable to deserialize message {}: {}. Message will be discarded. (Trace: {})",
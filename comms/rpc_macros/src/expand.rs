@@ -64,6 +64,7 @@ impl TraitInfoCollector {
             method_ident: node.sig.ident.clone(),
             method_num: 0,
             is_server_streaming: false,
+            is_client_streaming: false,
             request_type: None,
             return_type: None,
         };
@@ -198,8 +199,15 @@ impl TraitInfoCollector {
                                 .first()
                                 .ok_or_else(|| syn_error!(request_arg, "expected Request<T>"))?;
                             match arg {
-                                GenericArgument::Type(ty) => {
-                                    info.request_type = Some((*ty).clone());
+                                GenericArgument::Type(ty) => match request_stream_item_type(ty) {
+                                    // Check if the request is streaming i.e. Request<RequestStream<T>>
+                                    Some(item_ty) => {
+                                        info.is_client_streaming = true;
+                                        info.request_type = Some(item_ty.clone());
+                                    },
+                                    None => {
+                                        info.request_type = Some((*ty).clone());
+                                    },
                                 },
                                 _ => return Err(syn_error!(request_arg, "expected request type")),
                             }
@@ -299,6 +307,26 @@ impl Fold for TraitInfoCollector {
     }
 }
 
+/// Returns the `T` in `RequestStream<T>`, or None if the type is not a `RequestStream`
+fn request_stream_item_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(syn::TypePath { path, .. }) => {
+            let segment = path.segments.last()?;
+            if segment.ident != "RequestStream" {
+                return None;
+            }
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first()? {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                },
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
 fn extract_u32(ident: &syn::Ident, lit: &syn::Lit) -> syn::Result<u32> {
     match lit {
         syn::Lit::Int(int) => int.base10_parse(),
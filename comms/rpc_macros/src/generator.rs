@@ -66,10 +66,15 @@ impl RpcCodeGenerator {
                 } else {
                     quote!(Ok(resp.map(IntoBody::into_body)))
                 };
+                let decode = if m.is_client_streaming {
+                    quote!(req.decode_stream()?)
+                } else {
+                    quote!(req.decode()?)
+                };
                 quote! {
                     #method_num => {
                          let fut = async move {
-                            let resp = inner.#method_name(req.decode()?).await?;
+                            let resp = inner.#method_name(#decode).await?;
                             #ret
                         };
                         Box::pin(fut)
@@ -165,12 +170,17 @@ impl RpcCodeGenerator {
                 let result_type = &m.return_type;
                 let is_unit = m.request_type.as_ref().filter(|ty| is_unit_type(*ty)).is_some();
 
-                let var = if is_unit { quote!(()) } else { quote!(request) };
-
-                let body = if m.is_server_streaming {
-                    quote!(self.inner.server_streaming(#var, #method_num).await)
+                let var = if is_unit && !m.is_client_streaming {
+                    quote!(())
                 } else {
-                    quote!(self.inner.request_response(#var, #method_num).await)
+                    quote!(request)
+                };
+
+                let body = match (m.is_client_streaming, m.is_server_streaming) {
+                    (true, true) => quote!(self.inner.bidi_streaming(#var, #method_num).await),
+                    (true, false) => quote!(self.inner.client_streaming(#var, #method_num).await),
+                    (false, true) => quote!(self.inner.server_streaming(#var, #method_num).await),
+                    (false, false) => quote!(self.inner.request_response(#var, #method_num).await),
                 };
 
                 let ok_type = if m.is_server_streaming {
@@ -179,6 +189,15 @@ impl RpcCodeGenerator {
                     quote!(#result_type)
                 };
 
+                if m.is_client_streaming {
+                    return quote! {
+                        pub async fn #name<TStream>(&mut self, request: TStream) -> Result<#ok_type, #dep_mod::RpcError>
+                        where TStream: #dep_mod::Stream<Item = #request_type> + Send + 'static {
+                            #body
+                        }
+                    };
+                }
+
                 let params = if is_unit {
                     TokenStream::new()
                 } else {
@@ -213,6 +232,10 @@ impl RpcCodeGenerator {
             pub fn close(&mut self) {
                 self.inner.close();
             }
+
+            pub fn is_connected(&self) -> bool {
+                self.inner.is_connected()
+            }
         };
 
         quote! {
@@ -234,6 +257,12 @@ impl RpcCodeGenerator {
                     Self { inner }
                 }
             }
+
+            impl #dep_mod::RpcPoolClient for #client_struct {
+                fn is_connected(&self) -> bool {
+                    self.inner.is_connected()
+                }
+            }
         }
     }
 }
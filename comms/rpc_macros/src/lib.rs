@@ -14,7 +14,7 @@ mod options;
 ///
 /// ```no_run
 /// # use tari_comms_rpc_macros::tari_rpc;
-/// # use tari_comms::protocol::rpc::{Request, Streaming, Response, RpcStatus, RpcServer};
+/// # use tari_comms::protocol::rpc::{Request, RequestStream, Streaming, Response, RpcStatus, RpcServer};
 /// use tari_comms::{framing, memsocket::MemorySocket};
 ///
 /// #[tari_rpc(protocol_name = b"/tari/greeting/1.0", server_struct = GreetingServer, client_struct = GreetingClient)]
@@ -25,6 +25,10 @@ mod options;
 ///     async fn return_error(&self, request: Request<()>) -> Result<Response<()>, RpcStatus>;
 ///     #[rpc(method = 3)]
 ///     async fn get_greetings(&self, request: Request<u32>) -> Result<Streaming<String>, RpcStatus>;
+///     #[rpc(method = 4)]
+///     async fn sum(&self, request: Request<RequestStream<u32>>) -> Result<Response<u32>, RpcStatus>;
+///     #[rpc(method = 5)]
+///     async fn echo(&self, request: Request<RequestStream<String>>) -> Result<Streaming<String>, RpcStatus>;
 /// }
 ///
 /// // GreetingServer and GreetingClient can be used
@@ -42,6 +46,14 @@ mod options;
 ///     async fn get_greetings(&self, request: Request<u32>) -> Result<Streaming<String>, RpcStatus> {
 ///         unimplemented!()
 ///     }
+///
+///     async fn sum(&self, request: Request<RequestStream<u32>>) -> Result<Response<u32>, RpcStatus> {
+///         unimplemented!()
+///     }
+///
+///     async fn echo(&self, request: Request<RequestStream<String>>) -> Result<Streaming<String>, RpcStatus> {
+///         unimplemented!()
+///     }
 /// }
 ///
 /// fn server() {
@@ -55,6 +67,7 @@ mod options;
 ///     let (socket, _) = MemorySocket::new_pair();
 ///     let mut client = GreetingClient::connect(framing::canonical(socket, 1024)).await.unwrap();
 ///     let _ = client.say_hello("Barnaby Jones".to_string()).await.unwrap();
+///     let _ = client.sum(futures::stream::iter(vec![1, 2, 3])).await.unwrap();
 /// }
 /// ```
 ///
@@ -66,6 +79,12 @@ mod options;
 /// `rpc` attribute
 /// - `method` is a unique number that uniquely identifies each function within the service. Once a `method` is used it
 ///   should never be reused (think protobuf field numbers).
+///
+/// Streaming
+/// - A `Streaming<T>` response is streamed to the client, which receives a `ClientStreaming<T>`.
+/// - A `Request<RequestStream<T>>` request is streamed from the client, which passes any `Stream<Item = T>` to the
+///   generated client method. Combined with a `Streaming<T>` response, requests and responses are streamed in both
+///   directions at the same time.
 #[proc_macro_attribute]
 pub fn tari_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
     let options = syn::parse_macro_input!(attr as options::RpcTraitOptions);
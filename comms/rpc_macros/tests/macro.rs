@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use futures::{channel::mpsc, SinkExt, StreamExt};
+use futures::{channel::mpsc, stream, SinkExt, StreamExt};
 use prost::Message;
 use std::{collections::HashMap, ops::AddAssign, sync::Arc};
 use tari_comms::{
@@ -29,8 +29,9 @@ use tari_comms::{
     message::MessageExt,
     protocol::{
         rpc,
-        rpc::{NamedProtocolService, Request, Response, RpcStatus, RpcStatusCode, Streaming},
+        rpc::{NamedProtocolService, Request, RequestStream, Response, RpcStatus, RpcStatusCode, Streaming},
     },
+    Bytes,
 };
 use tari_comms_rpc_macros::tari_rpc;
 use tari_test_utils::unpack_enum;
@@ -46,6 +47,10 @@ pub trait Test: Sync + Send + 'static {
     /// Some docs for unit
     #[rpc(method = 3)]
     async fn unit(&self, request: Request<()>) -> Result<Response<()>, RpcStatus>;
+    #[rpc(method = 4)]
+    async fn client_streaming(&self, request: Request<RequestStream<u32>>) -> Result<Response<u32>, RpcStatus>;
+    #[rpc(method = 5)]
+    async fn bidi_streaming(&self, request: Request<RequestStream<u32>>) -> Result<Streaming<u32>, RpcStatus>;
 
     // Although not typically needed, there is no reason why other non-rpc methods can't be included in the resulting
     // trait
@@ -90,6 +95,21 @@ impl Test for TestService {
         Ok(Response::new(()))
     }
 
+    async fn client_streaming(&self, request: Request<RequestStream<u32>>) -> Result<Response<u32>, RpcStatus> {
+        self.add_call("client_streaming").await;
+        let mut sum = 0;
+        let mut stream = request.into_message();
+        while let Some(n) = stream.next().await {
+            sum += n?;
+        }
+        Ok(Response::new(sum))
+    }
+
+    async fn bidi_streaming(&self, _: Request<RequestStream<u32>>) -> Result<Streaming<u32>, RpcStatus> {
+        self.add_call("bidi_streaming").await;
+        Ok(Streaming::empty())
+    }
+
     fn some_non_rpc_method(&self) {
         unimplemented!()
     }
@@ -133,6 +153,49 @@ async fn it_correctly_maps_the_method_nums() {
         .await
         .unwrap();
     assert_eq!(*spy.read().await.get("unit").unwrap(), 1);
+
+    let (_, rx) = mpsc::channel(1);
+    server
+        .call(Request::new(4.into(), Bytes::new()).with_stream(rx))
+        .await
+        .unwrap();
+    assert_eq!(*spy.read().await.get("client_streaming").unwrap(), 1);
+
+    let (_, rx) = mpsc::channel(1);
+    server
+        .call(Request::new(5.into(), Bytes::new()).with_stream(rx))
+        .await
+        .unwrap();
+    assert_eq!(*spy.read().await.get("bidi_streaming").unwrap(), 1);
+}
+
+#[tokio_macros::test]
+async fn it_decodes_streaming_requests() {
+    let mut server = TestServer::new(TestService::default());
+    let (mut tx, rx) = mpsc::channel(3);
+    for n in 1..=3u32 {
+        tx.send(Ok(n.to_encoded_bytes().into())).await.unwrap();
+    }
+    drop(tx);
+    let resp = server
+        .call(Request::new(4.into(), Bytes::new()).with_stream(rx))
+        .await
+        .unwrap();
+    let v = resp.into_message().next().await.unwrap().unwrap();
+    assert_eq!(u32::decode(v).unwrap(), 6);
+
+    // Unary methods reject streaming requests, and streaming methods reject unary requests
+    let (_, rx) = mpsc::channel(1);
+    let err = server
+        .call(Request::new(1.into(), 11u32.to_encoded_bytes().into()).with_stream(rx))
+        .await
+        .unwrap_err();
+    unpack_enum!(RpcStatusCode::BadRequest = err.status_code());
+    let err = server
+        .call(Request::new(4.into(), 11u32.to_encoded_bytes().into()))
+        .await
+        .unwrap_err();
+    unpack_enum!(RpcStatusCode::BadRequest = err.status_code());
 }
 
 #[tokio_macros::test]
@@ -163,5 +226,8 @@ async fn it_generates_client_calls() {
         let mut streaming_resp = client.server_streaming(CustomMessage).await.unwrap();
         streaming_resp.next().await;
         let _ = client.unit().await;
+        let _ = client.client_streaming(stream::iter(vec![1, 2, 3])).await;
+        let mut streaming_resp = client.bidi_streaming(stream::iter(vec![1, 2, 3])).await.unwrap();
+        streaming_resp.next().await;
     });
 }
@@ -0,0 +1,228 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Payload compression that can be negotiated between peers.
+//!
+//! Compression is optional on both sides of a connection. Peers agree on a codec (or no codec) during protocol
+//! negotiation, and payloads smaller than [COMPRESSION_THRESHOLD](self::COMPRESSION_THRESHOLD) are always sent
+//! uncompressed.
+
+use bytes::Bytes;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use std::{
+    convert::TryFrom,
+    fmt,
+    io,
+    io::{Read, Write},
+};
+
+/// Payloads smaller than this are not worth compressing
+pub const COMPRESSION_THRESHOLD: usize = 1024;
+
+/// The compression codecs supported by this node, in order of preference
+pub const SUPPORTED_CODECS: &[CompressionCodec] = &[CompressionCodec::Deflate];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CompressionCodec {
+    /// The payload is not compressed
+    None = 0,
+    /// DEFLATE (RFC 1951)
+    Deflate = 1,
+}
+
+impl CompressionCodec {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn is_none(self) -> bool {
+        self == CompressionCodec::None
+    }
+
+    /// Selects the first of our supported codecs that the peer also supports, or None if there is no codec in common.
+    pub fn negotiate<I: IntoIterator<Item = u32>>(ours: &[CompressionCodec], theirs: I) -> CompressionCodec {
+        let theirs = theirs
+            .into_iter()
+            .filter_map(|c| CompressionCodec::try_from(c).ok())
+            .collect::<Vec<_>>();
+        ours.iter()
+            .find(|c| !c.is_none() && theirs.contains(c))
+            .copied()
+            .unwrap_or(CompressionCodec::None)
+    }
+
+    /// Compress the given bytes using this codec
+    pub fn compress(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            CompressionCodec::None => Ok(bytes.to_vec()),
+            CompressionCodec::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::with_capacity(bytes.len() / 2), Compression::fast());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            },
+        }
+    }
+
+    /// Decompress the given bytes using this codec. An error is returned if the decompressed payload would exceed
+    /// `max_len` bytes.
+    pub fn decompress(self, bytes: &[u8], max_len: usize) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(bytes.len() * 2);
+        match self {
+            CompressionCodec::None => buf.extend_from_slice(bytes),
+            CompressionCodec::Deflate => {
+                DeflateDecoder::new(bytes)
+                    .take(max_len as u64 + 1)
+                    .read_to_end(&mut buf)?;
+            },
+        }
+
+        if buf.len() > max_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Decompressed payload exceeds the maximum size of {} bytes", max_len),
+            ));
+        }
+        Ok(buf)
+    }
+
+    /// Compress the payload if it is large enough for compression to be worthwhile. The payload is returned with the
+    /// codec that was used, which will be `CompressionCodec::None` if the payload was not compressed or compression
+    /// did not reduce its size.
+    pub fn compress_if_beneficial(self, bytes: Bytes) -> io::Result<(CompressionCodec, Bytes)> {
+        if self.is_none() || bytes.len() < COMPRESSION_THRESHOLD {
+            return Ok((CompressionCodec::None, bytes));
+        }
+        let compressed = self.compress(&bytes)?;
+        if compressed.len() >= bytes.len() {
+            return Ok((CompressionCodec::None, bytes));
+        }
+        Ok((self, compressed.into()))
+    }
+}
+
+impl TryFrom<u32> for CompressionCodec {
+    type Error = io::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CompressionCodec::None),
+            1 => Ok(CompressionCodec::Deflate),
+            v => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown compression codec {}", v),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionCodec::None => write!(f, "none"),
+            CompressionCodec::Deflate => write!(f, "deflate"),
+        }
+    }
+}
+
+/// Prefixes the payload with a single byte that identifies the codec used to compress it, compressing the payload with
+/// `codec` if beneficial.
+pub fn encode_frame(codec: CompressionCodec, payload: Bytes) -> io::Result<Bytes> {
+    let (codec, payload) = codec.compress_if_beneficial(payload)?;
+    let mut buf = Vec::with_capacity(payload.len() + 1);
+    buf.push(codec.as_u8());
+    buf.extend_from_slice(&payload);
+    Ok(buf.into())
+}
+
+/// Decodes a frame produced by [encode_frame](self::encode_frame). The decompressed payload may be at most `max_len`
+/// bytes.
+pub fn decode_frame(frame: &[u8], max_len: usize) -> io::Result<Bytes> {
+    let (codec, payload) = frame
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty compression frame"))?;
+    let codec = CompressionCodec::try_from(u32::from(*codec))?;
+    codec.decompress(payload, max_len).map(Into::into)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::iter;
+
+    fn compressible_payload(len: usize) -> Bytes {
+        iter::repeat(b"tari")
+            .flatten()
+            .copied()
+            .take(len)
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn negotiate() {
+        use CompressionCodec::*;
+        assert_eq!(CompressionCodec::negotiate(SUPPORTED_CODECS, vec![1]), Deflate);
+        assert_eq!(CompressionCodec::negotiate(SUPPORTED_CODECS, vec![]), None);
+        assert_eq!(CompressionCodec::negotiate(SUPPORTED_CODECS, vec![0, 123]), None);
+        assert_eq!(CompressionCodec::negotiate(&[], vec![1]), None);
+    }
+
+    #[test]
+    fn compress_decompress() {
+        let payload = compressible_payload(10 * 1024);
+        let (codec, compressed) = CompressionCodec::Deflate
+            .compress_if_beneficial(payload.clone())
+            .unwrap();
+        assert_eq!(codec, CompressionCodec::Deflate);
+        assert!(compressed.len() < payload.len());
+        let decompressed = codec.decompress(&compressed, payload.len()).unwrap();
+        assert_eq!(decompressed, payload);
+
+        let err = codec.decompress(&compressed, payload.len() - 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn small_payloads_are_not_compressed() {
+        let payload = compressible_payload(COMPRESSION_THRESHOLD - 1);
+        let (codec, bytes) = CompressionCodec::Deflate
+            .compress_if_beneficial(payload.clone())
+            .unwrap();
+        assert_eq!(codec, CompressionCodec::None);
+        assert_eq!(bytes, payload);
+    }
+
+    #[test]
+    fn encode_decode_frame() {
+        for len in &[0, 10, 10 * 1024] {
+            let payload = compressible_payload(*len);
+            let frame = encode_frame(CompressionCodec::Deflate, payload.clone()).unwrap();
+            assert_eq!(decode_frame(&frame, *len).unwrap(), payload);
+            let frame = encode_frame(CompressionCodec::None, payload.clone()).unwrap();
+            assert_eq!(frame[0], CompressionCodec::None.as_u8());
+            assert_eq!(decode_frame(&frame, *len).unwrap(), payload);
+        }
+        assert!(decode_frame(&[], 10).is_err());
+        assert!(decode_frame(&[99, 1, 2], 10).is_err());
+    }
+}
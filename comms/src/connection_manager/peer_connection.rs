@@ -21,7 +21,15 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 #[cfg(feature = "rpc")]
-use crate::protocol::rpc::{NamedProtocolService, RpcClient, RpcClientBuilder, RpcError, RPC_MAX_FRAME_SIZE};
+use crate::protocol::rpc::{
+    NamedProtocolService,
+    RpcClient,
+    RpcClientBuilder,
+    RpcClientPool,
+    RpcError,
+    RpcPoolClient,
+    RPC_MAX_FRAME_SIZE,
+};
 
 use super::{
     error::{ConnectionManagerError, PeerConnectionError},
@@ -226,6 +234,14 @@ impl PeerConnection {
         builder.connect(framed).await
     }
 
+    /// Creates a new [RpcClientPool](crate::protocol::rpc::RpcClientPool) that establishes up to `num_sessions` RPC
+    /// sessions to this peer on demand. Sessions are only established when a client is requested from the pool.
+    #[cfg(feature = "rpc")]
+    pub fn create_rpc_client_pool<T>(&self, num_sessions: usize, client_config: RpcClientBuilder<T>) -> RpcClientPool<T>
+    where T: RpcPoolClient + From<RpcClient> + NamedProtocolService + Clone {
+        RpcClientPool::new(self.clone(), num_sessions, client_config)
+    }
+
     /// Immediately disconnects the peer connection. This can only fail if the peer connection worker
     /// is shut down (and the peer is already disconnected)
     pub async fn disconnect(&mut self) -> Result<(), PeerConnectionError> {
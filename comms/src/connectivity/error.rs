@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "rpc")]
+use crate::protocol::rpc::RpcClientPoolError;
 use crate::{connection_manager::ConnectionManagerError, peer_manager::PeerManagerError, PeerConnectionError};
 use thiserror::Error;
 
@@ -41,6 +43,9 @@ pub enum ConnectivityError {
     OnlineWaitTimeout(usize),
     #[error("Pending dial was cancelled")]
     DialCancelled,
+    #[cfg(feature = "rpc")]
+    #[error("RPC client pool error: {0}")]
+    RpcClientPoolError(#[from] RpcClientPoolError),
 }
 
 impl From<ConnectionManagerError> for ConnectivityError {
@@ -25,11 +25,11 @@ use super::{
     connection_stats::PeerConnectionStats,
     error::ConnectivityError,
     requester::{ConnectivityEvent, ConnectivityRequest},
-    selection::ConnectivitySelection,
+    selection::{select_connected_nodes, ConnectivitySelection},
 };
 use crate::{
     connection_manager::{ConnectionDirection, ConnectionManagerError, ConnectionManagerRequester},
-    peer_manager::NodeId,
+    peer_manager::{NodeId, ReputationEvent},
     runtime::task,
     utils::datetime::format_duration,
     ConnectionManagerEvent,
@@ -264,6 +264,11 @@ impl ConnectivityManagerActor {
                     error!(target: LOG_TARGET, "Error when banning peer: {:?}", err);
                 }
             },
+            AdjustPeerReputation(node_id, event) => {
+                if let Err(err) = self.adjust_peer_reputation(&node_id, event).await {
+                    error!(target: LOG_TARGET, "Error when adjusting peer reputation: {:?}", err);
+                }
+            },
             GetActiveConnections(reply) => {
                 let _ = reply.send(
                     self.pool
@@ -420,12 +425,38 @@ impl ConnectivityManagerActor {
             self.pool.count_connected_nodes()
         );
 
-        let conns = selection.select(&self.pool);
+        let reputations = self.connected_peer_reputations().await;
+        let conns = selection.select(&self.pool, &reputations);
         debug!(target: LOG_TARGET, "Selected {} connections(s)", conns.len());
 
         Ok(conns.into_iter().cloned().collect())
     }
 
+    /// Returns the reputation score of each connected node peer
+    async fn connected_peer_reputations(&self) -> HashMap<NodeId, i32> {
+        let node_ids = select_connected_nodes(&self.pool, &[])
+            .into_iter()
+            .map(|conn| conn.peer_node_id().clone())
+            .collect::<Vec<_>>();
+        let mut reputations = HashMap::with_capacity(node_ids.len());
+        for node_id in node_ids {
+            match self.peer_manager.find_by_node_id(&node_id).await {
+                Ok(peer) => {
+                    reputations.insert(node_id, peer.reputation);
+                },
+                Err(err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Could not fetch reputation of peer '{}': {}",
+                        node_id.short_str(),
+                        err
+                    );
+                },
+            }
+        }
+        reputations
+    }
+
     async fn add_managed_peers(&mut self, node_ids: Vec<NodeId>) {
         let pool = &mut self.pool;
         let mut should_update_connectivity = false;
@@ -817,13 +848,41 @@ impl ConnectivityManagerActor {
             reason
         );
 
+        self.peer_manager.ban_peer_by_node_id(node_id, duration, reason).await?;
+        self.disconnect_banned_peer(node_id).await
+    }
+
+    async fn adjust_peer_reputation(
+        &mut self,
+        node_id: &NodeId,
+        event: ReputationEvent,
+    ) -> Result<(), ConnectivityError>
+    {
+        debug!(
+            target: LOG_TARGET,
+            "Adjusting reputation of peer '{}' for event: {}",
+            node_id.short_str(),
+            event
+        );
+        let is_banned = self.peer_manager.adjust_reputation(node_id, event).await?;
+        if is_banned {
+            info!(
+                target: LOG_TARGET,
+                "Banning peer {} because its reputation score fell below the threshold (last event: {})",
+                node_id,
+                event
+            );
+            self.disconnect_banned_peer(node_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn disconnect_banned_peer(&mut self, node_id: &NodeId) -> Result<(), ConnectivityError> {
         if let Some(pos) = self.managed_peers.iter().position(|n| n == node_id) {
             let node_id = self.managed_peers.remove(pos);
             debug!(target: LOG_TARGET, "Banned managed peer '{}'", node_id);
         }
 
-        self.peer_manager.ban_peer_by_node_id(node_id, duration, reason).await?;
-
         self.publish_event(ConnectivityEvent::PeerBanned(node_id.clone()));
 
         if let Some(conn) = self.pool.get_connection_mut(node_id) {
@@ -26,9 +26,18 @@ use super::{
     manager::ConnectivityStatus,
     ConnectivitySelection,
 };
+#[cfg(feature = "rpc")]
+use crate::protocol::rpc::{
+    NamedProtocolService,
+    RpcClient,
+    RpcClientBuilder,
+    RpcClientLease,
+    RpcClientPools,
+    RpcPoolClient,
+};
 use crate::{
     connection_manager::{ConnectionDirection, ConnectionManagerError},
-    peer_manager::NodeId,
+    peer_manager::{NodeId, ReputationEvent},
     PeerConnection,
 };
 use futures::{
@@ -102,17 +111,25 @@ pub enum ConnectivityRequest {
     GetAllConnectionStates(oneshot::Sender<Vec<PeerConnectionState>>),
     GetActiveConnections(oneshot::Sender<Vec<PeerConnection>>),
     BanPeer(NodeId, Duration, String),
+    AdjustPeerReputation(NodeId, ReputationEvent),
 }
 
 #[derive(Debug, Clone)]
 pub struct ConnectivityRequester {
     sender: mpsc::Sender<ConnectivityRequest>,
     event_tx: ConnectivityEventTx,
+    #[cfg(feature = "rpc")]
+    rpc_client_pools: RpcClientPools,
 }
 
 impl ConnectivityRequester {
     pub fn new(sender: mpsc::Sender<ConnectivityRequest>, event_tx: ConnectivityEventTx) -> Self {
-        Self { sender, event_tx }
+        Self {
+            sender,
+            event_tx,
+            #[cfg(feature = "rpc")]
+            rpc_client_pools: Default::default(),
+        }
     }
 
     pub fn get_event_subscription(&self) -> ConnectivityEventRx {
@@ -148,6 +165,24 @@ impl ConnectivityRequester {
         }
     }
 
+    /// Returns a lease on a pooled `T` RPC session to the given peer, dialing the peer if necessary. The sessions are
+    /// shared with every other clone of this requester that requests a `T` client for the same peer. The given client
+    /// config is only used if no pool exists for the peer and protocol yet.
+    #[cfg(feature = "rpc")]
+    pub async fn get_rpc_client<T>(
+        &mut self,
+        peer: NodeId,
+        client_config: RpcClientBuilder<T>,
+    ) -> Result<RpcClientLease<T>, ConnectivityError>
+    where
+        T: RpcPoolClient + From<RpcClient> + NamedProtocolService + Clone + Send + 'static,
+    {
+        let connection = self.dial_peer(peer).await?;
+        let pool = self.rpc_client_pools.get_or_create(&connection, client_config);
+        let client = pool.get().await?;
+        Ok(client)
+    }
+
     pub async fn add_managed_peers(&mut self, peers: Vec<NodeId>) -> Result<(), ConnectivityError> {
         self.sender
             .send(ConnectivityRequest::AddManagedPeers(peers))
@@ -233,6 +268,21 @@ impl ConnectivityRequester {
             .await
     }
 
+    /// Adjusts the reputation score of a peer. The peer is banned and disconnected if its score falls below the ban
+    /// threshold.
+    pub async fn adjust_peer_reputation(
+        &mut self,
+        node_id: NodeId,
+        event: ReputationEvent,
+    ) -> Result<(), ConnectivityError>
+    {
+        self.sender
+            .send(ConnectivityRequest::AdjustPeerReputation(node_id, event))
+            .await
+            .map_err(|_| ConnectivityError::ActorDisconnected)?;
+        Ok(())
+    }
+
     pub async fn wait_started(&mut self) -> Result<(), ConnectivityError> {
         let (reply_tx, reply_rx) = oneshot::channel();
         self.sender
@@ -23,7 +23,7 @@
 use super::connection_pool::ConnectionPool;
 use crate::{connectivity::connection_pool::ConnectionStatus, peer_manager::NodeId, PeerConnection};
 use rand::{rngs::OsRng, seq::SliceRandom};
-use std::{fmt, fmt::Display};
+use std::{cmp, collections::HashMap, fmt, fmt::Display};
 
 #[derive(Debug, Clone)]
 pub struct ConnectivitySelection {
@@ -61,16 +61,30 @@ impl ConnectivitySelection {
         }
     }
 
-    /// Select peers from the pool according to the ConnectivitySelection
-    pub fn select<'a>(&self, pool: &'a ConnectionPool) -> Vec<&'a PeerConnection> {
+    /// Select peers from the pool according to the ConnectivitySelection. Peers with a higher reputation score are
+    /// preferred. All nodes are ordered by reputation, random and closest selections select peers with a poor
+    /// reputation only if there are not enough other peers.
+    pub fn select<'a>(&self, pool: &'a ConnectionPool, reputations: &HashMap<NodeId, i32>) -> Vec<&'a PeerConnection> {
         use SelectionMode::*;
+        let reputation_of = |conn: &PeerConnection| reputations.get(conn.peer_node_id()).copied().unwrap_or(0);
         match &self.selection_mode {
-            AllNodes => select_connected_nodes(pool, &self.excluded_peers),
-            RandomNodes(n) => select_random_nodes(pool, *n, &self.excluded_peers),
+            AllNodes => {
+                let mut connections = select_connected_nodes(pool, &self.excluded_peers);
+                connections.sort_by_key(|conn| cmp::Reverse(reputation_of(conn)));
+                connections
+            },
+            RandomNodes(n) => {
+                let mut connections = select_random_nodes(pool, pool.count_connected_nodes(), &self.excluded_peers);
+                // The sort is stable, so the order is otherwise random
+                connections.sort_by_key(|conn| reputation_of(conn) < 0);
+                connections.truncate(*n);
+                connections
+            },
             ClosestTo(dest_node_id, n) => {
                 let mut connections = select_closest(pool, dest_node_id, &self.excluded_peers);
+                connections.sort_by_key(|conn| reputation_of(conn) < 0);
                 connections.truncate(*n);
-                connections.to_vec()
+                connections
             },
         }
     }
@@ -101,8 +115,10 @@ pub fn select_closest<'a>(pool: &'a ConnectionPool, node_id: &NodeId, exclude: &
 }
 
 pub fn select_random_nodes<'a>(pool: &'a ConnectionPool, n: usize, exclude: &[NodeId]) -> Vec<&'a PeerConnection> {
-    let nodes = select_connected_nodes(pool, exclude);
-    nodes.choose_multiple(&mut OsRng, n).cloned().collect()
+    let mut nodes = select_connected_nodes(pool, exclude);
+    nodes.shuffle(&mut OsRng);
+    nodes.truncate(n);
+    nodes
 }
 
 impl Display for ConnectivitySelection {
@@ -28,7 +28,7 @@ use super::{
 };
 use crate::{
     connection_manager::ConnectionManagerError,
-    peer_manager::{Peer, PeerFeatures},
+    peer_manager::{Peer, PeerFeatures, ReputationEvent},
     runtime,
     runtime::task,
     test_utils::{
@@ -348,6 +348,51 @@ async fn ban_peer() {
     assert!(conn.is_none());
 }
 
+#[runtime::test_basic]
+async fn ban_peer_with_poor_reputation() {
+    let (mut connectivity, mut event_stream, node_identity, peer_manager, cm_mock_state, _shutdown) =
+        setup_connectivity_manager(Default::default());
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+    let (conn, _, _, _) = create_peer_connection_mock_pair(1, node_identity.to_peer(), peer.clone()).await;
+
+    let mut events = collect_stream!(event_stream, take = 1, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::ConnectivityStateInitialized = &*events.remove(0).unwrap());
+
+    cm_mock_state.publish_event(ConnectionManagerEvent::PeerConnected(conn.clone()));
+    let mut events = collect_stream!(event_stream, take = 2, timeout = Duration::from_secs(10));
+    unpack_enum!(ConnectivityEvent::PeerConnected(_conn) = &*events.remove(0).unwrap());
+    unpack_enum!(ConnectivityEvent::ConnectivityStateOnline(_n) = &*events.remove(0).unwrap());
+
+    connectivity
+        .adjust_peer_reputation(peer.node_id.clone(), ReputationEvent::UsefulContribution)
+        .await
+        .unwrap();
+    let conn = connectivity.get_connection(peer.node_id.clone()).await.unwrap();
+    assert!(conn.is_some());
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap();
+    assert_eq!(peer.reputation, ReputationEvent::UsefulContribution.score_delta());
+
+    for _ in 0..3 {
+        connectivity
+            .adjust_peer_reputation(peer.node_id.clone(), ReputationEvent::ValidationFailed)
+            .await
+            .unwrap();
+    }
+
+    let event = collect_stream!(event_stream, take = 1, timeout = Duration::from_secs(10))
+        .pop()
+        .unwrap()
+        .unwrap();
+    unpack_enum!(ConnectivityEvent::PeerBanned(node_id) = &*event);
+    assert_eq!(node_id, &peer.node_id);
+
+    let peer = peer_manager.find_by_node_id(&peer.node_id).await.unwrap();
+    assert!(peer.is_banned());
+
+    let conn = connectivity.get_connection(peer.node_id.clone()).await.unwrap();
+    assert!(conn.is_none());
+}
+
 #[runtime::test_basic]
 async fn peer_selection() {
     let config = ConnectivityConfig {
@@ -28,3 +28,11 @@ pub const PEER_MANAGER_MAX_FLOOD_PEERS: usize = 1000;
 /// The amount of time to consider a peer to be offline (i.e. dial to peer will fail without trying) after a failed
 /// connection attempt
 pub const PEER_OFFLINE_COOLDOWN_PERIOD: Duration = Duration::from_secs(60);
+
+/// The lowest and highest reputation score a peer can have
+pub const PEER_REPUTATION_MIN_SCORE: i32 = -100;
+pub const PEER_REPUTATION_MAX_SCORE: i32 = 100;
+
+/// A peer is banned for `PEER_REPUTATION_BAN_DURATION` once its reputation score falls to this value or below
+pub const PEER_REPUTATION_BAN_THRESHOLD: i32 = -50;
+pub const PEER_REPUTATION_BAN_DURATION: Duration = Duration::from_secs(6 * 60 * 60);
@@ -37,6 +37,7 @@ pub mod connectivity;
 pub mod peer_manager;
 pub use peer_manager::{NodeIdentity, PeerManager};
 
+pub mod compression;
 pub mod framing;
 
 mod common;
@@ -31,6 +31,7 @@ use crate::{
         PeerFeatures,
         PeerManagerError,
         PeerQuery,
+        ReputationEvent,
     },
     types::{CommsDatabase, CommsPublicKey},
 };
@@ -257,6 +258,12 @@ impl PeerManager {
             .ban_peer_by_node_id(node_id, duration, reason)
     }
 
+    /// Adjusts the reputation score of the peer for the given event. Returns true if the peer was banned because its
+    /// score fell below the ban threshold.
+    pub async fn adjust_reputation(&self, node_id: &NodeId, event: ReputationEvent) -> Result<bool, PeerManagerError> {
+        self.peer_storage.write().await.adjust_reputation(node_id, event)
+    }
+
     /// Changes the offline flag bit of the peer. Return the previous offline state.
     pub async fn set_offline(&self, node_id: &NodeId, is_offline: bool) -> Result<bool, PeerManagerError> {
         self.peer_storage.write().await.set_offline(node_id, is_offline)
@@ -99,6 +99,9 @@ pub use peer_query::{PeerQuery, PeerQuerySortBy};
 mod peer_storage;
 pub use peer_storage::PeerStorage;
 
+mod reputation;
+pub use reputation::ReputationEvent;
+
 mod migrations;
 
 mod wrapper;
@@ -25,9 +25,16 @@ use super::{
     node_id::{deserialize_node_id_from_hex, NodeId},
     peer_id::PeerId,
     PeerFeatures,
+    ReputationEvent,
 };
 use crate::{
-    consts::PEER_OFFLINE_COOLDOWN_PERIOD,
+    consts::{
+        PEER_OFFLINE_COOLDOWN_PERIOD,
+        PEER_REPUTATION_BAN_DURATION,
+        PEER_REPUTATION_BAN_THRESHOLD,
+        PEER_REPUTATION_MAX_SCORE,
+        PEER_REPUTATION_MIN_SCORE,
+    },
     net_address::MultiaddressesWithStats,
     protocol::ProtocolId,
     types::CommsPublicKey,
@@ -82,6 +89,8 @@ pub struct Peer {
     pub features: PeerFeatures,
     /// Connection statics for the peer
     pub connection_stats: PeerConnectionStats,
+    /// Reputation score of the peer. Misbehaviour lowers the score and useful contributions raise it.
+    pub reputation: i32,
     /// Protocols supported by the peer. This should not be considered a definitive list of supported protocols and is
     /// used as information for more efficient protocol negotiation.
     pub supported_protocols: Vec<ProtocolId>,
@@ -117,6 +126,7 @@ impl Peer {
             banned_reason: "".to_string(),
             offline_at: None,
             connection_stats: Default::default(),
+            reputation: 0,
             added_at: Utc::now().naive_utc(),
             supported_protocols,
             user_agent,
@@ -255,6 +265,28 @@ impl Peer {
         self.banned_until.as_ref().filter(|dt| *dt > &Utc::now().naive_utc())
     }
 
+    /// Adjusts the reputation score of the peer for the given event. If the score falls to the ban threshold or below,
+    /// the peer is banned, its score is reset and true is returned.
+    pub fn adjust_reputation(&mut self, event: ReputationEvent) -> bool {
+        self.reputation = (self.reputation + event.score_delta())
+            .max(PEER_REPUTATION_MIN_SCORE)
+            .min(PEER_REPUTATION_MAX_SCORE);
+        if self.reputation > PEER_REPUTATION_BAN_THRESHOLD {
+            return false;
+        }
+        self.ban_for(
+            PEER_REPUTATION_BAN_DURATION,
+            format!("Reputation score fell to {} after: {}", self.reputation, event),
+        );
+        self.reputation = 0;
+        true
+    }
+
+    /// Returns true if the peer has misbehaved more than it has contributed
+    pub fn has_poor_reputation(&self) -> bool {
+        self.reputation < 0
+    }
+
     /// Marks the peer as offline
     pub fn set_offline(&mut self, is_offline: bool) {
         if is_offline {
@@ -317,7 +349,7 @@ impl Display for Peer {
         };
 
         f.write_str(&format!(
-            "{}[{}] PK={} ({}) - {}. Type: {}. User agent: {}. {}.",
+            "{}[{}] PK={} ({}) - {}. Type: {}. User agent: {}. Reputation: {}. {}.",
             flags_str,
             self.node_id.short_str(),
             self.public_key,
@@ -334,6 +366,7 @@ impl Display for Peer {
                 f => format!("{:?}", f),
             },
             user_agent,
+            self.reputation,
             self.connection_stats,
         ))
     }
@@ -391,6 +424,25 @@ mod test {
         assert_eq!(peer.is_banned(), false);
     }
 
+    #[test]
+    fn test_adjust_reputation() {
+        let mut peer = build_node_identity(Default::default()).to_peer();
+        assert_eq!(peer.adjust_reputation(ReputationEvent::UsefulContribution), false);
+        assert_eq!(peer.reputation, ReputationEvent::UsefulContribution.score_delta());
+        assert_eq!(peer.has_poor_reputation(), false);
+
+        assert_eq!(peer.adjust_reputation(ReputationEvent::RpcTimeout), false);
+        assert_eq!(peer.has_poor_reputation(), true);
+        assert_eq!(peer.is_banned(), false);
+
+        let banned = (0..10)
+            .map(|_| peer.adjust_reputation(ReputationEvent::ValidationFailed))
+            .any(|banned| banned);
+        assert_eq!(banned, true);
+        assert_eq!(peer.is_banned(), true);
+        assert_eq!(peer.reputation, 0);
+    }
+
     #[test]
     fn test_offline_since() {
         let mut peer = build_node_identity(Default::default()).to_peer();
@@ -29,6 +29,7 @@ use crate::{
         PeerFeatures,
         PeerManagerError,
         PeerQuery,
+        ReputationEvent,
     },
     protocol::ProtocolId,
     types::{CommsDatabase, CommsPublicKey},
@@ -352,6 +353,8 @@ where DS: KeyValueStore<PeerId, Peer>
             return Ok(Vec::new());
         }
         peers.shuffle(&mut OsRng);
+        // Prefer peers that have not misbehaved, the sort is stable so the order is otherwise random
+        peers.sort_by_key(|peer| peer.has_poor_reputation());
         peers.truncate(n);
 
         Ok(peers)
@@ -476,6 +479,25 @@ where DS: KeyValueStore<PeerId, Peer>
         Ok(node_id)
     }
 
+    /// Adjusts the reputation score of the peer for the given event. Returns true if the peer was banned because its
+    /// score fell below the ban threshold.
+    pub fn adjust_reputation(&mut self, node_id: &NodeId, event: ReputationEvent) -> Result<bool, PeerManagerError> {
+        let peer_key = *self
+            .node_id_index
+            .get(&node_id)
+            .ok_or_else(|| PeerManagerError::PeerNotFoundError)?;
+        let mut peer: Peer = self
+            .peer_db
+            .get(&peer_key)
+            .map_err(PeerManagerError::DatabaseError)?
+            .expect("node_id_index is out of sync with peer db");
+        let is_banned = peer.adjust_reputation(event);
+        self.peer_db
+            .insert(peer_key, peer)
+            .map_err(PeerManagerError::DatabaseError)?;
+        Ok(is_banned)
+    }
+
     /// Changes the OFFLINE flag bit of the peer.
     pub fn set_offline(&mut self, node_id: &NodeId, offline: bool) -> Result<bool, PeerManagerError> {
         let peer_key = *self
@@ -844,4 +866,38 @@ mod test {
         let is_in_region = peer_storage.in_network_region(far_node, &main_peer_node_id, 3).unwrap();
         assert_eq!(is_in_region, false);
     }
+
+    #[test]
+    fn test_adjust_reputation() {
+        let mut peer_storage = PeerStorage::new_indexed(HashmapDatabase::new()).unwrap();
+        let poor_peer = create_test_peer(PeerFeatures::COMMUNICATION_NODE, false, false);
+        let good_peer = create_test_peer(PeerFeatures::COMMUNICATION_NODE, false, false);
+        peer_storage.add_peer(poor_peer.clone()).unwrap();
+        peer_storage.add_peer(good_peer.clone()).unwrap();
+
+        let is_banned = peer_storage
+            .adjust_reputation(&poor_peer.node_id, ReputationEvent::InvalidMessage)
+            .unwrap();
+        assert_eq!(is_banned, false);
+        assert!(peer_storage.find_by_node_id(&poor_peer.node_id).unwrap().reputation < 0);
+
+        // Peers with a poor reputation are selected last
+        let peers = peer_storage.random_peers(1, &[]).unwrap();
+        assert_eq!(peers[0].node_id, good_peer.node_id);
+
+        let is_banned = (0..10)
+            .map(|_| {
+                peer_storage
+                    .adjust_reputation(&poor_peer.node_id, ReputationEvent::ValidationFailed)
+                    .unwrap()
+            })
+            .any(|is_banned| is_banned);
+        assert_eq!(is_banned, true);
+        assert!(peer_storage.find_by_node_id(&poor_peer.node_id).unwrap().is_banned());
+
+        let unknown_peer = create_test_peer(PeerFeatures::COMMUNICATION_NODE, false, false);
+        assert!(peer_storage
+            .adjust_reputation(&unknown_peer.node_id, ReputationEvent::RpcTimeout)
+            .is_err());
+    }
 }
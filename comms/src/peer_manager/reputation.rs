@@ -0,0 +1,62 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt;
+
+/// Peer behaviour that adjusts the reputation score of the peer. Misbehaviour lowers the score and useful
+/// contributions raise it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationEvent {
+    /// The peer sent data that failed validation, such as an invalid block or header
+    ValidationFailed,
+    /// The peer sent a message that could not be decoded or was otherwise invalid
+    InvalidMessage,
+    /// The peer did not respond to an RPC request in time
+    RpcTimeout,
+    /// The peer provided data that was useful to this node, such as serving a sync request
+    UsefulContribution,
+}
+
+impl ReputationEvent {
+    /// The amount that this event adds to the reputation score of a peer
+    pub fn score_delta(self) -> i32 {
+        use ReputationEvent::*;
+        match self {
+            ValidationFailed => -25,
+            InvalidMessage => -10,
+            RpcTimeout => -5,
+            UsefulContribution => 2,
+        }
+    }
+}
+
+impl fmt::Display for ReputationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ReputationEvent::*;
+        match self {
+            ValidationFailed => write!(f, "Validation failed"),
+            InvalidMessage => write!(f, "Invalid message"),
+            RpcTimeout => write!(f, "RPC timeout"),
+            UsefulContribution => write!(f, "Useful contribution"),
+        }
+    }
+}
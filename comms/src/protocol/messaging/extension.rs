@@ -26,7 +26,11 @@ use crate::{
     message::InboundMessage,
     pipeline,
     protocol::{
-        messaging::{consts, protocol::MESSAGING_PROTOCOL, MessagingEventSender},
+        messaging::{
+            consts,
+            protocol::{COMPRESSED_MESSAGING_PROTOCOL, MESSAGING_PROTOCOL},
+            MessagingEventSender,
+        },
         ProtocolExtension,
         ProtocolExtensionContext,
         ProtocolExtensionError,
@@ -61,7 +65,10 @@ where
 {
     fn install(self: Box<Self>, context: &mut ProtocolExtensionContext) -> Result<(), ProtocolExtensionError> {
         let (proto_tx, proto_rx) = mpsc::channel(consts::MESSAGING_PROTOCOL_EVENTS_BUFFER_SIZE);
-        context.add_protocol(&[MESSAGING_PROTOCOL.clone()], proto_tx);
+        context.add_protocol(
+            &[COMPRESSED_MESSAGING_PROTOCOL.clone(), MESSAGING_PROTOCOL.clone()],
+            proto_tx,
+        );
 
         let (messaging_request_tx, messaging_request_rx) = mpsc::channel(consts::MESSAGING_REQUEST_BUFFER_SIZE);
         let (inbound_message_tx, inbound_message_rx) = mpsc::channel(consts::INBOUND_MESSAGE_BUFFER_SIZE);
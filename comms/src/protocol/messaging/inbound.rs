@@ -22,9 +22,10 @@
 
 use crate::{
     common::rate_limit::RateLimit,
+    compression,
     message::InboundMessage,
     peer_manager::NodeId,
-    protocol::messaging::{MessagingEvent, MessagingProtocol},
+    protocol::messaging::{protocol::MAX_FRAME_LENGTH, MessagingEvent, MessagingProtocol},
 };
 use futures::{channel::mpsc, future::Either, AsyncRead, AsyncWrite, SinkExt, StreamExt};
 use log::*;
@@ -40,6 +41,7 @@ pub struct InboundMessaging {
     rate_limit_capacity: usize,
     rate_limit_restock_interval: Duration,
     inactivity_timeout: Option<Duration>,
+    is_compressed: bool,
 }
 
 impl InboundMessaging {
@@ -59,9 +61,16 @@ impl InboundMessaging {
             rate_limit_capacity,
             rate_limit_restock_interval,
             inactivity_timeout,
+            is_compressed: false,
         }
     }
 
+    /// Set whether each frame received is prefixed with a compression codec (see `COMPRESSED_MESSAGING_PROTOCOL`)
+    pub fn with_compressed_frames(mut self, is_compressed: bool) -> Self {
+        self.is_compressed = is_compressed;
+        self
+    }
+
     pub async fn run<S>(mut self, socket: S)
     where S: AsyncRead + AsyncWrite + Unpin {
         let peer = &self.peer;
@@ -91,7 +100,26 @@ impl InboundMessaging {
         while let Some(result) = stream.next().await {
             match result {
                 Ok(Ok(raw_msg)) => {
-                    let inbound_msg = InboundMessage::new(peer.clone(), raw_msg.clone().freeze());
+                    let body = if self.is_compressed {
+                        match compression::decode_frame(&raw_msg, MAX_FRAME_LENGTH) {
+                            Ok(body) => body,
+                            Err(err) => {
+                                warn!(
+                                    target: LOG_TARGET,
+                                    "Failed to decompress message from peer '{}' because '{}'",
+                                    peer.short_str(),
+                                    err
+                                );
+                                let _ = self
+                                    .messaging_events_tx
+                                    .send(Arc::new(MessagingEvent::InvalidMessageReceived(peer.clone())));
+                                break;
+                            },
+                        }
+                    } else {
+                        raw_msg.clone().freeze()
+                    };
+                    let inbound_msg = InboundMessage::new(peer.clone(), body);
                     debug!(
                         target: LOG_TARGET,
                         "Received message {} from peer '{}' ({} bytes)",
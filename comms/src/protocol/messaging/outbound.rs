@@ -22,12 +22,18 @@
 
 use super::{error::MessagingProtocolError, MessagingEvent, MessagingProtocol, SendFailReason};
 use crate::{
+    compression,
+    compression::SUPPORTED_CODECS,
     connection_manager::{NegotiatedSubstream, PeerConnection},
     connectivity::{ConnectivityError, ConnectivityRequester},
     message::OutboundMessage,
     multiplexing::Substream,
     peer_manager::NodeId,
-    protocol::messaging::protocol::MESSAGING_PROTOCOL,
+    protocol::{
+        messaging::protocol::{COMPRESSED_MESSAGING_PROTOCOL, MESSAGING_PROTOCOL},
+        ProtocolError,
+    },
+    PeerConnectionError,
 };
 use futures::{channel::mpsc, future::Either, SinkExt, StreamExt};
 use log::*;
@@ -188,7 +194,20 @@ impl OutboundMessaging {
         mut conn: PeerConnection,
     ) -> Result<NegotiatedSubstream<Substream>, MessagingProtocolError>
     {
-        match conn.open_substream(&MESSAGING_PROTOCOL).await {
+        let result = match conn.open_substream(&COMPRESSED_MESSAGING_PROTOCOL).await {
+            // The peer does not support compression, fall back to the uncompressed protocol
+            Err(PeerConnectionError::ProtocolError(ProtocolError::ProtocolOutboundNegotiationFailed(_))) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Peer '{}' does not support compressed messaging. Falling back to uncompressed messaging.",
+                    self.peer_node_id.short_str()
+                );
+                conn.open_substream(&MESSAGING_PROTOCOL).await
+            },
+            result => result,
+        };
+
+        match result {
             Ok(substream) => Ok(substream),
             Err(err) => {
                 debug!(
@@ -212,6 +231,11 @@ impl OutboundMessaging {
             "Starting direct message forwarding for peer `{}`",
             self.peer_node_id.short_str()
         );
+        let codec = if substream.protocol == COMPRESSED_MESSAGING_PROTOCOL {
+            SUPPORTED_CODECS.first().copied()
+        } else {
+            None
+        };
         let substream = substream.stream;
 
         let (sink, _) = MessagingProtocol::framed(substream).split();
@@ -238,10 +262,13 @@ impl OutboundMessaging {
 
         stream
             .map(|msg| {
-                msg.map(|mut out_msg| {
+                msg.and_then(|mut out_msg| {
                     trace!(target: LOG_TARGET, "Message buffered for sending {}", out_msg);
                     out_msg.reply_success();
-                    out_msg.body
+                    match codec {
+                        Some(codec) => compression::encode_frame(codec, out_msg.body),
+                        None => Ok(out_msg.body),
+                    }
                 })
             })
             .forward(sink)
@@ -51,12 +51,15 @@ use tokio_util::codec::{Framed, LengthDelimitedCodec};
 
 const LOG_TARGET: &str = "comms::protocol::messaging";
 pub(super) static MESSAGING_PROTOCOL: Bytes = Bytes::from_static(b"/tari/messaging/0.1.0");
+/// Messaging protocol in which each frame is prefixed with the compression codec used for that frame. Peers that do not
+/// support this protocol fall back to `MESSAGING_PROTOCOL`.
+pub(super) static COMPRESSED_MESSAGING_PROTOCOL: Bytes = Bytes::from_static(b"/tari/messaging/0.2.0");
 const INTERNAL_MESSAGING_EVENT_CHANNEL_SIZE: usize = 150;
 
 /// The maximum amount of inbound messages to accept within the `RATE_LIMIT_RESTOCK_INTERVAL` window
 const RATE_LIMIT_CAPACITY: usize = 10;
 const RATE_LIMIT_RESTOCK_INTERVAL: Duration = Duration::from_millis(100);
-const MAX_FRAME_LENGTH: usize = 8 * 1_024 * 1_024;
+pub(super) const MAX_FRAME_LENGTH: usize = 8 * 1_024 * 1_024;
 
 pub type MessagingEventSender = broadcast::Sender<Arc<MessagingEvent>>;
 pub type MessagingEventReceiver = broadcast::Receiver<Arc<MessagingEvent>>;
@@ -302,7 +305,7 @@ impl MessagingProtocol {
         msg_tx
     }
 
-    fn spawn_inbound_handler(&mut self, peer: NodeId, substream: Substream) {
+    fn spawn_inbound_handler(&mut self, peer: NodeId, substream: Substream, is_compressed: bool) {
         let messaging_events_tx = self.messaging_events_tx.clone();
         let inbound_message_tx = self.inbound_message_tx.clone();
         let inbound_messaging = InboundMessaging::new(
@@ -312,7 +315,8 @@ impl MessagingProtocol {
             RATE_LIMIT_CAPACITY,
             RATE_LIMIT_RESTOCK_INTERVAL,
             self.config.inactivity_timeout,
-        );
+        )
+        .with_compressed_frames(is_compressed);
         task::spawn(inbound_messaging.run(substream));
     }
 
@@ -322,11 +326,13 @@ impl MessagingProtocol {
             ProtocolEvent::NewInboundSubstream(node_id, substream) => {
                 debug!(
                     target: LOG_TARGET,
-                    "NewInboundSubstream for peer '{}'",
-                    node_id.short_str()
+                    "NewInboundSubstream for peer '{}' ({})",
+                    node_id.short_str(),
+                    String::from_utf8_lossy(&notification.protocol)
                 );
 
-                self.spawn_inbound_handler(node_id, substream);
+                let is_compressed = notification.protocol == COMPRESSED_MESSAGING_PROTOCOL;
+                self.spawn_inbound_handler(node_id, substream, is_compressed);
             },
         }
     }
@@ -25,9 +25,12 @@ use super::protocol::{
     MessagingEventReceiver,
     MessagingProtocol,
     MessagingRequest,
+    MAX_FRAME_LENGTH,
     MESSAGING_PROTOCOL,
 };
 use crate::{
+    compression,
+    compression::CompressionCodec,
     memsocket::MemorySocket,
     message::{InboundMessage, MessageTag, MessagingReplyRx, OutboundMessage},
     multiplexing::Substream,
@@ -185,7 +188,8 @@ async fn send_message_request() {
     // Check that node got the message
     let stream = peer_conn_mock2.next_incoming_substream().await.unwrap();
     let mut framed = MessagingProtocol::framed(stream);
-    let msg = framed.next().await.unwrap().unwrap();
+    let frame = framed.next().await.unwrap().unwrap();
+    let msg = compression::decode_frame(&frame, MAX_FRAME_LENGTH).unwrap();
     assert_eq!(msg, TEST_MSG1);
 
     // Got the call to create a substream
@@ -392,3 +396,36 @@ async fn inactivity_timeout() {
 
     let _ = collect_stream!(inbound_msg_rx, take = 5, timeout = Duration::from_secs(10));
 }
+
+#[runtime::test_basic]
+async fn compressed_inbound_messages() {
+    let node_identity = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+    let (inbound_msg_tx, mut inbound_msg_rx) = mpsc::channel(5);
+    let (events_tx, _) = broadcast::channel(1);
+
+    let (socket_in, socket_out) = MemorySocket::new_pair();
+
+    task::spawn(
+        InboundMessaging::new(
+            node_identity.node_id().clone(),
+            inbound_msg_tx,
+            events_tx,
+            10,
+            Duration::from_millis(100),
+            None,
+        )
+        .with_compressed_frames(true)
+        .run(socket_in),
+    );
+
+    let large_msg = Bytes::from(vec![1u8; 10 * 1024]);
+    let mut framed = MessagingProtocol::framed(socket_out);
+    for msg in &[TEST_MSG1.clone(), large_msg.clone()] {
+        let frame = compression::encode_frame(CompressionCodec::Deflate, msg.clone()).unwrap();
+        framed.send(frame).await.unwrap();
+    }
+
+    let msgs = collect_stream!(inbound_msg_rx, take = 2, timeout = Duration::from_secs(10));
+    assert_eq!(msgs[0].body, TEST_MSG1);
+    assert_eq!(msgs[1].body, large_msg);
+}
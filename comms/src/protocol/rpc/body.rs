@@ -287,11 +287,53 @@ impl<T: prost::Message + Default + Unpin> Stream for ClientStreaming<T> {
     }
 }
 
+/// The stream of messages sent by the client in a client-streaming or bidirectional streaming request.
+///
+/// Messages are received on a bounded channel. A client that sends messages faster than the service consumes them is
+/// slowed down by the flow control of the underlying substream.
+#[derive(Debug)]
+pub struct RequestStream<T> {
+    inner: mpsc::Receiver<Result<Bytes, RpcStatus>>,
+    _out: PhantomData<T>,
+}
+
+impl<T> RequestStream<T> {
+    pub fn new(inner: mpsc::Receiver<Result<Bytes, RpcStatus>>) -> Self {
+        Self {
+            inner,
+            _out: PhantomData,
+        }
+    }
+}
+
+impl<T: prost::Message + Default + Unpin> Stream for RequestStream<T> {
+    type Item = Result<T, RpcStatus>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match ready!(self.inner.poll_next_unpin(cx)) {
+            Some(Ok(bytes)) => {
+                let result = T::decode(bytes).map_err(Into::into);
+                Poll::Ready(Some(result))
+            },
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{message::MessageExt, protocol::rpc::body::Body, runtime};
+    use crate::{
+        message::MessageExt,
+        protocol::rpc::{
+            body::{Body, RequestStream},
+            RpcStatus,
+            RpcStatusCode,
+        },
+        runtime,
+    };
     use bytes::Bytes;
-    use futures::{stream, StreamExt};
+    use futures::{channel::mpsc, stream, SinkExt, StreamExt};
     use prost::Message;
 
     #[runtime::test_basic]
@@ -312,4 +354,19 @@ mod test {
         assert!(body_bytes.iter().take(10).all(|b| !b.is_finished()));
         assert!(body_bytes.last().unwrap().is_finished());
     }
+
+    #[runtime::test_basic]
+    async fn request_stream() {
+        let (mut tx, rx) = mpsc::channel(3);
+        tx.send(Ok(1u32.to_encoded_bytes().into())).await.unwrap();
+        tx.send(Ok(Bytes::from_static(&[0xff]))).await.unwrap();
+        tx.send(Err(RpcStatus::timed_out("timed out"))).await.unwrap();
+        drop(tx);
+
+        let items = RequestStream::<u32>::new(rx).collect::<Vec<_>>().await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(*items[0].as_ref().unwrap(), 1);
+        assert_eq!(items[1].as_ref().unwrap_err().status_code(), RpcStatusCode::BadRequest);
+        assert!(items[2].as_ref().unwrap_err().status_code().is_timeout());
+    }
 }
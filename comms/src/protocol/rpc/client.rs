@@ -22,29 +22,35 @@
 
 use super::message::RpcMethod;
 use crate::{
+    compression::{CompressionCodec, SUPPORTED_CODECS},
     framing::CanonicalFraming,
     message::MessageExt,
     proto,
     protocol::rpc::{
         body::ClientStreaming,
-        message::BaseRequest,
+        handshake::STREAMING_REQUESTS_MIN_VERSION,
+        message::{BaseRequest, RpcMessageFlags},
         Handshake,
         NamedProtocolService,
         Response,
         RpcError,
         RpcStatus,
+        RPC_MAX_FRAME_SIZE,
     },
     runtime::task,
 };
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures::{
     channel::{mpsc, oneshot},
+    future,
     future::Either,
+    stream::BoxStream,
     task::{Context, Poll},
     AsyncRead,
     AsyncWrite,
     FutureExt,
     SinkExt,
+    Stream,
     StreamExt,
 };
 use log::*;
@@ -52,6 +58,7 @@ use prost::Message;
 use std::{
     fmt,
     future::Future,
+    io,
     marker::PhantomData,
     time::{Duration, Instant},
 };
@@ -120,11 +127,46 @@ impl RpcClient {
         Ok(ClientStreaming::new(resp))
     }
 
+    /// Perform a streaming request and single response. The request stream is sent to the server as it is produced,
+    /// and is stopped early if the server responds before the stream has ended.
+    pub async fn client_streaming<S, T, R, M>(&mut self, requests: S, method: M) -> Result<R, RpcError>
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: prost::Message + 'static,
+        R: prost::Message + Default,
+        M: Into<RpcMethod>,
+    {
+        let mut resp = self.call_streaming_inner(requests, method.into()).await?;
+        let resp = resp.next().await.ok_or_else(|| RpcError::ServerClosedRequest)??;
+        let resp = R::decode(resp.into_message())?;
+
+        Ok(resp)
+    }
+
+    /// Perform a streaming request and streaming response. Requests and responses are sent and received concurrently,
+    /// so the request stream may depend on the responses received so far.
+    pub async fn bidi_streaming<S, T, R, M>(&mut self, requests: S, method: M) -> Result<ClientStreaming<R>, RpcError>
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: prost::Message + 'static,
+        R: prost::Message + Default,
+        M: Into<RpcMethod>,
+    {
+        let resp = self.call_streaming_inner(requests, method.into()).await?;
+
+        Ok(ClientStreaming::new(resp))
+    }
+
     /// Close the RPC session. Any subsequent calls will error.
     pub fn close(&mut self) {
         self.connector.close()
     }
 
+    /// Returns true if the RPC session is open, otherwise false
+    pub fn is_connected(&self) -> bool {
+        self.connector.is_connected()
+    }
+
     /// Return the latency of the last request
     pub fn get_last_request_latency(&mut self) -> impl Future<Output = Result<Option<Duration>, RpcError>> + '_ {
         self.connector.get_last_request_latency()
@@ -139,6 +181,19 @@ impl RpcClient {
         let resp = svc.call(request).await?;
         Ok(resp)
     }
+
+    async fn call_streaming_inner<S, T>(
+        &mut self,
+        requests: S,
+        method: RpcMethod,
+    ) -> Result<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>, RpcError>
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: prost::Message + 'static,
+    {
+        let requests = requests.map(|msg| Bytes::from(msg.to_encoded_bytes())).boxed();
+        self.connector.streaming_request(method, requests).await
+    }
 }
 
 impl fmt::Debug for RpcClient {
@@ -198,6 +253,14 @@ where TClient: From<RpcClient> + NamedProtocolService
         self
     }
 
+    /// Enable or disable negotiating payload compression with the server. If the server does not support compression,
+    /// the session continues without it.
+    /// Default: enabled
+    pub fn with_compression(mut self, is_enabled: bool) -> Self {
+        self.config.compression_enabled = is_enabled;
+        self
+    }
+
     /// Negotiates and establishes a session to the peer's RPC service
     pub async fn connect<TSubstream>(self, framed: CanonicalFraming<TSubstream>) -> Result<TClient, RpcError>
     where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + 'static {
@@ -210,6 +273,7 @@ pub struct RpcClientConfig {
     pub deadline: Option<Duration>,
     pub deadline_grace_period: Duration,
     pub handshake_timeout: Duration,
+    pub compression_enabled: bool,
 }
 
 impl RpcClientConfig {
@@ -225,6 +289,7 @@ impl Default for RpcClientConfig {
             deadline: Some(Duration::from_secs(30)),
             deadline_grace_period: Duration::from_secs(10),
             handshake_timeout: Duration::from_secs(15),
+            compression_enabled: true,
         }
     }
 }
@@ -239,6 +304,10 @@ impl ClientConnector {
         self.inner.close_channel();
     }
 
+    pub fn is_connected(&self) -> bool {
+        !self.inner.is_closed()
+    }
+
     pub async fn get_last_request_latency(&mut self) -> Result<Option<Duration>, RpcError> {
         let (reply, reply_rx) = oneshot::channel();
         self.inner
@@ -248,6 +317,25 @@ impl ClientConnector {
 
         reply_rx.await.map_err(|_| RpcError::RequestCancelled)
     }
+
+    pub async fn streaming_request(
+        &mut self,
+        method: RpcMethod,
+        requests: BoxStream<'static, Bytes>,
+    ) -> Result<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>, RpcError>
+    {
+        let (reply, reply_rx) = oneshot::channel();
+        self.inner
+            .send(ClientRequest::SendStreamingRequest {
+                method,
+                requests,
+                reply,
+            })
+            .await
+            .map_err(|_| RpcError::ClientClosed)?;
+
+        reply_rx.await.map_err(|_| RpcError::RequestCancelled)
+    }
 }
 
 impl fmt::Debug for ClientConnector {
@@ -289,6 +377,8 @@ pub struct RpcClientWorker<TSubstream> {
     request_id: u16,
     ready_tx: Option<oneshot::Sender<Result<(), RpcError>>>,
     latency: Option<Duration>,
+    version: u32,
+    compression: CompressionCodec,
 }
 
 impl<TSubstream> RpcClientWorker<TSubstream>
@@ -308,19 +398,30 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
             request_id: 0,
             ready_tx: Some(ready_tx),
             latency: None,
+            version: 0,
+            compression: CompressionCodec::None,
         }
     }
 
     async fn run(mut self) {
         debug!(target: LOG_TARGET, "Performing client handshake");
         let start = Instant::now();
-        let mut handshake = Handshake::new(&mut self.framed).with_timeout(self.config.handshake_timeout);
+        let compression = if self.config.compression_enabled {
+            SUPPORTED_CODECS
+        } else {
+            &[]
+        };
+        let mut handshake = Handshake::new(&mut self.framed)
+            .with_timeout(self.config.handshake_timeout)
+            .with_compression(compression);
         match handshake.perform_client_handshake().await {
-            Ok(_) => {
+            Ok(version) => {
                 let latency = start.elapsed();
+                self.version = version;
+                self.compression = handshake.negotiated_compression();
                 debug!(
                     target: LOG_TARGET,
-                    "RPC Session negotiation completed. Latency: {:.0?}", latency
+                    "RPC Session negotiation completed. Latency: {:.0?}, Compression: {}", latency, self.compression
                 );
                 self.latency = Some(latency);
                 if let Some(r) = self.ready_tx.take() {
@@ -345,6 +446,16 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
                         break;
                     }
                 },
+                SendStreamingRequest {
+                    method,
+                    requests,
+                    reply,
+                } => {
+                    if let Err(err) = self.do_streaming_request(method, requests, reply).await {
+                        debug!(target: LOG_TARGET, "Unexpected error: {}. Worker is terminating.", err);
+                        break;
+                    }
+                },
                 GetLastRequestLatency(reply) => {
                     let _ = reply.send(self.latency);
                 },
@@ -384,26 +495,156 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
             response_tx.close_channel();
         }
 
+        Self::read_responses(
+            &mut self.framed,
+            &self.config,
+            self.compression,
+            &mut self.latency,
+            request_id,
+            method,
+            start,
+            response_tx,
+        )
+        .await
+    }
+
+    async fn do_streaming_request(
+        &mut self,
+        method: RpcMethod,
+        mut requests: BoxStream<'static, Bytes>,
+        reply: oneshot::Sender<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>>,
+    ) -> Result<(), RpcError>
+    {
+        let (mut response_tx, response_rx) = mpsc::channel(1);
+        if reply.send(response_rx).is_err() {
+            debug!(target: LOG_TARGET, "Client request was cancelled.");
+            response_tx.close_channel();
+        }
+
+        if self.version < STREAMING_REQUESTS_MIN_VERSION {
+            let _ = response_tx
+                .send(Err(RpcStatus::unsupported_method(format!(
+                    "Server negotiated RPC v{} which does not support streaming requests",
+                    self.version
+                ))))
+                .await;
+            response_tx.close_channel();
+            return Ok(());
+        }
+
+        let request_id = self.next_request_id();
+        let method = method.into();
+        let deadline = self.config.deadline.map(|t| t.as_secs()).unwrap_or(0);
+        debug!(
+            target: LOG_TARGET,
+            "Sending streaming request: RequestID={}, Method={}", request_id, method
+        );
+
+        let start = Instant::now();
+        let (mut sink, mut stream) = (&mut self.framed).split::<Bytes>();
+        // Resolves (cancelled) once all responses have been received so that no further request messages are sent
+        let (responses_done_tx, mut responses_done_rx) = oneshot::channel::<()>();
+
+        let send_requests = async move {
+            let mut num_sent = 0usize;
+            loop {
+                match future::select(&mut responses_done_rx, requests.next()).await {
+                    Either::Right((Some(msg), _)) => {
+                        let req = proto::rpc::RpcRequest {
+                            request_id: request_id as u32,
+                            method,
+                            deadline,
+                            flags: RpcMessageFlags::STREAMING.bits().into(),
+                            message: msg.to_vec(),
+                        };
+                        // Waiting for the message to be sent applies the substream's flow control to the request
+                        // stream
+                        sink.send(req.to_encoded_bytes().into()).await?;
+                        num_sent += 1;
+                    },
+                    Either::Right((None, _)) | Either::Left(_) => break,
+                }
+            }
+
+            // Terminate the request stream
+            let fin = proto::rpc::RpcRequest {
+                request_id: request_id as u32,
+                method,
+                deadline,
+                flags: (RpcMessageFlags::STREAMING | RpcMessageFlags::FIN).bits().into(),
+                message: Vec::new(),
+            };
+            sink.send(fin.to_encoded_bytes().into()).await?;
+            trace!(
+                target: LOG_TARGET,
+                "Request stream #{} (method={}) completed after {} message(s)",
+                request_id,
+                method,
+                num_sent
+            );
+            Result::<_, RpcError>::Ok(())
+        };
+
+        let config = &self.config;
+        let compression = self.compression;
+        let latency = &mut self.latency;
+        let receive_responses = async move {
+            let result = Self::read_responses(
+                &mut stream,
+                config,
+                compression,
+                latency,
+                request_id,
+                method,
+                start,
+                response_tx,
+            )
+            .await;
+            drop(responses_done_tx);
+            result
+        };
+
+        future::try_join(send_requests, receive_responses).await?;
+        Ok(())
+    }
+
+    /// Reads responses for the given request until the FIN flag is received, an error status is received or the
+    /// request times out.
+    #[allow(clippy::too_many_arguments)]
+    async fn read_responses<S>(
+        stream: &mut S,
+        config: &RpcClientConfig,
+        compression: CompressionCodec,
+        latency: &mut Option<Duration>,
+        request_id: u16,
+        method: u32,
+        start: Instant,
+        mut response_tx: mpsc::Sender<Result<Response<Bytes>, RpcStatus>>,
+    ) -> Result<(), RpcError>
+    where
+        S: Stream<Item = io::Result<BytesMut>> + Unpin,
+    {
         loop {
             // Wait until the timeout, allowing an extra grace period to account for latency
-            let next_msg_fut = match self.config.timeout_with_grace_period() {
-                Some(timeout) => Either::Left(time::timeout(timeout, self.framed.next())),
-                None => Either::Right(self.framed.next().map(Ok)),
+            let next_msg_fut = match config.timeout_with_grace_period() {
+                Some(timeout) => Either::Left(time::timeout(timeout, stream.next())),
+                None => Either::Right(stream.next().map(Ok)),
             };
 
             let resp = match next_msg_fut.await {
                 Ok(Some(Ok(resp))) => {
-                    let latency = start.elapsed();
+                    let elapsed = start.elapsed();
                     trace!(
                         target: LOG_TARGET,
                         "Received response ({} byte(s)) from request #{} (method={}) in {:.0?}",
                         resp.len(),
                         request_id,
                         method,
-                        latency
+                        elapsed
                     );
-                    self.latency = Some(latency);
-                    proto::rpc::RpcResponse::decode(resp)?
+                    *latency = Some(elapsed);
+                    let resp = proto::rpc::RpcResponse::decode(resp)?;
+                    Self::decompress_response(compression, resp)?
                 },
                 Ok(Some(Err(err))) => {
                     return Err(err.into());
@@ -455,6 +696,26 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
         Ok(())
     }
 
+    fn decompress_response(
+        compression: CompressionCodec,
+        mut resp: proto::rpc::RpcResponse,
+    ) -> Result<proto::rpc::RpcResponse, RpcError>
+    {
+        let mut flags = resp.flags();
+        if !flags.is_compressed() {
+            return Ok(resp);
+        }
+        if compression.is_none() {
+            return Err(RpcError::client_internal_error(
+                "Server sent a compressed response but compression was not negotiated",
+            ));
+        }
+        resp.message = compression.decompress(&resp.message, RPC_MAX_FRAME_SIZE)?;
+        flags.remove(RpcMessageFlags::COMPRESSED);
+        resp.flags = flags.bits().into();
+        Ok(resp)
+    }
+
     fn next_request_id(&mut self) -> u16 {
         let next_id = self.request_id;
         // request_id is allowed to wrap around back to 0
@@ -482,5 +743,10 @@ pub enum ClientRequest {
         request: BaseRequest<Bytes>,
         reply: oneshot::Sender<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>>,
     },
+    SendStreamingRequest {
+        method: RpcMethod,
+        requests: BoxStream<'static, Bytes>,
+        reply: oneshot::Sender<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>>,
+    },
     GetLastRequestLatency(oneshot::Sender<Option<Duration>>),
 }
@@ -0,0 +1,391 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    backoff::{Backoff, BoxedBackoff, ExponentialBackoff},
+    peer_manager::NodeId,
+    protocol::{
+        rpc::{error::HandshakeRejectReason, NamedProtocolService, RpcClient, RpcClientBuilder, RpcError},
+        ProtocolId,
+    },
+    PeerConnection,
+};
+use log::*;
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{self, Arc},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::{sync::Mutex, time};
+
+const LOG_TARGET: &str = "comms::protocol::rpc::client_pool";
+
+/// The number of sessions that each shared pool establishes to a peer for an RPC protocol
+const DEFAULT_SHARED_POOL_SIZE: usize = 3;
+
+/// Implemented by RPC clients that can be managed by an [RpcClientPool](self::RpcClientPool).
+pub trait RpcPoolClient {
+    /// Returns true if the underlying RPC session is still open
+    fn is_connected(&self) -> bool;
+}
+
+/// A pool of RPC client sessions to a single peer for the `T` RPC protocol.
+///
+/// Sessions are established lazily, up to `pool_size`. Each call to [get](Self::get) returns the least used healthy
+/// session, establishing a new one only if all existing sessions are in use. Sessions that have closed are discarded
+/// and replaced on the next call. Failed connection attempts are retried according to the pool's back-off policy.
+#[derive(Clone)]
+pub struct RpcClientPool<T> {
+    pool: Arc<Mutex<LazyPool<T>>>,
+    // Held while a new session is established, so that only one session is being established at a time
+    connect_lock: Arc<Mutex<()>>,
+}
+
+impl<T> RpcClientPool<T>
+where T: RpcPoolClient + From<RpcClient> + NamedProtocolService + Clone
+{
+    /// Create a new pool using the given peer connection. Sessions are not established until they are requested.
+    pub(crate) fn new(peer_connection: PeerConnection, pool_size: usize, client_config: RpcClientBuilder<T>) -> Self {
+        Self::with_backoff(peer_connection, pool_size, client_config, ExponentialBackoff::default())
+    }
+
+    /// Create a new pool that uses the given back-off policy between failed session attempts.
+    pub(crate) fn with_backoff<B>(
+        peer_connection: PeerConnection,
+        pool_size: usize,
+        client_config: RpcClientBuilder<T>,
+        backoff: B,
+    ) -> Self
+    where
+        B: Backoff + Send + Sync + 'static,
+    {
+        let pool = LazyPool::new(peer_connection, pool_size, client_config, Box::new(backoff));
+        Self {
+            pool: Arc::new(Mutex::new(pool)),
+            connect_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns a lease on the least used healthy session, connecting a new session if necessary. The pool is not
+    /// locked while waiting out the back-off period or establishing the session, so other callers can lease the
+    /// existing sessions in the meantime.
+    pub async fn get(&self) -> Result<RpcClientLease<T>, RpcClientPoolError> {
+        if let Some(client) = self.pool.lock().await.get_existing()? {
+            return Ok(client);
+        }
+
+        let _connect_guard = match self.connect_lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                // Share an existing session rather than waiting for the session that is being established
+                if let Some(client) = self.pool.lock().await.least_used() {
+                    return Ok(client);
+                }
+                self.connect_lock.lock().await
+            },
+        };
+
+        let (mut connection, client_config, backoff) = {
+            let mut pool = self.pool.lock().await;
+            // Another caller may have established a session while we were waiting
+            if let Some(client) = pool.get_existing()? {
+                return Ok(client);
+            }
+            (
+                pool.connection.clone(),
+                pool.client_config.clone(),
+                pool.backoff_remaining(),
+            )
+        };
+
+        if let Some(remaining) = backoff {
+            debug!(
+                target: LOG_TARGET,
+                "Backing off for {:.2?} before reconnecting RPC session to peer `{}`",
+                remaining,
+                connection.peer_node_id()
+            );
+            time::delay_for(remaining).await;
+        }
+        let result = connection.connect_rpc_using_builder(client_config).await;
+
+        self.pool.lock().await.add_client_session(result)
+    }
+
+    /// Returns the number of sessions currently held by the pool
+    pub async fn num_sessions(&self) -> usize {
+        let pool = self.pool.lock().await;
+        pool.clients.len()
+    }
+
+    /// Returns the number of healthy sessions currently held by the pool
+    pub async fn num_active_sessions(&self) -> usize {
+        let pool = self.pool.lock().await;
+        pool.clients.iter().filter(|c| c.is_connected()).count()
+    }
+}
+
+/// Shares [RpcClientPool](self::RpcClientPool)s between all callers, keyed by peer and RPC protocol, so that the
+/// callers that connect to the same peer for the same protocol share a limited number of sessions.
+///
+/// A pool is replaced when the peer connection that it uses is no longer connected.
+#[derive(Clone)]
+pub struct RpcClientPools {
+    pools: Arc<sync::Mutex<HashMap<(NodeId, ProtocolId), SharedPool>>>,
+    pool_size: usize,
+}
+
+struct SharedPool {
+    connection: PeerConnection,
+    pool: Box<dyn Any + Send + Sync>,
+}
+
+impl RpcClientPools {
+    /// Create a new set of shared pools that each establish up to `pool_size` sessions
+    pub fn new(pool_size: usize) -> Self {
+        Self {
+            pools: Default::default(),
+            pool_size,
+        }
+    }
+
+    /// Returns the pool for the peer of the given connection and the `T` RPC protocol, creating one that uses the
+    /// given client config if necessary. The client config of an existing pool is not changed.
+    pub fn get_or_create<T>(
+        &self,
+        connection: &PeerConnection,
+        client_config: RpcClientBuilder<T>,
+    ) -> RpcClientPool<T>
+    where
+        T: RpcPoolClient + From<RpcClient> + NamedProtocolService + Clone + Send + 'static,
+    {
+        let mut pools = self.pools.lock().expect("RpcClientPools lock poisoned");
+        pools.retain(|_, shared| shared.connection.is_connected());
+
+        let key = (
+            connection.peer_node_id().clone(),
+            ProtocolId::from_static(T::PROTOCOL_NAME),
+        );
+        if let Some(pool) = pools
+            .get(&key)
+            .filter(|shared| shared.connection.id() == connection.id())
+            .and_then(|shared| shared.pool.downcast_ref::<RpcClientPool<T>>())
+        {
+            return pool.clone();
+        }
+
+        let pool = RpcClientPool::new(connection.clone(), self.pool_size, client_config);
+        pools.insert(key, SharedPool {
+            connection: connection.clone(),
+            pool: Box::new(pool.clone()),
+        });
+        pool
+    }
+
+    /// Returns the number of pools that are currently shared
+    pub fn num_pools(&self) -> usize {
+        self.pools.lock().expect("RpcClientPools lock poisoned").len()
+    }
+}
+
+impl Default for RpcClientPools {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHARED_POOL_SIZE)
+    }
+}
+
+impl fmt::Debug for RpcClientPools {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcClientPools")
+            .field("num_pools", &self.num_pools())
+            .field("pool_size", &self.pool_size)
+            .finish()
+    }
+}
+
+struct LazyPool<T> {
+    connection: PeerConnection,
+    clients: Vec<RpcClientLease<T>>,
+    client_config: RpcClientBuilder<T>,
+    capacity: usize,
+    backoff: BoxedBackoff,
+    failed_attempts: usize,
+    last_failed_at: Option<Instant>,
+}
+
+impl<T> LazyPool<T>
+where T: RpcPoolClient + From<RpcClient> + NamedProtocolService + Clone
+{
+    pub fn new(
+        connection: PeerConnection,
+        capacity: usize,
+        client_config: RpcClientBuilder<T>,
+        backoff: BoxedBackoff,
+    ) -> Self
+    {
+        assert!(capacity > 0, "RPC client pool capacity must be greater than zero");
+        Self {
+            connection,
+            clients: Vec::with_capacity(capacity),
+            client_config,
+            capacity,
+            backoff,
+            failed_attempts: 0,
+            last_failed_at: None,
+        }
+    }
+
+    /// Returns the least used session if no new session should be established, that is if a session is idle, the
+    /// pool is full, or the pool is backing off after a failed attempt while it holds other sessions.
+    pub fn get_existing(&mut self) -> Result<Option<RpcClientLease<T>>, RpcClientPoolError> {
+        self.prune_disconnected();
+
+        if !self.connection.is_connected() {
+            return Err(RpcClientPoolError::PeerConnectionDropped {
+                peer: self.connection.peer_node_id().clone(),
+            });
+        }
+
+        let has_idle_session = self.clients.iter().any(|c| c.lease_count() == 0);
+        // Existing sessions are shared rather than waiting out the back-off period
+        let is_backing_off = !self.clients.is_empty() && self.backoff_remaining().is_some();
+        if has_idle_session || is_backing_off || self.clients.len() >= self.capacity {
+            return Ok(self.least_used());
+        }
+        Ok(None)
+    }
+
+    pub fn least_used(&self) -> Option<RpcClientLease<T>> {
+        self.clients.iter().min_by_key(|c| c.lease_count()).cloned()
+    }
+
+    fn prune_disconnected(&mut self) {
+        let before = self.clients.len();
+        self.clients.retain(|c| c.is_connected());
+        let num_pruned = before - self.clients.len();
+        if num_pruned > 0 {
+            debug!(
+                target: LOG_TARGET,
+                "Removed {} closed RPC session(s) to peer `{}`",
+                num_pruned,
+                self.connection.peer_node_id()
+            );
+        }
+    }
+
+    fn backoff_remaining(&self) -> Option<Duration> {
+        let last_failed_at = self.last_failed_at?;
+        let backoff = self.backoff.calculate_backoff(self.failed_attempts);
+        backoff
+            .checked_sub(last_failed_at.elapsed())
+            .filter(|d| *d > Duration::from_secs(0))
+    }
+
+    /// Adds the result of a session attempt to the pool. If the attempt failed, an existing session is shared if
+    /// there is one.
+    pub fn add_client_session(&mut self, result: Result<T, RpcError>) -> Result<RpcClientLease<T>, RpcClientPoolError> {
+        match result {
+            Ok(client) => {
+                self.failed_attempts = 0;
+                self.last_failed_at = None;
+                let client = RpcClientLease::new(client);
+                self.clients.push(client.clone());
+                Ok(client)
+            },
+            Err(err) => {
+                self.failed_attempts += 1;
+                self.last_failed_at = Some(Instant::now());
+                if let Some(client) = self.least_used() {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to establish additional RPC session to peer `{}`: {}. Sharing an existing session.",
+                        self.connection.peer_node_id(),
+                        err
+                    );
+                    return Ok(client);
+                }
+                match err {
+                    RpcError::HandshakeRejected(HandshakeRejectReason::NoSessionsAvailable) => {
+                        Err(RpcClientPoolError::NoMoreRemoteRpcSessions)
+                    },
+                    err => Err(RpcClientPoolError::FailedToConnect(err)),
+                }
+            },
+        }
+    }
+}
+
+/// A leased RPC client from an [RpcClientPool](self::RpcClientPool). The session is returned to the pool when all
+/// leases for it are dropped.
+#[derive(Debug, Clone)]
+pub struct RpcClientLease<T> {
+    inner: T,
+    rc: Arc<()>,
+}
+
+impl<T> RpcClientLease<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            rc: Arc::new(()),
+        }
+    }
+
+    /// Returns the number of leases held on this session, excluding the pool's own reference
+    fn lease_count(&self) -> usize {
+        Arc::strong_count(&self.rc) - 1
+    }
+}
+
+impl<T> Deref for RpcClientLease<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for RpcClientLease<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T: RpcPoolClient> RpcPoolClient for RpcClientLease<T> {
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RpcClientPoolError {
+    #[error("Peer connection to peer '{peer}' dropped")]
+    PeerConnectionDropped { peer: NodeId },
+    #[error("No peer RPC sessions are available")]
+    NoMoreRemoteRpcSessions,
+    #[error("Failed to create client connection: {0}")]
+    FailedToConnect(RpcError),
+}
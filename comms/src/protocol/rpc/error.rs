@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use super::RpcStatus;
+use super::{QuotaKind, RpcStatus};
 use crate::{
     connectivity::ConnectivityError,
     peer_manager::PeerManagerError,
@@ -43,6 +43,8 @@ pub enum RpcError {
     RequestFailed(#[from] RpcStatus),
     #[error("Maximum number of concurrent RPC sessions reached")]
     MaximumConcurrencyReached,
+    #[error("Peer exceeded the {0} quota")]
+    QuotaExceeded(QuotaKind),
     #[error("Service not found for protocol `{0}`")]
     ProtocolServiceNotFound(String),
     #[error("Remote peer unexpectedly closed the RPC connection")]
@@ -51,10 +53,16 @@ pub enum RpcError {
     RequestCancelled,
     #[error("Client internal error: {0}")]
     ClientInternalError(String),
+    #[error("Timed out waiting for the next message of a streaming request")]
+    StreamingRequestTimedOut,
+    #[error("Invalid streaming request: {0}")]
+    InvalidStreamingRequest(String),
     #[error("RPC handshake timed out")]
     HandshakeTimedOut,
     #[error("RPC handshake failed: The client does not support any RPC protocol version supported by this node")]
     HandshakeClientNoSupportedVersion,
+    #[error("RPC handshake failed: The server selected compression codec {0} which this node does not support")]
+    HandshakeUnsupportedCompression(u32),
     #[error("RPC handshake was explicitly rejected: {0}")]
     HandshakeRejected(#[from] HandshakeRejectReason),
     #[error("Peer connection error: {0}")]
@@ -71,6 +79,15 @@ impl RpcError {
     pub fn client_internal_error<T: ToString>(err: T) -> Self {
         RpcError::ClientInternalError(err.to_string())
     }
+
+    /// Returns true if the remote peer did not respond to the handshake or a request in time
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            RpcError::HandshakeTimedOut | RpcError::StreamingRequestTimedOut => true,
+            RpcError::RequestFailed(status) => status.status_code().is_timeout(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Error, Clone, Copy)]
@@ -21,6 +21,7 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
+    compression::CompressionCodec,
     framing::CanonicalFraming,
     message::MessageExt,
     proto,
@@ -30,19 +31,24 @@ use bytes::BytesMut;
 use futures::{AsyncRead, AsyncWrite, SinkExt, StreamExt};
 use log::*;
 use prost::Message;
-use std::{io, time::Duration};
+use std::{convert::TryFrom, io, time::Duration};
 use tokio::time;
 
 const LOG_TARGET: &str = "comms::rpc::handshake";
 
-/// Supported RPC protocol versions.
-/// Currently only v0 is supported
-pub(super) const SUPPORTED_RPC_VERSIONS: &[u32] = &[0];
+/// Supported RPC protocol versions, in order of preference.
+/// v1 adds client-streaming and bidirectional streaming requests.
+pub(super) const SUPPORTED_RPC_VERSIONS: &[u32] = &[1, 0];
+
+/// The minimum RPC protocol version that supports streaming requests
+pub(super) const STREAMING_REQUESTS_MIN_VERSION: u32 = 1;
 
 /// Handshake protocol
 pub struct Handshake<'a, T> {
     framed: &'a mut CanonicalFraming<T>,
     timeout: Option<Duration>,
+    supported_compression: &'a [CompressionCodec],
+    negotiated_compression: CompressionCodec,
 }
 
 impl<'a, T> Handshake<'a, T>
@@ -50,7 +56,12 @@ where T: AsyncRead + AsyncWrite + Unpin
 {
     /// Create a Handshake using the given framing and no timeout. To set a timeout, use `with_timeout`.
     pub fn new(framed: &'a mut CanonicalFraming<T>) -> Self {
-        Self { framed, timeout: None }
+        Self {
+            framed,
+            timeout: None,
+            supported_compression: &[],
+            negotiated_compression: CompressionCodec::None,
+        }
     }
 
     /// Set the length of time that a client/server should wait for the other side to response before timing out.
@@ -59,6 +70,19 @@ where T: AsyncRead + AsyncWrite + Unpin
         self
     }
 
+    /// Set the compression codecs that this side of the session supports, in order of preference. By default,
+    /// compression is not negotiated.
+    pub fn with_compression(mut self, codecs: &'a [CompressionCodec]) -> Self {
+        self.supported_compression = codecs;
+        self
+    }
+
+    /// The compression codec agreed upon by the client and server. This is `CompressionCodec::None` until the
+    /// handshake has completed, or if either side does not support compression.
+    pub fn negotiated_compression(&self) -> CompressionCodec {
+        self.negotiated_compression
+    }
+
     /// Server-side handshake protocol
     pub async fn perform_server_handshake(&mut self) -> Result<u32, RpcError> {
         match self.recv_next_frame().await {
@@ -68,12 +92,19 @@ where T: AsyncRead + AsyncWrite + Unpin
                     .iter()
                     .find(|v| msg.supported_versions.contains(v));
                 if let Some(version) = version {
-                    debug!(target: LOG_TARGET, "Server accepted version {}", version);
+                    let compression =
+                        CompressionCodec::negotiate(self.supported_compression, msg.supported_compression);
+                    debug!(
+                        target: LOG_TARGET,
+                        "Server accepted version {} (compression: {})", version, compression
+                    );
                     let reply = proto::rpc::RpcSessionReply {
                         session_result: Some(proto::rpc::rpc_session_reply::SessionResult::AcceptedVersion(*version)),
+                        compression: u32::from(compression.as_u8()),
                         ..Default::default()
                     };
                     self.framed.send(reply.to_encoded_bytes().into()).await?;
+                    self.negotiated_compression = compression;
                     return Ok(*version);
                 }
 
@@ -91,16 +122,22 @@ where T: AsyncRead + AsyncWrite + Unpin
         let reply = proto::rpc::RpcSessionReply {
             session_result: Some(proto::rpc::rpc_session_reply::SessionResult::Rejected(true)),
             reject_reason: reject_reason.as_i32(),
+            ..Default::default()
         };
         self.framed.send(reply.to_encoded_bytes().into()).await?;
         self.framed.close().await?;
         Ok(())
     }
 
-    /// Client-side handshake protocol
-    pub async fn perform_client_handshake(&mut self) -> Result<(), RpcError> {
+    /// Client-side handshake protocol. Returns the RPC version selected by the server.
+    pub async fn perform_client_handshake(&mut self) -> Result<u32, RpcError> {
         let msg = proto::rpc::RpcSession {
             supported_versions: SUPPORTED_RPC_VERSIONS.to_vec(),
+            supported_compression: self
+                .supported_compression
+                .iter()
+                .map(|c| u32::from(c.as_u8()))
+                .collect(),
         };
         // It is possible that the server rejects the session and closes the substream before we've had a chance to send
         // anything. Rather than returning an IO error, let's ignore the send error and see if we can receive anything,
@@ -110,8 +147,16 @@ where T: AsyncRead + AsyncWrite + Unpin
             Ok(Some(Ok(msg))) => {
                 let msg = proto::rpc::RpcSessionReply::decode(&mut msg.freeze())?;
                 let version = msg.result()?;
-                debug!(target: LOG_TARGET, "Server accepted version {}", version);
-                Ok(())
+                let compression = CompressionCodec::try_from(msg.compression)
+                    .ok()
+                    .filter(|c| c.is_none() || self.supported_compression.contains(c))
+                    .ok_or_else(|| RpcError::HandshakeUnsupportedCompression(msg.compression))?;
+                debug!(
+                    target: LOG_TARGET,
+                    "Server accepted version {} (compression: {})", version, compression
+                );
+                self.negotiated_compression = compression;
+                Ok(version)
             },
             Ok(Some(Err(err))) => Err(err.into()),
             Ok(None) => Err(RpcError::ServerClosedRequest),
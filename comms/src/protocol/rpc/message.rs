@@ -25,29 +25,56 @@ use crate::{
     proto,
     proto::rpc::rpc_session_reply::SessionResult,
     protocol::rpc::{
-        body::{Body, IntoBody},
+        body::{Body, IntoBody, RequestStream},
         context::RequestContext,
         error::HandshakeRejectReason,
+        RpcStatus,
     },
 };
 use bitflags::bitflags;
 use bytes::Bytes;
+use futures::channel::mpsc;
 use std::{fmt, time::Duration};
 
 #[derive(Debug)]
 pub struct Request<T> {
     pub(super) context: Option<RequestContext>,
     inner: BaseRequest<T>,
+    stream: Option<mpsc::Receiver<Result<Bytes, RpcStatus>>>,
 }
 
 impl Request<Bytes> {
     pub fn decode<T: prost::Message + Default>(mut self) -> Result<Request<T>, RpcError> {
+        if self.stream.is_some() {
+            return Err(RpcStatus::bad_request("Method does not accept a streaming request").into());
+        }
         let message = T::decode(&mut self.inner.message)?;
         Ok(Request {
             context: self.context,
             inner: BaseRequest::new(self.inner.method, message),
+            stream: None,
+        })
+    }
+
+    /// Converts this request into a request containing the stream of messages sent by the client. An error is returned
+    /// if the client did not make a streaming request.
+    pub fn decode_stream<T: prost::Message + Default>(mut self) -> Result<Request<RequestStream<T>>, RpcError> {
+        let stream = self
+            .stream
+            .take()
+            .ok_or_else(|| RpcStatus::bad_request("Method expects a streaming request"))?;
+        Ok(Request {
+            context: self.context,
+            inner: BaseRequest::new(self.inner.method, RequestStream::new(stream)),
+            stream: None,
         })
     }
+
+    /// Attach the stream of messages for a client-streaming request
+    pub fn with_stream(mut self, stream: mpsc::Receiver<Result<Bytes, RpcStatus>>) -> Self {
+        self.stream = Some(stream);
+        self
+    }
 }
 
 impl<T> Request<T> {
@@ -55,6 +82,7 @@ impl<T> Request<T> {
         Self {
             context: Some(context),
             inner: BaseRequest::new(method, message),
+            stream: None,
         }
     }
 
@@ -62,6 +90,7 @@ impl<T> Request<T> {
         Self {
             context: None,
             inner: BaseRequest::new(method, message),
+            stream: None,
         }
     }
 
@@ -197,12 +226,25 @@ impl Into<u32> for RpcMethod {
 bitflags! {
     pub struct RpcMessageFlags: u8 {
         const FIN = 0x01;
+        /// The message payload is compressed using the codec negotiated in the handshake
+        const COMPRESSED = 0x02;
+        /// The request is one message of a request stream. A request stream is terminated by an empty message with
+        /// the FIN flag set.
+        const STREAMING = 0x04;
     }
 }
 impl RpcMessageFlags {
     pub fn is_fin(&self) -> bool {
         self.contains(Self::FIN)
     }
+
+    pub fn is_compressed(&self) -> bool {
+        self.contains(Self::COMPRESSED)
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.contains(Self::STREAMING)
+    }
 }
 
 impl Default for RpcMessageFlags {
@@ -0,0 +1,80 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! RPC server metrics, which are only recorded when the `metrics` feature is enabled
+
+use crate::protocol::{rpc::quota::QuotaKind, ProtocolId};
+use std::time::Duration;
+#[cfg(feature = "metrics")]
+use tari_metrics::{HistogramVec, IntCounterVec};
+
+#[cfg(feature = "metrics")]
+lazy_static! {
+    static ref REQUEST_LATENCY: HistogramVec = tari_metrics::register_histogram_vec(
+        "comms_rpc_request_latency_seconds",
+        "The time taken by an RPC server to handle a request, including sending the response",
+        &["protocol"],
+    )
+    .expect("metric is registered once");
+    static ref REQUEST_ERRORS: IntCounterVec = tari_metrics::register_int_counter_vec(
+        "comms_rpc_request_errors_total",
+        "The number of RPC requests that an RPC server could not handle",
+        &["protocol"],
+    )
+    .expect("metric is registered once");
+    static ref QUOTAS_EXCEEDED: IntCounterVec = tari_metrics::register_int_counter_vec(
+        "comms_rpc_quotas_exceeded_total",
+        "The number of RPC sessions and requests that were rejected because a peer exceeded a quota",
+        &["protocol", "quota"],
+    )
+    .expect("metric is registered once");
+}
+
+#[cfg(feature = "metrics")]
+pub fn observe_request_latency(protocol: &ProtocolId, latency: Duration) {
+    REQUEST_LATENCY
+        .with_label_values(&[&String::from_utf8_lossy(protocol)])
+        .observe(latency.as_secs_f64());
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn observe_request_latency(_: &ProtocolId, _: Duration) {}
+
+#[cfg(feature = "metrics")]
+pub fn inc_request_errors(protocol: &ProtocolId) {
+    REQUEST_ERRORS
+        .with_label_values(&[&String::from_utf8_lossy(protocol)])
+        .inc();
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn inc_request_errors(_: &ProtocolId) {}
+
+#[cfg(feature = "metrics")]
+pub fn inc_quota_exceeded(protocol: &ProtocolId, quota: QuotaKind) {
+    QUOTAS_EXCEEDED
+        .with_label_values(&[&String::from_utf8_lossy(protocol), quota.as_str()])
+        .inc();
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn inc_quota_exceeded(_: &ProtocolId, _: QuotaKind) {}
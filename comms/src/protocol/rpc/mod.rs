@@ -27,7 +27,7 @@
 mod test;
 
 mod body;
-pub use body::{Body, ClientStreaming, IntoBody, Streaming};
+pub use body::{Body, ClientStreaming, IntoBody, RequestStream, Streaming};
 
 mod context;
 
@@ -37,11 +37,16 @@ pub use server::{NamedProtocolService, RpcServer};
 mod client;
 pub use client::{RpcClient, RpcClientBuilder, RpcClientConfig};
 
+mod client_pool;
+pub use client_pool::{RpcClientLease, RpcClientPool, RpcClientPoolError, RpcClientPools, RpcPoolClient};
+
 mod either;
 
 mod message;
 pub use message::{Request, Response};
 
+mod metrics;
+
 mod error;
 pub use error::RpcError;
 
@@ -55,6 +60,9 @@ pub use status::{RpcStatus, RpcStatusCode};
 
 mod not_found;
 
+mod quota;
+pub use quota::{QuotaKind, RpcQuotas};
+
 pub mod mock;
 
 /// Maximum frame size of each RPC message. This is enforced in tokio's length delimited codec.
@@ -71,15 +79,17 @@ pub mod __macro_reexports {
                 Body,
                 ClientStreaming,
                 IntoBody,
+                RequestStream,
                 RpcClient,
                 RpcClientBuilder,
                 RpcError,
+                RpcPoolClient,
                 RpcStatus,
             },
             ProtocolId,
         },
         Bytes,
     };
-    pub use futures::{future, future::BoxFuture, AsyncRead, AsyncWrite};
+    pub use futures::{future, future::BoxFuture, AsyncRead, AsyncWrite, Stream};
     pub use tower::Service;
 }
@@ -0,0 +1,284 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{peer_manager::NodeId, protocol::ProtocolId};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The window in which the per method request quota is counted
+const REQUEST_QUOTA_WINDOW: Duration = Duration::from_secs(1);
+
+/// Per peer quotas enforced by the RPC server. All quotas are unlimited by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcQuotas {
+    /// The maximum number of concurrent sessions a single peer may have open
+    pub max_sessions_per_peer: Option<usize>,
+    /// The maximum number of requests per second a single peer may make to each method of a service
+    pub max_requests_per_second_per_method: Option<usize>,
+    /// The maximum number of response bytes that will be sent to a single peer within the given window
+    pub max_bytes_per_peer: Option<(u64, Duration)>,
+}
+
+/// The quota that was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaKind {
+    SessionsPerPeer,
+    RequestsPerMethod,
+    BytesPerPeer,
+}
+
+impl QuotaKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuotaKind::SessionsPerPeer => "sessions_per_peer",
+            QuotaKind::RequestsPerMethod => "requests_per_method",
+            QuotaKind::BytesPerPeer => "bytes_per_peer",
+        }
+    }
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Keeps track of the usage of each peer across all RPC sessions for a server
+#[derive(Debug, Clone)]
+pub(super) struct QuotaTracker {
+    quotas: RpcQuotas,
+    state: Arc<Mutex<QuotaState>>,
+}
+
+#[derive(Debug, Default)]
+struct QuotaState {
+    sessions: HashMap<NodeId, usize>,
+    requests: HashMap<(NodeId, ProtocolId, u32), UsageWindow>,
+    bytes: HashMap<NodeId, UsageWindow>,
+}
+
+impl QuotaTracker {
+    pub fn new(quotas: RpcQuotas) -> Self {
+        Self {
+            quotas,
+            state: Default::default(),
+        }
+    }
+
+    /// Acquire a session for the given peer. None is returned if the peer has reached the maximum number of concurrent
+    /// sessions, otherwise a guard is returned that releases the session when dropped.
+    pub fn try_acquire_session(&self, node_id: &NodeId) -> Option<SessionQuotaGuard> {
+        let mut state = self.state.lock().unwrap();
+        let num_sessions = state.sessions.entry(node_id.clone()).or_insert(0);
+        if let Some(max) = self.quotas.max_sessions_per_peer {
+            if *num_sessions >= max {
+                return None;
+            }
+        }
+        *num_sessions += 1;
+        Some(SessionQuotaGuard {
+            node_id: node_id.clone(),
+            tracker: self.clone(),
+        })
+    }
+
+    /// Records a request to the given method. Returns false if the peer has exceeded the request quota for the method.
+    pub fn check_request(&self, node_id: &NodeId, protocol: &ProtocolId, method: u32) -> bool {
+        let max = match self.quotas.max_requests_per_second_per_method {
+            Some(max) => max as u64,
+            None => return true,
+        };
+        let mut state = self.state.lock().unwrap();
+        state
+            .requests
+            .entry((node_id.clone(), protocol.clone(), method))
+            .or_insert_with(UsageWindow::new)
+            .try_add(1, max, REQUEST_QUOTA_WINDOW)
+    }
+
+    /// Returns false if the peer has already used its byte quota for the current window
+    pub fn has_remaining_bytes(&self, node_id: &NodeId) -> bool {
+        self.check_bytes(node_id, 0)
+    }
+
+    /// Records bytes that are about to be sent to the peer. Returns false (and does not record the bytes) if sending
+    /// them would exceed the peer's byte quota.
+    pub fn check_bytes(&self, node_id: &NodeId, num_bytes: usize) -> bool {
+        let (max, window) = match self.quotas.max_bytes_per_peer {
+            Some(v) => v,
+            None => return true,
+        };
+        let mut state = self.state.lock().unwrap();
+        state
+            .bytes
+            .entry(node_id.clone())
+            .or_insert_with(UsageWindow::new)
+            .try_add(num_bytes as u64, max, window)
+    }
+
+    fn release_session(&self, node_id: &NodeId) {
+        let mut state = self.state.lock().unwrap();
+        let is_last_session = match state.sessions.get_mut(node_id) {
+            Some(n) => {
+                *n = n.saturating_sub(1);
+                *n == 0
+            },
+            None => false,
+        };
+
+        if is_last_session {
+            state.sessions.remove(node_id);
+            // Request and byte quotas outlive the session so that reconnecting does not reset them. Only expired
+            // windows are evicted.
+            state
+                .requests
+                .retain(|_, usage| !usage.is_expired(REQUEST_QUOTA_WINDOW));
+            if let Some((_, window)) = self.quotas.max_bytes_per_peer {
+                state.bytes.retain(|_, usage| !usage.is_expired(window));
+            }
+        }
+    }
+}
+
+/// Releases the session for the peer when dropped
+#[derive(Debug)]
+pub(super) struct SessionQuotaGuard {
+    node_id: NodeId,
+    tracker: QuotaTracker,
+}
+
+impl Drop for SessionQuotaGuard {
+    fn drop(&mut self) {
+        self.tracker.release_session(&self.node_id);
+    }
+}
+
+#[derive(Debug)]
+struct UsageWindow {
+    started_at: Instant,
+    used: u64,
+}
+
+impl UsageWindow {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            used: 0,
+        }
+    }
+
+    fn is_expired(&self, window: Duration) -> bool {
+        self.started_at.elapsed() >= window
+    }
+
+    fn try_add(&mut self, amount: u64, max: u64, window: Duration) -> bool {
+        if self.is_expired(window) {
+            self.started_at = Instant::now();
+            self.used = 0;
+        }
+        let used = self.used.saturating_add(amount);
+        if used > max || (amount == 0 && self.used >= max) {
+            return false;
+        }
+        self.used = used;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn session_quota() {
+        let tracker = QuotaTracker::new(RpcQuotas {
+            max_sessions_per_peer: Some(2),
+            ..Default::default()
+        });
+        let node_id = NodeId::new();
+        let guard1 = tracker.try_acquire_session(&node_id).unwrap();
+        let _guard2 = tracker.try_acquire_session(&node_id).unwrap();
+        assert!(tracker.try_acquire_session(&node_id).is_none());
+        drop(guard1);
+        assert!(tracker.try_acquire_session(&node_id).is_some());
+    }
+
+    #[test]
+    fn request_quota() {
+        let tracker = QuotaTracker::new(RpcQuotas {
+            max_requests_per_second_per_method: Some(2),
+            ..Default::default()
+        });
+        let node_id = NodeId::new();
+        let protocol = ProtocolId::from_static(b"test");
+        assert!(tracker.check_request(&node_id, &protocol, 1));
+        assert!(tracker.check_request(&node_id, &protocol, 1));
+        assert!(!tracker.check_request(&node_id, &protocol, 1));
+        // Each method has its own quota
+        assert!(tracker.check_request(&node_id, &protocol, 2));
+    }
+
+    #[test]
+    fn request_quota_is_not_reset_by_reconnecting() {
+        let tracker = QuotaTracker::new(RpcQuotas {
+            max_requests_per_second_per_method: Some(1),
+            ..Default::default()
+        });
+        let node_id = NodeId::new();
+        let protocol = ProtocolId::from_static(b"test");
+        let guard = tracker.try_acquire_session(&node_id).unwrap();
+        assert!(tracker.check_request(&node_id, &protocol, 1));
+        drop(guard);
+
+        let _guard = tracker.try_acquire_session(&node_id).unwrap();
+        assert!(!tracker.check_request(&node_id, &protocol, 1));
+    }
+
+    #[test]
+    fn byte_quota() {
+        let tracker = QuotaTracker::new(RpcQuotas {
+            max_bytes_per_peer: Some((100, Duration::from_secs(60))),
+            ..Default::default()
+        });
+        let node_id = NodeId::new();
+        assert!(tracker.check_bytes(&node_id, 60));
+        assert!(!tracker.check_bytes(&node_id, 60));
+        assert!(tracker.check_bytes(&node_id, 40));
+        assert!(!tracker.has_remaining_bytes(&node_id));
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let tracker = QuotaTracker::new(Default::default());
+        let node_id = NodeId::new();
+        let protocol = ProtocolId::from_static(b"test");
+        let _guards = (0..10)
+            .map(|_| tracker.try_acquire_session(&node_id).unwrap())
+            .collect::<Vec<_>>();
+        assert!((0..10).all(|_| tracker.check_request(&node_id, &protocol, 1)));
+        assert!(tracker.check_bytes(&node_id, usize::MAX));
+    }
+}
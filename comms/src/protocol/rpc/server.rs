@@ -30,6 +30,7 @@ use super::{
 };
 use crate::{
     bounded_executor::OptionallyBoundedExecutor,
+    compression::{CompressionCodec, SUPPORTED_CODECS},
     framing,
     framing::CanonicalFraming,
     message::MessageExt,
@@ -40,6 +41,8 @@ use crate::{
             context::{RequestContext, RpcCommsProvider},
             error::HandshakeRejectReason,
             message::RpcMessageFlags,
+            metrics,
+            quota::{QuotaKind, QuotaTracker, RpcQuotas, SessionQuotaGuard},
             Handshake,
             RpcStatusCode,
             RPC_MAX_FRAME_SIZE,
@@ -51,7 +54,18 @@ use crate::{
     },
     Bytes,
 };
-use futures::{AsyncRead, AsyncWrite, Sink, SinkExt, StreamExt};
+use bytes::BytesMut;
+use futures::{
+    channel::{mpsc, oneshot},
+    future,
+    future::Either,
+    AsyncRead,
+    AsyncWrite,
+    Sink,
+    SinkExt,
+    Stream,
+    StreamExt,
+};
 use log::*;
 use prost::Message;
 use std::{
@@ -65,6 +79,10 @@ use tower_make::MakeService;
 
 const LOG_TARGET: &str = "comms::rpc";
 
+/// The number of request stream messages that are buffered for a service before the server stops reading from the
+/// substream. This applies back pressure to clients that stream requests faster than the service consumes them.
+const REQUEST_STREAM_BUFFER_SIZE: usize = 10;
+
 pub trait NamedProtocolService {
     const PROTOCOL_NAME: &'static [u8];
 
@@ -79,6 +97,8 @@ pub struct RpcServer {
     maximum_concurrent_sessions: Option<usize>,
     minimum_client_deadline: Duration,
     handshake_timeout: Duration,
+    quotas: RpcQuotas,
+    compression_enabled: bool,
     shutdown_signal: OptionalShutdownSignal,
 }
 
@@ -113,6 +133,39 @@ impl RpcServer {
         self
     }
 
+    /// Limit the number of concurrent sessions that a single peer may have open. Sessions over this limit are rejected
+    /// in the handshake.
+    pub fn with_maximum_sessions_per_peer(mut self, limit: usize) -> Self {
+        self.quotas.max_sessions_per_peer = Some(limit);
+        self
+    }
+
+    /// Limit the number of requests per second that a single peer may make to each method. Requests over this limit
+    /// receive a `QuotaExceeded` status.
+    pub fn with_maximum_requests_per_second_per_method(mut self, limit: usize) -> Self {
+        self.quotas.max_requests_per_second_per_method = Some(limit);
+        self
+    }
+
+    /// Limit the number of response bytes sent to a single peer within the given window. Requests and response streams
+    /// over this limit receive a `QuotaExceeded` status.
+    pub fn with_maximum_bytes_per_peer(mut self, limit: u64, window: Duration) -> Self {
+        self.quotas.max_bytes_per_peer = Some((limit, window));
+        self
+    }
+
+    pub fn with_quotas(mut self, quotas: RpcQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Enable or disable negotiating payload compression with clients. Clients that do not support compression are
+    /// always served uncompressed responses.
+    pub fn with_compression(mut self, is_enabled: bool) -> Self {
+        self.compression_enabled = is_enabled;
+        self
+    }
+
     pub fn with_shutdown_signal(mut self, shutdown_signal: ShutdownSignal) -> Self {
         self.shutdown_signal = Some(shutdown_signal).into();
         self
@@ -147,6 +200,8 @@ impl Default for RpcServer {
             maximum_concurrent_sessions: Some(1000),
             minimum_client_deadline: Duration::from_secs(1),
             handshake_timeout: Duration::from_secs(15),
+            quotas: Default::default(),
+            compression_enabled: true,
             shutdown_signal: Default::default(),
         }
     }
@@ -154,6 +209,7 @@ impl Default for RpcServer {
 
 pub(super) struct PeerRpcServer<TSvc, TSubstream, TCommsProvider> {
     executor: OptionallyBoundedExecutor,
+    quota_tracker: QuotaTracker,
     config: RpcServer,
     service: TSvc,
     protocol_notifications: Option<ProtocolNotificationRx<TSubstream>>,
@@ -180,6 +236,7 @@ where
     {
         Self {
             executor: OptionallyBoundedExecutor::from_current(config.maximum_concurrent_sessions),
+            quota_tracker: QuotaTracker::new(config.quotas),
             config,
             service,
             protocol_notifications: Some(protocol_notifications),
@@ -241,7 +298,14 @@ where
         mut framed: CanonicalFraming<TSubstream>,
     ) -> Result<(), RpcError>
     {
-        let mut handshake = Handshake::new(&mut framed).with_timeout(self.config.handshake_timeout);
+        let compression = if self.config.compression_enabled {
+            SUPPORTED_CODECS
+        } else {
+            &[]
+        };
+        let mut handshake = Handshake::new(&mut framed)
+            .with_timeout(self.config.handshake_timeout)
+            .with_compression(compression);
 
         if !self.executor.can_spawn() {
             debug!(
@@ -256,6 +320,23 @@ where
             return Err(RpcError::MaximumConcurrencyReached);
         }
 
+        let session_guard = match self.quota_tracker.try_acquire_session(&node_id) {
+            Some(guard) => guard,
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejecting RPC session request for peer `{}` because the peer has reached the maximum number of \
+                     sessions",
+                    node_id
+                );
+                metrics::inc_quota_exceeded(&protocol, QuotaKind::SessionsPerPeer);
+                handshake
+                    .reject_with_reason(HandshakeRejectReason::NoSessionsAvailable)
+                    .await?;
+                return Err(RpcError::QuotaExceeded(QuotaKind::SessionsPerPeer));
+            },
+        };
+
         let service = match self.service.make_service(protocol).await {
             Ok(s) => s,
             Err(err) => {
@@ -267,17 +348,22 @@ where
         };
 
         let version = handshake.perform_server_handshake().await?;
+        let compression = handshake.negotiated_compression();
         debug!(
             target: LOG_TARGET,
-            "Server negotiated RPC v{} with client node `{}`", version, node_id
+            "Server negotiated RPC v{} (compression: {}) with client node `{}`", version, compression, node_id
         );
 
         let service = ActivePeerRpcService {
             config: self.config.clone(),
+            protocol,
             node_id,
             framed: Some(framed),
             service,
             comms_provider: self.comms_provider.clone(),
+            quota_tracker: self.quota_tracker.clone(),
+            _session_guard: session_guard,
+            compression,
             shutdown_signal: self.config.shutdown_signal.clone(),
         };
 
@@ -291,10 +377,14 @@ where
 
 struct ActivePeerRpcService<TSvc, TSubstream, TCommsProvider> {
     config: RpcServer,
+    protocol: ProtocolId,
     node_id: NodeId,
     service: TSvc,
     framed: Option<CanonicalFraming<TSubstream>>,
     comms_provider: TCommsProvider,
+    quota_tracker: QuotaTracker,
+    _session_guard: SessionQuotaGuard,
+    compression: CompressionCodec,
     shutdown_signal: OptionalShutdownSignal,
 }
 
@@ -321,11 +411,14 @@ where
 
         while let Some(result) = stream.next().await {
             let start = Instant::now();
-            if let Err(err) = self.handle(&mut sink, result?.freeze()).await {
+            if let Err(err) = self.handle(&mut sink, &mut stream, result?.freeze()).await {
+                metrics::inc_request_errors(&self.protocol);
                 sink.close().await?;
                 return Err(err);
             }
-            debug!(target: LOG_TARGET, "RPC request completed in {:.0?}", start.elapsed());
+            let elapsed = start.elapsed();
+            metrics::observe_request_latency(&self.protocol, elapsed);
+            debug!(target: LOG_TARGET, "RPC request completed in {:.0?}", elapsed);
         }
 
         sink.close().await?;
@@ -336,12 +429,16 @@ where
         RequestContext::new(self.node_id.clone(), Box::new(self.comms_provider.clone()))
     }
 
-    async fn handle<W>(&mut self, sink: &mut W, mut request: Bytes) -> Result<(), RpcError>
-    where W: Sink<Bytes, Error = io::Error> + Unpin {
+    async fn handle<W, R>(&mut self, sink: &mut W, reader: &mut R, mut request: Bytes) -> Result<(), RpcError>
+    where
+        W: Sink<Bytes, Error = io::Error> + Unpin,
+        R: Stream<Item = io::Result<BytesMut>> + Unpin,
+    {
         let decoded_msg = proto::rpc::RpcRequest::decode(&mut request)?;
 
         let request_id = decoded_msg.request_id;
-        let method = decoded_msg.method.into();
+        let method_num = decoded_msg.method;
+        let method = method_num.into();
         let deadline = Duration::from_secs(decoded_msg.deadline);
 
         // The client side deadline MUST be greater or equal to the minimum_client_deadline
@@ -362,6 +459,9 @@ where
                 message: status.details_bytes(),
             };
             sink.send(bad_request.to_encoded_bytes().into()).await?;
+            if decoded_msg.flags().is_streaming() {
+                discard_request_stream(reader, decoded_msg, self.config.minimum_client_deadline).await?;
+            }
             return Ok(());
         }
 
@@ -370,8 +470,74 @@ where
             "[Peer=`{}`] Got request {}", self.node_id, decoded_msg
         );
 
+        let over_quota = if !self
+            .quota_tracker
+            .check_request(&self.node_id, &self.protocol, method_num)
+        {
+            Some(QuotaKind::RequestsPerMethod)
+        } else if !self.quota_tracker.has_remaining_bytes(&self.node_id) {
+            Some(QuotaKind::BytesPerPeer)
+        } else {
+            None
+        };
+        if let Some(quota) = over_quota {
+            self.reject_over_quota(sink, request_id, quota).await?;
+            if decoded_msg.flags().is_streaming() {
+                discard_request_stream(reader, decoded_msg, deadline).await?;
+            }
+            return Ok(());
+        }
+
+        if decoded_msg.flags().is_streaming() {
+            let (stream_tx, stream_rx) = mpsc::channel(REQUEST_STREAM_BUFFER_SIZE);
+            let req = Request::with_context(self.create_request_context(), method, Bytes::new()).with_stream(stream_rx);
+            let (over_quota_tx, over_quota_rx) = oneshot::channel();
+            // Each streamed message is charged to the request quota of the method
+            let quota = RequestStreamQuota {
+                tracker: self.quota_tracker.clone(),
+                node_id: self.node_id.clone(),
+                protocol: self.protocol.clone(),
+                method: method_num,
+                exceeded_tx: Some(over_quota_tx),
+            };
+            // The request stream is read concurrently with the service call so that the service can respond while the
+            // client is still streaming (bidirectional streaming)
+            let respond = async {
+                let call = Box::pin(self.call_service(sink, req, request_id, deadline));
+                // The quota signal is polled first so that it takes precedence over a service call that completes
+                // because the request stream was closed
+                match future::select(over_quota_rx, call).await {
+                    Either::Left((Ok(quota), call)) => {
+                        // Abort the service call and let the client know that the quota was exceeded
+                        drop(call);
+                        self.reject_over_quota(sink, request_id, quota).await
+                    },
+                    Either::Left((Err(_), call)) => call.await,
+                    Either::Right((result, _)) => result,
+                }
+            };
+            future::try_join(
+                forward_request_stream(reader, stream_tx, decoded_msg, deadline, Some(quota)),
+                respond,
+            )
+            .await?;
+            return Ok(());
+        }
+
         let req = Request::with_context(self.create_request_context(), method, decoded_msg.message.into());
+        self.call_service(sink, req, request_id, deadline).await
+    }
 
+    async fn call_service<W>(
+        &mut self,
+        sink: &mut W,
+        req: Request<Bytes>,
+        request_id: u32,
+        deadline: Duration,
+    ) -> Result<(), RpcError>
+    where
+        W: Sink<Bytes, Error = io::Error> + Unpin,
+    {
         let service_result = time::timeout(deadline, self.service.call(req)).await;
         let service_result = match service_result {
             Ok(v) => v,
@@ -390,18 +556,46 @@ where
                 loop {
                     match time::timeout(deadline, message.next()).await {
                         Ok(Some(msg)) => {
+                            let mut is_over_quota = false;
                             let resp = match msg {
+                                Ok(msg) if !self.quota_tracker.check_bytes(&self.node_id, msg.len()) => {
+                                    debug!(
+                                        target: LOG_TARGET,
+                                        "[Peer=`{}`] Response stream aborted because the peer exceeded the {} quota",
+                                        self.node_id,
+                                        QuotaKind::BytesPerPeer
+                                    );
+                                    metrics::inc_quota_exceeded(&self.protocol, QuotaKind::BytesPerPeer);
+                                    is_over_quota = true;
+                                    let status = quota_exceeded_status(QuotaKind::BytesPerPeer);
+                                    proto::rpc::RpcResponse {
+                                        request_id,
+                                        status: status.as_code(),
+                                        flags: RpcMessageFlags::FIN.bits().into(),
+                                        message: status.details_bytes(),
+                                    }
+                                },
                                 Ok(msg) => {
                                     trace!(target: LOG_TARGET, "Sending body len = {}", msg.len());
                                     let mut flags = RpcMessageFlags::empty();
                                     if msg.is_finished() {
                                         flags |= RpcMessageFlags::FIN;
                                     }
+                                    // Oversized messages are left uncompressed so that they are rejected as before
+                                    let codec = if msg.len() > RPC_MAX_FRAME_SIZE {
+                                        CompressionCodec::None
+                                    } else {
+                                        self.compression
+                                    };
+                                    let (codec, message) = codec.compress_if_beneficial(msg.into())?;
+                                    if !codec.is_none() {
+                                        flags |= RpcMessageFlags::COMPRESSED;
+                                    }
                                     proto::rpc::RpcResponse {
                                         request_id,
                                         status: RpcStatus::ok().as_code(),
                                         flags: flags.bits().into(),
-                                        message: msg.into(),
+                                        message: message.to_vec(),
                                     }
                                 },
                                 Err(err) => {
@@ -415,7 +609,7 @@ where
                                 },
                             };
 
-                            if !send_response_checked(sink, request_id, resp).await? {
+                            if !send_response_checked(sink, request_id, resp).await? || is_over_quota {
                                 break;
                             }
                         },
@@ -446,6 +640,135 @@ where
 
         Ok(())
     }
+
+    async fn reject_over_quota<W>(&self, sink: &mut W, request_id: u32, quota: QuotaKind) -> Result<(), RpcError>
+    where W: Sink<Bytes, Error = io::Error> + Unpin {
+        debug!(
+            target: LOG_TARGET,
+            "[Peer=`{}`] Request rejected because the peer exceeded the {} quota", self.node_id, quota
+        );
+        metrics::inc_quota_exceeded(&self.protocol, quota);
+        let status = quota_exceeded_status(quota);
+        let resp = proto::rpc::RpcResponse {
+            request_id,
+            status: status.as_code(),
+            flags: RpcMessageFlags::FIN.bits().into(),
+            message: status.details_bytes(),
+        };
+        sink.send(resp.to_encoded_bytes().into()).await?;
+        Ok(())
+    }
+}
+
+/// Charges each message of a request stream to the request quota of the method
+struct RequestStreamQuota {
+    tracker: QuotaTracker,
+    node_id: NodeId,
+    protocol: ProtocolId,
+    method: u32,
+    exceeded_tx: Option<oneshot::Sender<QuotaKind>>,
+}
+
+impl RequestStreamQuota {
+    /// Records a streamed message. Returns false, and signals that the quota was exceeded, if the peer is over the
+    /// request quota.
+    fn check_message(&mut self) -> bool {
+        if self.tracker.check_request(&self.node_id, &self.protocol, self.method) {
+            return true;
+        }
+        if let Some(tx) = self.exceeded_tx.take() {
+            let _ = tx.send(QuotaKind::RequestsPerMethod);
+        }
+        false
+    }
+}
+
+/// Forwards the messages of a request stream to the service until the client terminates the stream with the FIN flag.
+/// Messages are discarded once the service has dropped the stream or the quota is exceeded, however the stream is
+/// always read to the end so that subsequent requests on the session are not misinterpreted.
+async fn forward_request_stream<R>(
+    reader: &mut R,
+    mut stream_tx: mpsc::Sender<Result<Bytes, RpcStatus>>,
+    first: proto::rpc::RpcRequest,
+    deadline: Duration,
+    mut quota: Option<RequestStreamQuota>,
+) -> Result<(), RpcError>
+where
+    R: Stream<Item = io::Result<BytesMut>> + Unpin,
+{
+    let request_id = first.request_id;
+    let method = first.method;
+    // The first message was charged to the quota as the request itself
+    let mut next_msg = Some(first);
+    let mut is_first = true;
+    let mut num_received = 0usize;
+    loop {
+        let msg = match next_msg.take() {
+            Some(msg) => msg,
+            None => match time::timeout(deadline, reader.next()).await {
+                Ok(Some(frame)) => proto::rpc::RpcRequest::decode(&mut frame?.freeze())?,
+                Ok(None) => return Err(RpcError::ClientClosed),
+                Err(_) => {
+                    let _ = stream_tx
+                        .send(Err(RpcStatus::timed_out(
+                            "Timed out waiting for the next message of the request stream",
+                        )))
+                        .await;
+                    return Err(RpcError::StreamingRequestTimedOut);
+                },
+            },
+        };
+
+        if msg.request_id != request_id || msg.method != method || !msg.flags().is_streaming() {
+            return Err(RpcError::InvalidStreamingRequest(format!(
+                "expected a message for request stream #{} (method={}) but got {}",
+                request_id, method, msg
+            )));
+        }
+
+        if msg.flags().is_fin() {
+            trace!(
+                target: LOG_TARGET,
+                "Request stream #{} completed after {} message(s)",
+                request_id,
+                num_received
+            );
+            return Ok(());
+        }
+
+        num_received += 1;
+        if !is_first {
+            if let Some(q) = quota.as_mut() {
+                if !q.check_message() {
+                    // Stop forwarding messages and charging the quota
+                    stream_tx.close_channel();
+                    quota = None;
+                }
+            }
+        }
+        is_first = false;
+        // The service may drop the request stream before the client has finished sending
+        if !stream_tx.is_closed() {
+            let _ = stream_tx.send(Ok(msg.message.into())).await;
+        }
+    }
+}
+
+/// Reads and discards the remainder of a request stream that was rejected
+async fn discard_request_stream<R>(
+    reader: &mut R,
+    first: proto::rpc::RpcRequest,
+    deadline: Duration,
+) -> Result<(), RpcError>
+where
+    R: Stream<Item = io::Result<BytesMut>> + Unpin,
+{
+    let (stream_tx, _) = mpsc::channel(0);
+    forward_request_stream(reader, stream_tx, first, deadline, None).await
+}
+
+fn quota_exceeded_status(quota: QuotaKind) -> RpcStatus {
+    RpcStatus::quota_exceeded(format!("The {} quota has been exceeded. Try again later.", quota))
 }
 
 /// Sends an RpcResponse on the given Sink. If the size of the message exceeds the RPC_MAX_FRAME_SIZE, an error is
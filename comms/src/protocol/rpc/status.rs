@@ -88,6 +88,13 @@ impl RpcStatus {
         }
     }
 
+    pub fn quota_exceeded<T: ToString>(details: T) -> Self {
+        Self {
+            code: RpcStatusCode::QuotaExceeded,
+            details: details.to_string(),
+        }
+    }
+
     pub fn log_internal_error<'a, E: std::error::Error + 'a>(target: &'a str) -> impl Fn(E) -> Self + 'a {
         move |err| {
             log::error!(target: target, "Internal error: {}", err);
@@ -173,6 +180,8 @@ pub enum RpcStatusCode {
     General = 6,
     /// Entity not found
     NotFound = 7,
+    /// The peer has exceeded a quota set by the server
+    QuotaExceeded = 8,
     // The following status represents anything that is not recognised (i.e not one of the above codes).
     /// Unrecognised RPC status code
     InvalidRpcStatusCode,
@@ -186,6 +195,14 @@ impl RpcStatusCode {
     pub fn is_not_found(self) -> bool {
         self == Self::NotFound
     }
+
+    pub fn is_timeout(self) -> bool {
+        self == Self::Timeout
+    }
+
+    pub fn is_quota_exceeded(self) -> bool {
+        self == Self::QuotaExceeded
+    }
 }
 
 impl From<u32> for RpcStatusCode {
@@ -200,6 +217,7 @@ impl From<u32> for RpcStatusCode {
             5 => MalformedResponse,
             6 => General,
             7 => NotFound,
+            8 => QuotaExceeded,
             _ => InvalidRpcStatusCode,
         }
     }
@@ -220,6 +238,7 @@ mod test {
         assert_eq!(RpcStatusCode::from(MalformedResponse as u32), MalformedResponse);
         assert_eq!(RpcStatusCode::from(Timeout as u32), Timeout);
         assert_eq!(RpcStatusCode::from(NotFound as u32), NotFound);
+        assert_eq!(RpcStatusCode::from(QuotaExceeded as u32), QuotaExceeded);
         assert_eq!(RpcStatusCode::from(InvalidRpcStatusCode as u32), InvalidRpcStatusCode);
         assert_eq!(RpcStatusCode::from(123), InvalidRpcStatusCode);
     }
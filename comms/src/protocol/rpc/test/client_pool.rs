@@ -0,0 +1,152 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    protocol::rpc::{
+        test::mock::{MockRpcClient, MockRpcService, MockRpcServiceState},
+        RpcClientPools,
+        RpcPoolClient,
+        RpcServer,
+    },
+    runtime,
+    test_utils::node_identity::build_node_identity,
+    transports::MemoryTransport,
+    types::CommsDatabase,
+    CommsBuilder,
+    CommsNode,
+    PeerConnection,
+};
+use futures::future;
+use tari_shutdown::Shutdown;
+
+async fn setup(shutdown: &Shutdown) -> (CommsNode, PeerConnection, MockRpcServiceState) {
+    let node_identity1 = build_node_identity(Default::default());
+    let rpc_service = MockRpcService::new();
+    let mock_state = rpc_service.shared_state();
+    let comms1 = CommsBuilder::new()
+        .with_listener_address(node_identity1.public_address())
+        .with_node_identity(node_identity1)
+        .with_shutdown_signal(shutdown.to_signal())
+        .with_peer_storage(CommsDatabase::new(), None)
+        .build()
+        .unwrap()
+        .add_rpc_server(RpcServer::new().add_service(rpc_service))
+        .spawn_with_transport(MemoryTransport)
+        .await
+        .unwrap();
+
+    let node_identity2 = build_node_identity(Default::default());
+    let comms2 = CommsBuilder::new()
+        .with_listener_address(node_identity2.public_address())
+        .with_shutdown_signal(shutdown.to_signal())
+        .with_node_identity(node_identity2)
+        .with_peer_storage(CommsDatabase::new(), None)
+        .build()
+        .unwrap();
+
+    comms2
+        .peer_manager()
+        .add_peer(comms1.node_identity().to_peer())
+        .await
+        .unwrap();
+
+    let comms2 = comms2.spawn_with_transport(MemoryTransport).await.unwrap();
+
+    let conn = comms2
+        .connectivity()
+        .dial_peer(comms1.node_identity().node_id().clone())
+        .await
+        .unwrap();
+
+    // Return comms2 so that it is not dropped before the test completes
+    (comms2, conn, mock_state)
+}
+
+#[runtime::test_basic]
+async fn it_shares_sessions_up_to_the_pool_size() {
+    let shutdown = Shutdown::new();
+    let (_comms, conn, mock_state) = setup(&shutdown).await;
+    let pool = conn.create_rpc_client_pool::<MockRpcClient>(2, Default::default());
+    assert_eq!(pool.num_sessions().await, 0);
+
+    let mut client1 = pool.get().await.unwrap();
+    assert_eq!(pool.num_sessions().await, 1);
+    let _client2 = pool.get().await.unwrap();
+    assert_eq!(pool.num_sessions().await, 2);
+    // The pool is full, so an existing session is shared
+    let _client3 = pool.get().await.unwrap();
+    assert_eq!(pool.num_sessions().await, 2);
+
+    mock_state.set_response_ok(());
+    client1.request_response::<_, ()>((), 0.into()).await.unwrap();
+    assert_eq!(mock_state.call_count(), 1);
+
+    drop(client1);
+    let _client4 = pool.get().await.unwrap();
+    assert_eq!(pool.num_sessions().await, 2);
+}
+
+#[runtime::test_basic]
+async fn it_reconnects_closed_sessions() {
+    let shutdown = Shutdown::new();
+    let (_comms, conn, mock_state) = setup(&shutdown).await;
+    let pool = conn.create_rpc_client_pool::<MockRpcClient>(1, Default::default());
+
+    let mut client = pool.get().await.unwrap();
+    client.close();
+    assert!(!client.is_connected());
+    assert_eq!(pool.num_active_sessions().await, 0);
+    drop(client);
+
+    let mut client = pool.get().await.unwrap();
+    assert!(client.is_connected());
+    assert_eq!(pool.num_sessions().await, 1);
+    mock_state.set_response_ok(());
+    client.request_response::<_, ()>((), 0.into()).await.unwrap();
+    assert_eq!(mock_state.call_count(), 1);
+}
+
+#[runtime::test_basic]
+async fn it_does_not_exceed_the_pool_size_when_connecting_concurrently() {
+    let shutdown = Shutdown::new();
+    let (_comms, conn, _) = setup(&shutdown).await;
+    let pool = conn.create_rpc_client_pool::<MockRpcClient>(2, Default::default());
+
+    let clients = future::join_all((0..5).map(|_| pool.get())).await;
+    assert!(clients.iter().all(|c| c.is_ok()));
+    assert_eq!(pool.num_sessions().await, 2);
+}
+
+#[runtime::test_basic]
+async fn it_shares_pools_by_peer_and_protocol() {
+    let shutdown = Shutdown::new();
+    let (_comms, conn, _) = setup(&shutdown).await;
+    let pools = RpcClientPools::new(1);
+
+    let pool1 = pools.get_or_create::<MockRpcClient>(&conn, Default::default());
+    let _client = pool1.get().await.unwrap();
+    let pool2 = pools.get_or_create::<MockRpcClient>(&conn, Default::default());
+    assert_eq!(pools.num_pools(), 1);
+    assert_eq!(pool2.num_sessions().await, 1);
+    let _client = pool2.get().await.unwrap();
+    assert_eq!(pool1.num_sessions().await, 1);
+}
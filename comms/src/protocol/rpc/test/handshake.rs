@@ -21,6 +21,7 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
+    compression::{CompressionCodec, SUPPORTED_CODECS},
     framing,
     memsocket::MemorySocket,
     protocol::rpc::{error::HandshakeRejectReason, handshake::SUPPORTED_RPC_VERSIONS, Handshake, RpcError},
@@ -42,9 +43,10 @@ async fn it_performs_the_handshake() {
     let mut client_framed = framing::canonical(client, 1024);
     let mut handshake_client = Handshake::new(&mut client_framed);
 
-    handshake_client.perform_client_handshake().await.unwrap();
+    let client_version = handshake_client.perform_client_handshake().await.unwrap();
     let v = handshake_result.await.unwrap().unwrap();
     assert!(SUPPORTED_RPC_VERSIONS.contains(&v));
+    assert_eq!(client_version, v);
 }
 
 #[runtime::test_basic]
@@ -65,3 +67,39 @@ async fn it_rejects_the_handshake() {
     unpack_enum!(RpcError::HandshakeRejected(reason) = err);
     unpack_enum!(HandshakeRejectReason::NoSessionsAvailable = reason);
 }
+
+#[runtime::test_basic]
+async fn it_negotiates_compression() {
+    async fn negotiate(
+        client_codecs: &'static [CompressionCodec],
+        server_codecs: &'static [CompressionCodec],
+    ) -> (CompressionCodec, CompressionCodec)
+    {
+        let (client, server) = MemorySocket::new_pair();
+
+        let server_result = task::spawn(async move {
+            let mut server_framed = framing::canonical(server, 1024);
+            let mut handshake_server = Handshake::new(&mut server_framed).with_compression(server_codecs);
+            handshake_server.perform_server_handshake().await.unwrap();
+            handshake_server.negotiated_compression()
+        });
+
+        let mut client_framed = framing::canonical(client, 1024);
+        let mut handshake_client = Handshake::new(&mut client_framed).with_compression(client_codecs);
+        handshake_client.perform_client_handshake().await.unwrap();
+        (handshake_client.negotiated_compression(), server_result.await.unwrap())
+    }
+
+    let (client, server) = negotiate(SUPPORTED_CODECS, SUPPORTED_CODECS).await;
+    assert_eq!(client, CompressionCodec::Deflate);
+    assert_eq!(server, CompressionCodec::Deflate);
+
+    // Either side not supporting compression falls back to no compression
+    let (client, server) = negotiate(&[], SUPPORTED_CODECS).await;
+    assert_eq!(client, CompressionCodec::None);
+    assert_eq!(server, CompressionCodec::None);
+
+    let (client, server) = negotiate(SUPPORTED_CODECS, &[]).await;
+    assert_eq!(client, CompressionCodec::None);
+    assert_eq!(server, CompressionCodec::None);
+}
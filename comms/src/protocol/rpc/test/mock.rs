@@ -32,6 +32,7 @@ use crate::{
             Request,
             Response,
             RpcError,
+            RpcPoolClient,
             RpcStatus,
         },
         ProtocolId,
@@ -148,6 +149,7 @@ impl MockRpcServiceState {
     }
 }
 
+#[derive(Clone)]
 pub struct MockRpcClient {
     inner: RpcClient,
 }
@@ -174,6 +176,16 @@ impl MockRpcClient {
     {
         self.inner.server_streaming(request, method).await
     }
+
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+impl RpcPoolClient for MockRpcClient {
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
 }
 
 impl From<RpcClient> for MockRpcClient {
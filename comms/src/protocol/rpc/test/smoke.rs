@@ -25,7 +25,7 @@ use crate::{
     memsocket::MemorySocket,
     protocol::{
         rpc::{
-            body::Streaming,
+            body::{RequestStream, Streaming},
             context::RpcCommsBackend,
             error::HandshakeRejectReason,
             message::Request,
@@ -71,6 +71,11 @@ pub trait GreetingRpc: Send + Sync + 'static {
     async fn get_public_key_hex(&self, _: Request<()>) -> Result<String, RpcStatus>;
     // #[rpc(method = 7)]
     async fn reply_with_msg_of_size(&self, request: Request<u64>) -> Result<Vec<u8>, RpcStatus>;
+    // #[rpc(method = 8)]
+    async fn count_greetings(&self, request: Request<RequestStream<String>>) -> Result<Response<u32>, RpcStatus>;
+    // #[rpc(method = 9)]
+    async fn reply_to_greetings(&self, request: Request<RequestStream<String>>)
+        -> Result<Streaming<String>, RpcStatus>;
 }
 
 async fn setup_service<T: GreetingRpc>(
@@ -82,13 +87,29 @@ async fn setup_service<T: GreetingRpc>(
     RpcCommsBackend,
     Shutdown,
 )
+{
+    setup_service_with_server(
+        service,
+        RpcServer::new().with_maximum_concurrent_sessions(num_concurrent_sessions),
+    )
+    .await
+}
+
+async fn setup_service_with_server<T: GreetingRpc>(
+    service: T,
+    server: RpcServer,
+) -> (
+    mpsc::Sender<ProtocolNotification<MemorySocket>>,
+    task::JoinHandle<Result<(), RpcError>>,
+    RpcCommsBackend,
+    Shutdown,
+)
 {
     let (notif_tx, notif_rx) = mpsc::channel(1);
     let shutdown = Shutdown::new();
     let (context, _) = create_mocked_rpc_context();
     let server_hnd = task::spawn(
-        RpcServer::new()
-            .with_maximum_concurrent_sessions(num_concurrent_sessions)
+        server
             .with_minimum_client_deadline(Duration::from_secs(0))
             .with_shutdown_signal(shutdown.to_signal())
             .add_service(GreetingServer::new(service))
@@ -199,6 +220,90 @@ async fn request_reponse_errors_and_streaming() // a.k.a  smoke test
     server_hnd.await.unwrap().unwrap();
 }
 
+#[runtime::test_basic]
+async fn client_streaming() {
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(&["Hello"]), 1).await;
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::connect(framed).await.unwrap();
+
+    let greetings = iter::repeat("Hello".to_string()).take(100).collect::<Vec<_>>();
+    let count = client.count_greetings(stream::iter(greetings)).await.unwrap();
+    assert_eq!(count, 100);
+
+    let count = client.count_greetings(stream::empty::<String>()).await.unwrap();
+    assert_eq!(count, 0);
+
+    // The service responds with an error before the request stream has ended
+    let greetings = iter::once(String::new()).chain(iter::repeat("Hello".to_string()).take(100));
+    let err = client.count_greetings(stream::iter(greetings)).await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert_eq!(status.status_code(), RpcStatusCode::BadRequest);
+
+    // The session remains usable
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Jim".to_string(),
+            language: 0,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Hello Jim");
+}
+
+#[runtime::test_basic]
+async fn bidirectional_streaming() {
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(&[]), 1).await;
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::connect(framed).await.unwrap();
+
+    // Each request is sent only once the response to the previous request has been received
+    let (mut requests_tx, requests_rx) = mpsc::channel(1);
+    let mut responses = client.reply_to_greetings(requests_rx).await.unwrap();
+    for greeting in &["Hello", "Jambo", "Bonjour"] {
+        requests_tx.send(greeting.to_string()).await.unwrap();
+        let reply = responses.next().await.unwrap().unwrap();
+        assert_eq!(reply, format!("{} to you too", greeting));
+    }
+    drop(requests_tx);
+    assert!(responses.next().await.is_none());
+
+    // Many more messages than are buffered are sent and received concurrently
+    let greetings = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+    let responses = client.reply_to_greetings(stream::iter(greetings)).await.unwrap();
+    let replies = responses.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(replies.len(), 1000);
+    assert_eq!(replies[999], "999 to you too");
+}
+
+#[runtime::test_basic]
+async fn streaming_request_to_unary_method() {
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(&["Hello"]), 1).await;
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::connect(framed).await.unwrap();
+
+    let requests = stream::iter(vec![SayHelloRequest {
+        name: "Jim".to_string(),
+        language: 0,
+    }]);
+    let err = client
+        .inner
+        .client_streaming::<_, _, SayHelloResponse, _>(requests, 1)
+        .await
+        .unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert_eq!(status.status_code(), RpcStatusCode::BadRequest);
+
+    let err = client.inner.request_response::<_, u32, _>((), 8).await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert_eq!(status.status_code(), RpcStatusCode::BadRequest);
+
+    let count = client
+        .count_greetings(stream::iter(vec!["Hello".to_string()]))
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
 #[runtime::test_basic]
 async fn response_too_big() {
     let (socket, _, _, _shutdown) = setup(GreetingService::new(&[]), 1).await;
@@ -220,6 +325,23 @@ async fn response_too_big() {
     let _ = client.reply_with_msg_of_size(max_size as u64).await.unwrap();
 }
 
+#[runtime::test_basic]
+async fn compressed_responses() {
+    for is_compression_enabled in &[true, false] {
+        let (socket, _, _, _shutdown) = setup(GreetingService::new(&[]), 1).await;
+        let framed = framing::canonical(socket, RPC_MAX_FRAME_SIZE);
+        let mut client = GreetingClient::builder()
+            .with_compression(*is_compression_enabled)
+            .connect(framed)
+            .await
+            .unwrap();
+
+        let resp = client.reply_with_msg_of_size(100 * 1024).await.unwrap();
+        assert_eq!(resp.len(), 100 * 1024);
+        assert!(resp.bytes().all(|b| b == 0));
+    }
+}
+
 #[runtime::test_basic]
 async fn server_shutdown_after_connect() {
     let (socket, _, _, mut shutdown) = setup(GreetingService::new(&[]), 1).await;
@@ -289,6 +411,79 @@ async fn unknown_protocol() {
     unpack_enum!(HandshakeRejectReason::ProtocolNotSupported = reason);
 }
 
+#[runtime::test_basic]
+async fn quotas_exceeded() {
+    let server = RpcServer::new()
+        .with_maximum_sessions_per_peer(1)
+        .with_maximum_requests_per_second_per_method(1);
+    let (mut notif_tx, _, _, _shutdown) = setup_service_with_server(GreetingService::new(&["Hi"]), server).await;
+    let node_identity = build_node_identity(Default::default());
+
+    let mut sockets = Vec::new();
+    for _ in 0..2 {
+        let (inbound, socket) = MemorySocket::new_pair();
+        notif_tx
+            .send(ProtocolNotification::new(
+                ProtocolId::from_static(b"/test/greeting/1.0"),
+                ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), inbound),
+            ))
+            .await
+            .unwrap();
+        sockets.push(socket);
+    }
+
+    let mut client = GreetingClient::connect(framing::canonical(sockets.remove(0), 1024))
+        .await
+        .unwrap();
+    // The peer already has a session open
+    let err = GreetingClient::connect(framing::canonical(sockets.remove(0), 1024))
+        .await
+        .unwrap_err();
+    unpack_enum!(RpcError::HandshakeRejected(reason) = err);
+    unpack_enum!(HandshakeRejectReason::NoSessionsAvailable = reason);
+
+    client.say_hello(Default::default()).await.unwrap();
+    let err = client.say_hello(Default::default()).await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert!(status.status_code().is_quota_exceeded());
+    // Other methods have their own quota
+    let err = client.return_error().await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert!(!status.status_code().is_quota_exceeded());
+}
+
+#[runtime::test_basic]
+async fn request_stream_quota_exceeded() {
+    let server = RpcServer::new().with_maximum_requests_per_second_per_method(5);
+    let (mut notif_tx, _, _, _shutdown) = setup_service_with_server(GreetingService::new(&["Hello"]), server).await;
+    let node_identity = build_node_identity(Default::default());
+    let (inbound, socket) = MemorySocket::new_pair();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), inbound),
+        ))
+        .await
+        .unwrap();
+
+    let mut client = GreetingClient::connect(framing::canonical(socket, 1024)).await.unwrap();
+    // Each streamed message is charged to the request quota of the method
+    let greetings = iter::repeat("Hello".to_string()).take(100).collect::<Vec<_>>();
+    let err = client.count_greetings(stream::iter(greetings)).await.unwrap_err();
+    unpack_enum!(RpcError::RequestFailed(status) = err);
+    assert!(status.status_code().is_quota_exceeded());
+
+    // The session remains usable for other methods
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Jim".to_string(),
+            language: 0,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Hello Jim");
+}
+
 #[runtime::test_basic]
 async fn rejected_no_sessions_available() {
     let (socket, _, _, _shutdown) = setup(GreetingService::new(&[]), 0).await;
@@ -374,6 +569,37 @@ impl GreetingRpc for GreetingService {
         let size = request.into_message() as usize;
         Ok(iter::repeat(0).take(size).collect())
     }
+
+    async fn count_greetings(&self, request: Request<RequestStream<String>>) -> Result<Response<u32>, RpcStatus> {
+        let mut stream = request.into_message();
+        let mut count = 0;
+        while let Some(greeting) = stream.next().await {
+            if greeting?.is_empty() {
+                return Err(RpcStatus::bad_request("Greeting cannot be empty"));
+            }
+            count += 1;
+        }
+        Ok(Response::new(count))
+    }
+
+    async fn reply_to_greetings(
+        &self,
+        request: Request<RequestStream<String>>,
+    ) -> Result<Streaming<String>, RpcStatus>
+    {
+        let (mut tx, rx) = mpsc::channel(1);
+        let mut stream = request.into_message();
+        task::spawn(async move {
+            while let Some(greeting) = stream.next().await {
+                let reply = greeting.map(|greeting| format!("{} to you too", greeting));
+                if tx.send(reply).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Streaming::new(rx))
+    }
 }
 
 pub struct SlowGreetingService {
@@ -419,6 +645,14 @@ impl GreetingRpc for SlowGreetingService {
     async fn reply_with_msg_of_size(&self, _: Request<u64>) -> Result<Vec<u8>, RpcStatus> {
         unimplemented!()
     }
+
+    async fn count_greetings(&self, _: Request<RequestStream<String>>) -> Result<Response<u32>, RpcStatus> {
+        unimplemented!()
+    }
+
+    async fn reply_to_greetings(&self, _: Request<RequestStream<String>>) -> Result<Streaming<String>, RpcStatus> {
+        unimplemented!()
+    }
 }
 
 #[derive(prost::Message)]
@@ -521,6 +755,22 @@ impl<T: GreetingRpc> __rpc_deps::Service<Request<__rpc_deps::Bytes>> for Greetin
                 };
                 Box::pin(fut)
             },
+            // count_greetings
+            8 => {
+                let fut = async move {
+                    let resp = inner.count_greetings(req.decode_stream()?).await?;
+                    Ok(resp.map(IntoBody::into_body))
+                };
+                Box::pin(fut)
+            },
+            // reply_to_greetings
+            9 => {
+                let fut = async move {
+                    let resp = inner.reply_to_greetings(req.decode_stream()?).await?;
+                    Ok(Response::new(resp.into_body()))
+                };
+                Box::pin(fut)
+            },
 
             id => Box::pin(__rpc_deps::future::ready(Err(RpcStatus::unsupported_method(format!(
                 "Method identifier `{}` is not recognised or supported",
@@ -607,6 +857,21 @@ impl GreetingClient {
         self.inner.request_response(request, 7).await
     }
 
+    pub async fn count_greetings<TStream>(&mut self, request: TStream) -> Result<u32, RpcError>
+    where TStream: __rpc_deps::Stream<Item = String> + Send + 'static {
+        self.inner.client_streaming(request, 8).await
+    }
+
+    pub async fn reply_to_greetings<TStream>(
+        &mut self,
+        request: TStream,
+    ) -> Result<__rpc_deps::ClientStreaming<String>, RpcError>
+    where
+        TStream: __rpc_deps::Stream<Item = String> + Send + 'static,
+    {
+        self.inner.bidi_streaming(request, 9).await
+    }
+
     pub async fn get_last_request_latency(&mut self) -> Result<Option<Duration>, RpcError> {
         self.inner.get_last_request_latency().await
     }
@@ -614,6 +879,10 @@ impl GreetingClient {
     pub fn close(&mut self) {
         self.inner.close();
     }
+
+    pub fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
 }
 
 impl From<__rpc_deps::RpcClient> for GreetingClient {
@@ -621,3 +890,9 @@ impl From<__rpc_deps::RpcClient> for GreetingClient {
         Self { inner }
     }
 }
+
+impl __rpc_deps::RpcPoolClient for GreetingClient {
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
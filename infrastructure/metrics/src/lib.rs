@@ -0,0 +1,125 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! # Tari Metrics
+//!
+//! A process-wide metrics registry that Tari subsystems register their metrics into. All metric names are prefixed
+//! with `tari_`. Metrics are always recorded, but they are only exposed if the application starts the Prometheus
+//! exporter in the [server] module (requires the `server` feature).
+
+#![cfg_attr(not(debug_assertions), deny(unused_variables))]
+#![cfg_attr(not(debug_assertions), deny(unused_imports))]
+#![cfg_attr(not(debug_assertions), deny(dead_code))]
+#![cfg_attr(not(debug_assertions), deny(unused_extern_crates))]
+#![deny(unused_must_use)]
+#![deny(unreachable_patterns)]
+#![deny(unknown_lints)]
+
+mod error;
+pub use error::MetricsError;
+
+#[cfg(feature = "server")]
+pub mod server;
+
+pub use prometheus::{Histogram, HistogramTimer, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
+
+use lazy_static::lazy_static;
+use prometheus::{Encoder, HistogramOpts, Opts, Registry, TextEncoder};
+
+lazy_static! {
+    static ref REGISTRY: Registry =
+        Registry::new_custom(Some("tari".to_string()), None).expect("registry prefix is a valid metric name");
+}
+
+/// Returns the registry that all Tari metrics are registered in
+pub fn get_default_registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// Registers a new gauge in the default registry
+pub fn register_int_gauge(name: &str, help: &str) -> Result<IntGauge, MetricsError> {
+    let gauge = IntGauge::new(name, help)?;
+    REGISTRY.register(Box::new(gauge.clone()))?;
+    Ok(gauge)
+}
+
+/// Registers a new gauge with the given labels in the default registry
+pub fn register_int_gauge_vec(name: &str, help: &str, label_names: &[&str]) -> Result<IntGaugeVec, MetricsError> {
+    let gauge = IntGaugeVec::new(Opts::new(name, help), label_names)?;
+    REGISTRY.register(Box::new(gauge.clone()))?;
+    Ok(gauge)
+}
+
+/// Registers a new counter with the given labels in the default registry
+pub fn register_int_counter_vec(name: &str, help: &str, label_names: &[&str]) -> Result<IntCounterVec, MetricsError> {
+    let counter = IntCounterVec::new(Opts::new(name, help), label_names)?;
+    REGISTRY.register(Box::new(counter.clone()))?;
+    Ok(counter)
+}
+
+/// Registers a new histogram, with the default buckets, in the default registry
+pub fn register_histogram(name: &str, help: &str) -> Result<Histogram, MetricsError> {
+    let histogram = Histogram::with_opts(HistogramOpts::new(name, help))?;
+    REGISTRY.register(Box::new(histogram.clone()))?;
+    Ok(histogram)
+}
+
+/// Registers a new histogram with the given labels, with the default buckets, in the default registry
+pub fn register_histogram_vec(name: &str, help: &str, label_names: &[&str]) -> Result<HistogramVec, MetricsError> {
+    let histogram = HistogramVec::new(HistogramOpts::new(name, help), label_names)?;
+    REGISTRY.register(Box::new(histogram.clone()))?;
+    Ok(histogram)
+}
+
+/// Encodes every metric in the default registry in the Prometheus text exposition format
+pub fn encode_text() -> Result<Vec<u8>, MetricsError> {
+    let mut buf = Vec::new();
+    TextEncoder::new().encode(&REGISTRY.gather(), &mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_encodes_registered_metrics() {
+        let gauge = register_int_gauge("test_gauge", "A test gauge").unwrap();
+        gauge.set(123);
+        let counter = register_int_counter_vec("test_counter", "A test counter", &["label"]).unwrap();
+        counter.with_label_values(&["a"]).inc();
+
+        let text = String::from_utf8(encode_text().unwrap()).unwrap();
+        assert!(text.contains("tari_test_gauge 123"));
+        assert!(text.contains("tari_test_counter{label=\"a\"} 1"));
+    }
+
+    #[test]
+    fn it_rejects_duplicate_registrations() {
+        register_int_gauge("test_duplicate", "A test gauge").unwrap();
+        let err = register_int_gauge("test_duplicate", "A test gauge").unwrap_err();
+        assert!(matches!(
+            err,
+            MetricsError::PrometheusError(prometheus::Error::AlreadyReg)
+        ));
+    }
+}
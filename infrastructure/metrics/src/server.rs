@@ -0,0 +1,83 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An HTTP server that exposes the default registry for Prometheus to scrape at `GET /metrics`.
+
+use crate::{encode_text, MetricsError};
+use futures::FutureExt;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+use log::*;
+use prometheus::TEXT_FORMAT;
+use std::{convert::Infallible, net::SocketAddr};
+use tari_shutdown::ShutdownSignal;
+
+const LOG_TARGET: &str = "metrics::server";
+
+pub struct MetricsServer {
+    bind_address: SocketAddr,
+}
+
+impl MetricsServer {
+    pub fn new(bind_address: SocketAddr) -> Self {
+        Self { bind_address }
+    }
+
+    /// Serves metrics until the shutdown signal is triggered
+    pub async fn run(self, shutdown_signal: ShutdownSignal) -> Result<(), MetricsError> {
+        let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(handle_request)) });
+        let server = Server::try_bind(&self.bind_address)?.serve(make_service);
+        info!(
+            target: LOG_TARGET,
+            "Metrics server listening on http://{}/metrics", self.bind_address
+        );
+        server.with_graceful_shutdown(shutdown_signal.map(|_| ())).await?;
+        info!(target: LOG_TARGET, "Metrics server stopped");
+        Ok(())
+    }
+}
+
+async fn handle_request(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => match encode_text() {
+            Ok(body) => Response::builder()
+                .header(CONTENT_TYPE, TEXT_FORMAT)
+                .body(Body::from(body)),
+            Err(err) => {
+                error!(target: LOG_TARGET, "Failed to encode metrics: {}", err);
+                Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::empty())
+            },
+        },
+        _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()),
+    };
+    Ok(response.expect("response is always valid"))
+}